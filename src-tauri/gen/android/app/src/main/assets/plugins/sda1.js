@@ -4,6 +4,12 @@ export default {
   // 不强制参数，插件接受任意文件类型
   parameters: [],
   supportedFileTypes: [],
+  // 权限声明：仅访问 sda1.dev，只读取宿主交给插件的文件
+  permissions: {
+    domains: ['sda1.dev'],
+    filesystem: 'none',
+    clipboard: false,
+  },
 
   /**
    * 上传文件。
@@ -1,5 +1,7 @@
 package com.yana.dev
 
+import android.content.Intent
+import android.net.Uri
 import android.os.Bundle
 import android.os.Build
 import android.view.WindowManager
@@ -14,6 +16,9 @@ class MainActivity : TauriActivity() {
     private const val PERMISSION_REQUEST_CODE = 1001
   }
 
+  // 由 Rust 实现（share_target.rs），接收其他应用分享的图片 content URI
+  private external fun onSharedUris(uris: Array<String>)
+
   override fun onCreate(savedInstanceState: Bundle?) {
     enableEdgeToEdge()
     
@@ -25,6 +30,9 @@ class MainActivity : TauriActivity() {
     
     super.onCreate(savedInstanceState)
 
+    // 从其他应用分享图片启动时，intent 中带有图片 URI
+    handleShareIntent(intent)
+
     // Proactively request runtime permissions needed for file/network access on Android
     try {
       if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.M) {
@@ -56,6 +64,43 @@ class MainActivity : TauriActivity() {
     }
   }
 
+  override fun onNewIntent(intent: Intent) {
+    super.onNewIntent(intent)
+    setIntent(intent)
+    handleShareIntent(intent)
+  }
+
+  private fun handleShareIntent(intent: Intent?) {
+    if (intent == null || intent.type?.startsWith("image/") != true) return
+    val uris: List<Uri> = when (intent.action) {
+      Intent.ACTION_SEND -> listOfNotNull(
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.TIRAMISU) {
+          intent.getParcelableExtra(Intent.EXTRA_STREAM, Uri::class.java)
+        } else {
+          @Suppress("DEPRECATION")
+          intent.getParcelableExtra(Intent.EXTRA_STREAM)
+        }
+      )
+      Intent.ACTION_SEND_MULTIPLE -> (
+        if (Build.VERSION.SDK_INT >= Build.VERSION_CODES.TIRAMISU) {
+          intent.getParcelableArrayListExtra(Intent.EXTRA_STREAM, Uri::class.java)
+        } else {
+          @Suppress("DEPRECATION")
+          intent.getParcelableArrayListExtra(Intent.EXTRA_STREAM)
+        }
+      ).orEmpty()
+      else -> emptyList()
+    }
+    if (uris.isEmpty()) return
+    try {
+      onSharedUris(uris.map { it.toString() }.toTypedArray())
+    } catch (e: UnsatisfiedLinkError) {
+      Log.w("MainActivity", "Failed to deliver shared images: ${e}")
+    }
+    // 避免重建 Activity（如旋转屏幕）时重复处理
+    intent.action = null
+  }
+
   override fun onRequestPermissionsResult(requestCode: Int, permissions: Array<String>, grantResults: IntArray) {
     super.onRequestPermissionsResult(requestCode, permissions, grantResults)
     if (requestCode == PERMISSION_REQUEST_CODE) {
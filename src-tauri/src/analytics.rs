@@ -0,0 +1,329 @@
+/*
+上传统计：
+1) 每次上传（成功或失败）记录一条事件：时间、图床、上传字节数、压缩前字节数、耗时与错误信息，
+   持久化在 app_data_dir/analytics.db；界面上传由前端批量调用 `record_upload_events`，
+   后端上传流程（命令行、本地服务）直接记录；
+2) `upload_analytics(days)` 汇总最近若干天（默认 30，按本地日期）的每日次数、各图床字节数、
+   失败率与平均压缩率，供统计页使用；
+3) 启动后首次打开数据库时删除超过一年的事件。
+*/
+
+use std::{
+    fs,
+    path::Path,
+    sync::{Mutex, OnceLock},
+};
+
+use chrono::{DateTime, Duration, Local, Utc};
+use log::warn;
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+const DB_FILE_NAME: &str = "analytics.db";
+const DEFAULT_DAYS: u32 = 30;
+const RETENTION_DAYS: i64 = 365;
+/// 错误信息最多保存的字符数
+const ERROR_MAX_CHARS: usize = 500;
+
+static ANALYTICS_STORE: OnceLock<AnalyticsStore> = OnceLock::new();
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadEvent {
+    pub host: String,
+    pub success: bool,
+    /// 实际上传的字节数（压缩后）
+    #[serde(default)]
+    pub bytes: Option<u64>,
+    /// 压缩前的字节数，未压缩时可与 bytes 相同或留空
+    #[serde(default)]
+    pub original_bytes: Option<u64>,
+    #[serde(default)]
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// RFC 3339 时间，留空为当前时间
+    #[serde(default)]
+    pub timestamp: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyStats {
+    /// 本地日期 YYYY-MM-DD
+    pub day: String,
+    pub uploads: u64,
+    pub failures: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HostStats {
+    pub host: String,
+    pub uploads: u64,
+    pub failures: u64,
+    pub bytes: u64,
+    pub avg_duration_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalyticsSummary {
+    pub since: String,
+    pub uploads: u64,
+    pub failures: u64,
+    /// 失败次数 / 总次数，没有记录时为 0
+    pub failure_rate: f64,
+    pub bytes: u64,
+    /// 压缩后 / 压缩前 的平均值，没有压缩记录时为 None
+    pub avg_compression_ratio: Option<f64>,
+    pub daily: Vec<DailyStats>,
+    pub hosts: Vec<HostStats>,
+}
+
+pub struct AnalyticsStore {
+    connection: Mutex<Connection>,
+}
+
+fn truncate_error(error: Option<String>) -> Option<String> {
+    error
+        .map(|e| e.trim().chars().take(ERROR_MAX_CHARS).collect::<String>())
+        .filter(|e| !e.is_empty())
+}
+
+impl AnalyticsStore {
+    fn new(app_data_dir: &Path) -> Result<Self, String> {
+        fs::create_dir_all(app_data_dir)
+            .map_err(|e| format!("create_dir_all {}: {e}", app_data_dir.display()))?;
+        let conn = Connection::open(app_data_dir.join(DB_FILE_NAME))
+            .map_err(|e| format!("open analytics db: {e}"))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE IF NOT EXISTS upload_events (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 ts TEXT NOT NULL,
+                 day TEXT NOT NULL,
+                 host TEXT NOT NULL,
+                 success INTEGER NOT NULL,
+                 bytes INTEGER,
+                 original_bytes INTEGER,
+                 duration_ms INTEGER NOT NULL DEFAULT 0,
+                 error TEXT
+             );
+             CREATE INDEX IF NOT EXISTS idx_upload_events_day ON upload_events (day);",
+        )
+        .map_err(|e| format!("init analytics db: {e}"))?;
+        let cutoff = (Local::now() - Duration::days(RETENTION_DAYS))
+            .format("%Y-%m-%d")
+            .to_string();
+        conn.execute("DELETE FROM upload_events WHERE day < ?1", params![cutoff])
+            .map_err(|e| format!("prune analytics: {e}"))?;
+        Ok(Self {
+            connection: Mutex::new(conn),
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, String> {
+        self.connection
+            .lock()
+            .map_err(|_| "analytics db connection poisoned".to_string())
+    }
+
+    fn insert(&self, events: Vec<UploadEvent>) -> Result<(), String> {
+        let mut conn = self.lock()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("begin analytics tx: {e}"))?;
+        for event in events {
+            let ts = match event.timestamp.as_deref() {
+                Some(ts) => DateTime::parse_from_rfc3339(ts)
+                    .map_err(|e| format!("无效的时间 {ts}: {e}"))?
+                    .with_timezone(&Utc),
+                None => Utc::now(),
+            };
+            let day = ts.with_timezone(&Local).format("%Y-%m-%d").to_string();
+            tx.execute(
+                "INSERT INTO upload_events
+                     (ts, day, host, success, bytes, original_bytes, duration_ms, error)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    ts.to_rfc3339(),
+                    day,
+                    event.host,
+                    event.success,
+                    event.bytes.map(|v| v as i64),
+                    event.original_bytes.map(|v| v as i64),
+                    event.duration_ms as i64,
+                    truncate_error(event.error),
+                ],
+            )
+            .map_err(|e| format!("insert upload event: {e}"))?;
+        }
+        tx.commit().map_err(|e| format!("commit analytics: {e}"))
+    }
+
+    fn summary(&self, since: &str) -> Result<AnalyticsSummary, String> {
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT day, COUNT(*), SUM(1 - success),
+                        COALESCE(SUM(CASE WHEN success THEN bytes END), 0)
+                 FROM upload_events WHERE day >= ?1
+                 GROUP BY day ORDER BY day",
+            )
+            .map_err(|e| format!("prepare daily stats: {e}"))?;
+        let daily = stmt
+            .query_map(params![since], |row| {
+                Ok(DailyStats {
+                    day: row.get(0)?,
+                    uploads: row.get::<_, i64>(1)? as u64,
+                    failures: row.get::<_, i64>(2)? as u64,
+                    bytes: row.get::<_, i64>(3)? as u64,
+                })
+            })
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| format!("query daily stats: {e}"))?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT host, COUNT(*), SUM(1 - success),
+                        COALESCE(SUM(CASE WHEN success THEN bytes END), 0),
+                        AVG(duration_ms)
+                 FROM upload_events WHERE day >= ?1
+                 GROUP BY host ORDER BY 4 DESC, host",
+            )
+            .map_err(|e| format!("prepare host stats: {e}"))?;
+        let hosts = stmt
+            .query_map(params![since], |row| {
+                Ok(HostStats {
+                    host: row.get(0)?,
+                    uploads: row.get::<_, i64>(1)? as u64,
+                    failures: row.get::<_, i64>(2)? as u64,
+                    bytes: row.get::<_, i64>(3)? as u64,
+                    avg_duration_ms: row.get(4)?,
+                })
+            })
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| format!("query host stats: {e}"))?;
+
+        let avg_compression_ratio: Option<f64> = conn
+            .query_row(
+                "SELECT AVG(CAST(bytes AS REAL) / original_bytes) FROM upload_events
+                 WHERE day >= ?1 AND success AND bytes IS NOT NULL AND original_bytes > 0",
+                params![since],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("query compression ratio: {e}"))?;
+
+        let uploads = hosts.iter().map(|h| h.uploads).sum::<u64>();
+        let failures = hosts.iter().map(|h| h.failures).sum::<u64>();
+        Ok(AnalyticsSummary {
+            since: since.to_string(),
+            uploads,
+            failures,
+            failure_rate: if uploads == 0 {
+                0.0
+            } else {
+                failures as f64 / uploads as f64
+            },
+            bytes: hosts.iter().map(|h| h.bytes).sum(),
+            avg_compression_ratio,
+            daily,
+            hosts,
+        })
+    }
+}
+
+fn store_from_app(app: &AppHandle) -> Result<&'static AnalyticsStore, String> {
+    if let Some(store) = ANALYTICS_STORE.get() {
+        return Ok(store);
+    }
+    let dir = crate::portable::app_data_dir(&app).map_err(|e| format!("app_data_dir: {e}"))?;
+    let store = AnalyticsStore::new(&dir)?;
+    let _ = ANALYTICS_STORE.set(store);
+    Ok(ANALYTICS_STORE.get().expect("analytics store initialized"))
+}
+
+/// 后端上传流程记录单次上传，失败只记录日志
+pub(crate) fn record(app: &AppHandle, event: UploadEvent) {
+    if let Err(err) = store_from_app(app).and_then(|store| store.insert(vec![event])) {
+        warn!("record upload event failed: {}", err);
+    }
+}
+
+#[tauri::command]
+pub fn record_upload_events(app: AppHandle, events: Vec<UploadEvent>) -> Result<(), String> {
+    if events.is_empty() {
+        return Ok(());
+    }
+    store_from_app(&app)?.insert(events)
+}
+
+/// 最近 `days` 天（含今天）的上传统计
+#[tauri::command]
+pub fn upload_analytics(app: AppHandle, days: Option<u32>) -> Result<AnalyticsSummary, String> {
+    let days = days.unwrap_or(DEFAULT_DAYS).clamp(1, RETENTION_DAYS as u32);
+    let since = (Local::now() - Duration::days(days as i64 - 1))
+        .format("%Y-%m-%d")
+        .to_string();
+    store_from_app(&app)?.summary(&since)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(host: &str, success: bool, bytes: u64, original: Option<u64>) -> UploadEvent {
+        UploadEvent {
+            host: host.to_string(),
+            success,
+            bytes: Some(bytes),
+            original_bytes: original,
+            duration_ms: 100,
+            error: (!success).then(|| "timeout".to_string()),
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = AnalyticsStore::new(dir.path()).unwrap();
+        store
+            .insert(vec![
+                event("s3", true, 400, Some(1000)),
+                event("s3", true, 300, Some(500)),
+                event("s3", false, 500, None),
+                event("webdav", true, 50, None),
+            ])
+            .unwrap();
+
+        let summary = store.summary("2000-01-01").unwrap();
+        assert_eq!(summary.uploads, 4);
+        assert_eq!(summary.failures, 1);
+        assert_eq!(summary.failure_rate, 0.25);
+        assert_eq!(summary.bytes, 750);
+        assert!((summary.avg_compression_ratio.unwrap() - 0.5).abs() < 1e-9);
+        assert_eq!(summary.daily.len(), 1);
+        assert_eq!(summary.daily[0].uploads, 4);
+        assert_eq!(summary.hosts[0].host, "s3");
+        assert_eq!(summary.hosts[0].bytes, 700);
+        assert_eq!(summary.hosts[0].failures, 1);
+
+        let empty = store.summary("9999-01-01").unwrap();
+        assert_eq!(empty.uploads, 0);
+        assert_eq!(empty.failure_rate, 0.0);
+        assert!(empty.avg_compression_ratio.is_none());
+    }
+
+    #[test]
+    fn test_invalid_timestamp() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = AnalyticsStore::new(dir.path()).unwrap();
+        let mut bad = event("s3", true, 1, None);
+        bad.timestamp = Some("yesterday".to_string());
+        assert!(store.insert(vec![bad]).is_err());
+    }
+}
@@ -0,0 +1,627 @@
+/*
+截图标注（在后端绘制，不依赖 webview 画布导出，避免有损的 canvas 转码）：
+1) `annotate_image(path, ops)` 按顺序执行绘制操作：箭头、矩形框、半透明高亮、文字与步骤序号徽标；
+2) 坐标与尺寸均为图片像素，颜色为 `#rrggbb` 或 `#rrggbbaa`，线条与形状边缘做抗锯齿；
+3) 文字与序号使用系统字体（优先中文字体），也可在操作中通过 `font` 指定字体文件；
+4) 结果以 PNG 写入应用临时目录（原图不变），`upload` 为 true 时直接通过 `upload://request` 交给上传流程。
+*/
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use ab_glyph::{Font, FontVec, PxScale, ScaleFont, point};
+use image::{Rgba, RgbaImage};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tempfile::Builder as TempFileBuilder;
+
+/// 依次尝试的系统字体（Windows、macOS、Linux、Android）
+const FONT_CANDIDATES: &[&str] = &[
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\simhei.ttf",
+    "C:\\Windows\\Fonts\\segoeui.ttf",
+    "C:\\Windows\\Fonts\\arial.ttf",
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "/System/Library/Fonts/Helvetica.ttc",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/system/fonts/NotoSansCJK-Regular.ttc",
+    "/system/fonts/Roboto-Regular.ttf",
+];
+
+static SYSTEM_FONT: OnceLock<Option<FontVec>> = OnceLock::new();
+
+/// 颜色，反序列化自 `#rrggbb` / `#rrggbbaa`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Color(pub [u8; 4]);
+
+impl TryFrom<String> for Color {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let hex = value.trim().trim_start_matches('#');
+        if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+            return Err(format!("无效的颜色: {value}"));
+        }
+        let mut rgba = [255u8; 4];
+        for (index, channel) in rgba.iter_mut().enumerate().take(hex.len() / 2) {
+            *channel = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16)
+                .map_err(|_| format!("无效的颜色: {value}"))?;
+        }
+        Ok(Color(rgba))
+    }
+}
+
+impl Color {
+    const RED: Color = Color([230, 57, 70, 255]);
+    const YELLOW: Color = Color([255, 214, 10, 255]);
+    const WHITE: Color = Color([255, 255, 255, 255]);
+
+    fn with_opacity(self, opacity: f32) -> Color {
+        let [r, g, b, a] = self.0;
+        Color([r, g, b, (a as f32 * opacity.clamp(0.0, 1.0)).round() as u8])
+    }
+}
+
+fn default_color() -> Color {
+    Color::RED
+}
+
+fn default_highlight() -> Color {
+    Color::YELLOW
+}
+
+fn default_stroke() -> f32 {
+    4.0
+}
+
+fn default_opacity() -> f32 {
+    0.4
+}
+
+fn default_font_size() -> f32 {
+    24.0
+}
+
+fn default_radius() -> f32 {
+    16.0
+}
+
+/// 标注操作，按数组顺序绘制（后绘制的覆盖在上层）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnnotationOp {
+    /// 从 (x1, y1) 指向 (x2, y2) 的箭头
+    Arrow {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        #[serde(default = "default_color")]
+        color: Color,
+        #[serde(default = "default_stroke")]
+        stroke: f32,
+    },
+    /// 矩形框（只描边）
+    Rect {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        #[serde(default = "default_color")]
+        color: Color,
+        #[serde(default = "default_stroke")]
+        stroke: f32,
+    },
+    /// 半透明高亮区域
+    Highlight {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        #[serde(default = "default_highlight")]
+        color: Color,
+        #[serde(default = "default_opacity")]
+        opacity: f32,
+    },
+    /// 文字，(x, y) 为左上角；支持换行
+    Text {
+        x: f32,
+        y: f32,
+        text: String,
+        #[serde(default = "default_color")]
+        color: Color,
+        #[serde(default = "default_font_size")]
+        size: f32,
+        /// 文字背景色，不指定时无背景
+        #[serde(default)]
+        background: Option<Color>,
+        /// 字体文件路径，不指定时使用系统字体
+        #[serde(default)]
+        font: Option<String>,
+    },
+    /// 步骤序号徽标，(x, y) 为圆心
+    Step {
+        x: f32,
+        y: f32,
+        number: u32,
+        #[serde(default = "default_color")]
+        color: Color,
+        #[serde(default = "default_radius")]
+        radius: f32,
+        #[serde(default)]
+        font: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotateResult {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 按覆盖率把颜色混合到像素上（源在上的 alpha 混合）
+fn blend(image: &mut RgbaImage, x: i64, y: i64, color: Color, coverage: f32) {
+    if x < 0 || y < 0 || x >= image.width() as i64 || y >= image.height() as i64 {
+        return;
+    }
+    let alpha = color.0[3] as f32 / 255.0 * coverage.clamp(0.0, 1.0);
+    if alpha <= 0.0 {
+        return;
+    }
+    let Rgba(dst) = image.get_pixel_mut(x as u32, y as u32);
+    let dst_alpha = dst[3] as f32 / 255.0;
+    let out_alpha = alpha + dst_alpha * (1.0 - alpha);
+    for (channel, src) in dst.iter_mut().zip(color.0).take(3) {
+        let value = (src as f32 * alpha + *channel as f32 * dst_alpha * (1.0 - alpha)) / out_alpha;
+        *channel = value.round().clamp(0.0, 255.0) as u8;
+    }
+    dst[3] = (out_alpha * 255.0).round() as u8;
+}
+
+/// 对 [min, max] 范围内（裁剪到图片内）的每个像素中心按 coverage 混合颜色
+fn fill_with(
+    image: &mut RgbaImage,
+    (min_x, min_y): (f32, f32),
+    (max_x, max_y): (f32, f32),
+    color: Color,
+    coverage: impl Fn(f32, f32) -> f32,
+) {
+    let x0 = min_x.floor().max(0.0) as i64;
+    let y0 = min_y.floor().max(0.0) as i64;
+    let x1 = (max_x.ceil() as i64).min(image.width() as i64 - 1);
+    let y1 = (max_y.ceil() as i64).min(image.height() as i64 - 1);
+    for y in y0..=y1 {
+        for x in x0..=x1 {
+            let value = coverage(x as f32 + 0.5, y as f32 + 0.5);
+            if value > 0.0 {
+                blend(image, x, y, color, value);
+            }
+        }
+    }
+}
+
+/// 点到线段的距离
+fn segment_distance(px: f32, py: f32, (ax, ay): (f32, f32), (bx, by): (f32, f32)) -> f32 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 == 0.0 {
+        0.0
+    } else {
+        (((px - ax) * dx + (py - ay) * dy) / len2).clamp(0.0, 1.0)
+    };
+    let (cx, cy) = (ax + t * dx, ay + t * dy);
+    ((px - cx).powi(2) + (py - cy).powi(2)).sqrt()
+}
+
+/// 带圆头的粗线段
+fn draw_line(image: &mut RgbaImage, a: (f32, f32), b: (f32, f32), color: Color, stroke: f32) {
+    let half = stroke.max(1.0) / 2.0;
+    fill_with(
+        image,
+        (a.0.min(b.0) - half - 1.0, a.1.min(b.1) - half - 1.0),
+        (a.0.max(b.0) + half + 1.0, a.1.max(b.1) + half + 1.0),
+        color,
+        |x, y| half - segment_distance(x, y, a, b) + 0.5,
+    );
+}
+
+/// 填充凸多边形（顶点按任意方向排列），边缘抗锯齿
+fn fill_convex(image: &mut RgbaImage, points: &[(f32, f32)], color: Color) {
+    let (mut min, mut max) = ((f32::MAX, f32::MAX), (f32::MIN, f32::MIN));
+    for &(x, y) in points {
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
+    }
+    // 按顶点顺序确定内侧方向
+    let area: f32 = (0..points.len())
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum();
+    let sign = if area >= 0.0 { 1.0 } else { -1.0 };
+    fill_with(image, min, max, color, |x, y| {
+        (0..points.len())
+            .map(|i| {
+                let (a, b) = (points[i], points[(i + 1) % points.len()]);
+                let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+                let len = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+                sign * (dx * (y - a.1) - dy * (x - a.0)) / len
+            })
+            .fold(f32::MAX, f32::min)
+            + 0.5
+    });
+}
+
+fn draw_arrow(image: &mut RgbaImage, from: (f32, f32), to: (f32, f32), color: Color, stroke: f32) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let len = (dx * dx + dy * dy).sqrt();
+    if len < 1.0 {
+        return;
+    }
+    let (ux, uy) = (dx / len, dy / len);
+    let head = (stroke * 4.0).max(12.0).min(len);
+    let half = head * 0.5;
+    let base = (to.0 - ux * head, to.1 - uy * head);
+    // 线身止于箭头中部，避免圆头露出箭头尖端
+    let body_end = (to.0 - ux * head * 0.5, to.1 - uy * head * 0.5);
+    draw_line(image, from, body_end, color, stroke);
+    fill_convex(
+        image,
+        &[
+            to,
+            (base.0 - uy * half, base.1 + ux * half),
+            (base.0 + uy * half, base.1 - ux * half),
+        ],
+        color,
+    );
+}
+
+fn draw_rect(image: &mut RgbaImage, rect: (f32, f32, f32, f32), color: Color, stroke: f32) {
+    let (x, y, w, h) = rect;
+    let corners = [(x, y), (x + w, y), (x + w, y + h), (x, y + h)];
+    for (&a, &b) in corners.iter().zip(corners.iter().cycle().skip(1)) {
+        draw_line(image, a, b, color, stroke);
+    }
+}
+
+fn draw_highlight(image: &mut RgbaImage, rect: (f32, f32, f32, f32), color: Color) {
+    let (x, y, w, h) = rect;
+    fill_with(image, (x, y), (x + w, y + h), color, |px, py| {
+        let inside_x = (px - x + 0.5).min(x + w - px + 0.5);
+        let inside_y = (py - y + 0.5).min(y + h - py + 0.5);
+        inside_x.min(inside_y)
+    });
+}
+
+fn draw_circle(image: &mut RgbaImage, center: (f32, f32), radius: f32, color: Color) {
+    fill_with(
+        image,
+        (center.0 - radius - 1.0, center.1 - radius - 1.0),
+        (center.0 + radius + 1.0, center.1 + radius + 1.0),
+        color,
+        |x, y| radius - ((x - center.0).powi(2) + (y - center.1).powi(2)).sqrt() + 0.5,
+    );
+}
+
+fn load_font(path: &Path) -> Result<FontVec, String> {
+    let data = std::fs::read(path).map_err(|e| format!("读取字体 {} 失败: {e}", path.display()))?;
+    FontVec::try_from_vec_and_index(data, 0)
+        .map_err(|e| format!("无效的字体 {}: {e}", path.display()))
+}
+
+fn system_font() -> Option<&'static FontVec> {
+    SYSTEM_FONT
+        .get_or_init(|| {
+            FONT_CANDIDATES
+                .iter()
+                .map(Path::new)
+                .filter(|path| path.is_file())
+                .find_map(|path| {
+                    load_font(path)
+                        .inspect_err(|err| warn!("annotate font: {}", err))
+                        .ok()
+                })
+        })
+        .as_ref()
+}
+
+/// 文字的排版尺寸（宽、高）
+fn measure_text<F: Font>(font: &F, size: f32, text: &str) -> (f32, f32) {
+    let scaled = font.as_scaled(PxScale::from(size));
+    let line_height = scaled.height() + scaled.line_gap();
+    let mut width: f32 = 0.0;
+    let mut lines = 0;
+    for line in text.lines() {
+        lines += 1;
+        let mut x = 0.0;
+        let mut prev = None;
+        for c in line.chars() {
+            let id = scaled.glyph_id(c);
+            if let Some(prev) = prev {
+                x += scaled.kern(prev, id);
+            }
+            x += scaled.h_advance(id);
+            prev = Some(id);
+        }
+        width = width.max(x);
+    }
+    (width, line_height * lines.max(1) as f32 - scaled.line_gap())
+}
+
+/// 以 (x, y) 为左上角绘制文字
+fn draw_text<F: Font>(
+    image: &mut RgbaImage,
+    font: &F,
+    size: f32,
+    (x, y): (f32, f32),
+    text: &str,
+    color: Color,
+) {
+    let scale = PxScale::from(size);
+    let scaled = font.as_scaled(scale);
+    let line_height = scaled.height() + scaled.line_gap();
+    for (index, line) in text.lines().enumerate() {
+        let baseline = y + scaled.ascent() + line_height * index as f32;
+        let mut caret = x;
+        let mut prev = None;
+        for c in line.chars() {
+            let id = scaled.glyph_id(c);
+            if let Some(prev) = prev {
+                caret += scaled.kern(prev, id);
+            }
+            let glyph = id.with_scale_and_position(scale, point(caret, baseline));
+            caret += scaled.h_advance(id);
+            prev = Some(id);
+            let Some(outlined) = font.outline_glyph(glyph) else {
+                continue;
+            };
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                blend(
+                    image,
+                    bounds.min.x as i64 + gx as i64,
+                    bounds.min.y as i64 + gy as i64,
+                    color,
+                    coverage,
+                );
+            });
+        }
+    }
+}
+
+fn resolve_font(font: Option<&str>) -> Result<Option<FontVec>, String> {
+    match font.map(str::trim).filter(|path| !path.is_empty()) {
+        Some(path) => load_font(Path::new(path)).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// 执行单个操作；文字类操作找不到字体时返回错误
+fn apply(image: &mut RgbaImage, op: &AnnotationOp) -> Result<(), String> {
+    match op {
+        AnnotationOp::Arrow {
+            x1,
+            y1,
+            x2,
+            y2,
+            color,
+            stroke,
+        } => draw_arrow(image, (*x1, *y1), (*x2, *y2), *color, *stroke),
+        AnnotationOp::Rect {
+            x,
+            y,
+            width,
+            height,
+            color,
+            stroke,
+        } => draw_rect(image, (*x, *y, *width, *height), *color, *stroke),
+        AnnotationOp::Highlight {
+            x,
+            y,
+            width,
+            height,
+            color,
+            opacity,
+        } => draw_highlight(
+            image,
+            (*x, *y, *width, *height),
+            color.with_opacity(*opacity),
+        ),
+        AnnotationOp::Text {
+            x,
+            y,
+            text,
+            color,
+            size,
+            background,
+            font,
+        } => {
+            let custom = resolve_font(font.as_deref())?;
+            let font = custom
+                .as_ref()
+                .or_else(system_font)
+                .ok_or_else(|| "未找到可用字体，请在标注中指定字体文件".to_string())?;
+            let size = size.max(1.0);
+            if let Some(background) = background {
+                let (width, height) = measure_text(font, size, text);
+                let padding = size * 0.25;
+                draw_highlight(
+                    image,
+                    (
+                        x - padding,
+                        y - padding,
+                        width + padding * 2.0,
+                        height + padding * 2.0,
+                    ),
+                    *background,
+                );
+            }
+            draw_text(image, font, size, (*x, *y), text, *color);
+        }
+        AnnotationOp::Step {
+            x,
+            y,
+            number,
+            color,
+            radius,
+            font,
+        } => {
+            let custom = resolve_font(font.as_deref())?;
+            let font = custom
+                .as_ref()
+                .or_else(system_font)
+                .ok_or_else(|| "未找到可用字体，请在标注中指定字体文件".to_string())?;
+            let radius = radius.max(4.0);
+            draw_circle(image, (*x, *y), radius, *color);
+            let label = number.to_string();
+            // 位数越多字号越小，保证序号落在圆内
+            let size = radius * if label.len() > 2 { 0.8 } else { 1.1 };
+            let (width, height) = measure_text(font, size, &label);
+            draw_text(
+                image,
+                font,
+                size,
+                (x - width / 2.0, y - height / 2.0),
+                &label,
+                Color::WHITE,
+            );
+        }
+    }
+    Ok(())
+}
+
+fn annotate(path: &Path, ops: &[AnnotationOp]) -> Result<AnnotateResult, String> {
+    let mut image = image::open(path)
+        .map_err(|e| format!("读取图片 {} 失败: {e}", path.display()))?
+        .to_rgba8();
+    for (index, op) in ops.iter().enumerate() {
+        apply(&mut image, op).map_err(|err| format!("第 {} 个标注: {err}", index + 1))?;
+    }
+
+    let dir = crate::portable::temp_dir();
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("create app temp dir {}: {}", dir.display(), e))?;
+    let tmp = TempFileBuilder::new()
+        .prefix("yana_annotate_")
+        .suffix(".png")
+        .tempfile_in(&dir)
+        .map_err(|e| format!("tempfile_in: {}", e))?;
+    image
+        .save_with_format(tmp.path(), image::ImageFormat::Png)
+        .map_err(|e| format!("encode annotated png: {e}"))?;
+    let output: PathBuf = tmp
+        .into_temp_path()
+        .keep()
+        .map_err(|e| format!("keep temp: {}", e))?;
+    Ok(AnnotateResult {
+        path: output.to_string_lossy().into_owned(),
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+/// 在图片上绘制标注，结果写入新的 PNG 文件；`upload` 为 true 时请求上传
+#[tauri::command]
+pub async fn annotate_image(
+    app: AppHandle,
+    path: String,
+    ops: Vec<AnnotationOp>,
+    upload: Option<bool>,
+) -> Result<AnnotateResult, String> {
+    let count = ops.len();
+    let result = tokio::task::spawn_blocking(move || annotate(Path::new(&path), &ops))
+        .await
+        .map_err(|e| format!("spawn_blocking error: {}", e))??;
+    info!(
+        "annotate image: ops={}, size={}x{}, path={}",
+        count, result.width, result.height, result.path
+    );
+    if upload.unwrap_or(false) {
+        crate::upload::request_upload(&app, vec![result.path.clone()], "annotate");
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas() -> RgbaImage {
+        RgbaImage::from_pixel(100, 100, Rgba([255, 255, 255, 255]))
+    }
+
+    #[test]
+    fn test_color() {
+        assert_eq!(
+            Color::try_from("#ff0080".to_string()),
+            Ok(Color([255, 0, 128, 255]))
+        );
+        assert_eq!(
+            Color::try_from("00ff0040".to_string()),
+            Ok(Color([0, 255, 0, 64]))
+        );
+        assert!(Color::try_from("#fff".to_string()).is_err());
+        assert!(Color::try_from("#gggggg".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_ops() {
+        let ops: Vec<AnnotationOp> = serde_json::from_value(serde_json::json!([
+            { "type": "arrow", "x1": 0, "y1": 0, "x2": 10, "y2": 10 },
+            { "type": "highlight", "x": 1, "y": 2, "width": 3, "height": 4, "opacity": 0.5 },
+            { "type": "step", "x": 5, "y": 5, "number": 2, "color": "#0000ff" },
+        ]))
+        .unwrap();
+        assert!(matches!(
+            ops[0],
+            AnnotationOp::Arrow { color, stroke, .. } if color == Color::RED && stroke == 4.0
+        ));
+        assert!(matches!(ops[2], AnnotationOp::Step { color, .. } if color.0 == [0, 0, 255, 255]));
+        assert!(
+            serde_json::from_value::<AnnotationOp>(serde_json::json!({ "type": "blur" })).is_err()
+        );
+    }
+
+    #[test]
+    fn test_rect_and_highlight() {
+        let mut image = canvas();
+        draw_rect(&mut image, (20.0, 20.0, 40.0, 40.0), Color::RED, 4.0);
+        assert_eq!(image.get_pixel(20, 40).0, Color::RED.0);
+        assert_eq!(image.get_pixel(40, 40).0, [255, 255, 255, 255]);
+
+        draw_highlight(
+            &mut image,
+            (30.0, 30.0, 20.0, 20.0),
+            Color([0, 0, 0, 255]).with_opacity(0.5),
+        );
+        let [r, g, b, a] = image.get_pixel(40, 40).0;
+        assert!((126..=129).contains(&r) && r == g && g == b && a == 255);
+        assert_eq!(image.get_pixel(10, 10).0, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_arrow_and_circle() {
+        let mut image = canvas();
+        draw_arrow(&mut image, (10.0, 50.0), (90.0, 50.0), Color::RED, 4.0);
+        assert_eq!(image.get_pixel(30, 50).0, Color::RED.0);
+        // 箭头尖端附近更宽
+        assert_eq!(image.get_pixel(80, 53).0, Color::RED.0);
+        assert_eq!(image.get_pixel(30, 60).0, [255, 255, 255, 255]);
+
+        let mut image = canvas();
+        draw_circle(&mut image, (50.0, 50.0), 10.0, Color::RED);
+        assert_eq!(image.get_pixel(50, 50).0, Color::RED.0);
+        assert_eq!(image.get_pixel(50, 65).0, [255, 255, 255, 255]);
+    }
+}
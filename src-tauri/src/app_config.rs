@@ -0,0 +1,189 @@
+/*
+完整应用配置导出 / 导入（换机迁移与备份）：
+1) `export_app_config` 将 settings.json、图床配置与用户插件（沿用 host_bundle 的打包内容）、
+   可选的钥匙串敏感字段与图库数据库打包为一个加密文件，加密方式与图床配置导出相同；
+2) `preview_app_config` 只解密并返回摘要，供界面展示后由用户确认；
+3) `import_app_config` 写回全局设置（立即生效并广播）与图床配置；
+   `includeGallery` 为 true 时以导出文件中的图库记录替换当前图库。
+*/
+
+use std::fs;
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use log::{error, info};
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::host_bundle::{
+    BundlePayload, apply_host_payload, collect_host_payload, open_bundle, seal_bundle,
+};
+use crate::settings::{SettingsPayload, current_settings, save_settings};
+
+const APP_CONFIG_FORMAT: &str = "yana-app-config";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AppConfigPayload {
+    exported_at: String,
+    #[serde(default)]
+    settings: Option<SettingsPayload>,
+    #[serde(default)]
+    image_hosts: BundlePayload,
+    /// 图库数据库快照（base64），未选择导出图库时为空
+    #[serde(default)]
+    gallery: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppConfigSummary {
+    pub exported_at: String,
+    pub settings: bool,
+    pub image_hosts: usize,
+    pub plugins: usize,
+    pub secrets: usize,
+    /// 导出文件中的图库记录数，未包含图库时为 None
+    pub gallery_items: Option<usize>,
+}
+
+fn write_temp_db(bytes: &[u8]) -> Result<tempfile::NamedTempFile, String> {
+    let file = tempfile::NamedTempFile::new().map_err(|e| format!("create temp file: {e}"))?;
+    fs::write(file.path(), bytes).map_err(|e| format!("write temp file: {e}"))?;
+    Ok(file)
+}
+
+fn decode_gallery(encoded: &str) -> Result<Vec<u8>, String> {
+    BASE64
+        .decode(encoded)
+        .map_err(|e| format!("invalid gallery in bundle: {e}"))
+}
+
+fn count_gallery_items(bytes: &[u8]) -> Result<usize, String> {
+    let file = write_temp_db(bytes)?;
+    let conn = Connection::open_with_flags(file.path(), OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("open gallery snapshot: {e}"))?;
+    conn.query_row("SELECT COUNT(1) FROM gallery_items", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map(|count| count as usize)
+    .map_err(|e| format!("read gallery snapshot: {e}"))
+}
+
+fn snapshot_gallery(app: &AppHandle) -> Result<String, String> {
+    let store = crate::gallery::store_from_app(app)?;
+    let dir = tempfile::tempdir().map_err(|e| format!("create temp dir: {e}"))?;
+    let path = dir.path().join("gallery.db");
+    store
+        .snapshot_to(&path)
+        .map_err(|e| format!("snapshot gallery: {e}"))?;
+    let bytes = fs::read(&path).map_err(|e| format!("read {}: {e}", path.display()))?;
+    Ok(BASE64.encode(bytes))
+}
+
+impl AppConfigPayload {
+    fn summary(&self) -> Result<AppConfigSummary, String> {
+        let hosts = self.image_hosts.summary();
+        let gallery_items = match &self.gallery {
+            Some(encoded) => Some(count_gallery_items(&decode_gallery(encoded)?)?),
+            None => None,
+        };
+        Ok(AppConfigSummary {
+            exported_at: self.exported_at.clone(),
+            settings: self.settings.is_some(),
+            image_hosts: hosts.settings,
+            plugins: hosts.plugins,
+            secrets: hosts.secrets,
+            gallery_items,
+        })
+    }
+}
+
+async fn read_bundle(path: &str, passphrase: &str) -> Result<AppConfigPayload, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("读取导入文件失败 {}: {e}", path))?;
+    open_bundle(&text, APP_CONFIG_FORMAT, passphrase).await
+}
+
+/// 导出完整应用配置为加密文件
+#[tauri::command]
+pub async fn export_app_config(
+    app: AppHandle,
+    path: String,
+    passphrase: String,
+    include_secrets: Option<bool>,
+    include_gallery: Option<bool>,
+) -> Result<AppConfigSummary, String> {
+    let settings = current_settings(&app);
+    // 未选择导出敏感字段时保留占位符，导入到本机时沿用钥匙串中的值
+    let settings = if include_secrets.unwrap_or(false) {
+        crate::settings_secrets::resolve_all_settings_secrets(settings)?
+    } else {
+        settings
+    };
+    let payload = AppConfigPayload {
+        exported_at: chrono::Local::now().to_rfc3339(),
+        settings: Some(settings),
+        image_hosts: collect_host_payload(&app, include_secrets.unwrap_or(false))?,
+        gallery: if include_gallery.unwrap_or(false) {
+            Some(snapshot_gallery(&app)?)
+        } else {
+            None
+        },
+    };
+    let text = seal_bundle(APP_CONFIG_FORMAT, &payload, &passphrase).await?;
+    fs::write(&path, text).map_err(|e| {
+        error!("export_app_config failed: path={}, error={}", path, e);
+        format!("写入导出文件失败 {}: {e}", path)
+    })?;
+
+    let summary = payload.summary()?;
+    info!(
+        "export_app_config success: path={}, image_hosts={}, plugins={}, gallery_items={:?}",
+        path, summary.image_hosts, summary.plugins, summary.gallery_items
+    );
+    Ok(summary)
+}
+
+/// 解密导出文件并返回摘要，不做任何修改
+#[tauri::command]
+pub async fn preview_app_config(
+    path: String,
+    passphrase: String,
+) -> Result<AppConfigSummary, String> {
+    read_bundle(&path, &passphrase).await?.summary()
+}
+
+/// 导入完整应用配置；同名图床配置与插件会被覆盖
+#[tauri::command]
+pub async fn import_app_config(
+    app: AppHandle,
+    path: String,
+    passphrase: String,
+    include_gallery: Option<bool>,
+) -> Result<AppConfigSummary, String> {
+    let mut payload = read_bundle(&path, &passphrase).await?;
+    let mut summary = payload.summary()?;
+
+    if let Some(settings) = payload.settings.take() {
+        save_settings(app.clone(), settings)?;
+    }
+    apply_host_payload(&app, std::mem::take(&mut payload.image_hosts))?;
+
+    summary.gallery_items = match (payload.gallery.as_deref(), include_gallery.unwrap_or(false)) {
+        (Some(encoded), true) => {
+            let file = write_temp_db(&decode_gallery(encoded)?)?;
+            let store = crate::gallery::store_from_app(&app)?;
+            let count = store
+                .replace_from(file.path())
+                .map_err(|e| format!("import gallery: {e}"))?;
+            Some(count)
+        }
+        _ => None,
+    };
+
+    info!(
+        "import_app_config success: path={}, image_hosts={}, plugins={}, gallery_items={:?}",
+        path, summary.image_hosts, summary.plugins, summary.gallery_items
+    );
+    Ok(summary)
+}
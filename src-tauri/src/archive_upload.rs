@@ -0,0 +1,238 @@
+/*
+打包上传（多张图片打包为一个 ZIP 上传）：
+1) 仅支持可存放任意文件的图床（S3、WebDAV、SFTP、本地文件夹），其他图床直接报错；
+2) 图片按选择顺序写入临时目录中的 ZIP（图片本身已压缩，以存储方式写入），
+   包内重名文件自动加序号；开启「上传前压缩」时先压缩再打包；
+3) 压缩包文件名为 `images_<日期>_<时间>.zip`（可在选项中指定），并套用设置中的文件名模板；
+   S3 的对象键会自行套用模板，上传时传入原始名称避免重复套用；
+4) 上传后作为一个图库条目记录，并写入包含的文件清单（`gallery_archive_files`）。
+*/
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use chrono::Local;
+use log::info;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use zip::write::SimpleFileOptions;
+
+use crate::batch_upload::BatchUploadOptions;
+use crate::gallery::{ArchiveFile, NewGalleryItem};
+
+/// 支持上传任意文件的图床（加密上传同样使用）
+pub(crate) const ARCHIVE_HOSTS: &[&str] = &["s3", "webdav", "sftp", "local"];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ArchiveOptions {
+    /// 压缩包文件名（不含模板处理），默认按当前时间生成
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub upload: BatchUploadOptions,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveUploadResult {
+    pub file_name: String,
+    pub url: String,
+    pub delete_id: Option<String>,
+    pub gallery_id: Option<i64>,
+    pub size: u64,
+    pub files: Vec<ArchiveFile>,
+}
+
+fn default_name() -> String {
+    format!("images_{}.zip", Local::now().format("%Y%m%d_%H%M%S"))
+}
+
+/// 压缩包名称：去除非法字符并保证扩展名为 .zip
+fn archive_name(name: Option<&str>) -> String {
+    let name = name
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(crate::internal_hosts::sanitize_file_name)
+        .unwrap_or_else(default_name);
+    if name.to_ascii_lowercase().ends_with(".zip") {
+        name
+    } else {
+        format!("{name}.zip")
+    }
+}
+
+/// 把 (包内名称, 文件路径) 依次写入 ZIP，返回文件清单
+fn write_zip(dest: &Path, entries: &[(String, String)]) -> Result<Vec<ArchiveFile>, String> {
+    let file = File::create(dest).map_err(|e| format!("创建压缩包失败: {e}"))?;
+    let mut zip = zip::ZipWriter::new(BufWriter::new(file));
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Stored)
+        .large_file(true);
+    let mut used = HashSet::new();
+    let mut files = Vec::with_capacity(entries.len());
+    for (name, path) in entries {
+        let name = crate::file_picker::unique_name(&mut used, name);
+        let bytes = std::fs::read(path).map_err(|e| format!("读取 {path} 失败: {e}"))?;
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("写入压缩包失败: {e}"))?;
+        zip.write_all(&bytes)
+            .map_err(|e| format!("写入压缩包失败: {e}"))?;
+        files.push(ArchiveFile {
+            name,
+            size: Some(bytes.len() as i64),
+        });
+    }
+    zip.finish()
+        .and_then(|mut writer| writer.flush().map_err(Into::into))
+        .map_err(|e| format!("写入压缩包失败: {e}"))?;
+    Ok(files)
+}
+
+/// 把多张图片打包为一个 ZIP 上传到支持任意文件的图床
+#[tauri::command]
+pub async fn upload_archive(
+    app: AppHandle,
+    paths: Vec<String>,
+    host: String,
+    options: Option<ArchiveOptions>,
+) -> Result<ArchiveUploadResult, String> {
+    let host = host.trim().to_string();
+    if !ARCHIVE_HOSTS.contains(&host.as_str()) {
+        return Err(format!("图床 {host} 不支持上传压缩包"));
+    }
+    let paths: Vec<String> = paths
+        .into_iter()
+        .filter(|path| crate::upload::is_image_file(Path::new(path)))
+        .collect();
+    if paths.is_empty() {
+        return Err("没有可打包的图片".to_string());
+    }
+    let options = options.unwrap_or_default();
+    let pipeline = options.upload.pipeline();
+
+    // 压缩后的文件名沿用原文件名（格式改变时替换扩展名）
+    let prepared = crate::host_upload::prepare(&app, &paths, &pipeline).await;
+    let entries: Vec<(String, String)> = paths
+        .iter()
+        .zip(prepared)
+        .map(|(source, prepared)| {
+            let name =
+                crate::host_upload::upload_file_name(Path::new(source), Path::new(&prepared));
+            (name, prepared)
+        })
+        .collect();
+
+    let original = archive_name(options.name.as_deref());
+    let dir = crate::rehost::temp_dir("yana_archive_")?;
+    let staging = dir.path().join(&original);
+    let files = {
+        let (staging, entries) = (staging.clone(), entries);
+        tokio::task::spawn_blocking(move || write_zip(&staging, &entries))
+            .await
+            .map_err(|e| format!("spawn_blocking error: {}", e))??
+    };
+    let bytes = std::fs::read(&staging).map_err(|e| format!("读取压缩包失败: {e}"))?;
+    let file_name = crate::internal_hosts::sanitize_file_name(&crate::filename_template::apply(
+        &original, &bytes,
+    ));
+    let archive = dir.path().join(&file_name);
+    std::fs::rename(&staging, &archive).map_err(|e| format!("重命名压缩包失败: {e}"))?;
+    info!(
+        "upload archive: host={}, file={}, images={}, bytes={}",
+        host,
+        file_name,
+        files.len(),
+        bytes.len()
+    );
+
+    let upload_name = if host == "s3" {
+        original.clone()
+    } else {
+        file_name.clone()
+    };
+    let result = crate::host_upload::upload_to_host(
+        &app,
+        &host,
+        &archive.to_string_lossy(),
+        Some(upload_name),
+    )
+    .await?;
+
+    let gallery_id = if pipeline.record_gallery {
+        let store = crate::gallery::store_from_app(&app)?;
+        let item = crate::gallery::gallery_insert_item(
+            app.clone(),
+            NewGalleryItem {
+                file_name: file_name.clone(),
+                url: result.url.clone(),
+                host: host.clone(),
+                delete_marker: Some(result.delete_id.clone()).filter(|id| !id.is_empty()),
+                inserted_at: None,
+                filesize: Some(bytes.len() as i64),
+                content_hash: None,
+            },
+        )?;
+        store
+            .set_archive_files(item.id, &files)
+            .map_err(|err| err.to_string())?;
+        Some(item.id)
+    } else {
+        None
+    };
+
+    Ok(ArchiveUploadResult {
+        file_name,
+        url: result.url,
+        delete_id: Some(result.delete_id).filter(|id| !id.is_empty()),
+        gallery_id,
+        size: bytes.len() as u64,
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_name() {
+        assert_eq!(archive_name(Some("trip")), "trip.zip");
+        assert_eq!(archive_name(Some("trip.ZIP")), "trip.ZIP");
+        assert!(archive_name(None).starts_with("images_"));
+        assert!(archive_name(Some("  ")).ends_with(".zip"));
+    }
+
+    #[test]
+    fn test_write_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.png");
+        let b = dir.path().join("b.png");
+        std::fs::write(&a, b"first").unwrap();
+        std::fs::write(&b, b"second!").unwrap();
+        let entries = vec![
+            ("cat.png".to_string(), a.to_string_lossy().into_owned()),
+            ("cat.png".to_string(), b.to_string_lossy().into_owned()),
+        ];
+        let dest = dir.path().join("out.zip");
+        let files = write_zip(&dest, &entries).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                ArchiveFile {
+                    name: "cat.png".to_string(),
+                    size: Some(5),
+                },
+                ArchiveFile {
+                    name: "cat_1.png".to_string(),
+                    size: Some(7),
+                },
+            ]
+        );
+
+        let mut archive = zip::ZipArchive::new(File::open(&dest).unwrap()).unwrap();
+        assert_eq!(archive.len(), 2);
+        assert_eq!(archive.by_index(1).unwrap().name(), "cat_1.png");
+    }
+}
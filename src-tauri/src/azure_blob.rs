@@ -0,0 +1,389 @@
+/*
+内置 Azure Blob Storage 图床（rusty_s3 无法对接 Azure API）：
+1) 认证：账号密钥（Shared Key，HMAC-SHA256 签名请求）或 SAS 令牌（追加在请求地址后）；
+2) 上传：Put Blob（BlockBlob）到 `container/remoteDir/路径模板`；
+3) 返回链接：公开容器直接返回 Blob 地址或自定义 CDN 地址；私有容器可选生成只读 SAS 链接
+   （仅账号密钥模式可按有效期签发；已配置的 SAS 令牌带写入权限，不会附加到链接中）；
+4) deleteId 中记录账号、容器与 Blob 路径，删除时凭据从已保存的配置中读取。
+*/
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use bytes::Bytes;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use log::{error, info};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::Sha256;
+use tauri::AppHandle;
+
+use crate::internal_hosts::{
+    HostDeleteResult, HostHttpConfig, HostUploadResult, build_public_url, decode_delete_marker,
+    encode_delete_marker, join_remote_path, load_host_settings, read_upload_file,
+    render_path_template,
+};
+use crate::upload::run_cancellable;
+
+const PLUGIN_ID: &str = "azure";
+const API_VERSION: &str = "2021-08-06";
+const DEFAULT_TIMEOUT_MS: u64 = 60_000;
+const DEFAULT_SAS_EXPIRY_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AzureUrlMode {
+    /// 容器允许公开读取，直接返回 Blob 地址
+    #[default]
+    Public,
+    /// 返回附带只读 SAS 的地址
+    Sas,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureBlobConfig {
+    pub account: String,
+    /// 存储账号访问密钥（base64）；与 sasToken 二选一
+    #[serde(default)]
+    pub account_key: Option<String>,
+    /// 具有写 / 删除权限的 SAS 令牌（`sv=...&sig=...`）
+    #[serde(default)]
+    pub sas_token: Option<String>,
+    pub container: String,
+    #[serde(default)]
+    pub remote_dir: Option<String>,
+    #[serde(default)]
+    pub path_template: Option<String>,
+    #[serde(default)]
+    pub url_mode: AzureUrlMode,
+    #[serde(default)]
+    pub sas_expiry_days: Option<i64>,
+    /// 自定义访问根地址（CDN / 自定义域名）
+    #[serde(default)]
+    pub public_url: Option<String>,
+    /// 自定义服务地址，默认 `https://{account}.blob.core.windows.net`（Azurite 等模拟器使用）
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(flatten)]
+    pub http: HostHttpConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureDeleteMarker {
+    account: String,
+    container: String,
+    path: String,
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn hmac_sha256(key_base64: &str, message: &str) -> Result<String, String> {
+    let key = BASE64
+        .decode(key_base64.trim())
+        .map_err(|e| format!("账号密钥不是有效的 base64: {e}"))?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(&key).map_err(|e| format!("init hmac: {e}"))?;
+    mac.update(message.as_bytes());
+    Ok(BASE64.encode(mac.finalize().into_bytes()))
+}
+
+/// Shared Key 签名字符串，仅包含本模块会发送的请求头
+fn shared_key_string_to_sign(
+    method: &Method,
+    content_length: usize,
+    content_type: &str,
+    ms_headers: &[(&str, String)],
+    canonical_resource: &str,
+) -> String {
+    let length = if content_length == 0 {
+        String::new()
+    } else {
+        content_length.to_string()
+    };
+    let mut headers: Vec<_> = ms_headers.iter().collect();
+    headers.sort_by(|a, b| a.0.cmp(b.0));
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+        .collect();
+    format!(
+        "{}\n\n\n{}\n\n{}\n\n\n\n\n\n\n{}{}",
+        method, length, content_type, canonical_headers, canonical_resource
+    )
+}
+
+impl AzureBlobConfig {
+    fn account(&self) -> &str {
+        self.account.trim()
+    }
+
+    fn container(&self) -> &str {
+        self.container.trim().trim_matches('/')
+    }
+
+    fn endpoint(&self) -> String {
+        match non_empty(self.endpoint.as_deref()) {
+            Some(endpoint) => endpoint.trim_end_matches('/').to_string(),
+            None => format!("https://{}.blob.core.windows.net", self.account()),
+        }
+    }
+
+    /// 只读 SAS 链接需要账号密钥签发；配置的 SAS 令牌用于上传与删除，不能公开
+    fn read_sas_key(&self) -> Result<&str, String> {
+        non_empty(self.account_key.as_deref()).ok_or_else(|| {
+            "生成 SAS 只读链接需要填写访问密钥；仅配置 SAS 令牌时请使用公开链接".to_string()
+        })
+    }
+
+    fn sas_token(&self) -> Option<&str> {
+        non_empty(self.sas_token.as_deref()).map(|t| t.trim_start_matches('?'))
+    }
+
+    fn blob_url(&self, container: &str, path: &str) -> Result<reqwest::Url, String> {
+        let mut url = reqwest::Url::parse(&self.endpoint())
+            .map_err(|e| format!("invalid Azure endpoint: {e}"))?;
+        url.path_segments_mut()
+            .map_err(|_| "invalid Azure endpoint".to_string())?
+            .pop_if_empty()
+            .push(container)
+            .extend(path.split('/'));
+        Ok(url)
+    }
+
+    fn client(&self) -> Result<reqwest::Client, String> {
+        self.http.client(DEFAULT_TIMEOUT_MS)
+    }
+
+    /// 构建已签名的请求：优先使用账号密钥，否则附加 SAS 令牌。
+    /// 签名包含请求时间，重试时需重新构建；请求体按图床限速发送
+    fn signed_request(
+        &self,
+        client: &reqwest::Client,
+        method: Method,
+        container: &str,
+        path: &str,
+        extra_ms_headers: Vec<(&'static str, String)>,
+        content_type: &str,
+        body: Bytes,
+    ) -> Result<reqwest::RequestBuilder, String> {
+        let mut url = self.blob_url(container, path)?;
+        let mut ms_headers = extra_ms_headers;
+        ms_headers.push((
+            "x-ms-date",
+            Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+        ));
+        ms_headers.push(("x-ms-version", API_VERSION.to_string()));
+
+        let authorization = match non_empty(self.account_key.as_deref()) {
+            Some(key) => {
+                let canonical_resource = format!("/{}{}", self.account(), url.path());
+                let string_to_sign = shared_key_string_to_sign(
+                    &method,
+                    body.len(),
+                    content_type,
+                    &ms_headers,
+                    &canonical_resource,
+                );
+                Some(format!(
+                    "SharedKey {}:{}",
+                    self.account(),
+                    hmac_sha256(key, &string_to_sign)?
+                ))
+            }
+            None => {
+                let token = self
+                    .sas_token()
+                    .ok_or_else(|| "请填写账号密钥或 SAS 令牌".to_string())?;
+                url.set_query(Some(token));
+                None
+            }
+        };
+
+        let throttle = self.http.throttle(url.as_str());
+        let mut request = client.request(method, url);
+        for (name, value) in ms_headers {
+            request = request.header(name, value);
+        }
+        if let Some(authorization) = authorization {
+            request = request.header(reqwest::header::AUTHORIZATION, authorization);
+        }
+        if !content_type.is_empty() {
+            request = request.header(reqwest::header::CONTENT_TYPE, content_type);
+        }
+        if body.is_empty() {
+            return Ok(request);
+        }
+        Ok(request
+            .header(reqwest::header::CONTENT_LENGTH, body.len())
+            .body(throttle.body(body)))
+    }
+
+    /// 使用账号密钥签发只读服务 SAS（sv=2020-12-06 起的签名格式）
+    fn read_sas(&self, container: &str, path: &str) -> Result<String, String> {
+        let key = self.read_sas_key()?;
+        let days = self
+            .sas_expiry_days
+            .filter(|d| *d > 0)
+            .unwrap_or(DEFAULT_SAS_EXPIRY_DAYS);
+        let expiry = (Utc::now() + chrono::Duration::days(days))
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string();
+        let canonical_resource = format!("/blob/{}/{}/{}", self.account(), container, path);
+        let string_to_sign = [
+            "r",
+            "",
+            expiry.as_str(),
+            canonical_resource.as_str(),
+            "",
+            "",
+            "https",
+            API_VERSION,
+            "b",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+        ]
+        .join("\n");
+        let signature = hmac_sha256(key, &string_to_sign)?;
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("sv", API_VERSION)
+            .append_pair("sr", "b")
+            .append_pair("sp", "r")
+            .append_pair("se", &expiry)
+            .append_pair("spr", "https")
+            .append_pair("sig", &signature)
+            .finish();
+        Ok(query)
+    }
+}
+
+#[tauri::command]
+pub async fn azure_blob_upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: AzureBlobConfig,
+    upload_id: Option<String>,
+) -> Result<HostUploadResult, String> {
+    run_cancellable(upload_id, upload(file_path, original_file_name, config)).await
+}
+
+async fn upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: AzureBlobConfig,
+) -> Result<HostUploadResult, String> {
+    if config.url_mode == AzureUrlMode::Sas {
+        config.read_sas_key()?;
+    }
+    let (file_name, bytes) = read_upload_file(&file_path, original_file_name).await?;
+    let path = join_remote_path(
+        config.remote_dir.as_deref(),
+        &render_path_template(config.path_template.as_deref(), &file_name),
+    );
+    let container = config.container().to_string();
+    let content_type = mime_guess::from_path(&file_name)
+        .first_or_octet_stream()
+        .to_string();
+    let size = bytes.len();
+
+    let client = config.client()?;
+    let response = config
+        .http
+        .send("Azure upload", || {
+            config.signed_request(
+                &client,
+                Method::PUT,
+                &container,
+                &path,
+                vec![("x-ms-blob-type", "BlockBlob".to_string())],
+                &content_type,
+                bytes.clone(),
+            )
+        })
+        .await
+        .map_err(|e| format!("Azure 上传失败: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        error!("azure_blob_upload failed: path={}, status={}", path, status);
+        return Err(format!("Azure 上传失败: HTTP {} {}", status, text));
+    }
+
+    let url = match (non_empty(config.public_url.as_deref()), config.url_mode) {
+        (Some(base), AzureUrlMode::Public) => build_public_url(base, &path),
+        (Some(base), AzureUrlMode::Sas) => format!(
+            "{}?{}",
+            build_public_url(base, &path),
+            config.read_sas(&container, &path)?
+        ),
+        (None, AzureUrlMode::Public) => config.blob_url(&container, &path)?.to_string(),
+        (None, AzureUrlMode::Sas) => {
+            let mut url = config.blob_url(&container, &path)?;
+            url.set_query(Some(&config.read_sas(&container, &path)?));
+            url.to_string()
+        }
+    };
+    let delete_id = encode_delete_marker(&AzureDeleteMarker {
+        account: config.account().to_string(),
+        container: container.clone(),
+        path: path.clone(),
+    })?;
+
+    info!(
+        "azure_blob_upload success: container={}, path={}, size={}",
+        container, path, size
+    );
+    Ok(HostUploadResult {
+        url,
+        delete_id,
+        metadata: Some(json!({ "container": container, "path": path, "size": size })),
+    })
+}
+
+#[tauri::command]
+pub async fn azure_blob_delete(
+    app: AppHandle,
+    delete_id: String,
+) -> Result<HostDeleteResult, String> {
+    let marker: AzureDeleteMarker = decode_delete_marker(&delete_id)?;
+    let mut config: AzureBlobConfig = load_host_settings(&app, PLUGIN_ID)?;
+    config.account = marker.account.clone();
+
+    let client = config.client()?;
+    let response = config
+        .http
+        .send("Azure delete", || {
+            config.signed_request(
+                &client,
+                Method::DELETE,
+                &marker.container,
+                &marker.path,
+                Vec::new(),
+                "",
+                Bytes::new(),
+            )
+        })
+        .await
+        .map_err(|e| format!("Azure 删除失败: {}", e))?;
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND {
+        return Ok(HostDeleteResult::deleted("Blob 已不存在"));
+    }
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("Azure 删除失败: HTTP {} {}", status, text));
+    }
+
+    info!(
+        "azure_blob_delete success: container={}, path={}",
+        marker.container, marker.path
+    );
+    Ok(HostDeleteResult::deleted("Blob 已从 Azure 删除"))
+}
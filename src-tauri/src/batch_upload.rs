@@ -0,0 +1,639 @@
+/*
+后端批量上传任务（取代前端逐个压缩、上传、写图库的循环）：
+1) `start_batch_upload(paths, host, options)` 登记任务并立即返回任务 id，任务在后台逐个文件执行
+   重复检查 → 压缩 → 上传（失败按 retries 重试）→ 写入图库，步骤与命令行共用 host_upload；
+2) 三段组成流水线：重复与隐私检查、压缩（专用线程池）与上传各自并行，文件压缩完成后立即上传，
+   不等整批压缩结束；段间为有界通道，上传跟不上时前面的阶段暂停。检查与上传的并发数取设置中的
+   最大并发上传数，结果按输入顺序保存，单个文件失败不影响其他文件；
+3) 每个文件状态变化发送 `batch-upload://progress`，全部结束后发送 `batch-upload://finished`，
+   并按设置发送系统通知（notifier）；
+4) 任务状态保存在后端，窗口刷新后可通过 `batch_upload_status` / `list_batch_uploads` 恢复，
+   已结束的任务保留最近 `MAX_FINISHED_JOBS` 个；`cancel_batch_upload` 跳过尚未开始的文件；
+5) 文件可附带标签（文件夹导入按子目录名生成），写入图库或命中已有条目后一并保存；
+6) 被元数据隐私策略阻止的文件标记为 `blocked` 并附带 `privacy`，界面可改用 `privacy` 选项重新上传。
+*/
+
+use std::{
+    collections::HashMap,
+    future::ready,
+    sync::{
+        Arc, Mutex, OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use chrono::Utc;
+use futures::{StreamExt, stream};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::host_upload::{PipelineOptions, PipelineOutcome};
+use crate::metadata_privacy::{PrivacyAction, PrivacyFindings, PrivacyPolicy};
+
+const PROGRESS_EVENT: &str = "batch-upload://progress";
+const FINISHED_EVENT: &str = "batch-upload://finished";
+const MAX_FINISHED_JOBS: usize = 10;
+const DEFAULT_RETRIES: u32 = 1;
+const MAX_RETRIES: u32 = 5;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BatchUploadOptions {
+    /// Some(true) 强制压缩，Some(false) 跳过压缩，None 按设置「上传前压缩」
+    pub compress: Option<bool>,
+    pub webp: bool,
+    pub quality: Option<u8>,
+    /// 是否写入图库，默认写入
+    pub record_gallery: Option<bool>,
+    /// 忽略「跳过重复上传」
+    pub force: bool,
+    /// 单个文件上传失败后的重试次数，默认 1
+    pub retries: Option<u32>,
+    /// 覆盖设置中的元数据隐私策略
+    pub privacy: Option<PrivacyPolicy>,
+}
+
+impl BatchUploadOptions {
+    pub(crate) fn pipeline(&self) -> PipelineOptions {
+        PipelineOptions {
+            compress: self.compress,
+            webp: self.webp,
+            quality: self.quality,
+            record_gallery: self.record_gallery.unwrap_or(true),
+            force: self.force,
+            retries: self.retries.unwrap_or(DEFAULT_RETRIES).min(MAX_RETRIES),
+            privacy: self.privacy,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+    Waiting,
+    Compressing,
+    Uploading,
+    Done,
+    /// 内容已上传过，沿用图库中的链接
+    Duplicate,
+    Failed,
+    /// 含有位置或序列号，按隐私策略未上传
+    Blocked,
+    Cancelled,
+}
+
+impl ItemStatus {
+    fn is_finished(self) -> bool {
+        matches!(
+            self,
+            Self::Done | Self::Duplicate | Self::Failed | Self::Blocked | Self::Cancelled
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchItem {
+    pub index: usize,
+    pub path: String,
+    pub status: ItemStatus,
+    pub url: Option<String>,
+    pub delete_id: Option<String>,
+    pub gallery_id: Option<i64>,
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy: Option<PrivacyFindings>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchJob {
+    pub id: String,
+    pub host: String,
+    pub created_at: String,
+    pub total: usize,
+    /// 已结束（成功、重复、失败、阻止或取消）的文件数
+    pub completed: usize,
+    pub failed: usize,
+    pub finished: bool,
+    pub cancelled: bool,
+    pub items: Vec<BatchItem>,
+}
+
+impl BatchJob {
+    fn recount(&mut self) {
+        self.completed = self
+            .items
+            .iter()
+            .filter(|item| item.status.is_finished())
+            .count();
+        self.failed = self
+            .items
+            .iter()
+            .filter(|item| item.status == ItemStatus::Failed)
+            .count();
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchProgress {
+    job_id: String,
+    total: usize,
+    completed: usize,
+    failed: usize,
+    item: BatchItem,
+}
+
+struct JobEntry {
+    job: BatchJob,
+    cancel: Arc<AtomicBool>,
+}
+
+static JOBS: OnceLock<Mutex<HashMap<String, JobEntry>>> = OnceLock::new();
+
+fn jobs() -> &'static Mutex<HashMap<String, JobEntry>> {
+    JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn lock_jobs() -> Result<std::sync::MutexGuard<'static, HashMap<String, JobEntry>>, String> {
+    jobs()
+        .lock()
+        .map_err(|_| "batch upload registry poisoned".to_string())
+}
+
+/// 只保留最近的已结束任务
+fn prune_finished(jobs: &mut HashMap<String, JobEntry>) {
+    let mut finished: Vec<(String, String)> = jobs
+        .values()
+        .filter(|entry| entry.job.finished)
+        .map(|entry| (entry.job.created_at.clone(), entry.job.id.clone()))
+        .collect();
+    if finished.len() <= MAX_FINISHED_JOBS {
+        return;
+    }
+    finished.sort();
+    for (_, id) in finished.iter().take(finished.len() - MAX_FINISHED_JOBS) {
+        jobs.remove(id);
+    }
+}
+
+/// 更新单个文件的状态并发送进度事件
+fn update_item(app: &AppHandle, job_id: &str, index: usize, apply: impl FnOnce(&mut BatchItem)) {
+    let progress = {
+        let Ok(mut jobs) = lock_jobs() else {
+            return;
+        };
+        let Some(entry) = jobs.get_mut(job_id) else {
+            return;
+        };
+        let Some(item) = entry.job.items.get_mut(index) else {
+            return;
+        };
+        apply(item);
+        let item = item.clone();
+        entry.job.recount();
+        BatchProgress {
+            job_id: job_id.to_string(),
+            total: entry.job.total,
+            completed: entry.job.completed,
+            failed: entry.job.failed,
+            item,
+        }
+    };
+    if let Err(err) = app.emit(PROGRESS_EVENT, &progress) {
+        warn!("emit {} failed: {}", PROGRESS_EVENT, err);
+    }
+}
+
+fn apply_outcome(item: &mut BatchItem, outcome: PipelineOutcome) {
+    item.gallery_id = outcome.gallery_id;
+    match outcome.result {
+        Ok(result) => {
+            item.status = if outcome.duplicate {
+                ItemStatus::Duplicate
+            } else {
+                ItemStatus::Done
+            };
+            item.url = Some(result.url);
+            item.delete_id = Some(result.delete_id).filter(|id| !id.is_empty());
+        }
+        Err(err) => {
+            item.status = if outcome.privacy.is_some() {
+                ItemStatus::Blocked
+            } else {
+                ItemStatus::Failed
+            };
+            item.error = Some(err);
+            item.privacy = outcome.privacy;
+        }
+    }
+}
+
+/// 为写入（或命中）的图库条目保存标签
+fn save_tags(app: &AppHandle, outcome: &PipelineOutcome, tags: &[String]) {
+    let Some(id) = outcome.gallery_id.filter(|_| !tags.is_empty()) else {
+        return;
+    };
+    if let Err(err) = crate::gallery::store_from_app(app)
+        .and_then(|store| store.add_tags(id, tags).map_err(|err| err.to_string()))
+    {
+        warn!("save gallery tags failed: id={}, error={}", id, err);
+    }
+}
+
+/// 通过检查、等待压缩与上传的文件
+struct PendingItem {
+    source: String,
+    tags: Vec<String>,
+    hash: Option<String>,
+    strip: bool,
+}
+
+/// 第一段：取消检查、重复检查与隐私策略检查；需要上传时返回待压缩的文件
+async fn check_item(
+    app: &AppHandle,
+    job_id: &str,
+    host: &str,
+    index: usize,
+    (source, tags): (String, Vec<String>),
+    options: &PipelineOptions,
+    cancel: &AtomicBool,
+) -> Option<PendingItem> {
+    if cancel.load(Ordering::Relaxed) {
+        update_item(app, job_id, index, |item| {
+            item.status = ItemStatus::Cancelled
+        });
+        return None;
+    }
+
+    let check = {
+        let (app, source, host, options) = (
+            app.clone(),
+            source.clone(),
+            host.to_string(),
+            options.clone(),
+        );
+        tauri::async_runtime::spawn_blocking(move || {
+            let check = crate::dedup::check(&app, &source, &host, options.force);
+            let action = if check.existing.is_some() {
+                PrivacyAction::Upload
+            } else {
+                crate::host_upload::privacy_action(&app, &source, &options)
+            };
+            (check, action)
+        })
+        .await
+        .ok()
+    };
+    let (hash, existing, action) = check
+        .map(|(check, action)| (check.hash, check.existing, action))
+        .unwrap_or((None, None, PrivacyAction::Upload));
+    if let Some(existing) = existing {
+        let outcome = crate::host_upload::duplicate_outcome(source, existing);
+        save_tags(app, &outcome, &tags);
+        update_item(app, job_id, index, |item| apply_outcome(item, outcome));
+        return None;
+    }
+    if let PrivacyAction::Block(findings) = action {
+        let outcome = crate::host_upload::blocked_outcome(source, findings);
+        update_item(app, job_id, index, |item| apply_outcome(item, outcome));
+        return None;
+    }
+
+    update_item(app, job_id, index, |item| {
+        item.status = ItemStatus::Compressing
+    });
+    Some(PendingItem {
+        source,
+        tags,
+        hash,
+        strip: action == PrivacyAction::Strip,
+    })
+}
+
+/// 第三段：上传压缩完成（或原样）的文件；等待期间取消的任务不再上传
+async fn upload_item(
+    app: &AppHandle,
+    job_id: &str,
+    host: &str,
+    index: usize,
+    (pending, path): (PendingItem, String),
+    options: &PipelineOptions,
+    cancel: &AtomicBool,
+) {
+    let PendingItem {
+        source,
+        tags,
+        hash,
+        strip,
+    } = pending;
+    if cancel.load(Ordering::Relaxed) {
+        if path != source {
+            let _ = std::fs::remove_file(&path);
+        }
+        update_item(app, job_id, index, |item| {
+            item.status = ItemStatus::Cancelled
+        });
+        return;
+    }
+
+    update_item(app, job_id, index, |item| {
+        item.status = ItemStatus::Uploading
+    });
+    let outcome =
+        crate::host_upload::upload_prepared(app, host, source, path, hash, strip, options).await;
+    if let Err(err) = &outcome.result {
+        crate::notifier::upload_failed(app, &outcome.source, host, err);
+    }
+    save_tags(app, &outcome, &tags);
+    update_item(app, job_id, index, |item| apply_outcome(item, outcome));
+}
+
+async fn run_job(
+    app: AppHandle,
+    job_id: String,
+    host: String,
+    files: Vec<(String, Vec<String>)>,
+    options: PipelineOptions,
+    cancel: Arc<AtomicBool>,
+) {
+    let concurrency = crate::host_upload::upload_concurrency(&app);
+    let pending: Mutex<HashMap<usize, PendingItem>> = Mutex::new(HashMap::new());
+    let (check_tx, check_rx) = crate::host_upload::pipeline_channel();
+    let prepared = crate::host_upload::prepare_pipelined(&app, check_rx, &options);
+
+    let checks = async {
+        stream::iter(files.into_iter().enumerate())
+            .map(|(index, file)| {
+                let (app, job_id, host, options, cancel) =
+                    (&app, &job_id, &host, &options, &cancel);
+                async move {
+                    check_item(app, job_id, host, index, file, options, cancel)
+                        .await
+                        .map(|item| (index, item))
+                }
+            })
+            .buffer_unordered(concurrency)
+            .filter_map(ready)
+            .for_each(|(index, item)| {
+                let source = item.source.clone();
+                if let Ok(mut pending) = pending.lock() {
+                    pending.insert(index, item);
+                }
+                let check_tx = &check_tx;
+                async move {
+                    if check_tx.send((index, source)).await.is_err() {
+                        warn!("batch upload compress stage closed: index={}", index);
+                    }
+                }
+            })
+            .await;
+        // 关闭入口通道，压缩段处理完剩余文件后结束
+        drop(check_tx);
+    };
+    let uploads = crate::host_upload::receiver_stream(prepared)
+        .filter_map(|(index, path)| {
+            let item = pending.lock().ok().and_then(|mut pending| pending.remove(&index));
+            ready(item.map(|item| (index, item, path)))
+        })
+        .map(|(index, item, path)| {
+            let (app, job_id, host, options, cancel) = (&app, &job_id, &host, &options, &cancel);
+            async move {
+                upload_item(app, job_id, host, index, (item, path), options, cancel).await
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect::<Vec<()>>();
+    futures::join!(checks, uploads);
+
+    // 压缩段异常退出时未送达的文件记为失败
+    let lost: Vec<usize> = pending
+        .lock()
+        .map(|pending| pending.keys().copied().collect())
+        .unwrap_or_default();
+    for index in lost {
+        update_item(&app, &job_id, index, |item| {
+            item.status = ItemStatus::Failed;
+            item.error = Some("压缩流水线意外结束，文件未上传".to_string());
+        });
+    }
+
+    let job = {
+        let Ok(mut jobs) = lock_jobs() else {
+            return;
+        };
+        let Some(entry) = jobs.get_mut(&job_id) else {
+            return;
+        };
+        entry.job.recount();
+        entry.job.finished = true;
+        let job = entry.job.clone();
+        prune_finished(&mut jobs);
+        job
+    };
+    info!(
+        "batch upload finished: id={}, total={}, failed={}, cancelled={}",
+        job.id, job.total, job.failed, job.cancelled
+    );
+    if let Err(err) = app.emit(FINISHED_EVENT, &job) {
+        warn!("emit {} failed: {}", FINISHED_EVENT, err);
+    }
+    crate::notifier::batch_finished(&app, &job);
+}
+
+/// 登记任务并在后台执行，返回任务 id；`files` 为（路径, 标签），调用方负责去重
+pub(crate) fn start_job(
+    app: AppHandle,
+    host: String,
+    files: Vec<(String, Vec<String>)>,
+    options: PipelineOptions,
+) -> Result<String, String> {
+    if files.is_empty() {
+        return Err("没有要上传的文件".to_string());
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let job = BatchJob {
+        id: id.clone(),
+        host: host.clone(),
+        created_at: Utc::now().to_rfc3339(),
+        total: files.len(),
+        completed: 0,
+        failed: 0,
+        finished: false,
+        cancelled: false,
+        items: files
+            .iter()
+            .enumerate()
+            .map(|(index, (path, tags))| BatchItem {
+                index,
+                path: path.clone(),
+                status: ItemStatus::Waiting,
+                url: None,
+                delete_id: None,
+                gallery_id: None,
+                error: None,
+                tags: tags.clone(),
+                privacy: None,
+            })
+            .collect(),
+    };
+    lock_jobs()?.insert(
+        id.clone(),
+        JobEntry {
+            job,
+            cancel: cancel.clone(),
+        },
+    );
+    info!(
+        "batch upload started: id={}, host={}, files={}",
+        id,
+        host,
+        files.len()
+    );
+
+    tauri::async_runtime::spawn(run_job(app, id.clone(), host, files, options, cancel));
+    Ok(id)
+}
+
+/// 开始批量上传，立即返回任务 id；进度通过事件通知
+#[tauri::command]
+pub fn start_batch_upload(
+    app: AppHandle,
+    paths: Vec<String>,
+    host: String,
+    options: Option<BatchUploadOptions>,
+) -> Result<String, String> {
+    let host = host.trim().to_string();
+    if host.is_empty() {
+        return Err("未指定图床".to_string());
+    }
+    let mut seen = std::collections::HashSet::new();
+    let files: Vec<(String, Vec<String>)> = paths
+        .into_iter()
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty() && seen.insert(path.clone()))
+        .map(|path| (path, Vec::new()))
+        .collect();
+    start_job(app, host, files, options.unwrap_or_default().pipeline())
+}
+
+#[tauri::command]
+pub fn batch_upload_status(job_id: String) -> Result<Option<BatchJob>, String> {
+    Ok(lock_jobs()?.get(&job_id).map(|entry| entry.job.clone()))
+}
+
+/// 进行中与最近结束的任务，按创建时间从新到旧
+#[tauri::command]
+pub fn list_batch_uploads() -> Result<Vec<BatchJob>, String> {
+    let mut jobs: Vec<BatchJob> = lock_jobs()?
+        .values()
+        .map(|entry| entry.job.clone())
+        .collect();
+    jobs.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(jobs)
+}
+
+/// 取消任务：尚未开始的文件标记为已取消，进行中的文件完成后结束；返回是否找到进行中的任务
+#[tauri::command]
+pub fn cancel_batch_upload(job_id: String) -> Result<bool, String> {
+    let mut jobs = lock_jobs()?;
+    let Some(entry) = jobs.get_mut(&job_id).filter(|entry| !entry.job.finished) else {
+        return Ok(false);
+    };
+    entry.cancel.store(true, Ordering::Relaxed);
+    entry.job.cancelled = true;
+    info!("batch upload cancelled: id={}", job_id);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internal_hosts::HostUploadResult;
+
+    fn item() -> BatchItem {
+        BatchItem {
+            index: 0,
+            path: "/a.png".to_string(),
+            status: ItemStatus::Uploading,
+            url: None,
+            delete_id: None,
+            gallery_id: None,
+            error: None,
+            tags: Vec::new(),
+            privacy: None,
+        }
+    }
+
+    #[test]
+    fn test_apply_outcome() {
+        let mut done = item();
+        apply_outcome(
+            &mut done,
+            PipelineOutcome {
+                source: "/a.png".to_string(),
+                result: Ok(HostUploadResult {
+                    url: "https://img.example.com/a.png".to_string(),
+                    delete_id: String::new(),
+                    metadata: None,
+                }),
+                duplicate: true,
+                gallery_id: Some(3),
+                privacy: None,
+            },
+        );
+        assert_eq!(done.status, ItemStatus::Duplicate);
+        assert_eq!(done.url.as_deref(), Some("https://img.example.com/a.png"));
+        assert!(done.delete_id.is_none());
+        assert_eq!(done.gallery_id, Some(3));
+
+        let mut failed = item();
+        apply_outcome(
+            &mut failed,
+            PipelineOutcome {
+                source: "/a.png".to_string(),
+                result: Err("timeout".to_string()),
+                duplicate: false,
+                gallery_id: None,
+                privacy: None,
+            },
+        );
+        assert_eq!(failed.status, ItemStatus::Failed);
+        assert_eq!(failed.error.as_deref(), Some("timeout"));
+
+        let mut blocked = item();
+        let findings = PrivacyFindings {
+            gps: true,
+            serial_number: false,
+        };
+        apply_outcome(
+            &mut blocked,
+            crate::host_upload::blocked_outcome("/a.png".to_string(), findings.clone()),
+        );
+        assert_eq!(blocked.status, ItemStatus::Blocked);
+        assert_eq!(blocked.privacy, Some(findings));
+        assert!(blocked.error.is_some());
+    }
+
+    #[test]
+    fn test_options_pipeline() {
+        let options: BatchUploadOptions = serde_json::from_value(
+            serde_json::json!({ "retries": 9, "webp": true, "privacy": "strip" }),
+        )
+        .unwrap();
+        let pipeline = options.pipeline();
+        assert_eq!(pipeline.privacy, Some(PrivacyPolicy::Strip));
+        assert!(pipeline.webp);
+        assert!(pipeline.record_gallery);
+        assert_eq!(pipeline.retries, MAX_RETRIES);
+        assert_eq!(
+            BatchUploadOptions::default().pipeline().retries,
+            DEFAULT_RETRIES
+        );
+    }
+}
@@ -0,0 +1,294 @@
+/*
+屏幕截图：
+1) 支持全屏（指定显示器，默认主显示器）、当前活动窗口（排除本应用自身的窗口）与选区三种模式；
+2) 选区坐标与显示器位置使用同一坐标系，跨越多个显示器时只截取选区左上角所在的显示器部分，
+   高 DPI 显示器按截图实际像素与显示器尺寸之比换算；
+3) 截图以 PNG 写入应用临时目录，返回路径并发送 `capture://completed` 事件，
+   `upload` 为 true 时直接通过 `upload://request` 交给压缩与上传流程；
+4) 最近一次截图路径保留在内存中，供「上传最近截图」快捷键优先使用。
+*/
+
+use std::{path::PathBuf, sync::Mutex};
+
+use image::{DynamicImage, RgbaImage};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tempfile::Builder as TempFileBuilder;
+use xcap::{Monitor, Window};
+
+const CAPTURE_COMPLETED_EVENT: &str = "capture://completed";
+
+static LAST_CAPTURE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureMode {
+    Fullscreen,
+    Window,
+    Region,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureRegion {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureResult {
+    pub path: String,
+    pub mode: CaptureMode,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub index: usize,
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub primary: bool,
+}
+
+fn capture_error(context: &str, err: impl std::fmt::Display) -> String {
+    format!("{context}: {err}")
+}
+
+fn capture_fullscreen(monitor: Option<usize>) -> Result<RgbaImage, String> {
+    let monitors = Monitor::all().map_err(|e| capture_error("list monitors", e))?;
+    let target = match monitor {
+        Some(index) => monitors
+            .into_iter()
+            .nth(index)
+            .ok_or_else(|| format!("monitor not found: {index}"))?,
+        None => monitors
+            .into_iter()
+            .find(|m| m.is_primary().unwrap_or(false))
+            .ok_or_else(|| "no primary monitor".to_string())?,
+    };
+    target
+        .capture_image()
+        .map_err(|e| capture_error("capture monitor", e))
+}
+
+/// 截取当前获得焦点的窗口；本应用窗口通常处于焦点（从界面触发时），因此排除自身进程
+fn capture_active_window() -> Result<RgbaImage, String> {
+    let own_pid = std::process::id();
+    let windows = Window::all().map_err(|e| capture_error("list windows", e))?;
+    let window = windows
+        .into_iter()
+        .filter(|w| !w.is_minimized().unwrap_or(true))
+        .filter(|w| w.pid().map(|pid| pid != own_pid).unwrap_or(false))
+        .find(|w| w.is_focused().unwrap_or(false))
+        .ok_or_else(|| "未找到活动窗口".to_string())?;
+    window
+        .capture_image()
+        .map_err(|e| capture_error("capture window", e))
+}
+
+/// 将选区换算为显示器截图中的像素矩形，超出显示器的部分被裁掉
+fn region_in_monitor(
+    region: CaptureRegion,
+    origin: (i32, i32),
+    logical_size: (u32, u32),
+    image_size: (u32, u32),
+) -> Option<(u32, u32, u32, u32)> {
+    if region.width == 0 || region.height == 0 || logical_size.0 == 0 || logical_size.1 == 0 {
+        return None;
+    }
+    let scale_x = image_size.0 as f64 / logical_size.0 as f64;
+    let scale_y = image_size.1 as f64 / logical_size.1 as f64;
+    let left = ((region.x - origin.0).max(0) as f64 * scale_x).round() as u32;
+    let top = ((region.y - origin.1).max(0) as f64 * scale_y).round() as u32;
+    if left >= image_size.0 || top >= image_size.1 {
+        return None;
+    }
+    let width = ((region.width as f64 * scale_x).round() as u32).min(image_size.0 - left);
+    let height = ((region.height as f64 * scale_y).round() as u32).min(image_size.1 - top);
+    if width == 0 || height == 0 {
+        return None;
+    }
+    Some((left, top, width, height))
+}
+
+fn capture_region(region: CaptureRegion) -> Result<RgbaImage, String> {
+    let monitor =
+        Monitor::from_point(region.x, region.y).map_err(|e| capture_error("find monitor", e))?;
+    let origin = (
+        monitor.x().map_err(|e| capture_error("monitor x", e))?,
+        monitor.y().map_err(|e| capture_error("monitor y", e))?,
+    );
+    let logical_size = (
+        monitor
+            .width()
+            .map_err(|e| capture_error("monitor width", e))?,
+        monitor
+            .height()
+            .map_err(|e| capture_error("monitor height", e))?,
+    );
+    let image = monitor
+        .capture_image()
+        .map_err(|e| capture_error("capture monitor", e))?;
+    let (left, top, width, height) =
+        region_in_monitor(region, origin, logical_size, image.dimensions())
+            .ok_or_else(|| "选区不在显示器范围内".to_string())?;
+    Ok(DynamicImage::ImageRgba8(image)
+        .crop_imm(left, top, width, height)
+        .to_rgba8())
+}
+
+fn write_png(image: &RgbaImage) -> Result<PathBuf, String> {
+    let dir = crate::portable::temp_dir();
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("create app temp dir {}: {}", dir.display(), e))?;
+    let tmp = TempFileBuilder::new()
+        .prefix("yana_capture_")
+        .suffix(".png")
+        .tempfile_in(&dir)
+        .map_err(|e| format!("tempfile_in: {}", e))?;
+    image
+        .save_with_format(tmp.path(), image::ImageFormat::Png)
+        .map_err(|e| format!("encode capture png: {e}"))?;
+    tmp.into_temp_path()
+        .keep()
+        .map_err(|e| format!("keep temp: {}", e))
+}
+
+/// 最近一次截图的路径（文件已被清理时返回 None）
+pub(crate) fn last_capture() -> Option<PathBuf> {
+    LAST_CAPTURE
+        .lock()
+        .ok()?
+        .clone()
+        .filter(|path| path.is_file())
+}
+
+fn capture(
+    mode: CaptureMode,
+    region: Option<CaptureRegion>,
+    monitor: Option<usize>,
+) -> Result<CaptureResult, String> {
+    let image = match mode {
+        CaptureMode::Fullscreen => capture_fullscreen(monitor)?,
+        CaptureMode::Window => capture_active_window()?,
+        CaptureMode::Region => {
+            capture_region(region.ok_or_else(|| "选区截图需要提供 region".to_string())?)?
+        }
+    };
+    let path = write_png(&image)?;
+    if let Ok(mut last) = LAST_CAPTURE.lock() {
+        *last = Some(path.clone());
+    }
+    Ok(CaptureResult {
+        path: path.to_string_lossy().into_owned(),
+        mode,
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+/// 截图并发送完成事件，`upload` 为 true 时请求上传（供命令、托盘与快捷键共用，阻塞执行）
+pub(crate) fn capture_and_emit(
+    app: &AppHandle,
+    mode: CaptureMode,
+    region: Option<CaptureRegion>,
+    monitor: Option<usize>,
+    upload: bool,
+) -> Result<CaptureResult, String> {
+    let result = capture(mode, region, monitor)?;
+    info!(
+        "capture success: mode={:?}, size={}x{}, path={}",
+        result.mode, result.width, result.height, result.path
+    );
+    if let Err(err) = app.emit(CAPTURE_COMPLETED_EVENT, &result) {
+        warn!("emit {} failed: {}", CAPTURE_COMPLETED_EVENT, err);
+    }
+    if upload {
+        crate::upload::request_upload(app, vec![result.path.clone()], "capture");
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn capture_screen(
+    app: AppHandle,
+    mode: CaptureMode,
+    region: Option<CaptureRegion>,
+    monitor: Option<usize>,
+    upload: Option<bool>,
+) -> Result<CaptureResult, String> {
+    tokio::task::spawn_blocking(move || {
+        capture_and_emit(&app, mode, region, monitor, upload.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("spawn_blocking error: {}", e))?
+}
+
+/// 列出显示器，供前端选择全屏截图目标与绘制选区
+#[tauri::command]
+pub fn list_monitors() -> Result<Vec<MonitorInfo>, String> {
+    let monitors = Monitor::all().map_err(|e| capture_error("list monitors", e))?;
+    Ok(monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| MonitorInfo {
+            index,
+            name: monitor.name().unwrap_or_default(),
+            x: monitor.x().unwrap_or_default(),
+            y: monitor.y().unwrap_or_default(),
+            width: monitor.width().unwrap_or_default(),
+            height: monitor.height().unwrap_or_default(),
+            primary: monitor.is_primary().unwrap_or(false),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_in_monitor() {
+        let region = CaptureRegion {
+            x: 1920 + 100,
+            y: 50,
+            width: 200,
+            height: 100,
+        };
+        // 第二块显示器，逻辑尺寸 1920x1080，截图为 2 倍像素
+        assert_eq!(
+            region_in_monitor(region, (1920, 0), (1920, 1080), (3840, 2160)),
+            Some((200, 100, 400, 200))
+        );
+        // 超出右下边界时裁剪
+        let edge = CaptureRegion {
+            x: 1800,
+            y: 1000,
+            width: 400,
+            height: 400,
+        };
+        assert_eq!(
+            region_in_monitor(edge, (0, 0), (1920, 1080), (1920, 1080)),
+            Some((1800, 1000, 120, 80))
+        );
+        let empty = CaptureRegion {
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 10,
+        };
+        assert_eq!(
+            region_in_monitor(empty, (0, 0), (1920, 1080), (1920, 1080)),
+            None
+        );
+    }
+}
@@ -0,0 +1,294 @@
+/*
+CDN 缓存清除（Cloudflare / BunnyCDN）：
+1) 配置保存在 S3 图床设置中（`cdnProvider`、`cdnZoneId`、`cdnApiToken`、`cdnAutoPurge`），
+   Cloudflare 按 Zone 的 purge_cache 接口每次最多清除 `CLOUDFLARE_BATCH` 个链接，BunnyCDN 逐个链接清除；
+2) 开启自动清除时，S3 上传前先检查同一对象键是否已存在，覆盖后清除该链接的缓存；
+   图床迁移结束后清除从 S3 迁出的条目的旧链接；
+3) `purge_urls(urls, config)` 手动清除指定链接，未传配置时使用已保存的 S3 设置；
+4) 清除失败只记录日志并返回错误列表，不影响上传与迁移结果。
+*/
+
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tauri::AppHandle;
+
+const CLOUDFLARE_API: &str = "https://api.cloudflare.com/client/v4";
+const BUNNY_PURGE_API: &str = "https://api.bunny.net/purge";
+/// Cloudflare 按链接清除时每次请求的上限
+const CLOUDFLARE_BATCH: usize = 30;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CdnProvider {
+    #[default]
+    None,
+    Cloudflare,
+    Bunny,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CdnPurgeConfig {
+    pub cdn_provider: CdnProvider,
+    /// Cloudflare 的 Zone ID，BunnyCDN 不需要
+    pub cdn_zone_id: Option<String>,
+    /// Cloudflare API Token（需 Cache Purge 权限）或 BunnyCDN 账户 API Key
+    pub cdn_api_token: Option<String>,
+    /// 覆盖同名对象与迁移后自动清除
+    pub cdn_auto_purge: bool,
+}
+
+impl CdnPurgeConfig {
+    fn token(&self) -> Option<&str> {
+        self.cdn_api_token
+            .as_deref()
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+    }
+
+    fn zone_id(&self) -> Option<&str> {
+        self.cdn_zone_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|zone| !zone.is_empty())
+    }
+
+    /// 配置完整（服务商、令牌与 Cloudflare 的 Zone ID）
+    pub(crate) fn is_configured(&self) -> bool {
+        match self.cdn_provider {
+            CdnProvider::None => false,
+            CdnProvider::Cloudflare => self.token().is_some() && self.zone_id().is_some(),
+            CdnProvider::Bunny => self.token().is_some(),
+        }
+    }
+
+    pub(crate) fn auto_purge_enabled(&self) -> bool {
+        self.cdn_auto_purge && self.is_configured()
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeResult {
+    pub purged: usize,
+    pub errors: Vec<String>,
+}
+
+/// 去掉空白与重复的链接，按服务商的单次上限分组
+fn batches(provider: CdnProvider, urls: &[String]) -> Vec<Vec<String>> {
+    let mut unique: Vec<String> = Vec::new();
+    for url in urls
+        .iter()
+        .map(|url| url.trim())
+        .filter(|url| !url.is_empty())
+    {
+        if !unique.iter().any(|existing| existing == url) {
+            unique.push(url.to_string());
+        }
+    }
+    let size = match provider {
+        CdnProvider::Cloudflare => CLOUDFLARE_BATCH,
+        _ => 1,
+    };
+    unique.chunks(size).map(<[String]>::to_vec).collect()
+}
+
+/// Cloudflare 响应为 `{ success, errors: [{ code, message }] }`
+fn cloudflare_error(body: &Value) -> Option<String> {
+    if body.get("success").and_then(Value::as_bool) == Some(true) {
+        return None;
+    }
+    let messages: Vec<String> = body
+        .get("errors")
+        .and_then(Value::as_array)
+        .map(|errors| {
+            errors
+                .iter()
+                .filter_map(|error| error.get("message").and_then(Value::as_str))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(if messages.is_empty() {
+        "Cloudflare 未返回成功".to_string()
+    } else {
+        messages.join("; ")
+    })
+}
+
+async fn purge_cloudflare(
+    client: &reqwest::Client,
+    zone_id: &str,
+    token: &str,
+    urls: &[String],
+) -> Result<(), String> {
+    let response = client
+        .post(format!("{CLOUDFLARE_API}/zones/{zone_id}/purge_cache"))
+        .bearer_auth(token)
+        .json(&json!({ "files": urls }))
+        .send()
+        .await
+        .map_err(|e| format!("请求 Cloudflare 失败: {e}"))?;
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or(Value::Null);
+    match cloudflare_error(&body) {
+        None if status.is_success() => Ok(()),
+        Some(error) => Err(format!("Cloudflare 清除失败（HTTP {status}）: {error}")),
+        None => Err(format!("Cloudflare 清除失败（HTTP {status}）")),
+    }
+}
+
+async fn purge_bunny(client: &reqwest::Client, token: &str, url: &str) -> Result<(), String> {
+    let response = client
+        .post(BUNNY_PURGE_API)
+        .query(&[("url", url), ("async", "false")])
+        .header("AccessKey", token)
+        .send()
+        .await
+        .map_err(|e| format!("请求 BunnyCDN 失败: {e}"))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        let text = response.text().await.unwrap_or_default();
+        Err(format!("BunnyCDN 清除 {url} 失败（HTTP {status}）: {text}"))
+    }
+}
+
+/// 按配置清除链接缓存
+pub(crate) async fn purge(config: &CdnPurgeConfig, urls: &[String]) -> Result<PurgeResult, String> {
+    if !config.is_configured() {
+        return Err(
+            "未配置 CDN 缓存清除（服务商、API Token，Cloudflare 还需 Zone ID）".to_string(),
+        );
+    }
+    let token = config.token().unwrap_or_default();
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("failed to build http client: {e}"))?;
+    let mut result = PurgeResult::default();
+    for batch in batches(config.cdn_provider, urls) {
+        let outcome = match config.cdn_provider {
+            CdnProvider::Cloudflare => {
+                purge_cloudflare(&client, config.zone_id().unwrap_or_default(), token, &batch).await
+            }
+            CdnProvider::Bunny => purge_bunny(&client, token, &batch[0]).await,
+            CdnProvider::None => unreachable!("checked by is_configured"),
+        };
+        match outcome {
+            Ok(()) => result.purged += batch.len(),
+            Err(err) => {
+                warn!("cdn purge failed: {}", err);
+                result.errors.push(err);
+            }
+        }
+    }
+    info!(
+        "cdn purge: provider={:?}, purged={}, errors={}",
+        config.cdn_provider,
+        result.purged,
+        result.errors.len()
+    );
+    Ok(result)
+}
+
+/// 已保存的 S3 设置中的 CDN 配置（密钥已从钥匙串解析）
+fn saved_config(app: &AppHandle) -> Result<CdnPurgeConfig, String> {
+    let settings = crate::image_hosts::resolve_plugin_secrets(
+        "s3",
+        crate::host_upload::saved_settings(app, "s3")?,
+    )?;
+    serde_json::from_value(settings).map_err(|e| format!("图床 s3 的 CDN 配置无效: {e}"))
+}
+
+/// 开启自动清除时按已保存的 S3 设置在后台清除（迁移结束后使用）
+pub(crate) fn auto_purge(app: &AppHandle, urls: Vec<String>) {
+    if urls.is_empty() {
+        return;
+    }
+    let config = match saved_config(app) {
+        Ok(config) if config.auto_purge_enabled() => config,
+        Ok(_) => return,
+        Err(err) => {
+            warn!("load cdn purge config failed: {}", err);
+            return;
+        }
+    };
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) = purge(&config, &urls).await {
+            warn!("cdn auto purge failed: {}", err);
+        }
+    });
+}
+
+/// 清除指定链接的 CDN 缓存；未传配置时使用已保存的 S3 设置
+#[tauri::command]
+pub async fn purge_urls(
+    app: AppHandle,
+    urls: Vec<String>,
+    config: Option<CdnPurgeConfig>,
+) -> Result<PurgeResult, String> {
+    let config = match config {
+        Some(config) => config,
+        None => saved_config(&app)?,
+    };
+    purge(&config, &urls).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batches() {
+        let urls: Vec<String> = (0..35)
+            .map(|i| format!("https://cdn.example.com/{i}.png"))
+            .chain([" ".to_string(), "https://cdn.example.com/0.png".to_string()])
+            .collect();
+        let cloudflare = batches(CdnProvider::Cloudflare, &urls);
+        assert_eq!(cloudflare.len(), 2);
+        assert_eq!(cloudflare[0].len(), CLOUDFLARE_BATCH);
+        assert_eq!(cloudflare[1].len(), 5);
+        assert_eq!(batches(CdnProvider::Bunny, &urls).len(), 35);
+    }
+
+    #[test]
+    fn test_config() {
+        let config: CdnPurgeConfig = serde_json::from_value(json!({
+            "bucket": "images",
+            "cdnProvider": "cloudflare",
+            "cdnApiToken": "token",
+            "cdnAutoPurge": true,
+        }))
+        .unwrap();
+        // Cloudflare 缺少 Zone ID
+        assert!(!config.auto_purge_enabled());
+        let config = CdnPurgeConfig {
+            cdn_zone_id: Some("zone".to_string()),
+            ..config
+        };
+        assert!(config.auto_purge_enabled());
+        assert!(!CdnPurgeConfig::default().is_configured());
+    }
+
+    #[test]
+    fn test_cloudflare_error() {
+        assert_eq!(
+            cloudflare_error(&json!({ "success": true, "errors": [] })),
+            None
+        );
+        assert_eq!(
+            cloudflare_error(&json!({
+                "success": false,
+                "errors": [{ "code": 10000, "message": "Authentication error" }]
+            }))
+            .as_deref(),
+            Some("Authentication error")
+        );
+    }
+}
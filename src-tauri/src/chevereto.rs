@@ -0,0 +1,212 @@
+/*
+内置 Chevereto 图床（自建站点通用）：
+1) 上传：POST `{baseUrl}/api/1/upload`，API Key 通过 `X-API-Key` 请求头与 `key` 字段同时提交，
+   兼容 V3 / V4，可选上传到指定相册；
+2) deleteId 中记录站点地址、图片 id 与站点返回的 delete_url；
+3) 删除：调用 `DELETE {baseUrl}/api/1/image/{id}`；站点不支持 API 删除时返回 delete_url，
+   由用户在浏览器中确认删除。deleteId 中的站点与当前配置不一致时不发送 API Key。
+*/
+
+use log::{error, info, warn};
+use reqwest::{StatusCode, multipart};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tauri::AppHandle;
+
+use crate::internal_hosts::{
+    HostDeleteResult, HostHttpConfig, HostUploadResult, decode_delete_marker, encode_delete_marker,
+    load_host_settings, read_upload_file,
+};
+use crate::upload::run_cancellable;
+
+const PLUGIN_ID: &str = "chevereto";
+const DEFAULT_TIMEOUT_MS: u64 = 60_000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheveretoConfig {
+    /// 站点根地址，例如 https://img.example.com
+    pub base_url: String,
+    pub api_key: String,
+    #[serde(default)]
+    pub album_id: Option<String>,
+    #[serde(flatten)]
+    pub http: HostHttpConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CheveretoDeleteMarker {
+    base_url: String,
+    id: String,
+    #[serde(default)]
+    delete_url: Option<String>,
+}
+
+impl CheveretoConfig {
+    fn base_url(&self) -> String {
+        self.base_url.trim().trim_end_matches('/').to_string()
+    }
+
+    fn client(&self) -> Result<reqwest::Client, String> {
+        self.http.client(DEFAULT_TIMEOUT_MS)
+    }
+}
+
+/// 提取 Chevereto 错误响应中的 `error.message`
+fn error_message(status: StatusCode, text: &str) -> String {
+    let message = serde_json::from_str::<Value>(text)
+        .ok()
+        .and_then(|v| {
+            v.pointer("/error/message")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| text.to_string());
+    format!("HTTP {} {}", status, message)
+}
+
+#[tauri::command]
+pub async fn chevereto_upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: CheveretoConfig,
+    upload_id: Option<String>,
+) -> Result<HostUploadResult, String> {
+    run_cancellable(upload_id, upload(file_path, original_file_name, config)).await
+}
+
+async fn upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: CheveretoConfig,
+) -> Result<HostUploadResult, String> {
+    let (file_name, bytes) = read_upload_file(&file_path, original_file_name).await?;
+
+    let mime = mime_guess::from_path(&file_name)
+        .first_or_octet_stream()
+        .to_string();
+    let album = config
+        .album_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty());
+
+    let base_url = config.base_url();
+    let client = config.client()?;
+    let url = format!("{}/api/1/upload", base_url);
+    let response = config
+        .http
+        .send("Chevereto upload", || {
+            let part = config
+                .http
+                .file_part(&url, &bytes, &file_name)
+                .mime_str(&mime)
+                .map_err(|e| format!("invalid content type {}: {}", mime, e))?;
+            let mut form = multipart::Form::new()
+                .text("key", config.api_key.trim().to_string())
+                .text("format", "json")
+                .part("source", part);
+            if let Some(album) = album {
+                form = form.text("album_id", album.to_string());
+            }
+            Ok(client
+                .post(&url)
+                .header("X-API-Key", config.api_key.trim())
+                .multipart(form))
+        })
+        .await
+        .map_err(|e| format!("Chevereto 上传失败: {}", e))?;
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        let message = error_message(status, &text);
+        error!(
+            "chevereto_upload failed: site={}, error={}",
+            base_url, message
+        );
+        return Err(format!("Chevereto 上传失败: {}", message));
+    }
+    let payload: Value =
+        serde_json::from_str(&text).map_err(|e| format!("解析 Chevereto 响应失败: {}", e))?;
+    let image = payload
+        .get("image")
+        .ok_or_else(|| format!("Chevereto 响应缺少 image: {}", text))?;
+    let field = |key: &str| image.get(key).and_then(Value::as_str).map(str::to_string);
+    let url = field("url").ok_or_else(|| "Chevereto 响应缺少 image.url".to_string())?;
+    let id = field("id_encoded")
+        .or_else(|| field("id"))
+        .ok_or_else(|| "Chevereto 响应缺少图片 id".to_string())?;
+
+    let delete_id = encode_delete_marker(&CheveretoDeleteMarker {
+        base_url: base_url.clone(),
+        id: id.clone(),
+        delete_url: field("delete_url"),
+    })?;
+    info!("chevereto_upload success: site={}, id={}", base_url, id);
+    Ok(HostUploadResult {
+        url,
+        delete_id,
+        metadata: Some(json!({
+            "id": id,
+            "viewerUrl": image.get("url_viewer"),
+            "deleteUrl": image.get("delete_url"),
+        })),
+    })
+}
+
+#[tauri::command]
+pub async fn chevereto_delete(
+    app: AppHandle,
+    delete_id: String,
+) -> Result<HostDeleteResult, String> {
+    let marker: CheveretoDeleteMarker = decode_delete_marker(&delete_id)?;
+    let config: CheveretoConfig = load_host_settings(&app, PLUGIN_ID)?;
+    if marker.base_url != config.base_url() {
+        let message = match marker.delete_url {
+            Some(url) => format!("站点地址已变更，请在浏览器中打开删除链接：{}", url),
+            None => format!("站点地址已变更（上传时为 {}），无法删除", marker.base_url),
+        };
+        return Ok(HostDeleteResult {
+            success: false,
+            message: Some(message),
+        });
+    }
+
+    let client = config.client()?;
+    let url = format!("{}/api/1/image/{}", marker.base_url, marker.id);
+    let response = config
+        .http
+        .send("Chevereto delete", || {
+            Ok(client
+                .delete(&url)
+                .header("X-API-Key", config.api_key.trim()))
+        })
+        .await
+        .map_err(|e| format!("Chevereto 删除失败: {}", e))?;
+    let status = response.status();
+    if status.is_success() {
+        info!(
+            "chevereto_delete success: site={}, id={}",
+            marker.base_url, marker.id
+        );
+        return Ok(HostDeleteResult::deleted("图片已从 Chevereto 删除"));
+    }
+
+    let text = response.text().await.unwrap_or_default();
+    warn!(
+        "chevereto_delete failed: site={}, id={}, error={}",
+        marker.base_url,
+        marker.id,
+        error_message(status, &text)
+    );
+    // 旧版站点没有删除接口，只能通过 delete_url 在浏览器中确认
+    let message = match marker.delete_url {
+        Some(url) => format!("站点不支持通过 API 删除，请在浏览器中打开删除链接：{}", url),
+        None => format!("Chevereto 删除失败: {}", error_message(status, &text)),
+    };
+    Ok(HostDeleteResult {
+        success: false,
+        message: Some(message),
+    })
+}
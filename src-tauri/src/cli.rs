@@ -0,0 +1,308 @@
+/*
+命令行模式（与图形界面共用同一可执行文件与后端，不创建窗口）：
+  yana upload <文件...> --host <图床 id> [--webp] [-q|--quality <0-100>] [--no-compress] [--no-gallery] [--force]
+              [--strip-metadata | --keep-metadata] [--json]
+1) 使用界面中已保存的图床配置与设置（压缩质量、PNG 模式、并发、限速）；
+2) 指定 `--webp` 或 `-q` 时强制压缩，否则按设置中的「上传前压缩」决定，`--no-compress` 跳过压缩；
+3) 每个文件上传成功后向标准输出打印链接（`--json` 时每行一个 JSON 对象），并写入图库；
+   开启「跳过重复上传」时，已上传到同一图床的文件直接输出已有链接，`--force` 强制重新上传；
+4) 元数据隐私策略默认取设置，`--strip-metadata` 去除位置等元数据后上传，`--keep-metadata` 不检查；
+5) 退出码：全部成功为 0，有文件失败为 1，参数错误为 2。
+Windows 发布版为 GUI 子系统，需重定向输出（管道或 `> out.txt`）才能看到结果。
+*/
+
+use std::path::PathBuf;
+
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+
+use crate::host_upload::{PipelineOptions, compress_and_upload};
+use crate::metadata_privacy::PrivacyPolicy;
+use crate::upload_queue::UploadQueue;
+
+const USAGE: &str = "用法: yana upload <文件...> --host <图床 id> [选项]
+
+选项:
+  --host <id>          使用的图床（与界面中的插件 id 一致，如 s3、webdav）
+  --webp               转换为 WebP 后上传
+  -q, --quality <n>    压缩质量 0-100（默认取设置）
+  --no-compress        不压缩，直接上传原图
+  --no-gallery         不写入图库
+  --force              内容已上传过也重新上传
+  --strip-metadata     去除 GPS、序列号等元数据后上传
+  --keep-metadata      不按隐私策略检查元数据
+  --json               每行输出一个 JSON 对象（含 file、url、deleteId、duplicate 或 error）
+  -h, --help           显示帮助";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UploadArgs {
+    files: Vec<PathBuf>,
+    host: String,
+    webp: bool,
+    quality: Option<u8>,
+    no_compress: bool,
+    no_gallery: bool,
+    force: bool,
+    privacy: Option<PrivacyPolicy>,
+    json: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CliCommand {
+    Upload(UploadArgs),
+    Help,
+}
+
+/// 解析命令行参数（不含程序名）；第一个参数不是子命令时返回 None，由调用方启动图形界面
+fn parse_args(args: &[String]) -> Result<Option<CliCommand>, String> {
+    match args.first().map(String::as_str) {
+        Some("upload") => {}
+        Some("help" | "--help" | "-h") => return Ok(Some(CliCommand::Help)),
+        _ => return Ok(None),
+    }
+
+    let mut upload = UploadArgs {
+        files: Vec::new(),
+        host: String::new(),
+        webp: false,
+        quality: None,
+        no_compress: false,
+        no_gallery: false,
+        force: false,
+        privacy: None,
+        json: false,
+    };
+    let mut iter = args[1..].iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--host" => {
+                upload.host = iter
+                    .next()
+                    .map(|value| value.trim().to_string())
+                    .ok_or_else(|| "--host 缺少图床 id".to_string())?;
+            }
+            "-q" | "--quality" => {
+                let value = iter.next().ok_or_else(|| format!("{arg} 缺少质量值"))?;
+                let quality = value
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|q| *q <= 100)
+                    .ok_or_else(|| format!("无效的质量值: {value}"))?;
+                upload.quality = Some(quality);
+            }
+            "--webp" => upload.webp = true,
+            "--no-compress" => upload.no_compress = true,
+            "--no-gallery" => upload.no_gallery = true,
+            "--force" => upload.force = true,
+            "--strip-metadata" | "--keep-metadata" => {
+                let policy = if arg == "--strip-metadata" {
+                    PrivacyPolicy::Strip
+                } else {
+                    PrivacyPolicy::Never
+                };
+                if upload.privacy.is_some_and(|current| current != policy) {
+                    return Err("--strip-metadata 不能与 --keep-metadata 同时使用".to_string());
+                }
+                upload.privacy = Some(policy);
+            }
+            "--json" => upload.json = true,
+            "-h" | "--help" => return Ok(Some(CliCommand::Help)),
+            other if other.starts_with('-') => return Err(format!("未知选项: {other}")),
+            file => upload.files.push(PathBuf::from(file)),
+        }
+    }
+
+    if upload.files.is_empty() {
+        return Err("缺少要上传的文件".to_string());
+    }
+    if upload.host.is_empty() {
+        return Err("缺少 --host 参数".to_string());
+    }
+    if upload.no_compress && (upload.webp || upload.quality.is_some()) {
+        return Err("--no-compress 不能与 --webp / --quality 同时使用".to_string());
+    }
+    Ok(Some(CliCommand::Upload(upload)))
+}
+
+fn build_app() -> tauri::Result<tauri::App> {
+    let mut context = crate::context();
+    context.config_mut().app.windows.clear();
+    tauri::Builder::default()
+        .plugin(tauri_plugin_shell::init())
+        .setup(|app| {
+            let settings = crate::settings::current_settings(app.handle());
+            app.manage(UploadQueue::new(settings.max_concurrent_uploads as usize));
+            crate::throttle::set_global_limit(settings.upload_rate_limit_kbps);
+            crate::filename_template::set_global_template(&settings.file_name_template);
+            Ok(())
+        })
+        .build(context)
+}
+
+fn report_error(args: &UploadArgs, file: &str, err: &str) {
+    if args.json {
+        println!("{}", json!({ "file": file, "error": err }));
+    } else {
+        eprintln!("{file}: {err}");
+    }
+}
+
+async fn upload_files(app: &AppHandle, args: &UploadArgs) -> i32 {
+    let mut failed = 0usize;
+
+    let mut sources = Vec::with_capacity(args.files.len());
+    for file in &args.files {
+        let display = file.to_string_lossy();
+        match std::path::absolute(file) {
+            Ok(path) if path.is_file() => sources.push(path.to_string_lossy().into_owned()),
+            _ => {
+                report_error(args, &display, "文件不存在");
+                failed += 1;
+            }
+        }
+    }
+
+    let options = PipelineOptions {
+        compress: if args.no_compress {
+            Some(false)
+        } else if args.webp || args.quality.is_some() {
+            Some(true)
+        } else {
+            None
+        },
+        webp: args.webp,
+        quality: args.quality,
+        record_gallery: !args.no_gallery,
+        force: args.force,
+        retries: 0,
+        privacy: args.privacy,
+    };
+    for outcome in compress_and_upload(app, &args.host, sources, &options).await {
+        match outcome.result {
+            Ok(result) if args.json => println!(
+                "{}",
+                json!({
+                    "file": outcome.source,
+                    "url": result.url,
+                    "deleteId": result.delete_id,
+                    "duplicate": outcome.duplicate,
+                })
+            ),
+            Ok(result) => println!("{}", result.url),
+            Err(err) => {
+                report_error(args, &outcome.source, &err);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 { 1 } else { 0 }
+}
+
+/// 按进程参数执行命令行模式；不是命令行子命令时返回 None
+pub(crate) fn run_from_env() -> Option<i32> {
+    let args: Vec<String> = std::env::args_os()
+        .skip(1)
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let command = match parse_args(&args) {
+        Ok(Some(command)) => command,
+        Ok(None) => return None,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            return Some(2);
+        }
+    };
+    let CliCommand::Upload(upload) = command else {
+        println!("{USAGE}");
+        return Some(0);
+    };
+
+    let app = match build_app() {
+        Ok(app) => app,
+        Err(err) => {
+            eprintln!("初始化失败: {err}");
+            return Some(1);
+        }
+    };
+    let handle = app.handle().clone();
+    Some(tauri::async_runtime::block_on(upload_files(
+        &handle, &upload,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(&args(&[])).unwrap(), None);
+        assert_eq!(
+            parse_args(&args(&["yana://upload?path=a.png"])).unwrap(),
+            None
+        );
+        assert_eq!(
+            parse_args(&args(&["--help"])).unwrap(),
+            Some(CliCommand::Help)
+        );
+
+        let parsed = parse_args(&args(&[
+            "upload",
+            "./img.png",
+            "b.jpg",
+            "--host",
+            "s3",
+            "--webp",
+            "-q",
+            "80",
+            "--strip-metadata",
+        ]))
+        .unwrap();
+        assert_eq!(
+            parsed,
+            Some(CliCommand::Upload(UploadArgs {
+                files: vec![PathBuf::from("./img.png"), PathBuf::from("b.jpg")],
+                host: "s3".to_string(),
+                webp: true,
+                quality: Some(80),
+                no_compress: false,
+                no_gallery: false,
+                force: false,
+                privacy: Some(PrivacyPolicy::Strip),
+                json: false,
+            }))
+        );
+
+        assert!(parse_args(&args(&["upload", "a.png"])).is_err());
+        assert!(parse_args(&args(&["upload", "--host", "s3"])).is_err());
+        assert!(parse_args(&args(&["upload", "a.png", "--host", "s3", "-q", "101"])).is_err());
+        assert!(parse_args(&args(&["upload", "a.png", "--host", "s3", "--bogus"])).is_err());
+        assert!(
+            parse_args(&args(&[
+                "upload",
+                "a.png",
+                "--host",
+                "s3",
+                "--no-compress",
+                "--webp"
+            ]))
+            .is_err()
+        );
+        assert!(
+            parse_args(&args(&[
+                "upload",
+                "a.png",
+                "--host",
+                "s3",
+                "--strip-metadata",
+                "--keep-metadata"
+            ]))
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,439 @@
+/*
+剪贴板监听：
+1) 设置 `clipboardWatch` 开启后，后台每秒轮询剪贴板，检测新的位图或复制的图片文件；
+   Windows（`GetClipboardSequenceNumber`）与 macOS（`NSPasteboard.changeCount`）先比较系统的变化计数，
+   未变化时不读取剪贴板内容，避免每秒复制整张位图；其他平台无计数可用，每次都读取；
+2) 复制的文件：Windows 读取资源管理器的 CF_HDROP，macOS 读取 Finder 的 `public.file-url`，
+   多个文件时取第一个；Linux 依赖文件管理器同时提供的路径文本（`file://` URI 或路径），
+   只提供 `x-special/gnome-copied-files` 等私有格式时无法识别；
+   位图编码为 PNG，经 `save_image_data` 写入应用临时目录；图片文件直接使用原路径；
+3) 检测到新图片时发送 `clipboard://image` 事件（含临时路径），`autoUpload` 取自设置
+   `clipboardAutoUpload`，前端据此直接进入压缩与上传流程；
+4) 开启监听时记录当前剪贴板内容作为基线，不会上传开启前已复制的图片。
+*/
+
+use std::{
+    io::Cursor,
+    path::PathBuf,
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use log::{debug, info, warn};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+pub(crate) const CLIPBOARD_IMAGE_EVENT: &str = "clipboard://image";
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static AUTO_UPLOAD: AtomicBool = AtomicBool::new(false);
+/// 上次处理过的剪贴板内容指纹，None 表示需要重新建立基线
+static LAST_FINGERPRINT: Mutex<Option<String>> = Mutex::new(None);
+/// 上次读取时系统剪贴板的变化计数
+static LAST_CHANGE_COUNT: Mutex<Option<u64>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ClipboardSource {
+    Bitmap,
+    File,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardImageEvent {
+    pub path: String,
+    pub source: ClipboardSource,
+    pub auto_upload: bool,
+}
+
+enum ClipboardImage {
+    Bitmap {
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    },
+    File(PathBuf),
+}
+
+impl ClipboardImage {
+    fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        match self {
+            Self::Bitmap {
+                width,
+                height,
+                rgba,
+            } => {
+                hasher.update(width.to_le_bytes());
+                hasher.update(height.to_le_bytes());
+                hasher.update(rgba);
+            }
+            Self::File(path) => hasher.update(path.to_string_lossy().as_bytes()),
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// 将剪贴板文本解析为图片文件路径（支持 file:// URI，多行时取第一行）
+fn image_path_from_text(text: &str) -> Option<PathBuf> {
+    let line = text.lines().next()?.trim().trim_matches('"');
+    if line.is_empty() {
+        return None;
+    }
+    let path = match url::Url::parse(line) {
+        Ok(url) if url.scheme() == "file" => url.to_file_path().ok()?,
+        _ => PathBuf::from(line),
+    };
+    crate::upload::is_image_file(&path).then_some(path)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::{ffi::c_void, os::windows::ffi::OsStringExt, path::PathBuf};
+
+    const CF_HDROP: u32 = 15;
+
+    #[link(name = "user32")]
+    unsafe extern "system" {
+        fn GetClipboardSequenceNumber() -> u32;
+        fn IsClipboardFormatAvailable(format: u32) -> i32;
+        fn OpenClipboard(owner: *mut c_void) -> i32;
+        fn CloseClipboard() -> i32;
+        fn GetClipboardData(format: u32) -> *mut c_void;
+    }
+
+    #[link(name = "shell32")]
+    unsafe extern "system" {
+        fn DragQueryFileW(drop: *mut c_void, index: u32, file: *mut u16, len: u32) -> u32;
+    }
+
+    pub(super) fn change_count() -> Option<u64> {
+        // SAFETY: 无参数，只读取系统维护的计数
+        Some(u64::from(unsafe { GetClipboardSequenceNumber() }))
+    }
+
+    /// 资源管理器中复制的第一个文件（CF_HDROP）
+    pub(super) fn copied_file() -> Option<PathBuf> {
+        // SAFETY: CF_HDROP 句柄归剪贴板所有，只在打开剪贴板期间读取，关闭前复制出路径
+        unsafe {
+            if IsClipboardFormatAvailable(CF_HDROP) == 0 || OpenClipboard(std::ptr::null_mut()) == 0
+            {
+                return None;
+            }
+            let handle = GetClipboardData(CF_HDROP);
+            let path = if handle.is_null() {
+                None
+            } else {
+                let len = DragQueryFileW(handle, 0, std::ptr::null_mut(), 0);
+                let mut buf = vec![0u16; len as usize + 1];
+                let written = DragQueryFileW(handle, 0, buf.as_mut_ptr(), buf.len() as u32);
+                (written > 0)
+                    .then(|| PathBuf::from(std::ffi::OsString::from_wide(&buf[..written as usize])))
+            };
+            CloseClipboard();
+            path
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::{
+        ffi::{CStr, c_char, c_void},
+        path::PathBuf,
+    };
+
+    type Id = *mut c_void;
+    type MsgSend = unsafe extern "C" fn();
+
+    #[link(name = "AppKit", kind = "framework")]
+    unsafe extern "C" {}
+
+    #[link(name = "objc")]
+    unsafe extern "C" {
+        fn objc_getClass(name: *const c_char) -> Id;
+        fn sel_registerName(name: *const c_char) -> Id;
+        fn objc_msgSend();
+        fn objc_autoreleasePoolPush() -> *mut c_void;
+        fn objc_autoreleasePoolPop(pool: *mut c_void);
+    }
+
+    /// 在自动释放池中访问 `[NSPasteboard generalPasteboard]`，池释放前需复制出结果
+    fn with_pasteboard<T>(read: impl FnOnce(Id) -> Option<T>) -> Option<T> {
+        // SAFETY: objc_msgSend 按被调方法的签名转换后调用；返回的对象为自动释放对象，
+        // 只在池释放前使用
+        unsafe {
+            let pool = objc_autoreleasePoolPush();
+            let send =
+                std::mem::transmute::<MsgSend, unsafe extern "C" fn(Id, Id) -> Id>(objc_msgSend);
+            let pasteboard = send(
+                objc_getClass(c"NSPasteboard".as_ptr()),
+                sel_registerName(c"generalPasteboard".as_ptr()),
+            );
+            let result = if pasteboard.is_null() {
+                None
+            } else {
+                read(pasteboard)
+            };
+            objc_autoreleasePoolPop(pool);
+            result
+        }
+    }
+
+    pub(super) fn change_count() -> Option<u64> {
+        with_pasteboard(|pasteboard| {
+            // SAFETY: `changeCount` 返回 NSInteger
+            let count = unsafe {
+                let send = std::mem::transmute::<MsgSend, unsafe extern "C" fn(Id, Id) -> isize>(
+                    objc_msgSend,
+                );
+                send(pasteboard, sel_registerName(c"changeCount".as_ptr()))
+            };
+            Some(count as u64)
+        })
+    }
+
+    /// Finder 中复制的第一个文件（`public.file-url`）
+    pub(super) fn copied_file() -> Option<PathBuf> {
+        let url = with_pasteboard(|pasteboard| {
+            // SAFETY: 按 `stringWithUTF8String:`、`stringForType:`、`UTF8String` 的签名调用，
+            // 返回的 C 字符串在池释放前复制
+            unsafe {
+                let new_string = std::mem::transmute::<
+                    MsgSend,
+                    unsafe extern "C" fn(Id, Id, *const c_char) -> Id,
+                >(objc_msgSend);
+                let string_for_type = std::mem::transmute::<
+                    MsgSend,
+                    unsafe extern "C" fn(Id, Id, Id) -> Id,
+                >(objc_msgSend);
+                let utf8 = std::mem::transmute::<
+                    MsgSend,
+                    unsafe extern "C" fn(Id, Id) -> *const c_char,
+                >(objc_msgSend);
+                let file_url_type = new_string(
+                    objc_getClass(c"NSString".as_ptr()),
+                    sel_registerName(c"stringWithUTF8String:".as_ptr()),
+                    c"public.file-url".as_ptr(),
+                );
+                let url = string_for_type(
+                    pasteboard,
+                    sel_registerName(c"stringForType:".as_ptr()),
+                    file_url_type,
+                );
+                if url.is_null() {
+                    return None;
+                }
+                let text = utf8(url, sel_registerName(c"UTF8String".as_ptr()));
+                (!text.is_null()).then(|| CStr::from_ptr(text).to_string_lossy().into_owned())
+            }
+        })?;
+        url::Url::parse(&url).ok()?.to_file_path().ok()
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod platform {
+    use std::path::PathBuf;
+
+    pub(super) fn change_count() -> Option<u64> {
+        None
+    }
+
+    pub(super) fn copied_file() -> Option<PathBuf> {
+        None
+    }
+}
+
+/// 比较并记录变化计数；无法获取计数时视为已变化
+fn counter_changed(last: &mut Option<u64>, count: Option<u64>) -> bool {
+    match count {
+        Some(count) => last.replace(count) != Some(count),
+        None => true,
+    }
+}
+
+/// 系统剪贴板自上次读取后是否可能发生了变化
+fn clipboard_changed() -> bool {
+    match LAST_CHANGE_COUNT.lock() {
+        Ok(mut last) => counter_changed(&mut last, platform::change_count()),
+        Err(_) => true,
+    }
+}
+
+fn read_clipboard(app: &AppHandle) -> Option<ClipboardImage> {
+    // Finder 复制文件时剪贴板中同时有文件图标的位图，先检查文件
+    if let Some(path) = platform::copied_file().filter(|path| crate::upload::is_image_file(path)) {
+        return Some(ClipboardImage::File(path));
+    }
+    if let Ok(image) = app.clipboard().read_image() {
+        return Some(ClipboardImage::Bitmap {
+            width: image.width(),
+            height: image.height(),
+            rgba: image.rgba().to_vec(),
+        });
+    }
+    let text = app.clipboard().read_text().ok()?;
+    image_path_from_text(&text).map(ClipboardImage::File)
+}
+
+fn encode_png(width: u32, height: u32, rgba: Vec<u8>) -> Result<Vec<u8>, String> {
+    let buffer = image::RgbaImage::from_raw(width, height, rgba)
+        .ok_or_else(|| "clipboard image size mismatch".to_string())?;
+    let mut bytes = Vec::new();
+    buffer
+        .write_to(&mut Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .map_err(|e| format!("encode clipboard png: {e}"))?;
+    Ok(bytes)
+}
+
+fn store_image(image: ClipboardImage) -> Result<(String, ClipboardSource), String> {
+    match image {
+        ClipboardImage::Bitmap {
+            width,
+            height,
+            rgba,
+        } => {
+            let bytes = encode_png(width, height, rgba)?;
+            let path = crate::process::save_image_data(bytes)?;
+            Ok((path, ClipboardSource::Bitmap))
+        }
+        ClipboardImage::File(path) => {
+            Ok((path.to_string_lossy().into_owned(), ClipboardSource::File))
+        }
+    }
+}
+
+fn poll(app: &AppHandle) {
+    if !clipboard_changed() {
+        return;
+    }
+    let Some(image) = read_clipboard(app) else {
+        return;
+    };
+    let fingerprint = image.fingerprint();
+    {
+        let Ok(mut last) = LAST_FINGERPRINT.lock() else {
+            return;
+        };
+        match last.as_deref() {
+            Some(previous) if previous == fingerprint => return,
+            None => {
+                // 建立基线，不处理开启监听前已存在的内容
+                *last = Some(fingerprint);
+                return;
+            }
+            Some(_) => *last = Some(fingerprint),
+        }
+    }
+
+    match store_image(image) {
+        Ok((path, source)) => {
+            let event = ClipboardImageEvent {
+                path,
+                source,
+                auto_upload: AUTO_UPLOAD.load(Ordering::Relaxed),
+            };
+            info!(
+                "clipboard image detected: source={:?}, path={}",
+                event.source, event.path
+            );
+            if let Err(err) = app.emit(CLIPBOARD_IMAGE_EVENT, event) {
+                warn!("emit {} failed: {}", CLIPBOARD_IMAGE_EVENT, err);
+            }
+        }
+        Err(err) => warn!("store clipboard image failed: {}", err),
+    }
+}
+
+/// 立即读取剪贴板中的图片并写入临时目录（供快捷键等主动触发使用），没有图片时返回 None。
+/// 同时更新指纹，避免监听再次发送同一张图片。
+pub(crate) fn take_current(app: &AppHandle) -> Result<Option<String>, String> {
+    let Some(image) = read_clipboard(app) else {
+        return Ok(None);
+    };
+    if let Ok(mut last) = LAST_FINGERPRINT.lock() {
+        *last = Some(image.fingerprint());
+    }
+    store_image(image).map(|(path, _)| Some(path))
+}
+
+/// 应用设置中的监听开关；从关闭切换到开启时重新建立基线
+pub(crate) fn apply(enabled: bool, auto_upload: bool) {
+    AUTO_UPLOAD.store(auto_upload, Ordering::Relaxed);
+    let was_enabled = ENABLED.swap(enabled, Ordering::Relaxed);
+    if was_enabled != enabled {
+        if let Ok(mut last) = LAST_FINGERPRINT.lock() {
+            *last = None;
+        }
+        if let Ok(mut last) = LAST_CHANGE_COUNT.lock() {
+            *last = None;
+        }
+        info!(
+            "clipboard watcher {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+    }
+}
+
+/// 启动后台轮询任务，未开启监听时每个周期只检查开关
+pub(crate) fn init(app: &AppHandle) {
+    if cfg!(mobile) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if !ENABLED.load(Ordering::Relaxed) {
+                continue;
+            }
+            let app = app.clone();
+            if let Err(err) = tauri::async_runtime::spawn_blocking(move || poll(&app)).await {
+                debug!("clipboard poll task failed: {}", err);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_path_from_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("shot.PNG");
+        std::fs::write(&image, b"png").unwrap();
+        let text_file = dir.path().join("notes.txt");
+        std::fs::write(&text_file, b"text").unwrap();
+
+        let plain = image.to_string_lossy().into_owned();
+        assert_eq!(image_path_from_text(&plain), Some(image.clone()));
+        let uri = url::Url::from_file_path(&image).unwrap().to_string();
+        assert_eq!(image_path_from_text(&format!("{uri}\nother")), Some(image));
+        assert_eq!(image_path_from_text(&text_file.to_string_lossy()), None);
+        assert_eq!(image_path_from_text("hello world"), None);
+        assert_eq!(image_path_from_text("/missing/a.png"), None);
+    }
+
+    #[test]
+    fn test_counter_changed() {
+        let mut last = None;
+        assert!(counter_changed(&mut last, Some(7)));
+        assert!(!counter_changed(&mut last, Some(7)));
+        assert!(counter_changed(&mut last, Some(8)));
+        assert_eq!(last, Some(8));
+        // 无计数的平台每次都读取
+        assert!(counter_changed(&mut last, None));
+        assert!(counter_changed(&mut last, None));
+    }
+}
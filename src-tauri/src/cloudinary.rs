@@ -0,0 +1,247 @@
+/*
+内置 Cloudinary 图床：
+1) 签名上传：参数按键名排序拼接后追加 API Secret 做 SHA-1 签名，Secret 不会随请求发送；
+2) 可选上传预设（upload preset）与目录（folder）；
+3) 返回的分发地址可附加变换字符串（如 `f_auto,q_auto`），插入在 `/upload/` 之后；
+4) deleteId 中记录 cloud name 与 public_id，删除时同样以签名请求调用 destroy 接口。
+*/
+
+use std::collections::BTreeMap;
+
+use log::{error, info};
+use reqwest::multipart;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha1::{Digest, Sha1};
+use tauri::AppHandle;
+
+use crate::internal_hosts::{
+    HostDeleteResult, HostHttpConfig, HostUploadResult, decode_delete_marker, encode_delete_marker,
+    load_host_settings, read_upload_file,
+};
+use crate::upload::run_cancellable;
+
+const PLUGIN_ID: &str = "cloudinary";
+const API_BASE: &str = "https://api.cloudinary.com/v1_1";
+const DEFAULT_TIMEOUT_MS: u64 = 60_000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudinaryConfig {
+    pub cloud_name: String,
+    pub api_key: String,
+    pub api_secret: String,
+    #[serde(default)]
+    pub upload_preset: Option<String>,
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// 分发地址中使用的变换字符串，例如 `f_auto,q_auto`
+    #[serde(default)]
+    pub transformation: Option<String>,
+    #[serde(flatten)]
+    pub http: HostHttpConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CloudinaryDeleteMarker {
+    cloud_name: String,
+    public_id: String,
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// Cloudinary 签名：`k1=v1&k2=v2...` + api_secret 的 SHA-1 十六进制摘要
+fn sign_params(params: &BTreeMap<&str, String>, api_secret: &str) -> String {
+    let joined = params
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{:x}", Sha1::digest(format!("{}{}", joined, api_secret)))
+}
+
+/// 在 `/upload/` 之后插入变换字符串
+fn apply_transformation(url: &str, transformation: Option<&str>) -> String {
+    match non_empty(transformation) {
+        Some(transformation) => url.replacen(
+            "/upload/",
+            &format!("/upload/{}/", transformation.trim_matches('/')),
+            1,
+        ),
+        None => url.to_string(),
+    }
+}
+
+impl CloudinaryConfig {
+    fn client(&self) -> Result<reqwest::Client, String> {
+        self.http.client(DEFAULT_TIMEOUT_MS)
+    }
+
+    /// 生成带 api_key、timestamp 与 signature 的完整参数
+    fn signed_form(&self, mut params: BTreeMap<&'static str, String>) -> multipart::Form {
+        params.insert("timestamp", chrono::Utc::now().timestamp().to_string());
+        let signature = sign_params(&params, self.api_secret.trim());
+        let mut form = multipart::Form::new()
+            .text("api_key", self.api_key.trim().to_string())
+            .text("signature", signature);
+        for (key, value) in params {
+            form = form.text(key, value);
+        }
+        form
+    }
+}
+
+/// 提取 Cloudinary 错误响应中的 `error.message`
+async fn error_message(response: reqwest::Response) -> String {
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<Value>(&text)
+        .ok()
+        .and_then(|v| {
+            v.pointer("/error/message")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .unwrap_or(text);
+    format!("HTTP {} {}", status, message)
+}
+
+#[tauri::command]
+pub async fn cloudinary_upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: CloudinaryConfig,
+    upload_id: Option<String>,
+) -> Result<HostUploadResult, String> {
+    run_cancellable(upload_id, upload(file_path, original_file_name, config)).await
+}
+
+async fn upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: CloudinaryConfig,
+) -> Result<HostUploadResult, String> {
+    let (file_name, bytes) = read_upload_file(&file_path, original_file_name).await?;
+
+    let mut params = BTreeMap::new();
+    if let Some(preset) = non_empty(config.upload_preset.as_deref()) {
+        params.insert("upload_preset", preset.to_string());
+    }
+    if let Some(folder) = non_empty(config.folder.as_deref()) {
+        params.insert("folder", folder.trim_matches('/').to_string());
+    }
+
+    let cloud_name = config.cloud_name.trim();
+    let client = config.client()?;
+    let url = format!("{}/{}/image/upload", API_BASE, cloud_name);
+    let response = config
+        .http
+        .send("Cloudinary upload", || {
+            let part = config.http.file_part(&url, &bytes, &file_name);
+            let form = config.signed_form(params.clone()).part("file", part);
+            Ok(client.post(&url).multipart(form))
+        })
+        .await
+        .map_err(|e| format!("Cloudinary 上传失败: {}", e))?;
+    if !response.status().is_success() {
+        let message = error_message(response).await;
+        error!(
+            "cloudinary_upload failed: file={}, error={}",
+            file_name, message
+        );
+        return Err(format!("Cloudinary 上传失败: {}", message));
+    }
+    let payload: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("解析 Cloudinary 响应失败: {}", e))?;
+    let field = |key: &str| payload.get(key).and_then(Value::as_str).map(str::to_string);
+    let secure_url =
+        field("secure_url").ok_or_else(|| "Cloudinary 响应缺少 secure_url".to_string())?;
+    let public_id =
+        field("public_id").ok_or_else(|| "Cloudinary 响应缺少 public_id".to_string())?;
+
+    let delete_id = encode_delete_marker(&CloudinaryDeleteMarker {
+        cloud_name: cloud_name.to_string(),
+        public_id: public_id.clone(),
+    })?;
+    info!("cloudinary_upload success: public_id={}", public_id);
+    Ok(HostUploadResult {
+        url: apply_transformation(&secure_url, config.transformation.as_deref()),
+        delete_id,
+        metadata: Some(json!({
+            "publicId": public_id,
+            "originalUrl": secure_url,
+            "width": payload.get("width"),
+            "height": payload.get("height"),
+            "format": payload.get("format"),
+        })),
+    })
+}
+
+#[tauri::command]
+pub async fn cloudinary_delete(
+    app: AppHandle,
+    delete_id: String,
+) -> Result<HostDeleteResult, String> {
+    let marker: CloudinaryDeleteMarker = decode_delete_marker(&delete_id)?;
+    let config: CloudinaryConfig = load_host_settings(&app, PLUGIN_ID)?;
+
+    let params = BTreeMap::from([("public_id", marker.public_id.clone())]);
+    let client = config.client()?;
+    let url = format!("{}/{}/image/destroy", API_BASE, marker.cloud_name);
+    let response = config
+        .http
+        .send("Cloudinary delete", || {
+            Ok(client
+                .post(&url)
+                .multipart(config.signed_form(params.clone())))
+        })
+        .await
+        .map_err(|e| format!("Cloudinary 删除失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Cloudinary 删除失败: {}",
+            error_message(response).await
+        ));
+    }
+    let payload: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("解析 Cloudinary 响应失败: {}", e))?;
+    // destroy 接口对不存在的资源返回 `{"result": "not found"}`
+    match payload.get("result").and_then(Value::as_str) {
+        Some("ok") => {
+            info!("cloudinary_delete success: public_id={}", marker.public_id);
+            Ok(HostDeleteResult::deleted("图片已从 Cloudinary 删除"))
+        }
+        Some("not found") => Ok(HostDeleteResult::deleted("图片已不存在")),
+        other => Err(format!(
+            "Cloudinary 删除失败: {}",
+            other.unwrap_or("unknown result")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_transformation() {
+        assert_eq!(
+            apply_transformation(
+                "https://res.cloudinary.com/demo/image/upload/v1/a.png",
+                Some("f_auto,q_auto")
+            ),
+            "https://res.cloudinary.com/demo/image/upload/f_auto,q_auto/v1/a.png"
+        );
+        assert_eq!(
+            apply_transformation("https://x/upload/a.png", Some(" ")),
+            "https://x/upload/a.png"
+        );
+    }
+}
@@ -0,0 +1,415 @@
+/*
+资源管理器 / 访达右键菜单「使用 Yana 上传」：
+1) Windows：在 HKCU\Software\Classes\SystemFileAssociations\image\shell 下注册 shell verb（无需管理员权限，
+   只对图片类文件显示），命令为 `yana.exe --open-upload "%1"`；选中多个文件时系统为每个文件各启动一次，
+   已运行时由单实例插件把参数转发到现有实例；
+2) macOS：在 ~/Library/Services 写入快速操作（Automator 工作流），为选中的每张图片打开
+   `yana://upload?path=...` 深层链接，由深层链接模块交给运行中的实例（未运行时由系统启动）；
+3) 启动参数与转发参数中 `--open-upload` 之后的图片文件经 `upload://request` 交给上传流程，
+   非图片或不存在的文件忽略；
+4) 其他平台注册时返回不支持。
+*/
+
+use log::info;
+use serde::Serialize;
+use tauri::AppHandle;
+
+/// 右键菜单启动应用时携带的参数，其后为要上传的文件
+pub const OPEN_UPLOAD_ARG: &str = "--open-upload";
+const MENU_TITLE: &str = "使用 Yana 上传";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextMenuStatus {
+    /// 当前系统支持注册右键菜单
+    pub supported: bool,
+    pub registered: bool,
+}
+
+/// 取出 `--open-upload` 之后的图片文件
+fn upload_paths(args: &[String]) -> Vec<String> {
+    args.iter()
+        .skip_while(|arg| arg.as_str() != OPEN_UPLOAD_ARG)
+        .skip(1)
+        .filter(|arg| crate::upload::is_image_file(std::path::Path::new(arg)))
+        .cloned()
+        .collect()
+}
+
+/// 处理（转发的）启动参数，包含要上传的文件时交给上传流程并返回 true
+pub(crate) fn handle_args(app: &AppHandle, args: &[String]) -> bool {
+    let paths = upload_paths(args);
+    if paths.is_empty() {
+        return false;
+    }
+    crate::show_main_window(app);
+    crate::upload::request_upload(app, paths, "context-menu");
+    true
+}
+
+/// 处理本次启动的参数；等待前端加载完成后再交给上传流程
+pub(crate) fn init(app: &AppHandle) {
+    let args: Vec<String> = std::env::args_os()
+        .skip(1)
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    if upload_paths(&args).is_empty() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        handle_args(&app, &args);
+    });
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::process::Command;
+
+    const VERB_KEY: &str = r"HKCU\Software\Classes\SystemFileAssociations\image\shell\YanaUpload";
+
+    fn reg(args: &[&str]) -> Result<(), String> {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        let output = Command::new("reg")
+            .args(args)
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map_err(|e| format!("执行 reg 失败: {e}"))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    }
+
+    pub(super) fn is_registered() -> bool {
+        reg(&["query", &format!(r"{VERB_KEY}\command")]).is_ok()
+    }
+
+    pub(super) fn register() -> Result<(), String> {
+        let exe = std::env::current_exe().map_err(|e| format!("current_exe: {e}"))?;
+        let exe = exe.to_string_lossy();
+        let command = format!("\"{exe}\" {} \"%1\"", super::OPEN_UPLOAD_ARG);
+        reg(&["add", VERB_KEY, "/ve", "/d", super::MENU_TITLE, "/f"])?;
+        reg(&[
+            "add",
+            VERB_KEY,
+            "/v",
+            "Icon",
+            "/d",
+            &format!("{exe},0"),
+            "/f",
+        ])?;
+        reg(&[
+            "add",
+            &format!(r"{VERB_KEY}\command"),
+            "/ve",
+            "/d",
+            &command,
+            "/f",
+        ])
+    }
+
+    pub(super) fn unregister() -> Result<(), String> {
+        if !is_registered() {
+            return Ok(());
+        }
+        reg(&["delete", VERB_KEY, "/f"])
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::path::PathBuf;
+
+    /// 逐个把文件路径编码后打开深层链接
+    const SCRIPT: &str = r#"for f in "$@"; do
+  encoded=$(/usr/bin/osascript -l JavaScript -e 'function run(argv) { return encodeURIComponent(argv[0]) }' "$f")
+  /usr/bin/open "yana://upload?path=${encoded}"
+done"#;
+
+    fn workflow_dir() -> Result<PathBuf, String> {
+        let home = std::env::var_os("HOME").ok_or_else(|| "未找到用户目录".to_string())?;
+        Ok(PathBuf::from(home)
+            .join("Library/Services")
+            .join(format!("{}.workflow", super::MENU_TITLE)))
+    }
+
+    fn escape_xml(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    fn info_plist() -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>NSServices</key>
+	<array>
+		<dict>
+			<key>NSMenuItem</key>
+			<dict>
+				<key>default</key>
+				<string>{title}</string>
+			</dict>
+			<key>NSMessage</key>
+			<string>runWorkflowAsService</string>
+			<key>NSRequiredContext</key>
+			<dict>
+				<key>NSApplicationIdentifier</key>
+				<string>com.apple.finder</string>
+			</dict>
+			<key>NSSendFileTypes</key>
+			<array>
+				<string>public.image</string>
+			</array>
+		</dict>
+	</array>
+</dict>
+</plist>
+"#,
+            title = escape_xml(super::MENU_TITLE)
+        )
+    }
+
+    fn document_wflow() -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>AMApplicationBuild</key>
+	<string>523</string>
+	<key>AMApplicationVersion</key>
+	<string>2.10</string>
+	<key>AMDocumentVersion</key>
+	<string>2</string>
+	<key>actions</key>
+	<array>
+		<dict>
+			<key>action</key>
+			<dict>
+				<key>AMAccepts</key>
+				<dict>
+					<key>Container</key>
+					<string>List</string>
+					<key>Optional</key>
+					<true/>
+					<key>Types</key>
+					<array>
+						<string>com.apple.cocoa.string</string>
+					</array>
+				</dict>
+				<key>AMActionVersion</key>
+				<string>2.0.3</string>
+				<key>AMApplication</key>
+				<array>
+					<string>Automator</string>
+				</array>
+				<key>AMParameterProperties</key>
+				<dict>
+					<key>COMMAND_STRING</key>
+					<dict/>
+					<key>CheckedForUserDefaultShell</key>
+					<dict/>
+					<key>inputMethod</key>
+					<dict/>
+					<key>shell</key>
+					<dict/>
+					<key>source</key>
+					<dict/>
+				</dict>
+				<key>AMProvides</key>
+				<dict>
+					<key>Container</key>
+					<string>List</string>
+					<key>Types</key>
+					<array>
+						<string>com.apple.cocoa.string</string>
+					</array>
+				</dict>
+				<key>ActionBundlePath</key>
+				<string>/System/Library/Automator/Run Shell Script.action</string>
+				<key>ActionName</key>
+				<string>Run Shell Script</string>
+				<key>ActionParameters</key>
+				<dict>
+					<key>COMMAND_STRING</key>
+					<string>{script}</string>
+					<key>CheckedForUserDefaultShell</key>
+					<true/>
+					<key>inputMethod</key>
+					<integer>1</integer>
+					<key>shell</key>
+					<string>/bin/zsh</string>
+					<key>source</key>
+					<string></string>
+				</dict>
+				<key>BundleIdentifier</key>
+				<string>com.apple.RunShellScript</string>
+				<key>CFBundleVersion</key>
+				<string>2.0.3</string>
+				<key>CanShowSelectedItemsWhenRun</key>
+				<false/>
+				<key>CanShowWhenRun</key>
+				<true/>
+				<key>Category</key>
+				<array>
+					<string>AMCategoryUtilities</string>
+				</array>
+				<key>Class Name</key>
+				<string>RunShellScriptAction</string>
+				<key>InputUUID</key>
+				<string>0B9D7E7C-4A5E-4C36-9F1B-1D6C1E0B6A01</string>
+				<key>OutputUUID</key>
+				<string>0B9D7E7C-4A5E-4C36-9F1B-1D6C1E0B6A02</string>
+				<key>UUID</key>
+				<string>0B9D7E7C-4A5E-4C36-9F1B-1D6C1E0B6A03</string>
+				<key>UnlocalizedApplications</key>
+				<array>
+					<string>Automator</string>
+				</array>
+				<key>isViewVisible</key>
+				<integer>1</integer>
+			</dict>
+		</dict>
+	</array>
+	<key>connectors</key>
+	<dict/>
+	<key>workflowMetaData</key>
+	<dict>
+		<key>applicationBundleIDsByPath</key>
+		<dict/>
+		<key>applicationPaths</key>
+		<array/>
+		<key>inputTypeIdentifier</key>
+		<string>com.apple.Automator.fileSystemObject.image</string>
+		<key>outputTypeIdentifier</key>
+		<string>com.apple.Automator.nothing</string>
+		<key>presentationMode</key>
+		<integer>15</integer>
+		<key>processesInput</key>
+		<false/>
+		<key>serviceApplicationBundleID</key>
+		<string>com.apple.finder</string>
+		<key>serviceApplicationPath</key>
+		<string>/System/Library/CoreServices/Finder.app</string>
+		<key>serviceInputTypeIdentifier</key>
+		<string>com.apple.Automator.fileSystemObject.image</string>
+		<key>serviceOutputTypeIdentifier</key>
+		<string>com.apple.Automator.nothing</string>
+		<key>serviceProcessesInput</key>
+		<false/>
+		<key>systemImageName</key>
+		<string>NSActionTemplate</string>
+		<key>useAutomaticInputType</key>
+		<false/>
+		<key>workflowTypeIdentifier</key>
+		<string>com.apple.Automator.servicesMenu</string>
+	</dict>
+</dict>
+</plist>
+"#,
+            script = escape_xml(SCRIPT)
+        )
+    }
+
+    /// 通知系统重新扫描服务菜单
+    fn refresh_services() {
+        let _ = std::process::Command::new("/System/Library/CoreServices/pbs")
+            .arg("-update")
+            .status();
+    }
+
+    pub(super) fn is_registered() -> bool {
+        workflow_dir().is_ok_and(|dir| dir.join("Contents/document.wflow").is_file())
+    }
+
+    pub(super) fn register() -> Result<(), String> {
+        let contents = workflow_dir()?.join("Contents");
+        std::fs::create_dir_all(&contents)
+            .map_err(|e| format!("create_dir_all {}: {e}", contents.display()))?;
+        std::fs::write(contents.join("Info.plist"), info_plist())
+            .map_err(|e| format!("写入 Info.plist 失败: {e}"))?;
+        std::fs::write(contents.join("document.wflow"), document_wflow())
+            .map_err(|e| format!("写入 document.wflow 失败: {e}"))?;
+        refresh_services();
+        Ok(())
+    }
+
+    pub(super) fn unregister() -> Result<(), String> {
+        let dir = workflow_dir()?;
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)
+                .map_err(|e| format!("删除 {} 失败: {e}", dir.display()))?;
+            refresh_services();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod platform {
+    pub(super) fn is_registered() -> bool {
+        false
+    }
+
+    pub(super) fn register() -> Result<(), String> {
+        Err("当前系统不支持注册右键菜单".to_string())
+    }
+
+    pub(super) fn unregister() -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub fn context_menu_status() -> ContextMenuStatus {
+    ContextMenuStatus {
+        supported: cfg!(any(windows, target_os = "macos")),
+        registered: platform::is_registered(),
+    }
+}
+
+/// 注册「使用 Yana 上传」右键菜单（重复注册会更新可执行文件路径）
+#[tauri::command]
+pub fn register_context_menu() -> Result<ContextMenuStatus, String> {
+    platform::register()?;
+    info!("context menu registered");
+    Ok(context_menu_status())
+}
+
+#[tauri::command]
+pub fn unregister_context_menu() -> Result<ContextMenuStatus, String> {
+    platform::unregister()?;
+    info!("context menu unregistered");
+    Ok(context_menu_status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upload_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("a.png");
+        std::fs::write(&image, b"png").unwrap();
+        let image = image.to_string_lossy().into_owned();
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            upload_paths(&args(&["yana.exe", OPEN_UPLOAD_ARG, &image, "missing.png"])),
+            vec![image.clone()]
+        );
+        assert!(upload_paths(&args(&["yana.exe", &image])).is_empty());
+        assert!(upload_paths(&args(&["yana.exe", OPEN_UPLOAD_ARG])).is_empty());
+    }
+}
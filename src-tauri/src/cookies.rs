@@ -0,0 +1,340 @@
+/*
+基于会话 Cookie 的图床（Lychee、Piwigo 等）支持：
+1) 每个主机（host[:port]）一个持久化 Cookie 存储，保存在应用数据目录的 `cookies/` 下；
+2) `host_login` 发送登录请求，服务端下发的 Cookie 被写入对应主机的存储；
+3) `UploadConfig.use_cookies` 为 true 时上传请求携带并更新该主机的 Cookie；
+4) 写入存储时 `Max-Age` 换算为绝对的 `Expires`，重启后重新加载不会延长有效期。
+*/
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex, OnceLock},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use log::{error, info, warn};
+use reqwest::{
+    Client, Url,
+    cookie::{CookieStore, Jar},
+    header::HeaderValue,
+};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::http_client::{HttpClientOptions, apply_http_options};
+use crate::upload::UploadMethod;
+
+const COOKIE_DIR_NAME: &str = "cookies";
+/// Max-Age 上限（RFC 6265bis 建议的 400 天）
+const MAX_AGE_LIMIT_SECS: i64 = 400 * 24 * 60 * 60;
+
+static COOKIE_DIR: OnceLock<PathBuf> = OnceLock::new();
+static COOKIE_JARS: OnceLock<Mutex<HashMap<String, Arc<PersistentJar>>>> = OnceLock::new();
+
+/// 持久化保存的单条 Cookie（原始 Set-Cookie 值及其来源地址）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredCookie {
+    url: String,
+    set_cookie: String,
+}
+
+/// 在 reqwest 内置 Jar 之上记录原始 Set-Cookie，变化时写回磁盘
+pub(crate) struct PersistentJar {
+    jar: Jar,
+    path: PathBuf,
+    entries: Mutex<Vec<StoredCookie>>,
+}
+
+impl PersistentJar {
+    fn load(path: PathBuf) -> Self {
+        let entries: Vec<StoredCookie> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+
+        let jar = Jar::default();
+        for entry in &entries {
+            if let Ok(url) = Url::parse(&entry.url) {
+                jar.add_cookie_str(&entry.set_cookie, &url);
+            }
+        }
+
+        Self {
+            jar,
+            path,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.lock().map(|e| e.len()).unwrap_or(0)
+    }
+
+    fn persist(&self, entries: &[StoredCookie]) {
+        let result = serde_json::to_string_pretty(entries)
+            .map_err(|e| e.to_string())
+            .and_then(|raw| fs::write(&self.path, raw).map_err(|e| e.to_string()));
+        if let Err(err) = result {
+            warn!(
+                "failed to persist cookies to {}: {}",
+                self.path.display(),
+                err
+            );
+        }
+    }
+}
+
+fn cookie_name(set_cookie: &str) -> &str {
+    set_cookie
+        .split(';')
+        .next()
+        .and_then(|pair| pair.split('=').next())
+        .unwrap_or("")
+        .trim()
+}
+
+/// 将 Set-Cookie 中的 Max-Age 换算为设置时刻的绝对 Expires；
+/// Max-Age 优先于 Expires，因此同时移除原有的 Expires
+fn with_absolute_expiry(set_cookie: &str, now: DateTime<Utc>) -> String {
+    let attribute = |part: &str| {
+        part.split('=')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase()
+    };
+    let max_age = set_cookie
+        .split(';')
+        .skip(1)
+        .filter(|part| attribute(part) == "max-age")
+        .filter_map(|part| part.split_once('=')?.1.trim().parse::<i64>().ok())
+        .last();
+    let Some(max_age) = max_age else {
+        return set_cookie.to_string();
+    };
+
+    let expires = now + chrono::Duration::seconds(max_age.clamp(0, MAX_AGE_LIMIT_SECS));
+    let mut parts: Vec<String> = set_cookie
+        .split(';')
+        .enumerate()
+        .filter(|(index, part)| {
+            *index == 0 || !matches!(attribute(part).as_str(), "max-age" | "expires")
+        })
+        .map(|(_, part)| part.trim().to_string())
+        .collect();
+    parts.push(format!(
+        "Expires={}",
+        expires.format("%a, %d %b %Y %H:%M:%S GMT")
+    ));
+    parts.join("; ")
+}
+
+impl CookieStore for PersistentJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let headers: Vec<HeaderValue> = cookie_headers.cloned().collect();
+        if headers.is_empty() {
+            return;
+        }
+        self.jar.set_cookies(&mut headers.iter(), url);
+
+        let Ok(mut entries) = self.entries.lock() else {
+            return;
+        };
+        let origin = url.origin().ascii_serialization();
+        for header in &headers {
+            let Ok(value) = header.to_str() else {
+                continue;
+            };
+            // 同一来源的同名 Cookie 只保留最新一条
+            let name = cookie_name(value);
+            entries.retain(|e| !(e.url == origin && cookie_name(&e.set_cookie) == name));
+            entries.push(StoredCookie {
+                url: origin.clone(),
+                set_cookie: with_absolute_expiry(value, Utc::now()),
+            });
+        }
+        self.persist(&entries);
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        self.jar.cookies(url)
+    }
+}
+
+/// 在应用启动时记录 Cookie 存储目录
+pub(crate) fn init(app: &AppHandle) {
+    match crate::portable::app_data_dir(&app) {
+        Ok(dir) => {
+            let _ = COOKIE_DIR.set(dir.join(COOKIE_DIR_NAME));
+        }
+        Err(err) => warn!("cookie store disabled, app data dir unavailable: {}", err),
+    }
+}
+
+/// 主机标识：host[:port]，用作存储文件名
+fn host_key(url: &str) -> Result<String, String> {
+    let parsed = Url::parse(url).map_err(|e| format!("invalid url {}: {e}", url))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| format!("url has no host: {}", url))?;
+    Ok(match parsed.port() {
+        Some(port) => format!("{}_{}", host, port),
+        None => host.to_string(),
+    })
+}
+
+/// 获取（必要时从磁盘加载）目标地址所属主机的 Cookie 存储
+pub(crate) fn jar_for_url(url: &str) -> Result<Arc<PersistentJar>, String> {
+    let key = host_key(url)?;
+    let dir = COOKIE_DIR
+        .get()
+        .ok_or_else(|| "cookie store is not initialized".to_string())?;
+
+    let jars = COOKIE_JARS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut jars = jars
+        .lock()
+        .map_err(|_| "cookie store poisoned".to_string())?;
+    if let Some(jar) = jars.get(&key) {
+        return Ok(jar.clone());
+    }
+
+    fs::create_dir_all(dir).map_err(|e| format!("create_dir_all {}: {e}", dir.display()))?;
+    let jar = Arc::new(PersistentJar::load(dir.join(format!("{}.json", key))));
+    jars.insert(key, jar.clone());
+    Ok(jar)
+}
+
+/// 登录请求参数
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostLoginCredentials {
+    #[serde(default)]
+    pub method: UploadMethod,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// 以 application/x-www-form-urlencoded 提交的字段
+    #[serde(default)]
+    pub form: HashMap<String, String>,
+    /// 以 JSON 提交的请求体，设置后忽略 form
+    #[serde(default)]
+    pub json: Option<serde_json::Value>,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    #[serde(default, flatten)]
+    pub http: HttpClientOptions,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostLoginResult {
+    pub status: u16,
+    /// 该主机当前保存的 Cookie 数量
+    pub cookie_count: usize,
+    pub body: serde_json::Value,
+    pub raw_text: String,
+}
+
+/// 发送登录请求，将服务端下发的会话 Cookie 保存到该主机的存储中
+#[tauri::command]
+pub async fn host_login(
+    url: String,
+    credentials: HostLoginCredentials,
+) -> Result<HostLoginResult, String> {
+    let jar = jar_for_url(&url)?;
+    let builder = Client::builder()
+        .cookie_provider(jar.clone())
+        .timeout(Duration::from_millis(
+            credentials.timeout_ms.unwrap_or(30_000),
+        ));
+    let client = apply_http_options(builder, Some(&credentials.http))?
+        .build()
+        .map_err(|e| format!("failed to build http client: {}", e))?;
+
+    let mut request = client.request(credentials.method.as_reqwest(), &url);
+    for (key, value) in &credentials.headers {
+        request = request.header(key.trim(), value);
+    }
+    request = match &credentials.json {
+        Some(json) => request.json(json),
+        None => request.form(&credentials.form),
+    };
+
+    let response = request.send().await.map_err(|e| {
+        error!("host_login request failed: url={}, error={}", url, e);
+        format!("login request failed: {}", e)
+    })?;
+    let status = response.status();
+    let raw_text = response
+        .text()
+        .await
+        .map_err(|e| format!("failed to read login response: {}", e))?;
+
+    if !status.is_success() {
+        error!("host_login failed: url={}, status={}", url, status);
+        return Err(format!(
+            "login failed with status {}: {}",
+            status.as_u16(),
+            raw_text
+        ));
+    }
+
+    let cookie_count = jar.len();
+    info!("host_login success: url={}, cookies={}", url, cookie_count);
+    Ok(HostLoginResult {
+        status: status.as_u16(),
+        cookie_count,
+        body: serde_json::from_str(&raw_text).unwrap_or(serde_json::Value::Null),
+        raw_text,
+    })
+}
+
+/// 清除目标地址所属主机保存的 Cookie
+#[tauri::command]
+pub fn host_logout(url: String) -> Result<(), String> {
+    let key = host_key(&url)?;
+    if let Some(jars) = COOKIE_JARS.get()
+        && let Ok(mut jars) = jars.lock()
+    {
+        jars.remove(&key);
+    }
+    if let Some(dir) = COOKIE_DIR.get() {
+        let path = dir.join(format!("{}.json", key));
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("remove {}: {e}", path.display()))?;
+        }
+    }
+    info!("host_logout: host={}", key);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_absolute_expiry() {
+        let now = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            with_absolute_expiry(
+                "sid=abc; Path=/; Expires=Wed, 01 Jan 2031 00:00:00 GMT; Max-Age=3600; HttpOnly",
+                now
+            ),
+            "sid=abc; Path=/; HttpOnly; Expires=Thu, 01 Jan 2026 01:00:00 GMT"
+        );
+        // 没有 Max-Age 时原样保存
+        assert_eq!(
+            with_absolute_expiry("sid=abc; Path=/", now),
+            "sid=abc; Path=/"
+        );
+        // Max-Age 为 0 或负数表示立即过期
+        assert_eq!(
+            with_absolute_expiry("sid=; max-age=-1", now),
+            "sid=; Expires=Thu, 01 Jan 2026 00:00:00 GMT"
+        );
+    }
+}
@@ -0,0 +1,329 @@
+/*
+内置自定义 HTTP 图床（声明式配置，与 ShareX 自定义上传器的字段一一对应）：
+1) 请求：`requestUrl` + 请求方法，查询参数 / 请求头 / 表单字段均以 JSON 对象文本配置，
+   请求体为 multipart 表单（文件放在 `fileFormName` 字段）或直接发送文件二进制；
+2) 表单字段与查询参数中的 `{filename}` 会替换为原始文件名；
+3) 响应解析：`urlTemplate` / `deletionUrlTemplate` 使用 ShareX 语法，支持 `{response}` 与
+   `{json:data.files[0].url}`，也兼容旧版的 `$response$` / `$json:...$`；
+4) deleteId 中记录解析出的删除地址，删除时按配置的方法请求该地址。
+*/
+
+use std::collections::BTreeMap;
+
+use log::{error, info};
+use reqwest::{Method, multipart};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tauri::AppHandle;
+
+use crate::internal_hosts::{
+    HostDeleteResult, HostHttpConfig, HostUploadResult, decode_delete_marker, encode_delete_marker,
+    load_host_settings, read_upload_file,
+};
+use crate::upload::run_cancellable;
+
+pub(crate) const PLUGIN_ID: &str = "custom";
+const DEFAULT_TIMEOUT_MS: u64 = 60_000;
+const DEFAULT_URL_TEMPLATE: &str = "{response}";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomBody {
+    #[default]
+    MultipartFormData,
+    Binary,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomHttpConfig {
+    /// 导出 ShareX 配置时使用的名称
+    #[serde(default)]
+    pub name: Option<String>,
+    pub request_url: String,
+    #[serde(default)]
+    pub method: Option<String>,
+    #[serde(default)]
+    pub body: CustomBody,
+    #[serde(default)]
+    pub file_form_name: Option<String>,
+    /// JSON 对象文本：查询参数
+    #[serde(default)]
+    pub parameters: Option<String>,
+    /// JSON 对象文本：请求头
+    #[serde(default)]
+    pub headers: Option<String>,
+    /// JSON 对象文本：附加表单字段
+    #[serde(default)]
+    pub arguments: Option<String>,
+    #[serde(default)]
+    pub url_template: Option<String>,
+    #[serde(default)]
+    pub deletion_url_template: Option<String>,
+    #[serde(default)]
+    pub deletion_method: Option<String>,
+    #[serde(flatten)]
+    pub http: HostHttpConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CustomDeleteMarker {
+    deletion_url: String,
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// 解析 JSON 对象文本为字符串映射，非字符串值按 JSON 文本保存
+pub(crate) fn parse_string_map(
+    label: &str,
+    text: Option<&str>,
+) -> Result<BTreeMap<String, String>, String> {
+    let Some(text) = non_empty(text) else {
+        return Ok(BTreeMap::new());
+    };
+    let parsed: serde_json::Map<String, Value> =
+        serde_json::from_str(text).map_err(|e| format!("{} 不是有效的 JSON 对象: {e}", label))?;
+    Ok(parsed
+        .into_iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(s) => s,
+                other => other.to_string(),
+            };
+            (key, value)
+        })
+        .collect())
+}
+
+/// 按 `a.b[0].c` 形式的路径读取 JSON 值
+fn json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut current = value;
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        let (key, indexes) = match segment.find('[') {
+            Some(pos) => segment.split_at(pos),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            current = current.get(key)?;
+        }
+        for index in indexes.split(['[', ']']).filter(|s| !s.is_empty()) {
+            current = current.get(index.parse::<usize>().ok()?)?;
+        }
+    }
+    Some(current)
+}
+
+/// 渲染 ShareX 语法的响应模板
+pub(crate) fn render_response_template(template: &str, response: &str) -> Result<String, String> {
+    let parsed: Option<Value> = serde_json::from_str(response).ok();
+    let lookup = |path: &str| -> Result<String, String> {
+        let root = parsed
+            .as_ref()
+            .ok_or_else(|| format!("响应不是 JSON，无法解析 json:{}", path))?;
+        match json_path(root, path) {
+            Some(Value::String(s)) => Ok(s.clone()),
+            Some(Value::Null) | None => Err(format!("响应中缺少字段 {}", path)),
+            Some(other) => Ok(other.to_string()),
+        }
+    };
+
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '$']) {
+        output.push_str(&rest[..start]);
+        let close = if rest[start..].starts_with('{') {
+            '}'
+        } else {
+            '$'
+        };
+        let Some(len) = rest[start + 1..].find(close) else {
+            output.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let token = &rest[start + 1..start + 1 + len];
+        match (token, token.strip_prefix("json:")) {
+            ("response", _) => output.push_str(response.trim()),
+            (_, Some(path)) => output.push_str(&lookup(path)?),
+            // 其他占位符（如 regex、xml）不支持，原样保留
+            _ => output.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    output.push_str(rest);
+    Ok(output.trim().to_string())
+}
+
+fn parse_method(value: Option<&str>, default: Method) -> Result<Method, String> {
+    match non_empty(value) {
+        Some(method) => Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+            .map_err(|_| format!("不支持的请求方法: {}", method)),
+        None => Ok(default),
+    }
+}
+
+impl CustomHttpConfig {
+    fn client(&self) -> Result<reqwest::Client, String> {
+        self.http.client(DEFAULT_TIMEOUT_MS)
+    }
+
+    fn apply_headers(
+        &self,
+        mut request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::RequestBuilder, String> {
+        for (name, value) in parse_string_map("请求头", self.headers.as_deref())? {
+            request = request.header(name, value);
+        }
+        Ok(request)
+    }
+}
+
+#[tauri::command]
+pub async fn custom_http_upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: CustomHttpConfig,
+    upload_id: Option<String>,
+) -> Result<HostUploadResult, String> {
+    run_cancellable(upload_id, upload(file_path, original_file_name, config)).await
+}
+
+async fn upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: CustomHttpConfig,
+) -> Result<HostUploadResult, String> {
+    let (file_name, bytes) = read_upload_file(&file_path, original_file_name).await?;
+    let mime = mime_guess::from_path(&file_name)
+        .first_or_octet_stream()
+        .to_string();
+    let fill = |value: String| value.replace("{filename}", &file_name);
+
+    let mut url =
+        reqwest::Url::parse(config.request_url.trim()).map_err(|e| format!("请求地址无效: {e}"))?;
+    for (key, value) in parse_string_map("查询参数", config.parameters.as_deref())? {
+        url.query_pairs_mut().append_pair(&key, &fill(value));
+    }
+    let method = parse_method(config.method.as_deref(), Method::POST)?;
+    let arguments: Vec<(String, String)> =
+        parse_string_map("表单字段", config.arguments.as_deref())?
+            .into_iter()
+            .map(|(key, value)| (key, fill(value)))
+            .collect();
+    let field = non_empty(config.file_form_name.as_deref()).unwrap_or("file");
+    let client = config.client()?;
+    let throttle = config.http.throttle(url.as_str());
+
+    let response = config
+        .http
+        .send("custom upload", || {
+            let request = config.apply_headers(client.request(method.clone(), url.clone()))?;
+            Ok(match config.body {
+                CustomBody::MultipartFormData => {
+                    let part = config
+                        .http
+                        .file_part(url.as_str(), &bytes, &file_name)
+                        .mime_str(&mime)
+                        .map_err(|e| format!("invalid content type {}: {}", mime, e))?;
+                    let form = arguments
+                        .iter()
+                        .fold(multipart::Form::new(), |form, (key, value)| {
+                            form.text(key.clone(), value.clone())
+                        });
+                    request.multipart(form.part(field.to_string(), part))
+                }
+                CustomBody::Binary => request
+                    .header(reqwest::header::CONTENT_TYPE, &mime)
+                    .header(reqwest::header::CONTENT_LENGTH, bytes.len())
+                    .body(throttle.body(bytes.clone())),
+            })
+        })
+        .await
+        .map_err(|e| format!("上传失败: {}", e))?;
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        error!(
+            "custom_http_upload failed: status={}, body={}",
+            status, text
+        );
+        return Err(format!("上传失败: HTTP {} {}", status, text));
+    }
+
+    let template = non_empty(config.url_template.as_deref()).unwrap_or(DEFAULT_URL_TEMPLATE);
+    let url = render_response_template(template, &text)?;
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(format!("未能从响应中解析出图片地址: {}", url));
+    }
+    let deletion_url = match non_empty(config.deletion_url_template.as_deref()) {
+        Some(template) => Some(render_response_template(template, &text)?),
+        None => None,
+    }
+    .filter(|u| !u.is_empty());
+    let delete_id = match &deletion_url {
+        Some(deletion_url) => encode_delete_marker(&CustomDeleteMarker {
+            deletion_url: deletion_url.clone(),
+        })?,
+        None => String::new(),
+    };
+
+    info!("custom_http_upload success: url={}", url);
+    Ok(HostUploadResult {
+        url,
+        delete_id,
+        metadata: Some(json!({ "deletionUrl": deletion_url })),
+    })
+}
+
+#[tauri::command]
+pub async fn custom_http_delete(
+    app: AppHandle,
+    delete_id: String,
+) -> Result<HostDeleteResult, String> {
+    let marker: CustomDeleteMarker = decode_delete_marker(&delete_id)?;
+    let config: CustomHttpConfig = load_host_settings(&app, PLUGIN_ID)?;
+
+    let method = parse_method(config.deletion_method.as_deref(), Method::GET)?;
+    let client = config.client()?;
+    let response = config
+        .http
+        .send("custom delete", || {
+            config.apply_headers(client.request(method.clone(), &marker.deletion_url))
+        })
+        .await
+        .map_err(|e| format!("删除失败: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("删除失败: HTTP {} {}", status, text));
+    }
+
+    info!("custom_http_delete success: url={}", marker.deletion_url);
+    Ok(HostDeleteResult::deleted("已请求删除地址"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_response_template() {
+        let body = r#"{"data":{"files":[{"url":"https://i.example.com/a.png"}],"id":7}}"#;
+        assert_eq!(
+            render_response_template("{json:data.files[0].url}", body).unwrap(),
+            "https://i.example.com/a.png"
+        );
+        assert_eq!(
+            render_response_template("https://x.com/d/$json:data.id$", body).unwrap(),
+            "https://x.com/d/7"
+        );
+        assert_eq!(
+            render_response_template("{response}", " https://a/b.png\n").unwrap(),
+            "https://a/b.png"
+        );
+        assert!(render_response_template("{json:missing}", body).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+/*
+上传前的重复检查：
+1) 压缩前计算源文件内容的 SHA-256，写入图库时一并保存（图库按内容哈希 + 图床建立索引）；
+2) 开启「跳过重复上传」时，同一内容已上传到所选图床的文件不再压缩与上传，直接返回图库中已有的链接，
+   也不会新增图库记录；调用方可传入 force 强制重新上传；
+3) 界面上传通过 `find_duplicate_uploads` 批量查询，命令行与本地服务等后端入口在 `compress_and_upload` 中检查。
+*/
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use log::warn;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use crate::gallery::GalleryItem;
+
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateMatch {
+    pub path: String,
+    /// 文件无法读取时为 None
+    pub hash: Option<String>,
+    /// 同一图床上已有的图库条目；未开启跳过重复上传时始终为 None
+    pub existing: Option<GalleryItem>,
+}
+
+/// 流式计算文件内容的 SHA-256（十六进制小写）
+pub(crate) fn hash_file(path: &Path) -> Result<String, String> {
+    let file = File::open(path).map_err(|e| format!("打开文件失败 {}: {e}", path.display()))?;
+    let mut reader = BufReader::with_capacity(READ_BUFFER_SIZE, file);
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|e| format!("读取文件失败 {}: {e}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 计算哈希并按设置查找已有条目；`force` 为 true 或未开启跳过重复上传时只计算哈希
+pub(crate) fn check(app: &AppHandle, path: &str, host: &str, force: bool) -> DuplicateMatch {
+    let hash = match hash_file(Path::new(path)) {
+        Ok(hash) => Some(hash),
+        Err(err) => {
+            warn!("hash upload source failed: {}", err);
+            None
+        }
+    };
+    let skip_duplicates = !force && crate::settings::current_settings(app).skip_duplicate_uploads;
+    let existing = hash
+        .as_deref()
+        .filter(|_| skip_duplicates)
+        .and_then(|hash| {
+            crate::gallery::store_from_app(app)
+                .and_then(|store| {
+                    store
+                        .find_by_hash(hash, host)
+                        .map_err(|err| err.to_string())
+                })
+                .unwrap_or_else(|err| {
+                    warn!("lookup duplicate upload failed: {}", err);
+                    None
+                })
+        });
+    DuplicateMatch {
+        path: path.to_string(),
+        hash,
+        existing,
+    }
+}
+
+/// 批量计算待上传文件的哈希并查找同一图床上的已有条目，结果顺序与输入一致
+#[tauri::command]
+pub async fn find_duplicate_uploads(
+    app: AppHandle,
+    paths: Vec<String>,
+    host: String,
+    force: Option<bool>,
+) -> Result<Vec<DuplicateMatch>, String> {
+    let force = force.unwrap_or(false);
+    tauri::async_runtime::spawn_blocking(move || {
+        paths
+            .iter()
+            .map(|path| check(&app, path, &host, force))
+            .collect()
+    })
+    .await
+    .map_err(|err| format!("重复检查任务失败: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            hash_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(hash_file(&dir.path().join("missing")).is_err());
+    }
+}
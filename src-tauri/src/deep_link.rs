@@ -0,0 +1,169 @@
+/*
+`yana://` 深层链接：
+1) `yana://upload?path=<绝对路径>`：上传本地图片，可重复 `path` 参数一次上传多张，
+   只接受存在的图片文件，避免外部链接借此上传任意文件；
+2) `yana://gallery?host=<图床>&q=<关键字>`：打开图库并按条件筛选，`tag` 等同于 `q`（按文件名匹配）；
+3) 应用未运行时由系统启动并携带链接，已运行时由单实例插件（deep-link 特性）转发到现有实例；
+   处理时显示主窗口，上传通过 `upload://request`，图库筛选通过 `deep-link://gallery` 交给前端。
+*/
+
+use std::path::PathBuf;
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_deep_link::DeepLinkExt;
+use url::Url;
+
+const SCHEME: &str = "yana";
+const GALLERY_EVENT: &str = "deep-link://gallery";
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryLinkFilter {
+    pub host: Option<String>,
+    pub keyword: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DeepLinkAction {
+    Upload(Vec<PathBuf>),
+    Gallery(GalleryLinkFilter),
+}
+
+fn parse(url: &Url) -> Result<DeepLinkAction, String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("unsupported scheme: {}", url.scheme()));
+    }
+    // `yana://upload?...` 中 upload 为 host；兼容 `yana:upload?...` 写法
+    let action = url
+        .host_str()
+        .map(str::to_string)
+        .unwrap_or_else(|| url.path().trim_matches('/').to_string());
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, value)| key == name && !value.trim().is_empty())
+            .map(|(_, value)| value.trim().to_string())
+    };
+
+    match action.as_str() {
+        "upload" => {
+            let paths: Vec<PathBuf> = url
+                .query_pairs()
+                .filter(|(key, _)| key == "path")
+                .map(|(_, value)| PathBuf::from(value.trim()))
+                .collect();
+            if paths.is_empty() {
+                return Err("upload 链接缺少 path 参数".to_string());
+            }
+            if let Some(invalid) = paths
+                .iter()
+                .find(|path| !crate::upload::is_image_file(path))
+            {
+                return Err(format!("不是可上传的图片文件: {}", invalid.display()));
+            }
+            Ok(DeepLinkAction::Upload(paths))
+        }
+        "gallery" => Ok(DeepLinkAction::Gallery(GalleryLinkFilter {
+            host: param("host"),
+            keyword: param("q").or_else(|| param("tag")),
+        })),
+        other => Err(format!("unsupported deep link action: {other}")),
+    }
+}
+
+pub(crate) fn handle_urls(app: &AppHandle, urls: &[Url]) {
+    for url in urls {
+        let action = match parse(url) {
+            Ok(action) => action,
+            Err(err) => {
+                warn!("ignore deep link {}: {}", url, err);
+                continue;
+            }
+        };
+        info!("deep link received: {}", url);
+        crate::show_main_window(app);
+        match action {
+            DeepLinkAction::Upload(paths) => crate::upload::request_upload(
+                app,
+                paths
+                    .into_iter()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect(),
+                "deep-link",
+            ),
+            DeepLinkAction::Gallery(filter) => {
+                if let Err(err) = app.emit(GALLERY_EVENT, filter) {
+                    warn!("emit {} failed: {}", GALLERY_EVENT, err);
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn init(app: &AppHandle) {
+    // Windows / Linux 开发与免安装运行时需在运行时注册协议
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(err) = app.deep_link().register_all() {
+        warn!("register deep link schemes failed: {}", err);
+    }
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        handle_urls(&handle, &event.urls());
+    });
+
+    match app.deep_link().get_current() {
+        Ok(Some(urls)) => {
+            // 等待前端加载完成后再处理启动链接
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                handle_urls(&app, &urls);
+            });
+        }
+        Ok(None) => {}
+        Err(err) => warn!("read launch deep link failed: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_deep_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("a.png");
+        std::fs::write(&image, b"png").unwrap();
+        let secret = dir.path().join("id_rsa");
+        std::fs::write(&secret, b"key").unwrap();
+
+        let mut upload = Url::parse("yana://upload").unwrap();
+        upload
+            .query_pairs_mut()
+            .append_pair("path", &image.to_string_lossy());
+        assert_eq!(
+            parse(&upload).unwrap(),
+            DeepLinkAction::Upload(vec![image.clone()])
+        );
+
+        let mut rejected = Url::parse("yana://upload").unwrap();
+        rejected
+            .query_pairs_mut()
+            .append_pair("path", &secret.to_string_lossy());
+        assert!(parse(&rejected).is_err());
+        assert!(parse(&Url::parse("yana://upload").unwrap()).is_err());
+
+        let gallery = Url::parse("yana://gallery?host=smms&tag=cat").unwrap();
+        assert_eq!(
+            parse(&gallery).unwrap(),
+            DeepLinkAction::Gallery(GalleryLinkFilter {
+                host: Some("smms".to_string()),
+                keyword: Some("cat".to_string()),
+            })
+        );
+        assert!(parse(&Url::parse("yana://unknown").unwrap()).is_err());
+        assert!(parse(&Url::parse("https://upload").unwrap()).is_err());
+    }
+}
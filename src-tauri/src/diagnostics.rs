@@ -0,0 +1,359 @@
+/*
+崩溃与错误报告：
+1) 启动时安装 panic hook（保留原有的输出行为），panic 时将消息、位置与调用栈写入
+   app_data_dir/crash-reports/<id>.json；前端未捕获的异常与命令错误经 `report_frontend_error` 记录；
+2) 报告附带应用版本、系统与架构，以及当前日志文件的最后若干行，本地最多保留 `MAX_REPORTS` 份；
+3) 只有在设置中明确同意并填写了接收地址时才会发送：启动后在后台发送尚未发送的报告，
+   也可在设置页手动发送；从不在未同意时访问网络。
+*/
+
+use std::{
+    fs,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
+
+use chrono::Utc;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+const REPORTS_DIR: &str = "crash-reports";
+/// 本地最多保留的报告数，超出时删除最旧的
+const MAX_REPORTS: usize = 50;
+/// 报告附带的日志行数
+const LOG_TAIL_LINES: usize = 200;
+const MESSAGE_MAX_CHARS: usize = 4000;
+const SUBMIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// panic hook 中无法访问 AppHandle，启动时记录所需的信息
+struct ReportEnv {
+    reports_dir: PathBuf,
+    log_dir: Option<PathBuf>,
+    app_version: String,
+}
+
+static REPORT_ENV: OnceLock<ReportEnv> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportKind {
+    Panic,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub id: String,
+    pub kind: ReportKind,
+    pub message: String,
+    /// panic 位置或出错的命令 / 页面
+    #[serde(default)]
+    pub context: Option<String>,
+    #[serde(default)]
+    pub backtrace: Option<String>,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub log_tail: Vec<String>,
+    #[serde(default)]
+    pub submitted: bool,
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    text.chars().take(max_chars).collect()
+}
+
+/// 最近修改的日志文件的最后 `lines` 行
+fn log_tail(log_dir: &Path, lines: usize) -> Vec<String> {
+    let latest = fs::read_dir(log_dir).ok().and_then(|entries| {
+        entries
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "log"))
+            .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+            .max_by_key(|(modified, _)| *modified)
+            .map(|(_, path)| path)
+    });
+    let Some(file) = latest.and_then(|path| fs::File::open(path).ok()) else {
+        return Vec::new();
+    };
+    let mut tail = std::collections::VecDeque::with_capacity(lines);
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        if tail.len() == lines {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    }
+    tail.into()
+}
+
+fn new_report(
+    env: &ReportEnv,
+    kind: ReportKind,
+    message: &str,
+    context: Option<String>,
+    backtrace: Option<String>,
+) -> CrashReport {
+    let now = Utc::now();
+    CrashReport {
+        id: format!(
+            "{}-{}",
+            now.format("%Y%m%d%H%M%S"),
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        ),
+        kind,
+        message: truncate(message, MESSAGE_MAX_CHARS),
+        context,
+        backtrace,
+        app_version: env.app_version.clone(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        created_at: now.to_rfc3339(),
+        log_tail: env
+            .log_dir
+            .as_deref()
+            .map(|dir| log_tail(dir, LOG_TAIL_LINES))
+            .unwrap_or_default(),
+        submitted: false,
+    }
+}
+
+fn report_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{id}.json"))
+}
+
+fn save_report(dir: &Path, report: &CrashReport) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("create_dir_all {}: {e}", dir.display()))?;
+    let json = serde_json::to_vec_pretty(report).map_err(|e| format!("serialize report: {e}"))?;
+    let path = report_path(dir, &report.id);
+    fs::write(&path, json).map_err(|e| format!("write {}: {e}", path.display()))?;
+    prune_reports(dir, MAX_REPORTS);
+    Ok(())
+}
+
+/// 读取全部报告，按时间从新到旧
+fn load_reports(dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| {
+            let bytes = fs::read(entry.path()).ok()?;
+            serde_json::from_slice(&bytes).ok()
+        })
+        .collect();
+    // id 以时间开头，按 id 倒序即从新到旧
+    reports.sort_by(|a, b| b.id.cmp(&a.id));
+    reports
+}
+
+fn prune_reports(dir: &Path, keep: usize) {
+    for report in load_reports(dir).into_iter().skip(keep) {
+        let _ = fs::remove_file(report_path(dir, &report.id));
+    }
+}
+
+fn record(kind: ReportKind, message: &str, context: Option<String>, backtrace: Option<String>) {
+    let Some(env) = REPORT_ENV.get() else {
+        return;
+    };
+    let report = new_report(env, kind, message, context, backtrace);
+    if let Err(err) = save_report(&env.reports_dir, &report) {
+        warn!("save crash report failed: {}", err);
+    }
+}
+
+fn panic_message(info: &std::panic::PanicHookInfo<'_>) -> String {
+    let payload = info.payload();
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// 安装 panic hook，panic 时先写入报告再交给原有的 hook
+pub(crate) fn init(app: &AppHandle) {
+    let reports_dir = match crate::portable::app_data_dir(app) {
+        Ok(dir) => dir.join(REPORTS_DIR),
+        Err(err) => {
+            warn!("crash reports disabled: app_data_dir: {}", err);
+            return;
+        }
+    };
+    let env = ReportEnv {
+        reports_dir,
+        log_dir: crate::portable::app_log_dir(app).ok(),
+        app_version: app.package_info().version.to_string(),
+    };
+    if REPORT_ENV.set(env).is_err() {
+        return;
+    }
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = panic_message(info);
+        let location = info
+            .location()
+            .map(|loc| format!("{}:{}:{}", loc.file(), loc.line(), loc.column()));
+        error!(
+            "panic: {} at {}",
+            message,
+            location.as_deref().unwrap_or("unknown")
+        );
+        let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+        record(ReportKind::Panic, &message, location, Some(backtrace));
+        previous(info);
+    }));
+
+    submit_in_background(app);
+}
+
+/// 已同意发送时在后台发送尚未发送的报告
+fn submit_in_background(app: &AppHandle) {
+    let settings = crate::settings::current_settings(app);
+    if !settings.crash_reporting_enabled || settings.crash_report_endpoint.is_empty() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        match submit_pending(&settings.crash_report_endpoint, None).await {
+            Ok(0) => {}
+            Ok(count) => info!("submitted {} crash reports", count),
+            Err(err) => warn!("submit crash reports failed: {}", err),
+        }
+    });
+}
+
+/// 发送尚未发送的报告（`ids` 为空时发送全部），返回发送成功的数量
+async fn submit_pending(endpoint: &str, ids: Option<&[String]>) -> Result<usize, String> {
+    let Some(env) = REPORT_ENV.get() else {
+        return Ok(0);
+    };
+    let client = reqwest::Client::builder()
+        .timeout(SUBMIT_TIMEOUT)
+        .build()
+        .map_err(|e| format!("failed to build http client: {e}"))?;
+    let mut submitted = 0;
+    for mut report in load_reports(&env.reports_dir) {
+        if report.submitted || ids.is_some_and(|ids| !ids.contains(&report.id)) {
+            continue;
+        }
+        let response = client
+            .post(endpoint)
+            .json(&report)
+            .send()
+            .await
+            .map_err(|e| format!("发送错误报告失败: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("发送错误报告失败: HTTP {}", response.status()));
+        }
+        report.submitted = true;
+        save_report(&env.reports_dir, &report)?;
+        submitted += 1;
+    }
+    Ok(submitted)
+}
+
+/// 记录前端未捕获的异常或命令错误
+#[tauri::command]
+pub fn report_frontend_error(message: String, context: Option<String>, stack: Option<String>) {
+    let message = message.trim();
+    if message.is_empty() {
+        return;
+    }
+    record(
+        ReportKind::Error,
+        message,
+        context.filter(|c| !c.trim().is_empty()),
+        stack.filter(|s| !s.trim().is_empty()),
+    );
+}
+
+/// 本地保存的报告，从新到旧
+#[tauri::command]
+pub fn list_crash_reports() -> Vec<CrashReport> {
+    REPORT_ENV
+        .get()
+        .map(|env| load_reports(&env.reports_dir))
+        .unwrap_or_default()
+}
+
+/// 删除指定报告，`ids` 为空时删除全部
+#[tauri::command]
+pub fn delete_crash_reports(ids: Option<Vec<String>>) -> Result<usize, String> {
+    let Some(env) = REPORT_ENV.get() else {
+        return Ok(0);
+    };
+    let mut deleted = 0;
+    for report in load_reports(&env.reports_dir) {
+        if ids.as_ref().is_some_and(|ids| !ids.contains(&report.id)) {
+            continue;
+        }
+        fs::remove_file(report_path(&env.reports_dir, &report.id))
+            .map_err(|e| format!("删除报告 {} 失败: {e}", report.id))?;
+        deleted += 1;
+    }
+    Ok(deleted)
+}
+
+/// 发送尚未发送的报告；未在设置中同意或未填写接收地址时拒绝
+#[tauri::command]
+pub async fn submit_crash_reports(
+    app: AppHandle,
+    ids: Option<Vec<String>>,
+) -> Result<usize, String> {
+    let settings = crate::settings::current_settings(&app);
+    if !settings.crash_reporting_enabled {
+        return Err("未开启错误报告发送".to_string());
+    }
+    if settings.crash_report_endpoint.is_empty() {
+        return Err("未设置错误报告接收地址".to_string());
+    }
+    submit_pending(&settings.crash_report_endpoint, ids.as_deref()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(dir: &Path) -> ReportEnv {
+        ReportEnv {
+            reports_dir: dir.join(REPORTS_DIR),
+            log_dir: Some(dir.to_path_buf()),
+            app_version: "1.0.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_log_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let lines: Vec<String> = (0..10).map(|i| format!("line {i}")).collect();
+        fs::write(dir.path().join("yana.log"), lines.join("\n")).unwrap();
+        fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+        assert_eq!(log_tail(dir.path(), 3), vec!["line 7", "line 8", "line 9"]);
+        assert!(log_tail(&dir.path().join("missing"), 3).is_empty());
+    }
+
+    #[test]
+    fn test_save_and_prune_reports() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = env(dir.path());
+        for i in 0..3 {
+            let mut report = new_report(&env, ReportKind::Error, "boom", None, None);
+            report.id = format!("2024010100000{i}-abcd");
+            save_report(&env.reports_dir, &report).unwrap();
+        }
+        prune_reports(&env.reports_dir, 2);
+        let ids: Vec<String> = load_reports(&env.reports_dir)
+            .into_iter()
+            .map(|r| r.id)
+            .collect();
+        assert_eq!(ids, vec!["20240101000002-abcd", "20240101000001-abcd"]);
+    }
+}
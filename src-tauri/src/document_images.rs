@@ -0,0 +1,538 @@
+/*
+从文稿中提取并转存图片（迁移草稿、旧文章）：
+1) 识别 Markdown 图片 `![alt](src "title")` / `![alt](<src>)` 与 HTML `<img src="...">` 中的图片引用；
+2) 本地路径（绝对路径、`file://` 链接或相对 `base_dir` 的路径）直接上传，`data:` URI 解码到临时文件，
+   http(s) 链接经转存模块下载（可用 `keep_remote` 保留远程链接）；其他协议的引用原样保留；
+3) 相同的引用只上传一次，统一经 host_upload 完成重复检查、隐私策略、压缩、上传与写入图库；
+4) 上传成功的引用在原文中替换为新链接，失败的保留原样并在结果中列出原因。
+*/
+
+use std::path::{Path, PathBuf};
+
+use base64::{Engine as _, engine::general_purpose};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use url::Url;
+
+use crate::batch_upload::BatchUploadOptions;
+
+/// 结果中展示的 data URI 最大长度
+const DATA_URI_PREVIEW: usize = 48;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DocumentImageOptions {
+    /// 相对路径的基准目录（通常为文稿所在目录）
+    pub base_dir: Option<String>,
+    /// 保留远程图片链接，只处理本地路径与 data URI
+    pub keep_remote: bool,
+    #[serde(flatten)]
+    pub upload: BatchUploadOptions,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacedImage {
+    pub source: String,
+    pub url: String,
+    /// 原文中出现的次数
+    pub count: usize,
+    /// 内容已上传过，沿用图库中的链接
+    pub duplicate: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedImage {
+    pub source: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentImagesResult {
+    /// 替换链接后的文稿
+    pub text: String,
+    pub replaced: Vec<ReplacedImage>,
+    pub failed: Vec<FailedImage>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    Markdown,
+    Html,
+}
+
+/// 文稿中的一处图片引用，start..end 为链接在原文中的字节范围
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Reference {
+    start: usize,
+    end: usize,
+    syntax: Syntax,
+}
+
+impl Reference {
+    /// 引用的实际链接（HTML 属性中的 `&amp;` 还原为 `&`）
+    fn target(&self, text: &str) -> String {
+        let raw = text[self.start..self.end].trim();
+        match self.syntax {
+            Syntax::Markdown => raw.to_string(),
+            Syntax::Html => raw.replace("&amp;", "&"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Source {
+    Local(PathBuf),
+    Data { mime: String, bytes: Vec<u8> },
+    Remote(Url),
+}
+
+/// Markdown 图片 `![alt](target "title")` 中链接的范围
+fn markdown_target(text: &str, bang: usize) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    // alt 文本不跨段落
+    let mut pos = bang + 2;
+    let mut depth = 0;
+    loop {
+        match *bytes.get(pos)? {
+            b'[' => depth += 1,
+            b']' if depth == 0 => break,
+            b']' => depth -= 1,
+            b'\n' if bytes.get(pos + 1) == Some(&b'\n') => return None,
+            _ => {}
+        }
+        pos += 1;
+    }
+    if bytes.get(pos + 1) != Some(&b'(') {
+        return None;
+    }
+    pos += 2;
+    while matches!(bytes.get(pos), Some(b' ' | b'\t')) {
+        pos += 1;
+    }
+    if bytes.get(pos) == Some(&b'<') {
+        let start = pos + 1;
+        let end = start + text[start..].find(['>', '\n'])?;
+        return (bytes[end] == b'>' && end > start).then_some((start, end));
+    }
+    let start = pos;
+    let mut depth = 0;
+    while let Some(&byte) = bytes.get(pos) {
+        match byte {
+            b'(' => depth += 1,
+            b')' if depth == 0 => break,
+            b')' => depth -= 1,
+            b' ' | b'\t' | b'\n' => break,
+            _ => {}
+        }
+        pos += 1;
+    }
+    (pos > start).then_some((start, pos))
+}
+
+/// `<img ...>` 标签中 src 属性值的范围（tag 为小写后的标签文本，offset 为其在原文中的位置）
+fn html_src(tag: &str, offset: usize) -> Option<(usize, usize)> {
+    let bytes = tag.as_bytes();
+    let mut search = 0;
+    while let Some(found) = tag[search..].find("src") {
+        let name = search + found;
+        search = name + 3;
+        // 排除 data-src 等属性名中的 src
+        if !bytes[name - 1].is_ascii_whitespace() {
+            continue;
+        }
+        let mut pos = name + 3;
+        while bytes.get(pos).is_some_and(u8::is_ascii_whitespace) {
+            pos += 1;
+        }
+        if bytes.get(pos) != Some(&b'=') {
+            continue;
+        }
+        pos += 1;
+        while bytes.get(pos).is_some_and(u8::is_ascii_whitespace) {
+            pos += 1;
+        }
+        let (start, end) = match bytes.get(pos)? {
+            quote @ (b'"' | b'\'') => {
+                let start = pos + 1;
+                (start, start + tag[start..].find(*quote as char)?)
+            }
+            _ => {
+                let start = pos;
+                let len = tag[start..]
+                    .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                    .unwrap_or(tag.len() - start);
+                (start, start + len)
+            }
+        };
+        return (end > start).then_some((offset + start, offset + end));
+    }
+    None
+}
+
+/// 按出现顺序找出文稿中的全部图片引用
+fn find_references(text: &str) -> Vec<Reference> {
+    let mut references: Vec<Reference> = text
+        .match_indices("![")
+        .filter_map(|(bang, _)| markdown_target(text, bang))
+        .map(|(start, end)| Reference {
+            start,
+            end,
+            syntax: Syntax::Markdown,
+        })
+        .collect();
+
+    // ASCII 小写不改变字节位置
+    let lower = text.to_ascii_lowercase();
+    for (open, _) in lower.match_indices("<img") {
+        let rest = &lower[open..];
+        if !rest[4..].starts_with(|c: char| c.is_ascii_whitespace()) {
+            continue;
+        }
+        let close = rest.find('>').unwrap_or(rest.len());
+        if let Some((start, end)) = html_src(&rest[..close], open) {
+            references.push(Reference {
+                start,
+                end,
+                syntax: Syntax::Html,
+            });
+        }
+    }
+
+    references.sort_by_key(|reference| reference.start);
+    references.dedup_by(|next, prev| next.start < prev.end);
+    references
+}
+
+/// 判断引用的类型；返回 None 表示不处理（锚点、其他协议等）
+fn classify(target: &str, base_dir: Option<&Path>) -> Option<Result<Source, String>> {
+    if target
+        .get(..5)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"))
+    {
+        return Some(decode_data_uri(target));
+    }
+    if target.starts_with('#') {
+        return None;
+    }
+    let path = Path::new(target);
+    if !path.is_absolute()
+        && let Ok(url) = Url::parse(target)
+    {
+        return match url.scheme() {
+            "http" | "https" => Some(Ok(Source::Remote(url))),
+            "file" => Some(
+                url.to_file_path()
+                    .map(Source::Local)
+                    .map_err(|_| format!("无效的文件链接: {target}")),
+            ),
+            _ => None,
+        };
+    }
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        match base_dir {
+            Some(base) => base.join(path),
+            None => return Some(Err("相对路径需要指定文稿所在目录".to_string())),
+        }
+    };
+    // Markdown 中的路径常被百分号编码（如空格写作 %20）
+    if !path.exists() {
+        let decoded = PathBuf::from(crate::rehost::percent_decode(&path.to_string_lossy()));
+        if decoded.exists() {
+            return Some(Ok(Source::Local(decoded)));
+        }
+    }
+    Some(Ok(Source::Local(path)))
+}
+
+/// 解码 `data:image/png;base64,...`
+fn decode_data_uri(uri: &str) -> Result<Source, String> {
+    let (header, data) = uri[5..]
+        .split_once(',')
+        .ok_or_else(|| "无效的 data URI".to_string())?;
+    let mut parts = header.split(';');
+    let mime = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+    if !mime.starts_with("image/") {
+        return Err(format!("data URI 不是图片: {mime}"));
+    }
+    if !parts.any(|part| part.trim().eq_ignore_ascii_case("base64")) {
+        return Err("仅支持 base64 编码的 data URI".to_string());
+    }
+    let data: String = data.chars().filter(|c| !c.is_whitespace()).collect();
+    let bytes = general_purpose::STANDARD
+        .decode(data)
+        .map_err(|err| format!("data URI 解码失败: {err}"))?;
+    Ok(Source::Data { mime, bytes })
+}
+
+/// 结果中展示的引用（data URI 只保留开头）
+fn display_source(target: &str) -> String {
+    if target.starts_with("data:") && target.chars().count() > DATA_URI_PREVIEW {
+        let preview: String = target.chars().take(DATA_URI_PREVIEW).collect();
+        format!("{preview}…")
+    } else {
+        target.to_string()
+    }
+}
+
+/// 新链接写回原文；HTML 属性中转义 `&` 与引号
+fn escape_url(url: &str, syntax: Syntax) -> String {
+    match syntax {
+        Syntax::Markdown => url.replace(' ', "%20"),
+        Syntax::Html => url
+            .replace('&', "&amp;")
+            .replace('"', "&quot;")
+            .replace('\'', "&#39;"),
+    }
+}
+
+/// 按引用替换链接；replacement 返回 None 的引用保留原样
+fn rewrite(
+    text: &str,
+    references: &[Reference],
+    replacement: impl Fn(&str) -> Option<String>,
+) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for reference in references {
+        let Some(url) = replacement(&reference.target(text)) else {
+            continue;
+        };
+        out.push_str(&text[last..reference.start]);
+        out.push_str(&escape_url(&url, reference.syntax));
+        last = reference.end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+/// 把引用对应的图片准备为本地文件（下载远程图片、写出 data URI）
+async fn prepare(source: Source, dir: &Path) -> Result<String, String> {
+    let path = match source {
+        Source::Local(path) => {
+            if !path.is_file() {
+                return Err(format!("文件不存在: {}", path.display()));
+            }
+            if !crate::upload::is_image_file(&path) {
+                return Err(format!("不是图片文件: {}", path.display()));
+            }
+            path
+        }
+        Source::Data { mime, bytes } => {
+            std::fs::create_dir_all(dir).map_err(|e| format!("创建临时目录失败: {e}"))?;
+            let ext = match mime.trim_start_matches("image/") {
+                "jpeg" => "jpg",
+                "svg+xml" => "svg",
+                other => other,
+            };
+            let name = crate::rehost::fix_extension(
+                &crate::internal_hosts::sanitize_file_name(&format!("image.{ext}")),
+                &bytes,
+            );
+            let path = dir.join(name);
+            std::fs::write(&path, &bytes).map_err(|e| format!("写入临时文件失败: {e}"))?;
+            path
+        }
+        Source::Remote(url) => {
+            std::fs::create_dir_all(dir).map_err(|e| format!("创建临时目录失败: {e}"))?;
+            crate::rehost::download_to(dir, &url).await?.0
+        }
+    };
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// 提取文稿中的图片并上传到指定图床，返回替换为新链接后的文稿
+#[tauri::command]
+pub async fn rewrite_document_images(
+    app: AppHandle,
+    text: String,
+    host: String,
+    options: Option<DocumentImageOptions>,
+) -> Result<DocumentImagesResult, String> {
+    let host = host.trim().to_string();
+    if host.is_empty() {
+        return Err("未指定图床".to_string());
+    }
+    let options = options.unwrap_or_default();
+    let base_dir = options
+        .base_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+
+    let references = find_references(&text);
+    // 相同的引用只处理一次，保留首次出现的顺序
+    let mut targets: Vec<(String, usize)> = Vec::new();
+    for reference in &references {
+        let target = reference.target(&text);
+        match targets.iter_mut().find(|(existing, _)| *existing == target) {
+            Some((_, count)) => *count += 1,
+            None => targets.push((target, 1)),
+        }
+    }
+
+    let dir = crate::rehost::temp_dir("yana_document_")?;
+    let mut failed = Vec::new();
+    let mut pending = Vec::new();
+    for (index, (target, count)) in targets.into_iter().enumerate() {
+        let source = match classify(&target, base_dir.as_deref()) {
+            None => continue,
+            Some(Ok(Source::Remote(_))) if options.keep_remote => continue,
+            Some(Ok(source)) => source,
+            Some(Err(error)) => {
+                failed.push(FailedImage {
+                    source: display_source(&target),
+                    error,
+                });
+                continue;
+            }
+        };
+        // 每个引用使用单独的子目录，同名文件互不覆盖
+        match prepare(source, &dir.path().join(index.to_string())).await {
+            Ok(path) => pending.push((target, count, path)),
+            Err(error) => {
+                warn!("prepare document image failed: {}", error);
+                failed.push(FailedImage {
+                    source: display_source(&target),
+                    error,
+                });
+            }
+        }
+    }
+    info!(
+        "rewrite document images: host={}, references={}, upload={}, failed={}",
+        host,
+        references.len(),
+        pending.len(),
+        failed.len()
+    );
+
+    let paths: Vec<String> = pending.iter().map(|(_, _, path)| path.clone()).collect();
+    let outcomes = if paths.is_empty() {
+        Vec::new()
+    } else {
+        crate::host_upload::compress_and_upload(&app, &host, paths, &options.upload.pipeline())
+            .await
+    };
+    let mut replaced = Vec::new();
+    for ((target, count, _), outcome) in pending.into_iter().zip(outcomes) {
+        match outcome.result {
+            Ok(result) => replaced.push((target, count, result.url, outcome.duplicate)),
+            Err(error) => failed.push(FailedImage {
+                source: display_source(&target),
+                error,
+            }),
+        }
+    }
+
+    let text = rewrite(&text, &references, |target| {
+        replaced
+            .iter()
+            .find(|(source, ..)| source == target)
+            .map(|(_, _, url, _)| url.clone())
+    });
+    Ok(DocumentImagesResult {
+        text,
+        replaced: replaced
+            .into_iter()
+            .map(|(source, count, url, duplicate)| ReplacedImage {
+                source: display_source(&source),
+                url,
+                count,
+                duplicate,
+            })
+            .collect(),
+        failed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(text: &str) -> Vec<String> {
+        find_references(text)
+            .iter()
+            .map(|reference| reference.target(text))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_references() {
+        let text = concat!(
+            "# 标题\n",
+            "![cat](./img/cat.png \"猫\") 与 ![](<my dog.jpg>)\n",
+            "[普通链接](https://example.com/page) ![a [b]](https://x.com/a_(1).png)\n",
+            "<p><IMG class=\"x\" data-src=\"lazy.png\" SRC='https://x.com/b.png?a=1&amp;b=2'></p>\n",
+            "<img src=/abs/c.gif alt=c> <image src=\"no.png\"> ![broken](\n",
+        );
+        assert_eq!(
+            targets(text),
+            vec![
+                "./img/cat.png",
+                "my dog.jpg",
+                "https://x.com/a_(1).png",
+                "https://x.com/b.png?a=1&b=2",
+                "/abs/c.gif",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_classify() {
+        let base = Path::new("/docs");
+        assert_eq!(classify("#top", Some(base)), None);
+        assert_eq!(classify("mailto:a@b.c", Some(base)), None);
+        assert!(matches!(
+            classify("https://x.com/a.png", None),
+            Some(Ok(Source::Remote(_)))
+        ));
+        assert_eq!(
+            classify("img/a.png", Some(base)),
+            Some(Ok(Source::Local(PathBuf::from("/docs/img/a.png"))))
+        );
+        assert!(matches!(classify("img/a.png", None), Some(Err(_))));
+        assert_eq!(
+            classify("data:image/png;base64,iVBORw0KGgo=", None),
+            Some(Ok(Source::Data {
+                mime: "image/png".to_string(),
+                bytes: b"\x89PNG\r\n\x1a\n".to_vec(),
+            }))
+        );
+        assert!(matches!(
+            classify("data:text/plain;base64,aGk=", None),
+            Some(Err(_))
+        ));
+        assert!(matches!(
+            classify("data:image/svg+xml,<svg/>", None),
+            Some(Err(_))
+        ));
+    }
+
+    #[test]
+    fn test_rewrite() {
+        let text = "![a](a.png) <img src=\"a.png\"> ![b](b.png)";
+        let references = find_references(text);
+        let rewritten = rewrite(text, &references, |target| {
+            (target == "a.png").then(|| "https://i.example.com/x.png?a=1&b=2".to_string())
+        });
+        assert_eq!(
+            rewritten,
+            "![a](https://i.example.com/x.png?a=1&b=2) \
+             <img src=\"https://i.example.com/x.png?a=1&amp;b=2\"> ![b](b.png)"
+        );
+    }
+
+    #[test]
+    fn test_display_source() {
+        let uri = format!("data:image/png;base64,{}", "A".repeat(100));
+        assert_eq!(display_source(&uri).chars().count(), DATA_URI_PREVIEW + 1);
+        assert_eq!(display_source("a.png"), "a.png");
+    }
+}
@@ -0,0 +1,383 @@
+/*
+编码器性能测试与设置推荐：
+1) `benchmark_encoders(sample_path?)` 在本机用样张依次运行可用编码器：PNG 无损（快速 / 标准 / 最佳）、
+   PNG 有损、JPEG、WebP 与 AVIF（有损编码的画质均为 `BENCH_QUALITY`），每项运行 `ROUNDS` 次取最短耗时；
+2) 未指定样张时使用内置的合成图（渐变加细节纹理，接近照片的压缩难度），
+   样张长边超过 `MAX_SAMPLE_SIDE` 时先缩小，避免测试耗时过长；
+3) 报告包含每项的耗时、每百万像素耗时、输出大小与压缩比，有损编码附带与样张的 SSIM；
+4) 按每百万像素耗时给出推荐：PNG 优化级别、是否转为 WebP、是否开启自动画质（需多次编码），
+   设置界面可直接应用；
+5) 本构建的 JPEG 使用 image 自带的编码器（未集成 mozjpeg），报告中如实列出编码器名称。
+*/
+
+use std::time::{Duration, Instant};
+
+use image::codecs::avif::AvifEncoder;
+use image::{DynamicImage, ImageEncoder, Rgb, RgbImage};
+use log::info;
+use serde::Serialize;
+
+use crate::process::{PngCompressionMode, PngOptimizationLevel};
+
+const ROUNDS: usize = 3;
+const BENCH_QUALITY: u8 = 80;
+const MAX_SAMPLE_SIDE: u32 = 2048;
+const SYNTHETIC_WIDTH: u32 = 1920;
+const SYNTHETIC_HEIGHT: u32 = 1080;
+/// AVIF 编码速度（1-10，越大越快），与常见图床转换工具的默认值接近
+const AVIF_SPEED: u8 = 8;
+/// 每百万像素耗时在此以内视为「快」，可使用更慢但压缩率更高的选项
+const FAST_MS_PER_MEGAPIXEL: f64 = 150.0;
+/// 自动画质约需编码 6 次，单次 JPEG 编码在此以内时推荐开启
+const AUTO_QUALITY_MS_PER_MEGAPIXEL: f64 = 40.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Codec {
+    PngFast,
+    PngDefault,
+    PngBest,
+    PngLossy,
+    Jpeg,
+    WebP,
+    Avif,
+}
+
+impl Codec {
+    const ALL: [Codec; 7] = [
+        Codec::PngFast,
+        Codec::PngDefault,
+        Codec::PngBest,
+        Codec::PngLossy,
+        Codec::Jpeg,
+        Codec::WebP,
+        Codec::Avif,
+    ];
+
+    fn id(self) -> &'static str {
+        match self {
+            Self::PngFast => "png_fast",
+            Self::PngDefault => "png_default",
+            Self::PngBest => "png_best",
+            Self::PngLossy => "png_lossy",
+            Self::Jpeg => "jpeg",
+            Self::WebP => "webp",
+            Self::Avif => "avif",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::PngFast => "PNG 无损（快速）",
+            Self::PngDefault => "PNG 无损（标准）",
+            Self::PngBest => "PNG 无损（最佳）",
+            Self::PngLossy => "PNG 有损",
+            Self::Jpeg => "JPEG（image）",
+            Self::WebP => "WebP（libwebp）",
+            Self::Avif => "AVIF（rav1e）",
+        }
+    }
+
+    fn lossy(self) -> bool {
+        !matches!(self, Self::PngFast | Self::PngDefault | Self::PngBest)
+    }
+
+    fn encode(self, img: &DynamicImage) -> Result<Vec<u8>, String> {
+        use crate::process::{encode_jpeg, encode_png, encode_webp_static};
+        let png = |level| encode_png(img, BENCH_QUALITY, PngCompressionMode::Lossless, level);
+        match self {
+            Self::PngFast => png(PngOptimizationLevel::Fast),
+            Self::PngDefault => png(PngOptimizationLevel::Default),
+            Self::PngBest => png(PngOptimizationLevel::Best),
+            Self::PngLossy => encode_png(
+                img,
+                BENCH_QUALITY,
+                PngCompressionMode::Lossy,
+                PngOptimizationLevel::Default,
+            ),
+            Self::Jpeg => encode_jpeg(img, BENCH_QUALITY),
+            Self::WebP => encode_webp_static(img, BENCH_QUALITY),
+            Self::Avif => {
+                let rgba = img.to_rgba8();
+                let mut out = Vec::new();
+                AvifEncoder::new_with_speed_quality(&mut out, AVIF_SPEED, BENCH_QUALITY)
+                    .write_image(
+                        rgba.as_raw(),
+                        rgba.width(),
+                        rgba.height(),
+                        image::ExtendedColorType::Rgba8,
+                    )
+                    .map_err(|e| format!("avif encode: {e}"))?;
+                Ok(out)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncoderResult {
+    pub id: String,
+    pub label: String,
+    /// 最短一次的耗时（毫秒）
+    pub millis: f64,
+    pub millis_per_megapixel: f64,
+    pub size: u64,
+    /// 输出大小 / 未压缩 RGB 大小
+    pub ratio: f64,
+    /// 有损编码与样张的 SSIM；无法解码输出（如 AVIF）时为空
+    pub ssim: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncoderRecommendation {
+    pub png_optimization: PngOptimizationLevel,
+    pub convert_to_webp: bool,
+    pub auto_quality: bool,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReport {
+    /// 样张路径，内置合成图时为空
+    pub sample: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub threads: usize,
+    pub results: Vec<EncoderResult>,
+    pub recommendation: EncoderRecommendation,
+}
+
+/// 内置样张：平滑渐变叠加细节纹理
+fn synthetic_sample() -> DynamicImage {
+    DynamicImage::ImageRgb8(RgbImage::from_fn(
+        SYNTHETIC_WIDTH,
+        SYNTHETIC_HEIGHT,
+        |x, y| {
+            let detail = ((x.wrapping_mul(2_654_435_761) ^ y.wrapping_mul(40_503)) >> 27) as u8;
+            Rgb([
+                ((x * 255 / SYNTHETIC_WIDTH) as u8).saturating_add(detail),
+                ((y * 255 / SYNTHETIC_HEIGHT) as u8).saturating_add(detail / 2),
+                (((x + y) / 8 % 256) as u8).saturating_add(detail),
+            ])
+        },
+    ))
+}
+
+fn load_sample(path: Option<&str>) -> Result<DynamicImage, String> {
+    let Some(path) = path else {
+        return Ok(synthetic_sample());
+    };
+    let img = crate::quality_metrics::decode(path)?;
+    if img.width().max(img.height()) > MAX_SAMPLE_SIDE {
+        return Ok(crate::fast_resize::fit_within(
+            &img,
+            MAX_SAMPLE_SIDE,
+            MAX_SAMPLE_SIDE,
+            image::imageops::FilterType::Triangle,
+        ));
+    }
+    Ok(img)
+}
+
+fn run(codec: Codec, img: &DynamicImage, reference: &RgbImage) -> EncoderResult {
+    let megapixels = (img.width() as f64 * img.height() as f64 / 1_000_000.0).max(0.001);
+    let raw_size = img.width() as f64 * img.height() as f64 * 3.0;
+    let mut best = Duration::MAX;
+    let mut output = Err(String::new());
+    for _ in 0..ROUNDS {
+        let started = Instant::now();
+        output = codec.encode(img);
+        best = best.min(started.elapsed());
+        if output.is_err() {
+            break;
+        }
+    }
+    let millis = best.as_secs_f64() * 1000.0;
+    let (size, ssim, error) = match output {
+        Ok(encoded) => {
+            let ssim = codec
+                .lossy()
+                .then(|| image::load_from_memory(&encoded).ok())
+                .flatten()
+                .map(|decoded| {
+                    crate::quality_metrics::ssim(
+                        reference,
+                        &crate::quality_metrics::flatten(&decoded),
+                    )
+                });
+            (encoded.len() as u64, ssim, None)
+        }
+        Err(err) => (0, None, Some(err)),
+    };
+    EncoderResult {
+        id: codec.id().to_string(),
+        label: codec.label().to_string(),
+        millis,
+        millis_per_megapixel: millis / megapixels,
+        size,
+        ratio: size as f64 / raw_size,
+        ssim,
+        error,
+    }
+}
+
+fn recommend(results: &[EncoderResult]) -> EncoderRecommendation {
+    let find = |codec: Codec| {
+        results
+            .iter()
+            .find(|result| result.id == codec.id() && result.error.is_none())
+    };
+    let mut reasons = Vec::new();
+
+    let png_optimization = match (find(Codec::PngBest), find(Codec::PngDefault)) {
+        (Some(best), _) if best.millis_per_megapixel <= FAST_MS_PER_MEGAPIXEL => {
+            reasons.push(format!(
+                "PNG 最佳压缩每百万像素 {:.0} ms，本机足够快",
+                best.millis_per_megapixel
+            ));
+            PngOptimizationLevel::Best
+        }
+        (_, Some(default)) if default.millis_per_megapixel <= FAST_MS_PER_MEGAPIXEL => {
+            reasons.push("PNG 最佳压缩较慢，推荐标准级别".to_string());
+            PngOptimizationLevel::Default
+        }
+        _ => {
+            reasons.push("PNG 压缩较慢，推荐快速级别".to_string());
+            PngOptimizationLevel::Fast
+        }
+    };
+
+    let convert_to_webp = match (find(Codec::WebP), find(Codec::Jpeg)) {
+        (Some(webp), Some(jpeg)) => {
+            let smaller = webp.size as f64 <= jpeg.size as f64 * 0.9;
+            let fast_enough = webp.millis <= jpeg.millis * 4.0
+                || webp.millis_per_megapixel <= FAST_MS_PER_MEGAPIXEL;
+            if smaller && fast_enough {
+                reasons.push(format!(
+                    "WebP 比 JPEG 小 {:.0}%",
+                    (1.0 - webp.size as f64 / jpeg.size.max(1) as f64) * 100.0
+                ));
+                true
+            } else {
+                reasons.push("WebP 相比 JPEG 优势不明显，保持原格式".to_string());
+                false
+            }
+        }
+        _ => false,
+    };
+
+    let auto_quality = find(Codec::Jpeg)
+        .is_some_and(|jpeg| jpeg.millis_per_megapixel <= AUTO_QUALITY_MS_PER_MEGAPIXEL);
+    reasons.push(if auto_quality {
+        "JPEG 编码很快，可开启自动画质".to_string()
+    } else {
+        "自动画质需多次编码，本机上会明显变慢".to_string()
+    });
+
+    EncoderRecommendation {
+        png_optimization,
+        convert_to_webp,
+        auto_quality,
+        reasons,
+    }
+}
+
+fn benchmark(sample_path: Option<String>) -> Result<BenchmarkReport, String> {
+    let img = load_sample(sample_path.as_deref())?;
+    let reference = crate::quality_metrics::flatten(&img);
+    let results: Vec<EncoderResult> = Codec::ALL
+        .iter()
+        .map(|codec| run(*codec, &img, &reference))
+        .collect();
+    let recommendation = recommend(&results);
+    Ok(BenchmarkReport {
+        sample: sample_path,
+        width: img.width(),
+        height: img.height(),
+        threads: std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1),
+        results,
+        recommendation,
+    })
+}
+
+/// 在本机运行各编码器并给出推荐设置；未指定样张时使用内置合成图
+#[tauri::command]
+pub async fn benchmark_encoders(sample_path: Option<String>) -> Result<BenchmarkReport, String> {
+    let sample_path = sample_path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty());
+    let report = tauri::async_runtime::spawn_blocking(move || benchmark(sample_path))
+        .await
+        .map_err(|e| format!("benchmark task failed: {e}"))??;
+    info!(
+        "benchmark encoders: {}x{}, {}",
+        report.width,
+        report.height,
+        report
+            .results
+            .iter()
+            .map(|result| format!("{}={:.0}ms/{}B", result.id, result.millis, result.size))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(codec: Codec, millis_per_megapixel: f64, size: u64) -> EncoderResult {
+        EncoderResult {
+            id: codec.id().to_string(),
+            label: codec.label().to_string(),
+            millis: millis_per_megapixel * 2.0,
+            millis_per_megapixel,
+            size,
+            ratio: 0.1,
+            ssim: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_recommend() {
+        let fast = recommend(&[
+            result(Codec::PngBest, 120.0, 900),
+            result(Codec::PngDefault, 60.0, 1000),
+            result(Codec::Jpeg, 20.0, 1000),
+            result(Codec::WebP, 50.0, 700),
+        ]);
+        assert_eq!(fast.png_optimization, PngOptimizationLevel::Best);
+        assert!(fast.convert_to_webp);
+        assert!(fast.auto_quality);
+
+        let slow = recommend(&[
+            result(Codec::PngBest, 900.0, 900),
+            result(Codec::PngDefault, 400.0, 1000),
+            result(Codec::Jpeg, 80.0, 1000),
+            result(Codec::WebP, 500.0, 980),
+        ]);
+        assert_eq!(slow.png_optimization, PngOptimizationLevel::Fast);
+        assert!(!slow.convert_to_webp);
+        assert!(!slow.auto_quality);
+    }
+
+    #[test]
+    fn test_run() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(64, 48, |x, y| {
+            Rgb([(x * 4) as u8, (y * 5) as u8, 128])
+        }));
+        let reference = crate::quality_metrics::flatten(&img);
+        let jpeg = run(Codec::Jpeg, &img, &reference);
+        assert!(jpeg.error.is_none());
+        assert!(jpeg.size > 0);
+        assert!(jpeg.ssim.is_some_and(|ssim| ssim > 0.8));
+        assert!(run(Codec::PngFast, &img, &reference).ssim.is_none());
+    }
+}
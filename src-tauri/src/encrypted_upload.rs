@@ -0,0 +1,277 @@
+/*
+端到端加密上传（在不可信的图床上分享敏感截图）：
+1) `upload_encrypted(paths, host, options)` 为每个文件随机生成 AES-256-GCM 密钥，
+   文件按设置压缩后加密为 `nonce(12 字节) || 密文`，以随机名称（`.bin`）上传，图床上看不到原文件名与内容；
+2) 仅支持可存放任意文件的图床（与打包上传相同）；
+3) 密钥只放在链接的片段（`#k=...`）中，浏览器不会把片段发送给服务器。
+   图库记录的链接为「密文地址#k=密钥&n=文件名&t=类型」，删除标记与普通上传相同；
+   订阅源、静态网站等对外发布的导出通过 `is_encrypted_link` / `strip_key_fragment` 避免泄露密钥；
+4) 设置了解密页面地址（`encryptedViewerUrl`）时，分享链接为「解密页面#u=密文地址&k=...」；
+   `save_decrypt_page(path)` 导出解密页面模板（浏览器内用 WebCrypto 解密），可放在任意静态托管上。
+*/
+
+use std::path::Path;
+
+use aes_gcm::{
+    Aes256Gcm, KeyInit,
+    aead::{Aead, AeadCore, OsRng},
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL};
+use log::{info, warn};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::batch_upload::BatchUploadOptions;
+use crate::gallery::NewGalleryItem;
+
+const NONCE_LEN: usize = 12;
+/// 解密页面模板
+const DECRYPT_PAGE: &str = include_str!("encrypted_viewer.html");
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedUpload {
+    pub path: String,
+    pub file_name: String,
+    /// 带密钥片段的密文链接（写入图库）
+    pub url: Option<String>,
+    /// 分享链接：设置了解密页面时指向解密页面，否则与 url 相同
+    pub share_url: Option<String>,
+    pub delete_id: Option<String>,
+    pub gallery_id: Option<i64>,
+    pub error: Option<String>,
+}
+
+/// 加密文件内容，返回 (密钥, nonce || 密文)
+fn encrypt(plain: &[u8]) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let key = Aes256Gcm::generate_key(OsRng);
+    let cipher = Aes256Gcm::new(&key);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plain)
+        .map_err(|e| format!("加密失败: {e}"))?;
+    let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok((key.to_vec(), sealed))
+}
+
+#[cfg(test)]
+fn decrypt(key: &[u8], sealed: &[u8]) -> Result<Vec<u8>, String> {
+    if sealed.len() < NONCE_LEN {
+        return Err("密文过短".to_string());
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| format!("密钥无效: {e}"))?;
+    cipher
+        .decrypt(aes_gcm::Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "解密失败".to_string())
+}
+
+/// 链接片段：k=密钥&n=文件名&t=类型，前面可带 u=密文地址
+fn fragment(url: Option<&str>, key: &[u8], file_name: &str, mime: &str) -> String {
+    let mut params = url::form_urlencoded::Serializer::new(String::new());
+    if let Some(url) = url {
+        params.append_pair("u", url);
+    }
+    params
+        .append_pair("k", &BASE64_URL.encode(key))
+        .append_pair("n", file_name)
+        .append_pair("t", mime);
+    params.finish()
+}
+
+/// 图库链接是否为加密上传（带 `#k=` 密钥片段）
+pub(crate) fn is_encrypted_link(url: &str) -> bool {
+    url.contains("#k=")
+}
+
+/// 去掉加密上传链接中的密钥片段，只保留密文地址
+pub(crate) fn strip_key_fragment(url: &str) -> &str {
+    url.split_once("#k=").map_or(url, |(base, _)| base)
+}
+
+/// 把旧链接的密钥片段接到新的密文地址后（迁移图床时使用）
+pub(crate) fn with_key_fragment(keyed: &str, url: &str) -> String {
+    match keyed.split_once("#k=") {
+        Some((_, fragment)) => format!("{}#k={fragment}", strip_key_fragment(url)),
+        None => url.to_string(),
+    }
+}
+
+/// 返回 (图库链接, 分享链接)
+fn share_urls(
+    url: &str,
+    viewer: &str,
+    key: &[u8],
+    file_name: &str,
+    mime: &str,
+) -> (String, String) {
+    let keyed = format!("{url}#{}", fragment(None, key, file_name, mime));
+    let viewer = viewer.trim();
+    if viewer.is_empty() {
+        return (keyed.clone(), keyed);
+    }
+    let viewer = viewer.split('#').next().unwrap_or(viewer);
+    let share = format!("{viewer}#{}", fragment(Some(url), key, file_name, mime));
+    (keyed, share)
+}
+
+async fn upload_one(
+    app: &AppHandle,
+    host: &str,
+    source: &str,
+    prepared: &str,
+    options: &BatchUploadOptions,
+) -> Result<EncryptedUpload, String> {
+    let file_name = crate::host_upload::upload_file_name(Path::new(source), Path::new(prepared));
+    let mime = mime_guess::from_path(&file_name)
+        .first_or_octet_stream()
+        .to_string();
+    let plain = std::fs::read(prepared).map_err(|e| format!("读取 {prepared} 失败: {e}"))?;
+    let (key, sealed) = encrypt(&plain)?;
+
+    let dir = crate::rehost::temp_dir("yana_encrypted_")?;
+    let name = format!("{}.bin", uuid::Uuid::new_v4().simple());
+    let sealed_path = dir.path().join(&name);
+    std::fs::write(&sealed_path, &sealed).map_err(|e| format!("写入密文失败: {e}"))?;
+    let result =
+        crate::host_upload::upload_to_host(app, host, &sealed_path.to_string_lossy(), Some(name))
+            .await?;
+
+    let viewer = crate::settings::current_settings(app).encrypted_viewer_url;
+    let (url, share_url) = share_urls(&result.url, &viewer, &key, &file_name, &mime);
+    let delete_id = Some(result.delete_id).filter(|id| !id.is_empty());
+    let gallery_id = if options.record_gallery.unwrap_or(true) {
+        let item = crate::gallery::gallery_insert_item(
+            app.clone(),
+            NewGalleryItem {
+                file_name: file_name.clone(),
+                url: url.clone(),
+                host: host.to_string(),
+                delete_marker: delete_id.clone(),
+                inserted_at: None,
+                filesize: Some(sealed.len() as i64),
+                content_hash: None,
+            },
+        )?;
+        Some(item.id)
+    } else {
+        None
+    };
+    Ok(EncryptedUpload {
+        path: source.to_string(),
+        file_name,
+        url: Some(url),
+        share_url: Some(share_url),
+        delete_id,
+        gallery_id,
+        error: None,
+    })
+}
+
+/// 加密后上传到支持任意文件的图床，返回带密钥片段的链接
+#[tauri::command]
+pub async fn upload_encrypted(
+    app: AppHandle,
+    paths: Vec<String>,
+    host: String,
+    options: Option<BatchUploadOptions>,
+) -> Result<Vec<EncryptedUpload>, String> {
+    let host = host.trim().to_string();
+    if !crate::archive_upload::ARCHIVE_HOSTS.contains(&host.as_str()) {
+        return Err(format!("图床 {host} 不支持加密上传"));
+    }
+    let options = options.unwrap_or_default();
+    let prepared = crate::host_upload::prepare(&app, &paths, &options.pipeline()).await;
+    info!("upload encrypted: host={}, files={}", host, paths.len());
+    let mut results = Vec::with_capacity(paths.len());
+    for (source, prepared) in paths.iter().zip(prepared) {
+        match upload_one(&app, &host, source, &prepared, &options).await {
+            Ok(result) => results.push(result),
+            Err(error) => {
+                warn!("encrypted upload failed: path={}, error={}", source, error);
+                results.push(EncryptedUpload {
+                    path: source.clone(),
+                    file_name: Path::new(source)
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    url: None,
+                    share_url: None,
+                    delete_id: None,
+                    gallery_id: None,
+                    error: Some(error),
+                });
+            }
+        }
+    }
+    Ok(results)
+}
+
+/// 导出解密页面模板
+#[tauri::command]
+pub fn save_decrypt_page(path: String) -> Result<(), String> {
+    std::fs::write(&path, DECRYPT_PAGE).map_err(|e| format!("写入 {path} 失败: {e}"))?;
+    info!("saved decrypt page: {}", path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let (key, sealed) = encrypt(b"secret screenshot").unwrap();
+        assert_eq!(key.len(), 32);
+        assert_eq!(sealed.len(), NONCE_LEN + 17 + 16);
+        assert_eq!(decrypt(&key, &sealed).unwrap(), b"secret screenshot");
+
+        let mut tampered = sealed;
+        tampered[NONCE_LEN] ^= 1;
+        assert!(decrypt(&key, &tampered).is_err());
+        // 每次加密使用不同的密钥
+        assert_ne!(encrypt(b"secret screenshot").unwrap().0, key);
+    }
+
+    #[test]
+    fn test_share_urls() {
+        let key = [0xfbu8; 32];
+        let (url, share) = share_urls(
+            "https://s3.example.com/a.bin",
+            "",
+            &key,
+            "my shot.png",
+            "image/png",
+        );
+        assert_eq!(share, url);
+        assert!(url.starts_with("https://s3.example.com/a.bin#k=-_v7"));
+        assert!(url.ends_with("&n=my+shot.png&t=image%2Fpng"));
+        assert!(is_encrypted_link(&url));
+        assert_eq!(strip_key_fragment(&url), "https://s3.example.com/a.bin");
+        assert!(!is_encrypted_link("https://img.example.com/a.png#top"));
+        assert_eq!(
+            with_key_fragment(&url, "https://dav.example.com/b.bin"),
+            format!(
+                "https://dav.example.com/b.bin#{}",
+                url.split_once('#').unwrap().1
+            )
+        );
+        assert_eq!(
+            strip_key_fragment("https://img.example.com/a.png#top"),
+            "https://img.example.com/a.png#top"
+        );
+
+        let (_, share) = share_urls(
+            "https://s3.example.com/a.bin",
+            "https://viewer.example.com/decrypt.html#old",
+            &key,
+            "a.png",
+            "image/png",
+        );
+        assert!(share.starts_with(
+            "https://viewer.example.com/decrypt.html#u=https%3A%2F%2Fs3.example.com%2Fa.bin&k="
+        ));
+    }
+}
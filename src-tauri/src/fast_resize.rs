@@ -0,0 +1,175 @@
+/*
+图片缩放（fast_image_resize，SIMD 加速）：
+1) `resize` / `resize_rgb` 缩放到指定尺寸，`fit_within` 等比缩放到给定范围内（缩略图使用）；
+2) 带透明通道的图片按 RGBA8 缩放（自动预乘 alpha，避免透明边缘发黑），其余按 RGB8；
+3) fast_image_resize 失败时（如尺寸为 0）回退到 image 的通用实现，调用方无需处理错误；
+4) 4K 以上的图片缩略图比 image 的通用滤镜快数倍，可运行
+   `cargo test --release fast_resize -- --ignored --nocapture` 对比两者耗时。
+*/
+
+use fast_image_resize::{FilterType as FirFilter, PixelType, ResizeAlg, ResizeOptions, Resizer};
+use image::imageops::FilterType;
+use image::{DynamicImage, RgbImage, RgbaImage};
+use log::debug;
+
+fn resize_alg(filter: FilterType) -> ResizeAlg {
+    match filter {
+        FilterType::Nearest => ResizeAlg::Nearest,
+        FilterType::Triangle => ResizeAlg::Convolution(FirFilter::Bilinear),
+        FilterType::CatmullRom => ResizeAlg::Convolution(FirFilter::CatmullRom),
+        FilterType::Gaussian => ResizeAlg::Convolution(FirFilter::Gaussian),
+        FilterType::Lanczos3 => ResizeAlg::Convolution(FirFilter::Lanczos3),
+    }
+}
+
+/// 缩放按行排列的 8 位像素数据
+fn resize_buffer(
+    src: Vec<u8>,
+    (width, height): (u32, u32),
+    (dst_width, dst_height): (u32, u32),
+    pixel_type: PixelType,
+    filter: FilterType,
+) -> Result<Vec<u8>, String> {
+    let src = fast_image_resize::images::Image::from_vec_u8(width, height, src, pixel_type)
+        .map_err(|e| format!("source image: {e}"))?;
+    let mut dst = fast_image_resize::images::Image::new(dst_width, dst_height, pixel_type);
+    Resizer::new()
+        .resize(
+            &src,
+            &mut dst,
+            &ResizeOptions::new().resize_alg(resize_alg(filter)),
+        )
+        .map_err(|e| format!("resize: {e}"))?;
+    Ok(dst.into_vec())
+}
+
+/// 缩放到指定尺寸（不保持比例）
+pub(crate) fn resize(
+    img: &DynamicImage,
+    width: u32,
+    height: u32,
+    filter: FilterType,
+) -> DynamicImage {
+    let size = (img.width(), img.height());
+    let resized = if img.color().has_alpha() {
+        resize_buffer(
+            img.to_rgba8().into_raw(),
+            size,
+            (width, height),
+            PixelType::U8x4,
+            filter,
+        )
+        .and_then(|buf| {
+            RgbaImage::from_raw(width, height, buf)
+                .map(DynamicImage::ImageRgba8)
+                .ok_or_else(|| "unexpected buffer size".to_string())
+        })
+    } else {
+        resize_buffer(
+            img.to_rgb8().into_raw(),
+            size,
+            (width, height),
+            PixelType::U8x3,
+            filter,
+        )
+        .and_then(|buf| {
+            RgbImage::from_raw(width, height, buf)
+                .map(DynamicImage::ImageRgb8)
+                .ok_or_else(|| "unexpected buffer size".to_string())
+        })
+    };
+    resized.unwrap_or_else(|err| {
+        debug!("fast resize failed, fallback to image: {}", err);
+        img.resize_exact(width, height, filter)
+    })
+}
+
+/// 缩放 RGB 图像到指定尺寸
+pub(crate) fn resize_rgb(img: &RgbImage, width: u32, height: u32, filter: FilterType) -> RgbImage {
+    resize_buffer(
+        img.as_raw().clone(),
+        img.dimensions(),
+        (width, height),
+        PixelType::U8x3,
+        filter,
+    )
+    .ok()
+    .and_then(|buf| RgbImage::from_raw(width, height, buf))
+    .unwrap_or_else(|| image::imageops::resize(img, width, height, filter))
+}
+
+/// 保持比例缩放后恰好放入 max_width x max_height 的尺寸（至少 1 像素）
+pub(crate) fn fit_dimensions(
+    (width, height): (u32, u32),
+    max_width: u32,
+    max_height: u32,
+) -> (u32, u32) {
+    if width == 0 || height == 0 {
+        return (width, height);
+    }
+    let ratio = f64::min(
+        max_width as f64 / width as f64,
+        max_height as f64 / height as f64,
+    );
+    let scale = |value: u32| ((value as f64 * ratio).round() as u32).max(1);
+    (scale(width), scale(height))
+}
+
+/// 保持比例缩放到给定范围内
+pub(crate) fn fit_within(
+    img: &DynamicImage,
+    max_width: u32,
+    max_height: u32,
+    filter: FilterType,
+) -> DynamicImage {
+    let (width, height) = fit_dimensions((img.width(), img.height()), max_width, max_height);
+    resize(img, width, height, filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn gradient(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            image::Rgb([(x % 256) as u8, (y % 256) as u8, ((x + y) % 256) as u8])
+        }))
+    }
+
+    #[test]
+    fn test_fit_dimensions() {
+        assert_eq!(fit_dimensions((3840, 2160), 320, 225), (320, 180));
+        assert_eq!(fit_dimensions((1000, 4000), 320, 225), (56, 225));
+        assert_eq!(fit_dimensions((10000, 1), 320, 225), (320, 1));
+    }
+
+    #[test]
+    fn test_resize() {
+        let img = gradient(64, 48);
+        let resized = fit_within(&img, 32, 32, FilterType::Lanczos3);
+        assert_eq!((resized.width(), resized.height()), (32, 24));
+
+        let rgba = DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+            16,
+            16,
+            image::Rgba([200, 100, 50, 128]),
+        ));
+        let resized = resize(&rgba, 8, 8, FilterType::Triangle);
+        assert_eq!(resized.as_rgba8().unwrap().get_pixel(4, 4).0[3], 128);
+    }
+
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_thumbnail() {
+        let img = gradient(3840, 2160);
+        let started = Instant::now();
+        let _ = img.resize_exact(320, 180, FilterType::Lanczos3);
+        let generic = started.elapsed();
+        let started = Instant::now();
+        let _ = fit_within(&img, 320, 225, FilterType::Lanczos3);
+        let fast = started.elapsed();
+        println!("4K -> 320x180 Lanczos3: image {generic:?}, fast_image_resize {fast:?}");
+        assert!(fast < generic);
+    }
+}
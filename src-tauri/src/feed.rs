@@ -0,0 +1,261 @@
+/*
+最近上传的订阅源（RSS 2.0 / JSON Feed 1.1）：
+1) `export_feed(options, dest)` 按上传时间取最近的图库条目（默认 50 条，最多 500 条），
+   可按图床、标签筛选，生成订阅源文本并可写入指定文件；
+2) 每个条目以文件名为标题、图片链接为链接与附件，附带上传时间、大小与标签；
+3) 本地 HTTP 服务（picgo_server）开启时同样提供 `GET /feed.xml` 与 `GET /feed.json`，
+   查询参数 `limit`、`host`、`tag` 与选项含义相同，且必须设置访问令牌；
+4) 加密上传的链接去掉 `#k=...` 密钥片段后再写入订阅源，订阅源中不会出现解密密钥。
+*/
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use chrono::DateTime;
+use log::info;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tauri::AppHandle;
+
+use crate::encrypted_upload::strip_key_fragment;
+use crate::gallery::{GalleryItem, GalleryQuery};
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+const DEFAULT_TITLE: &str = "最近上传";
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedFormat {
+    #[default]
+    Rss,
+    Json,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FeedOptions {
+    pub format: FeedFormat,
+    /// 条目数，默认 50
+    pub limit: Option<usize>,
+    pub host: Option<String>,
+    pub tag: Option<String>,
+    /// 订阅源标题，默认「最近上传」
+    pub title: Option<String>,
+}
+
+impl FeedOptions {
+    /// 从 HTTP 查询参数读取（format 由路径决定）
+    pub(crate) fn from_query(format: FeedFormat, query: &HashMap<String, String>) -> Self {
+        let text = |key: &str| {
+            query
+                .get(key)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        Self {
+            format,
+            limit: query.get("limit").and_then(|value| value.parse().ok()),
+            host: text("host"),
+            tag: text("tag"),
+            title: text("title"),
+        }
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn mime_type(item: &GalleryItem) -> String {
+    mime_guess::from_path(&item.file_name)
+        .first_or_octet_stream()
+        .to_string()
+}
+
+fn render_rss(title: &str, items: &[(GalleryItem, Vec<String>)]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">\n<channel>\n",
+    );
+    let _ = writeln!(out, "<title>{}</title>", escape_xml(title));
+    out.push_str(
+        "<description>Yana 图库中最近上传的图片</description>\n<generator>Yana</generator>\n",
+    );
+    if let Some(date) = items
+        .first()
+        .and_then(|(item, _)| DateTime::parse_from_rfc3339(&item.inserted_at).ok())
+    {
+        let _ = writeln!(out, "<lastBuildDate>{}</lastBuildDate>", date.to_rfc2822());
+    }
+    for (item, tags) in items {
+        out.push_str("<item>\n");
+        let _ = writeln!(out, "<title>{}</title>", escape_xml(&item.file_name));
+        let _ = writeln!(out, "<link>{}</link>", escape_xml(&item.url));
+        let _ = writeln!(out, "<guid isPermaLink=\"false\">yana-{}</guid>", item.id);
+        if let Ok(date) = DateTime::parse_from_rfc3339(&item.inserted_at) {
+            let _ = writeln!(out, "<pubDate>{}</pubDate>", date.to_rfc2822());
+        }
+        for tag in tags {
+            let _ = writeln!(out, "<category>{}</category>", escape_xml(tag));
+        }
+        let _ = writeln!(
+            out,
+            "<enclosure url=\"{}\" length=\"{}\" type=\"{}\"/>",
+            escape_xml(&item.url),
+            item.filesize.unwrap_or(0),
+            mime_type(item)
+        );
+        out.push_str("</item>\n");
+    }
+    out.push_str("</channel>\n</rss>\n");
+    out
+}
+
+fn render_json(title: &str, items: &[(GalleryItem, Vec<String>)]) -> Value {
+    let entries: Vec<Value> = items
+        .iter()
+        .map(|(item, tags)| {
+            let mut attachment = json!({ "url": item.url, "mime_type": mime_type(item) });
+            if let Some(size) = item.filesize {
+                attachment["size_in_bytes"] = json!(size);
+            }
+            let mut entry = json!({
+                "id": format!("yana-{}", item.id),
+                "url": item.url,
+                "title": item.file_name,
+                "image": item.url,
+                "date_published": item.inserted_at,
+                "attachments": [attachment],
+            });
+            if !tags.is_empty() {
+                entry["tags"] = json!(tags);
+            }
+            entry
+        })
+        .collect();
+    json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": title,
+        "description": "Yana 图库中最近上传的图片",
+        "items": entries,
+    })
+}
+
+/// 按选项生成订阅源，返回 (内容, Content-Type)
+pub(crate) fn build(
+    app: &AppHandle,
+    options: &FeedOptions,
+) -> Result<(String, &'static str), String> {
+    let store = crate::gallery::store_from_app(app)?;
+    let limit = options.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let mut items = store
+        .query(GalleryQuery {
+            host: options.host.clone(),
+            tag: options.tag.clone(),
+            ..Default::default()
+        })
+        .map_err(|err| err.to_string())?;
+    items.truncate(limit);
+    let ids: Vec<i64> = items.iter().map(|item| item.id).collect();
+    let mut tags: HashMap<i64, Vec<String>> = store
+        .tags_of(&ids)
+        .map_err(|err| err.to_string())?
+        .into_iter()
+        .collect();
+    let items: Vec<(GalleryItem, Vec<String>)> = items
+        .into_iter()
+        .map(|mut item| {
+            let item_tags = tags.remove(&item.id).unwrap_or_default();
+            item.url = strip_key_fragment(&item.url).to_string();
+            (item, item_tags)
+        })
+        .collect();
+    let title = options
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .unwrap_or(DEFAULT_TITLE);
+    Ok(match options.format {
+        FeedFormat::Rss => (
+            render_rss(title, &items),
+            "application/rss+xml; charset=utf-8",
+        ),
+        FeedFormat::Json => (
+            serde_json::to_string_pretty(&render_json(title, &items))
+                .map_err(|e| format!("serialize feed: {e}"))?,
+            "application/feed+json; charset=utf-8",
+        ),
+    })
+}
+
+/// 生成最近上传的订阅源，指定 dest 时同时写入文件
+#[tauri::command]
+pub fn export_feed(
+    app: AppHandle,
+    options: Option<FeedOptions>,
+    dest: Option<String>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    let (content, _) = build(&app, &options)?;
+    if let Some(dest) = dest
+        .as_deref()
+        .map(str::trim)
+        .filter(|dest| !dest.is_empty())
+    {
+        std::fs::write(dest, &content).map_err(|e| format!("写入 {dest} 失败: {e}"))?;
+        info!("exported {:?} feed to {}", options.format, dest);
+    }
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<(GalleryItem, Vec<String>)> {
+        vec![(
+            GalleryItem {
+                id: 7,
+                file_name: "a&b.png".to_string(),
+                url: "https://img.example.com/a.png?x=1&y=2".to_string(),
+                host: "s3".to_string(),
+                delete_marker: None,
+                inserted_at: "2026-05-01T08:00:00+00:00".to_string(),
+                filesize: Some(1024),
+            },
+            vec!["trip".to_string()],
+        )]
+    }
+
+    #[test]
+    fn test_render_rss() {
+        let rss = render_rss("我的 <图>", &sample());
+        assert!(rss.contains("<title>我的 &lt;图&gt;</title>"));
+        assert!(rss.contains("<title>a&amp;b.png</title>"));
+        assert!(rss.contains("<link>https://img.example.com/a.png?x=1&amp;y=2</link>"));
+        assert!(rss.contains("May 2026 08:00:00 +0000</pubDate>"));
+        assert!(rss.contains("<category>trip</category>"));
+        assert!(rss.contains("length=\"1024\" type=\"image/png\""));
+    }
+
+    #[test]
+    fn test_render_json() {
+        let feed = render_json("最近上传", &sample());
+        assert_eq!(feed["version"], "https://jsonfeed.org/version/1.1");
+        let item = &feed["items"][0];
+        assert_eq!(item["id"], "yana-7");
+        assert_eq!(item["tags"][0], "trip");
+        assert_eq!(item["attachments"][0]["size_in_bytes"], 1024);
+
+        let mut query = HashMap::new();
+        query.insert("limit".to_string(), "5".to_string());
+        query.insert("tag".to_string(), " ".to_string());
+        let options = FeedOptions::from_query(FeedFormat::Json, &query);
+        assert_eq!(options.limit, Some(5));
+        assert_eq!(options.tag, None);
+    }
+}
@@ -1,5 +1,5 @@
 use std::io::{Read, Write};
-use tauri_plugin_android_fs::{AndroidFsExt, PrivateDir, PublicGeneralPurposeDir};
+use tauri_plugin_android_fs::{AndroidFsExt, FileUri, PrivateDir, PublicGeneralPurposeDir};
 
 #[tauri::command]
 pub async fn select_single_image(app: tauri::AppHandle) -> Result<String, String> {
@@ -22,6 +22,7 @@ pub async fn save_to_download_dir(
 
     // 读取源文件内容
     let source_bytes = std::fs::read(&source_path).map_err(|e| format!("读取源文件失败: {}", e))?;
+    let file_name = crate::filename_template::apply(&file_name, &source_bytes);
 
     // 在 Download 目录创建新文件
     let file_uri = api
@@ -79,9 +80,18 @@ async fn select_images(app: tauri::AppHandle, multiple: bool) -> Result<Vec<Stri
             .map_or(vec![], |f| vec![f])
     };
 
-    if selected_files.is_empty() {
+    copy_to_cache(&app, selected_files).await
+}
+
+/// 将 content URI 指向的文件复制到私有缓存目录，返回本地路径；同一批中重名的文件加序号
+pub(crate) async fn copy_to_cache(
+    app: &tauri::AppHandle,
+    uris: Vec<FileUri>,
+) -> Result<Vec<String>, String> {
+    if uris.is_empty() {
         return Ok(vec![]);
     }
+    let api = app.android_fs_async();
 
     let temp_dir = api
         .private_storage()
@@ -90,9 +100,11 @@ async fn select_images(app: tauri::AppHandle, multiple: bool) -> Result<Vec<Stri
         .map_err(|e| e.to_string())?;
 
     let mut result_paths = Vec::new();
+    let mut used = std::collections::HashSet::new();
 
-    for uri in selected_files {
+    for uri in uris {
         let file_name = api.get_name(&uri).await.map_err(|e| e.to_string())?;
+        let file_name = unique_name(&mut used, &file_name);
         let dest_path = temp_dir.join(&file_name);
 
         let mut source_file = api
@@ -111,3 +123,37 @@ async fn select_images(app: tauri::AppHandle, multiple: bool) -> Result<Vec<Stri
 
     Ok(result_paths)
 }
+
+pub(crate) fn unique_name(used: &mut std::collections::HashSet<String>, name: &str) -> String {
+    let path = std::path::Path::new(name);
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| name.to_string());
+    let ext = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let mut candidate = name.to_string();
+    let mut index = 1;
+    while !used.insert(candidate.clone()) {
+        candidate = format!("{stem}_{index}{ext}");
+        index += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_name() {
+        let mut used = std::collections::HashSet::new();
+        assert_eq!(unique_name(&mut used, "image.jpg"), "image.jpg");
+        assert_eq!(unique_name(&mut used, "image.jpg"), "image_1.jpg");
+        assert_eq!(unique_name(&mut used, "image.jpg"), "image_2.jpg");
+        assert_eq!(unique_name(&mut used, "shared"), "shared");
+        assert_eq!(unique_name(&mut used, "shared"), "shared_1");
+    }
+}
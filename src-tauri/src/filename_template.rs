@@ -0,0 +1,178 @@
+/*
+输出文件名模板：
+1) 设置中的 `fileNameTemplate` 为空时保持原文件名，非空时由 `save_files`、`upload_image`、
+   S3 对象键与 Android `save_to_download_dir` 统一套用；
+2) 占位符：`{date}`（YYYYMMDD）、`{time}`（HHMMSS）、`{uuid}`、`{random:6}`（字母数字）、
+   `{original}`（原文件名主体）、`{hash:8}`（内容 SHA-256 前 N 位），未知占位符原样保留；
+3) 模板只决定文件名主体，扩展名沿用原文件；路径分隔符等非法字符替换为 `_`。
+*/
+
+use std::sync::{OnceLock, RwLock};
+
+use sha2::Digest;
+use uuid::Uuid;
+
+const DEFAULT_RANDOM_LEN: usize = 6;
+const DEFAULT_HASH_LEN: usize = 8;
+const MAX_TOKEN_LEN: usize = 64;
+
+static TEMPLATE: OnceLock<RwLock<String>> = OnceLock::new();
+
+/// 模板支持的占位符名称
+pub(crate) const PLACEHOLDERS: &[&str] = &["date", "time", "uuid", "random", "original", "hash"];
+
+fn template_slot() -> &'static RwLock<String> {
+    TEMPLATE.get_or_init(|| RwLock::new(String::new()))
+}
+
+/// 更新全局文件名模板，随设置加载与保存同步
+pub(crate) fn set_global_template(template: &str) {
+    if let Ok(mut slot) = template_slot().write() {
+        *slot = template.trim().to_string();
+    }
+}
+
+fn global_template() -> String {
+    template_slot()
+        .read()
+        .map(|slot| slot.clone())
+        .unwrap_or_default()
+}
+
+fn split_name(file_name: &str) -> (&str, Option<&str>) {
+    match file_name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && !ext.is_empty() => (stem, Some(ext)),
+        _ => (file_name, None),
+    }
+}
+
+fn token_len(arg: Option<&str>, default: usize) -> usize {
+    arg.and_then(|n| n.trim().parse::<usize>().ok())
+        .unwrap_or(default)
+        .clamp(1, MAX_TOKEN_LEN)
+}
+
+fn random_alphanumeric(len: usize) -> String {
+    const CHARSET: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+    let mut output = String::with_capacity(len);
+    while output.len() < len {
+        for byte in Uuid::new_v4().as_bytes() {
+            if output.len() == len {
+                break;
+            }
+            output.push(CHARSET[*byte as usize % CHARSET.len()] as char);
+        }
+    }
+    output
+}
+
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|ch| match ch {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            ch if ch.is_control() => '_',
+            ch => ch,
+        })
+        .collect::<String>()
+        .trim_matches(|ch: char| ch == '.' || ch.is_whitespace())
+        .to_string()
+}
+
+/// 按模板渲染文件名；模板为空或渲染结果为空时返回原文件名
+pub(crate) fn render_file_name(template: &str, original: &str, bytes: &[u8]) -> String {
+    let template = template.trim();
+    if template.is_empty() {
+        return original.to_string();
+    }
+    let (stem, ext) = split_name(original);
+    let now = chrono::Local::now();
+
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let Some(len) = rest[start + 1..].find('}') else {
+            rendered.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let token = &rest[start + 1..start + 1 + len];
+        let (name, arg) = match token.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (token, None),
+        };
+        match name {
+            "date" => rendered.push_str(&now.format("%Y%m%d").to_string()),
+            "time" => rendered.push_str(&now.format("%H%M%S").to_string()),
+            "uuid" => rendered.push_str(&Uuid::new_v4().to_string()),
+            "random" => rendered.push_str(&random_alphanumeric(token_len(arg, DEFAULT_RANDOM_LEN))),
+            "original" => rendered.push_str(stem),
+            "hash" => {
+                let digest = format!("{:x}", sha2::Sha256::digest(bytes));
+                rendered.push_str(&digest[..token_len(arg, DEFAULT_HASH_LEN)]);
+            }
+            _ => rendered.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    rendered.push_str(rest);
+
+    let rendered = sanitize(&rendered);
+    if rendered.is_empty() {
+        return original.to_string();
+    }
+    match ext {
+        Some(ext) => format!("{}.{}", rendered, ext),
+        None => rendered,
+    }
+}
+
+/// 列出模板中不受支持的占位符（原样保留在文件名中）
+pub(crate) fn unknown_placeholders(template: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start + 1..].find('}') else {
+            break;
+        };
+        let token = &rest[start + 1..start + 1 + len];
+        let name = token.split_once(':').map_or(token, |(name, _)| name);
+        if !PLACEHOLDERS.contains(&name) {
+            unknown.push(token.to_string());
+        }
+        rest = &rest[start + len + 2..];
+    }
+    unknown
+}
+
+/// 套用设置中的全局模板
+pub(crate) fn apply(original: &str, bytes: &[u8]) -> String {
+    render_file_name(&global_template(), original, bytes)
+}
+
+/// 设置中是否配置了模板
+pub(crate) fn is_enabled() -> bool {
+    !global_template().is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_file_name() {
+        let bytes = b"hello";
+        assert_eq!(render_file_name("", "a.png", bytes), "a.png");
+        assert_eq!(
+            render_file_name("{original}-{hash:8}", "photo.jpg", bytes),
+            "photo-2cf24dba.jpg"
+        );
+        let random = render_file_name("{random:10}", "a.png", bytes);
+        assert_eq!(random.len(), "0123456789.png".len());
+        assert_eq!(
+            render_file_name("../{original}/{unknown}", "a.png", bytes),
+            "_a_{unknown}.png"
+        );
+        assert_eq!(render_file_name("{date}", "noext", bytes).len(), 8);
+    }
+}
@@ -0,0 +1,204 @@
+/*
+导入本地图片文件夹（把已有图库迁移到图床）：
+1) `import_folder(dir, host, options)` 扫描文件夹（默认包含子文件夹）中的图片，
+   跳过隐藏文件与隐藏目录，不跟随符号链接，按路径排序；
+2) 扫描结果交给批量上传任务（batch_upload）执行，压缩、并发、重试、重复检查与进度事件与普通批量上传一致；
+3) 默认以图片所在子文件夹相对导入根目录的各级名称作为标签写入图库，例如 `旅行/2023/a.png` 标记为「旅行」「2023」。
+*/
+
+use std::path::{Path, PathBuf};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::batch_upload::BatchUploadOptions;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FolderImportOptions {
+    #[serde(flatten)]
+    pub upload: BatchUploadOptions,
+    /// 是否包含子文件夹，默认包含
+    pub recursive: Option<bool>,
+    /// 是否以子文件夹名作为标签，默认开启
+    pub folder_tags: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderImport {
+    pub job_id: String,
+    pub total: usize,
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+/// 收集文件夹中的图片文件
+fn scan_images(root: &Path, recursive: bool) -> Result<Vec<PathBuf>, String> {
+    let mut images = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            // 根目录读取失败直接报错，子目录无权限等情况跳过
+            Err(err) if dir == root => {
+                return Err(format!("读取文件夹失败 {}: {err}", root.display()));
+            }
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if is_hidden(&path) {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if recursive {
+                    pending.push(path);
+                }
+            } else if file_type.is_file() && crate::upload::is_image_file(&path) {
+                images.push(path);
+            }
+        }
+    }
+    images.sort();
+    Ok(images)
+}
+
+/// 图片所在子文件夹相对根目录的各级名称
+fn folder_tags(root: &Path, file: &Path) -> Vec<String> {
+    file.parent()
+        .and_then(|parent| parent.strip_prefix(root).ok())
+        .map(|relative| {
+            relative
+                .components()
+                .filter_map(|component| component.as_os_str().to_str())
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 扫描文件夹并开始批量上传，返回任务 id 与图片数；进度通过 batch-upload 事件通知
+#[tauri::command]
+pub async fn import_folder(
+    app: AppHandle,
+    dir: String,
+    host: String,
+    options: Option<FolderImportOptions>,
+) -> Result<FolderImport, String> {
+    let host = host.trim().to_string();
+    if host.is_empty() {
+        return Err("未指定图床".to_string());
+    }
+    let root = PathBuf::from(dir.trim());
+    if !root.is_dir() {
+        return Err(format!("文件夹不存在: {}", root.display()));
+    }
+    let options = options.unwrap_or_default();
+    let recursive = options.recursive.unwrap_or(true);
+    let with_tags = options.folder_tags.unwrap_or(true);
+
+    let scan_root = root.clone();
+    let images = tauri::async_runtime::spawn_blocking(move || scan_images(&scan_root, recursive))
+        .await
+        .map_err(|err| format!("扫描文件夹失败: {err}"))??;
+    if images.is_empty() {
+        return Err("文件夹中没有图片".to_string());
+    }
+
+    let files: Vec<(String, Vec<String>)> = images
+        .iter()
+        .map(|path| {
+            let tags = if with_tags {
+                folder_tags(&root, path)
+            } else {
+                Vec::new()
+            };
+            (path.to_string_lossy().into_owned(), tags)
+        })
+        .collect();
+    let total = files.len();
+    info!(
+        "import folder: dir={}, host={}, images={}",
+        root.display(),
+        host,
+        total
+    );
+    let job_id = crate::batch_upload::start_job(app, host, files, options.upload.pipeline())?;
+    Ok(FolderImport { job_id, total })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_images() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("旅行/2023")).unwrap();
+        std::fs::create_dir_all(root.join(".thumbnails")).unwrap();
+        for file in [
+            "a.png",
+            "notes.txt",
+            "旅行/b.JPG",
+            "旅行/2023/c.webp",
+            ".thumbnails/d.png",
+            ".hidden.png",
+        ] {
+            std::fs::write(root.join(file), b"x").unwrap();
+        }
+
+        let names = |paths: Vec<PathBuf>| {
+            paths
+                .iter()
+                .map(|path| {
+                    path.strip_prefix(root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(scan_images(root, true).unwrap()),
+            vec!["a.png", "旅行/2023/c.webp", "旅行/b.JPG"]
+        );
+        assert_eq!(names(scan_images(root, false).unwrap()), vec!["a.png"]);
+        assert!(scan_images(&root.join("missing"), true).is_err());
+    }
+
+    #[test]
+    fn test_folder_tags() {
+        let root = Path::new("/photos");
+        assert_eq!(
+            folder_tags(root, Path::new("/photos/旅行/2023/c.webp")),
+            vec!["旅行", "2023"]
+        );
+        assert!(folder_tags(root, Path::new("/photos/a.png")).is_empty());
+        assert!(folder_tags(root, Path::new("/other/a.png")).is_empty());
+    }
+
+    #[test]
+    fn test_options() {
+        let options: FolderImportOptions = serde_json::from_value(serde_json::json!({
+            "recursive": false,
+            "webp": true,
+            "retries": 2
+        }))
+        .unwrap();
+        assert_eq!(options.recursive, Some(false));
+        assert!(options.folder_tags.is_none());
+        assert!(options.upload.webp);
+        assert_eq!(options.upload.pipeline().retries, 2);
+    }
+}
@@ -5,14 +5,17 @@ use std::{
 };
 
 use chrono::{DateTime, Utc};
-use rusqlite::{Connection, params, types::Value};
+use rusqlite::{Connection, OptionalExtension, params, types::Value};
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 const DB_FILE_NAME: &str = "gallery.db";
 
 static GALLERY_STORE: OnceLock<GalleryStore> = OnceLock::new();
 
+const SELECT_ITEM: &str =
+    "SELECT id, file_name, url, host, delete_marker, inserted_at, filesize FROM gallery_items";
+
 #[derive(Debug, Serialize)]
 pub struct GalleryItem {
     pub id: i64,
@@ -24,6 +27,31 @@ pub struct GalleryItem {
     pub filesize: Option<i64>,
 }
 
+/// 条目迁移到其他图床之前使用过的链接
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct UrlHistoryEntry {
+    pub url: String,
+    pub host: String,
+    pub delete_marker: Option<String>,
+    pub replaced_at: String,
+}
+
+/// 回收站中等待彻底删除的条目
+#[derive(Debug, Serialize)]
+pub struct TrashedItem {
+    pub item: GalleryItem,
+    pub trashed_at: String,
+    /// 到期后是否同时删除图床上的文件
+    pub remote_delete: bool,
+}
+
+/// 压缩包条目中包含的文件（打包上传时记录）
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct ArchiveFile {
+    pub name: String,
+    pub size: Option<i64>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct NewGalleryItem {
     pub file_name: String,
@@ -34,6 +62,9 @@ pub struct NewGalleryItem {
     pub inserted_at: Option<String>,
     /// 文件大小（字节），可选
     pub filesize: Option<i64>,
+    /// 上传前源文件内容的 SHA-256（十六进制），用于跳过重复上传
+    #[serde(default)]
+    pub content_hash: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -48,6 +79,10 @@ pub struct GalleryQuery {
     pub min_filesize: Option<i64>,
     /// 文件大小上限（字节）
     pub max_filesize: Option<i64>,
+    /// 图片中识别出的文字（OCR），模糊匹配
+    pub text: Option<String>,
+    /// 标签，精确匹配
+    pub tag: Option<String>,
 }
 
 #[derive(Debug)]
@@ -89,6 +124,18 @@ impl From<chrono::ParseError> for GalleryError {
     }
 }
 
+fn row_to_item(row: &rusqlite::Row<'_>) -> rusqlite::Result<GalleryItem> {
+    Ok(GalleryItem {
+        id: row.get(0)?,
+        file_name: row.get(1)?,
+        url: row.get(2)?,
+        host: row.get(3)?,
+        delete_marker: row.get(4)?,
+        inserted_at: row.get(5)?,
+        filesize: row.get(6)?,
+    })
+}
+
 pub struct GalleryStore {
     connection: Mutex<Connection>,
 }
@@ -112,6 +159,7 @@ impl GalleryStore {
             delete_marker,
             inserted_at: provided_ts,
             filesize,
+            content_hash,
         } = new_item;
 
         let inserted_at = if let Some(ts) = provided_ts {
@@ -123,14 +171,15 @@ impl GalleryStore {
 
         let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
         connection.execute(
-            "INSERT INTO gallery_items (file_name, url, host, delete_marker, inserted_at, filesize) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            "INSERT INTO gallery_items (file_name, url, host, delete_marker, inserted_at, filesize, content_hash) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
             params![
                 &file_name,
                 &url,
                 &host,
                 &delete_marker,
                 &inserted_at,
-                &filesize
+                &filesize,
+                &content_hash
             ],
         )?;
 
@@ -150,18 +199,106 @@ impl GalleryStore {
     pub fn delete(&self, id: i64) -> Result<(), GalleryError> {
         let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
         connection.execute("DELETE FROM gallery_items WHERE id = ?1", params![id])?;
-        Ok(())
+        delete_related(&connection, id)
+    }
+
+    /// 把条目移入回收站（附属记录保留，便于撤销）；条目不存在时返回 None
+    pub fn trash(&self, id: i64, remote_delete: bool) -> Result<Option<GalleryItem>, GalleryError> {
+        let mut connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let tx = connection.transaction()?;
+        let Some(item) = tx
+            .query_row(
+                &format!("{SELECT_ITEM} WHERE id = ?1"),
+                params![id],
+                row_to_item,
+            )
+            .optional()?
+        else {
+            return Ok(None);
+        };
+        tx.execute(
+            "INSERT OR REPLACE INTO gallery_trash
+                 (id, file_name, url, host, delete_marker, inserted_at, filesize, content_hash,
+                  trashed_at, remote_delete)
+             SELECT id, file_name, url, host, delete_marker, inserted_at, filesize, content_hash,
+                  ?2, ?3
+             FROM gallery_items WHERE id = ?1",
+            params![id, Utc::now().to_rfc3339(), remote_delete],
+        )?;
+        tx.execute("DELETE FROM gallery_items WHERE id = ?1", params![id])?;
+        tx.commit()?;
+        Ok(Some(item))
+    }
+
+    /// 把回收站中的条目恢复到图库；不在回收站中时返回 None
+    pub fn restore(&self, id: i64) -> Result<Option<GalleryItem>, GalleryError> {
+        let mut connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let tx = connection.transaction()?;
+        let restored = tx.execute(
+            "INSERT INTO gallery_items
+                 (id, file_name, url, host, delete_marker, inserted_at, filesize, content_hash)
+             SELECT id, file_name, url, host, delete_marker, inserted_at, filesize, content_hash
+             FROM gallery_trash WHERE id = ?1",
+            params![id],
+        )?;
+        if restored == 0 {
+            return Ok(None);
+        }
+        tx.execute("DELETE FROM gallery_trash WHERE id = ?1", params![id])?;
+        let item = tx.query_row(
+            &format!("{SELECT_ITEM} WHERE id = ?1"),
+            params![id],
+            row_to_item,
+        )?;
+        tx.commit()?;
+        Ok(Some(item))
+    }
+
+    /// 回收站中的条目，最早删除的在前
+    pub fn trashed(&self) -> Result<Vec<TrashedItem>, GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let mut stmt = connection.prepare(
+            "SELECT id, file_name, url, host, delete_marker, inserted_at, filesize,
+                    trashed_at, remote_delete
+             FROM gallery_trash ORDER BY trashed_at, id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(TrashedItem {
+                item: row_to_item(row)?,
+                trashed_at: row.get(7)?,
+                remote_delete: row.get(8)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 彻底删除回收站中的条目及其附属记录，返回被删除的条目
+    pub fn purge(&self, id: i64) -> Result<Option<GalleryItem>, GalleryError> {
+        let mut connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let tx = connection.transaction()?;
+        let item = tx
+            .query_row(
+                "SELECT id, file_name, url, host, delete_marker, inserted_at, filesize
+                 FROM gallery_trash WHERE id = ?1",
+                params![id],
+                row_to_item,
+            )
+            .optional()?;
+        if item.is_some() {
+            tx.execute("DELETE FROM gallery_trash WHERE id = ?1", params![id])?;
+            delete_related(&tx, id)?;
+        }
+        tx.commit()?;
+        Ok(item)
     }
 
     pub fn query(&self, filters: GalleryQuery) -> Result<Vec<GalleryItem>, GalleryError> {
-        let mut sql = String::from(
-            "SELECT id, file_name, url, host, delete_marker, inserted_at, filesize FROM gallery_items WHERE 1=1",
-        );
+        let mut sql = format!("{SELECT_ITEM} WHERE 1=1");
         let mut params: Vec<Value> = Vec::new();
 
         if let Some(name) = filters.file_name {
-            sql.push_str(" AND file_name LIKE ?");
-            params.push(Value::from(format!("%{name}%")));
+            sql.push_str(" AND file_name LIKE ? ESCAPE '\\'");
+            params.push(Value::from(contains_pattern(&name)));
         }
 
         if let Some(host) = filters.host {
@@ -191,21 +328,25 @@ impl GalleryStore {
             params.push(Value::from(max_size));
         }
 
+        if let Some(text) = filters.text {
+            // trigram 分词的表支持 LIKE 子串匹配，中文等无空格文本同样适用；
+            // 用户输入中的通配符需转义，带 ESCAPE 时 SQLite 可能退回逐行比较
+            sql.push_str(
+                " AND id IN (SELECT rowid FROM gallery_ocr WHERE text LIKE ? ESCAPE '\\')",
+            );
+            params.push(Value::from(contains_pattern(&text)));
+        }
+
+        if let Some(tag) = filters.tag {
+            sql.push_str(" AND id IN (SELECT item_id FROM gallery_tags WHERE tag = ?)");
+            params.push(Value::from(tag));
+        }
+
         sql.push_str(" ORDER BY inserted_at DESC, id DESC");
 
         let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
         let mut stmt = connection.prepare(&sql)?;
-        let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
-            Ok(GalleryItem {
-                id: row.get(0)?,
-                file_name: row.get(1)?,
-                url: row.get(2)?,
-                host: row.get(3)?,
-                delete_marker: row.get(4)?,
-                inserted_at: row.get(5)?,
-                filesize: row.get(6)?,
-            })
-        })?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), row_to_item)?;
 
         let mut results = Vec::new();
         for row in rows {
@@ -214,6 +355,278 @@ impl GalleryStore {
         Ok(results)
     }
 
+    /// 按 id 读取条目，结果顺序与传入的 id 一致，不存在的 id 被忽略
+    pub fn get_many(&self, ids: &[i64]) -> Result<Vec<GalleryItem>, GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let mut stmt = connection.prepare(&format!("{SELECT_ITEM} WHERE id = ?1"))?;
+        let mut results = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(item) = stmt.query_row(params![id], row_to_item).optional()? {
+                results.push(item);
+            }
+        }
+        Ok(results)
+    }
+
+    /// 按链接查找最近写入的条目
+    pub fn find_by_url(&self, url: &str) -> Result<Option<GalleryItem>, GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        Ok(connection
+            .query_row(
+                &format!("{SELECT_ITEM} WHERE url = ?1 ORDER BY inserted_at DESC, id DESC LIMIT 1"),
+                params![url],
+                row_to_item,
+            )
+            .optional()?)
+    }
+
+    /// 按源文件内容哈希查找同一图床上最近写入的条目
+    pub fn find_by_hash(
+        &self,
+        content_hash: &str,
+        host: &str,
+    ) -> Result<Option<GalleryItem>, GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        Ok(connection
+            .query_row(
+                &format!(
+                    "{SELECT_ITEM} WHERE content_hash = ?1 AND host = ?2
+                     ORDER BY inserted_at DESC, id DESC LIMIT 1"
+                ),
+                params![content_hash, host],
+                row_to_item,
+            )
+            .optional()?)
+    }
+
+    /// 写入（覆盖）条目的 OCR 文字
+    pub fn set_ocr_text(&self, id: i64, text: &str) -> Result<(), GalleryError> {
+        let mut connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let tx = connection.transaction()?;
+        tx.execute("DELETE FROM gallery_ocr WHERE rowid = ?1", params![id])?;
+        tx.execute(
+            "INSERT INTO gallery_ocr (rowid, text) VALUES (?1, ?2)",
+            params![id, text],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn ocr_text(&self, id: i64) -> Result<Option<String>, GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        Ok(connection
+            .query_row(
+                "SELECT text FROM gallery_ocr WHERE rowid = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// 为条目添加标签，已有的标签忽略
+    pub fn add_tags(&self, id: i64, tags: &[String]) -> Result<(), GalleryError> {
+        let mut connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let tx = connection.transaction()?;
+        for tag in tags {
+            tx.execute(
+                "INSERT OR IGNORE INTO gallery_tags (item_id, tag) VALUES (?1, ?2)",
+                params![id, tag],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 条目的标签（id, 标签），没有标签的条目不返回
+    pub fn tags_of(&self, ids: &[i64]) -> Result<Vec<(i64, Vec<String>)>, GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let mut stmt =
+            connection.prepare("SELECT tag FROM gallery_tags WHERE item_id = ?1 ORDER BY rowid")?;
+        let mut results = Vec::new();
+        for id in ids {
+            let tags = stmt
+                .query_map(params![id], |row| row.get::<_, String>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            if !tags.is_empty() {
+                results.push((*id, tags));
+            }
+        }
+        Ok(results)
+    }
+
+    pub fn list_tags(&self) -> Result<Vec<String>, GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let mut stmt = connection
+            .prepare("SELECT DISTINCT tag FROM gallery_tags ORDER BY tag COLLATE NOCASE")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 把条目改为新链接（迁移图床），原链接、图床与删除标记写入历史，链接检查状态清空；
+    /// 条目不存在时返回 None
+    pub fn replace_url(
+        &self,
+        id: i64,
+        url: &str,
+        host: &str,
+        delete_marker: Option<&str>,
+        filesize: Option<i64>,
+    ) -> Result<Option<GalleryItem>, GalleryError> {
+        let mut connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let tx = connection.transaction()?;
+        let inserted = tx.execute(
+            "INSERT INTO gallery_url_history (item_id, url, host, delete_marker, replaced_at)
+             SELECT id, url, host, delete_marker, ?2 FROM gallery_items WHERE id = ?1",
+            params![id, Utc::now().to_rfc3339()],
+        )?;
+        if inserted == 0 {
+            return Ok(None);
+        }
+        tx.execute(
+            "UPDATE gallery_items
+             SET url = ?2, host = ?3, delete_marker = ?4, filesize = COALESCE(?5, filesize)
+             WHERE id = ?1",
+            params![id, url, host, delete_marker, filesize],
+        )?;
+        tx.execute(
+            "DELETE FROM gallery_link_status WHERE item_id = ?1",
+            params![id],
+        )?;
+        let item = tx.query_row(
+            &format!("{SELECT_ITEM} WHERE id = ?1"),
+            params![id],
+            row_to_item,
+        )?;
+        tx.commit()?;
+        Ok(Some(item))
+    }
+
+    /// 条目此前使用过的链接，最近替换的在前
+    pub fn url_history(&self, id: i64) -> Result<Vec<UrlHistoryEntry>, GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let mut stmt = connection.prepare(
+            "SELECT url, host, delete_marker, replaced_at FROM gallery_url_history
+             WHERE item_id = ?1 ORDER BY replaced_at DESC, rowid DESC",
+        )?;
+        let rows = stmt.query_map(params![id], |row| {
+            Ok(UrlHistoryEntry {
+                url: row.get(0)?,
+                host: row.get(1)?,
+                delete_marker: row.get(2)?,
+                replaced_at: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 写入（覆盖）压缩包条目包含的文件清单
+    pub fn set_archive_files(&self, id: i64, files: &[ArchiveFile]) -> Result<(), GalleryError> {
+        let mut connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let tx = connection.transaction()?;
+        tx.execute(
+            "DELETE FROM gallery_archive_files WHERE item_id = ?1",
+            params![id],
+        )?;
+        for (position, file) in files.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO gallery_archive_files (item_id, position, name, size)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![id, position as i64, file.name, file.size],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 压缩包条目包含的文件，按打包顺序；非压缩包条目为空
+    pub fn archive_files(&self, id: i64) -> Result<Vec<ArchiveFile>, GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let mut stmt = connection.prepare(
+            "SELECT name, size FROM gallery_archive_files WHERE item_id = ?1 ORDER BY position",
+        )?;
+        let rows = stmt.query_map(params![id], |row| {
+            Ok(ArchiveFile {
+                name: row.get(0)?,
+                size: row.get(1)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 按最久未检查的顺序取一批条目（从未检查的优先），用于轮换复查链接
+    pub fn items_to_verify(&self, limit: usize) -> Result<Vec<GalleryItem>, GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let mut stmt = connection.prepare(&format!(
+            "{SELECT_ITEM} LEFT JOIN gallery_link_status ON item_id = id
+             ORDER BY checked_at IS NOT NULL, checked_at, id LIMIT ?1"
+        ))?;
+        let rows = stmt.query_map(params![limit as i64], row_to_item)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 记录链接检查结果，返回此前的状态；`status` 为 None（结果不确定）时保留此前的状态
+    pub fn update_link_status(
+        &self,
+        id: i64,
+        status: Option<&str>,
+        http_status: Option<u16>,
+        error: Option<&str>,
+    ) -> Result<Option<String>, GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let previous: Option<String> = connection
+            .query_row(
+                "SELECT status FROM gallery_link_status WHERE item_id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        connection.execute(
+            "INSERT INTO gallery_link_status (item_id, status, http_status, checked_at, error)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(item_id) DO UPDATE SET
+                 status = COALESCE(excluded.status, status),
+                 http_status = excluded.http_status,
+                 checked_at = excluded.checked_at,
+                 error = excluded.error",
+            params![id, status, http_status, Utc::now().to_rfc3339(), error],
+        )?;
+        Ok(previous)
+    }
+
+    /// 最近一次链接检查的时间
+    pub fn last_link_check(&self) -> Result<Option<String>, GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        Ok(connection.query_row(
+            "SELECT MAX(checked_at) FROM gallery_link_status",
+            [],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// 条目的链接状态（id, 状态, 检查时间），未检查过的条目不返回
+    pub fn link_statuses(
+        &self,
+        ids: &[i64],
+    ) -> Result<Vec<(i64, Option<String>, String)>, GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let mut stmt = connection.prepare(
+            "SELECT item_id, status, checked_at FROM gallery_link_status WHERE item_id = ?1",
+        )?;
+        let mut results = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(row) = stmt
+                .query_row(params![id], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                })
+                .optional()?
+            {
+                results.push(row);
+            }
+        }
+        Ok(results)
+    }
+
     pub fn list_hosts(&self) -> Result<Vec<String>, GalleryError> {
         let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
         let mut stmt = connection
@@ -225,6 +638,150 @@ impl GalleryStore {
         }
         Ok(hosts)
     }
+
+    /// 将当前数据库导出为一致性快照文件（`VACUUM INTO`），目标文件不能已存在
+    pub fn snapshot_to(&self, path: &Path) -> Result<(), GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        connection.execute(
+            "VACUUM INTO ?1",
+            params![path.to_string_lossy().into_owned()],
+        )?;
+        Ok(())
+    }
+
+    /// 以快照文件中的记录替换全部图库记录，返回导入的条数
+    pub fn replace_from(&self, path: &Path) -> Result<usize, GalleryError> {
+        let mut connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        connection.execute(
+            "ATTACH DATABASE ?1 AS incoming",
+            params![path.to_string_lossy().into_owned()],
+        )?;
+        let result = (|| {
+            let tx = connection.transaction()?;
+            tx.execute("DELETE FROM main.gallery_items", [])?;
+            tx.execute("DELETE FROM main.gallery_ocr", [])?;
+            tx.execute("DELETE FROM main.gallery_link_status", [])?;
+            tx.execute("DELETE FROM main.gallery_tags", [])?;
+            tx.execute("DELETE FROM main.gallery_url_history", [])?;
+            tx.execute("DELETE FROM main.gallery_archive_files", [])?;
+            tx.execute("DELETE FROM main.gallery_trash", [])?;
+            // 旧版本快照没有内容哈希列
+            let has_hash: bool = tx.query_row(
+                "SELECT COUNT(1) > 0 FROM pragma_table_info('gallery_items', 'incoming')
+                 WHERE name = 'content_hash'",
+                [],
+                |row| row.get(0),
+            )?;
+            let hash_column = if has_hash { "content_hash" } else { "NULL" };
+            let count = tx.execute(
+                &format!(
+                    "INSERT INTO main.gallery_items
+                         (id, file_name, url, host, delete_marker, inserted_at, filesize, content_hash)
+                     SELECT id, file_name, url, host, delete_marker, inserted_at, filesize, {hash_column}
+                     FROM incoming.gallery_items"
+                ),
+                [],
+            )?;
+            // 旧版本快照没有 OCR 表
+            let has_ocr: bool = tx.query_row(
+                "SELECT COUNT(1) > 0 FROM incoming.sqlite_master WHERE name = 'gallery_ocr'",
+                [],
+                |row| row.get(0),
+            )?;
+            if has_ocr {
+                tx.execute(
+                    "INSERT INTO main.gallery_ocr (rowid, text)
+                     SELECT rowid, text FROM incoming.gallery_ocr",
+                    [],
+                )?;
+            }
+            let has_tags: bool = tx.query_row(
+                "SELECT COUNT(1) > 0 FROM incoming.sqlite_master WHERE name = 'gallery_tags'",
+                [],
+                |row| row.get(0),
+            )?;
+            if has_tags {
+                tx.execute(
+                    "INSERT INTO main.gallery_tags (item_id, tag)
+                     SELECT item_id, tag FROM incoming.gallery_tags",
+                    [],
+                )?;
+            }
+            let has_history: bool = tx.query_row(
+                "SELECT COUNT(1) > 0 FROM incoming.sqlite_master WHERE name = 'gallery_url_history'",
+                [],
+                |row| row.get(0),
+            )?;
+            if has_history {
+                tx.execute(
+                    "INSERT INTO main.gallery_url_history
+                         (item_id, url, host, delete_marker, replaced_at)
+                     SELECT item_id, url, host, delete_marker, replaced_at
+                     FROM incoming.gallery_url_history",
+                    [],
+                )?;
+            }
+            let has_archives: bool = tx.query_row(
+                "SELECT COUNT(1) > 0 FROM incoming.sqlite_master WHERE name = 'gallery_archive_files'",
+                [],
+                |row| row.get(0),
+            )?;
+            if has_archives {
+                tx.execute(
+                    "INSERT INTO main.gallery_archive_files (item_id, position, name, size)
+                     SELECT item_id, position, name, size FROM incoming.gallery_archive_files",
+                    [],
+                )?;
+            }
+            tx.commit()?;
+            Ok::<_, GalleryError>(count)
+        })();
+        connection.execute("DETACH DATABASE incoming", [])?;
+        result
+    }
+
+    pub fn contains_url(&self, url: &str) -> Result<bool, GalleryError> {
+        let connection = self.connection.lock().map_err(|_| GalleryError::Poisoned)?;
+        let count: i64 = connection.query_row(
+            "SELECT COUNT(1) FROM gallery_items WHERE url = ?1",
+            params![url],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+}
+
+/// 删除条目的附属记录（文字识别、链接状态、标签、链接历史、压缩包清单）
+fn delete_related(connection: &Connection, id: i64) -> Result<(), GalleryError> {
+    connection.execute("DELETE FROM gallery_ocr WHERE rowid = ?1", params![id])?;
+    connection.execute(
+        "DELETE FROM gallery_link_status WHERE item_id = ?1",
+        params![id],
+    )?;
+    connection.execute("DELETE FROM gallery_tags WHERE item_id = ?1", params![id])?;
+    connection.execute(
+        "DELETE FROM gallery_url_history WHERE item_id = ?1",
+        params![id],
+    )?;
+    connection.execute(
+        "DELETE FROM gallery_archive_files WHERE item_id = ?1",
+        params![id],
+    )?;
+    Ok(())
+}
+
+/// 构造“包含”匹配的 LIKE 模式，转义用户输入中的 `%`、`_` 与转义符 `\`
+fn contains_pattern(text: &str) -> String {
+    let mut pattern = String::with_capacity(text.len() + 2);
+    pattern.push('%');
+    for ch in text.chars() {
+        if matches!(ch, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(ch);
+    }
+    pattern.push('%');
+    pattern
 }
 
 fn parse_datetime(value: &str) -> Result<DateTime<Utc>, GalleryError> {
@@ -247,33 +804,82 @@ fn ensure_schema(conn: &Connection) -> Result<(), GalleryError> {
          CREATE INDEX IF NOT EXISTS idx_gallery_host ON gallery_items (host);
          CREATE INDEX IF NOT EXISTS idx_gallery_inserted_at ON gallery_items (inserted_at);
          CREATE INDEX IF NOT EXISTS idx_gallery_file_name ON gallery_items (file_name);
+         CREATE VIRTUAL TABLE IF NOT EXISTS gallery_ocr USING fts5(text, tokenize = 'trigram');
+         CREATE TABLE IF NOT EXISTS gallery_link_status (
+             item_id INTEGER PRIMARY KEY,
+             status TEXT,
+             http_status INTEGER,
+             checked_at TEXT NOT NULL,
+             error TEXT
+         );
+         CREATE INDEX IF NOT EXISTS idx_gallery_link_checked_at ON gallery_link_status (checked_at);
+         CREATE TABLE IF NOT EXISTS gallery_tags (
+             item_id INTEGER NOT NULL,
+             tag TEXT NOT NULL,
+             PRIMARY KEY (item_id, tag)
+         );
+         CREATE INDEX IF NOT EXISTS idx_gallery_tags_tag ON gallery_tags (tag);
+         CREATE TABLE IF NOT EXISTS gallery_url_history (
+             item_id INTEGER NOT NULL,
+             url TEXT NOT NULL,
+             host TEXT NOT NULL,
+             delete_marker TEXT,
+             replaced_at TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_gallery_url_history_item ON gallery_url_history (item_id);
+         CREATE TABLE IF NOT EXISTS gallery_archive_files (
+             item_id INTEGER NOT NULL,
+             position INTEGER NOT NULL,
+             name TEXT NOT NULL,
+             size INTEGER,
+             PRIMARY KEY (item_id, position)
+         );
+         CREATE TABLE IF NOT EXISTS gallery_trash (
+             id INTEGER PRIMARY KEY,
+             file_name TEXT NOT NULL,
+             url TEXT NOT NULL,
+             host TEXT NOT NULL,
+             delete_marker TEXT,
+             inserted_at TEXT NOT NULL,
+             filesize INTEGER,
+             content_hash TEXT,
+             trashed_at TEXT NOT NULL,
+             remote_delete INTEGER NOT NULL DEFAULT 0
+         );
         ",
     )?;
 
     let mut pragma_stmt = conn.prepare("PRAGMA table_info(gallery_items)")?;
     let columns = pragma_stmt.query_map([], |row| row.get::<_, String>(1))?;
     let mut has_filesize = false;
+    let mut has_content_hash = false;
     for column in columns {
-        if column? == "filesize" {
-            has_filesize = true;
-            break;
+        match column?.as_str() {
+            "filesize" => has_filesize = true,
+            "content_hash" => has_content_hash = true,
+            _ => {}
         }
     }
 
     if !has_filesize {
         conn.execute("ALTER TABLE gallery_items ADD COLUMN filesize INTEGER", [])?;
     }
+    if !has_content_hash {
+        conn.execute("ALTER TABLE gallery_items ADD COLUMN content_hash TEXT", [])?;
+    }
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_gallery_content_hash ON gallery_items (content_hash, host)",
+        [],
+    )?;
     Ok(())
 }
 
-fn store_from_app(app: &AppHandle) -> Result<&'static GalleryStore, String> {
+pub(crate) fn store_from_app(app: &AppHandle) -> Result<&'static GalleryStore, String> {
     if let Some(store) = GALLERY_STORE.get() {
         return Ok(store);
     }
 
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
+    let app_data_dir = crate::portable::app_data_dir(&app)
         .map_err(|err| format!("Failed to resolve app data dir: {err}"))?;
     let store = GalleryStore::new(app_data_dir).map_err(|err| err.to_string())?;
 
@@ -288,7 +894,17 @@ fn store_from_app(app: &AppHandle) -> Result<&'static GalleryStore, String> {
 #[tauri::command]
 pub fn gallery_insert_item(app: AppHandle, item: NewGalleryItem) -> Result<GalleryItem, String> {
     let store = store_from_app(&app)?;
-    store.insert(item).map_err(|err| err.to_string())
+    let item = store.insert(item).map_err(|err| err.to_string())?;
+    #[cfg(desktop)]
+    crate::tray::record_upload(&app, &item.url);
+    Ok(item)
+}
+
+/// 条目的 OCR 文字，未识别时为 None
+#[tauri::command]
+pub fn gallery_ocr_text(app: AppHandle, id: i64) -> Result<Option<String>, String> {
+    let store = store_from_app(&app)?;
+    store.ocr_text(id).map_err(|err| err.to_string())
 }
 
 #[tauri::command]
@@ -307,6 +923,39 @@ pub fn gallery_query_items(
     store.query(filters).map_err(|err| err.to_string())
 }
 
+/// 条目的标签，键为条目 id；没有标签的条目不返回
+#[tauri::command]
+pub fn gallery_item_tags(
+    app: AppHandle,
+    ids: Vec<i64>,
+) -> Result<std::collections::HashMap<i64, Vec<String>>, String> {
+    let store = store_from_app(&app)?;
+    store
+        .tags_of(&ids)
+        .map(|rows| rows.into_iter().collect())
+        .map_err(|err| err.to_string())
+}
+
+/// 条目迁移前使用过的链接
+#[tauri::command]
+pub fn gallery_url_history(app: AppHandle, id: i64) -> Result<Vec<UrlHistoryEntry>, String> {
+    let store = store_from_app(&app)?;
+    store.url_history(id).map_err(|err| err.to_string())
+}
+
+/// 压缩包条目包含的文件清单
+#[tauri::command]
+pub fn gallery_archive_files(app: AppHandle, id: i64) -> Result<Vec<ArchiveFile>, String> {
+    let store = store_from_app(&app)?;
+    store.archive_files(id).map_err(|err| err.to_string())
+}
+
+#[tauri::command]
+pub fn gallery_list_tags(app: AppHandle) -> Result<Vec<String>, String> {
+    let store = store_from_app(&app)?;
+    store.list_tags().map_err(|err| err.to_string())
+}
+
 #[tauri::command]
 pub fn gallery_list_hosts(app: AppHandle) -> Result<Vec<String>, String> {
     let store = store_from_app(&app)?;
@@ -320,3 +969,234 @@ pub fn gallery_list_hosts(app: AppHandle) -> Result<Vec<String>, String> {
         })
         .map_err(|err| err.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_item(file_name: &str) -> NewGalleryItem {
+        NewGalleryItem {
+            file_name: file_name.to_string(),
+            url: format!("https://img.example.com/{file_name}"),
+            host: "s3".to_string(),
+            delete_marker: None,
+            inserted_at: None,
+            filesize: None,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_ocr_text_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = GalleryStore::new(dir.path()).unwrap();
+        let error = store.insert(new_item("error.png")).unwrap();
+        let other = store.insert(new_item("other.png")).unwrap();
+        store
+            .set_ocr_text(error.id, "thread 'main' panicked: 连接被拒绝")
+            .unwrap();
+        store.set_ocr_text(other.id, "hello world").unwrap();
+        store.set_ocr_text(other.id, "build succeeded").unwrap();
+
+        let search = |text: &str| {
+            store
+                .query(GalleryQuery {
+                    text: Some(text.to_string()),
+                    ..Default::default()
+                })
+                .unwrap()
+                .into_iter()
+                .map(|item| item.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(search("PANICKED"), vec![error.id]);
+        assert_eq!(search("连接被拒"), vec![error.id]);
+        assert!(search("hello").is_empty());
+        // 通配符按字面匹配
+        assert!(search("%").is_empty());
+        assert!(search("build_succeeded").is_empty());
+        assert_eq!(contains_pattern(r"50%_a\b"), r"%50\%\_a\\b%");
+        assert_eq!(
+            store.ocr_text(other.id).unwrap().as_deref(),
+            Some("build succeeded")
+        );
+
+        store.delete(error.id).unwrap();
+        assert!(store.ocr_text(error.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_link_status_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = GalleryStore::new(dir.path()).unwrap();
+        let first = store.insert(new_item("a.png")).unwrap();
+        let second = store.insert(new_item("b.png")).unwrap();
+
+        let ids = |items: Vec<GalleryItem>| items.into_iter().map(|i| i.id).collect::<Vec<_>>();
+        assert_eq!(ids(store.items_to_verify(1).unwrap()), vec![first.id]);
+
+        assert_eq!(
+            store
+                .update_link_status(first.id, Some("alive"), Some(200), None)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            ids(store.items_to_verify(2).unwrap()),
+            vec![second.id, first.id]
+        );
+
+        // 结果不确定时保留此前的状态
+        store
+            .update_link_status(first.id, None, Some(503), Some("HTTP 503"))
+            .unwrap();
+        assert_eq!(
+            store
+                .update_link_status(first.id, Some("dead"), Some(404), None)
+                .unwrap()
+                .as_deref(),
+            Some("alive")
+        );
+        let statuses = store.link_statuses(&[first.id, second.id]).unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].1.as_deref(), Some("dead"));
+        assert!(store.last_link_check().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_find_by_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = GalleryStore::new(dir.path()).unwrap();
+        let mut item = new_item("a.png");
+        item.content_hash = Some("abc".to_string());
+        let inserted = store.insert(item).unwrap();
+
+        let found = store.find_by_hash("abc", "s3").unwrap().unwrap();
+        assert_eq!(found.id, inserted.id);
+        assert!(store.find_by_hash("abc", "webdav").unwrap().is_none());
+        assert!(store.find_by_hash("def", "s3").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = GalleryStore::new(dir.path()).unwrap();
+        let travel = store.insert(new_item("a.png")).unwrap();
+        let other = store.insert(new_item("b.png")).unwrap();
+        let tags = |list: &[&str]| list.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        store.add_tags(travel.id, &tags(&["旅行", "2023"])).unwrap();
+        store.add_tags(travel.id, &tags(&["2023"])).unwrap();
+        store.add_tags(other.id, &tags(&["2023"])).unwrap();
+
+        assert_eq!(
+            store.tags_of(&[travel.id]).unwrap(),
+            vec![(travel.id, tags(&["旅行", "2023"]))]
+        );
+        assert_eq!(store.list_tags().unwrap(), tags(&["2023", "旅行"]));
+        let found = store
+            .query(GalleryQuery {
+                tag: Some("旅行".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, travel.id);
+
+        store.delete(travel.id).unwrap();
+        assert!(store.tags_of(&[travel.id]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_replace_url() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = GalleryStore::new(dir.path()).unwrap();
+        let item = store.insert(new_item("a.png")).unwrap();
+        store
+            .update_link_status(item.id, Some("dead"), Some(404), None)
+            .unwrap();
+
+        let moved = store
+            .replace_url(
+                item.id,
+                "https://cdn.example.com/a.png",
+                "webdav",
+                Some("a.png"),
+                Some(42),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(moved.url, "https://cdn.example.com/a.png");
+        assert_eq!(moved.host, "webdav");
+        assert_eq!(moved.delete_marker.as_deref(), Some("a.png"));
+        assert_eq!(moved.filesize, Some(42));
+        assert!(store.link_statuses(&[item.id]).unwrap().is_empty());
+
+        let history = store.url_history(item.id).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].url, item.url);
+        assert_eq!(history[0].host, "s3");
+        assert!(
+            store
+                .replace_url(9999, "x", "s3", None, None)
+                .unwrap()
+                .is_none()
+        );
+
+        store.delete(item.id).unwrap();
+        assert!(store.url_history(item.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_archive_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = GalleryStore::new(dir.path()).unwrap();
+        let item = store.insert(new_item("photos.zip")).unwrap();
+        let files = vec![
+            ArchiveFile {
+                name: "b.png".to_string(),
+                size: Some(2),
+            },
+            ArchiveFile {
+                name: "a.png".to_string(),
+                size: None,
+            },
+        ];
+        store.set_archive_files(item.id, &files).unwrap();
+        assert_eq!(store.archive_files(item.id).unwrap(), files);
+        store.set_archive_files(item.id, &files[..1]).unwrap();
+        assert_eq!(store.archive_files(item.id).unwrap(), files[..1]);
+
+        store.delete(item.id).unwrap();
+        assert!(store.archive_files(item.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_trash_restore_purge() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = GalleryStore::new(dir.path()).unwrap();
+        let item = store.insert(new_item("a.png")).unwrap();
+        store.add_tags(item.id, &["trip".to_string()]).unwrap();
+
+        let trashed = store.trash(item.id, true).unwrap().unwrap();
+        assert_eq!(trashed.url, item.url);
+        assert!(store.get_many(&[item.id]).unwrap().is_empty());
+        let pending = store.trashed().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert!(pending[0].remote_delete);
+        assert!(store.trash(item.id, true).unwrap().is_none());
+
+        // 撤销后条目与标签原样恢复
+        let restored = store.restore(item.id).unwrap().unwrap();
+        assert_eq!(restored.id, item.id);
+        assert_eq!(restored.inserted_at, item.inserted_at);
+        assert_eq!(store.tags_of(&[item.id]).unwrap().len(), 1);
+        assert!(store.trashed().unwrap().is_empty());
+        assert!(store.restore(item.id).unwrap().is_none());
+
+        store.trash(item.id, false).unwrap();
+        assert_eq!(store.purge(item.id).unwrap().unwrap().id, item.id);
+        assert!(store.tags_of(&[item.id]).unwrap().is_empty());
+        assert!(store.purge(item.id).unwrap().is_none());
+        assert!(store.restore(item.id).unwrap().is_none());
+    }
+}
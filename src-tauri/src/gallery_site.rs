@@ -0,0 +1,449 @@
+/*
+导出静态图库网站（发布或归档一组精选图片）：
+1) `export_gallery_site(query, dest_dir, options)` 按筛选条件（或 `options.ids` 指定的选中条目）生成纯静态页面，
+   不依赖脚本即可浏览，可直接上传到任意静态托管；
+2) `index.html` 为缩略图网格，缩略图取自本地缓存（复制到 `thumbs/`），未缓存的条目直接显示原图；
+3) 每个条目有单独的大图页（`items/<id>.html`），含文件名、上传时间、标签与上一张 / 下一张导航；
+4) 有标签时生成标签筛选页（`tags/<序号>.html`），各页顶部列出全部标签；
+5) `copyOriginals` 开启时把原图下载到 `images/`，页面引用本地副本，适合离线归档；下载失败的仍引用原链接；
+6) 加密上传的条目（链接带 `#k=` 密钥片段）不会导出，避免把解密密钥发布出去。
+*/
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::encrypted_upload::is_encrypted_link;
+use crate::gallery::{GalleryItem, GalleryQuery};
+
+const DEFAULT_TITLE: &str = "图库";
+const STYLE: &str = r#"*{box-sizing:border-box}
+body{margin:0;font:14px/1.5 system-ui,sans-serif;background:#16181d;color:#e4e4e7}
+header{padding:20px 24px 8px}
+h1{margin:0 0 12px;font-size:22px}
+a{color:#8ab4ff;text-decoration:none}
+nav.tags{display:flex;flex-wrap:wrap;gap:6px}
+nav.tags a{padding:2px 10px;border-radius:999px;background:#262a33;color:#c9cdd6}
+nav.tags a.active{background:#3b6fd8;color:#fff}
+main.grid{display:grid;grid-template-columns:repeat(auto-fill,minmax(200px,1fr));gap:12px;padding:16px 24px}
+main.grid a{display:block;border-radius:10px;overflow:hidden;background:#20232a}
+main.grid img{display:block;width:100%;aspect-ratio:320/225;object-fit:cover}
+main.grid span{display:block;padding:6px 8px;font-size:12px;white-space:nowrap;overflow:hidden;text-overflow:ellipsis;color:#a1a1aa}
+main.photo{display:flex;flex-direction:column;align-items:center;gap:12px;padding:12px 24px 32px}
+main.photo img{max-width:100%;max-height:78vh;border-radius:6px}
+.meta{color:#a1a1aa;font-size:13px;text-align:center}
+.pager{display:flex;gap:24px}
+"#;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SiteOptions {
+    /// 网站标题，默认「图库」
+    pub title: Option<String>,
+    /// 仅导出这些条目（图库中的选中项），优先于 query
+    pub ids: Option<Vec<i64>>,
+    /// 把原图下载到导出目录
+    pub copy_originals: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteExportResult {
+    pub index_path: String,
+    pub items: usize,
+    /// 使用缓存缩略图的条目数
+    pub thumbnails: usize,
+    /// 已下载到本地的原图数
+    pub originals: usize,
+    pub failed: Vec<String>,
+    /// 跳过的加密上传条目数
+    pub skipped_encrypted: usize,
+}
+
+/// 页面中的一个条目；thumb 与 image 为相对站点根目录的路径或原链接
+struct SiteItem {
+    item: GalleryItem,
+    tags: Vec<String>,
+    thumb: String,
+    image: String,
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// 站点内的相对路径加上页面所在层级的前缀，外部链接原样返回
+fn link(prefix: &str, target: &str) -> String {
+    if target.contains("://") {
+        escape(target)
+    } else {
+        escape(&format!("{prefix}{target}"))
+    }
+}
+
+fn page(title: &str, prefix: &str, body: &str) -> String {
+    format!(
+        "<!doctype html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n<link rel=\"stylesheet\" href=\"{prefix}style.css\">\n</head>\n\
+         <body>\n{body}</body>\n</html>\n",
+        escape(title)
+    )
+}
+
+fn tag_nav(tags: &[String], active: Option<usize>, prefix: &str) -> String {
+    if tags.is_empty() {
+        return String::new();
+    }
+    let mut nav = String::from("<nav class=\"tags\">");
+    let class = |on: bool| if on { " class=\"active\"" } else { "" };
+    let _ = write!(
+        nav,
+        "<a href=\"{prefix}index.html\"{}>全部</a>",
+        class(active.is_none())
+    );
+    for (index, tag) in tags.iter().enumerate() {
+        let _ = write!(
+            nav,
+            "<a href=\"{prefix}tags/{index}.html\"{}>{}</a>",
+            class(active == Some(index)),
+            escape(tag)
+        );
+    }
+    nav.push_str("</nav>\n");
+    nav
+}
+
+/// 缩略图网格（首页或标签页）
+fn render_index(
+    title: &str,
+    items: &[&SiteItem],
+    tags: &[String],
+    active: Option<usize>,
+    prefix: &str,
+) -> String {
+    let heading = match active.and_then(|index| tags.get(index)) {
+        Some(tag) => format!("{} · {}", escape(title), escape(tag)),
+        None => escape(title),
+    };
+    let mut body = format!(
+        "<header>\n<h1>{heading}</h1>\n{}</header>\n<main class=\"grid\">\n",
+        tag_nav(tags, active, prefix)
+    );
+    for entry in items {
+        let _ = writeln!(
+            body,
+            "<a href=\"{prefix}items/{}.html\"><img src=\"{}\" alt=\"{}\" loading=\"lazy\"><span>{}</span></a>",
+            entry.item.id,
+            link(prefix, &entry.thumb),
+            escape(&entry.item.file_name),
+            escape(&entry.item.file_name)
+        );
+    }
+    body.push_str("</main>\n");
+    let full_title = match active.and_then(|index| tags.get(index)) {
+        Some(tag) => format!("{title} · {tag}"),
+        None => title.to_string(),
+    };
+    page(&full_title, prefix, &body)
+}
+
+/// 单个条目的大图页
+fn render_item(title: &str, items: &[SiteItem], position: usize, tags: &[String]) -> String {
+    const PREFIX: &str = "../";
+    let entry = &items[position];
+    let mut body = format!(
+        "<header>\n<h1><a href=\"{PREFIX}index.html\">{}</a></h1>\n</header>\n<main class=\"photo\">\n\
+         <a href=\"{}\"><img src=\"{}\" alt=\"{}\"></a>\n",
+        escape(title),
+        link(PREFIX, &entry.image),
+        link(PREFIX, &entry.image),
+        escape(&entry.item.file_name)
+    );
+    let date = entry
+        .item
+        .inserted_at
+        .split('T')
+        .next()
+        .unwrap_or(&entry.item.inserted_at);
+    let _ = writeln!(
+        body,
+        "<p class=\"meta\">{} · {}</p>",
+        escape(&entry.item.file_name),
+        escape(date)
+    );
+    if !entry.tags.is_empty() {
+        body.push_str("<nav class=\"tags\">");
+        for tag in &entry.tags {
+            if let Some(index) = tags.iter().position(|known| known == tag) {
+                let _ = write!(
+                    body,
+                    "<a href=\"{PREFIX}tags/{index}.html\">{}</a>",
+                    escape(tag)
+                );
+            }
+        }
+        body.push_str("</nav>\n");
+    }
+    body.push_str("<p class=\"pager\">");
+    if let Some(previous) = position.checked_sub(1).and_then(|index| items.get(index)) {
+        let _ = write!(
+            body,
+            "<a href=\"{}.html\" rel=\"prev\">← 上一张</a>",
+            previous.item.id
+        );
+    }
+    if let Some(next) = items.get(position + 1) {
+        let _ = write!(
+            body,
+            "<a href=\"{}.html\" rel=\"next\">下一张 →</a>",
+            next.item.id
+        );
+    }
+    body.push_str("</p>\n</main>\n");
+    page(
+        &format!("{} · {title}", entry.item.file_name),
+        PREFIX,
+        &body,
+    )
+}
+
+/// 写出全部页面，返回首页路径
+fn write_site(dest: &Path, title: &str, items: &[SiteItem]) -> Result<PathBuf, String> {
+    let write = |path: PathBuf, content: String| {
+        std::fs::write(&path, content).map_err(|e| format!("写入 {} 失败: {e}", path.display()))
+    };
+    for dir in ["items", "tags"] {
+        std::fs::create_dir_all(dest.join(dir)).map_err(|e| format!("创建目录失败: {e}"))?;
+    }
+    write(dest.join("style.css"), STYLE.to_string())?;
+
+    let mut tags: Vec<String> = items.iter().flat_map(|entry| entry.tags.clone()).collect();
+    tags.sort_by_key(|tag| tag.to_lowercase());
+    tags.dedup();
+
+    let all: Vec<&SiteItem> = items.iter().collect();
+    let index = dest.join("index.html");
+    write(index.clone(), render_index(title, &all, &tags, None, ""))?;
+    for (position, tag) in tags.iter().enumerate() {
+        let tagged: Vec<&SiteItem> = items
+            .iter()
+            .filter(|entry| entry.tags.contains(tag))
+            .collect();
+        write(
+            dest.join("tags").join(format!("{position}.html")),
+            render_index(title, &tagged, &tags, Some(position), "../"),
+        )?;
+    }
+    for (position, entry) in items.iter().enumerate() {
+        write(
+            dest.join("items").join(format!("{}.html", entry.item.id)),
+            render_item(title, items, position, &tags),
+        )?;
+    }
+    Ok(index)
+}
+
+/// 去掉加密上传的条目，返回（可发布的条目，跳过数）
+fn without_encrypted(items: Vec<GalleryItem>) -> (Vec<GalleryItem>, usize) {
+    let total = items.len();
+    let items: Vec<GalleryItem> = items
+        .into_iter()
+        .filter(|item| !is_encrypted_link(&item.url))
+        .collect();
+    let skipped = total - items.len();
+    (items, skipped)
+}
+
+/// 把图库条目导出为静态网站
+#[tauri::command]
+pub async fn export_gallery_site(
+    app: AppHandle,
+    query: Option<GalleryQuery>,
+    dest_dir: String,
+    options: Option<SiteOptions>,
+) -> Result<SiteExportResult, String> {
+    let options = options.unwrap_or_default();
+    let store = crate::gallery::store_from_app(&app)?;
+    let items = match &options.ids {
+        Some(ids) => store.get_many(ids),
+        None => store.query(query.unwrap_or_default()),
+    }
+    .map_err(|err| err.to_string())?;
+    let (items, skipped_encrypted) = without_encrypted(items);
+    if items.is_empty() {
+        return Err("没有可导出的图片".to_string());
+    }
+    let ids: Vec<i64> = items.iter().map(|item| item.id).collect();
+    let mut tags: HashMap<i64, Vec<String>> = store
+        .tags_of(&ids)
+        .map_err(|err| err.to_string())?
+        .into_iter()
+        .collect();
+
+    let dest = PathBuf::from(dest_dir.trim());
+    let dirs: &[&str] = if options.copy_originals {
+        &["thumbs", "images"]
+    } else {
+        &["thumbs"]
+    };
+    for dir in dirs {
+        std::fs::create_dir_all(dest.join(dir)).map_err(|e| format!("创建目录失败: {e}"))?;
+    }
+    let (mut thumbnails, mut originals, mut failed) = (0, 0, Vec::new());
+    let mut site_items = Vec::with_capacity(items.len());
+    for item in items {
+        let mut image = item.url.clone();
+        if options.copy_originals {
+            let name = format!(
+                "{}_{}",
+                item.id,
+                crate::internal_hosts::sanitize_file_name(&item.file_name)
+            );
+            match crate::thumbnail::download_image(&item.url, &dest.join("images").join(&name))
+                .await
+            {
+                Ok(_) => {
+                    image = format!("images/{name}");
+                    originals += 1;
+                }
+                Err(err) => {
+                    warn!("download original failed: id={}, error={}", item.id, err);
+                    failed.push(format!("{}：{err}", item.file_name));
+                }
+            }
+        }
+        let thumb = match crate::thumbnail::cached_thumbnail(&app, &item.url)? {
+            Some(cached) => {
+                let name = format!("thumbs/{}.webp", item.id);
+                std::fs::copy(&cached, dest.join(&name))
+                    .map_err(|e| format!("复制缩略图失败: {e}"))?;
+                thumbnails += 1;
+                name
+            }
+            None => image.clone(),
+        };
+        site_items.push(SiteItem {
+            tags: tags.remove(&item.id).unwrap_or_default(),
+            item,
+            thumb,
+            image,
+        });
+    }
+
+    let title = options
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|title| !title.is_empty())
+        .unwrap_or(DEFAULT_TITLE)
+        .to_string();
+    let index = {
+        let dest = dest.clone();
+        let title = title.clone();
+        tokio::task::spawn_blocking(move || write_site(&dest, &title, &site_items))
+            .await
+            .map_err(|e| format!("spawn_blocking error: {}", e))??
+    };
+    info!(
+        "exported gallery site: dest={}, items={}, thumbnails={}, originals={}",
+        dest.display(),
+        ids.len(),
+        thumbnails,
+        originals
+    );
+    Ok(SiteExportResult {
+        index_path: index.to_string_lossy().into_owned(),
+        items: ids.len(),
+        thumbnails,
+        originals,
+        failed,
+        skipped_encrypted,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site_item(id: i64, name: &str, tags: &[&str]) -> SiteItem {
+        SiteItem {
+            item: GalleryItem {
+                id,
+                file_name: name.to_string(),
+                url: format!("https://img.example.com/{name}"),
+                host: "s3".to_string(),
+                delete_marker: None,
+                inserted_at: "2026-05-01T08:00:00+00:00".to_string(),
+                filesize: None,
+            },
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            thumb: format!("thumbs/{id}.webp"),
+            image: format!("https://img.example.com/{name}"),
+        }
+    }
+
+    #[test]
+    fn test_escape_and_link() {
+        assert_eq!(escape("<a & \"b\">"), "&lt;a &amp; &quot;b&quot;&gt;");
+        assert_eq!(link("../", "thumbs/1.webp"), "../thumbs/1.webp");
+        assert_eq!(
+            link("../", "https://a.example.com/x.png?a=1&b=2"),
+            "https://a.example.com/x.png?a=1&amp;b=2"
+        );
+    }
+
+    #[test]
+    fn test_write_site() {
+        let dir = tempfile::tempdir().unwrap();
+        let items = vec![
+            site_item(1, "beach.png", &["trip"]),
+            site_item(2, "<cat>.png", &[]),
+            site_item(3, "hill.png", &["trip", "Alps"]),
+        ];
+        let index = write_site(dir.path(), "我的图库", &items).unwrap();
+        let html = std::fs::read_to_string(index).unwrap();
+        assert!(html.contains("href=\"items/2.html\""));
+        assert!(html.contains("&lt;cat&gt;.png"));
+        assert!(html.contains("src=\"thumbs/1.webp\""));
+        // 标签按名称排序：Alps=0，trip=1
+        assert!(html.contains("href=\"tags/1.html\">trip</a>"));
+
+        let trip = std::fs::read_to_string(dir.path().join("tags/1.html")).unwrap();
+        assert!(trip.contains("items/1.html") && trip.contains("items/3.html"));
+        assert!(!trip.contains("items/2.html"));
+        assert!(trip.contains("src=\"../thumbs/1.webp\""));
+
+        let middle = std::fs::read_to_string(dir.path().join("items/2.html")).unwrap();
+        assert!(middle.contains("href=\"1.html\" rel=\"prev\""));
+        assert!(middle.contains("href=\"3.html\" rel=\"next\""));
+        assert!(middle.contains("alt=\"&lt;cat&gt;.png\""));
+        assert!(dir.path().join("style.css").exists());
+    }
+
+    #[test]
+    fn test_without_encrypted() {
+        let mut encrypted = site_item(4, "a.bin", &[]).item;
+        encrypted.url = "https://img.example.com/a.bin#k=secret&n=a.png&t=image%2Fpng".to_string();
+        let items = vec![site_item(1, "beach.png", &[]).item, encrypted];
+        let (items, skipped) = without_encrypted(items);
+        assert_eq!(skipped, 1);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, 1);
+    }
+}
@@ -0,0 +1,176 @@
+/*
+图库删除的撤销窗口：
+1) `stage_gallery_delete(ids, remote)` 把条目移入回收站（`gallery_trash`），图库中立即不可见，
+   彻底删除推迟到设置的撤销时长（`deleteUndoSeconds`，默认 15 秒）之后；
+2) 撤销时长内调用 `undo_delete(id)` 取消定时任务并恢复条目，标签、文字识别、链接历史等附属记录保持不变；
+3) 到期后先按已保存的图床配置删除远程文件（`remote` 为 true 且有删除标记时），
+   再清除回收站与附属记录，完成后发送 `gallery-trash://purged` 事件（含远程删除结果）；
+4) 撤销时长为 0 时立即执行；撤销窗口内退出应用时，下次启动后补完回收站中的删除。
+*/
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+
+use crate::gallery::GalleryItem;
+
+pub(crate) const TRASH_PURGED_EVENT: &str = "gallery-trash://purged";
+
+/// 等待到期的删除任务
+static PENDING: std::sync::Mutex<BTreeMap<i64, JoinHandle<()>>> =
+    std::sync::Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StagedDelete {
+    /// 已移入回收站的条目
+    pub ids: Vec<i64>,
+    /// 可撤销的时长（秒）
+    pub undo_seconds: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashPurgedEvent {
+    pub id: i64,
+    pub file_name: String,
+    pub host: String,
+    /// 是否尝试了远程删除
+    pub remote: bool,
+    /// 远程删除或清理失败时的原因
+    pub error: Option<String>,
+}
+
+/// 删除远程文件并清除回收站记录；远程删除失败时仍清除本地记录（与直接删除时一致）
+async fn purge(app: &AppHandle, item: GalleryItem, remote: bool) {
+    let delete_marker = item
+        .delete_marker
+        .clone()
+        .filter(|marker| !marker.is_empty());
+    let remote = remote && delete_marker.is_some();
+    let mut error = None;
+    if let Some(marker) = delete_marker.filter(|_| remote) {
+        match crate::host_upload::delete_from_host(app, &item.host, &marker).await {
+            Ok(result) if result.success => {}
+            Ok(result) => {
+                error = Some(result.message.unwrap_or_else(|| "远程删除失败".to_string()));
+            }
+            Err(err) => error = Some(err),
+        }
+    }
+    let purged = crate::gallery::store_from_app(app)
+        .and_then(|store| store.purge(item.id).map_err(|err| err.to_string()));
+    if let Err(err) = purged {
+        error.get_or_insert(err);
+    }
+    match &error {
+        Some(err) => warn!("purge gallery item {} failed: {}", item.id, err),
+        None => info!("purged gallery item {} (remote={})", item.id, remote),
+    }
+    let event = TrashPurgedEvent {
+        id: item.id,
+        file_name: item.file_name,
+        host: item.host,
+        remote,
+        error,
+    };
+    if let Err(err) = app.emit(TRASH_PURGED_EVENT, &event) {
+        warn!("emit trash purged event failed: {}", err);
+    }
+}
+
+fn schedule(app: &AppHandle, item: GalleryItem, remote: bool, delay: Duration) {
+    let id = item.id;
+    let mut pending = PENDING.lock().unwrap_or_else(|err| err.into_inner());
+    let app = app.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        // 撤销与到期同时发生时以先取得任务的一方为准
+        let owned = PENDING
+            .lock()
+            .map(|mut pending| pending.remove(&id).is_some())
+            .unwrap_or(false);
+        if owned {
+            purge(&app, item, remote).await;
+        }
+    });
+    if let Some(previous) = pending.insert(id, handle) {
+        previous.abort();
+    }
+}
+
+/// 把图库条目移入回收站，撤销时长结束后再彻底删除（含远程文件）
+#[tauri::command]
+pub fn stage_gallery_delete(
+    app: AppHandle,
+    ids: Vec<i64>,
+    remote: bool,
+) -> Result<StagedDelete, String> {
+    let store = crate::gallery::store_from_app(&app)?;
+    let undo_seconds = crate::settings::current_settings(&app).delete_undo_seconds;
+    let delay = Duration::from_secs(u64::from(undo_seconds));
+    let mut staged = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(item) = store.trash(id, remote).map_err(|err| err.to_string())? {
+            schedule(&app, item, remote, delay);
+            staged.push(id);
+        }
+    }
+    info!(
+        "staged gallery delete: items={}, remote={}, undo={}s",
+        staged.len(),
+        remote,
+        undo_seconds
+    );
+    Ok(StagedDelete {
+        ids: staged,
+        undo_seconds,
+    })
+}
+
+/// 撤销尚未到期的删除，返回恢复后的条目
+#[tauri::command]
+pub fn undo_delete(app: AppHandle, id: i64) -> Result<GalleryItem, String> {
+    let handle = PENDING
+        .lock()
+        .map_err(|_| "删除任务状态异常".to_string())?
+        .remove(&id);
+    let Some(handle) = handle else {
+        return Err("删除已完成，无法撤销".to_string());
+    };
+    handle.abort();
+    let store = crate::gallery::store_from_app(&app)?;
+    let item = store
+        .restore(id)
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "回收站中找不到该条目".to_string())?;
+    info!("undo gallery delete: id={}", id);
+    Ok(item)
+}
+
+/// 启动时补完上次退出前未到期的删除
+pub(crate) fn init(app: &AppHandle) {
+    let trashed = match crate::gallery::store_from_app(app)
+        .and_then(|store| store.trashed().map_err(|err| err.to_string()))
+    {
+        Ok(trashed) => trashed,
+        Err(err) => {
+            warn!("load gallery trash failed: {}", err);
+            return;
+        }
+    };
+    if trashed.is_empty() {
+        return;
+    }
+    info!("finishing {} staged gallery deletes", trashed.len());
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        for entry in trashed {
+            purge(&app, entry.item, entry.remote_delete).await;
+        }
+    });
+}
@@ -0,0 +1,367 @@
+/*
+内置 Google Cloud Storage 图床：
+1) 服务账号模式：用服务账号 JSON 中的私钥签发 RS256 JWT 换取访问令牌（进程内缓存至过期前一分钟），
+   通过 JSON API 的 media 上传与对象删除接口操作；
+2) HMAC 模式：GCS 的 XML API 兼容 S3，使用互操作密钥经 rusty_s3 预签名后上传 / 删除；
+3) 返回链接：默认 `https://storage.googleapis.com/{bucket}/{path}`，也可自定义访问根地址或模板；
+   可选在上传时设置公开读 ACL（启用统一存储桶级访问权限的存储桶请保持关闭）；
+4) deleteId 中记录认证方式、存储桶与对象路径，删除时凭据从已保存的配置中读取。
+*/
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use chrono::Utc;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use log::{error, info};
+use reqwest::StatusCode;
+use rusty_s3::{Bucket, Credentials, S3Action, UrlStyle};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tauri::AppHandle;
+
+use crate::internal_hosts::{
+    HostDeleteResult, HostHttpConfig, HostUploadResult, build_public_url, decode_delete_marker,
+    encode_delete_marker, join_remote_path, load_host_settings, read_upload_file,
+    render_path_template,
+};
+use crate::upload::run_cancellable;
+
+const PLUGIN_ID: &str = "gcs";
+const STORAGE_BASE: &str = "https://storage.googleapis.com";
+const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+const STORAGE_SCOPE: &str = "https://www.googleapis.com/auth/devstorage.read_write";
+const DEFAULT_TIMEOUT_MS: u64 = 60_000;
+const PRESIGN_EXPIRY: Duration = Duration::from_secs(600);
+
+/// 服务账号访问令牌缓存：client_email -> (令牌, 过期时间戳)
+static TOKEN_CACHE: OnceLock<Mutex<HashMap<String, (String, i64)>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GcsAuthMode {
+    #[default]
+    ServiceAccount,
+    Hmac,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GcsConfig {
+    #[serde(default)]
+    pub auth: GcsAuthMode,
+    /// 服务账号 JSON 密钥文件的完整内容
+    #[serde(default)]
+    pub service_account_json: Option<String>,
+    #[serde(default)]
+    pub hmac_access_id: Option<String>,
+    #[serde(default)]
+    pub hmac_secret: Option<String>,
+    pub bucket: String,
+    #[serde(default)]
+    pub remote_dir: Option<String>,
+    #[serde(default)]
+    pub path_template: Option<String>,
+    /// 上传时设置公开读 ACL
+    #[serde(default)]
+    pub make_public: bool,
+    /// 自定义访问根地址或包含 `{path}` 的模板
+    #[serde(default)]
+    pub public_url: Option<String>,
+    #[serde(flatten)]
+    pub http: HostHttpConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GcsDeleteMarker {
+    auth: GcsAuthMode,
+    bucket: String,
+    path: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServiceAccountKey {
+    client_email: String,
+    private_key: String,
+    #[serde(default)]
+    token_uri: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct JwtClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: i64,
+    exp: i64,
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// 提取 Google API 错误响应中的 `error.message`（XML API 返回的错误原样保留）
+async fn error_message(response: reqwest::Response) -> String {
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<Value>(&text)
+        .ok()
+        .and_then(|v| {
+            v.pointer("/error/message")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })
+        .unwrap_or(text);
+    format!("HTTP {} {}", status, message)
+}
+
+impl GcsConfig {
+    fn bucket(&self) -> &str {
+        self.bucket.trim()
+    }
+
+    fn client(&self) -> Result<reqwest::Client, String> {
+        self.http.client(DEFAULT_TIMEOUT_MS)
+    }
+
+    fn service_account(&self) -> Result<ServiceAccountKey, String> {
+        let raw = non_empty(self.service_account_json.as_deref())
+            .ok_or_else(|| "请填写服务账号 JSON".to_string())?;
+        serde_json::from_str(raw).map_err(|e| format!("服务账号 JSON 格式错误: {e}"))
+    }
+
+    /// 获取服务账号访问令牌，优先使用未过期的缓存
+    async fn access_token(&self, client: &reqwest::Client) -> Result<String, String> {
+        let key = self.service_account()?;
+        let now = Utc::now().timestamp();
+        let cache = TOKEN_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+        let cached = cache
+            .lock()
+            .map_err(|e| format!("gcs token cache poisoned: {e}"))?
+            .get(&key.client_email)
+            .filter(|(_, expires_at)| *expires_at - 60 > now)
+            .map(|(token, _)| token.clone());
+        if let Some(token) = cached {
+            return Ok(token);
+        }
+
+        let token_uri = key.token_uri.as_deref().unwrap_or(DEFAULT_TOKEN_URI);
+        let claims = JwtClaims {
+            iss: &key.client_email,
+            scope: STORAGE_SCOPE,
+            aud: token_uri,
+            iat: now,
+            exp: now + 3600,
+        };
+        let encoding_key = EncodingKey::from_rsa_pem(key.private_key.as_bytes())
+            .map_err(|e| format!("服务账号私钥无效: {e}"))?;
+        let assertion =
+            jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &encoding_key)
+                .map_err(|e| format!("签发 JWT 失败: {e}"))?;
+
+        let response = self
+            .http
+            .send("GCS token", || {
+                Ok(client.post(token_uri).form(&[
+                    ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                    ("assertion", assertion.as_str()),
+                ]))
+            })
+            .await
+            .map_err(|e| format!("获取 GCS 访问令牌失败: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "获取 GCS 访问令牌失败: {}",
+                error_message(response).await
+            ));
+        }
+        let payload: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("解析令牌响应失败: {}", e))?;
+        let token = payload
+            .get("access_token")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "令牌响应缺少 access_token".to_string())?
+            .to_string();
+        let expires_in = payload
+            .get("expires_in")
+            .and_then(Value::as_i64)
+            .unwrap_or(3600);
+        cache
+            .lock()
+            .map_err(|e| format!("gcs token cache poisoned: {e}"))?
+            .insert(key.client_email, (token.clone(), now + expires_in));
+        Ok(token)
+    }
+
+    fn hmac_bucket(&self, bucket: &str) -> Result<(Bucket, Credentials), String> {
+        let access_id = non_empty(self.hmac_access_id.as_deref())
+            .ok_or_else(|| "请填写 HMAC Access ID".to_string())?;
+        let secret = non_empty(self.hmac_secret.as_deref())
+            .ok_or_else(|| "请填写 HMAC Secret".to_string())?;
+        let endpoint =
+            url::Url::parse(STORAGE_BASE).map_err(|e| format!("invalid endpoint URL: {}", e))?;
+        let bucket = Bucket::new(endpoint, UrlStyle::Path, bucket.to_string(), "auto")
+            .map_err(|e| format!("failed to create bucket: {}", e))?;
+        Ok((bucket, Credentials::new(access_id, secret)))
+    }
+
+    fn file_url(&self, bucket: &str, path: &str) -> String {
+        match non_empty(self.public_url.as_deref()) {
+            Some(base) => build_public_url(base, path),
+            None => format!("{}/{}/{}", STORAGE_BASE, bucket, path),
+        }
+    }
+}
+
+fn object_url(bucket: &str, path: &str) -> Result<reqwest::Url, String> {
+    let mut url = reqwest::Url::parse(STORAGE_BASE).map_err(|e| e.to_string())?;
+    // 对象名作为单个路径段，`/` 需编码为 %2F
+    url.path_segments_mut()
+        .map_err(|_| "invalid GCS api url".to_string())?
+        .extend(["storage", "v1", "b", bucket, "o", path]);
+    Ok(url)
+}
+
+#[tauri::command]
+pub async fn gcs_upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: GcsConfig,
+    upload_id: Option<String>,
+) -> Result<HostUploadResult, String> {
+    run_cancellable(upload_id, upload(file_path, original_file_name, config)).await
+}
+
+async fn upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: GcsConfig,
+) -> Result<HostUploadResult, String> {
+    let (file_name, bytes) = read_upload_file(&file_path, original_file_name).await?;
+    let path = join_remote_path(
+        config.remote_dir.as_deref(),
+        &render_path_template(config.path_template.as_deref(), &file_name),
+    );
+    let bucket = config.bucket().to_string();
+    let content_type = mime_guess::from_path(&file_name)
+        .first_or_octet_stream()
+        .to_string();
+    let size = bytes.len();
+    let client = config.client()?;
+
+    // (方法, 地址, 访问令牌)：签名地址与令牌在重试之间保持不变
+    let (method, url, token) = match config.auth {
+        GcsAuthMode::ServiceAccount => {
+            let token = config.access_token(&client).await?;
+            let mut url = reqwest::Url::parse(&format!(
+                "{}/upload/storage/v1/b/{}/o",
+                STORAGE_BASE, bucket
+            ))
+            .map_err(|e| format!("invalid GCS upload url: {e}"))?;
+            url.query_pairs_mut()
+                .append_pair("uploadType", "media")
+                .append_pair("name", &path);
+            if config.make_public {
+                url.query_pairs_mut()
+                    .append_pair("predefinedAcl", "publicRead");
+            }
+            (reqwest::Method::POST, url, Some(token))
+        }
+        GcsAuthMode::Hmac => {
+            let (bucket_obj, credentials) = config.hmac_bucket(&bucket)?;
+            let url = bucket_obj
+                .put_object(Some(&credentials), &path)
+                .sign(PRESIGN_EXPIRY);
+            (reqwest::Method::PUT, url, None)
+        }
+    };
+    let throttle = config.http.throttle(url.as_str());
+    let response = config
+        .http
+        .send("GCS upload", || {
+            let mut request = client
+                .request(method.clone(), url.clone())
+                .header(reqwest::header::CONTENT_TYPE, &content_type)
+                .header(reqwest::header::CONTENT_LENGTH, size)
+                .body(throttle.body(bytes.clone()));
+            match &token {
+                Some(token) => request = request.bearer_auth(token),
+                None if config.make_public => request = request.header("x-goog-acl", "public-read"),
+                None => {}
+            }
+            Ok(request)
+        })
+        .await
+        .map_err(|e| format!("GCS 上传失败: {}", e))?;
+    if !response.status().is_success() {
+        let message = error_message(response).await;
+        error!("gcs_upload failed: path={}, error={}", path, message);
+        return Err(format!("GCS 上传失败: {}", message));
+    }
+
+    let delete_id = encode_delete_marker(&GcsDeleteMarker {
+        auth: config.auth,
+        bucket: bucket.clone(),
+        path: path.clone(),
+    })?;
+    info!(
+        "gcs_upload success: auth={:?}, bucket={}, path={}, size={}",
+        config.auth, bucket, path, size
+    );
+    Ok(HostUploadResult {
+        url: config.file_url(&bucket, &path),
+        delete_id,
+        metadata: Some(json!({ "bucket": bucket, "path": path, "size": size })),
+    })
+}
+
+#[tauri::command]
+pub async fn gcs_delete(app: AppHandle, delete_id: String) -> Result<HostDeleteResult, String> {
+    let marker: GcsDeleteMarker = decode_delete_marker(&delete_id)?;
+    let config: GcsConfig = load_host_settings(&app, PLUGIN_ID)?;
+    let client = config.client()?;
+
+    // 以上传时的认证方式删除，即使之后切换了设置中的模式
+    let (url, token) = match marker.auth {
+        GcsAuthMode::ServiceAccount => (
+            object_url(&marker.bucket, &marker.path)?,
+            Some(config.access_token(&client).await?),
+        ),
+        GcsAuthMode::Hmac => {
+            let (bucket_obj, credentials) = config.hmac_bucket(&marker.bucket)?;
+            let url = bucket_obj
+                .delete_object(Some(&credentials), &marker.path)
+                .sign(PRESIGN_EXPIRY);
+            (url, None)
+        }
+    };
+    let response = config
+        .http
+        .send("GCS delete", || {
+            let request = client.delete(url.clone());
+            Ok(match &token {
+                Some(token) => request.bearer_auth(token),
+                None => request,
+            })
+        })
+        .await
+        .map_err(|e| format!("GCS 删除失败: {}", e))?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(HostDeleteResult::deleted("对象已不存在"));
+    }
+    if !response.status().is_success() {
+        return Err(format!("GCS 删除失败: {}", error_message(response).await));
+    }
+
+    info!(
+        "gcs_delete success: bucket={}, path={}",
+        marker.bucket, marker.path
+    );
+    Ok(HostDeleteResult::deleted("对象已从 GCS 删除"))
+}
@@ -0,0 +1,271 @@
+/*
+内置 GitHub 仓库图床：
+1) 通过 Contents API（PUT /repos/{owner}/{repo}/contents/{path}）将图片提交到指定分支；
+2) 返回 raw.githubusercontent.com 或 jsDelivr 地址，也可自定义访问根地址；
+3) deleteId 中记录仓库、分支、路径与提交返回的 blob SHA，删除时以该 SHA 调用 DELETE 接口，
+   令牌从已保存的配置中读取。
+*/
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use bytes::Bytes;
+use log::{error, info};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tauri::AppHandle;
+
+use crate::internal_hosts::{
+    HostDeleteResult, HostHttpConfig, HostUploadResult, build_public_url, decode_delete_marker,
+    encode_delete_marker, join_remote_path, load_host_settings, read_upload_file,
+    render_path_template,
+};
+use crate::upload::run_cancellable;
+
+const PLUGIN_ID: &str = "github";
+const API_BASE: &str = "https://api.github.com";
+const DEFAULT_BRANCH: &str = "main";
+const DEFAULT_TIMEOUT_MS: u64 = 60_000;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GitHubCdn {
+    #[default]
+    Raw,
+    Jsdelivr,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHubConfig {
+    /// 具有 contents 写权限的 Personal Access Token
+    pub token: String,
+    pub owner: String,
+    pub repo: String,
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// 仓库内的存放目录
+    #[serde(default)]
+    pub remote_dir: Option<String>,
+    #[serde(default)]
+    pub path_template: Option<String>,
+    #[serde(default)]
+    pub cdn: GitHubCdn,
+    /// 自定义访问根地址（如 GitHub Pages 域名），设置后忽略 cdn
+    #[serde(default)]
+    pub public_url: Option<String>,
+    #[serde(default)]
+    pub commit_message: Option<String>,
+    #[serde(flatten)]
+    pub http: HostHttpConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GitHubDeleteMarker {
+    owner: String,
+    repo: String,
+    branch: String,
+    path: String,
+    sha: String,
+}
+
+fn contents_url(owner: &str, repo: &str, path: &str) -> Result<reqwest::Url, String> {
+    let mut url = reqwest::Url::parse(API_BASE).map_err(|e| e.to_string())?;
+    url.path_segments_mut()
+        .map_err(|_| "invalid GitHub api url".to_string())?
+        .extend(["repos", owner, repo, "contents"])
+        .extend(path.split('/'));
+    Ok(url)
+}
+
+fn github_client(http: &HostHttpConfig) -> Result<reqwest::Client, String> {
+    http.client_builder(DEFAULT_TIMEOUT_MS)?
+        .user_agent(concat!("yana/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("failed to build http client: {}", e))
+}
+
+/// 发送 Contents API 请求；请求体按图床限速发送，失败时按重试策略重试
+async fn send_contents_request(
+    client: &reqwest::Client,
+    http: &HostHttpConfig,
+    method: Method,
+    url: reqwest::Url,
+    token: &str,
+    body: &Value,
+) -> Result<reqwest::Response, String> {
+    let payload = Bytes::from(
+        serde_json::to_vec(body).map_err(|e| format!("serialize GitHub request: {}", e))?,
+    );
+    let throttle = http.throttle(url.as_str());
+    http.send("GitHub request", || {
+        Ok(client
+            .request(method.clone(), url.clone())
+            .bearer_auth(token.trim())
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(reqwest::header::CONTENT_LENGTH, payload.len())
+            .header("X-GitHub-Api-Version", "2022-11-28")
+            .body(throttle.body(payload.clone())))
+    })
+    .await
+    .map_err(|e| format!("GitHub 请求失败: {}", e))
+}
+
+/// 提取 GitHub 错误响应中的 message 字段
+async fn error_message(response: reqwest::Response) -> String {
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<Value>(&text)
+        .ok()
+        .and_then(|v| v.get("message").and_then(Value::as_str).map(str::to_string))
+        .unwrap_or(text);
+    format!("HTTP {} {}", status, message)
+}
+
+impl GitHubConfig {
+    fn branch(&self) -> String {
+        self.branch
+            .as_deref()
+            .map(str::trim)
+            .filter(|b| !b.is_empty())
+            .unwrap_or(DEFAULT_BRANCH)
+            .to_string()
+    }
+
+    fn file_url(&self, branch: &str, path: &str) -> String {
+        if let Some(base) = self.public_url.as_deref().filter(|u| !u.trim().is_empty()) {
+            return build_public_url(base.trim(), path);
+        }
+        let (owner, repo) = (self.owner.trim(), self.repo.trim());
+        match self.cdn {
+            GitHubCdn::Raw => format!(
+                "https://raw.githubusercontent.com/{}/{}/{}/{}",
+                owner, repo, branch, path
+            ),
+            GitHubCdn::Jsdelivr => format!(
+                "https://cdn.jsdelivr.net/gh/{}/{}@{}/{}",
+                owner, repo, branch, path
+            ),
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn github_upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: GitHubConfig,
+    upload_id: Option<String>,
+) -> Result<HostUploadResult, String> {
+    run_cancellable(upload_id, upload(file_path, original_file_name, config)).await
+}
+
+async fn upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: GitHubConfig,
+) -> Result<HostUploadResult, String> {
+    let (file_name, bytes) = read_upload_file(&file_path, original_file_name).await?;
+    let path = join_remote_path(
+        config.remote_dir.as_deref(),
+        &render_path_template(config.path_template.as_deref(), &file_name),
+    );
+    let branch = config.branch();
+    let message = config
+        .commit_message
+        .as_deref()
+        .filter(|m| !m.trim().is_empty())
+        .map(|m| m.replace("{filename}", &file_name))
+        .unwrap_or_else(|| format!("Upload {}", file_name));
+
+    let client = github_client(&config.http)?;
+    let url = contents_url(config.owner.trim(), config.repo.trim(), &path)?;
+    let body = json!({
+        "message": message,
+        "content": BASE64.encode(&bytes),
+        "branch": branch,
+    });
+    let response = send_contents_request(
+        &client,
+        &config.http,
+        Method::PUT,
+        url,
+        &config.token,
+        &body,
+    )
+    .await?;
+    if !response.status().is_success() {
+        let message = error_message(response).await;
+        error!("github_upload failed: path={}, error={}", path, message);
+        return Err(format!("GitHub 上传失败: {}", message));
+    }
+    let payload: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("解析 GitHub 响应失败: {}", e))?;
+    let sha = payload
+        .pointer("/content/sha")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "GitHub 响应缺少 content.sha".to_string())?
+        .to_string();
+
+    let delete_id = encode_delete_marker(&GitHubDeleteMarker {
+        owner: config.owner.trim().to_string(),
+        repo: config.repo.trim().to_string(),
+        branch: branch.clone(),
+        path: path.clone(),
+        sha: sha.clone(),
+    })?;
+    info!(
+        "github_upload success: repo={}/{}, branch={}, path={}",
+        config.owner, config.repo, branch, path
+    );
+    Ok(HostUploadResult {
+        url: config.file_url(&branch, &path),
+        delete_id,
+        metadata: Some(json!({
+            "path": path,
+            "sha": sha,
+            "htmlUrl": payload.pointer("/content/html_url"),
+        })),
+    })
+}
+
+#[tauri::command]
+pub async fn github_delete(app: AppHandle, delete_id: String) -> Result<HostDeleteResult, String> {
+    let marker: GitHubDeleteMarker = decode_delete_marker(&delete_id)?;
+    let config: GitHubConfig = load_host_settings(&app, PLUGIN_ID)?;
+
+    let client = github_client(&config.http)?;
+    let url = contents_url(&marker.owner, &marker.repo, &marker.path)?;
+    let body = json!({
+        "message": format!("Delete {}", marker.path),
+        "sha": marker.sha,
+        "branch": marker.branch,
+    });
+    let response = send_contents_request(
+        &client,
+        &config.http,
+        Method::DELETE,
+        url,
+        &config.token,
+        &body,
+    )
+    .await?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(HostDeleteResult::deleted("文件已不存在"));
+    }
+    if !response.status().is_success() {
+        return Err(format!(
+            "GitHub 删除失败: {}",
+            error_message(response).await
+        ));
+    }
+
+    info!(
+        "github_delete success: repo={}/{}, path={}",
+        marker.owner, marker.repo, marker.path
+    );
+    Ok(HostDeleteResult::deleted("文件已从 GitHub 仓库删除"))
+}
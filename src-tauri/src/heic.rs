@@ -0,0 +1,306 @@
+/*
+HEIC / HEIF 输入（libheif，仅桌面端）：
+1) 按 ISOBMFF 的 ftyp 品牌识别 HEIC（heic、heix、hevc、msf1 等 HEVC 品牌），AVIF 不在此列；
+2) 含多张顶层图片的文件（连拍、图像序列）按设置 `heicSequenceMode` 处理：
+   - still（默认）：只取主图（关键帧），与普通 HEIC 相同；
+   - webp / gif：依次取全部顶层图片转为动画 WebP / GIF，每帧 `FRAME_DELAY_MS`，尺寸不一致的帧缩放到主图尺寸；
+   以轨道（moov）保存的序列 libheif 暂无法逐帧读取：选择转为动画时返回错误（`TRACK_UNSUPPORTED`），
+   不会静默输出静态图，只取主图模式不受影响；
+3) 静态结果交给 process 按目标格式编码（原格式模式下输出 JPEG，图床与浏览器普遍不支持 HEIC）；
+4) 移动端未链接 libheif，解码返回错误，压缩回退为上传原图。
+*/
+
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::imageops::FilterType;
+use image::{Delay, DynamicImage, Frame, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+/// 动画中每帧的显示时长
+const FRAME_DELAY_MS: u32 = 100;
+
+/// 以轨道保存的图像序列无法转为动画时的错误
+const TRACK_UNSUPPORTED: &str =
+    "该 HEIC 的图像序列以轨道（moov）保存，暂不支持转为动画；请将「HEIC 图像序列」设为只取主图";
+
+/// HEVC 编码的 HEIF 品牌（含图像序列品牌 msf1 / hevs）
+const HEIC_BRANDS: &[&[u8; 4]] = &[
+    b"heic", b"heix", b"heim", b"heis", b"hevc", b"hevx", b"hevm", b"hevs", b"msf1",
+];
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HeicSequenceMode {
+    /// 只取主图
+    #[default]
+    Still,
+    /// 转为动画 WebP
+    Webp,
+    /// 转为 GIF
+    Gif,
+}
+
+/// 解码结果
+pub(crate) enum HeicImage {
+    Still(DynamicImage),
+    /// 动画（已编码）与扩展名
+    Animated(Vec<u8>, &'static str),
+}
+
+/// ftyp 中的主品牌与兼容品牌
+fn brands(bytes: &[u8]) -> Vec<&[u8]> {
+    if bytes.len() < 16 || &bytes[4..8] != b"ftyp" {
+        return Vec::new();
+    }
+    let size = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+    let end = size.clamp(16, bytes.len());
+    // 主品牌后是 4 字节的次版本号，其后为兼容品牌
+    std::iter::once(&bytes[8..12])
+        .chain(bytes[16..end].chunks_exact(4))
+        .collect()
+}
+
+/// 顶层是否有 `moov` 盒（以轨道保存的图像序列）
+fn has_track(bytes: &[u8]) -> bool {
+    let mut offset = 0;
+    while let Some(header) = bytes.get(offset..offset + 8) {
+        if &header[4..8] == b"moov" {
+            return true;
+        }
+        let size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let size = match size {
+            // 大小为 0 表示延续到文件末尾
+            0 => return false,
+            // 64 位大小紧跟在类型之后
+            1 => match bytes.get(offset + 8..offset + 16) {
+                Some(large) => u64::from_be_bytes(large.try_into().unwrap_or_default()),
+                None => return false,
+            },
+            size => size,
+        };
+        if size < 8 {
+            return false;
+        }
+        offset = match usize::try_from(size)
+            .ok()
+            .and_then(|size| offset.checked_add(size))
+        {
+            Some(next) => next,
+            None => return false,
+        };
+    }
+    false
+}
+
+pub(crate) fn is_heic(bytes: &[u8]) -> bool {
+    brands(bytes)
+        .iter()
+        .any(|brand| HEIC_BRANDS.iter().any(|heic| *brand == heic.as_slice()))
+}
+
+/// 多帧转为 GIF
+fn encode_gif(frames: &[RgbaImage]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    {
+        let mut encoder = GifEncoder::new(&mut out);
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .map_err(|e| format!("gif repeat: {e}"))?;
+        encoder
+            .encode_frames(frames.iter().map(|frame| {
+                Frame::from_parts(
+                    frame.clone(),
+                    0,
+                    0,
+                    Delay::from_numer_denom_ms(FRAME_DELAY_MS, 1),
+                )
+            }))
+            .map_err(|e| format!("gif encode: {e}"))?;
+    }
+    Ok(out)
+}
+
+/// 多帧转为动画 WebP
+fn encode_webp(frames: &[RgbaImage], quality: u8) -> Result<Vec<u8>, String> {
+    let first = frames.first().ok_or("没有可编码的帧")?;
+    let mut config = webp::WebPConfig::new().map_err(|_| "webp config".to_string())?;
+    config.quality = quality as f32;
+    let mut encoder = webp::AnimEncoder::new(first.width(), first.height(), &config);
+    for (index, frame) in frames.iter().enumerate() {
+        encoder.add_frame(webp::AnimFrame::from_rgba(
+            frame.as_raw(),
+            frame.width(),
+            frame.height(),
+            (index as u32 * FRAME_DELAY_MS) as i32,
+        ));
+    }
+    Ok(encoder.encode().to_vec())
+}
+
+/// 各帧统一为第一帧的尺寸
+fn uniform_frames(frames: Vec<RgbaImage>) -> Vec<RgbaImage> {
+    let Some((width, height)) = frames.first().map(RgbaImage::dimensions) else {
+        return frames;
+    };
+    frames
+        .into_iter()
+        .map(|frame| {
+            if frame.dimensions() == (width, height) {
+                frame
+            } else {
+                crate::fast_resize::resize(
+                    &DynamicImage::ImageRgba8(frame),
+                    width,
+                    height,
+                    FilterType::Lanczos3,
+                )
+                .into_rgba8()
+            }
+        })
+        .collect()
+}
+
+#[cfg(desktop)]
+mod decoder {
+    use image::RgbaImage;
+    use libheif_rs::{ColorSpace, HeifContext, ImageHandle, ItemId, LibHeif, RgbChroma};
+
+    fn decode_handle(lib: &LibHeif, handle: &ImageHandle) -> Result<RgbaImage, String> {
+        let image = lib
+            .decode(handle, ColorSpace::Rgb(RgbChroma::Rgba), None)
+            .map_err(|e| format!("heic decode: {e}"))?;
+        let planes = image.planes();
+        let plane = planes.interleaved.ok_or("heic decode: 缺少像素数据")?;
+        let (width, height) = (plane.width, plane.height);
+        let row = width as usize * 4;
+        let mut pixels = Vec::with_capacity(row * height as usize);
+        for line in plane.data.chunks(plane.stride).take(height as usize) {
+            pixels.extend_from_slice(&line[..row]);
+        }
+        RgbaImage::from_raw(width, height, pixels).ok_or_else(|| "heic decode: 尺寸不符".into())
+    }
+
+    /// 主图与（多于一张时的）全部顶层图片
+    pub(super) fn decode(bytes: &[u8], all_frames: bool) -> Result<Vec<RgbaImage>, String> {
+        let lib = LibHeif::new();
+        let context = HeifContext::read_from_bytes(bytes).map_err(|e| format!("heic read: {e}"))?;
+        let primary = context
+            .primary_image_handle()
+            .map_err(|e| format!("heic primary image: {e}"))?;
+        let count = context.number_of_top_level_images();
+        if !all_frames || count < 2 {
+            return Ok(vec![decode_handle(&lib, &primary)?]);
+        }
+        let mut ids: Vec<ItemId> = vec![0; count];
+        let count = context.top_level_image_ids(&mut ids);
+        ids[..count]
+            .iter()
+            .map(|id| {
+                let handle = context
+                    .image_handle(*id)
+                    .map_err(|e| format!("heic image {id}: {e}"))?;
+                decode_handle(&lib, &handle)
+            })
+            .collect()
+    }
+}
+
+#[cfg(mobile)]
+mod decoder {
+    use image::RgbaImage;
+
+    pub(super) fn decode(_bytes: &[u8], _all_frames: bool) -> Result<Vec<RgbaImage>, String> {
+        Err("当前平台不支持 HEIC 解码".to_string())
+    }
+}
+
+/// 解码 HEIC：图像序列按 `mode` 取主图或转为动画
+pub(crate) fn decode(
+    bytes: &[u8],
+    mode: HeicSequenceMode,
+    quality: u8,
+) -> Result<HeicImage, String> {
+    let all_frames = mode != HeicSequenceMode::Still;
+    let mut frames = decoder::decode(bytes, all_frames)?;
+    if frames.len() < 2 {
+        if all_frames && has_track(bytes) {
+            return Err(TRACK_UNSUPPORTED.to_string());
+        }
+        let still = frames.pop().ok_or("heic decode: 没有图片")?;
+        return Ok(HeicImage::Still(DynamicImage::ImageRgba8(still)));
+    }
+    let frames = uniform_frames(frames);
+    match mode {
+        HeicSequenceMode::Gif => Ok(HeicImage::Animated(encode_gif(&frames)?, ".gif")),
+        _ => Ok(HeicImage::Animated(encode_webp(&frames, quality)?, ".webp")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ftyp(major: &[u8; 4], compatible: &[&[u8; 4]]) -> Vec<u8> {
+        let size = 16 + compatible.len() * 4;
+        let mut bytes = (size as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(b"ftyp");
+        bytes.extend_from_slice(major);
+        bytes.extend_from_slice(&[0, 0, 0, 0]);
+        for brand in compatible {
+            bytes.extend_from_slice(*brand);
+        }
+        bytes.extend_from_slice(b"\0\0\0\x08meta");
+        bytes
+    }
+
+    #[test]
+    fn test_is_heic() {
+        assert!(is_heic(&ftyp(b"heic", &[b"mif1", b"heic"])));
+        assert!(is_heic(&ftyp(b"msf1", &[b"msf1", b"hevc"])));
+        assert!(is_heic(&ftyp(b"mif1", &[b"mif1", b"heix"])));
+        assert!(!is_heic(&ftyp(b"avif", &[b"mif1", b"avif"])));
+        assert!(!is_heic(b"\x89PNG\r\n\x1a\n0000000000"));
+    }
+
+    fn boxed(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut bytes = ((8 + payload.len()) as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(kind);
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    #[test]
+    fn test_has_track() {
+        // 轨道序列：ftyp + meta（主图）+ moov（trak）+ mdat
+        let mut sequence = ftyp(b"msf1", &[b"msf1", b"hevc"]);
+        sequence.extend(boxed(b"moov", &boxed(b"trak", &[0; 16])));
+        sequence.extend(boxed(b"mdat", &[0; 32]));
+        assert!(is_heic(&sequence));
+        assert!(has_track(&sequence));
+
+        // 64 位大小的 mdat 之后的 moov
+        let mut large = ftyp(b"msf1", &[b"msf1"]);
+        large.extend_from_slice(&1u32.to_be_bytes());
+        large.extend_from_slice(b"mdat");
+        large.extend_from_slice(&24u64.to_be_bytes());
+        large.extend_from_slice(&[0; 8]);
+        large.extend(boxed(b"moov", &[]));
+        assert!(has_track(&large));
+
+        // 静态图与损坏的盒大小
+        let mut still = ftyp(b"heic", &[b"mif1", b"heic"]);
+        still.extend(boxed(b"mdat", &[0; 32]));
+        assert!(!has_track(&still));
+        assert!(!has_track(b"\0\0\0\x04free\0\0\0\x08moov"));
+    }
+
+    #[test]
+    fn test_encode_gif() {
+        let frames = uniform_frames(vec![
+            RgbaImage::from_pixel(4, 4, image::Rgba([255, 0, 0, 255])),
+            RgbaImage::from_pixel(8, 8, image::Rgba([0, 0, 255, 255])),
+        ]);
+        assert!(frames.iter().all(|frame| frame.dimensions() == (4, 4)));
+        let gif = encode_gif(&frames).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+    }
+}
@@ -0,0 +1,396 @@
+/*
+图床配置导入 / 导出：
+1) `export_image_host_settings` 将 image-hosts.json、插件启用状态与用户插件脚本打包为一个文件，
+   可选包含钥匙串中的敏感字段；
+2) 打包内容使用口令加密：PBKDF2-HMAC-SHA256 派生密钥，AES-256-GCM 加密，盐与 nonce 随机生成；
+   密钥派生耗时较长，在阻塞线程中执行；导入时拒绝迭代次数超出上限的文件，避免构造的文件长时间占用 CPU；
+3) `import_image_host_settings` 解密后按插件覆盖配置、写回用户插件与敏感字段。
+   未包含敏感字段的导出文件导入后，这些字段会被清空，需要重新填写；
+4) 加密外壳与图床部分的收集 / 写回逻辑供完整应用配置导出（`app_config`）复用，以 `format` 区分文件类型。
+*/
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use aes_gcm::{
+    Aes256Gcm, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng, rand_core::RngCore},
+};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::image_hosts::{
+    SECRET_PLACEHOLDER, ensure_config_path, plugin_secret_id, read_plugin_state, read_settings,
+    user_plugin_dir, validate_plugin_id, write_plugin_state, write_settings,
+};
+use crate::secrets::{read_secret, write_secret};
+
+const BUNDLE_FORMAT: &str = "yana-image-hosts";
+const BUNDLE_VERSION: u32 = 1;
+const PBKDF2_ITERATIONS: u32 = 600_000;
+/// 导入文件允许的最大迭代次数
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+const SALT_LEN: usize = 16;
+
+/// 写入磁盘的加密外壳
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptedBundle {
+    format: String,
+    version: u32,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundledPlugin {
+    file_name: String,
+    source: String,
+}
+
+/// 加密前的打包内容
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BundlePayload {
+    exported_at: String,
+    #[serde(default)]
+    settings: BTreeMap<String, Value>,
+    /// 插件 id → 字段 → 敏感值，仅在导出时选择包含敏感字段才存在
+    #[serde(default)]
+    secrets: BTreeMap<String, BTreeMap<String, String>>,
+    #[serde(default)]
+    disabled: Vec<String>,
+    #[serde(default)]
+    plugins: Vec<BundledPlugin>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub settings: usize,
+    pub plugins: usize,
+    pub secrets: usize,
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    key
+}
+
+fn encrypt_bundle(
+    format: &str,
+    plain: &[u8],
+    passphrase: &str,
+    iterations: u32,
+) -> Result<EncryptedBundle, String> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, iterations);
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| format!("init cipher: {e}"))?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plain)
+        .map_err(|e| format!("加密失败: {e}"))?;
+    Ok(EncryptedBundle {
+        format: format.to_string(),
+        version: BUNDLE_VERSION,
+        iterations,
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn decrypt_bundle(
+    bundle: &EncryptedBundle,
+    format: &str,
+    passphrase: &str,
+) -> Result<Vec<u8>, String> {
+    if bundle.format != format {
+        return Err(format!("导出文件类型不符: {}", bundle.format));
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!("不支持的导出文件版本: {}", bundle.version));
+    }
+    if bundle.iterations == 0 || bundle.iterations > MAX_PBKDF2_ITERATIONS {
+        return Err(format!("导出文件的迭代次数无效: {}", bundle.iterations));
+    }
+    let decode = |field: &str, value: &str| {
+        BASE64
+            .decode(value)
+            .map_err(|e| format!("invalid {} in bundle: {e}", field))
+    };
+    let salt = decode("salt", &bundle.salt)?;
+    let nonce = decode("nonce", &bundle.nonce)?;
+    let ciphertext = decode("ciphertext", &bundle.ciphertext)?;
+    if nonce.len() != 12 {
+        return Err("invalid nonce in bundle".to_string());
+    }
+    let key = derive_key(passphrase, &salt, bundle.iterations);
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| format!("init cipher: {e}"))?;
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| "口令错误或文件已损坏".to_string())
+}
+
+/// 序列化并加密打包内容，返回写入磁盘的文本；`format` 区分不同用途的导出文件
+pub(crate) async fn seal_bundle<T: Serialize>(
+    format: &str,
+    payload: &T,
+    passphrase: &str,
+) -> Result<String, String> {
+    require_passphrase(passphrase)?;
+    let plain = serde_json::to_vec(payload).map_err(|e| format!("serialize export bundle: {e}"))?;
+    let (format, passphrase) = (format.to_string(), passphrase.to_string());
+    let bundle = tauri::async_runtime::spawn_blocking(move || {
+        encrypt_bundle(&format, &plain, &passphrase, PBKDF2_ITERATIONS)
+    })
+    .await
+    .map_err(|e| format!("failed to join encrypt task: {e}"))??;
+    serde_json::to_string_pretty(&bundle).map_err(|e| format!("serialize bundle: {e}"))
+}
+
+/// 解析并解密导出文件内容
+pub(crate) async fn open_bundle<T: DeserializeOwned>(
+    text: &str,
+    format: &str,
+    passphrase: &str,
+) -> Result<T, String> {
+    require_passphrase(passphrase)?;
+    let bundle: EncryptedBundle =
+        serde_json::from_str(text).map_err(|_| "不是有效的导出文件".to_string())?;
+    let (format, passphrase) = (format.to_string(), passphrase.to_string());
+    let plain =
+        tauri::async_runtime::spawn_blocking(move || decrypt_bundle(&bundle, &format, &passphrase))
+            .await
+            .map_err(|e| format!("failed to join decrypt task: {e}"))??;
+    serde_json::from_slice(&plain).map_err(|e| format!("parse export bundle: {e}"))
+}
+
+fn require_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.is_empty() {
+        Err("口令不能为空".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn collect_user_plugins(app: &AppHandle) -> Result<Vec<BundledPlugin>, String> {
+    let dir = user_plugin_dir(app)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(&dir).map_err(|e| format!("read_dir {}: {e}", dir.display()))?;
+    let mut plugins = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_script = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext == "js" || ext == "mjs");
+        let Some(file_name) = path.file_name().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if !is_script || !path.is_file() {
+            continue;
+        }
+        match fs::read_to_string(&path) {
+            Ok(source) => plugins.push(BundledPlugin {
+                file_name: file_name.to_string(),
+                source,
+            }),
+            Err(err) => warn!("read plugin {} failed: {}", path.display(), err),
+        }
+    }
+    plugins.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    Ok(plugins)
+}
+
+fn collect_secrets(plugin_id: &str, values: &Value) -> Result<BTreeMap<String, String>, String> {
+    let mut secrets = BTreeMap::new();
+    let Some(map) = values.as_object() else {
+        return Ok(secrets);
+    };
+    for (key, value) in map {
+        if value.as_str() == Some(SECRET_PLACEHOLDER)
+            && let Some(secret) = read_secret(&plugin_secret_id(plugin_id, key))?
+        {
+            secrets.insert(key.clone(), secret);
+        }
+    }
+    Ok(secrets)
+}
+
+/// 收集图床配置、插件启用状态与用户插件；`include_secrets` 时附带钥匙串中的敏感字段
+pub(crate) fn collect_host_payload(
+    app: &AppHandle,
+    include_secrets: bool,
+) -> Result<BundlePayload, String> {
+    let settings = read_settings(&ensure_config_path(app)?)?;
+    let mut payload = BundlePayload {
+        exported_at: chrono::Local::now().to_rfc3339(),
+        disabled: read_plugin_state(app)?.disabled.into_iter().collect(),
+        plugins: collect_user_plugins(app)?,
+        ..Default::default()
+    };
+    for (plugin_id, values) in settings.plugins {
+        if include_secrets {
+            let secrets = collect_secrets(&plugin_id, &values)?;
+            if !secrets.is_empty() {
+                payload.secrets.insert(plugin_id.clone(), secrets);
+            }
+        }
+        payload.settings.insert(plugin_id, values);
+    }
+    Ok(payload)
+}
+
+/// 导出全部图床配置与用户插件为加密文件
+#[tauri::command]
+pub async fn export_image_host_settings(
+    app: AppHandle,
+    path: String,
+    include_secrets: bool,
+    passphrase: String,
+) -> Result<(), String> {
+    require_passphrase(&passphrase)?;
+    let payload = collect_host_payload(&app, include_secrets)?;
+    let text = seal_bundle(BUNDLE_FORMAT, &payload, &passphrase).await?;
+    fs::write(&path, text).map_err(|e| {
+        error!(
+            "export_image_host_settings failed: path={}, error={}",
+            path, e
+        );
+        format!("写入导出文件失败 {}: {e}", path)
+    })?;
+
+    info!(
+        "export_image_host_settings success: path={}, settings={}, plugins={}, secrets={}",
+        path,
+        payload.settings.len(),
+        payload.plugins.len(),
+        include_secrets
+    );
+    Ok(())
+}
+
+/// 将配置中的占位符替换为空字符串（对应敏感值未随文件导出）
+fn clear_missing_secrets(values: &mut Value, secrets: Option<&BTreeMap<String, String>>) {
+    let Some(map) = values.as_object_mut() else {
+        return;
+    };
+    for (key, value) in map.iter_mut() {
+        let missing = secrets.is_none_or(|secrets| !secrets.contains_key(key));
+        if value.as_str() == Some(SECRET_PLACEHOLDER) && missing {
+            *value = Value::String(String::new());
+        }
+    }
+}
+
+impl BundlePayload {
+    pub(crate) fn summary(&self) -> ImportSummary {
+        ImportSummary {
+            settings: self.settings.len(),
+            plugins: self.plugins.len(),
+            secrets: self.secrets.values().map(BTreeMap::len).sum(),
+        }
+    }
+}
+
+/// 写回打包内容中的用户插件、敏感字段与图床配置，同名插件与配置会被覆盖
+pub(crate) fn apply_host_payload(
+    app: &AppHandle,
+    mut payload: BundlePayload,
+) -> Result<ImportSummary, String> {
+    let summary = payload.summary();
+    let plugin_dir = user_plugin_dir(app)?;
+    for plugin in &payload.plugins {
+        let file_path = Path::new(&plugin.file_name);
+        let stem = file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        validate_plugin_id(stem)?;
+        if file_path.file_name().and_then(|s| s.to_str()) != Some(plugin.file_name.as_str()) {
+            return Err(format!("非法的插件文件名: {}", plugin.file_name));
+        }
+    }
+    if !payload.plugins.is_empty() {
+        fs::create_dir_all(&plugin_dir)
+            .map_err(|e| format!("create_dir_all {}: {e}", plugin_dir.display()))?;
+    }
+    for plugin in &payload.plugins {
+        let target = plugin_dir.join(&plugin.file_name);
+        fs::write(&target, &plugin.source)
+            .map_err(|e| format!("write {}: {e}", target.display()))?;
+    }
+
+    for (plugin_id, secrets) in &payload.secrets {
+        for (key, value) in secrets {
+            write_secret(&plugin_secret_id(plugin_id, key), value)?;
+        }
+    }
+
+    let settings_path = ensure_config_path(app)?;
+    let mut file = read_settings(&settings_path)?;
+    for (plugin_id, mut values) in std::mem::take(&mut payload.settings) {
+        clear_missing_secrets(&mut values, payload.secrets.get(&plugin_id));
+        file.plugins.insert(plugin_id, values);
+    }
+    write_settings(&settings_path, &file)?;
+
+    if !payload.disabled.is_empty() {
+        let mut state = read_plugin_state(app)?;
+        state.disabled.extend(payload.disabled.iter().cloned());
+        write_plugin_state(app, &state)?;
+    }
+    Ok(summary)
+}
+
+/// 从加密文件导入图床配置与用户插件，同名插件与配置会被覆盖
+#[tauri::command]
+pub async fn import_image_host_settings(
+    app: AppHandle,
+    path: String,
+    passphrase: String,
+) -> Result<ImportSummary, String> {
+    let text = fs::read_to_string(&path).map_err(|e| format!("读取导入文件失败 {}: {e}", path))?;
+    let payload: BundlePayload = open_bundle(&text, BUNDLE_FORMAT, &passphrase).await?;
+    let summary = apply_host_payload(&app, payload)?;
+    info!(
+        "import_image_host_settings success: path={}, settings={}, plugins={}, secrets={}",
+        path, summary.settings, summary.plugins, summary.secrets
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let bundle = encrypt_bundle(BUNDLE_FORMAT, b"{\"settings\":{}}", "hunter2", 1_000).unwrap();
+        assert_eq!(
+            decrypt_bundle(&bundle, BUNDLE_FORMAT, "hunter2").unwrap(),
+            b"{\"settings\":{}}"
+        );
+        assert!(decrypt_bundle(&bundle, BUNDLE_FORMAT, "wrong").is_err());
+        assert!(decrypt_bundle(&bundle, "yana-app-config", "hunter2").is_err());
+    }
+
+    #[test]
+    fn test_rejects_excessive_iterations() {
+        let mut bundle = encrypt_bundle(BUNDLE_FORMAT, b"{}", "hunter2", 1_000).unwrap();
+        bundle.iterations = u32::MAX;
+        assert!(decrypt_bundle(&bundle, BUNDLE_FORMAT, "hunter2").is_err());
+        bundle.iterations = 0;
+        assert!(decrypt_bundle(&bundle, BUNDLE_FORMAT, "hunter2").is_err());
+    }
+}
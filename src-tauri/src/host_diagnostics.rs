@@ -0,0 +1,205 @@
+/*
+图床连通性诊断：
+`test_image_host` 生成一张极小的测试图片，按已保存的配置通过插件上传，
+随后请求返回的地址确认能取回图片内容，可选再调用插件删除。
+每一步的耗时与错误信息都会返回，便于排查令牌失效、域名配置错误等问题。
+*/
+
+use std::{io::Write, time::Instant};
+
+use image::{ImageFormat, Rgba, RgbaImage};
+use log::{info, warn};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::image_hosts::{ensure_config_path, read_settings};
+use crate::plugin_runtime::{execute_plugin_delete, execute_plugin_upload};
+
+const TEST_IMAGE_SIZE: u32 = 16;
+const VERIFY_TIMEOUT_SECS: u64 = 20;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticStep {
+    pub name: String,
+    pub ok: bool,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostDiagnostic {
+    pub plugin_id: String,
+    pub ok: bool,
+    pub url: Option<String>,
+    pub delete_id: Option<String>,
+    pub steps: Vec<DiagnosticStep>,
+}
+
+impl HostDiagnostic {
+    fn record(&mut self, name: &str, started: Instant, result: Result<Option<String>, String>) {
+        let (ok, message) = match result {
+            Ok(message) => (true, message),
+            Err(err) => (false, Some(err)),
+        };
+        self.ok &= ok;
+        self.steps.push(DiagnosticStep {
+            name: name.to_string(),
+            ok,
+            duration_ms: started.elapsed().as_millis() as u64,
+            message,
+        });
+    }
+}
+
+/// 生成带渐变的测试 PNG，避免图床因纯色 / 重复内容拒收或去重
+fn generate_test_png() -> Result<Vec<u8>, String> {
+    let seed = chrono::Local::now().timestamp_subsec_nanos();
+    let image = RgbaImage::from_fn(TEST_IMAGE_SIZE, TEST_IMAGE_SIZE, |x, y| {
+        Rgba([(x * 16) as u8, (y * 16) as u8, (seed % 256) as u8, 255])
+    });
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    image
+        .write_to(&mut bytes, ImageFormat::Png)
+        .map_err(|e| format!("生成测试图片失败: {e}"))?;
+    Ok(bytes.into_inner())
+}
+
+/// 请求上传得到的地址，确认返回的是图片内容
+async fn verify_url(url: &str) -> Result<Option<String>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(VERIFY_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("failed to build http client: {}", e))?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("请求 {} 失败: {}", url, e))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("请求 {} 返回 HTTP {}", url, status));
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("unknown")
+        .to_string();
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("读取 {} 响应失败: {}", url, e))?;
+    match image::guess_format(&bytes) {
+        Ok(format) => Ok(Some(format!(
+            "{} bytes, content-type {}, format {:?}",
+            bytes.len(),
+            content_type,
+            format
+        ))),
+        Err(_) => Err(format!(
+            "返回内容不是图片（{} bytes, content-type {}）",
+            bytes.len(),
+            content_type
+        )),
+    }
+}
+
+fn string_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// 使用已保存的配置对图床做一次完整的上传 / 取回 / 删除测试
+#[tauri::command]
+pub async fn test_image_host(
+    app: AppHandle,
+    plugin_id: String,
+    delete_after: Option<bool>,
+) -> Result<HostDiagnostic, String> {
+    let settings = read_settings(&ensure_config_path(&app)?)?
+        .plugins
+        .remove(&plugin_id)
+        .unwrap_or_else(|| Value::Object(Default::default()));
+
+    let mut tmp = tempfile::Builder::new()
+        .prefix("yana-host-test-")
+        .suffix(".png")
+        .tempfile()
+        .map_err(|e| format!("创建临时文件失败: {e}"))?;
+    tmp.write_all(&generate_test_png()?)
+        .map_err(|e| format!("写入测试图片失败: {e}"))?;
+    let file_path = tmp.path().to_string_lossy().to_string();
+
+    let mut diagnostic = HostDiagnostic {
+        plugin_id: plugin_id.clone(),
+        ok: true,
+        url: None,
+        delete_id: None,
+        steps: Vec::new(),
+    };
+
+    let started = Instant::now();
+    let uploaded = execute_plugin_upload(
+        app.clone(),
+        plugin_id.clone(),
+        file_path,
+        Some("yana-test.png".to_string()),
+        settings,
+        None,
+    )
+    .await
+    .and_then(|result| {
+        string_field(&result, "url")
+            .map(|url| (url, string_field(&result, "deleteId")))
+            .ok_or_else(|| format!("插件未返回 url: {}", result))
+    });
+    match uploaded {
+        Ok((url, delete_id)) => {
+            diagnostic.url = Some(url);
+            diagnostic.delete_id = delete_id;
+            diagnostic.record("upload", started, Ok(None));
+        }
+        Err(err) => {
+            diagnostic.record("upload", started, Err(err));
+            warn!("test_image_host failed at upload: plugin_id={}", plugin_id);
+            return Ok(diagnostic);
+        }
+    }
+
+    if let Some(url) = diagnostic.url.clone() {
+        let started = Instant::now();
+        let verified = verify_url(&url).await;
+        diagnostic.record("verify", started, verified);
+    }
+
+    if delete_after.unwrap_or(true) {
+        let started = Instant::now();
+        let deleted = match diagnostic.delete_id.clone() {
+            Some(delete_id) => execute_plugin_delete(app.clone(), plugin_id.clone(), delete_id)
+                .await
+                .and_then(|result| {
+                    if result.get("success").and_then(Value::as_bool) == Some(false) {
+                        Err(string_field(&result, "message")
+                            .unwrap_or_else(|| "插件删除失败".to_string()))
+                    } else {
+                        Ok(None)
+                    }
+                }),
+            None => Ok(Some("插件未返回 deleteId，跳过删除".to_string())),
+        };
+        diagnostic.record("delete", started, deleted);
+    }
+
+    info!(
+        "test_image_host finished: plugin_id={}, ok={}",
+        plugin_id, diagnostic.ok
+    );
+    Ok(diagnostic)
+}
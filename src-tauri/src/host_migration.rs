@@ -0,0 +1,632 @@
+/*
+图床迁移（把图库条目搬到另一个图床）：
+1) `start_host_migration(options)` 选择图库条目（`itemIds`）或某个图床上的全部条目（`sourceHost`），
+   后台逐个下载原图：S3 条目按删除标记中的对象键用已保存的凭据下载（失败时退回链接），其余按链接下载；
+2) 下载后经 host_upload 上传到目标图床（默认不压缩、不新增图库条目），成功后更新原条目的链接、
+   图床与删除标记，旧链接写入图库的链接历史；已在目标图床上的条目跳过；
+3) 条目之间按 `itemsPerMinute` 限速（默认 `DEFAULT_ITEMS_PER_MINUTE`），同一时间只运行一个迁移任务，
+   `cancel_host_migration` 跳过尚未开始的条目；
+4) 每个条目状态变化发送 `host-migration://progress`，结束后发送 `host-migration://finished`，
+   并在 app_data/migrations/ 下写出「旧链接 → 新链接」的 CSV 映射，便于在文章中批量替换；
+5) S3 设置开启 CDN 自动清除时，结束后清除从 S3 迁出的条目的旧链接缓存（cdn_purge）；
+6) 加密上传的条目只能迁移到支持任意文件的图床（`ARCHIVE_HOSTS`），密文原样上传（不压缩），
+   新链接沿用原来的 `#k=...` 密钥片段，否则条目会失去解密密钥。
+*/
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use chrono::Utc;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::batch_upload::BatchUploadOptions;
+use crate::encrypted_upload::{is_encrypted_link, strip_key_fragment, with_key_fragment};
+use crate::gallery::{GalleryItem, GalleryQuery};
+use crate::host_upload::PipelineOptions;
+
+const PROGRESS_EVENT: &str = "host-migration://progress";
+const FINISHED_EVENT: &str = "host-migration://finished";
+const MAPPING_DIR: &str = "migrations";
+const DEFAULT_ITEMS_PER_MINUTE: u32 = 30;
+const MAX_ITEMS_PER_MINUTE: u32 = 600;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MigrationOptions {
+    /// 要迁移的图库条目；指定 source_host 时忽略
+    pub item_ids: Vec<i64>,
+    /// 迁移该图床上的全部条目
+    pub source_host: Option<String>,
+    pub target_host: String,
+    /// 每分钟最多迁移的条目数
+    pub items_per_minute: Option<u32>,
+    /// 上传选项；未指定 compress 时不压缩，record_gallery 不生效（始终更新原条目）
+    #[serde(flatten)]
+    pub upload: BatchUploadOptions,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationStatus {
+    Waiting,
+    Downloading,
+    Uploading,
+    Done,
+    /// 已在目标图床上
+    Skipped,
+    Failed,
+    Cancelled,
+}
+
+impl MigrationStatus {
+    fn is_finished(self) -> bool {
+        matches!(
+            self,
+            Self::Done | Self::Skipped | Self::Failed | Self::Cancelled
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationItem {
+    pub index: usize,
+    pub item_id: i64,
+    pub file_name: String,
+    pub old_url: String,
+    pub new_url: Option<String>,
+    pub status: MigrationStatus,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationJob {
+    pub id: String,
+    pub target_host: String,
+    pub created_at: String,
+    pub total: usize,
+    /// 已结束（成功、跳过、失败或取消）的条目数
+    pub completed: usize,
+    pub failed: usize,
+    pub finished: bool,
+    pub cancelled: bool,
+    /// 旧链接 → 新链接映射文件，任务结束且有成功条目时生成
+    pub mapping_path: Option<String>,
+    pub items: Vec<MigrationItem>,
+}
+
+impl MigrationJob {
+    fn recount(&mut self) {
+        self.completed = self
+            .items
+            .iter()
+            .filter(|item| item.status.is_finished())
+            .count();
+        self.failed = self
+            .items
+            .iter()
+            .filter(|item| item.status == MigrationStatus::Failed)
+            .count();
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MigrationProgress {
+    job_id: String,
+    total: usize,
+    completed: usize,
+    failed: usize,
+    item: MigrationItem,
+}
+
+struct RunningMigration {
+    job: MigrationJob,
+    cancel: Arc<AtomicBool>,
+}
+
+static MIGRATION: Mutex<Option<RunningMigration>> = Mutex::new(None);
+
+fn lock_migration() -> Result<std::sync::MutexGuard<'static, Option<RunningMigration>>, String> {
+    MIGRATION
+        .lock()
+        .map_err(|_| "host migration state poisoned".to_string())
+}
+
+/// 两个条目之间的间隔
+fn interval(items_per_minute: Option<u32>) -> Duration {
+    let per_minute = items_per_minute
+        .unwrap_or(DEFAULT_ITEMS_PER_MINUTE)
+        .clamp(1, MAX_ITEMS_PER_MINUTE);
+    Duration::from_millis(60_000 / per_minute as u64)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 成功迁移的条目生成 `old_url,new_url,item_id,file_name` 映射
+fn mapping_csv(items: &[MigrationItem]) -> String {
+    let mut csv = String::from("old_url,new_url,item_id,file_name\n");
+    for item in items
+        .iter()
+        .filter(|item| item.status == MigrationStatus::Done)
+    {
+        let Some(new_url) = &item.new_url else {
+            continue;
+        };
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            csv_field(&item.old_url),
+            csv_field(new_url),
+            item.item_id,
+            csv_field(&item.file_name)
+        ));
+    }
+    csv
+}
+
+fn mapping_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = crate::portable::app_data_dir(app)
+        .map_err(|e| format!("app_data_dir: {e}"))?
+        .join(MAPPING_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("create_dir_all {}: {e}", dir.display()))?;
+    Ok(dir)
+}
+
+fn write_mapping(app: &AppHandle, job: &MigrationJob) -> Result<Option<String>, String> {
+    if !job
+        .items
+        .iter()
+        .any(|item| item.status == MigrationStatus::Done)
+    {
+        return Ok(None);
+    }
+    let name = format!(
+        "{}-{}.csv",
+        Utc::now().format("%Y%m%d-%H%M%S"),
+        crate::internal_hosts::sanitize_file_name(&job.target_host)
+    );
+    let path = mapping_dir(app)?.join(name);
+    std::fs::write(&path, mapping_csv(&job.items))
+        .map_err(|e| format!("写入迁移映射 {} 失败: {e}", path.display()))?;
+    Ok(Some(path.to_string_lossy().into_owned()))
+}
+
+/// 更新单个条目的状态并发送进度事件
+fn update_item(app: &AppHandle, index: usize, apply: impl FnOnce(&mut MigrationItem)) {
+    let progress = {
+        let Ok(mut running) = lock_migration() else {
+            return;
+        };
+        let Some(entry) = running.as_mut() else {
+            return;
+        };
+        let Some(item) = entry.job.items.get_mut(index) else {
+            return;
+        };
+        apply(item);
+        let item = item.clone();
+        entry.job.recount();
+        MigrationProgress {
+            job_id: entry.job.id.clone(),
+            total: entry.job.total,
+            completed: entry.job.completed,
+            failed: entry.job.failed,
+            item,
+        }
+    };
+    if let Err(err) = app.emit(PROGRESS_EVENT, &progress) {
+        warn!("emit {} failed: {}", PROGRESS_EVENT, err);
+    }
+}
+
+/// S3 条目的删除标记记录了对象键，用已保存的凭据下载原对象
+async fn download_s3(app: &AppHandle, marker: &str, dest: &Path) -> Result<(), String> {
+    let settings = crate::image_hosts::resolve_plugin_secrets(
+        "s3",
+        crate::host_upload::saved_settings(app, "s3")?,
+    )?;
+    let credentials: crate::s3::S3CredentialInput =
+        serde_json::from_value(settings).map_err(|e| format!("图床 s3 配置无效: {e}"))?;
+    crate::s3::s3_download(
+        marker.to_string(),
+        dest.to_string_lossy().into_owned(),
+        credentials,
+    )
+    .await
+    .map(|_| ())
+}
+
+async fn download(app: &AppHandle, item: &GalleryItem, dest: &Path) -> Result<(), String> {
+    if item.host == "s3"
+        && let Some(marker) = item.delete_marker.as_deref().filter(|m| !m.is_empty())
+    {
+        match download_s3(app, marker, dest).await {
+            Ok(()) => return Ok(()),
+            Err(err) => warn!(
+                "migration s3 download failed, fallback to url: id={}, error={}",
+                item.id, err
+            ),
+        }
+    }
+    // 加密条目的密钥片段不发送也不写入下载日志
+    crate::thumbnail::download_image(strip_key_fragment(&item.url), &dest.to_path_buf())
+        .await
+        .map(|_| ())
+}
+
+/// 加密条目只能迁到可存放任意文件的图床
+fn check_encrypted_target(item: &GalleryItem, target: &str) -> Result<(), String> {
+    if is_encrypted_link(&item.url) && !crate::archive_upload::ARCHIVE_HOSTS.contains(&target) {
+        return Err(format!(
+            "加密图片不能迁移到图床 {target}，请选择支持任意文件的图床"
+        ));
+    }
+    Ok(())
+}
+
+/// 加密条目：密文以随机名称原样上传，新链接带上原来的密钥片段
+async fn migrate_encrypted(
+    app: &AppHandle,
+    item: &GalleryItem,
+    target: &str,
+    path: &Path,
+) -> Result<(String, String), String> {
+    let name = format!("{}.bin", uuid::Uuid::new_v4().simple());
+    let result =
+        crate::host_upload::upload_to_host(app, target, &path.to_string_lossy(), Some(name))
+            .await?;
+    Ok((with_key_fragment(&item.url, &result.url), result.delete_id))
+}
+
+/// 下载、上传并更新图库条目，返回新链接
+async fn migrate_one(
+    app: &AppHandle,
+    dir: &Path,
+    index: usize,
+    item: &GalleryItem,
+    target: &str,
+    options: &PipelineOptions,
+) -> Result<String, String> {
+    check_encrypted_target(item, target)?;
+    let item_dir = dir.join(item.id.to_string());
+    std::fs::create_dir_all(&item_dir)
+        .map_err(|e| format!("create_dir_all {}: {e}", item_dir.display()))?;
+    let downloaded = item_dir.join("download");
+    download(app, item, &downloaded).await?;
+
+    let bytes = std::fs::read(&downloaded).map_err(|e| format!("读取下载文件失败: {e}"))?;
+    let name = crate::rehost::fix_extension(
+        &crate::internal_hosts::sanitize_file_name(&item.file_name),
+        &bytes,
+    );
+    let path = item_dir.join(name);
+    std::fs::rename(&downloaded, &path).map_err(|e| format!("重命名下载文件失败: {e}"))?;
+
+    update_item(app, index, |entry| {
+        entry.status = MigrationStatus::Uploading
+    });
+    let uploaded = if is_encrypted_link(&item.url) {
+        migrate_encrypted(app, item, target, &path).await
+    } else {
+        crate::host_upload::compress_and_upload(
+            app,
+            target,
+            vec![path.to_string_lossy().into_owned()],
+            options,
+        )
+        .await
+        .pop()
+        .ok_or_else(|| "上传未返回结果".to_string())
+        .and_then(|outcome| outcome.result)
+        .map(|result| (result.url, result.delete_id))
+    };
+    let _ = std::fs::remove_dir_all(&item_dir);
+    let (url, delete_id) = uploaded?;
+
+    let store = crate::gallery::store_from_app(app)?;
+    let delete_marker = Some(delete_id.as_str()).filter(|id| !id.is_empty());
+    store
+        .replace_url(item.id, &url, target, delete_marker, None)
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "图库条目已被删除".to_string())?;
+    Ok(url)
+}
+
+async fn run_migration(
+    app: AppHandle,
+    items: Vec<GalleryItem>,
+    target: String,
+    options: PipelineOptions,
+    pause: Duration,
+    cancel: Arc<AtomicBool>,
+) {
+    let base = crate::portable::temp_dir();
+    let dir = std::fs::create_dir_all(&base)
+        .map_err(|e| format!("create app temp dir {}: {}", base.display(), e))
+        .and_then(|_| {
+            tempfile::Builder::new()
+                .prefix("yana_migrate_")
+                .tempdir_in(&base)
+                .map_err(|e| format!("tempdir_in: {}", e))
+        });
+
+    let mut started = false;
+    for (index, item) in items.iter().enumerate() {
+        if item.host == target {
+            update_item(&app, index, |entry| entry.status = MigrationStatus::Skipped);
+            continue;
+        }
+        if started && !cancel.load(Ordering::Relaxed) {
+            tokio::time::sleep(pause).await;
+        }
+        if cancel.load(Ordering::Relaxed) {
+            update_item(&app, index, |entry| {
+                entry.status = MigrationStatus::Cancelled
+            });
+            continue;
+        }
+        started = true;
+
+        update_item(&app, index, |entry| {
+            entry.status = MigrationStatus::Downloading
+        });
+        let result = match &dir {
+            Ok(dir) => migrate_one(&app, dir.path(), index, item, &target, &options).await,
+            Err(err) => Err(err.clone()),
+        };
+        match &result {
+            Ok(url) => info!(
+                "migrated gallery item: id={}, url={}",
+                item.id,
+                strip_key_fragment(url)
+            ),
+            Err(err) => warn!("migrate gallery item failed: id={}, error={}", item.id, err),
+        }
+        update_item(&app, index, |entry| match result {
+            Ok(url) => {
+                entry.status = MigrationStatus::Done;
+                entry.new_url = Some(url);
+            }
+            Err(err) => {
+                entry.status = MigrationStatus::Failed;
+                entry.error = Some(err);
+            }
+        });
+    }
+
+    let snapshot = {
+        let Ok(running) = lock_migration() else {
+            return;
+        };
+        let Some(entry) = running.as_ref() else {
+            return;
+        };
+        entry.job.clone()
+    };
+    let mapping_path = write_mapping(&app, &snapshot)
+        .inspect_err(|err| warn!("write migration mapping failed: {}", err))
+        .ok()
+        .flatten();
+    let job = {
+        let Ok(mut running) = lock_migration() else {
+            return;
+        };
+        let Some(entry) = running.as_mut() else {
+            return;
+        };
+        entry.job.recount();
+        entry.job.finished = true;
+        entry.job.mapping_path = mapping_path;
+        entry.job.clone()
+    };
+    info!(
+        "host migration finished: id={}, target={}, total={}, failed={}, cancelled={}",
+        job.id, job.target_host, job.total, job.failed, job.cancelled
+    );
+    if let Err(err) = app.emit(FINISHED_EVENT, &job) {
+        warn!("emit {} failed: {}", FINISHED_EVENT, err);
+    }
+    let moved_from_s3 = job
+        .items
+        .iter()
+        .filter(|entry| entry.status == MigrationStatus::Done)
+        .filter(|entry| items.get(entry.index).is_some_and(|item| item.host == "s3"))
+        .map(|entry| entry.old_url.clone())
+        .collect();
+    crate::cdn_purge::auto_purge(&app, moved_from_s3);
+}
+
+/// 开始迁移，返回初始任务状态；已有迁移在进行时返回错误
+#[tauri::command]
+pub fn start_host_migration(
+    app: AppHandle,
+    options: MigrationOptions,
+) -> Result<MigrationJob, String> {
+    let target = options.target_host.trim().to_string();
+    if target.is_empty() {
+        return Err("未指定目标图床".to_string());
+    }
+    let store = crate::gallery::store_from_app(&app)?;
+    let items = match options
+        .source_host
+        .as_deref()
+        .map(str::trim)
+        .filter(|host| !host.is_empty())
+    {
+        Some(host) if host == target => return Err("源图床与目标图床相同".to_string()),
+        Some(host) => store.query(GalleryQuery {
+            host: Some(host.to_string()),
+            ..Default::default()
+        }),
+        None => store.get_many(&options.item_ids),
+    }
+    .map_err(|err| err.to_string())?;
+    if items.is_empty() {
+        return Err("没有要迁移的图库条目".to_string());
+    }
+
+    let mut running = lock_migration()?;
+    if running.as_ref().is_some_and(|entry| !entry.job.finished) {
+        return Err("已有迁移任务正在进行".to_string());
+    }
+    let job = MigrationJob {
+        id: uuid::Uuid::new_v4().to_string(),
+        target_host: target.clone(),
+        created_at: Utc::now().to_rfc3339(),
+        total: items.len(),
+        completed: 0,
+        failed: 0,
+        finished: false,
+        cancelled: false,
+        mapping_path: None,
+        items: items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| MigrationItem {
+                index,
+                item_id: item.id,
+                file_name: item.file_name.clone(),
+                old_url: item.url.clone(),
+                new_url: None,
+                status: MigrationStatus::Waiting,
+                error: None,
+            })
+            .collect(),
+    };
+    let cancel = Arc::new(AtomicBool::new(false));
+    *running = Some(RunningMigration {
+        job: job.clone(),
+        cancel: cancel.clone(),
+    });
+    drop(running);
+    info!(
+        "host migration started: id={}, target={}, items={}",
+        job.id, target, job.total
+    );
+
+    let mut pipeline = options.upload.pipeline();
+    pipeline.compress = options.upload.compress.or(Some(false));
+    pipeline.record_gallery = false;
+    let pause = interval(options.items_per_minute);
+    tauri::async_runtime::spawn(run_migration(app, items, target, pipeline, pause, cancel));
+    Ok(job)
+}
+
+/// 当前或最近一次迁移任务
+#[tauri::command]
+pub fn host_migration_status() -> Result<Option<MigrationJob>, String> {
+    Ok(lock_migration()?.as_ref().map(|entry| entry.job.clone()))
+}
+
+/// 取消迁移：尚未开始的条目标记为已取消，进行中的条目完成后结束；返回是否有进行中的任务
+#[tauri::command]
+pub fn cancel_host_migration() -> Result<bool, String> {
+    let mut running = lock_migration()?;
+    let Some(entry) = running.as_mut().filter(|entry| !entry.job.finished) else {
+        return Ok(false);
+    };
+    entry.cancel.store(true, Ordering::Relaxed);
+    entry.job.cancelled = true;
+    info!("host migration cancelled: id={}", entry.job.id);
+    Ok(true)
+}
+
+/// 在文件管理器中打开迁移映射文件所在目录
+#[tauri::command]
+pub fn open_migration_dir(app: AppHandle) -> Result<(), String> {
+    let dir = mapping_dir(&app)?;
+    app.opener()
+        .open_path(dir.to_string_lossy().into_owned(), None::<&str>)
+        .map_err(|err| format!("打开目录 {} 失败: {err}", dir.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: i64, status: MigrationStatus, new_url: Option<&str>) -> MigrationItem {
+        MigrationItem {
+            index: 0,
+            item_id: id,
+            file_name: "a, \"b\".png".to_string(),
+            old_url: format!("https://old.example.com/{id}.png"),
+            new_url: new_url.map(str::to_string),
+            status,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_mapping_csv() {
+        let csv = mapping_csv(&[
+            item(
+                1,
+                MigrationStatus::Done,
+                Some("https://new.example.com/1.png"),
+            ),
+            item(2, MigrationStatus::Failed, None),
+            item(3, MigrationStatus::Skipped, None),
+        ]);
+        assert_eq!(
+            csv,
+            "old_url,new_url,item_id,file_name\n\
+             https://old.example.com/1.png,https://new.example.com/1.png,1,\"a, \"\"b\"\".png\"\n"
+        );
+    }
+
+    #[test]
+    fn test_interval() {
+        assert_eq!(interval(None), Duration::from_secs(2));
+        assert_eq!(interval(Some(0)), Duration::from_secs(60));
+        assert_eq!(interval(Some(60)), Duration::from_secs(1));
+        assert_eq!(interval(Some(100_000)), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_options_deserialize() {
+        let options: MigrationOptions = serde_json::from_value(serde_json::json!({
+            "itemIds": [1, 2],
+            "targetHost": "webdav",
+            "itemsPerMinute": 10,
+            "compress": true,
+        }))
+        .unwrap();
+        assert_eq!(options.item_ids, vec![1, 2]);
+        assert_eq!(options.target_host, "webdav");
+        assert_eq!(options.items_per_minute, Some(10));
+        assert_eq!(options.upload.compress, Some(true));
+    }
+
+    #[test]
+    fn test_check_encrypted_target() {
+        let mut gallery_item = GalleryItem {
+            id: 1,
+            file_name: "shot.png".to_string(),
+            url: "https://s3.example.com/a.bin#k=secret&n=shot.png&t=image%2Fpng".to_string(),
+            host: "s3".to_string(),
+            delete_marker: None,
+            inserted_at: "2026-05-01T08:00:00+00:00".to_string(),
+            filesize: None,
+        };
+        assert!(check_encrypted_target(&gallery_item, "webdav").is_ok());
+        assert!(check_encrypted_target(&gallery_item, "smms").is_err());
+        gallery_item.url = "https://s3.example.com/a.png".to_string();
+        assert!(check_encrypted_target(&gallery_item, "smms").is_ok());
+    }
+}
@@ -0,0 +1,779 @@
+/*
+后端直接按已保存的图床配置上传文件（不经过前端插件注册表）：
+1) 配置读取自 image-hosts.json，并解析钥匙串字段；已禁用的图床拒绝上传；
+2) S3 与其他内置图床直接调用对应实现，用户插件交给后端 JS 运行时执行；
+3) 上传经过上传队列（并发与暂停生效），结果统一为 url / deleteId / metadata；
+4) `compress_and_upload` 串起重复检查、压缩（按设置或调用方覆盖）、上传（失败按 retries 重试）、
+   写入图库、上传统计、图床用量、后台文字识别与 Webhook 通知，供命令行、本地 HTTP 服务等没有界面参与的入口使用；
+   批量上传任务（batch_upload）逐个文件复用其中的步骤；
+   压缩与上传组成流水线（`prepare_pipelined`）：每个文件压缩完成后立即进入上传，不等整批压缩结束，
+   两段之间为有界通道，上传跟不上时压缩暂停，避免临时文件堆积；
+5) 重复检查之后按元数据隐私策略（metadata_privacy）检查原图：被阻止的文件不压缩、不上传，
+   需要去除的在压缩之后、上传之前去除；
+6) `upload_prepared` 在上传日志（pending_uploads 的 host_upload 记录）中记录各阶段，崩溃后启动时据此补写图库或重新上传；
+7) `delete_from_host` 同样按已保存的配置删除图床上的文件，供延迟删除（gallery_trash）使用。
+*/
+
+use std::path::Path;
+use std::time::Instant;
+
+use futures::{Stream, StreamExt, stream};
+use log::{info, warn};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tauri::AppHandle;
+use tokio::sync::mpsc;
+
+use crate::analytics::UploadEvent;
+use crate::dedup::DuplicateMatch;
+use crate::gallery::{GalleryItem, NewGalleryItem};
+use crate::image_hosts::{ensure_config_path, is_plugin_enabled, read_settings};
+use crate::internal_hosts::{HostDeleteResult, HostUploadResult};
+use crate::metadata_privacy::{PrivacyAction, PrivacyFindings, PrivacyPolicy};
+use crate::process::{CompressParams, Mode};
+
+/// 流水线中等待上传的已压缩文件上限（超过时压缩暂停）
+const PIPELINE_BUFFER: usize = 4;
+
+/// 压缩与上传选项，未指定的项按设置决定
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PipelineOptions {
+    /// Some(true) 强制压缩，Some(false) 跳过压缩，None 按设置「上传前压缩」
+    pub compress: Option<bool>,
+    /// 强制转为 WebP（否则按设置）
+    pub webp: bool,
+    pub quality: Option<u8>,
+    pub record_gallery: bool,
+    /// 忽略「跳过重复上传」，同一内容也重新上传
+    pub force: bool,
+    /// 上传失败后的重试次数
+    pub retries: u32,
+    /// 元数据隐私策略，None 按设置
+    pub privacy: Option<PrivacyPolicy>,
+}
+
+#[derive(Debug)]
+pub(crate) struct PipelineOutcome {
+    /// 调用方传入的原始路径
+    pub source: String,
+    pub result: Result<HostUploadResult, String>,
+    /// 内容已上传过，结果为图库中已有的链接
+    pub duplicate: bool,
+    /// 写入或沿用的图库条目 id
+    pub gallery_id: Option<i64>,
+    /// 按隐私策略阻止上传时发现的元数据
+    pub privacy: Option<PrivacyFindings>,
+}
+
+/// 读取已保存的图床配置（钥匙串字段保持占位符），未配置时返回空对象
+pub(crate) fn saved_settings(app: &AppHandle, plugin_id: &str) -> Result<Value, String> {
+    Ok(read_settings(&ensure_config_path(app)?)?
+        .plugins
+        .remove(plugin_id)
+        .unwrap_or_else(|| Value::Object(Default::default())))
+}
+
+fn config<T: DeserializeOwned>(plugin_id: &str, settings: Value) -> Result<T, String> {
+    serde_json::from_value(settings).map_err(|e| format!("图床 {} 配置无效: {}", plugin_id, e))
+}
+
+fn string_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// 内置图床的上传目标（URL 或主机名），用于判断是否为局域网 / 本机地址；公共服务返回 None
+fn destination(plugin_id: &str, settings: &Value) -> Option<String> {
+    match plugin_id {
+        "local" => Some("localhost".to_string()),
+        "s3" | "webdav" => string_field(settings, "endpoint"),
+        "sftp" => string_field(settings, "host"),
+        "chevereto" => string_field(settings, "baseUrl"),
+        "custom" => string_field(settings, "requestUrl"),
+        _ => None,
+    }
+}
+
+/// 将插件返回的 `{ url, deleteId, metadata }` 转换为统一结果
+fn plugin_result(plugin_id: &str, result: Value) -> Result<HostUploadResult, String> {
+    let url = string_field(&result, "url")
+        .ok_or_else(|| format!("插件 {} 未返回 url: {}", plugin_id, result))?;
+    Ok(HostUploadResult {
+        url,
+        delete_id: string_field(&result, "deleteId").unwrap_or_default(),
+        metadata: result.get("metadata").cloned().filter(|v| !v.is_null()),
+    })
+}
+
+async fn dispatch(
+    app: &AppHandle,
+    plugin_id: &str,
+    file_path: String,
+    file_name: String,
+    settings: Value,
+) -> Result<HostUploadResult, String> {
+    let resolved = || crate::image_hosts::resolve_plugin_secrets(plugin_id, settings.clone());
+    let name = Some(file_name.clone());
+    match plugin_id {
+        "s3" => {
+            let options = config(plugin_id, resolved()?)?;
+            let result = crate::s3::s3_upload_impl(file_path, file_name, options).await?;
+            Ok(HostUploadResult {
+                url: result.url,
+                delete_id: result.delete_id,
+                metadata: result.metadata,
+            })
+        }
+        "azure" => {
+            crate::azure_blob::azure_blob_upload(
+                file_path,
+                name,
+                config(plugin_id, resolved()?)?,
+                None,
+            )
+            .await
+        }
+        "chevereto" => {
+            crate::chevereto::chevereto_upload(
+                file_path,
+                name,
+                config(plugin_id, resolved()?)?,
+                None,
+            )
+            .await
+        }
+        "cloudinary" => {
+            crate::cloudinary::cloudinary_upload(
+                file_path,
+                name,
+                config(plugin_id, resolved()?)?,
+                None,
+            )
+            .await
+        }
+        "custom" => {
+            crate::custom_http::custom_http_upload(
+                file_path,
+                name,
+                config(plugin_id, resolved()?)?,
+                None,
+            )
+            .await
+        }
+        "gcs" => {
+            crate::gcs::gcs_upload(file_path, name, config(plugin_id, resolved()?)?, None).await
+        }
+        "github" => {
+            crate::github::github_upload(file_path, name, config(plugin_id, resolved()?)?, None)
+                .await
+        }
+        "imgur" => {
+            crate::imgur::imgur_upload(file_path, name, config(plugin_id, resolved()?)?, None).await
+        }
+        "local" => {
+            crate::local_folder::local_folder_upload(
+                file_path,
+                name,
+                config(plugin_id, resolved()?)?,
+                None,
+            )
+            .await
+        }
+        "sftp" => {
+            crate::sftp::sftp_upload(
+                app.clone(),
+                file_path,
+                name,
+                config(plugin_id, resolved()?)?,
+                None,
+            )
+            .await
+        }
+        "smms" => {
+            crate::smms::smms_upload(file_path, name, config(plugin_id, resolved()?)?, None).await
+        }
+        "webdav" => {
+            crate::webdav::webdav_upload(file_path, name, config(plugin_id, resolved()?)?, None)
+                .await
+        }
+        _ => {
+            // 用户插件：运行时内部自行排队并解析钥匙串字段
+            let result = crate::plugin_runtime::execute_plugin_upload(
+                app.clone(),
+                plugin_id.to_string(),
+                file_path,
+                name,
+                settings,
+                None,
+            )
+            .await?;
+            plugin_result(plugin_id, result)
+        }
+    }
+}
+
+/// 使用已保存的配置将文件上传到指定图床
+pub(crate) async fn upload_to_host(
+    app: &AppHandle,
+    plugin_id: &str,
+    file_path: &str,
+    original_file_name: Option<String>,
+) -> Result<HostUploadResult, String> {
+    if !is_plugin_enabled(app, plugin_id)? {
+        return Err(format!("图床 {} 已禁用", plugin_id));
+    }
+    let file_name = original_file_name.unwrap_or_else(|| {
+        std::path::Path::new(file_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "upload.bin".to_string())
+    });
+    let settings = saved_settings(app, plugin_id)?;
+    let file_path = file_path.to_string();
+    if crate::internal_hosts::INTERNAL_PLUGINS.contains(&plugin_id) {
+        let destination = destination(plugin_id, &settings);
+        crate::upload_queue::run_queued(
+            app,
+            None,
+            destination.as_deref(),
+            dispatch(app, plugin_id, file_path, file_name, settings),
+        )
+        .await
+    } else {
+        dispatch(app, plugin_id, file_path, file_name, settings).await
+    }
+}
+
+/// 使用已保存的配置从图床删除文件（deleteId 为上传时返回的删除标记）
+pub(crate) async fn delete_from_host(
+    app: &AppHandle,
+    plugin_id: &str,
+    delete_id: &str,
+) -> Result<HostDeleteResult, String> {
+    let (app, delete_id) = (app.clone(), delete_id.to_string());
+    match plugin_id {
+        "s3" => {
+            let settings = crate::image_hosts::resolve_plugin_secrets(
+                plugin_id,
+                saved_settings(&app, plugin_id)?,
+            )?;
+            let credentials: crate::s3::S3CredentialInput = config(plugin_id, settings)?;
+            let result = crate::s3::s3_delete(
+                delete_id,
+                credentials.access_key_id,
+                credentials.secret_access_key,
+                credentials.credential_id,
+                credentials.session_token,
+                credentials.presign_expiry_secs,
+                credentials.client_certificate,
+                credentials.http_options,
+            )
+            .await?;
+            Ok(HostDeleteResult {
+                success: result.success,
+                message: result.message,
+            })
+        }
+        "azure" => crate::azure_blob::azure_blob_delete(app, delete_id).await,
+        "chevereto" => crate::chevereto::chevereto_delete(app, delete_id).await,
+        "cloudinary" => crate::cloudinary::cloudinary_delete(app, delete_id).await,
+        "custom" => crate::custom_http::custom_http_delete(app, delete_id).await,
+        "gcs" => crate::gcs::gcs_delete(app, delete_id).await,
+        "github" => crate::github::github_delete(app, delete_id).await,
+        "imgur" => crate::imgur::imgur_delete(app, delete_id).await,
+        "local" => crate::local_folder::local_folder_delete(app, delete_id).await,
+        "sftp" => crate::sftp::sftp_delete(app, delete_id).await,
+        "smms" => crate::smms::smms_delete(app, delete_id).await,
+        "webdav" => crate::webdav::webdav_delete(app, delete_id).await,
+        _ => {
+            let result =
+                crate::plugin_runtime::execute_plugin_delete(app, plugin_id.to_string(), delete_id)
+                    .await?;
+            Ok(HostDeleteResult {
+                success: result.get("success").and_then(Value::as_bool) != Some(false),
+                message: string_field(&result, "message"),
+            })
+        }
+    }
+}
+
+/// 界面删除图床上的文件：配置与钥匙串字段均由后端读取，前端不接触密钥
+#[tauri::command]
+pub async fn delete_image_from_host(
+    app: AppHandle,
+    plugin_id: String,
+    delete_id: String,
+) -> Result<HostDeleteResult, String> {
+    delete_from_host(&app, &plugin_id, &delete_id).await
+}
+
+/// 上传时使用的文件名：沿用原文件名，压缩后格式改变时替换扩展名
+pub(crate) fn upload_file_name(source: &Path, prepared: &Path) -> String {
+    let original = source
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "upload.bin".to_string());
+    let (Some(stem), Some(ext)) = (source.file_stem(), prepared.extension()) else {
+        return original;
+    };
+    let same_ext = source
+        .extension()
+        .is_some_and(|src| src.eq_ignore_ascii_case(ext));
+    if same_ext {
+        original
+    } else {
+        format!("{}.{}", stem.to_string_lossy(), ext.to_string_lossy())
+    }
+}
+
+/// 按选项与设置决定的压缩参数，不压缩时返回 None
+fn compress_params(app: &AppHandle, options: &PipelineOptions) -> Option<CompressParams> {
+    let settings = crate::settings::current_settings(app);
+    let compress = options
+        .compress
+        .unwrap_or(settings.enable_upload_compression);
+    if !compress {
+        return None;
+    }
+    let mode = if options.webp || settings.convert_to_webp {
+        Mode::webp
+    } else {
+        Mode::original_format
+    };
+    // 调用方指定画质时不使用自动画质
+    let target_ssim =
+        (settings.auto_quality && options.quality.is_none()).then_some(settings.auto_quality_ssim);
+    Some(CompressParams::new(
+        options.quality.unwrap_or(settings.quality),
+        mode,
+        settings.png_compression_mode,
+        settings.png_optimization,
+        target_ssim,
+        settings.heic_sequence_mode,
+    ))
+}
+
+/// 按选项压缩，失败时回退为原图（与 `compress_images` 的单文件回退一致）
+pub(crate) async fn prepare(
+    app: &AppHandle,
+    sources: &[String],
+    options: &PipelineOptions,
+) -> Vec<String> {
+    let Some(params) = compress_params(app, options).filter(|_| !sources.is_empty()) else {
+        return sources.to_vec();
+    };
+    crate::process::compress_images(
+        app.clone(),
+        sources.to_vec(),
+        params.quality,
+        params.mode,
+        params.png_mode,
+        params.png_optimization,
+        params.target_ssim,
+    )
+    .await
+    .unwrap_or_else(|err| {
+        warn!("compress before upload failed, upload originals: {}", err);
+        sources.to_vec()
+    })
+}
+
+/// 流水线的入口通道，容量与待上传文件上限一致
+pub(crate) fn pipeline_channel() -> (
+    mpsc::Sender<(usize, String)>,
+    mpsc::Receiver<(usize, String)>,
+) {
+    mpsc::channel(PIPELINE_BUFFER)
+}
+
+/// 流水线压缩：从 `input` 接收（索引, 原图），每个文件压缩完成（失败时为原图）后立即发出（索引, 待上传路径），
+/// 完成顺序与输入顺序无关；不压缩时原样转发
+pub(crate) fn prepare_pipelined(
+    app: &AppHandle,
+    mut input: mpsc::Receiver<(usize, String)>,
+    options: &PipelineOptions,
+) -> mpsc::Receiver<(usize, String)> {
+    let Some(params) = compress_params(app, options) else {
+        return input;
+    };
+    let (tx, rx) = mpsc::channel(PIPELINE_BUFFER);
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let files = std::iter::from_fn(move || input.blocking_recv());
+        crate::process::compress_each(&app, files, params, tx);
+    });
+    rx
+}
+
+/// 将通道转为 Stream，配合 `buffer_unordered` 按上传并发数消费，未取走的文件留在通道中形成背压
+pub(crate) fn receiver_stream<T>(receiver: mpsc::Receiver<T>) -> impl Stream<Item = T> {
+    stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|item| (item, receiver))
+    })
+}
+
+/// 同时进行的上传数，取设置中的最大并发上传数（上传队列同样按此限制）
+pub(crate) fn upload_concurrency(app: &AppHandle) -> usize {
+    (crate::settings::current_settings(app).max_concurrent_uploads as usize).max(1)
+}
+
+fn file_len(path: &str) -> Option<u64> {
+    std::fs::metadata(path).ok().map(|m| m.len())
+}
+
+fn record_gallery(
+    app: &AppHandle,
+    file_name: &str,
+    plugin_id: &str,
+    result: &HostUploadResult,
+    uploaded: &str,
+    content_hash: Option<String>,
+) -> Result<i64, String> {
+    crate::gallery::gallery_insert_item(
+        app.clone(),
+        NewGalleryItem {
+            file_name: file_name.to_string(),
+            url: result.url.clone(),
+            host: plugin_id.to_string(),
+            delete_marker: Some(result.delete_id.clone()).filter(|id| !id.is_empty()),
+            inserted_at: None,
+            filesize: file_len(uploaded).map(|len| len as i64),
+            content_hash,
+        },
+    )
+    .map(|item| item.id)
+}
+
+/// 已上传过的内容直接沿用图库中的链接
+pub(crate) fn duplicate_outcome(source: String, item: GalleryItem) -> PipelineOutcome {
+    info!("skip duplicate upload: file={}, url={}", source, item.url);
+    PipelineOutcome {
+        source,
+        result: Ok(HostUploadResult {
+            url: item.url,
+            delete_id: item.delete_marker.unwrap_or_default(),
+            metadata: None,
+        }),
+        duplicate: true,
+        gallery_id: Some(item.id),
+        privacy: None,
+    }
+}
+
+/// 按隐私策略（调用方覆盖或设置）检查待上传的原图
+pub(crate) fn privacy_action(
+    app: &AppHandle,
+    source: &str,
+    options: &PipelineOptions,
+) -> PrivacyAction {
+    let policy = options
+        .privacy
+        .unwrap_or_else(|| crate::settings::current_settings(app).metadata_privacy_policy);
+    crate::metadata_privacy::decide(policy, Path::new(source))
+}
+
+/// 含有位置或序列号的原图未上传
+pub(crate) fn blocked_outcome(source: String, findings: PrivacyFindings) -> PipelineOutcome {
+    info!("upload blocked by metadata privacy policy: file={}", source);
+    PipelineOutcome {
+        source,
+        result: Err(crate::metadata_privacy::blocked_message(&findings)),
+        duplicate: false,
+        gallery_id: None,
+        privacy: Some(findings),
+    }
+}
+
+/// 上传失败后重试，第 n 次重试前等待 n 秒；取消的上传不重试
+async fn upload_with_retries(
+    app: &AppHandle,
+    plugin_id: &str,
+    path: &str,
+    file_name: &str,
+    retries: u32,
+) -> Result<HostUploadResult, String> {
+    let mut attempt = 0;
+    loop {
+        let result = upload_to_host(app, plugin_id, path, Some(file_name.to_string())).await;
+        match result {
+            Err(err) if attempt < retries && err != crate::upload::CANCELLED_ERROR => {
+                attempt += 1;
+                warn!(
+                    "upload failed, retry {}/{}: file={}, error={}",
+                    attempt, retries, path, err
+                );
+                tokio::time::sleep(std::time::Duration::from_secs(attempt as u64)).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// 上传已压缩（或原样）的文件（`strip` 时先去除元数据），记录上传统计、图床用量与图库，
+/// 之后删除压缩或去除元数据产生的临时文件
+pub(crate) async fn upload_prepared(
+    app: &AppHandle,
+    plugin_id: &str,
+    source: String,
+    path: String,
+    content_hash: Option<String>,
+    strip: bool,
+    options: &PipelineOptions,
+) -> PipelineOutcome {
+    let path = if strip {
+        match crate::metadata_privacy::strip_prepared(&source, &path) {
+            Ok(stripped) => stripped,
+            Err(err) => {
+                warn!("strip metadata failed: file={}, error={}", source, err);
+                if path != source {
+                    let _ = std::fs::remove_file(&path);
+                }
+                return PipelineOutcome {
+                    source,
+                    result: Err(err),
+                    duplicate: false,
+                    gallery_id: None,
+                    privacy: None,
+                };
+            }
+        }
+    } else {
+        path
+    };
+    let file_name = upload_file_name(Path::new(&source), Path::new(&path));
+    let journal = crate::pending_uploads::begin_host_upload(
+        app,
+        plugin_id,
+        &crate::pending_uploads::HostUploadJob {
+            source: source.clone(),
+            prepared: path.clone(),
+            file_name: file_name.clone(),
+            content_hash: content_hash.clone(),
+            record_gallery: options.record_gallery,
+        },
+    );
+    let bytes = file_len(&path);
+    journal.uploading();
+    let started = Instant::now();
+    let result = upload_with_retries(app, plugin_id, &path, &file_name, options.retries).await;
+    crate::analytics::record(
+        app,
+        UploadEvent {
+            host: plugin_id.to_string(),
+            success: result.is_ok(),
+            bytes,
+            original_bytes: file_len(&source),
+            duration_ms: started.elapsed().as_millis() as u64,
+            error: result.as_ref().err().cloned(),
+            timestamp: None,
+        },
+    );
+    let mut gallery_id = None;
+    if let Ok(uploaded) = &result {
+        if let Err(err) = crate::host_usage::record_host_usage(
+            app.clone(),
+            plugin_id.to_string(),
+            1,
+            bytes.unwrap_or(0),
+        ) {
+            warn!("record host usage failed: {}", err);
+        }
+        if options.record_gallery {
+            journal.gallery_pending(uploaded, bytes);
+            match record_gallery(
+                app,
+                &file_name,
+                plugin_id,
+                uploaded,
+                &path,
+                content_hash.clone(),
+            ) {
+                Ok(id) => {
+                    gallery_id = Some(id);
+                    crate::ocr::index_in_background(app, id, source.clone());
+                }
+                Err(err) => warn!("record gallery item failed: file={}, error={}", source, err),
+            }
+        }
+        crate::telegram::post(app, plugin_id, &uploaded.url, &file_name, &path).await;
+    }
+    crate::webhooks::notify(
+        app,
+        crate::webhooks::UploadNotice {
+            host: plugin_id.to_string(),
+            file_name,
+            url: result.as_ref().ok().map(|uploaded| uploaded.url.clone()),
+            size: bytes,
+            hash: content_hash,
+            gallery_id,
+            error: result.as_ref().err().cloned(),
+            source_path: Some(source.clone()),
+        },
+    );
+    if path != source {
+        let _ = std::fs::remove_file(&path);
+    }
+    journal.finish();
+    PipelineOutcome {
+        source,
+        result,
+        duplicate: false,
+        gallery_id,
+        privacy: None,
+    }
+}
+
+/// 检查重复后边压缩边上传并写入图库，结果顺序与输入一致；压缩产生的临时文件上传后删除
+pub(crate) async fn compress_and_upload(
+    app: &AppHandle,
+    plugin_id: &str,
+    sources: Vec<String>,
+    options: &PipelineOptions,
+) -> Vec<PipelineOutcome> {
+    let checks: Vec<(DuplicateMatch, PrivacyAction)> = {
+        let (app, host, paths, options) = (
+            app.clone(),
+            plugin_id.to_string(),
+            sources.clone(),
+            options.clone(),
+        );
+        tauri::async_runtime::spawn_blocking(move || {
+            paths
+                .iter()
+                .map(|path| {
+                    let check = crate::dedup::check(&app, path, &host, options.force);
+                    // 已上传过的内容不会再发送给图床，无需检查
+                    let action = if check.existing.is_some() {
+                        PrivacyAction::Upload
+                    } else {
+                        privacy_action(&app, path, &options)
+                    };
+                    (check, action)
+                })
+                .collect()
+        })
+        .await
+        .unwrap_or_else(|err| {
+            warn!("duplicate check failed: {}", err);
+            Vec::new()
+        })
+    };
+    let mut checks = checks.into_iter();
+
+    // 已上传过或被隐私策略阻止的文件不参与压缩，其余按（索引, 原图）送入流水线
+    let mut outcomes: Vec<Option<PipelineOutcome>> = Vec::with_capacity(sources.len());
+    let mut pending = Vec::with_capacity(sources.len());
+    let mut to_upload = Vec::new();
+    for (index, source) in sources.into_iter().enumerate() {
+        let (hash, existing, action) = checks
+            .next()
+            .map(|(check, action)| (check.hash, check.existing, action))
+            .unwrap_or((None, None, PrivacyAction::Upload));
+        let outcome = match (existing, action) {
+            (Some(item), _) => Some(duplicate_outcome(source, item)),
+            (None, PrivacyAction::Block(findings)) => Some(blocked_outcome(source, findings)),
+            (None, action) => {
+                to_upload.push((index, source.clone()));
+                pending.push(Some((source, hash, action == PrivacyAction::Strip)));
+                outcomes.push(None);
+                continue;
+            }
+        };
+        pending.push(None);
+        outcomes.push(outcome);
+    }
+    if to_upload.is_empty() {
+        return outcomes.into_iter().flatten().collect();
+    }
+
+    let (tx, rx) = pipeline_channel();
+    tauri::async_runtime::spawn(async move {
+        for file in to_upload {
+            if tx.send(file).await.is_err() {
+                break;
+            }
+        }
+    });
+    let uploaded: Vec<(usize, PipelineOutcome)> =
+        receiver_stream(prepare_pipelined(app, rx, options))
+            .map(|(index, path)| {
+                let file = pending.get_mut(index).and_then(Option::take);
+                async move {
+                    let (source, hash, strip) = file?;
+                    let outcome =
+                        upload_prepared(app, plugin_id, source, path, hash, strip, options).await;
+                    Some((index, outcome))
+                }
+            })
+            .buffer_unordered(upload_concurrency(app))
+            .filter_map(std::future::ready)
+            .collect()
+            .await;
+    for (index, outcome) in uploaded {
+        outcomes[index] = Some(outcome);
+    }
+    // 压缩线程异常退出时未送达的文件记为失败，保证结果与输入一一对应
+    for (index, file) in pending.into_iter().enumerate() {
+        if let Some((source, _, _)) = file {
+            outcomes[index] = Some(PipelineOutcome {
+                source,
+                result: Err("压缩流水线意外结束，文件未上传".to_string()),
+                duplicate: false,
+                gallery_id: None,
+                privacy: None,
+            });
+        }
+    }
+    outcomes.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_plugin_result() {
+        let result = plugin_result(
+            "smms",
+            json!({ "url": "https://i.example.com/a.png", "deleteId": "abc", "metadata": null }),
+        )
+        .unwrap();
+        assert_eq!(result.url, "https://i.example.com/a.png");
+        assert_eq!(result.delete_id, "abc");
+        assert!(result.metadata.is_none());
+        assert!(plugin_result("smms", json!({ "deleteId": "abc" })).is_err());
+    }
+
+    #[test]
+    fn test_receiver_stream() {
+        let (tx, rx) = pipeline_channel();
+        let sent = std::thread::spawn(move || {
+            // 超过通道容量，接收端开始消费后才能全部发出
+            for index in 0..PIPELINE_BUFFER * 3 {
+                tx.blocking_send((index, format!("/tmp/{index}.png")))
+                    .unwrap();
+            }
+        });
+        let received: Vec<(usize, String)> =
+            tauri::async_runtime::block_on(receiver_stream(rx).collect());
+        sent.join().unwrap();
+        assert_eq!(received.len(), PIPELINE_BUFFER * 3);
+        assert_eq!(received[5], (5, "/tmp/5.png".to_string()));
+    }
+
+    #[test]
+    fn test_upload_file_name() {
+        assert_eq!(
+            upload_file_name(Path::new("/a/cat.PNG"), Path::new("/tmp/x.png")),
+            "cat.PNG"
+        );
+        assert_eq!(
+            upload_file_name(Path::new("/a/cat.png"), Path::new("/tmp/x.webp")),
+            "cat.webp"
+        );
+    }
+}
@@ -0,0 +1,410 @@
+/*
+图床用量与月度配额：
+1) 每次上传成功后由前端调用 `record_host_usage`，按 图床 + 自然月（本地时间 YYYY-MM）累计文件数与字节数，
+   持久化在 app_data_dir/usage.db；
+2) `set_host_quota` 为图床设置可选的月度字节 / 文件数上限，两者都为空时删除配额；
+3) 用量首次越过配额的 80% 或 100% 时发送 `host-usage://quota-warning` 事件，并把提示文本返回给调用方；
+4) `host_usage_stats(month)` 返回指定月份（默认当月）各图床的用量与配额；
+   提供容量查询接口的图床（目前为 SM.MS）额外附带已用空间、容量上限与剩余空间，查询失败时只记录日志。
+*/
+
+use std::{
+    fs,
+    path::Path,
+    sync::{Mutex, OnceLock},
+};
+
+use chrono::Local;
+use log::{info, warn};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+const DB_FILE_NAME: &str = "usage.db";
+const QUOTA_WARNING_EVENT: &str = "host-usage://quota-warning";
+/// 达到配额的该比例时提前提醒
+const NEAR_QUOTA_PERCENT: u64 = 80;
+
+static USAGE_STORE: OnceLock<HostUsageStore> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaLevel {
+    Near,
+    Exceeded,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostUsage {
+    pub host: String,
+    pub month: String,
+    pub files: u64,
+    pub bytes: u64,
+    pub quota_files: Option<u64>,
+    pub quota_bytes: Option<u64>,
+    /// 图床账号的已用空间 / 容量上限 / 剩余空间（由图床接口返回，与月份无关）
+    pub storage_used_bytes: Option<u64>,
+    pub storage_limit_bytes: Option<u64>,
+    pub storage_remaining_bytes: Option<u64>,
+}
+
+impl HostUsage {
+    fn empty(host: &str, month: &str) -> Self {
+        Self {
+            host: host.to_string(),
+            month: month.to_string(),
+            files: 0,
+            bytes: 0,
+            quota_files: None,
+            quota_bytes: None,
+            storage_used_bytes: None,
+            storage_limit_bytes: None,
+            storage_remaining_bytes: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QuotaWarning {
+    host: String,
+    month: String,
+    level: QuotaLevel,
+    message: String,
+    usage: HostUsage,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Quota {
+    files: Option<u64>,
+    bytes: Option<u64>,
+}
+
+pub struct HostUsageStore {
+    connection: Mutex<Connection>,
+}
+
+fn current_month() -> String {
+    Local::now().format("%Y-%m").to_string()
+}
+
+/// 用量从 before 增加到 after 时首次越过的提醒级别
+fn crossed_level(before: u64, after: u64, limit: Option<u64>) -> Option<QuotaLevel> {
+    let limit = limit.filter(|l| *l > 0)?;
+    let near = limit.saturating_mul(NEAR_QUOTA_PERCENT) / 100;
+    if before < limit && after >= limit {
+        Some(QuotaLevel::Exceeded)
+    } else if before < near && after >= near {
+        Some(QuotaLevel::Near)
+    } else {
+        None
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    format!("{:.1} MB", bytes as f64 / MB)
+}
+
+impl HostUsageStore {
+    fn new(app_data_dir: &Path) -> Result<Self, String> {
+        fs::create_dir_all(app_data_dir)
+            .map_err(|e| format!("create_dir_all {}: {e}", app_data_dir.display()))?;
+        let conn = Connection::open(app_data_dir.join(DB_FILE_NAME))
+            .map_err(|e| format!("open usage db: {e}"))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE IF NOT EXISTS host_usage (
+                 host TEXT NOT NULL,
+                 month TEXT NOT NULL,
+                 files INTEGER NOT NULL DEFAULT 0,
+                 bytes INTEGER NOT NULL DEFAULT 0,
+                 PRIMARY KEY (host, month)
+             );
+             CREATE TABLE IF NOT EXISTS host_quotas (
+                 host TEXT PRIMARY KEY,
+                 monthly_files INTEGER,
+                 monthly_bytes INTEGER
+             );",
+        )
+        .map_err(|e| format!("init usage db: {e}"))?;
+        Ok(Self {
+            connection: Mutex::new(conn),
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, String> {
+        self.connection
+            .lock()
+            .map_err(|_| "usage db connection poisoned".to_string())
+    }
+
+    fn quota(conn: &Connection, host: &str) -> Result<Quota, String> {
+        conn.query_row(
+            "SELECT monthly_files, monthly_bytes FROM host_quotas WHERE host = ?1",
+            params![host],
+            |row| {
+                Ok(Quota {
+                    files: row.get::<_, Option<i64>>(0)?.map(|v| v as u64),
+                    bytes: row.get::<_, Option<i64>>(1)?.map(|v| v as u64),
+                })
+            },
+        )
+        .optional()
+        .map(Option::unwrap_or_default)
+        .map_err(|e| format!("query quota: {e}"))
+    }
+
+    /// 累加用量，返回累加前后的 (files, bytes)
+    fn add(
+        &self,
+        host: &str,
+        month: &str,
+        files: u64,
+        bytes: u64,
+    ) -> Result<((u64, u64), HostUsage), String> {
+        let conn = self.lock()?;
+        let before: (i64, i64) = conn
+            .query_row(
+                "SELECT files, bytes FROM host_usage WHERE host = ?1 AND month = ?2",
+                params![host, month],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(|e| format!("query usage: {e}"))?
+            .unwrap_or((0, 0));
+        conn.execute(
+            "INSERT INTO host_usage (host, month, files, bytes) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(host, month) DO UPDATE SET
+                 files = files + excluded.files,
+                 bytes = bytes + excluded.bytes",
+            params![host, month, files as i64, bytes as i64],
+        )
+        .map_err(|e| format!("update usage: {e}"))?;
+        let quota = Self::quota(&conn, host)?;
+        let before = (before.0 as u64, before.1 as u64);
+        Ok((
+            before,
+            HostUsage {
+                host: host.to_string(),
+                month: month.to_string(),
+                files: before.0 + files,
+                bytes: before.1 + bytes,
+                quota_files: quota.files,
+                quota_bytes: quota.bytes,
+                ..HostUsage::empty(host, month)
+            },
+        ))
+    }
+
+    fn set_quota(&self, host: &str, quota: Quota) -> Result<(), String> {
+        let conn = self.lock()?;
+        let result = if quota.files.is_none() && quota.bytes.is_none() {
+            conn.execute("DELETE FROM host_quotas WHERE host = ?1", params![host])
+        } else {
+            conn.execute(
+                "INSERT INTO host_quotas (host, monthly_files, monthly_bytes) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(host) DO UPDATE SET
+                     monthly_files = excluded.monthly_files,
+                     monthly_bytes = excluded.monthly_bytes",
+                params![
+                    host,
+                    quota.files.map(|v| v as i64),
+                    quota.bytes.map(|v| v as i64)
+                ],
+            )
+        };
+        result.map(|_| ()).map_err(|e| format!("update quota: {e}"))
+    }
+
+    fn stats(&self, month: &str) -> Result<Vec<HostUsage>, String> {
+        let conn = self.lock()?;
+        // 设置了配额但本月尚未上传的图床也列出
+        let mut stmt = conn
+            .prepare(
+                "SELECT h.host, COALESCE(u.files, 0), COALESCE(u.bytes, 0),
+                        q.monthly_files, q.monthly_bytes
+                 FROM (SELECT host FROM host_usage WHERE month = ?1
+                       UNION SELECT host FROM host_quotas) h
+                 LEFT JOIN host_usage u ON u.host = h.host AND u.month = ?1
+                 LEFT JOIN host_quotas q ON q.host = h.host
+                 ORDER BY h.host",
+            )
+            .map_err(|e| format!("prepare usage stats: {e}"))?;
+        let rows = stmt
+            .query_map(params![month], |row| {
+                Ok(HostUsage {
+                    host: row.get(0)?,
+                    month: month.to_string(),
+                    files: row.get::<_, i64>(1)? as u64,
+                    bytes: row.get::<_, i64>(2)? as u64,
+                    quota_files: row.get::<_, Option<i64>>(3)?.map(|v| v as u64),
+                    quota_bytes: row.get::<_, Option<i64>>(4)?.map(|v| v as u64),
+                    ..HostUsage::empty("", month)
+                })
+            })
+            .map_err(|e| format!("query usage stats: {e}"))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("read usage stats: {e}"))
+    }
+}
+
+fn store_from_app(app: &AppHandle) -> Result<&'static HostUsageStore, String> {
+    if let Some(store) = USAGE_STORE.get() {
+        return Ok(store);
+    }
+    let dir = crate::portable::app_data_dir(&app).map_err(|e| format!("app_data_dir: {e}"))?;
+    let store = HostUsageStore::new(&dir)?;
+    let _ = USAGE_STORE.set(store);
+    Ok(USAGE_STORE.get().expect("usage store initialized"))
+}
+
+fn warning_message(usage: &HostUsage, level: QuotaLevel) -> String {
+    let detail = match (usage.quota_files, usage.quota_bytes) {
+        (Some(files), Some(bytes)) => format!(
+            "{}/{} 个文件，{}/{}",
+            usage.files,
+            files,
+            format_bytes(usage.bytes),
+            format_bytes(bytes)
+        ),
+        (Some(files), None) => format!("{}/{} 个文件", usage.files, files),
+        (None, Some(bytes)) => format!("{}/{}", format_bytes(usage.bytes), format_bytes(bytes)),
+        (None, None) => String::new(),
+    };
+    match level {
+        QuotaLevel::Near => format!("{} 本月用量已接近配额（{}）", usage.host, detail),
+        QuotaLevel::Exceeded => format!("{} 本月用量已超出配额（{}）", usage.host, detail),
+    }
+}
+
+/// 记录一次上传的用量，首次越过配额提醒线时返回提示文本
+#[tauri::command]
+pub fn record_host_usage(
+    app: AppHandle,
+    host: String,
+    files: u64,
+    bytes: u64,
+) -> Result<Option<String>, String> {
+    let month = current_month();
+    let (before, usage) = store_from_app(&app)?.add(&host, &month, files, bytes)?;
+    let level = crossed_level(before.0, usage.files, usage.quota_files).max(crossed_level(
+        before.1,
+        usage.bytes,
+        usage.quota_bytes,
+    ));
+    let Some(level) = level else {
+        return Ok(None);
+    };
+
+    let message = warning_message(&usage, level);
+    warn!("host quota warning: host={}, level={:?}", host, level);
+    if let Err(err) = app.emit(
+        QUOTA_WARNING_EVENT,
+        QuotaWarning {
+            host,
+            month,
+            level,
+            message: message.clone(),
+            usage,
+        },
+    ) {
+        warn!("emit {} failed: {}", QUOTA_WARNING_EVENT, err);
+    }
+    Ok(Some(message))
+}
+
+#[tauri::command]
+pub fn set_host_quota(
+    app: AppHandle,
+    host: String,
+    monthly_files: Option<u64>,
+    monthly_bytes: Option<u64>,
+) -> Result<(), String> {
+    let quota = Quota {
+        files: monthly_files.filter(|v| *v > 0),
+        bytes: monthly_bytes.filter(|v| *v > 0),
+    };
+    store_from_app(&app)?.set_quota(&host, quota)?;
+    info!(
+        "set_host_quota success: host={}, files={:?}, bytes={:?}",
+        host, quota.files, quota.bytes
+    );
+    Ok(())
+}
+
+/// 补充图床账号的容量信息（目前只有 SM.MS 提供查询接口）
+async fn attach_remote_storage(app: &AppHandle, month: &str, stats: &mut Vec<HostUsage>) {
+    let profile = match crate::smms::saved_profile(app).await {
+        Ok(Some(profile)) => profile,
+        Ok(None) => return,
+        Err(err) => {
+            warn!("query smms profile failed: {}", err);
+            return;
+        }
+    };
+    let host = crate::smms::PLUGIN_ID;
+    if !stats.iter().any(|usage| usage.host == host) {
+        stats.push(HostUsage::empty(host, month));
+        stats.sort_by(|a, b| a.host.cmp(&b.host));
+    }
+    if let Some(usage) = stats.iter_mut().find(|usage| usage.host == host) {
+        usage.storage_used_bytes = Some(profile.disk_usage_bytes);
+        usage.storage_limit_bytes = Some(profile.disk_limit_bytes);
+        usage.storage_remaining_bytes = Some(profile.remaining_bytes());
+    }
+}
+
+#[tauri::command]
+pub async fn host_usage_stats(
+    app: AppHandle,
+    month: Option<String>,
+) -> Result<Vec<HostUsage>, String> {
+    let month = month
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(current_month);
+    let mut stats = store_from_app(&app)?.stats(&month)?;
+    attach_remote_storage(&app, &month, &mut stats).await;
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crossed_level() {
+        assert_eq!(crossed_level(70, 85, Some(100)), Some(QuotaLevel::Near));
+        assert_eq!(crossed_level(85, 90, Some(100)), None);
+        assert_eq!(
+            crossed_level(70, 120, Some(100)),
+            Some(QuotaLevel::Exceeded)
+        );
+        assert_eq!(crossed_level(0, 500, None), None);
+    }
+
+    #[test]
+    fn test_usage_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HostUsageStore::new(dir.path()).unwrap();
+        store
+            .set_quota(
+                "smms",
+                Quota {
+                    files: Some(10),
+                    bytes: None,
+                },
+            )
+            .unwrap();
+        store.add("smms", "2024-05", 3, 100).unwrap();
+        let (before, usage) = store.add("smms", "2024-05", 2, 50).unwrap();
+        assert_eq!(before, (3, 100));
+        assert_eq!((usage.files, usage.bytes), (5, 150));
+
+        let stats = store.stats("2024-06").unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].files, 0);
+        assert_eq!(stats[0].quota_files, Some(10));
+    }
+}
@@ -0,0 +1,209 @@
+/*
+全局快捷键：
+1) 设置中的 `hotkeyUploadClipboard` / `hotkeyUploadScreenshot` / `hotkeyQuickDrop` 为快捷键字符串
+   （如 `CmdOrCtrl+Shift+U`），空字符串表示不注册；
+2) 保存设置时先注销全部快捷键再按新配置注册，单个快捷键注册失败（如被其他程序占用）只记录日志；
+3) 上传剪贴板：读取剪贴板图片写入临时目录，通过 `upload://request` 交给上传流程；
+   上传最近截图：优先取本应用最近一次截图，否则取系统截图目录中最新的图片；
+   快速投递：显示并聚焦主窗口，发送 `hotkey://quick-drop` 让前端切换到上传页。
+*/
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use log::{info, warn};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::settings::SettingsPayload;
+
+const QUICK_DROP_EVENT: &str = "hotkey://quick-drop";
+const SCREENSHOT_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "bmp"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HotkeyAction {
+    UploadClipboard,
+    UploadScreenshot,
+    QuickDrop,
+}
+
+/// 当前已注册的快捷键及其动作
+static REGISTERED: Mutex<Vec<(u32, HotkeyAction)>> = Mutex::new(Vec::new());
+
+/// 解析快捷键字符串，空字符串返回 None
+pub(crate) fn parse_shortcut(value: &str) -> Result<Option<Shortcut>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .parse::<Shortcut>()
+        .map(Some)
+        .map_err(|e| format!("无效的快捷键 {}: {e}", value))
+}
+
+fn configured(settings: &SettingsPayload) -> [(HotkeyAction, &str); 3] {
+    [
+        (
+            HotkeyAction::UploadClipboard,
+            settings.hotkey_upload_clipboard.as_str(),
+        ),
+        (
+            HotkeyAction::UploadScreenshot,
+            settings.hotkey_upload_screenshot.as_str(),
+        ),
+        (HotkeyAction::QuickDrop, settings.hotkey_quick_drop.as_str()),
+    ]
+}
+
+/// 按设置重新注册全部快捷键
+pub(crate) fn apply(app: &AppHandle, settings: &SettingsPayload) {
+    let Ok(mut registered) = REGISTERED.lock() else {
+        return;
+    };
+    let shortcuts = app.global_shortcut();
+    if let Err(err) = shortcuts.unregister_all() {
+        warn!("unregister global shortcuts failed: {}", err);
+    }
+    registered.clear();
+
+    for (action, value) in configured(settings) {
+        let shortcut = match parse_shortcut(value) {
+            Ok(Some(shortcut)) => shortcut,
+            Ok(None) => continue,
+            Err(err) => {
+                warn!("skip hotkey {:?}: {}", action, err);
+                continue;
+            }
+        };
+        if registered.iter().any(|(id, _)| *id == shortcut.id()) {
+            warn!("skip hotkey {:?}: {} already assigned", action, value);
+            continue;
+        }
+        match shortcuts.register(shortcut) {
+            Ok(()) => {
+                info!("hotkey registered: {:?} => {}", action, value);
+                registered.push((shortcut.id(), action));
+            }
+            Err(err) => warn!("register hotkey {} failed: {}", value, err),
+        }
+    }
+}
+
+fn is_screenshot_candidate(path: &Path) -> bool {
+    let Some(ext) = path.extension().and_then(|ext| ext.to_str()) else {
+        return false;
+    };
+    SCREENSHOT_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()) && path.is_file()
+}
+
+/// 在给定目录中查找修改时间最新的图片
+fn newest_image(dirs: &[PathBuf]) -> Option<PathBuf> {
+    dirs.iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .map(|entry| entry.path())
+        .filter(|path| is_screenshot_candidate(path))
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+/// 系统截图常见的保存位置：图片/Screenshots（Windows、Linux）与桌面（macOS）
+fn screenshot_dirs(app: &AppHandle) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(pictures) = app.path().picture_dir() {
+        dirs.push(pictures.join("Screenshots"));
+        dirs.push(pictures);
+    }
+    if let Ok(desktop) = app.path().desktop_dir() {
+        dirs.push(desktop);
+    }
+    dirs
+}
+
+/// 优先使用本应用最近一次截图，否则取系统截图目录中最新的图片
+pub(crate) fn last_screenshot(app: &AppHandle) -> Option<PathBuf> {
+    crate::capture::last_capture().or_else(|| newest_image(&screenshot_dirs(app)))
+}
+
+fn run_action(app: &AppHandle, action: HotkeyAction) {
+    match action {
+        HotkeyAction::UploadClipboard => match crate::clipboard_watcher::take_current(app) {
+            Ok(Some(path)) => crate::upload::request_upload(app, vec![path], "hotkey"),
+            Ok(None) => info!("hotkey upload clipboard: no image in clipboard"),
+            Err(err) => warn!("hotkey upload clipboard failed: {}", err),
+        },
+        HotkeyAction::UploadScreenshot => match last_screenshot(app) {
+            Some(path) => crate::upload::request_upload(
+                app,
+                vec![path.to_string_lossy().into_owned()],
+                "hotkey",
+            ),
+            None => info!("hotkey upload screenshot: no screenshot found"),
+        },
+        HotkeyAction::QuickDrop => {
+            crate::show_main_window(app);
+            if let Err(err) = app.emit(QUICK_DROP_EVENT, ()) {
+                warn!("emit {} failed: {}", QUICK_DROP_EVENT, err);
+            }
+        }
+    }
+}
+
+/// 全局快捷键插件的回调
+pub(crate) fn handle(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let action = REGISTERED.lock().ok().and_then(|registered| {
+        registered
+            .iter()
+            .find(|(id, _)| *id == shortcut.id())
+            .map(|(_, action)| *action)
+    });
+    let Some(action) = action else {
+        return;
+    };
+    info!("hotkey pressed: {:?}", action);
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || run_action(&app, action));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shortcut() {
+        assert!(parse_shortcut("").unwrap().is_none());
+        assert!(parse_shortcut("CmdOrCtrl+Shift+U").unwrap().is_some());
+        assert!(parse_shortcut("Alt+F9").unwrap().is_some());
+        assert!(parse_shortcut("Ctrl+Shift+NotAKey").is_err());
+    }
+
+    #[test]
+    fn test_newest_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let older = dir.path().join("older.png");
+        let newer = dir.path().join("newer.jpg");
+        std::fs::write(&older, b"a").unwrap();
+        std::fs::write(&newer, b"b").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"c").unwrap();
+        let past = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        std::fs::File::options()
+            .write(true)
+            .open(&older)
+            .unwrap()
+            .set_modified(past)
+            .unwrap();
+
+        assert_eq!(newest_image(&[dir.path().to_path_buf()]), Some(newer));
+        assert_eq!(newest_image(&[dir.path().join("missing")]), None);
+    }
+}
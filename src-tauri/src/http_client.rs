@@ -0,0 +1,89 @@
+/*
+上传相关 HTTP 客户端的通用选项：
+1) 重定向策略：跟随（默认，最多 10 次）/ 限制次数 / 不跟随。
+   预签名地址被重定向后签名会失效，此时应关闭重定向并直接返回 3xx 响应；
+2) 静态 DNS 覆盖（主机名 → IP），用于只能通过备用 IP 访问的图床。
+*/
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+};
+
+use reqwest::{ClientBuilder, redirect};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RedirectMode {
+    #[default]
+    Follow,
+    /// 最多跟随 `max_redirects` 次
+    Limited,
+    None,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpClientOptions {
+    #[serde(default)]
+    pub redirect: RedirectMode,
+    #[serde(default)]
+    pub max_redirects: Option<usize>,
+    /// 主机名 → IP（多个 IP 以逗号分隔），端口沿用请求地址中的端口
+    #[serde(default)]
+    pub dns_overrides: HashMap<String, String>,
+}
+
+impl HttpClientOptions {
+    fn redirect_policy(&self) -> redirect::Policy {
+        match self.redirect {
+            RedirectMode::Follow => redirect::Policy::limited(DEFAULT_MAX_REDIRECTS),
+            RedirectMode::Limited => {
+                redirect::Policy::limited(self.max_redirects.unwrap_or(DEFAULT_MAX_REDIRECTS))
+            }
+            RedirectMode::None => redirect::Policy::none(),
+        }
+    }
+}
+
+fn parse_override(host: &str, value: &str) -> Result<Vec<SocketAddr>, String> {
+    let addrs = value
+        .split(',')
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty())
+        .map(|ip| {
+            ip.trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+                .map(|ip| SocketAddr::new(ip, 0))
+                .map_err(|e| format!("invalid dns override for {}: `{}` ({e})", host, ip))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if addrs.is_empty() {
+        return Err(format!("dns override for {} has no address", host));
+    }
+    Ok(addrs)
+}
+
+/// 将重定向策略与 DNS 覆盖应用到客户端构建器
+pub(crate) fn apply_http_options(
+    builder: ClientBuilder,
+    options: Option<&HttpClientOptions>,
+) -> Result<ClientBuilder, String> {
+    let Some(options) = options else {
+        return Ok(builder);
+    };
+
+    let mut builder = builder.redirect(options.redirect_policy());
+    for (host, value) in &options.dns_overrides {
+        let host = host.trim();
+        if host.is_empty() {
+            continue;
+        }
+        builder = builder.resolve_to_addrs(host, &parse_override(host, value)?);
+    }
+    Ok(builder)
+}
@@ -1,4 +1,4 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -8,31 +8,76 @@ use serde_json::Value;
 use tauri::Manager;
 use tauri::path::BaseDirectory;
 
+use crate::internal_hosts::INTERNAL_PLUGINS;
+use crate::plugin_permissions::PluginPermissions;
+use crate::plugin_runtime::inspect_plugin_permissions;
+use crate::secrets::{delete_secret, keyring_available, read_secret, write_secret};
+use crate::settings::{SettingsChange, broadcast_settings_change};
+
 const IMAGE_HOST_SETTINGS_FILE: &str = "image-hosts.json";
+/// 插件启用状态单独存放，避免与 image-hosts.json 中以插件 id 为键的配置冲突
+const PLUGIN_STATE_FILE: &str = "image-host-plugins.json";
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PluginEntryPayload {
     pub id: String,
     pub script: String,
+    /// 是否启用；禁用的插件默认不出现在列表中，但保留其配置
+    pub enabled: bool,
+    /// 插件声明的权限，仅在安装 / 更新时返回，供界面向用户展示
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<PluginPermissions>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
-struct ImageHostSettingsFile {
+pub(crate) struct ImageHostSettingsFile {
     #[serde(flatten)]
-    plugins: HashMap<String, Value>,
+    pub plugins: HashMap<String, Value>,
 }
 
-fn ensure_config_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("app_config_dir: {e}"))?;
+pub(crate) fn ensure_config_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = crate::portable::app_config_dir(&app).map_err(|e| format!("app_config_dir: {e}"))?;
     fs::create_dir_all(&dir).map_err(|e| format!("create_dir_all {}: {e}", dir.display()))?;
     Ok(dir.join(IMAGE_HOST_SETTINGS_FILE))
 }
 
-fn read_settings(path: &Path) -> Result<ImageHostSettingsFile, String> {
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct PluginStateFile {
+    #[serde(default)]
+    pub disabled: BTreeSet<String>,
+}
+
+fn plugin_state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = crate::portable::app_config_dir(&app).map_err(|e| format!("app_config_dir: {e}"))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("create_dir_all {}: {e}", dir.display()))?;
+    Ok(dir.join(PLUGIN_STATE_FILE))
+}
+
+pub(crate) fn read_plugin_state(app: &tauri::AppHandle) -> Result<PluginStateFile, String> {
+    let path = plugin_state_path(app)?;
+    if !path.exists() {
+        return Ok(PluginStateFile::default());
+    }
+    let text = fs::read_to_string(&path).map_err(|e| format!("read {}: {e}", path.display()))?;
+    serde_json::from_str(&text).map_err(|e| format!("parse {}: {e}", path.display()))
+}
+
+pub(crate) fn is_plugin_enabled(app: &tauri::AppHandle, id: &str) -> Result<bool, String> {
+    Ok(!read_plugin_state(app)?.disabled.contains(id))
+}
+
+pub(crate) fn write_plugin_state(
+    app: &tauri::AppHandle,
+    state: &PluginStateFile,
+) -> Result<(), String> {
+    let path = plugin_state_path(app)?;
+    let text =
+        serde_json::to_string_pretty(state).map_err(|e| format!("serialize plugin state: {e}"))?;
+    fs::write(&path, text).map_err(|e| format!("write {}: {e}", path.display()))
+}
+
+pub(crate) fn read_settings(path: &Path) -> Result<ImageHostSettingsFile, String> {
     if !path.exists() {
         return Ok(ImageHostSettingsFile::default());
     }
@@ -43,13 +88,47 @@ fn read_settings(path: &Path) -> Result<ImageHostSettingsFile, String> {
     Ok(parsed)
 }
 
-fn write_settings(path: &Path, payload: &ImageHostSettingsFile) -> Result<(), String> {
+pub(crate) fn write_settings(path: &Path, payload: &ImageHostSettingsFile) -> Result<(), String> {
     let text = serde_json::to_string_pretty(payload)
         .map_err(|e| format!("serialize image host settings: {e}"))?;
     fs::write(path, text).map_err(|e| format!("write {}: {e}", path.display()))?;
     Ok(())
 }
 
+/// 用户插件目录（app_config_dir/plugins），通过 `add_image_host_plugin` 添加的插件都放在这里
+pub(crate) fn user_plugin_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let config_dir =
+        crate::portable::app_config_dir(&app).map_err(|e| format!("获取用户配置目录失败: {e}"))?;
+    Ok(config_dir.join("plugins"))
+}
+
+/// 插件 id 只能是文件名主干，禁止路径分隔符等
+pub(crate) fn validate_plugin_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !id.starts_with('.');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("非法的插件 id: {}", id))
+    }
+}
+
+/// 在用户插件目录中查找插件脚本（.js 或 .mjs）
+pub(crate) fn find_user_plugin(
+    app: &tauri::AppHandle,
+    id: &str,
+) -> Result<Option<PathBuf>, String> {
+    validate_plugin_id(id)?;
+    let dir = user_plugin_dir(app)?;
+    Ok(["js", "mjs"]
+        .iter()
+        .map(|ext| dir.join(format!("{}.{}", id, ext)))
+        .find(|path| path.is_file()))
+}
+
 fn candidate_plugin_dirs(app: &tauri::AppHandle) -> Vec<PathBuf> {
     let mut dirs = Vec::new();
 
@@ -59,7 +138,7 @@ fn candidate_plugin_dirs(app: &tauri::AppHandle) -> Vec<PathBuf> {
     }
 
     // User-added plugins in the user's app config directory
-    if let Ok(config_dir) = app.path().app_config_dir() {
+    if let Ok(config_dir) = crate::portable::app_config_dir(&app) {
         let user_plugin_dir = config_dir.join("plugins");
         dirs.push(user_plugin_dir);
     }
@@ -129,7 +208,7 @@ fn discover_plugins(app: &tauri::AppHandle) -> Result<Vec<PluginEntryPayload>, S
         let mut plugins = Vec::new();
 
         // 硬编码的内置插件列表
-        let builtin_plugins = ["freeimagehost", "sda1", "smms"];
+        let builtin_plugins = ["freeimagehost", "sda1"];
 
         // 尝试从 Resource 目录获取基础路径
         if let Ok(resource_path) = app.path().resolve("plugins", BaseDirectory::Resource) {
@@ -146,12 +225,14 @@ fn discover_plugins(app: &tauri::AppHandle) -> Result<Vec<PluginEntryPayload>, S
                 plugins.push(PluginEntryPayload {
                     id: plugin_id.to_string(),
                     script: script_path,
+                    enabled: true,
+                    permissions: None,
                 });
             }
         }
 
         // 同时检查用户插件目录（这个可以正常访问）
-        if let Ok(config_dir) = app.path().app_config_dir() {
+        if let Ok(config_dir) = crate::portable::app_config_dir(&app) {
             let user_plugin_dir = config_dir.join("plugins");
             let mut user_collected = BTreeMap::new();
             if let Err(err) = collect_plugins_from_dir(&user_plugin_dir, &mut user_collected) {
@@ -166,6 +247,8 @@ fn discover_plugins(app: &tauri::AppHandle) -> Result<Vec<PluginEntryPayload>, S
                 plugins.push(PluginEntryPayload {
                     id,
                     script: script_path,
+                    enabled: true,
+                    permissions: None,
                 });
             }
         }
@@ -202,17 +285,28 @@ fn discover_plugins(app: &tauri::AppHandle) -> Result<Vec<PluginEntryPayload>, S
                 PluginEntryPayload {
                     id,
                     script: script_path,
+                    enabled: true,
+                    permissions: None,
                 }
             })
             .collect()
     };
 
-    // 添加内置 S3 插件（所有平台）
-    if !result.iter().any(|entry| entry.id == "s3") {
-        result.push(PluginEntryPayload {
-            id: "s3".to_string(),
-            script: "__internal__/s3".to_string(),
-        });
+    // 添加后端实现的内置插件（所有平台）
+    for id in INTERNAL_PLUGINS {
+        if !result.iter().any(|entry| entry.id == *id) {
+            result.push(PluginEntryPayload {
+                id: id.to_string(),
+                script: format!("__internal__/{}", id),
+                enabled: true,
+                permissions: None,
+            });
+        }
+    }
+
+    let disabled = read_plugin_state(app)?.disabled;
+    for entry in &mut result {
+        entry.enabled = !disabled.contains(&entry.id);
     }
 
     result.sort_by(|a, b| a.id.cmp(&b.id));
@@ -220,13 +314,204 @@ fn discover_plugins(app: &tauri::AppHandle) -> Result<Vec<PluginEntryPayload>, S
     Ok(result)
 }
 
+/// Android 资源包内插件脚本的地址前缀（见 `discover_plugins`）
+const ANDROID_ASSET_PREFIX: &str = "asset://localhost/";
+
+/// 读取插件脚本源码，供后端运行时执行。
+/// Android 资源包内的插件无法以文件方式读取，经资源解析器 / fs 插件从 APK assets 读取；
+/// 内部插件（S3、WebDAV 等）没有脚本，返回错误。
+pub(crate) fn read_plugin_script(app: &tauri::AppHandle, id: &str) -> Result<String, String> {
+    let entry = discover_plugins(app)?
+        .into_iter()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| format!("插件不存在: {}", id))?;
+    if entry.script.starts_with("__internal__/") {
+        return Err(format!("内置插件 {} 不支持在后端运行时中执行", id));
+    }
+    if let Some(asset) = entry.script.strip_prefix(ANDROID_ASSET_PREFIX) {
+        return read_asset_script(app, asset, &entry.script);
+    }
+    let path = PathBuf::from(&entry.script);
+    if !path.is_file() {
+        return Err(format!("插件脚本不可读取: {}", entry.script));
+    }
+    fs::read_to_string(&path).map_err(|e| format!("read plugin {}: {e}", path.display()))
+}
+
+/// 读取资源包内的脚本：先查内嵌资源，Android 上再经 fs 插件读取 APK assets
+fn read_asset_script(app: &tauri::AppHandle, asset: &str, url: &str) -> Result<String, String> {
+    if let Some(found) = app.asset_resolver().get(asset.to_string()) {
+        return String::from_utf8(found.bytes().to_vec())
+            .map_err(|e| format!("plugin script {} is not utf-8: {e}", url));
+    }
+
+    #[cfg(target_os = "android")]
+    {
+        use std::io::Read;
+        use tauri_plugin_fs::{FilePath, FsExt, OpenOptions};
+
+        let path: FilePath = url
+            .parse()
+            .map_err(|e| format!("invalid plugin asset {}: {e}", url))?;
+        let mut options = OpenOptions::new();
+        options.read(true);
+        let mut file = app
+            .fs()
+            .open(path, options)
+            .map_err(|e| format!("read plugin {}: {e}", url))?;
+        let mut source = String::new();
+        file.read_to_string(&mut source)
+            .map_err(|e| format!("read plugin {}: {e}", url))?;
+        Ok(source)
+    }
+
+    #[cfg(not(target_os = "android"))]
+    Err(format!("插件脚本不可读取: {}", url))
+}
+
+/// 列出插件；默认隐藏已禁用的插件，`include_disabled` 为 true 时全部返回（供管理界面使用）
 #[tauri::command]
-pub fn list_image_host_plugins(app: tauri::AppHandle) -> Result<Vec<PluginEntryPayload>, String> {
-    let plugins = discover_plugins(&app)?;
+pub fn list_image_host_plugins(
+    app: tauri::AppHandle,
+    include_disabled: Option<bool>,
+) -> Result<Vec<PluginEntryPayload>, String> {
+    let mut plugins = discover_plugins(&app)?;
+    if !include_disabled.unwrap_or(false) {
+        plugins.retain(|entry| entry.enabled);
+    }
     info!("list_image_host_plugins success: count={}", plugins.len());
     Ok(plugins)
 }
 
+/// 存入钥匙串的字段在 image-hosts.json 与返回前端的配置中以该占位符表示；
+/// 保存时字段值仍为占位符代表未修改
+pub(crate) const SECRET_PLACEHOLDER: &str = "__yana_secret__";
+
+pub(crate) fn plugin_secret_id(plugin_id: &str, key: &str) -> String {
+    format!("image-host:{}:{}", plugin_id, key)
+}
+
+/// 早期版本以明文保存在 image-hosts.json 中的敏感字段
+const PLAINTEXT_SECRET_KEYS: &[&str] = &[
+    "token",
+    "password",
+    "secretAccessKey",
+    "sessionToken",
+    "clientSecret",
+    "apiKey",
+    "apiSecret",
+    "accountKey",
+    "sasToken",
+    "serviceAccountJson",
+    "hmacSecret",
+    "privateKeyPassphrase",
+];
+
+/// 启动时将配置中仍为明文的敏感字段迁移到钥匙串，配置中改为占位符；
+/// 迁移后不再有明文字段，后续启动不做任何修改。返回迁移的字段数。
+/// 没有系统钥匙串的平台（Android）保持明文，避免写入模拟存储后重启丢失
+pub(crate) fn migrate_plaintext_secrets(app: &tauri::AppHandle) -> Result<usize, String> {
+    if !keyring_available() {
+        return Ok(0);
+    }
+    let path = ensure_config_path(app)?;
+    let mut file = read_settings(&path)?;
+    let mut migrated = 0;
+    for (plugin_id, values) in file.plugins.iter_mut() {
+        let Some(map) = values.as_object_mut() else {
+            continue;
+        };
+        for key in PLAINTEXT_SECRET_KEYS {
+            let Some(Value::String(text)) = map.get(*key) else {
+                continue;
+            };
+            if text.is_empty() || text == SECRET_PLACEHOLDER {
+                continue;
+            }
+            write_secret(&plugin_secret_id(plugin_id, key), text)?;
+            map.insert(
+                (*key).to_string(),
+                Value::String(SECRET_PLACEHOLDER.to_string()),
+            );
+            migrated += 1;
+        }
+    }
+    if migrated > 0 {
+        write_settings(&path, &file)?;
+        info!("migrate_plaintext_secrets success: migrated={}", migrated);
+    }
+    Ok(migrated)
+}
+
+/// 将标记为 secret 的字段写入钥匙串，配置中只保留占位符
+fn store_plugin_secrets(
+    plugin_id: &str,
+    values: &mut serde_json::Map<String, Value>,
+    secret_keys: &[String],
+) -> Result<(), String> {
+    // 没有系统钥匙串时保持明文；导入时写入的占位符由明文存储解析，原样保留
+    if !keyring_available() {
+        return Ok(());
+    }
+    for key in secret_keys {
+        let secret_id = plugin_secret_id(plugin_id, key);
+        match values.get(key) {
+            Some(Value::String(text)) if text == SECRET_PLACEHOLDER => {}
+            None | Some(Value::Null) => delete_secret(&secret_id)?,
+            Some(Value::String(text)) if text.is_empty() => delete_secret(&secret_id)?,
+            Some(value) => {
+                let text = match value {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                write_secret(&secret_id, &text)?;
+                values.insert(key.clone(), Value::String(SECRET_PLACEHOLDER.to_string()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 删除配置中所有占位符字段对应的钥匙串条目
+fn delete_plugin_secrets(plugin_id: &str, values: &Value) {
+    let Some(map) = values.as_object() else {
+        return;
+    };
+    for (key, value) in map {
+        if value.as_str() == Some(SECRET_PLACEHOLDER)
+            && let Err(err) = delete_secret(&plugin_secret_id(plugin_id, key))
+        {
+            warn!(
+                "delete secret {} of plugin {} failed: {}",
+                key, plugin_id, err
+            );
+        }
+    }
+}
+
+/// 将配置中的占位符替换为钥匙串中的真实值，仅在上传等需要时调用
+pub(crate) fn resolve_plugin_secrets(plugin_id: &str, values: Value) -> Result<Value, String> {
+    let Value::Object(mut map) = values else {
+        return Ok(values);
+    };
+    for (key, value) in map.iter_mut() {
+        if value.as_str() != Some(SECRET_PLACEHOLDER) {
+            continue;
+        }
+        match read_secret(&plugin_secret_id(plugin_id, key))? {
+            Some(secret) => *value = Value::String(secret),
+            None => {
+                warn!(
+                    "secret {} of plugin {} is missing in keyring",
+                    key, plugin_id
+                );
+                *value = Value::String(String::new());
+            }
+        }
+    }
+    Ok(Value::Object(map))
+}
+
 #[tauri::command]
 pub fn load_image_host_settings(
     app: tauri::AppHandle,
@@ -254,21 +539,30 @@ pub fn load_image_host_settings(
     }
 }
 
+/// 保存插件配置；`secret_keys` 中的字段（插件参数中标记为 secret 的项）写入系统钥匙串，
+/// 配置文件中只保留占位符
 #[tauri::command]
 pub fn save_image_host_settings(
     app: tauri::AppHandle,
     plugin_id: String,
     values: Value,
+    secret_keys: Option<Vec<String>>,
 ) -> Result<(), String> {
     let path = ensure_config_path(&app)?;
     let mut file = read_settings(&path)?;
 
-    match values {
-        Value::Object(_) => {
-            file.plugins.insert(plugin_id.clone(), values);
+    let saved = match values {
+        Value::Object(mut map) => {
+            store_plugin_secrets(&plugin_id, &mut map, &secret_keys.unwrap_or_default())?;
+            file.plugins
+                .insert(plugin_id.clone(), Value::Object(map.clone()));
+            Some(Value::Object(map))
         }
         Value::Null => {
-            file.plugins.remove(&plugin_id);
+            if let Some(previous) = file.plugins.remove(&plugin_id) {
+                delete_plugin_secrets(&plugin_id, &previous);
+            }
+            None
         }
         other => {
             warn!(
@@ -277,7 +571,7 @@ pub fn save_image_host_settings(
             );
             return Err("插件配置必须是对象".to_string());
         }
-    }
+    };
 
     match write_settings(&path, &file) {
         Ok(()) => {
@@ -286,6 +580,14 @@ pub fn save_image_host_settings(
                 path.display(),
                 plugin_id
             );
+            crate::settings_watcher::record_host_save(&plugin_id, saved.as_ref());
+            broadcast_settings_change(
+                &app,
+                SettingsChange::ImageHost {
+                    plugin_id,
+                    values: saved,
+                },
+            );
             Ok(())
         }
         Err(err) => {
@@ -300,11 +602,20 @@ pub fn save_image_host_settings(
     }
 }
 
-/// 将指定的本地 JS 文件复制到资源插件目录并注册为新插件
+/// 安装前读取待添加插件声明的权限，供界面请用户确认
 #[tauri::command]
-pub fn add_image_host_plugin(
+pub async fn inspect_image_host_plugin(source: String) -> Result<PluginPermissions, String> {
+    let script = fs::read_to_string(&source).map_err(|e| format!("读取插件文件失败: {e}"))?;
+    inspect_plugin_permissions(script).await
+}
+
+/// 将指定的本地 JS 文件复制到资源插件目录并注册为新插件。
+/// `approved` 为用户确认过的权限（见 `inspect_image_host_plugin`），与文件当前声明不一致时拒绝安装。
+#[tauri::command]
+pub async fn add_image_host_plugin(
     app: tauri::AppHandle,
     source: String,
+    approved: PluginPermissions,
 ) -> Result<PluginEntryPayload, String> {
     use std::fs;
     use std::path::PathBuf;
@@ -323,11 +634,14 @@ pub fn add_image_host_plugin(
     if !(file_name.ends_with(".js") || file_name.ends_with(".mjs")) {
         return Err("仅支持 .js 或 .mjs 文件".into());
     }
+    let script = fs::read_to_string(&src_path).map_err(|e| format!("读取插件文件失败: {e}"))?;
+    let permissions = inspect_plugin_permissions(script).await?;
+    if permissions != approved {
+        return Err("插件声明的权限与确认时不一致，请重新添加".into());
+    }
     // 获取用户插件目录
-    let config_dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("获取用户配置目录失败: {e}"))?;
+    let config_dir =
+        crate::portable::app_config_dir(&app).map_err(|e| format!("获取用户配置目录失败: {e}"))?;
     let plugin_dir = config_dir.join("plugins");
     fs::create_dir_all(&plugin_dir).map_err(|e| format!("创建用户插件目录失败: {e}"))?;
     // 复制文件
@@ -341,5 +655,120 @@ pub fn add_image_host_plugin(
         .to_string();
     let script = dest_path.to_string_lossy().to_string();
 
-    Ok(PluginEntryPayload { id, script })
+    let enabled = is_plugin_enabled(&app, &id)?;
+    info!(
+        "add_image_host_plugin success: id={}, permissions={:?}",
+        id, permissions
+    );
+    Ok(PluginEntryPayload {
+        id,
+        script,
+        enabled,
+        permissions: Some(permissions),
+    })
+}
+
+/// 删除用户添加的插件脚本；内置插件（资源目录与内部 S3）不可删除。
+/// `purge_settings` 为 true 时同时移除其在 image-hosts.json 中的配置。
+#[tauri::command]
+pub fn remove_image_host_plugin(
+    app: tauri::AppHandle,
+    id: String,
+    purge_settings: Option<bool>,
+) -> Result<(), String> {
+    if id == "s3" {
+        return Err("内置插件不可删除".to_string());
+    }
+    let Some(path) = find_user_plugin(&app, &id)? else {
+        warn!(
+            "remove_image_host_plugin refused: {} is not a user plugin",
+            id
+        );
+        return Err(format!("插件 {} 不是用户添加的插件，无法删除", id));
+    };
+
+    fs::remove_file(&path).map_err(|e| {
+        error!(
+            "remove_image_host_plugin failed: path={}, error={}",
+            path.display(),
+            e
+        );
+        format!("删除插件文件失败: {e}")
+    })?;
+
+    if purge_settings.unwrap_or(false) {
+        let settings_path = ensure_config_path(&app)?;
+        let mut file = read_settings(&settings_path)?;
+        if let Some(previous) = file.plugins.remove(&id) {
+            delete_plugin_secrets(&id, &previous);
+            write_settings(&settings_path, &file)?;
+        }
+        let mut state = read_plugin_state(&app)?;
+        if state.disabled.remove(&id) {
+            write_plugin_state(&app, &state)?;
+        }
+    }
+
+    info!(
+        "remove_image_host_plugin success: id={}, path={}, purge_settings={}",
+        id,
+        path.display(),
+        purge_settings.unwrap_or(false)
+    );
+    Ok(())
+}
+
+/// 启用或禁用插件；禁用只影响是否出现在插件列表中，不删除脚本与配置
+#[tauri::command]
+pub fn set_image_host_plugin_enabled(
+    app: tauri::AppHandle,
+    id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    validate_plugin_id(&id)?;
+    let mut state = read_plugin_state(&app)?;
+    let changed = if enabled {
+        state.disabled.remove(&id)
+    } else {
+        state.disabled.insert(id.clone())
+    };
+    if changed {
+        write_plugin_state(&app, &state)?;
+    }
+    info!(
+        "set_image_host_plugin_enabled success: id={}, enabled={}",
+        id, enabled
+    );
+    Ok(())
+}
+
+/// 读取插件声明的权限
+#[tauri::command]
+pub async fn get_image_host_plugin_permissions(
+    app: tauri::AppHandle,
+    id: String,
+) -> Result<PluginPermissions, String> {
+    let source = read_plugin_script(&app, &id)?;
+    inspect_plugin_permissions(source).await
+}
+
+/// 返回解析了钥匙串字段的完整插件配置，仅供上传 / 删除时使用，不要用于界面回显
+#[tauri::command]
+pub fn resolve_image_host_settings(
+    app: tauri::AppHandle,
+    plugin_id: String,
+) -> Result<Option<Value>, String> {
+    let path = ensure_config_path(&app)?;
+    let file = read_settings(&path)?;
+    file.plugins
+        .get(&plugin_id)
+        .cloned()
+        .map(|values| resolve_plugin_secrets(&plugin_id, values))
+        .transpose()
+        .inspect_err(|err| {
+            error!(
+                "resolve_image_host_settings failed: plugin_id={}, error={}",
+                plugin_id, err
+            );
+        })
 }
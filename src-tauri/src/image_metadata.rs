@@ -0,0 +1,517 @@
+/*
+读取图片元数据（上传前查看"将要公开的内容"）：
+1) `read_image_metadata(path)` 返回尺寸、格式、相机与拍摄参数、GPS 坐标、EXIF 全部字段、
+   XMP 原文与 ICC 配置文件信息；
+2) EXIF 由 kamadak-exif 解析（JPEG / PNG / WebP / TIFF / HEIF），缩略图 IFD 中的字段不列出；
+3) XMP 与 ICC 按容器格式读取：JPEG 的 APP1 / APP2 段、PNG 的 iTXt / iCCP 块、WebP 的 `XMP ` / `ICCP` 块；
+4) `has_gps` 供界面在上传前提示位置泄露。
+*/
+
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+use exif::{Exif, In, Tag, Value};
+use flate2::read::ZlibDecoder;
+use serde::Serialize;
+
+const JPEG_XMP_PREFIX: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+const JPEG_ICC_PREFIX: &[u8] = b"ICC_PROFILE\0";
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const PNG_XMP_KEYWORD: &[u8] = b"XML:com.adobe.xmp";
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraInfo {
+    pub make: Option<String>,
+    pub model: Option<String>,
+    pub lens: Option<String>,
+    pub serial_number: Option<String>,
+    pub software: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureInfo {
+    pub taken_at: Option<String>,
+    pub exposure_time: Option<String>,
+    pub f_number: Option<String>,
+    pub iso: Option<u32>,
+    pub focal_length: Option<String>,
+    pub orientation: Option<u32>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GpsInfo {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// 海拔（米），低于海平面为负
+    pub altitude: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExifField {
+    pub tag: String,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IccInfo {
+    pub size: usize,
+    /// 颜色空间签名，如 RGB、GRAY、CMYK
+    pub color_space: Option<String>,
+    /// 配置文件描述，如 sRGB IEC61966-2.1、Display P3
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageMetadata {
+    pub path: String,
+    pub format: Option<String>,
+    pub file_size: u64,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub camera: CameraInfo,
+    pub capture: CaptureInfo,
+    pub gps: Option<GpsInfo>,
+    pub has_gps: bool,
+    pub exif: Vec<ExifField>,
+    pub xmp: Option<String>,
+    pub icc: Option<IccInfo>,
+}
+
+/// 容器中除 EXIF 以外的元数据段
+#[derive(Debug, Default)]
+struct Segments {
+    xmp: Option<String>,
+    icc: Option<Vec<u8>>,
+}
+
+pub(crate) fn be_u16(bytes: &[u8], at: usize) -> Option<usize> {
+    bytes
+        .get(at..at + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]) as usize)
+}
+
+pub(crate) fn be_u32(bytes: &[u8], at: usize) -> Option<usize> {
+    bytes
+        .get(at..at + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+}
+
+pub(crate) fn le_u32(bytes: &[u8], at: usize) -> Option<usize> {
+    bytes
+        .get(at..at + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+}
+
+fn inflate(data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    ZlibDecoder::new(data).read_to_end(&mut out).ok()?;
+    Some(out)
+}
+
+fn scan_jpeg(bytes: &[u8]) -> Segments {
+    let mut segments = Segments::default();
+    let mut icc: Vec<u8> = Vec::new();
+    let mut pos = 2;
+    while pos + 4 <= bytes.len() {
+        if bytes[pos] != 0xFF {
+            break;
+        }
+        let marker = bytes[pos + 1];
+        match marker {
+            // 填充字节
+            0xFF => {
+                pos += 1;
+                continue;
+            }
+            0x01 | 0xD0..=0xD7 => {
+                pos += 2;
+                continue;
+            }
+            // 图像数据开始后不再有元数据段
+            0xD9 | 0xDA => break,
+            _ => {}
+        }
+        let Some(len) = be_u16(bytes, pos + 2).filter(|len| *len >= 2) else {
+            break;
+        };
+        let Some(data) = bytes.get(pos + 4..pos + 2 + len) else {
+            break;
+        };
+        if marker == 0xE1 && data.starts_with(JPEG_XMP_PREFIX) {
+            segments.xmp =
+                Some(String::from_utf8_lossy(&data[JPEG_XMP_PREFIX.len()..]).into_owned());
+        } else if marker == 0xE2 && data.starts_with(JPEG_ICC_PREFIX) {
+            // 前缀后依次为序号与总数，较大的配置文件按顺序分为多段
+            if let Some(chunk) = data.get(JPEG_ICC_PREFIX.len() + 2..) {
+                icc.extend_from_slice(chunk);
+            }
+        }
+        pos += 2 + len;
+    }
+    segments.icc = (!icc.is_empty()).then_some(icc);
+    segments
+}
+
+fn split_nul(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let at = data.iter().position(|b| *b == 0)?;
+    Some((&data[..at], &data[at + 1..]))
+}
+
+fn png_xmp(data: &[u8]) -> Option<String> {
+    let (keyword, rest) = split_nul(data)?;
+    if keyword != PNG_XMP_KEYWORD {
+        return None;
+    }
+    let (compressed, rest) = (*rest.first()? == 1, rest.get(2..)?);
+    let (_language, rest) = split_nul(rest)?;
+    let (_translated, text) = split_nul(rest)?;
+    let text = if compressed {
+        inflate(text)?
+    } else {
+        text.to_vec()
+    };
+    Some(String::from_utf8_lossy(&text).into_owned())
+}
+
+fn scan_png(bytes: &[u8]) -> Segments {
+    let mut segments = Segments::default();
+    let mut pos = PNG_SIGNATURE.len();
+    while let Some(len) = be_u32(bytes, pos) {
+        let Some(kind) = bytes.get(pos + 4..pos + 8) else {
+            break;
+        };
+        let Some(data) = bytes.get(pos + 8..pos + 8 + len) else {
+            break;
+        };
+        match kind {
+            b"iTXt" if segments.xmp.is_none() => segments.xmp = png_xmp(data),
+            b"iCCP" => {
+                // 配置文件名、压缩方式（仅 0 = zlib），之后为压缩数据
+                segments.icc = split_nul(data)
+                    .and_then(|(_, rest)| rest.get(1..))
+                    .and_then(inflate);
+            }
+            b"IEND" => break,
+            _ => {}
+        }
+        pos += 12 + len;
+    }
+    segments
+}
+
+fn scan_webp(bytes: &[u8]) -> Segments {
+    let mut segments = Segments::default();
+    let mut pos = 12;
+    while let Some(len) = le_u32(bytes, pos + 4) {
+        let kind = &bytes[pos..pos + 4];
+        let Some(data) = bytes.get(pos + 8..pos + 8 + len) else {
+            break;
+        };
+        match kind {
+            b"XMP " => segments.xmp = Some(String::from_utf8_lossy(data).into_owned()),
+            b"ICCP" => segments.icc = Some(data.to_vec()),
+            _ => {}
+        }
+        // 块按偶数字节对齐
+        pos += 8 + len + (len & 1);
+    }
+    segments
+}
+
+fn scan_segments(bytes: &[u8]) -> Segments {
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        scan_jpeg(bytes)
+    } else if bytes.starts_with(PNG_SIGNATURE) {
+        scan_png(bytes)
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(&b"WEBP"[..]) {
+        scan_webp(bytes)
+    } else {
+        Segments::default()
+    }
+}
+
+/// 读取 ICC 配置文件头部的颜色空间与 `desc` 标签
+fn icc_info(profile: &[u8]) -> IccInfo {
+    let text = |bytes: &[u8]| {
+        let value = String::from_utf8_lossy(bytes)
+            .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+            .to_string();
+        (!value.is_empty()).then_some(value)
+    };
+    let color_space = profile.get(16..20).and_then(text);
+    let description = be_u32(profile, 128).and_then(|count| {
+        (0..count.min(256)).find_map(|index| {
+            let entry = 132 + index * 12;
+            if profile.get(entry..entry + 4)? != b"desc" {
+                return None;
+            }
+            let offset = be_u32(profile, entry + 4)?;
+            let tag = profile.get(offset..offset + be_u32(profile, entry + 8)?)?;
+            match tag.get(0..4)? {
+                // ICC v2：ASCII 描述
+                b"desc" => {
+                    let len = be_u32(tag, 8)?;
+                    text(tag.get(12..12 + len)?)
+                }
+                // ICC v4：多语言 UTF-16BE，取第一条
+                b"mluc" => {
+                    let len = be_u32(tag, 20)?;
+                    let start = be_u32(tag, 24)?;
+                    let units: Vec<u16> = tag
+                        .get(start..start + len)?
+                        .chunks_exact(2)
+                        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                        .collect();
+                    let value = String::from_utf16_lossy(&units).trim().to_string();
+                    (!value.is_empty()).then_some(value)
+                }
+                _ => None,
+            }
+        })
+    });
+    IccInfo {
+        size: profile.len(),
+        color_space,
+        description,
+    }
+}
+
+fn ascii(exif: &Exif, tag: Tag) -> Option<String> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Ascii(values) => values
+            .first()
+            .map(|bytes| {
+                String::from_utf8_lossy(bytes)
+                    .trim_end_matches('\0')
+                    .trim()
+                    .to_string()
+            })
+            .filter(|value| !value.is_empty()),
+        _ => None,
+    }
+}
+
+fn display(exif: &Exif, tag: Tag) -> Option<String> {
+    exif.get_field(tag, In::PRIMARY)
+        .map(|field| field.display_value().with_unit(exif).to_string())
+}
+
+fn uint(exif: &Exif, tag: Tag) -> Option<u32> {
+    exif.get_field(tag, In::PRIMARY)?.value.get_uint(0)
+}
+
+fn rationals(exif: &Exif, tag: Tag) -> Option<Vec<f64>> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Rational(values) => Some(values.iter().map(|r| r.to_f64()).collect()),
+        _ => None,
+    }
+}
+
+/// 度分秒转为十进制度数
+fn dms_to_degrees(dms: &[f64], negative: bool) -> Option<f64> {
+    let [degrees, minutes, seconds] = dms.get(..3)? else {
+        return None;
+    };
+    let value = degrees + minutes / 60.0 + seconds / 3600.0;
+    value
+        .is_finite()
+        .then_some(if negative { -value } else { value })
+}
+
+fn gps(exif: &Exif) -> Option<GpsInfo> {
+    let latitude = dms_to_degrees(
+        &rationals(exif, Tag::GPSLatitude)?,
+        ascii(exif, Tag::GPSLatitudeRef).as_deref() == Some("S"),
+    )?;
+    let longitude = dms_to_degrees(
+        &rationals(exif, Tag::GPSLongitude)?,
+        ascii(exif, Tag::GPSLongitudeRef).as_deref() == Some("W"),
+    )?;
+    // GPSAltitudeRef 为 1 表示低于海平面
+    let altitude = rationals(exif, Tag::GPSAltitude)
+        .and_then(|values| values.first().copied())
+        .filter(|value| value.is_finite())
+        .map(|value| {
+            if uint(exif, Tag::GPSAltitudeRef) == Some(1) {
+                -value
+            } else {
+                value
+            }
+        });
+    Some(GpsInfo {
+        latitude,
+        longitude,
+        altitude,
+    })
+}
+
+pub(crate) fn read_metadata(path: &Path) -> Result<ImageMetadata, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("读取文件失败 {}: {e}", path.display()))?;
+    let format = image::guess_format(&bytes)
+        .ok()
+        .map(|format| format!("{format:?}").to_ascii_lowercase());
+    let (width, height) = image::image_dimensions(path)
+        .map(|(w, h)| (Some(w), Some(h)))
+        .unwrap_or((None, None));
+    let segments = scan_segments(&bytes);
+    let exif = exif::Reader::new()
+        .read_from_container(&mut Cursor::new(&bytes))
+        .ok();
+
+    let (camera, capture, gps, fields) = match &exif {
+        Some(exif) => (
+            CameraInfo {
+                make: ascii(exif, Tag::Make),
+                model: ascii(exif, Tag::Model),
+                lens: ascii(exif, Tag::LensModel),
+                serial_number: ascii(exif, Tag::BodySerialNumber),
+                software: ascii(exif, Tag::Software),
+            },
+            CaptureInfo {
+                taken_at: ascii(exif, Tag::DateTimeOriginal).or_else(|| ascii(exif, Tag::DateTime)),
+                exposure_time: display(exif, Tag::ExposureTime),
+                f_number: display(exif, Tag::FNumber),
+                iso: uint(exif, Tag::PhotographicSensitivity),
+                focal_length: display(exif, Tag::FocalLength),
+                orientation: uint(exif, Tag::Orientation),
+            },
+            gps(exif),
+            exif.fields()
+                .filter(|field| field.ifd_num == In::PRIMARY)
+                .map(|field| ExifField {
+                    tag: field.tag.to_string(),
+                    value: field.display_value().with_unit(exif).to_string(),
+                })
+                .collect(),
+        ),
+        None => Default::default(),
+    };
+    // 坐标不完整时也视为含有位置信息
+    let has_gps = gps.is_some()
+        || exif.as_ref().is_some_and(|exif| {
+            exif.get_field(Tag::GPSLatitude, In::PRIMARY).is_some()
+                || exif.get_field(Tag::GPSLongitude, In::PRIMARY).is_some()
+        });
+
+    Ok(ImageMetadata {
+        path: path.to_string_lossy().into_owned(),
+        format,
+        file_size: bytes.len() as u64,
+        width,
+        height,
+        camera,
+        capture,
+        gps,
+        has_gps,
+        exif: fields,
+        xmp: segments.xmp,
+        icc: segments.icc.as_deref().map(icc_info),
+    })
+}
+
+#[tauri::command]
+pub async fn read_image_metadata(path: String) -> Result<ImageMetadata, String> {
+    tauri::async_runtime::spawn_blocking(move || read_metadata(Path::new(&path)))
+        .await
+        .map_err(|err| format!("读取元数据任务失败: {err}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jpeg_segment(marker: u8, data: &[u8]) -> Vec<u8> {
+        let mut out = vec![0xFF, marker];
+        out.extend_from_slice(&((data.len() + 2) as u16).to_be_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn icc_profile() -> Vec<u8> {
+        let mut profile = vec![0u8; 132];
+        profile[16..20].copy_from_slice(b"RGB ");
+        profile[128..132].copy_from_slice(&1u32.to_be_bytes());
+        let mut desc = b"desc\0\0\0\0".to_vec();
+        desc.extend_from_slice(&5u32.to_be_bytes());
+        desc.extend_from_slice(b"sRGB\0");
+        let offset = 144u32;
+        profile.extend_from_slice(b"desc");
+        profile.extend_from_slice(&offset.to_be_bytes());
+        profile.extend_from_slice(&(desc.len() as u32).to_be_bytes());
+        profile.extend_from_slice(&desc);
+        profile
+    }
+
+    #[test]
+    fn test_scan_jpeg_segments() {
+        let profile = icc_profile();
+        let mut bytes = vec![0xFF, 0xD8];
+        let mut xmp = JPEG_XMP_PREFIX.to_vec();
+        xmp.extend_from_slice(b"<x:xmpmeta/>");
+        bytes.extend(jpeg_segment(0xE1, &xmp));
+        let (first, second) = profile.split_at(100);
+        for (index, chunk) in [first, second].iter().enumerate() {
+            let mut data = JPEG_ICC_PREFIX.to_vec();
+            data.extend_from_slice(&[index as u8 + 1, 2]);
+            data.extend_from_slice(chunk);
+            bytes.extend(jpeg_segment(0xE2, &data));
+        }
+        bytes.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+
+        let segments = scan_segments(&bytes);
+        assert_eq!(segments.xmp.as_deref(), Some("<x:xmpmeta/>"));
+        assert_eq!(segments.icc.as_deref(), Some(profile.as_slice()));
+        assert_eq!(
+            icc_info(&profile),
+            IccInfo {
+                size: profile.len(),
+                color_space: Some("RGB".to_string()),
+                description: Some("sRGB".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_scan_webp_segments() {
+        let mut bytes = b"RIFF\0\0\0\0WEBP".to_vec();
+        for (kind, data) in [(b"VP8X", &b"0123456789"[..]), (b"XMP ", &b"<xmp/>"[..])] {
+            bytes.extend_from_slice(kind);
+            bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(data);
+            if data.len() % 2 == 1 {
+                bytes.push(0);
+            }
+        }
+        let segments = scan_segments(&bytes);
+        assert_eq!(segments.xmp.as_deref(), Some("<xmp/>"));
+        assert!(segments.icc.is_none());
+    }
+
+    #[test]
+    fn test_dms_to_degrees() {
+        let value = dms_to_degrees(&[31.0, 13.0, 48.0], false).unwrap();
+        assert!((value - 31.23).abs() < 1e-9);
+        assert_eq!(dms_to_degrees(&[121.0, 30.0, 0.0], true), Some(-121.5));
+        assert_eq!(dms_to_degrees(&[1.0, 2.0], false), None);
+        assert_eq!(dms_to_degrees(&[f64::NAN, 0.0, 0.0], false), None);
+    }
+
+    #[test]
+    fn test_read_metadata_png() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.png");
+        image::RgbImage::new(3, 2).save(&path).unwrap();
+        let metadata = read_metadata(&path).unwrap();
+        assert_eq!(metadata.format.as_deref(), Some("png"));
+        assert_eq!((metadata.width, metadata.height), (Some(3), Some(2)));
+        assert!(!metadata.has_gps);
+        assert!(metadata.exif.is_empty());
+        assert!(read_metadata(&dir.path().join("missing.png")).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+/*
+内置 Imgur 图床：
+1) 匿名模式：请求头 `Authorization: Client-ID <clientId>`，图片不归属任何账号，凭 deletehash 删除；
+2) 账号模式：通过 `imgur_login` 走 OAuth 授权（令牌保存在钥匙串 `oauth:imgur`），
+   上传时自动刷新令牌，图片归属登录账号，可选上传到指定相册；
+3) deleteId 中记录上传模式与 deletehash（账号模式额外记录图片 id）。
+*/
+
+use std::collections::HashMap;
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use bytes::Bytes;
+use log::{error, info};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tauri::AppHandle;
+
+use crate::image_hosts::resolve_plugin_secrets;
+use crate::internal_hosts::{
+    HostDeleteResult, HostHttpConfig, HostUploadResult, decode_delete_marker, encode_delete_marker,
+    load_host_settings, read_upload_file,
+};
+use crate::oauth::{OAuthClientConfig, oauth_authorize, oauth_get_token, oauth_logout};
+use crate::upload::run_cancellable;
+
+const PLUGIN_ID: &str = "imgur";
+const API_BASE: &str = "https://api.imgur.com/3";
+const AUTHORIZE_URL: &str = "https://api.imgur.com/oauth2/authorize";
+const TOKEN_URL: &str = "https://api.imgur.com/oauth2/token";
+const DEFAULT_TIMEOUT_MS: u64 = 60_000;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImgurMode {
+    #[default]
+    Anonymous,
+    Account,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImgurConfig {
+    pub client_id: String,
+    /// 账号模式换取令牌时需要
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub mode: ImgurMode,
+    /// 账号模式下上传到的相册 id
+    #[serde(default)]
+    pub album_id: Option<String>,
+    /// 在 Imgur 应用中登记的回调端口
+    #[serde(default)]
+    pub redirect_port: Option<u16>,
+    #[serde(flatten)]
+    pub http: HostHttpConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImgurDeleteMarker {
+    mode: ImgurMode,
+    delete_hash: String,
+    #[serde(default)]
+    id: Option<String>,
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+impl ImgurConfig {
+    fn oauth_config(&self) -> OAuthClientConfig {
+        OAuthClientConfig {
+            provider_id: PLUGIN_ID.to_string(),
+            client_id: self.client_id.trim().to_string(),
+            client_secret: non_empty(self.client_secret.as_deref()).map(str::to_string),
+            authorize_url: Some(AUTHORIZE_URL.to_string()),
+            token_url: TOKEN_URL.to_string(),
+            device_authorization_url: None,
+            scopes: Vec::new(),
+            extra_params: HashMap::new(),
+            redirect_port: self.redirect_port,
+            // Imgur 不支持 PKCE，依赖 client_secret 换取令牌
+            use_pkce: false,
+        }
+    }
+
+    /// 按模式生成 Authorization 请求头
+    async fn authorization(&self, mode: ImgurMode) -> Result<String, String> {
+        match mode {
+            ImgurMode::Anonymous => Ok(format!("Client-ID {}", self.client_id.trim())),
+            ImgurMode::Account => {
+                let token = oauth_get_token(self.oauth_config())
+                    .await?
+                    .ok_or_else(|| "尚未登录 Imgur 账号，请先在插件设置中登录".to_string())?;
+                Ok(format!("Bearer {}", token.access_token))
+            }
+        }
+    }
+
+    fn client(&self) -> Result<reqwest::Client, String> {
+        self.http.client(DEFAULT_TIMEOUT_MS)
+    }
+}
+
+/// 解析 Imgur 统一的 `{ data, success, status }` 响应
+async fn parse_response(response: reqwest::Response) -> Result<Value, String> {
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    let payload: Value = serde_json::from_str(&text)
+        .map_err(|_| format!("Imgur 返回了无法解析的响应: HTTP {} {}", status, text))?;
+    if status.is_success() && payload.get("success").and_then(Value::as_bool) != Some(false) {
+        return Ok(payload.get("data").cloned().unwrap_or(Value::Null));
+    }
+    let message = payload
+        .pointer("/data/error/message")
+        .or_else(|| payload.pointer("/data/error"))
+        .and_then(Value::as_str)
+        .unwrap_or(&text)
+        .to_string();
+    Err(format!("HTTP {} {}", status, message))
+}
+
+#[tauri::command]
+pub async fn imgur_upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: ImgurConfig,
+    upload_id: Option<String>,
+) -> Result<HostUploadResult, String> {
+    run_cancellable(upload_id, upload(file_path, original_file_name, config)).await
+}
+
+async fn upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: ImgurConfig,
+) -> Result<HostUploadResult, String> {
+    let (file_name, bytes) = read_upload_file(&file_path, original_file_name).await?;
+
+    let authorization = config.authorization(config.mode).await?;
+    let mut form = url::form_urlencoded::Serializer::new(String::new());
+    form.append_pair("image", &BASE64.encode(&bytes))
+        .append_pair("type", "base64")
+        .append_pair("name", &file_name);
+    if config.mode == ImgurMode::Account
+        && let Some(album) = non_empty(config.album_id.as_deref())
+    {
+        form.append_pair("album", album);
+    }
+    let body = Bytes::from(form.finish());
+
+    let client = config.client()?;
+    let url = format!("{}/image", API_BASE);
+    let throttle = config.http.throttle(&url);
+    let response = config
+        .http
+        .send("Imgur upload", || {
+            Ok(client
+                .post(&url)
+                .header(reqwest::header::AUTHORIZATION, &authorization)
+                .header(
+                    reqwest::header::CONTENT_TYPE,
+                    "application/x-www-form-urlencoded",
+                )
+                .header(reqwest::header::CONTENT_LENGTH, body.len())
+                .body(throttle.body(body.clone())))
+        })
+        .await
+        .map_err(|e| format!("Imgur 上传失败: {}", e))?;
+    let data = parse_response(response).await.map_err(|err| {
+        error!("imgur_upload failed: file={}, error={}", file_name, err);
+        format!("Imgur 上传失败: {}", err)
+    })?;
+
+    let field = |key: &str| data.get(key).and_then(Value::as_str).map(str::to_string);
+    let url = field("link").ok_or_else(|| "Imgur 响应缺少 link".to_string())?;
+    let delete_hash = field("deletehash").ok_or_else(|| "Imgur 响应缺少 deletehash".to_string())?;
+    let delete_id = encode_delete_marker(&ImgurDeleteMarker {
+        mode: config.mode,
+        delete_hash,
+        id: field("id"),
+    })?;
+
+    info!(
+        "imgur_upload success: mode={:?}, id={}",
+        config.mode,
+        field("id").unwrap_or_default()
+    );
+    Ok(HostUploadResult {
+        url,
+        delete_id,
+        metadata: Some(json!({
+            "id": data.get("id"),
+            "width": data.get("width"),
+            "height": data.get("height"),
+        })),
+    })
+}
+
+#[tauri::command]
+pub async fn imgur_delete(app: AppHandle, delete_id: String) -> Result<HostDeleteResult, String> {
+    let marker: ImgurDeleteMarker = decode_delete_marker(&delete_id)?;
+    let config: ImgurConfig = load_host_settings(&app, PLUGIN_ID)?;
+
+    // 匿名图片只能以 deletehash 删除；账号图片用图片 id 删除，不依赖 deletehash
+    let target = match (marker.mode, marker.id.as_deref()) {
+        (ImgurMode::Account, Some(id)) => id.to_string(),
+        _ => marker.delete_hash.clone(),
+    };
+    let authorization = config.authorization(marker.mode).await?;
+    let client = config.client()?;
+    let url = format!("{}/image/{}", API_BASE, target);
+    let response = config
+        .http
+        .send("Imgur delete", || {
+            Ok(client
+                .delete(&url)
+                .header(reqwest::header::AUTHORIZATION, &authorization))
+        })
+        .await
+        .map_err(|e| format!("Imgur 删除失败: {}", e))?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(HostDeleteResult::deleted("图片已不存在"));
+    }
+    parse_response(response)
+        .await
+        .map_err(|err| format!("Imgur 删除失败: {}", err))?;
+
+    info!("imgur_delete success: mode={:?}", marker.mode);
+    Ok(HostDeleteResult::deleted("图片已从 Imgur 删除"))
+}
+
+/// 打开浏览器登录 Imgur 账号，返回登录的用户名
+#[tauri::command]
+pub async fn imgur_login(app: AppHandle, config: Value) -> Result<Option<String>, String> {
+    // 设置页传入的配置中 Client Secret 可能是钥匙串占位符
+    let config: ImgurConfig = serde_json::from_value(resolve_plugin_secrets(PLUGIN_ID, config)?)
+        .map_err(|e| format!("invalid imgur settings: {e}"))?;
+    if non_empty(config.client_secret.as_deref()).is_none() {
+        return Err("账号模式需要填写 Client Secret".to_string());
+    }
+    let token = oauth_authorize(app, config.oauth_config()).await?;
+    Ok(token
+        .extra
+        .get("account_username")
+        .and_then(Value::as_str)
+        .map(str::to_string))
+}
+
+#[tauri::command]
+pub fn imgur_logout() -> Result<(), String> {
+    oauth_logout(PLUGIN_ID.to_string())
+}
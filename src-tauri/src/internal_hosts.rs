@@ -0,0 +1,262 @@
+/*
+内置图床后端的公共部分：
+1) `INTERNAL_PLUGINS` 列出由后端实现的图床，插件列表中以 `__internal__/<id>` 注册，
+   前端只负责收集参数并调用对应命令；
+2) 上传 / 删除结果与 S3 后端保持同样的结构（url、deleteId、metadata）；
+3) 删除时由后端读取已保存的配置并解析钥匙串字段，前端无需再传递凭据；
+4) 远程路径模板：`{year}` `{month}` `{day}` `{uuid}` `{filename}` `{name}` `{ext}`，
+   默认 `{year}/{month}/{day}/{uuid}-{filename}`；
+5) HTTP 图床的配置平铺 `HostHttpConfig`：超时、重试策略、限速、mTLS 客户端证书与重定向 / DNS 覆盖，
+   与 `upload_image`、S3 共用同一套实现（upload::send_with_retry、throttle、tls、http_client）；
+6) 上传命令接受可选的 `upload_id`，可通过 `cancel_upload` 取消。
+*/
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use chrono::Local;
+use reqwest::multipart;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use tauri::AppHandle;
+use uuid::Uuid;
+
+use crate::http_client::{HttpClientOptions, apply_http_options};
+use crate::image_hosts::{ensure_config_path, read_settings, resolve_plugin_secrets};
+use crate::throttle::Throttle;
+use crate::tls::{ClientCertificate, apply_client_certificate};
+use crate::upload::{RetryPolicy, send_with_retry};
+
+/// 由后端实现的内置图床 id
+pub(crate) const INTERNAL_PLUGINS: &[&str] = &[
+    "azure",
+    "chevereto",
+    "cloudinary",
+    "custom",
+    "gcs",
+    "github",
+    "imgur",
+    "local",
+    "s3",
+    "sftp",
+    "smms",
+    "webdav",
+];
+
+const DEFAULT_PATH_TEMPLATE: &str = "{year}/{month}/{day}/{uuid}-{filename}";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostUploadResult {
+    pub url: String,
+    pub delete_id: String,
+    pub metadata: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostDeleteResult {
+    pub success: bool,
+    pub message: Option<String>,
+}
+
+impl HostDeleteResult {
+    pub(crate) fn deleted(message: &str) -> Self {
+        Self {
+            success: true,
+            message: Some(message.to_string()),
+        }
+    }
+}
+
+/// 内置 HTTP 图床共用的网络选项，在各图床的配置中平铺
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostHttpConfig {
+    /// 整个请求的超时（毫秒），默认值由各图床决定
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// 重试策略，默认不重试
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// 该图床的上传限速（KB/s），与全局限速同时生效
+    #[serde(default)]
+    pub rate_limit_kbps: Option<u32>,
+    /// 图床位于 mTLS 反向代理之后时使用的客户端证书
+    #[serde(default)]
+    pub client_certificate: Option<ClientCertificate>,
+    /// 重定向策略与 DNS 覆盖
+    #[serde(default)]
+    pub http_options: Option<HttpClientOptions>,
+}
+
+impl HostHttpConfig {
+    /// 应用超时、客户端证书与重定向 / DNS 覆盖后的客户端构建器，图床可再追加自己的设置
+    pub(crate) fn client_builder(
+        &self,
+        default_timeout_ms: u64,
+    ) -> Result<reqwest::ClientBuilder, String> {
+        let builder = reqwest::Client::builder().timeout(Duration::from_millis(
+            self.timeout_ms.unwrap_or(default_timeout_ms),
+        ));
+        let builder = apply_client_certificate(builder, self.client_certificate.as_ref())?;
+        apply_http_options(builder, self.http_options.as_ref())
+    }
+
+    pub(crate) fn client(&self, default_timeout_ms: u64) -> Result<reqwest::Client, String> {
+        self.client_builder(default_timeout_ms)?
+            .build()
+            .map_err(|e| format!("failed to build http client: {}", e))
+    }
+
+    /// 发往 `url` 的请求体适用的限速
+    pub(crate) fn throttle(&self, url: &str) -> Throttle {
+        Throttle::for_url(url, self.rate_limit_kbps)
+    }
+
+    /// 限速后的 multipart 文件字段
+    pub(crate) fn file_part(&self, url: &str, bytes: &Bytes, file_name: &str) -> multipart::Part {
+        multipart::Part::stream_with_length(
+            self.throttle(url).body(bytes.clone()),
+            bytes.len() as u64,
+        )
+        .file_name(file_name.to_string())
+    }
+
+    /// 按重试策略发送请求，`build` 在每次尝试时重新构建请求
+    pub(crate) async fn send<F>(&self, context: &str, build: F) -> Result<reqwest::Response, String>
+    where
+        F: FnMut() -> Result<reqwest::RequestBuilder, String>,
+    {
+        send_with_retry(&self.retry, context, build).await
+    }
+}
+
+/// 读取待上传的文件：返回上传使用的文件名（未指定时取路径中的文件名）与文件内容
+pub(crate) async fn read_upload_file(
+    file_path: &str,
+    original_file_name: Option<String>,
+) -> Result<(String, Bytes), String> {
+    let file_name = original_file_name
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| {
+            std::path::Path::new(file_path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "upload.bin".to_string())
+        });
+    let bytes = tokio::fs::read(file_path)
+        .await
+        .map_err(|e| format!("failed to read file {}: {}", file_path, e))?;
+    Ok((file_name, Bytes::from(bytes)))
+}
+
+/// 读取内置图床已保存的配置（钥匙串字段已解析为真实值）
+pub(crate) fn load_host_settings<T: DeserializeOwned>(
+    app: &AppHandle,
+    plugin_id: &str,
+) -> Result<T, String> {
+    let values = read_settings(&ensure_config_path(app)?)?
+        .plugins
+        .remove(plugin_id)
+        .ok_or_else(|| format!("请先在 {} 插件设置中完成配置", plugin_id))?;
+    let values = resolve_plugin_secrets(plugin_id, values)?;
+    serde_json::from_value(values).map_err(|e| format!("invalid {} settings: {e}", plugin_id))
+}
+
+/// 将文件名中的非常见字符替换为下划线，避免远程路径需要额外转义
+pub(crate) fn sanitize_file_name(input: &str) -> String {
+    let trimmed = input.trim();
+    let candidate = if trimmed.is_empty() {
+        "upload.bin"
+    } else {
+        trimmed
+    };
+    candidate
+        .chars()
+        .map(|ch| match ch {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => ch,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// 渲染远程路径模板，返回不带首尾斜杠的相对路径
+pub(crate) fn render_path_template(template: Option<&str>, original_name: &str) -> String {
+    let template = template
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or(DEFAULT_PATH_TEMPLATE);
+    let filename = sanitize_file_name(original_name);
+    let (name, ext) = match filename.rsplit_once('.') {
+        Some((name, ext)) if !name.is_empty() => (name.to_string(), ext.to_string()),
+        _ => (filename.clone(), String::new()),
+    };
+    let now = Local::now();
+    let rendered = template
+        .replace("{year}", &now.format("%Y").to_string())
+        .replace("{month}", &now.format("%m").to_string())
+        .replace("{day}", &now.format("%d").to_string())
+        .replace("{uuid}", &Uuid::new_v4().to_string())
+        .replace("{filename}", &filename)
+        .replace("{name}", &name)
+        .replace("{ext}", &ext);
+    rendered
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != "." && *segment != "..")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 拼接目录前缀与相对路径
+pub(crate) fn join_remote_path(prefix: Option<&str>, path: &str) -> String {
+    match prefix
+        .map(|p| p.trim_matches('/'))
+        .filter(|p| !p.is_empty())
+    {
+        Some(prefix) => format!("{}/{}", prefix, path),
+        None => path.to_string(),
+    }
+}
+
+/// 将相对路径拼接到公开访问根地址后；模板中的 `{path}` `{filename}` 会被替换
+pub(crate) fn build_public_url(template_or_base: &str, path: &str) -> String {
+    let filename = path.rsplit('/').next().unwrap_or(path);
+    if template_or_base.contains("{path}") || template_or_base.contains("{filename}") {
+        template_or_base
+            .replace("{path}", path)
+            .replace("{filename}", filename)
+    } else {
+        format!("{}/{}", template_or_base.trim_end_matches('/'), path)
+    }
+}
+
+pub(crate) fn encode_delete_marker<T: Serialize>(marker: &T) -> Result<String, String> {
+    serde_json::to_string(marker).map_err(|e| format!("serialize delete marker: {e}"))
+}
+
+pub(crate) fn decode_delete_marker<T: DeserializeOwned>(delete_id: &str) -> Result<T, String> {
+    serde_json::from_str(delete_id).map_err(|err| format!("invalid deleteId payload: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_path_template() {
+        assert_eq!(
+            render_path_template(Some("/images/{name}.{ext}"), "my photo.png"),
+            "images/my_photo.png"
+        );
+        assert_eq!(
+            render_path_template(Some("../{filename}"), "a.jpg"),
+            "a.jpg"
+        );
+        assert_eq!(join_remote_path(Some("/blog/"), "a.jpg"), "blog/a.jpg");
+        assert_eq!(
+            build_public_url("https://cdn.example.com/", "blog/a.jpg"),
+            "https://cdn.example.com/blog/a.jpg"
+        );
+    }
+}
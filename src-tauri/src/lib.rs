@@ -1,35 +1,157 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+mod analytics;
+mod annotate;
+mod app_config;
+mod archive_upload;
+mod azure_blob;
+mod batch_upload;
+#[cfg(desktop)]
+mod capture;
+mod cdn_purge;
+mod chevereto;
+#[cfg(desktop)]
+mod cli;
+mod clipboard_watcher;
+mod cloudinary;
+#[cfg(desktop)]
+mod context_menu;
+mod cookies;
+mod custom_http;
+mod dedup;
+mod deep_link;
+mod diagnostics;
+mod document_images;
+mod encoder_benchmark;
+mod encrypted_upload;
+mod fast_resize;
+mod feed;
 mod file_picker;
+mod filename_template;
+mod folder_import;
 mod gallery;
+mod gallery_site;
+mod gallery_trash;
+mod gcs;
+mod github;
+mod heic;
+mod host_bundle;
+mod host_diagnostics;
+mod host_migration;
+mod host_upload;
+mod host_usage;
+#[cfg(desktop)]
+mod hotkeys;
+mod http_client;
 mod image_hosts;
+mod image_metadata;
+mod imgur;
+mod internal_hosts;
+mod link_format;
+mod link_health;
+mod local_folder;
+mod logging;
+mod metadata_privacy;
+mod network_status;
+mod notifier;
+mod oauth;
+mod ocr;
+mod pending_uploads;
+mod picgo_import;
+#[cfg(desktop)]
+mod picgo_server;
+mod plugin_permissions;
+mod plugin_runtime;
+mod plugin_updates;
+mod plugin_watcher;
+mod portable;
 mod process;
+mod quality_metrics;
+mod rehost;
 mod s3;
+mod s3_presets;
+mod secrets;
 mod settings;
+mod settings_secrets;
+mod settings_validation;
+mod settings_watcher;
+mod sftp;
+mod share_target;
+mod sharex;
+mod smms;
+mod telegram;
+mod temp_cleanup;
+mod throttle;
 mod thumbnail;
+mod tiling;
+mod tls;
+#[cfg(desktop)]
+mod tray;
 mod upload;
+mod upload_queue;
+#[cfg(desktop)]
+mod watch_folder;
+mod webdav;
+mod webhooks;
 
+use tauri::Manager;
 use tauri_plugin_log::{RotationStrategy, Target, TargetKind};
 
+/// 显示并聚焦主窗口（托盘、快捷键、深层链接等入口共用）
+pub(crate) fn show_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// 应用上下文（图形界面与命令行模式共用，避免重复嵌入前端资源）
+fn context() -> tauri::Context<tauri::Wry> {
+    tauri::generate_context!()
+}
+
+/// 命令行入口：参数为命令行子命令（如 `upload`）时执行并返回退出码，否则返回 None
+#[cfg(desktop)]
+pub fn run_cli() -> Option<i32> {
+    cli::run_from_env()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let is_dev = cfg!(debug_assertions);
-    let log_level = if is_dev {
-        log::LevelFilter::Debug
-    } else {
-        log::LevelFilter::Info
-    };
 
+    // 便携模式下日志写入可执行文件旁的 data/logs
+    let file_target = || match portable::portable_root() {
+        Some(root) => Target::new(TargetKind::Folder {
+            path: root.join("logs"),
+            file_name: None,
+        }),
+        None => Target::new(TargetKind::LogDir { file_name: None }),
+    };
     let log_targets = if is_dev {
         vec![
-            Target::new(TargetKind::LogDir { file_name: None }),
+            file_target(),
             Target::new(TargetKind::Stdout),
             Target::new(TargetKind::Webview),
         ]
     } else {
-        vec![Target::new(TargetKind::LogDir { file_name: None })]
+        vec![file_target()]
     };
 
-    tauri::Builder::default()
+    let mut builder = tauri::Builder::default();
+    // 单实例须最先注册：再次启动（如打开 yana:// 链接、右键菜单上传）时转发到已运行的实例
+    #[cfg(desktop)]
+    {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            // 右键菜单「使用 Yana 上传」再次启动时携带要上传的文件
+            if !context_menu::handle_args(app, &args) {
+                show_main_window(app);
+            }
+        }));
+    }
+
+    builder
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_opener::init())
@@ -37,6 +159,7 @@ pub fn run() {
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_os::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_android_fs::init())
         // 日志：根据环境选择输出目标与日志级别，开发环境输出到控制台/前端，生产仅写文件
         .plugin(
@@ -44,7 +167,8 @@ pub fn run() {
                 .rotation_strategy(RotationStrategy::KeepSome(5))
                 .max_file_size(128u128 * 1024 * 1024)
                 .targets(log_targets)
-                .level(log_level)
+                // 插件按最详细级别初始化，实际级别由 logging::apply_level 在运行时控制
+                .level(log::LevelFilter::Trace)
                 .format(move |out, message, record| {
                     out.finish(format_args!(
                         "{} [{}] [{}] {}",
@@ -56,27 +180,199 @@ pub fn run() {
                 })
                 .build(),
         )
+        .setup(|app| {
+            if let Some(root) = portable::portable_root() {
+                log::info!("portable mode enabled: data_dir={}", root.display());
+            }
+            diagnostics::init(app.handle());
+            // 上传队列并发数取自设置，保存设置时同步更新
+            let settings = settings::current_settings(app.handle());
+            app.manage(upload_queue::UploadQueue::new(
+                settings.max_concurrent_uploads as usize,
+            ));
+            throttle::set_global_limit(settings.upload_rate_limit_kbps);
+            filename_template::set_global_template(&settings.file_name_template);
+            logging::apply_level(&settings.log_level);
+            clipboard_watcher::apply(settings.clipboard_watch, settings.clipboard_auto_upload);
+            clipboard_watcher::init(app.handle());
+            link_health::init(app.handle());
+            network_status::init(app.handle());
+            share_target::init(app.handle());
+            #[cfg(desktop)]
+            {
+                app.handle().plugin(
+                    tauri_plugin_global_shortcut::Builder::new()
+                        .with_handler(hotkeys::handle)
+                        .build(),
+                )?;
+                hotkeys::apply(app.handle(), &settings);
+                picgo_server::apply(app.handle(), &settings);
+                watch_folder::apply(app.handle(), &settings);
+                context_menu::init(app.handle());
+                if let Err(err) = tray::init(app.handle()) {
+                    log::warn!("init tray failed: {}", err);
+                }
+            }
+            deep_link::init(app.handle());
+            cookies::init(app.handle());
+            secrets::init(app.handle());
+            if let Err(err) = image_hosts::migrate_plaintext_secrets(app.handle()) {
+                log::warn!("migrate plaintext secrets failed: {}", err);
+            }
+            if let Err(err) = settings_secrets::migrate_plaintext_settings_secrets(app.handle()) {
+                log::warn!("migrate plaintext settings secrets failed: {}", err);
+            }
+            plugin_watcher::init(app.handle());
+            settings_watcher::init(app.handle());
+            // 恢复上次崩溃或退出时未完成的上传
+            pending_uploads::resume_pending(app.handle());
+            temp_cleanup::on_startup(app.handle());
+            gallery_trash::init(app.handle());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             process::compress_images,
             process::compress_image_data,
             process::save_image_data,
             process::save_files,
-            process::clean_app_temp_dir,
+            process::save_processed_to_default,
+            temp_cleanup::clean_app_temp_dir,
             process::get_file_sizes,
+            quality_metrics::compare_quality,
+            encoder_benchmark::benchmark_encoders,
             settings::load_settings,
             settings::save_settings,
+            settings_validation::validate_settings,
             settings::open_log_dir,
+            logging::get_log_level,
+            logging::set_log_level,
+            logging::export_logs,
+            portable::get_portable_status,
             image_hosts::list_image_host_plugins,
             image_hosts::load_image_host_settings,
             image_hosts::save_image_host_settings,
+            image_hosts::resolve_image_host_settings,
+            host_bundle::export_image_host_settings,
+            host_bundle::import_image_host_settings,
+            app_config::export_app_config,
+            app_config::preview_app_config,
+            app_config::import_app_config,
+            host_diagnostics::test_image_host,
+            image_hosts::inspect_image_host_plugin,
             image_hosts::add_image_host_plugin,
+            image_hosts::remove_image_host_plugin,
+            image_hosts::set_image_host_plugin_enabled,
+            image_hosts::get_image_host_plugin_permissions,
+            plugin_runtime::execute_plugin_upload,
+            plugin_runtime::execute_plugin_delete,
+            plugin_updates::check_plugin_updates,
+            plugin_updates::update_plugin,
+            secrets::secret_store,
+            secrets::secret_set,
+            secrets::secret_get,
+            secrets::secret_delete,
+            oauth::oauth_authorize,
+            oauth::oauth_device_start,
+            oauth::oauth_device_poll,
+            oauth::oauth_refresh,
+            oauth::oauth_get_token,
+            oauth::oauth_logout,
             upload::upload_image,
+            upload::upload_image_data,
+            upload::cancel_upload,
+            cookies::host_login,
+            cookies::host_logout,
+            upload_queue::upload_queue_status,
+            batch_upload::start_batch_upload,
+            batch_upload::batch_upload_status,
+            batch_upload::list_batch_uploads,
+            batch_upload::cancel_batch_upload,
+            folder_import::import_folder,
+            rehost::rehost_url,
+            document_images::rewrite_document_images,
+            tiling::upload_image_tiles,
+            archive_upload::upload_archive,
+            encrypted_upload::upload_encrypted,
+            encrypted_upload::save_decrypt_page,
+            host_migration::start_host_migration,
+            host_migration::host_migration_status,
+            host_migration::cancel_host_migration,
+            host_migration::open_migration_dir,
+            network_status::network_status,
+            image_metadata::read_image_metadata,
+            upload_queue::upload_queue_pause,
+            upload_queue::upload_queue_resume,
+            pending_uploads::pending_uploads_list,
+            pending_uploads::pending_uploads_retry,
+            pending_uploads::pending_uploads_discard,
             s3::s3_upload,
             s3::s3_delete,
+            s3::s3_head_object,
+            s3::s3_download,
+            s3::s3_upload_dir,
+            cdn_purge::purge_urls,
+            s3_presets::s3_list_presets,
+            webdav::webdav_upload,
+            webdav::webdav_delete,
+            webhooks::test_webhook,
+            telegram::test_telegram_post,
+            sftp::sftp_upload,
+            sftp::sftp_delete,
+            github::github_upload,
+            github::github_delete,
+            imgur::imgur_upload,
+            imgur::imgur_delete,
+            imgur::imgur_login,
+            imgur::imgur_logout,
+            chevereto::chevereto_upload,
+            chevereto::chevereto_delete,
+            smms::smms_upload,
+            smms::smms_delete,
+            smms::smms_profile,
+            host_upload::delete_image_from_host,
+            cloudinary::cloudinary_upload,
+            cloudinary::cloudinary_delete,
+            azure_blob::azure_blob_upload,
+            azure_blob::azure_blob_delete,
+            gcs::gcs_upload,
+            gcs::gcs_delete,
+            local_folder::local_folder_upload,
+            local_folder::local_folder_delete,
+            picgo_import::import_picgo_config,
+            custom_http::custom_http_upload,
+            custom_http::custom_http_delete,
+            sharex::export_sharex_uploader,
+            sharex::import_sharex_uploader,
+            host_usage::record_host_usage,
+            host_usage::set_host_quota,
+            host_usage::host_usage_stats,
+            analytics::record_upload_events,
+            analytics::upload_analytics,
             gallery::gallery_insert_item,
+            dedup::find_duplicate_uploads,
             gallery::gallery_delete_item,
             gallery::gallery_query_items,
             gallery::gallery_list_hosts,
+            gallery::gallery_ocr_text,
+            gallery::gallery_item_tags,
+            gallery::gallery_list_tags,
+            gallery::gallery_url_history,
+            gallery::gallery_archive_files,
+            feed::export_feed,
+            gallery_site::export_gallery_site,
+            gallery_trash::stage_gallery_delete,
+            gallery_trash::undo_delete,
+            ocr::ocr_image,
+            annotate::annotate_image,
+            link_health::check_gallery_links,
+            link_health::gallery_link_status,
+            diagnostics::report_frontend_error,
+            diagnostics::list_crash_reports,
+            diagnostics::delete_crash_reports,
+            diagnostics::submit_crash_reports,
+            link_format::list_link_formats,
+            link_format::format_links,
+            link_format::copy_links_as,
             thumbnail::generate_thumbnails,
             thumbnail::generate_thumbnails_from_local,
             thumbnail::get_thumbnail_path,
@@ -85,7 +381,27 @@ pub fn run() {
             file_picker::select_single_image,
             file_picker::select_multiple_images,
             file_picker::save_to_download_dir,
+            share_target::take_shared_images,
+            #[cfg(desktop)]
+            capture::capture_screen,
+            #[cfg(desktop)]
+            capture::list_monitors,
+            #[cfg(desktop)]
+            tray::recent_uploads,
+            #[cfg(desktop)]
+            picgo_server::picgo_server_status,
+            #[cfg(desktop)]
+            context_menu::context_menu_status,
+            #[cfg(desktop)]
+            context_menu::register_context_menu,
+            #[cfg(desktop)]
+            context_menu::unregister_context_menu,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                temp_cleanup::on_exit(app);
+            }
+        });
 }
@@ -0,0 +1,352 @@
+/*
+链接格式化：
+1) 内置格式：纯链接、Markdown、HTML、BBCode、Org-mode；设置 `linkTemplates` 中可覆盖内置格式
+   （同 id）或追加自定义格式；
+2) 模板占位符：`{url}` `{name}`（文件名）`{stem}`（不含扩展名）`{ext}` `{host}` `{width}` `{height}`
+   `{size}`（如 1.2 MB）`{bytes}` `{date}`（本地日期）`{id}`（图库 id），未知占位符原样保留；
+3) 条目可直接传入（上传结果），也可传入图库 id 由后端读取元数据；
+   宽高未提供时，若给出了本地文件路径则读取图片尺寸，否则留空；
+4) `copy_links_as` 渲染后直接写入剪贴板，供界面与托盘使用。
+*/
+
+use std::path::Path;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::gallery::GalleryItem;
+
+pub(crate) const PLACEHOLDERS: &[&str] = &[
+    "url", "name", "stem", "ext", "host", "width", "height", "size", "bytes", "date", "id",
+];
+
+const BUILTIN_TEMPLATES: &[(&str, &str, &str)] = &[
+    ("link", "纯链接", "{url}"),
+    ("markdown", "Markdown", "![{name}]({url})"),
+    ("html", "HTML", "<img src=\"{url}\" alt=\"{name}\" />"),
+    ("bbcode", "BBCode", "[img]{url}[/img]"),
+    ("org", "Org-mode", "[[{url}][{name}]]"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkTemplate {
+    pub id: String,
+    pub name: String,
+    pub template: String,
+}
+
+/// 待格式化的条目；`path` 为本地文件，仅用于读取宽高
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkItem {
+    pub url: String,
+    #[serde(default)]
+    pub file_name: Option<String>,
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub filesize: Option<i64>,
+    #[serde(default)]
+    pub inserted_at: Option<String>,
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub id: Option<i64>,
+}
+
+impl From<GalleryItem> for LinkItem {
+    fn from(item: GalleryItem) -> Self {
+        Self {
+            url: item.url,
+            file_name: Some(item.file_name).filter(|name| !name.is_empty()),
+            host: Some(item.host),
+            filesize: item.filesize,
+            inserted_at: Some(item.inserted_at),
+            id: Some(item.id),
+            ..Default::default()
+        }
+    }
+}
+
+/// 内置格式与设置中的自定义格式（同 id 时自定义覆盖内置）
+pub(crate) fn templates(custom: &[LinkTemplate]) -> Vec<LinkTemplate> {
+    let mut result: Vec<LinkTemplate> = BUILTIN_TEMPLATES
+        .iter()
+        .map(|(id, name, template)| LinkTemplate {
+            id: id.to_string(),
+            name: name.to_string(),
+            template: template.to_string(),
+        })
+        .collect();
+    for template in custom {
+        match result.iter_mut().find(|t| t.id == template.id) {
+            Some(existing) => *existing = template.clone(),
+            None => result.push(template.clone()),
+        }
+    }
+    result
+}
+
+/// 模板中不支持的占位符
+pub(crate) fn unknown_placeholders(template: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start + 1..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + len];
+        if !PLACEHOLDERS.contains(&name) && !unknown.iter().any(|u| u == name) {
+            unknown.push(name.to_string());
+        }
+        rest = &rest[start + 1 + len + 1..];
+    }
+    unknown
+}
+
+fn file_name_of(item: &LinkItem) -> String {
+    if let Some(name) = item.file_name.as_deref().filter(|name| !name.is_empty()) {
+        return name.to_string();
+    }
+    url::Url::parse(&item.url)
+        .ok()
+        .and_then(|url| {
+            url.path_segments()?
+                .filter(|segment| !segment.is_empty())
+                .next_back()
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| "image".to_string())
+}
+
+fn human_size(bytes: i64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
+    let mut size = bytes.max(0) as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes.max(0))
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+fn placeholder_value(name: &str, item: &LinkItem, file_name: &str) -> Option<String> {
+    let path = Path::new(file_name);
+    let value = match name {
+        "url" => item.url.clone(),
+        "name" => file_name.to_string(),
+        "stem" => path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        "ext" => path
+            .extension()
+            .map(|ext| ext.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        "host" => item.host.clone().unwrap_or_default(),
+        "width" => item.width.map(|w| w.to_string()).unwrap_or_default(),
+        "height" => item.height.map(|h| h.to_string()).unwrap_or_default(),
+        "size" => item.filesize.map(human_size).unwrap_or_default(),
+        "bytes" => item.filesize.map(|b| b.to_string()).unwrap_or_default(),
+        "date" => item
+            .inserted_at
+            .as_deref()
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .map(|dt| dt.with_timezone(&Local).format("%Y-%m-%d").to_string())
+            .unwrap_or_default(),
+        "id" => item.id.map(|id| id.to_string()).unwrap_or_default(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// 按模板渲染单个条目
+pub(crate) fn render(template: &str, item: &LinkItem) -> String {
+    let file_name = file_name_of(item);
+    let mut output = String::with_capacity(template.len() + item.url.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after
+            .find('}')
+            .and_then(|len| Some((len, placeholder_value(&after[..len], item, &file_name)?)))
+        {
+            Some((len, value)) => {
+                output.push_str(&value);
+                rest = &after[len + 1..];
+            }
+            None => {
+                output.push('{');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// 补全缺失的宽高（读取本地文件头）
+fn fill_dimensions(item: &mut LinkItem) {
+    if item.width.is_some() && item.height.is_some() {
+        return;
+    }
+    if let Some((width, height)) = item
+        .path
+        .as_deref()
+        .and_then(|path| image::image_dimensions(path).ok())
+    {
+        item.width.get_or_insert(width);
+        item.height.get_or_insert(height);
+    }
+}
+
+fn collect_items(
+    app: &AppHandle,
+    items: Option<Vec<LinkItem>>,
+    gallery_ids: Option<Vec<i64>>,
+) -> Result<Vec<LinkItem>, String> {
+    let mut result = items.unwrap_or_default();
+    if let Some(ids) = gallery_ids.filter(|ids| !ids.is_empty()) {
+        let store = crate::gallery::store_from_app(app)?;
+        let gallery = store.get_many(&ids).map_err(|err| err.to_string())?;
+        result.extend(gallery.into_iter().map(LinkItem::from));
+    }
+    for item in &mut result {
+        fill_dimensions(item);
+    }
+    Ok(result)
+}
+
+/// 按格式 id 渲染多个条目，每行一条
+pub(crate) fn format_items(
+    app: &AppHandle,
+    format: &str,
+    items: &[LinkItem],
+) -> Result<String, String> {
+    let custom = crate::settings::current_settings(app).link_templates;
+    let template = templates(&custom)
+        .into_iter()
+        .find(|t| t.id == format)
+        .ok_or_else(|| format!("未知的链接格式: {format}"))?;
+    Ok(items
+        .iter()
+        .map(|item| render(&template.template, item))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// 按设置中的默认格式复制图库中某条链接（托盘使用），图库中找不到时仅用链接渲染
+pub(crate) fn copy_url_as_default(app: &AppHandle, url: &str) -> Result<String, String> {
+    let format = crate::settings::current_settings(app).default_link_format;
+    let item = crate::gallery::store_from_app(app)
+        .ok()
+        .and_then(|store| store.find_by_url(url).ok().flatten())
+        .map(LinkItem::from)
+        .unwrap_or_else(|| LinkItem {
+            url: url.to_string(),
+            ..Default::default()
+        });
+    let text = format_items(app, &format, &[item])?;
+    app.clipboard()
+        .write_text(text.clone())
+        .map_err(|e| format!("写入剪贴板失败: {e}"))?;
+    Ok(text)
+}
+
+#[tauri::command]
+pub fn list_link_formats(app: AppHandle) -> Vec<LinkTemplate> {
+    templates(&crate::settings::current_settings(&app).link_templates)
+}
+
+#[tauri::command]
+pub fn format_links(
+    app: AppHandle,
+    format: String,
+    items: Option<Vec<LinkItem>>,
+    gallery_ids: Option<Vec<i64>>,
+) -> Result<String, String> {
+    let items = collect_items(&app, items, gallery_ids)?;
+    format_items(&app, &format, &items)
+}
+
+/// 渲染并写入剪贴板，返回写入的文本
+#[tauri::command]
+pub fn copy_links_as(
+    app: AppHandle,
+    format: String,
+    items: Option<Vec<LinkItem>>,
+    gallery_ids: Option<Vec<i64>>,
+) -> Result<String, String> {
+    let text = format_links(app.clone(), format, items, gallery_ids)?;
+    app.clipboard()
+        .write_text(text.clone())
+        .map_err(|e| format!("写入剪贴板失败: {e}"))?;
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let item = LinkItem {
+            url: "https://img.example.com/2024/cat.png".to_string(),
+            host: Some("s3".to_string()),
+            filesize: Some(1536),
+            width: Some(640),
+            height: Some(480),
+            ..Default::default()
+        };
+        assert_eq!(
+            render("![{name}]({url})", &item),
+            "![cat.png](https://img.example.com/2024/cat.png)"
+        );
+        assert_eq!(
+            render("<img src=\"{url}\" width=\"{width}\">", &item),
+            "<img src=\"https://img.example.com/2024/cat.png\" width=\"640\">"
+        );
+        assert_eq!(
+            render("{stem}.{ext} {size} {host} {unknown} {", &item),
+            "cat.png 1.5 KB s3 {unknown} {"
+        );
+    }
+
+    #[test]
+    fn test_templates_and_placeholders() {
+        let custom = vec![
+            LinkTemplate {
+                id: "markdown".to_string(),
+                name: "Markdown".to_string(),
+                template: "![]({url})".to_string(),
+            },
+            LinkTemplate {
+                id: "wiki".to_string(),
+                name: "Wiki".to_string(),
+                template: "[[File:{name}]]".to_string(),
+            },
+        ];
+        let merged = templates(&custom);
+        assert_eq!(merged.len(), BUILTIN_TEMPLATES.len() + 1);
+        assert_eq!(
+            merged.iter().find(|t| t.id == "markdown").unwrap().template,
+            "![]({url})"
+        );
+        assert_eq!(
+            unknown_placeholders("{url} {foo} {foo} {width}"),
+            vec!["foo"]
+        );
+    }
+}
@@ -0,0 +1,477 @@
+/*
+图库链接健康检查：
+1) `check_gallery_links(ids)` 检查指定条目，留空时按最久未检查的顺序取一批；
+   先发 HEAD，服务端不支持时退回只取首字节的 GET；2xx 为可用，404 / 410 为失效，
+   其他状态码与网络错误记为不确定（可能是暂时故障），保留此前的判定；
+   S3 条目（带删除标记）的公开链接在私有桶上无法访问，改用已保存的凭据对对象发带签名的 HEAD
+   （`s3::s3_head_object`），对象不存在为失效；未保存 S3 配置时仍检查公开链接；
+2) 定时复查：开启后按设置的间隔在后台轮换检查一批链接，
+   仅在接通电源（可在设置中放宽）且上传队列空闲时执行，避免占用电量与上传带宽；
+3) 此前可用的链接变为失效时发送 `link-health://dead` 事件并记录日志。
+*/
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, stream};
+use log::{info, warn};
+use reqwest::{Client, StatusCode, header};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::gallery::GalleryItem;
+use crate::s3::{S3CredentialInput, S3HeadResult};
+use crate::upload_queue::UploadQueue;
+
+const DEAD_LINKS_EVENT: &str = "link-health://dead";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const CONCURRENCY: usize = 4;
+/// 定时任务检查是否到期的间隔
+const SCHEDULER_TICK: Duration = Duration::from_secs(5 * 60);
+/// 手动检查且未指定条目时的数量
+const DEFAULT_BATCH: usize = 50;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkState {
+    Alive,
+    Dead,
+    /// 超时、5xx、限流等无法判断的情况
+    Unknown,
+}
+
+impl LinkState {
+    fn stored(self) -> Option<&'static str> {
+        match self {
+            Self::Alive => Some("alive"),
+            Self::Dead => Some("dead"),
+            Self::Unknown => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkCheckResult {
+    pub id: i64,
+    pub url: String,
+    pub file_name: String,
+    pub state: LinkState,
+    pub http_status: Option<u16>,
+    pub error: Option<String>,
+    /// 此前可用、本次判定为失效
+    pub newly_dead: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkStatus {
+    pub id: i64,
+    pub state: LinkState,
+    pub checked_at: String,
+}
+
+fn classify(status: StatusCode) -> LinkState {
+    if status.is_success() {
+        LinkState::Alive
+    } else if status == StatusCode::NOT_FOUND || status == StatusCode::GONE {
+        LinkState::Dead
+    } else {
+        LinkState::Unknown
+    }
+}
+
+/// 不支持 HEAD 的服务端常见的响应
+fn head_unsupported(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::METHOD_NOT_ALLOWED
+            | StatusCode::FORBIDDEN
+            | StatusCode::NOT_IMPLEMENTED
+            | StatusCode::BAD_REQUEST
+    )
+}
+
+async fn check_url(client: &Client, url: &str) -> (LinkState, Option<u16>, Option<String>) {
+    let mut response = client.head(url).send().await;
+    if matches!(&response, Ok(resp) if head_unsupported(resp.status())) {
+        response = client
+            .get(url)
+            .header(header::RANGE, "bytes=0-0")
+            .send()
+            .await;
+    }
+    match response {
+        Ok(resp) => {
+            let status = resp.status();
+            let state = classify(status);
+            let error = (state != LinkState::Alive).then(|| format!("HTTP {status}"));
+            (state, Some(status.as_u16()), error)
+        }
+        Err(err) => (LinkState::Unknown, None, Some(err.to_string())),
+    }
+}
+
+/// 带删除标记的 S3 条目通过签名 HEAD 检查对象本身
+fn s3_marker(item: &GalleryItem) -> Option<&str> {
+    item.delete_marker
+        .as_deref()
+        .filter(|marker| item.host == "s3" && !marker.trim().is_empty())
+}
+
+/// 签名 HEAD 的结果：对象存在为可用，不存在为失效，其他错误为不确定
+fn classify_head(result: Result<S3HeadResult, String>) -> (LinkState, Option<u16>, Option<String>) {
+    match result {
+        Ok(head) if head.exists => (LinkState::Alive, Some(StatusCode::OK.as_u16()), None),
+        Ok(_) => (
+            LinkState::Dead,
+            Some(StatusCode::NOT_FOUND.as_u16()),
+            Some("S3 object not found".to_string()),
+        ),
+        Err(err) => (LinkState::Unknown, None, Some(err)),
+    }
+}
+
+/// 已保存的 S3 凭据（已解析钥匙串字段），未配置时为 None
+fn s3_credentials(app: &AppHandle) -> Option<S3CredentialInput> {
+    let settings = crate::host_upload::saved_settings(app, "s3")
+        .and_then(|settings| crate::image_hosts::resolve_plugin_secrets("s3", settings));
+    match settings.map(serde_json::from_value::<S3CredentialInput>) {
+        Ok(Ok(credentials)) => Some(credentials),
+        Ok(Err(err)) => {
+            warn!("link check: invalid s3 settings: {}", err);
+            None
+        }
+        Err(err) => {
+            warn!("link check: load s3 settings failed: {}", err);
+            None
+        }
+    }
+}
+
+async fn check_s3_object(
+    marker: &str,
+    credentials: S3CredentialInput,
+) -> (LinkState, Option<u16>, Option<String>) {
+    classify_head(
+        crate::s3::s3_head_object(
+            marker.to_string(),
+            credentials.access_key_id,
+            credentials.secret_access_key,
+            credentials.credential_id,
+            credentials.session_token,
+            credentials.presign_expiry_secs,
+            credentials.client_certificate,
+            credentials.http_options,
+        )
+        .await,
+    )
+}
+
+fn build_client() -> Result<Client, String> {
+    Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("failed to build http client: {e}"))
+}
+
+async fn check_items(
+    app: &AppHandle,
+    items: Vec<GalleryItem>,
+) -> Result<Vec<LinkCheckResult>, String> {
+    let store = crate::gallery::store_from_app(app)?;
+    let client = build_client()?;
+    let s3 = if items.iter().any(|item| s3_marker(item).is_some()) {
+        s3_credentials(app)
+    } else {
+        None
+    };
+    let results: Vec<LinkCheckResult> = stream::iter(items)
+        .map(|item| {
+            let client = &client;
+            let s3 = &s3;
+            async move {
+                let (state, http_status, error) = match (s3_marker(&item), s3) {
+                    (Some(marker), Some(credentials)) => {
+                        check_s3_object(marker, credentials.clone()).await
+                    }
+                    _ => check_url(client, &item.url).await,
+                };
+                let previous = store
+                    .update_link_status(item.id, state.stored(), http_status, error.as_deref())
+                    .unwrap_or_else(|err| {
+                        warn!("save link status failed: id={}, error={}", item.id, err);
+                        None
+                    });
+                LinkCheckResult {
+                    id: item.id,
+                    url: item.url,
+                    file_name: item.file_name,
+                    state,
+                    http_status,
+                    error,
+                    newly_dead: state == LinkState::Dead && previous.as_deref() == Some("alive"),
+                }
+            }
+        })
+        .buffer_unordered(CONCURRENCY)
+        .collect()
+        .await;
+
+    let dead: Vec<&LinkCheckResult> = results.iter().filter(|r| r.newly_dead).collect();
+    if !dead.is_empty() {
+        warn!(
+            "{} gallery links died: {}",
+            dead.len(),
+            dead.iter()
+                .map(|r| r.url.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        if let Err(err) = app.emit(DEAD_LINKS_EVENT, &dead) {
+            warn!("emit {} failed: {}", DEAD_LINKS_EVENT, err);
+        }
+    }
+    Ok(results)
+}
+
+/// 是否使用电池供电；无法判断时返回 None
+#[cfg(target_os = "linux")]
+fn on_battery() -> Option<bool> {
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let mut has_battery = false;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let read = |name: &str| {
+            std::fs::read_to_string(path.join(name))
+                .map(|v| v.trim().to_string())
+                .unwrap_or_default()
+        };
+        match read("type").as_str() {
+            "Mains" | "USB" if read("online") == "1" => return Some(false),
+            "Battery" => has_battery = true,
+            _ => {}
+        }
+    }
+    has_battery.then_some(true)
+}
+
+#[cfg(target_os = "macos")]
+fn on_battery() -> Option<bool> {
+    let output = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    if text.contains("'Battery Power'") {
+        Some(true)
+    } else if text.contains("'AC Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn on_battery() -> Option<bool> {
+    #[repr(C)]
+    #[derive(Default)]
+    struct SystemPowerStatus {
+        ac_line_status: u8,
+        battery_flag: u8,
+        battery_life_percent: u8,
+        system_status_flag: u8,
+        battery_life_time: u32,
+        battery_full_life_time: u32,
+    }
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+    }
+    let mut status = SystemPowerStatus::default();
+    // SAFETY: 传入有效的结构体指针，函数只写入该结构体
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return None;
+    }
+    match status.ac_line_status {
+        0 => Some(true),
+        1 => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn on_battery() -> Option<bool> {
+    None
+}
+
+fn queue_idle(app: &AppHandle) -> bool {
+    app.try_state::<UploadQueue>().is_none_or(|queue| {
+        let status = queue.status();
+        status.running == 0 && status.waiting == 0
+    })
+}
+
+/// 距上次检查是否已超过间隔
+fn is_due(last_check: Option<&str>, interval_hours: u32, now: DateTime<Utc>) -> bool {
+    let Some(last) = last_check.and_then(|ts| DateTime::parse_from_rfc3339(ts).ok()) else {
+        return true;
+    };
+    now.signed_duration_since(last.with_timezone(&Utc))
+        >= chrono::Duration::hours(interval_hours as i64)
+}
+
+async fn scheduled_run(app: &AppHandle) -> Result<(), String> {
+    let settings = crate::settings::current_settings(app);
+    if !settings.link_check_enabled {
+        return Ok(());
+    }
+    let store = crate::gallery::store_from_app(app)?;
+    let last = store.last_link_check().map_err(|e| e.to_string())?;
+    if !is_due(
+        last.as_deref(),
+        settings.link_check_interval_hours,
+        Utc::now(),
+    ) {
+        return Ok(());
+    }
+    if settings.link_check_require_ac && on_battery() == Some(true) {
+        return Ok(());
+    }
+    if !queue_idle(app) {
+        return Ok(());
+    }
+    let items = store
+        .items_to_verify(settings.link_check_batch_size as usize)
+        .map_err(|e| e.to_string())?;
+    if items.is_empty() {
+        return Ok(());
+    }
+    let results = check_items(app, items).await?;
+    info!(
+        "scheduled link check finished: checked={}, dead={}",
+        results.len(),
+        results
+            .iter()
+            .filter(|r| r.state == LinkState::Dead)
+            .count()
+    );
+    Ok(())
+}
+
+/// 启动定时复查任务；未开启时每个周期只检查开关
+pub(crate) fn init(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULER_TICK);
+        // 第一次 tick 立即返回，跳过以免与启动时的其他任务争抢
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            if let Err(err) = scheduled_run(&app).await {
+                warn!("scheduled link check failed: {}", err);
+            }
+        }
+    });
+}
+
+/// 检查图库链接；`ids` 留空时按最久未检查的顺序取一批
+#[tauri::command]
+pub async fn check_gallery_links(
+    app: AppHandle,
+    ids: Option<Vec<i64>>,
+) -> Result<Vec<LinkCheckResult>, String> {
+    let store = crate::gallery::store_from_app(&app)?;
+    let items = match ids {
+        Some(ids) => store.get_many(&ids),
+        None => store.items_to_verify(DEFAULT_BATCH),
+    }
+    .map_err(|e| e.to_string())?;
+    check_items(&app, items).await
+}
+
+/// 已检查过的条目的链接状态
+#[tauri::command]
+pub fn gallery_link_status(app: AppHandle, ids: Vec<i64>) -> Result<Vec<LinkStatus>, String> {
+    let store = crate::gallery::store_from_app(&app)?;
+    let rows = store.link_statuses(&ids).map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, status, checked_at)| LinkStatus {
+            id,
+            state: match status.as_deref() {
+                Some("alive") => LinkState::Alive,
+                Some("dead") => LinkState::Dead,
+                _ => LinkState::Unknown,
+            },
+            checked_at,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(StatusCode::OK), LinkState::Alive);
+        assert_eq!(classify(StatusCode::PARTIAL_CONTENT), LinkState::Alive);
+        assert_eq!(classify(StatusCode::NOT_FOUND), LinkState::Dead);
+        assert_eq!(classify(StatusCode::GONE), LinkState::Dead);
+        assert_eq!(
+            classify(StatusCode::SERVICE_UNAVAILABLE),
+            LinkState::Unknown
+        );
+        assert_eq!(classify(StatusCode::TOO_MANY_REQUESTS), LinkState::Unknown);
+    }
+
+    #[test]
+    fn test_s3_head_classification() {
+        let item = |host: &str, marker: Option<&str>| GalleryItem {
+            id: 1,
+            file_name: "a.png".to_string(),
+            url: "https://bucket.example.com/a.png".to_string(),
+            host: host.to_string(),
+            delete_marker: marker.map(str::to_string),
+            inserted_at: String::new(),
+            filesize: None,
+        };
+        assert_eq!(
+            s3_marker(&item("s3", Some("{\"key\":\"a.png\"}"))),
+            Some("{\"key\":\"a.png\"}")
+        );
+        assert_eq!(s3_marker(&item("s3", None)), None);
+        assert_eq!(s3_marker(&item("s3", Some(""))), None);
+        assert_eq!(s3_marker(&item("smms", Some("hash"))), None);
+
+        let head = |exists| S3HeadResult {
+            exists,
+            size: None,
+            content_type: None,
+            last_modified: None,
+        };
+        assert_eq!(
+            classify_head(Ok(head(true))),
+            (LinkState::Alive, Some(200), None)
+        );
+        assert_eq!(classify_head(Ok(head(false))).0, LinkState::Dead);
+        assert_eq!(classify_head(Ok(head(false))).1, Some(404));
+        // 403（凭据失效）与网络错误不判定为失效
+        assert_eq!(
+            classify_head(Err("head failed with status 403 Forbidden".to_string())).0,
+            LinkState::Unknown
+        );
+    }
+
+    #[test]
+    fn test_is_due() {
+        let now = DateTime::parse_from_rfc3339("2024-05-02T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert!(is_due(None, 24, now));
+        assert!(is_due(Some("2024-05-01T11:00:00Z"), 24, now));
+        assert!(!is_due(Some("2024-05-02T01:00:00+00:00"), 24, now));
+        assert!(is_due(Some("garbage"), 24, now));
+    }
+}
@@ -0,0 +1,220 @@
+/*
+内置本地目录“图床”：
+1) 将处理后的图片复制到用户选择的目录（如 Hugo 的 `static/images` 或网盘同步目录），
+   子路径与文件名由路径模板决定，同名文件存在时追加 `-1`、`-2` 等后缀而不覆盖；
+2) 返回链接由访问根地址拼接相对路径得到，未配置时返回 `file://` 地址；
+3) 上传结果与其他图床一致，仍会由前端记录到图库；
+4) deleteId 中记录目录与相对路径，删除时仅允许删除当前配置目录内的文件。
+*/
+
+use std::path::{Path, PathBuf};
+
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::AppHandle;
+
+use crate::internal_hosts::{
+    HostDeleteResult, HostUploadResult, build_public_url, decode_delete_marker,
+    encode_delete_marker, load_host_settings, render_path_template,
+};
+use crate::upload::run_cancellable;
+
+const PLUGIN_ID: &str = "local";
+const DEFAULT_FILE_TEMPLATE: &str = "{year}/{month}/{filename}";
+const MAX_NAME_ATTEMPTS: u32 = 1000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalFolderConfig {
+    /// 目标目录（绝对路径）
+    pub target_dir: String,
+    #[serde(default)]
+    pub path_template: Option<String>,
+    /// 目标目录对应的访问根地址，例如 `/images` 或 https://blog.example.com/images
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LocalFolderDeleteMarker {
+    target_dir: String,
+    path: String,
+}
+
+fn target_dir(raw: &str) -> Result<PathBuf, String> {
+    let dir = PathBuf::from(raw.trim());
+    if raw.trim().is_empty() || !dir.is_absolute() {
+        return Err("目标目录必须是绝对路径".to_string());
+    }
+    Ok(dir)
+}
+
+/// 在目标目录中寻找不冲突的相对路径：`a.png` 已存在时依次尝试 `a-1.png`、`a-2.png`
+pub(crate) fn unique_relative_path(dir: &Path, relative: &str) -> Result<String, String> {
+    if !dir.join(relative).exists() {
+        return Ok(relative.to_string());
+    }
+    let (parent, file) = match relative.rsplit_once('/') {
+        Some((parent, file)) => (format!("{}/", parent), file),
+        None => (String::new(), relative),
+    };
+    let (stem, ext) = match file.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{}", ext)),
+        _ => (file, String::new()),
+    };
+    (1..=MAX_NAME_ATTEMPTS)
+        .map(|index| format!("{}{}-{}{}", parent, stem, index, ext))
+        .find(|candidate| !dir.join(candidate).exists())
+        .ok_or_else(|| format!("目标目录中同名文件过多: {}", relative))
+}
+
+#[tauri::command]
+pub async fn local_folder_upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: LocalFolderConfig,
+    upload_id: Option<String>,
+) -> Result<HostUploadResult, String> {
+    run_cancellable(
+        upload_id,
+        copy_to_folder(file_path, original_file_name, config),
+    )
+    .await
+}
+
+async fn copy_to_folder(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: LocalFolderConfig,
+) -> Result<HostUploadResult, String> {
+    let file_name = original_file_name.unwrap_or_else(|| {
+        Path::new(&file_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "upload.bin".to_string())
+    });
+    let dir = target_dir(&config.target_dir)?;
+    if !dir.is_dir() {
+        return Err(format!("目标目录不存在: {}", dir.display()));
+    }
+    let template = config
+        .path_template
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or(DEFAULT_FILE_TEMPLATE);
+    let relative = unique_relative_path(&dir, &render_path_template(Some(template), &file_name))?;
+    let destination = dir.join(&relative);
+    if let Some(parent) = destination.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("failed to create dir {}: {}", parent.display(), e))?;
+    }
+    let size = tokio::fs::copy(&file_path, &destination)
+        .await
+        .map_err(|e| {
+            error!(
+                "local_folder_upload failed: dest={}, error={}",
+                destination.display(),
+                e
+            );
+            format!("复制到 {} 失败: {}", destination.display(), e)
+        })?;
+
+    let url = match config
+        .base_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+    {
+        Some(base) => build_public_url(base, &relative),
+        None => url::Url::from_file_path(&destination)
+            .map(|u| u.to_string())
+            .map_err(|_| format!("无法生成文件地址: {}", destination.display()))?,
+    };
+    let delete_id = encode_delete_marker(&LocalFolderDeleteMarker {
+        target_dir: dir.to_string_lossy().to_string(),
+        path: relative.clone(),
+    })?;
+
+    info!(
+        "local_folder_upload success: dest={}, size={}",
+        destination.display(),
+        size
+    );
+    Ok(HostUploadResult {
+        url,
+        delete_id,
+        metadata: Some(json!({
+            "path": relative,
+            "localPath": destination.to_string_lossy(),
+            "size": size,
+        })),
+    })
+}
+
+#[tauri::command]
+pub async fn local_folder_delete(
+    app: AppHandle,
+    delete_id: String,
+) -> Result<HostDeleteResult, String> {
+    let marker: LocalFolderDeleteMarker = decode_delete_marker(&delete_id)?;
+    let config: LocalFolderConfig = load_host_settings(&app, PLUGIN_ID)?;
+
+    // deleteId 来自图库记录，只删除当前配置目录内的文件，防止被篡改的记录删除任意文件
+    let dir = target_dir(&config.target_dir)?;
+    if Path::new(&marker.target_dir) != dir {
+        return Ok(HostDeleteResult {
+            success: false,
+            message: Some(format!(
+                "文件位于 {}，与当前配置的目标目录不一致，请手动删除",
+                marker.target_dir
+            )),
+        });
+    }
+    let path = dir.join(&marker.path);
+    let canonical_dir = dir
+        .canonicalize()
+        .map_err(|e| format!("目标目录不可用 {}: {}", dir.display(), e))?;
+    match path.canonicalize() {
+        Ok(canonical) if canonical.starts_with(&canonical_dir) => {
+            tokio::fs::remove_file(&canonical)
+                .await
+                .map_err(|e| format!("删除 {} 失败: {}", canonical.display(), e))?;
+            info!("local_folder_delete success: path={}", canonical.display());
+            Ok(HostDeleteResult::deleted("文件已从本地目录删除"))
+        }
+        Ok(canonical) => Err(format!(
+            "拒绝删除目标目录之外的文件: {}",
+            canonical.display()
+        )),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Ok(HostDeleteResult::deleted("文件已不存在"))
+        }
+        Err(e) => Err(format!("删除 {} 失败: {}", path.display(), e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_relative_path() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("2024")).unwrap();
+        std::fs::write(dir.path().join("2024/a.png"), b"x").unwrap();
+        std::fs::write(dir.path().join("2024/a-1.png"), b"x").unwrap();
+
+        assert_eq!(
+            unique_relative_path(dir.path(), "2024/a.png").unwrap(),
+            "2024/a-2.png"
+        );
+        assert_eq!(
+            unique_relative_path(dir.path(), "2024/b.png").unwrap(),
+            "2024/b.png"
+        );
+    }
+}
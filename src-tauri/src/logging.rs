@@ -0,0 +1,176 @@
+/*
+日志级别与日志导出：
+1) 日志插件以 Trace 级别初始化，实际生效级别由 `log::set_max_level` 控制，可在运行时调整；
+   设置中的 `logLevel` 为空时使用默认级别（开发环境 debug，生产环境 info）；
+2) `set_log_level` 立即生效但不写入设置，便于临时开启 debug 排查问题，下次保存设置或重启后恢复；
+3) `export_logs` 将日志目录中的现有日志与环境信息打包为带时间戳的 zip，供提交问题时附带。
+*/
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use log::{LevelFilter, error, info};
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+
+const LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+pub(crate) fn default_level() -> LevelFilter {
+    if cfg!(debug_assertions) {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    }
+}
+
+/// 解析级别名称，空字符串表示默认级别
+pub(crate) fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    let level = level.trim().to_ascii_lowercase();
+    if level.is_empty() {
+        return Ok(default_level());
+    }
+    if !LEVELS.contains(&level.as_str()) {
+        return Err(format!(
+            "不支持的日志级别: {}（可选 {}）",
+            level,
+            LEVELS.join(", ")
+        ));
+    }
+    level
+        .parse::<LevelFilter>()
+        .map_err(|e| format!("invalid log level {}: {e}", level))
+}
+
+/// 应用设置中的日志级别，无效时回退到默认级别
+pub(crate) fn apply_level(level: &str) {
+    let filter = parse_level(level).unwrap_or_else(|_| default_level());
+    if log::max_level() != filter {
+        log::set_max_level(filter);
+        info!("log level set to {}", filter);
+    }
+}
+
+#[tauri::command]
+pub fn get_log_level() -> String {
+    log::max_level().to_string().to_ascii_lowercase()
+}
+
+/// 运行时调整日志级别，不写入设置，下次保存设置或重启后恢复设置中的级别
+#[tauri::command]
+pub fn set_log_level(level: String) -> Result<(), String> {
+    let filter = parse_level(&level)?;
+    log::set_max_level(filter);
+    info!("set_log_level success: level={}", filter);
+    Ok(())
+}
+
+fn collect_log_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(dir).map_err(|e| format!("read_dir {}: {e}", dir.display()))?;
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| ext == "log")
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn environment_info(app: &AppHandle) -> String {
+    format!(
+        "app: {} {}\nos: {} {}\nportable: {}\nlog level: {}\nexported at: {}\n",
+        app.package_info().name,
+        app.package_info().version,
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        crate::portable::portable_root().is_some(),
+        get_log_level(),
+        chrono::Local::now().to_rfc3339()
+    )
+}
+
+fn write_zip(target: &Path, files: &[PathBuf], info_text: &str) -> Result<(), String> {
+    let file = fs::File::create(target).map_err(|e| format!("create {}: {e}", target.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    zip.start_file("environment.txt", options)
+        .map_err(|e| format!("zip environment.txt: {e}"))?;
+    zip.write_all(info_text.as_bytes())
+        .map_err(|e| format!("zip environment.txt: {e}"))?;
+    for path in files {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        // 日志文件可能正被写入，读取当前内容即可
+        let bytes = fs::read(path).map_err(|e| format!("read {}: {e}", path.display()))?;
+        zip.start_file(format!("logs/{}", name), options)
+            .map_err(|e| format!("zip {}: {e}", name))?;
+        zip.write_all(&bytes)
+            .map_err(|e| format!("zip {}: {e}", name))?;
+    }
+    zip.finish().map_err(|e| format!("finish zip: {e}"))?;
+    Ok(())
+}
+
+/// 打包当前日志为 `yana-logs-<时间戳>.zip`；`dir` 为空时保存到下载目录，返回 zip 路径
+#[tauri::command]
+pub fn export_logs(app: AppHandle, dir: Option<String>) -> Result<String, String> {
+    let log_dir = crate::portable::app_log_dir(&app).map_err(|e| format!("app_log_dir: {e}"))?;
+    let target_dir = match dir.filter(|d| !d.trim().is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => app
+            .path()
+            .download_dir()
+            .unwrap_or_else(|_| log_dir.clone()),
+    };
+    fs::create_dir_all(&target_dir)
+        .map_err(|e| format!("create_dir_all {}: {e}", target_dir.display()))?;
+
+    let files = collect_log_files(&log_dir)?;
+    let target = target_dir.join(format!(
+        "yana-logs-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    if let Err(err) = write_zip(&target, &files, &environment_info(&app)) {
+        error!(
+            "export_logs failed: path={}, error={}",
+            target.display(),
+            err
+        );
+        let _ = fs::remove_file(&target);
+        return Err(err);
+    }
+
+    info!(
+        "export_logs success: path={}, files={}",
+        target.display(),
+        files.len()
+    );
+    Ok(target.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("DEBUG").unwrap(), LevelFilter::Debug);
+        assert_eq!(parse_level(" warn ").unwrap(), LevelFilter::Warn);
+        assert_eq!(parse_level("").unwrap(), default_level());
+        assert!(parse_level("off").is_err());
+        assert!(parse_level("verbose").is_err());
+    }
+}
@@ -0,0 +1,426 @@
+/*
+上传前的元数据隐私策略（设置 `metadataPrivacyPolicy`）：
+1) 检查待上传原图是否含 GPS 位置（EXIF 或 XMP）或设备序列号（EXIF `*SerialNumber`）；
+2) `warn`（默认）：含有时阻止上传，返回带 `PrivacyFindings` 的结构化结果，由界面提示后选择去除或仍然上传；
+   `strip`：在任何图床收到文件之前去除元数据；`never`：不检查；
+3) 去除元数据不重新编码像素：JPEG 删除 APP1（EXIF / XMP）、APP13（IPTC）与注释段，
+   保留 ICC，方向不为 1 时写回只含方向的最小 EXIF；PNG 删除 eXIf 与文本块；
+   WebP 删除 EXIF / XMP 块并清除 VP8X 中的对应标记；其他格式无法去除时不上传。
+*/
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use exif::{In, Tag};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+/// PNG 中可能携带元数据的块
+const PNG_METADATA_CHUNKS: [&[u8]; 5] = [b"eXIf", b"tEXt", b"iTXt", b"zTXt", b"tIME"];
+const WEBP_FLAG_EXIF: u8 = 0x08;
+const WEBP_FLAG_XMP: u8 = 0x04;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PrivacyPolicy {
+    /// 含有位置或序列号时阻止上传
+    #[default]
+    Warn,
+    /// 自动去除元数据后上传
+    Strip,
+    /// 不检查
+    Never,
+}
+
+/// 图片中会泄露隐私的元数据
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyFindings {
+    pub gps: bool,
+    pub serial_number: bool,
+}
+
+impl PrivacyFindings {
+    pub fn is_empty(&self) -> bool {
+        !self.gps && !self.serial_number
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PrivacyAction {
+    Upload,
+    Strip,
+    Block(PrivacyFindings),
+}
+
+fn inspect(path: &Path) -> Result<PrivacyFindings, String> {
+    let metadata = crate::image_metadata::read_metadata(path)?;
+    let xmp_gps = metadata
+        .xmp
+        .as_deref()
+        .is_some_and(|xmp| xmp.contains("GPSLatitude") || xmp.contains("GPSLongitude"));
+    Ok(PrivacyFindings {
+        gps: metadata.has_gps || xmp_gps,
+        serial_number: metadata.camera.serial_number.is_some()
+            || metadata
+                .exif
+                .iter()
+                .any(|field| field.tag.ends_with("SerialNumber")),
+    })
+}
+
+/// 按策略决定原图如何上传；读取失败时照常上传，由上传步骤报告文件错误
+pub(crate) fn decide(policy: PrivacyPolicy, path: &Path) -> PrivacyAction {
+    if policy == PrivacyPolicy::Never {
+        return PrivacyAction::Upload;
+    }
+    let findings = match inspect(path) {
+        Ok(findings) => findings,
+        Err(err) => {
+            warn!(
+                "inspect metadata failed: file={}, error={}",
+                path.display(),
+                err
+            );
+            return PrivacyAction::Upload;
+        }
+    };
+    match policy {
+        _ if findings.is_empty() => PrivacyAction::Upload,
+        PrivacyPolicy::Strip => PrivacyAction::Strip,
+        _ => PrivacyAction::Block(findings),
+    }
+}
+
+/// 阻止上传时的错误信息
+pub(crate) fn blocked_message(findings: &PrivacyFindings) -> String {
+    let mut found = Vec::new();
+    if findings.gps {
+        found.push("GPS 位置");
+    }
+    if findings.serial_number {
+        found.push("设备序列号");
+    }
+    format!(
+        "图片包含{}，已按隐私策略阻止上传（可去除元数据后上传）",
+        found.join("、")
+    )
+}
+
+fn jpeg_orientation(bytes: &[u8]) -> Option<u32> {
+    exif::Reader::new()
+        .read_from_container(&mut Cursor::new(bytes))
+        .ok()?
+        .get_field(Tag::Orientation, In::PRIMARY)?
+        .value
+        .get_uint(0)
+}
+
+/// 只含方向字段的 EXIF APP1 段（大端 TIFF，单个 IFD 项）
+fn orientation_segment(orientation: u16) -> Vec<u8> {
+    let mut data = b"Exif\0\0MM\0\x2a".to_vec();
+    data.extend_from_slice(&8u32.to_be_bytes());
+    data.extend_from_slice(&1u16.to_be_bytes());
+    data.extend_from_slice(&0x0112u16.to_be_bytes());
+    data.extend_from_slice(&3u16.to_be_bytes());
+    data.extend_from_slice(&1u32.to_be_bytes());
+    data.extend_from_slice(&orientation.to_be_bytes());
+    data.extend_from_slice(&[0, 0]);
+    data.extend_from_slice(&0u32.to_be_bytes());
+
+    let mut segment = vec![0xFF, 0xE1];
+    segment.extend_from_slice(&((data.len() + 2) as u16).to_be_bytes());
+    segment.extend_from_slice(&data);
+    segment
+}
+
+fn strip_jpeg(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let invalid = || "JPEG 结构无效，无法去除元数据".to_string();
+    let mut kept: Vec<(u8, &[u8])> = Vec::new();
+    let mut pos = 2;
+    let scan_start = loop {
+        if bytes.get(pos) != Some(&0xFF) {
+            return Err(invalid());
+        }
+        let marker = *bytes.get(pos + 1).ok_or_else(invalid)?;
+        match marker {
+            0xFF => {
+                pos += 1;
+                continue;
+            }
+            0x01 | 0xD0..=0xD7 => {
+                kept.push((marker, &bytes[pos..pos + 2]));
+                pos += 2;
+                continue;
+            }
+            // 扫描数据及之后的内容原样保留
+            0xDA | 0xD9 => break pos,
+            _ => {}
+        }
+        let len = crate::image_metadata::be_u16(bytes, pos + 2)
+            .filter(|len| *len >= 2)
+            .ok_or_else(invalid)?;
+        let segment = bytes.get(pos..pos + 2 + len).ok_or_else(invalid)?;
+        if !matches!(marker, 0xE1 | 0xED | 0xFE) {
+            kept.push((marker, segment));
+        }
+        pos += 2 + len;
+    };
+
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(&bytes[..2]);
+    // JFIF（APP0）须位于最前，方向段紧随其后
+    let leading_app0 = kept
+        .iter()
+        .take_while(|(marker, _)| *marker == 0xE0)
+        .count();
+    for (_, segment) in &kept[..leading_app0] {
+        out.extend_from_slice(segment);
+    }
+    if let Some(orientation) = jpeg_orientation(bytes).filter(|o| (2..=8).contains(o)) {
+        out.extend(orientation_segment(orientation as u16));
+    }
+    for (_, segment) in &kept[leading_app0..] {
+        out.extend_from_slice(segment);
+    }
+    out.extend_from_slice(&bytes[scan_start..]);
+    Ok(out)
+}
+
+fn strip_png(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let invalid = || "PNG 结构无效，无法去除元数据".to_string();
+    let mut out = PNG_SIGNATURE.to_vec();
+    let mut pos = PNG_SIGNATURE.len();
+    loop {
+        let len = crate::image_metadata::be_u32(bytes, pos).ok_or_else(invalid)?;
+        let chunk = bytes.get(pos..pos + 12 + len).ok_or_else(invalid)?;
+        let kind = &chunk[4..8];
+        if !PNG_METADATA_CHUNKS.contains(&kind) {
+            out.extend_from_slice(chunk);
+        }
+        pos += 12 + len;
+        if kind == b"IEND" {
+            return Ok(out);
+        }
+    }
+}
+
+fn strip_webp(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let invalid = || "WebP 结构无效，无法去除元数据".to_string();
+    let mut out = bytes.get(..12).ok_or_else(invalid)?.to_vec();
+    let mut pos = 12;
+    while pos < bytes.len() {
+        let len = crate::image_metadata::le_u32(bytes, pos + 4).ok_or_else(invalid)?;
+        let padded = len + (len & 1);
+        // 最后一块的填充字节可能缺失
+        let end = (pos + 8 + padded).min(bytes.len());
+        let chunk = bytes
+            .get(pos..end)
+            .filter(|c| c.len() >= 8 + len)
+            .ok_or_else(invalid)?;
+        match &chunk[..4] {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" if len > 0 => {
+                let mut chunk = chunk.to_vec();
+                chunk[8] &= !(WEBP_FLAG_EXIF | WEBP_FLAG_XMP);
+                out.extend_from_slice(&chunk);
+            }
+            _ => out.extend_from_slice(chunk),
+        }
+        pos = end;
+    }
+    let riff_size = (out.len() - 8) as u32;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Ok(out)
+}
+
+/// 去除图片中的元数据，像素数据保持不变
+fn strip_bytes(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        strip_jpeg(bytes)
+    } else if bytes.starts_with(PNG_SIGNATURE) {
+        strip_png(bytes)
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(&b"WEBP"[..]) {
+        strip_webp(bytes)
+    } else {
+        let format = image::guess_format(bytes)
+            .map(|format| format!("{format:?}"))
+            .unwrap_or_else(|_| "未知".to_string());
+        Err(format!("暂不支持去除 {format} 格式图片的元数据"))
+    }
+}
+
+/// 去除待上传文件的元数据：`path` 为压缩产生的临时文件时原地改写，
+/// 为原图时写入临时副本（上传后按临时文件删除）；返回实际上传的路径
+pub(crate) fn strip_prepared(source: &str, path: &str) -> Result<String, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("读取文件失败 {path}: {e}"))?;
+    let stripped = strip_bytes(&bytes)?;
+    let output = if path == source {
+        let dir = crate::portable::temp_dir();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("create app temp dir {}: {}", dir.display(), e))?;
+        let extension = Path::new(source)
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .unwrap_or_default();
+        tempfile::Builder::new()
+            .prefix("yana_strip_")
+            .suffix(&extension)
+            .tempfile_in(&dir)
+            .map_err(|e| format!("tempfile_in: {}", e))?
+            .into_temp_path()
+            .keep()
+            .map_err(|e| format!("keep temp file: {}", e))?
+    } else {
+        PathBuf::from(path)
+    };
+    std::fs::write(&output, &stripped)
+        .map_err(|e| format!("写入文件失败 {}: {e}", output.display()))?;
+    info!(
+        "metadata stripped: file={}, bytes={} -> {}",
+        source,
+        bytes.len(),
+        stripped.len()
+    );
+    Ok(output.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jpeg_segment(marker: u8, data: &[u8]) -> Vec<u8> {
+        let mut out = vec![0xFF, marker];
+        out.extend_from_slice(&((data.len() + 2) as u16).to_be_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn png_chunk(kind: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut out = (data.len() as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(kind);
+        out.extend_from_slice(data);
+        out.extend_from_slice(&[0; 4]);
+        out
+    }
+
+    #[test]
+    fn test_strip_jpeg() {
+        let app0 = jpeg_segment(0xE0, b"JFIF\0\x01\x01\0\0\x01\0\x01\0\0");
+        let icc = jpeg_segment(0xE2, b"ICC_PROFILE\0\x01\x01data");
+        let exif = orientation_segment(6);
+        let mut bytes = vec![0xFF, 0xD8];
+        bytes.extend(&app0);
+        bytes.extend(&exif);
+        bytes.extend(jpeg_segment(0xE1, b"http://ns.adobe.com/xap/1.0/\0<x/>"));
+        bytes.extend(jpeg_segment(0xFE, b"comment"));
+        bytes.extend(&icc);
+        let scan = [0xFF, 0xDA, 0x00, 0x02, 0x12, 0x34, 0xFF, 0xD9];
+        bytes.extend_from_slice(&scan);
+
+        // 方向保留为只含方向的 EXIF，XMP 与注释删除
+        let mut expected = vec![0xFF, 0xD8];
+        expected.extend(&app0);
+        expected.extend(&exif);
+        expected.extend(&icc);
+        expected.extend_from_slice(&scan);
+        let stripped = strip_bytes(&bytes).unwrap();
+        assert_eq!(stripped, expected);
+        assert_eq!(jpeg_orientation(&stripped), Some(6));
+
+        assert!(strip_bytes(&[0xFF, 0xD8, 0x00]).is_err());
+    }
+
+    #[test]
+    fn test_strip_png() {
+        let ihdr = png_chunk(b"IHDR", &[0; 13]);
+        let idat = png_chunk(b"IDAT", b"pixels");
+        let iend = png_chunk(b"IEND", b"");
+        let mut bytes = PNG_SIGNATURE.to_vec();
+        bytes.extend(&ihdr);
+        bytes.extend(png_chunk(b"eXIf", b"MM\0*"));
+        bytes.extend(png_chunk(b"tEXt", b"Author\0me"));
+        bytes.extend(&idat);
+        bytes.extend(&iend);
+
+        let mut expected = PNG_SIGNATURE.to_vec();
+        expected.extend(&ihdr);
+        expected.extend(&idat);
+        expected.extend(&iend);
+        assert_eq!(strip_bytes(&bytes).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_strip_webp() {
+        let mut bytes = b"RIFF\0\0\0\0WEBP".to_vec();
+        let vp8x = [
+            WEBP_FLAG_EXIF | WEBP_FLAG_XMP | 0x20,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+        ];
+        for (kind, data) in [
+            (b"VP8X", &vp8x[..]),
+            (b"ICCP", &b"icc"[..]),
+            (b"VP8L", &b"pixels"[..]),
+            (b"EXIF", &b"MM\0*"[..]),
+            (b"XMP ", &b"<x/>"[..]),
+        ] {
+            bytes.extend_from_slice(kind);
+            bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(data);
+            if data.len() % 2 == 1 {
+                bytes.push(0);
+            }
+        }
+
+        let stripped = strip_bytes(&bytes).unwrap();
+        assert_eq!(
+            u32::from_le_bytes(stripped[4..8].try_into().unwrap()) as usize,
+            stripped.len() - 8
+        );
+        // VP8X 只保留 ICC 标记
+        assert_eq!(stripped[20], 0x20);
+        let text = String::from_utf8_lossy(&stripped);
+        assert!(text.contains("ICCP") && text.contains("VP8L"));
+        assert!(!text.contains("EXIF") && !text.contains("XMP "));
+    }
+
+    #[test]
+    fn test_decide_and_strip_prepared() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.png");
+        image::RgbImage::new(2, 2).save(&path).unwrap();
+        let source = path.to_string_lossy().into_owned();
+
+        for policy in [
+            PrivacyPolicy::Warn,
+            PrivacyPolicy::Strip,
+            PrivacyPolicy::Never,
+        ] {
+            assert_eq!(decide(policy, &path), PrivacyAction::Upload);
+        }
+
+        // 压缩产生的临时文件原地改写
+        let copy = dir.path().join("b.png");
+        std::fs::copy(&path, &copy).unwrap();
+        let copy = copy.to_string_lossy().into_owned();
+        assert_eq!(strip_prepared(&source, &copy).unwrap(), copy);
+
+        assert!(strip_bytes(b"GIF89a").is_err());
+        assert_eq!(
+            blocked_message(&PrivacyFindings {
+                gps: true,
+                serial_number: true
+            }),
+            "图片包含GPS 位置、设备序列号，已按隐私策略阻止上传（可去除元数据后上传）"
+        );
+    }
+}
@@ -0,0 +1,355 @@
+/*
+网络状态感知与离线队列：
+1) 离线以真实上传为准：上传请求连接公网地址失败时记录（`report_send_result`），任一请求成功即清除；
+   后台每隔 `POLL_INTERVAL` 探测一次连通性（对若干公共地址与环境变量中的代理发起 TCP 连接，
+   任一成功即为在线），只有 `FAILURE_WINDOW` 内有上传连接失败且连续 `OFFLINE_AFTER` 次探测失败
+   才判定离线；探测地址被防火墙屏蔽但上传正常时不会误判；
+2) 失败记录过期后解除挂起，排队的上传会再次尝试，仍失败则重新挂起，不会无限期阻塞；
+3) 计费网络（按流量计费）仅在能判断的平台上检测：Linux 读取 NetworkManager 的 Metered 属性，
+   其他平台视为未知；
+4) 离线（开启「断网时暂停上传」时）或开启「计费网络下暂停上传」且处于计费网络时挂起上传队列：
+   新提交的上传照常排队，不会发起请求，恢复后按提交顺序自动继续；
+   目标为局域网 / 本机地址（`is_private_destination`）的上传不受挂起影响；
+5) 状态变化时发送 `network://status`；挂起期间排队的上传数见上传队列事件中的 `waiting`。
+*/
+
+use std::{
+    net::IpAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::net::TcpStream;
+
+use crate::settings::SettingsPayload;
+use crate::upload_queue::UploadQueue;
+
+const STATUS_EVENT: &str = "network://status";
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+/// 连续探测失败多少次后判定离线
+const OFFLINE_AFTER: u32 = 2;
+/// 上传连接失败记录的有效期
+const FAILURE_WINDOW: Duration = Duration::from_secs(60);
+/// 探测地址：公共 DNS 的 TCP 端口，覆盖国内外网络
+const PROBE_ADDRS: &[&str] = &["223.5.5.5:53", "1.1.1.1:443", "8.8.8.8:53"];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStatus {
+    pub online: bool,
+    /// 是否为计费网络，无法判断时为 None
+    pub metered: Option<bool>,
+    /// 上传队列是否因网络状态挂起
+    pub held: bool,
+}
+
+static STATUS: Mutex<NetworkStatus> = Mutex::new(NetworkStatus {
+    online: true,
+    metered: None,
+    held: false,
+});
+
+/// 最近一次上传连接公网地址失败的时间
+static LAST_CONNECT_FAILURE: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// 去掉端口与 IPv6 方括号后的主机名
+fn destination_host(destination: &str) -> String {
+    let destination = destination.trim();
+    let host = match url::Url::parse(destination) {
+        Ok(url) if url.has_host() => url.host_str().unwrap_or_default().to_string(),
+        _ => destination
+            .rsplit_once('@')
+            .map_or(destination, |(_, host)| host)
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+    };
+    if let Some(inner) = host.strip_prefix('[') {
+        return inner.split(']').next().unwrap_or_default().to_string();
+    }
+    match host.rsplit_once(':') {
+        Some((name, port)) if !name.contains(':') && port.parse::<u16>().is_ok() => {
+            name.to_string()
+        }
+        _ => host,
+    }
+    .to_ascii_lowercase()
+}
+
+fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                // 100.64.0.0/10（运营商级 NAT、Tailscale 等组网）
+                || (ip.octets()[0] == 100 && ip.octets()[1] & 0xc0 == 64)
+        }
+        IpAddr::V6(ip) => {
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.segments()[0] & 0xfe00 == 0xfc00
+                || ip.segments()[0] & 0xffc0 == 0xfe80
+                || ip
+                    .to_ipv4_mapped()
+                    .is_some_and(|ip| is_private_ip(IpAddr::V4(ip)))
+        }
+    }
+}
+
+/// 上传目标（URL、`host[:port]` 或主机名）是否位于局域网 / 本机：私有与回环地址、
+/// localhost、`.local` / `.lan` / `.home.arpa` / `.internal` 域名与不含点的单标签主机名。
+/// 不做 DNS 解析
+pub(crate) fn is_private_destination(destination: &str) -> bool {
+    let host = destination_host(destination);
+    if host.is_empty() {
+        return false;
+    }
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return is_private_ip(ip);
+    }
+    let host = host.trim_end_matches('.');
+    host == "localhost"
+        || !host.contains('.')
+        || [".localhost", ".local", ".lan", ".home.arpa", ".internal"]
+            .iter()
+            .any(|suffix| host.ends_with(suffix))
+}
+
+/// 记录上传请求的发送结果：连接公网地址失败时记下时间，任一请求收到响应即清除记录
+pub(crate) fn report_send_result<T>(result: &Result<T, reqwest::Error>) {
+    let failed_at = match result {
+        Ok(_) => None,
+        Err(err) if err.is_connect() || err.is_timeout() => {
+            let public = err
+                .url()
+                .and_then(|url| url.host_str())
+                .is_some_and(|host| !is_private_destination(host));
+            if !public {
+                return;
+            }
+            Some(Instant::now())
+        }
+        Err(_) => return,
+    };
+    if let Ok(mut last) = LAST_CONNECT_FAILURE.lock() {
+        *last = failed_at;
+    }
+}
+
+fn recent_connect_failure() -> bool {
+    LAST_CONNECT_FAILURE
+        .lock()
+        .ok()
+        .and_then(|last| *last)
+        .is_some_and(|at| at.elapsed() < FAILURE_WINDOW)
+}
+
+/// 环境变量中代理的 `host:port`，直连被屏蔽但代理可用时也应视为在线
+fn proxy_addr(value: &str) -> Option<String> {
+    let url = url::Url::parse(value.trim()).ok()?;
+    let host = url.host_str()?;
+    let port = url.port_or_known_default()?;
+    Some(format!("{host}:{port}"))
+}
+
+fn probe_addrs() -> Vec<String> {
+    let mut addrs: Vec<String> = ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"]
+        .iter()
+        .filter_map(|key| std::env::var(key).ok())
+        .filter_map(|value| proxy_addr(&value))
+        .collect();
+    addrs.dedup();
+    addrs.extend(PROBE_ADDRS.iter().map(|addr| addr.to_string()));
+    addrs
+}
+
+async fn probe() -> bool {
+    for addr in probe_addrs() {
+        if let Ok(Ok(_)) = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(&addr)).await {
+            return true;
+        }
+    }
+    false
+}
+
+/// NetworkManager 的 NMMetered：1 是，2 否，3 推测是，4 推测否，0 未知
+fn parse_nm_metered(output: &str) -> Option<bool> {
+    match output.split_whitespace().nth(1)? {
+        "1" | "3" => Some(true),
+        "2" | "4" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn metered() -> Option<bool> {
+    let output = std::process::Command::new("busctl")
+        .args([
+            "get-property",
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager",
+            "org.freedesktop.NetworkManager",
+            "Metered",
+        ])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    parse_nm_metered(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn metered() -> Option<bool> {
+    None
+}
+
+/// 是否应挂起上传队列
+fn should_hold(online: bool, metered: Option<bool>, settings: &SettingsPayload) -> bool {
+    (settings.pause_uploads_when_offline && !online)
+        || (settings.pause_uploads_on_metered && metered == Some(true))
+}
+
+/// 按最新的探测结果更新状态，状态变化时挂起或恢复上传队列并通知前端
+fn update(app: &AppHandle, online: bool, metered: Option<bool>, settings: &SettingsPayload) {
+    let held = should_hold(online, metered, settings);
+    let status = {
+        let Ok(mut status) = STATUS.lock() else {
+            return;
+        };
+        let changed = status.online != online || status.metered != metered || status.held != held;
+        if !changed {
+            return;
+        }
+        *status = NetworkStatus {
+            online,
+            metered,
+            held,
+        };
+        *status
+    };
+    info!(
+        "network status: online={}, metered={:?}, held={}",
+        online, metered, held
+    );
+    if let Some(queue) = app.try_state::<UploadQueue>() {
+        queue.set_offline(held);
+    }
+    if let Err(err) = app.emit(STATUS_EVENT, status) {
+        warn!("emit {} failed: {}", STATUS_EVENT, err);
+    }
+    #[cfg(desktop)]
+    crate::tray::refresh(app);
+}
+
+/// 设置变化（如「计费网络下暂停上传」）后按当前状态重新判定
+pub(crate) fn apply(app: &AppHandle, settings: &SettingsPayload) {
+    let Ok(status) = STATUS.lock().map(|status| *status) else {
+        return;
+    };
+    update(app, status.online, status.metered, settings);
+}
+
+pub(crate) fn init(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut failures = 0;
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            failures = if probe().await { 0 } else { failures + 1 };
+            let metered = tauri::async_runtime::spawn_blocking(metered)
+                .await
+                .ok()
+                .flatten();
+            let online = failures < OFFLINE_AFTER || !recent_connect_failure();
+            update(
+                &app,
+                online,
+                metered,
+                &crate::settings::current_settings(&app),
+            );
+        }
+    });
+}
+
+#[tauri::command]
+pub fn network_status() -> Result<NetworkStatus, String> {
+    STATUS
+        .lock()
+        .map(|status| *status)
+        .map_err(|_| "network status poisoned".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nm_metered() {
+        assert_eq!(parse_nm_metered("u 1\n"), Some(true));
+        assert_eq!(parse_nm_metered("u 3"), Some(true));
+        assert_eq!(parse_nm_metered("u 4"), Some(false));
+        assert_eq!(parse_nm_metered("u 0"), None);
+        assert_eq!(parse_nm_metered(""), None);
+    }
+
+    #[test]
+    fn test_should_hold() {
+        let settings = |when_offline, on_metered| SettingsPayload {
+            pause_uploads_when_offline: when_offline,
+            pause_uploads_on_metered: on_metered,
+            ..Default::default()
+        };
+        assert!(should_hold(false, None, &settings(true, false)));
+        assert!(!should_hold(false, None, &settings(false, false)));
+        assert!(!should_hold(true, Some(true), &settings(true, false)));
+        assert!(should_hold(true, Some(true), &settings(false, true)));
+        assert!(!should_hold(true, None, &settings(true, true)));
+    }
+
+    #[test]
+    fn test_is_private_destination() {
+        for destination in [
+            "http://192.168.1.10:9000",
+            "https://10.0.0.2/upload",
+            "127.0.0.1:22",
+            "http://[::1]:8080",
+            "[fd00::1]:22",
+            "100.100.1.2",
+            "nas",
+            "nas.local",
+            "http://minio.lan:9000/bucket",
+            "localhost",
+        ] {
+            assert!(is_private_destination(destination), "{destination}");
+        }
+        for destination in [
+            "https://s3.amazonaws.com",
+            "sm.ms",
+            "8.8.8.8:53",
+            "https://api.example.com/upload",
+            "",
+        ] {
+            assert!(!is_private_destination(destination), "{destination}");
+        }
+    }
+
+    #[test]
+    fn test_proxy_addr() {
+        assert_eq!(
+            proxy_addr("http://127.0.0.1:7890").as_deref(),
+            Some("127.0.0.1:7890")
+        );
+        assert_eq!(
+            proxy_addr("https://proxy.example.com").as_deref(),
+            Some("proxy.example.com:443")
+        );
+        assert_eq!(proxy_addr("not a url"), None);
+    }
+}
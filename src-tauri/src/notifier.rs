@@ -0,0 +1,265 @@
+/*
+后端系统通知（设置 `desktopNotifications` 关闭时全部不发送）：
+1) 批量上传任务结束时汇总成功、失败、未上传的数量；
+2) 单个文件重试后仍上传失败时通知，`FAILURE_INTERVAL` 内只通知一次，避免整批失败时刷屏；
+   主窗口在前台时以上两类不发送，界面已显示进度与结果；
+3) 监听文件夹自动上传成功或失败时通知，成功时正文带链接（已复制时注明）；
+4) 带链接的通知附加 `copy-url` 操作与 `extra.url`，支持通知操作的平台（Android）上点击「复制链接」
+   由前端写入剪贴板；桌面端通知不支持点击回调，仅显示链接。
+*/
+
+use std::{
+    path::Path,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use log::warn;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::{NotificationExt, PermissionState};
+
+use crate::batch_upload::{BatchJob, ItemStatus};
+
+/// 与前端注册的操作类型一致
+const COPY_URL_ACTION: &str = "copy-url";
+const FAILURE_INTERVAL: Duration = Duration::from_secs(30);
+
+static LAST_FAILURE: Mutex<Option<Instant>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Notice {
+    title: String,
+    body: String,
+    url: Option<String>,
+}
+
+fn enabled(app: &AppHandle) -> bool {
+    crate::settings::current_settings(app).desktop_notifications
+}
+
+fn main_window_focused(app: &AppHandle) -> bool {
+    app.get_webview_window("main")
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false)
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+fn show(app: &AppHandle, notice: Notice) {
+    let notification = app.notification();
+    match notification.permission_state() {
+        Ok(PermissionState::Granted) => {}
+        Ok(PermissionState::Denied) => return,
+        _ => {
+            if !matches!(
+                notification.request_permission(),
+                Ok(PermissionState::Granted)
+            ) {
+                return;
+            }
+        }
+    }
+    let mut builder = notification
+        .builder()
+        .title(&notice.title)
+        .body(&notice.body);
+    if let Some(url) = &notice.url {
+        builder = builder
+            .action_type_id(COPY_URL_ACTION)
+            .extra("url", url.clone());
+    }
+    if let Err(err) = builder.show() {
+        warn!("show notification failed: {}", err);
+    }
+}
+
+fn batch_notice(job: &BatchJob) -> Notice {
+    let count = |matches: fn(ItemStatus) -> bool| {
+        job.items.iter().filter(|item| matches(item.status)).count()
+    };
+    let succeeded = count(|status| matches!(status, ItemStatus::Done | ItemStatus::Duplicate));
+    let blocked = count(|status| status == ItemStatus::Blocked);
+    let mut body = format!("{}：成功 {} 个", job.host, succeeded);
+    if job.failed > 0 {
+        body.push_str(&format!("，失败 {} 个", job.failed));
+    }
+    if blocked > 0 {
+        body.push_str(&format!("，{} 个含隐私信息未上传", blocked));
+    }
+    if job.cancelled {
+        body.push_str("，已取消");
+    }
+    // 只有一个链接时附带，便于直接复制
+    let url = if succeeded == 1 {
+        job.items.iter().find_map(|item| item.url.clone())
+    } else {
+        None
+    };
+    if let Some(url) = &url {
+        body.push('\n');
+        body.push_str(url);
+    }
+    Notice {
+        title: if job.failed == 0 && blocked == 0 && !job.cancelled {
+            "批量上传完成".to_string()
+        } else {
+            "批量上传结束".to_string()
+        },
+        body,
+        url,
+    }
+}
+
+/// 批量上传任务结束
+pub(crate) fn batch_finished(app: &AppHandle, job: &BatchJob) {
+    if job.total == 0 || !enabled(app) || main_window_focused(app) {
+        return;
+    }
+    show(app, batch_notice(job));
+}
+
+/// 距上次失败通知超过间隔时返回 true 并记录本次时间
+fn take_failure_slot(last: &mut Option<Instant>, now: Instant) -> bool {
+    if last.is_some_and(|last| now.duration_since(last) < FAILURE_INTERVAL) {
+        return false;
+    }
+    *last = Some(now);
+    true
+}
+
+/// 单个文件重试后仍上传失败（取消的上传不通知）
+pub(crate) fn upload_failed(app: &AppHandle, path: &str, host: &str, error: &str) {
+    if error == crate::upload::CANCELLED_ERROR || !enabled(app) || main_window_focused(app) {
+        return;
+    }
+    let Ok(mut last) = LAST_FAILURE.lock() else {
+        return;
+    };
+    if !take_failure_slot(&mut last, Instant::now()) {
+        return;
+    }
+    drop(last);
+    show(
+        app,
+        Notice {
+            title: "上传失败".to_string(),
+            body: format!("{} → {}：{}", file_name(path), host, error),
+            url: None,
+        },
+    );
+}
+
+/// 监听文件夹自动上传完成
+pub(crate) fn watch_folder_uploaded(app: &AppHandle, path: &str, url: &str, copied: bool) {
+    if !enabled(app) {
+        return;
+    }
+    let title = if copied {
+        "已上传，链接已复制"
+    } else {
+        "已上传"
+    };
+    show(
+        app,
+        Notice {
+            title: title.to_string(),
+            body: format!("{}\n{}", file_name(path), url),
+            url: Some(url.to_string()),
+        },
+    );
+}
+
+/// 监听文件夹自动上传失败，不受失败通知间隔限制
+pub(crate) fn watch_folder_failed(app: &AppHandle, path: &str, host: &str, error: &str) {
+    if !enabled(app) {
+        return;
+    }
+    show(
+        app,
+        Notice {
+            title: "监听文件夹上传失败".to_string(),
+            body: format!("{} → {}：{}", file_name(path), host, error),
+            url: None,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch_upload::BatchItem;
+
+    fn item(index: usize, status: ItemStatus, url: Option<&str>) -> BatchItem {
+        BatchItem {
+            index,
+            path: format!("/tmp/{index}.png"),
+            status,
+            url: url.map(str::to_string),
+            delete_id: None,
+            gallery_id: None,
+            error: None,
+            tags: Vec::new(),
+            privacy: None,
+        }
+    }
+
+    fn job(items: Vec<BatchItem>) -> BatchJob {
+        BatchJob {
+            id: "job".to_string(),
+            host: "s3".to_string(),
+            created_at: String::new(),
+            total: items.len(),
+            completed: items.len(),
+            failed: items
+                .iter()
+                .filter(|item| item.status == ItemStatus::Failed)
+                .count(),
+            finished: true,
+            cancelled: false,
+            items,
+        }
+    }
+
+    #[test]
+    fn test_batch_notice() {
+        let notice = batch_notice(&job(vec![item(
+            0,
+            ItemStatus::Done,
+            Some("https://img.example.com/0.png"),
+        )]));
+        assert_eq!(notice.title, "批量上传完成");
+        assert_eq!(notice.body, "s3：成功 1 个\nhttps://img.example.com/0.png");
+        assert_eq!(notice.url.as_deref(), Some("https://img.example.com/0.png"));
+
+        let notice = batch_notice(&job(vec![
+            item(0, ItemStatus::Done, Some("https://img.example.com/0.png")),
+            item(
+                1,
+                ItemStatus::Duplicate,
+                Some("https://img.example.com/1.png"),
+            ),
+            item(2, ItemStatus::Failed, None),
+            item(3, ItemStatus::Blocked, None),
+        ]));
+        assert_eq!(notice.title, "批量上传结束");
+        assert_eq!(
+            notice.body,
+            "s3：成功 2 个，失败 1 个，1 个含隐私信息未上传"
+        );
+        assert_eq!(notice.url, None);
+    }
+
+    #[test]
+    fn test_failure_interval() {
+        let mut last = None;
+        let now = Instant::now();
+        assert!(take_failure_slot(&mut last, now));
+        assert!(!take_failure_slot(&mut last, now + Duration::from_secs(5)));
+        assert!(take_failure_slot(&mut last, now + FAILURE_INTERVAL));
+    }
+}
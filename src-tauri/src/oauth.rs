@@ -0,0 +1,569 @@
+/*
+图床 OAuth2 授权辅助：
+1) 授权码模式：本地回环地址接收回调，配合 PKCE，适用于桌面端（Imgur、Google Photos、Flickr 等）；
+2) 设备码模式：在无法回调的场景下由用户在浏览器中输入验证码；
+3) 令牌以 JSON 形式保存在系统钥匙串（`oauth:<provider_id>`），过期前自动刷新。
+插件只需提供端点与 client_id，由后端完成交互与令牌管理。
+*/
+
+use std::{collections::HashMap, time::Duration};
+
+use base64::{Engine as _, engine::general_purpose};
+use log::{error, info, warn};
+use reqwest::{Client, header::ACCEPT};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+use tauri_plugin_opener::OpenerExt;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+use url::Url;
+
+use crate::secrets::{delete_secret, read_secret, write_secret};
+
+/// 等待浏览器回调的最长时间
+const AUTHORIZE_TIMEOUT_SECS: u64 = 300;
+/// 令牌剩余有效期低于该值时视为需要刷新
+const REFRESH_MARGIN_SECS: i64 = 60;
+const DEFAULT_DEVICE_POLL_INTERVAL_SECS: u64 = 5;
+const CALLBACK_PATH: &str = "/callback";
+const MAX_CALLBACK_REQUEST_BYTES: usize = 16 * 1024;
+
+/// 插件提供的 OAuth 客户端配置
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthClientConfig {
+    /// 令牌存储标识，通常为插件 id
+    pub provider_id: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub authorize_url: Option<String>,
+    pub token_url: String,
+    #[serde(default)]
+    pub device_authorization_url: Option<String>,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// 附加到授权地址上的额外参数（如 `access_type=offline`）
+    #[serde(default)]
+    pub extra_params: HashMap<String, String>,
+    /// 固定回调端口，部分服务要求回调地址与注册值完全一致；默认随机端口
+    #[serde(default)]
+    pub redirect_port: Option<u16>,
+    /// 是否启用 PKCE，默认启用
+    #[serde(default = "default_use_pkce")]
+    pub use_pkce: bool,
+}
+
+fn default_use_pkce() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthToken {
+    pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub token_type: Option<String>,
+    /// 过期时间（Unix 秒），服务端未返回有效期时为 None
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+    #[serde(default)]
+    pub scope: Option<String>,
+    /// 服务端返回的其他字段（如 Imgur 的 account_username）
+    #[serde(default)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl OAuthToken {
+    fn is_expiring(&self) -> bool {
+        self.expires_at
+            .is_some_and(|at| at - chrono::Utc::now().timestamp() <= REFRESH_MARGIN_SECS)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceAuthorization {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: Option<String>,
+    pub expires_in: u64,
+    pub interval: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    token_type: Option<String>,
+    expires_in: Option<serde_json::Value>,
+    scope: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+    #[serde(flatten)]
+    extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    user_code: String,
+    #[serde(alias = "verification_url")]
+    verification_uri: String,
+    verification_uri_complete: Option<String>,
+    expires_in: Option<u64>,
+    interval: Option<u64>,
+}
+
+/// 令牌请求的结果：成功、需继续轮询或失败
+enum TokenOutcome {
+    Token(OAuthToken),
+    Pending { slow_down: bool },
+}
+
+fn storage_id(provider_id: &str) -> Result<String, String> {
+    let trimmed = provider_id.trim();
+    if trimmed.is_empty() {
+        return Err("oauth provider id must not be empty".to_string());
+    }
+    Ok(format!("oauth:{}", trimmed))
+}
+
+fn load_token(provider_id: &str) -> Result<Option<OAuthToken>, String> {
+    let Some(raw) = read_secret(&storage_id(provider_id)?)? else {
+        return Ok(None);
+    };
+    serde_json::from_str(&raw)
+        .map(Some)
+        .map_err(|e| format!("parse stored oauth token: {e}"))
+}
+
+fn store_token(provider_id: &str, token: &OAuthToken) -> Result<(), String> {
+    let raw = serde_json::to_string(token).map_err(|e| format!("serialize oauth token: {e}"))?;
+    write_secret(&storage_id(provider_id)?, &raw)
+}
+
+fn http_client() -> Result<Client, String> {
+    Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("failed to build http client: {}", e))
+}
+
+/// 生成 PKCE code_verifier（64 个十六进制字符，满足 43~128 的长度要求）
+fn generate_code_verifier() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+fn code_challenge(verifier: &str) -> String {
+    let digest = Sha256::digest(verifier.as_bytes());
+    general_purpose::URL_SAFE_NO_PAD.encode(digest)
+}
+
+fn parse_expires_in(value: Option<&serde_json::Value>) -> Option<i64> {
+    match value? {
+        serde_json::Value::Number(n) => n.as_i64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// 向令牌端点提交表单；`authorization_pending` / `slow_down` 作为轮询状态返回
+async fn request_token(
+    client: &Client,
+    config: &OAuthClientConfig,
+    mut params: Vec<(&str, String)>,
+) -> Result<TokenOutcome, String> {
+    params.push(("client_id", config.client_id.clone()));
+    if let Some(secret) = config.client_secret.as_ref().filter(|s| !s.is_empty()) {
+        params.push(("client_secret", secret.clone()));
+    }
+
+    let response = client
+        .post(&config.token_url)
+        .header(ACCEPT, "application/json")
+        .form(&params)
+        .send()
+        .await
+        .map_err(|e| format!("oauth token request failed: {}", e))?;
+    let status = response.status();
+    let raw_text = response
+        .text()
+        .await
+        .map_err(|e| format!("failed to read oauth token response: {}", e))?;
+
+    let parsed: TokenResponse = serde_json::from_str(&raw_text)
+        .map_err(|_| format!("oauth token endpoint returned {}: {}", status, raw_text))?;
+
+    if let Some(code) = parsed.error.as_deref() {
+        return match code {
+            "authorization_pending" => Ok(TokenOutcome::Pending { slow_down: false }),
+            "slow_down" => Ok(TokenOutcome::Pending { slow_down: true }),
+            _ => Err(format!(
+                "oauth error {}: {}",
+                code,
+                parsed.error_description.as_deref().unwrap_or("")
+            )),
+        };
+    }
+
+    let access_token = parsed
+        .access_token
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| format!("oauth token endpoint returned {}: {}", status, raw_text))?;
+    let expires_at = parse_expires_in(parsed.expires_in.as_ref())
+        .map(|secs| chrono::Utc::now().timestamp() + secs);
+
+    Ok(TokenOutcome::Token(OAuthToken {
+        access_token,
+        refresh_token: parsed.refresh_token,
+        token_type: parsed.token_type,
+        expires_at,
+        scope: parsed.scope,
+        extra: parsed.extra,
+    }))
+}
+
+async fn request_token_once(
+    client: &Client,
+    config: &OAuthClientConfig,
+    params: Vec<(&str, String)>,
+) -> Result<OAuthToken, String> {
+    match request_token(client, config, params).await? {
+        TokenOutcome::Token(token) => Ok(token),
+        TokenOutcome::Pending { .. } => Err("oauth authorization still pending".to_string()),
+    }
+}
+
+/// 在本地回环端口上等待一次授权回调，返回查询参数
+async fn wait_for_callback(listener: TcpListener) -> Result<HashMap<String, String>, String> {
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| format!("accept oauth callback: {e}"))?;
+
+        let mut buffer = Vec::with_capacity(1024);
+        let mut chunk = [0u8; 1024];
+        while !buffer.windows(4).any(|w| w == b"\r\n\r\n")
+            && buffer.len() < MAX_CALLBACK_REQUEST_BYTES
+        {
+            let read = stream
+                .read(&mut chunk)
+                .await
+                .map_err(|e| format!("read oauth callback: {e}"))?;
+            if read == 0 {
+                break;
+            }
+            buffer.extend_from_slice(&chunk[..read]);
+        }
+
+        let request = String::from_utf8_lossy(&buffer);
+        let target = request
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .unwrap_or("");
+        let parsed = Url::parse(&format!("http://127.0.0.1{}", target)).ok();
+
+        // 浏览器可能顺带请求 favicon 等资源，忽略非回调路径
+        let Some(parsed) = parsed.filter(|u| u.path() == CALLBACK_PATH) else {
+            let _ = stream
+                .write_all(
+                    b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .await;
+            continue;
+        };
+
+        let params: HashMap<String, String> = parsed.query_pairs().into_owned().collect();
+        let body = if params.contains_key("code") {
+            "<html><body><h3>授权成功，可以关闭此页面并返回应用。</h3></body></html>"
+        } else {
+            "<html><body><h3>授权失败，请返回应用重试。</h3></body></html>"
+        };
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        let _ = stream.shutdown().await;
+        return Ok(params);
+    }
+}
+
+async fn authorize_with_browser(
+    app: &AppHandle,
+    config: &OAuthClientConfig,
+) -> Result<OAuthToken, String> {
+    let authorize_url = config
+        .authorize_url
+        .as_deref()
+        .ok_or_else(|| "authorizeUrl is required for authorization code flow".to_string())?;
+
+    let listener = TcpListener::bind(("127.0.0.1", config.redirect_port.unwrap_or(0)))
+        .await
+        .map_err(|e| format!("bind oauth callback listener: {e}"))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("oauth callback listener address: {e}"))?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{}{}", port, CALLBACK_PATH);
+
+    let state = uuid::Uuid::new_v4().simple().to_string();
+    let verifier = config.use_pkce.then(generate_code_verifier);
+
+    let mut url = Url::parse(authorize_url).map_err(|e| format!("invalid authorizeUrl: {e}"))?;
+    {
+        let mut query = url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &config.client_id)
+            .append_pair("redirect_uri", &redirect_uri)
+            .append_pair("state", &state);
+        if !config.scopes.is_empty() {
+            query.append_pair("scope", &config.scopes.join(" "));
+        }
+        if let Some(verifier) = &verifier {
+            query
+                .append_pair("code_challenge", &code_challenge(verifier))
+                .append_pair("code_challenge_method", "S256");
+        }
+        for (key, value) in &config.extra_params {
+            query.append_pair(key, value);
+        }
+    }
+
+    app.opener()
+        .open_url(url.as_str(), None::<&str>)
+        .map_err(|e| format!("open authorization page: {e}"))?;
+
+    let params = tokio::time::timeout(
+        Duration::from_secs(AUTHORIZE_TIMEOUT_SECS),
+        wait_for_callback(listener),
+    )
+    .await
+    .map_err(|_| "oauth authorization timed out".to_string())??;
+
+    if let Some(err) = params.get("error") {
+        return Err(format!(
+            "oauth authorization denied: {} {}",
+            err,
+            params
+                .get("error_description")
+                .map(String::as_str)
+                .unwrap_or("")
+        ));
+    }
+    if params.get("state") != Some(&state) {
+        return Err("oauth state mismatch".to_string());
+    }
+    let code = params
+        .get("code")
+        .cloned()
+        .ok_or_else(|| "oauth callback missing code".to_string())?;
+
+    let mut form = vec![
+        ("grant_type", "authorization_code".to_string()),
+        ("code", code),
+        ("redirect_uri", redirect_uri),
+    ];
+    if let Some(verifier) = verifier {
+        form.push(("code_verifier", verifier));
+    }
+    request_token_once(&http_client()?, config, form).await
+}
+
+async fn refresh_token(
+    config: &OAuthClientConfig,
+    current: &OAuthToken,
+) -> Result<OAuthToken, String> {
+    let refresh = current
+        .refresh_token
+        .clone()
+        .ok_or_else(|| "oauth token has no refresh token".to_string())?;
+    let form = vec![
+        ("grant_type", "refresh_token".to_string()),
+        ("refresh_token", refresh.clone()),
+    ];
+    let mut token = request_token_once(&http_client()?, config, form).await?;
+    // 部分服务刷新时不返回新的 refresh_token，沿用旧值
+    if token.refresh_token.is_none() {
+        token.refresh_token = Some(refresh);
+    }
+    Ok(token)
+}
+
+/// 授权码 + PKCE 模式：打开系统浏览器，在本地回环地址接收回调并换取令牌
+#[tauri::command]
+pub async fn oauth_authorize(
+    app: AppHandle,
+    config: OAuthClientConfig,
+) -> Result<OAuthToken, String> {
+    let result = authorize_with_browser(&app, &config).await;
+    match result {
+        Ok(token) => {
+            store_token(&config.provider_id, &token)?;
+            info!("oauth_authorize success: provider={}", config.provider_id);
+            Ok(token)
+        }
+        Err(err) => {
+            error!(
+                "oauth_authorize failed: provider={}, error={}",
+                config.provider_id, err
+            );
+            Err(err)
+        }
+    }
+}
+
+/// 设备码模式第一步：获取用户验证码与验证地址
+#[tauri::command]
+pub async fn oauth_device_start(config: OAuthClientConfig) -> Result<DeviceAuthorization, String> {
+    let endpoint = config
+        .device_authorization_url
+        .as_deref()
+        .ok_or_else(|| "deviceAuthorizationUrl is required for device flow".to_string())?;
+
+    let mut form = vec![("client_id", config.client_id.clone())];
+    if !config.scopes.is_empty() {
+        form.push(("scope", config.scopes.join(" ")));
+    }
+
+    let response = http_client()?
+        .post(endpoint)
+        .header(ACCEPT, "application/json")
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| format!("oauth device request failed: {}", e))?;
+    let status = response.status();
+    let raw_text = response
+        .text()
+        .await
+        .map_err(|e| format!("failed to read oauth device response: {}", e))?;
+    if !status.is_success() {
+        return Err(format!(
+            "oauth device endpoint returned {}: {}",
+            status, raw_text
+        ));
+    }
+
+    let parsed: DeviceCodeResponse =
+        serde_json::from_str(&raw_text).map_err(|e| format!("parse oauth device response: {e}"))?;
+    info!("oauth_device_start: provider={}", config.provider_id);
+    Ok(DeviceAuthorization {
+        device_code: parsed.device_code,
+        user_code: parsed.user_code,
+        verification_uri: parsed.verification_uri,
+        verification_uri_complete: parsed.verification_uri_complete,
+        expires_in: parsed.expires_in.unwrap_or(AUTHORIZE_TIMEOUT_SECS),
+        interval: parsed.interval.unwrap_or(DEFAULT_DEVICE_POLL_INTERVAL_SECS),
+    })
+}
+
+/// 设备码模式第二步：按间隔轮询令牌端点，直到用户完成授权或验证码过期
+#[tauri::command]
+pub async fn oauth_device_poll(
+    config: OAuthClientConfig,
+    device: DeviceAuthorization,
+) -> Result<OAuthToken, String> {
+    let client = http_client()?;
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(device.expires_in);
+    let mut interval = device.interval.max(1);
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        if tokio::time::Instant::now() >= deadline {
+            return Err("oauth device code expired".to_string());
+        }
+
+        let form = vec![
+            (
+                "grant_type",
+                "urn:ietf:params:oauth:grant-type:device_code".to_string(),
+            ),
+            ("device_code", device.device_code.clone()),
+        ];
+        match request_token(&client, &config, form).await {
+            Ok(TokenOutcome::Token(token)) => {
+                store_token(&config.provider_id, &token)?;
+                info!("oauth_device_poll success: provider={}", config.provider_id);
+                return Ok(token);
+            }
+            Ok(TokenOutcome::Pending { slow_down }) => {
+                if slow_down {
+                    interval += 5;
+                }
+            }
+            Err(err) => {
+                error!(
+                    "oauth_device_poll failed: provider={}, error={}",
+                    config.provider_id, err
+                );
+                return Err(err);
+            }
+        }
+    }
+}
+
+/// 强制使用 refresh_token 刷新令牌
+#[tauri::command]
+pub async fn oauth_refresh(config: OAuthClientConfig) -> Result<OAuthToken, String> {
+    let current = load_token(&config.provider_id)?
+        .ok_or_else(|| format!("no oauth token stored for {}", config.provider_id))?;
+    let token = refresh_token(&config, &current).await.inspect_err(|err| {
+        error!(
+            "oauth_refresh failed: provider={}, error={}",
+            config.provider_id, err
+        );
+    })?;
+    store_token(&config.provider_id, &token)?;
+    info!("oauth_refresh success: provider={}", config.provider_id);
+    Ok(token)
+}
+
+/// 读取已保存的令牌；即将过期且可刷新时自动刷新，未授权时返回 None
+#[tauri::command]
+pub async fn oauth_get_token(config: OAuthClientConfig) -> Result<Option<OAuthToken>, String> {
+    let Some(current) = load_token(&config.provider_id)? else {
+        return Ok(None);
+    };
+    if !current.is_expiring() || current.refresh_token.is_none() {
+        return Ok(Some(current));
+    }
+
+    match refresh_token(&config, &current).await {
+        Ok(token) => {
+            store_token(&config.provider_id, &token)?;
+            Ok(Some(token))
+        }
+        Err(err) => {
+            warn!(
+                "oauth_get_token refresh failed: provider={}, error={}",
+                config.provider_id, err
+            );
+            Err(err)
+        }
+    }
+}
+
+/// 删除已保存的令牌
+#[tauri::command]
+pub fn oauth_logout(provider_id: String) -> Result<(), String> {
+    delete_secret(&storage_id(&provider_id)?)?;
+    info!("oauth_logout: provider={}", provider_id);
+    Ok(())
+}
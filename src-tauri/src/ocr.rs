@@ -0,0 +1,143 @@
+/*
+图片文字识别（OCR）：
+1) 调用本机安装的 Tesseract 命令行（设置中可指定可执行文件路径，留空时从 PATH 查找），
+   不为各平台捆绑原生库与语言模型；语言沿用 Tesseract 写法，如 `eng`、`chi_sim+eng`；
+2) `ocr_image` 识别指定文件，传入图库 id 时将文字写入图库全文索引，图库可按图片中的文字搜索；
+3) 开启「上传后识别文字」时，界面与后端上传流程写入图库后在后台识别，失败只记录日志。
+*/
+
+use std::path::Path;
+use std::process::Command;
+
+use log::{info, warn};
+use tauri::AppHandle;
+
+const DEFAULT_BINARY: &str = "tesseract";
+
+/// 校验 Tesseract 语言参数，避免被当作额外的命令行选项
+pub(crate) fn validate_language(lang: &str) -> Result<(), String> {
+    let valid = !lang.is_empty()
+        && lang.split('+').all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("无效的识别语言: {lang}，应形如 eng 或 chi_sim+eng"))
+    }
+}
+
+/// 去掉每行首尾空白与空行
+fn normalize_text(raw: &str) -> String {
+    raw.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn recognize(binary: &str, path: &Path, lang: &str) -> Result<String, String> {
+    validate_language(lang)?;
+    if !path.is_file() {
+        return Err(format!("文件不存在: {}", path.display()));
+    }
+    let binary = if binary.is_empty() {
+        DEFAULT_BINARY
+    } else {
+        binary
+    };
+    let mut command = Command::new(binary);
+    command.arg(path).arg("stdout").arg("-l").arg(lang);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW：不弹出控制台窗口
+        command.creation_flags(0x0800_0000);
+    }
+    let output = command.output().map_err(|err| {
+        if err.kind() == std::io::ErrorKind::NotFound {
+            "未找到 Tesseract，请先安装，或在设置中指定 tesseract 可执行文件路径".to_string()
+        } else {
+            format!("启动 Tesseract 失败: {err}")
+        }
+    })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("文字识别失败: {}", stderr.trim()));
+    }
+    Ok(normalize_text(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn store_text(app: &AppHandle, gallery_id: i64, text: &str) -> Result<(), String> {
+    crate::gallery::store_from_app(app)?
+        .set_ocr_text(gallery_id, text)
+        .map_err(|err| err.to_string())
+}
+
+/// 上传写入图库后按设置在后台识别文字
+pub(crate) fn index_in_background(app: &AppHandle, gallery_id: i64, path: String) {
+    let settings = crate::settings::current_settings(app);
+    if !settings.ocr_auto_index {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = recognize(
+            &settings.tesseract_path,
+            Path::new(&path),
+            &settings.ocr_language,
+        )
+        .and_then(|text| store_text(&app, gallery_id, &text));
+        match result {
+            Ok(()) => info!("ocr indexed gallery item {}", gallery_id),
+            Err(err) => warn!("ocr gallery item {} failed: {}", gallery_id, err),
+        }
+    });
+}
+
+/// 识别图片中的文字；`lang` 留空时使用设置中的语言，传入 `gallery_id` 时写入图库索引
+#[tauri::command]
+pub async fn ocr_image(
+    app: AppHandle,
+    path: String,
+    lang: Option<String>,
+    gallery_id: Option<i64>,
+) -> Result<String, String> {
+    let settings = crate::settings::current_settings(&app);
+    let lang = lang
+        .map(|lang| lang.trim().to_string())
+        .filter(|lang| !lang.is_empty())
+        .unwrap_or(settings.ocr_language);
+    tauri::async_runtime::spawn_blocking(move || {
+        let text = recognize(&settings.tesseract_path, Path::new(&path), &lang)?;
+        if let Some(id) = gallery_id {
+            store_text(&app, id, &text)?;
+        }
+        Ok(text)
+    })
+    .await
+    .map_err(|err| format!("文字识别任务失败: {err}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_language() {
+        assert!(validate_language("eng").is_ok());
+        assert!(validate_language("chi_sim+eng").is_ok());
+        assert!(validate_language("").is_err());
+        assert!(validate_language("eng+").is_err());
+        assert!(validate_language("--psm").is_err());
+        assert!(validate_language("eng 6").is_err());
+    }
+
+    #[test]
+    fn test_normalize_text() {
+        assert_eq!(
+            normalize_text("  Error: file not found \n\n\x0c  at main.rs:3\n"),
+            "Error: file not found\nat main.rs:3"
+        );
+    }
+}
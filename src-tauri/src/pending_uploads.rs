@@ -0,0 +1,1083 @@
+/*
+上传日志（uploads.db 的 pending_uploads 表，持久化上传队列与崩溃恢复共用）：
+1) 每次 `upload_image` / `s3_upload` 调用前在 SQLite 中登记一条待上传记录（含请求参数）；
+   参数中的密钥（请求头、S3 Secret Key、CDN Token 等）不写入数据库，
+   以 `pending-upload:<id>` 存入钥匙串，重新执行时再合并回参数；
+2) 上传成功或被用户取消后删除记录，失败则标记为 failed 并记录错误与尝试次数；
+3) 应用崩溃或中途退出后，下次启动时自动重新执行仍为 pending 的记录，
+   结果写回数据库并通过 `pending-uploads://completed` 事件通知前端（设置页「未完成的上传」）；
+4) 后端上传流程（host_upload::upload_prepared）的每个文件登记为 host_upload 记录并记录阶段：
+   compressed（待上传文件已就绪）→ uploading（正在发送）→ gallery_pending（图床已返回链接、
+   已发送的字节数，尚未写入图库），上传失败或全部完成后删除记录；写日志失败只记录警告，不影响上传本身；
+5) 启动时 `resume_pending` 统一处理上次遗留的记录：
+   - upload_image / s3_upload：重新执行；
+   - host_upload 的 gallery_pending：用记录中的链接补写图库，再删除临时文件；
+   - host_upload 的 compressed / uploading：待上传文件仍存在时重新上传（经过上传队列与重试），
+     文件已不存在时回滚，只删除记录（去除元数据后的临时文件丢失时不会改传原图）；
+6) 记录中的源文件与临时文件在清理临时目录时保留（temp_cleanup）；
+7) 提供列出、手动重试与丢弃记录的命令；
+8) 启动时清理过期记录：completed 保留 7 天，failed 保留 30 天。
+*/
+
+use std::{
+    fs,
+    future::Future,
+    path::Path,
+    sync::{Mutex, OnceLock},
+};
+
+use chrono::{Duration, Utc};
+use log::{error, info, warn};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter};
+
+use crate::gallery::NewGalleryItem;
+use crate::host_upload::PipelineOptions;
+use crate::internal_hosts::HostUploadResult;
+use crate::s3::S3UploadOptions;
+use crate::secrets::{delete_secret, read_secret, write_secret};
+use crate::upload::{UploadConfig, UploadFormat};
+
+const DB_FILE_NAME: &str = "uploads.db";
+const COMPLETED_EVENT: &str = "pending-uploads://completed";
+/// 已完成记录（等待前端处理结果）的保留天数
+const COMPLETED_RETENTION_DAYS: i64 = 7;
+/// 失败记录的保留天数
+const FAILED_RETENTION_DAYS: i64 = 30;
+
+static PENDING_STORE: OnceLock<PendingUploadStore> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingKind {
+    UploadImage,
+    S3Upload,
+    /// 后端上传流程中的文件（参数为 `HostUploadJob`，凭据按图床 id 从配置读取）
+    HostUpload,
+}
+
+impl PendingKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::UploadImage => "upload_image",
+            Self::S3Upload => "s3_upload",
+            Self::HostUpload => "host_upload",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "upload_image" => Some(Self::UploadImage),
+            "s3_upload" => Some(Self::S3Upload),
+            "host_upload" => Some(Self::HostUpload),
+            _ => None,
+        }
+    }
+
+    /// 请求参数中不写入数据库的敏感字段（JSON Pointer）
+    fn secret_fields(self) -> &'static [&'static str] {
+        match self {
+            Self::UploadImage => &[
+                "/config/headers",
+                "/config/additionalFields",
+                "/config/additionalJson",
+            ],
+            Self::S3Upload => &[
+                "/options/secretAccessKey",
+                "/options/sessionToken",
+                "/options/cdnApiToken",
+            ],
+            Self::HostUpload => &[],
+        }
+    }
+}
+
+/// host_upload 记录所处的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Stage {
+    Compressed,
+    Uploading,
+    GalleryPending,
+}
+
+impl Stage {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Compressed => "compressed",
+            Self::Uploading => "uploading",
+            Self::GalleryPending => "gallery_pending",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "uploading" => Self::Uploading,
+            "gallery_pending" => Self::GalleryPending,
+            _ => Self::Compressed,
+        }
+    }
+}
+
+fn secret_id(id: i64) -> String {
+    format!("pending-upload:{}", id)
+}
+
+/// 从参数中移出敏感字段，返回 JSON Pointer -> 值
+fn split_secrets(kind: PendingKind, payload: &mut Value) -> serde_json::Map<String, Value> {
+    let mut secrets = serde_json::Map::new();
+    for pointer in kind.secret_fields() {
+        let Some((parent, key)) = pointer.rsplit_once('/') else {
+            continue;
+        };
+        if let Some(value) = payload
+            .pointer_mut(parent)
+            .and_then(Value::as_object_mut)
+            .and_then(|object| object.remove(key))
+            && !value.is_null()
+        {
+            secrets.insert((*pointer).to_string(), value);
+        }
+    }
+    secrets
+}
+
+/// 将钥匙串中保存的敏感字段合并回参数
+fn merge_secrets(payload: &mut Value, secrets: serde_json::Map<String, Value>) {
+    for (pointer, value) in secrets {
+        let Some((parent, key)) = pointer.rsplit_once('/') else {
+            continue;
+        };
+        if let Some(object) = payload.pointer_mut(parent).and_then(Value::as_object_mut) {
+            object.insert(key.to_string(), value);
+        }
+    }
+}
+
+fn store_secrets(id: i64, secrets: &serde_json::Map<String, Value>) -> Result<(), String> {
+    if secrets.is_empty() {
+        return Ok(());
+    }
+    let text = serde_json::to_string(secrets).map_err(|e| format!("serialize secrets: {e}"))?;
+    write_secret(&secret_id(id), &text)
+}
+
+fn load_secrets(id: i64) -> Result<serde_json::Map<String, Value>, String> {
+    match read_secret(&secret_id(id))? {
+        Some(text) => serde_json::from_str(&text)
+            .map_err(|e| format!("parse secrets of pending upload {id}: {e}")),
+        None => Ok(serde_json::Map::new()),
+    }
+}
+
+/// 删除记录对应的钥匙串条目，失败只记录日志
+fn forget_secrets(id: i64) {
+    if let Err(err) = delete_secret(&secret_id(id)) {
+        warn!("failed to delete secrets of pending upload {}: {}", id, err);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingStatus {
+    Pending,
+    Failed,
+    Completed,
+}
+
+impl PendingStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Failed => "failed",
+            Self::Completed => "completed",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "failed" => Self::Failed,
+            "completed" => Self::Completed,
+            _ => Self::Pending,
+        }
+    }
+}
+
+/// 返回给前端的记录（不含请求参数，避免暴露其中的密钥）
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingUpload {
+    pub id: i64,
+    pub kind: PendingKind,
+    pub file_path: String,
+    pub host: Option<String>,
+    pub config_hash: String,
+    pub attempts: i64,
+    pub status: PendingStatus,
+    /// host_upload 记录的阶段
+    pub stage: Option<String>,
+    pub last_error: Option<String>,
+    pub result: Option<Value>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// `upload_image` 的请求参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadImageJob {
+    pub file_path: String,
+    pub format: UploadFormat,
+    pub config: UploadConfig,
+}
+
+/// `s3_upload` 的请求参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3UploadJob {
+    pub file_path: String,
+    pub original_file_name: String,
+    pub options: S3UploadOptions,
+}
+
+/// 后端上传流程中一个文件的信息（host_upload 记录的参数）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HostUploadJob {
+    pub source: String,
+    /// 实际上传的文件（压缩或去除元数据后的临时文件，或源文件本身）
+    pub prepared: String,
+    pub file_name: String,
+    pub content_hash: Option<String>,
+    pub record_gallery: bool,
+}
+
+/// 遗留的 host_upload 记录
+#[derive(Debug, Clone, PartialEq)]
+struct HostEntry {
+    id: i64,
+    host: String,
+    job: HostUploadJob,
+    stage: Stage,
+    bytes_sent: Option<i64>,
+    url: Option<String>,
+    delete_id: Option<String>,
+}
+
+pub struct PendingUploadStore {
+    connection: Mutex<Connection>,
+}
+
+impl PendingUploadStore {
+    pub fn new<P: AsRef<Path>>(app_data_dir: P) -> Result<Self, String> {
+        fs::create_dir_all(&app_data_dir)
+            .map_err(|e| format!("create_dir_all {}: {e}", app_data_dir.as_ref().display()))?;
+        let db_path = app_data_dir.as_ref().join(DB_FILE_NAME);
+        let conn =
+            Connection::open(&db_path).map_err(|e| format!("open {}: {e}", db_path.display()))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             CREATE TABLE IF NOT EXISTS pending_uploads (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 kind TEXT NOT NULL,
+                 file_path TEXT NOT NULL,
+                 host TEXT,
+                 config_hash TEXT NOT NULL,
+                 payload TEXT NOT NULL,
+                 attempts INTEGER NOT NULL DEFAULT 0,
+                 status TEXT NOT NULL,
+                 last_error TEXT,
+                 result TEXT,
+                 created_at TEXT NOT NULL,
+                 updated_at TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_pending_status ON pending_uploads (status);
+            ",
+        )
+        .map_err(|e| format!("init pending_uploads schema: {e}"))?;
+        ensure_stage_columns(&conn)?;
+        Ok(Self {
+            connection: Mutex::new(conn),
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, String> {
+        self.connection
+            .lock()
+            .map_err(|_| "pending upload store poisoned".to_string())
+    }
+
+    fn insert(
+        &self,
+        kind: PendingKind,
+        file_path: &str,
+        host: Option<&str>,
+        mut payload: Value,
+    ) -> Result<i64, String> {
+        let secrets = split_secrets(kind, &mut payload);
+        let text = payload.to_string();
+        let now = Utc::now().to_rfc3339();
+        let connection = self.lock()?;
+        connection
+            .execute(
+                "INSERT INTO pending_uploads (kind, file_path, host, config_hash, payload, attempts, status, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 1, ?6, ?7, ?7)",
+                params![
+                    kind.as_str(),
+                    file_path,
+                    host,
+                    config_hash(&text),
+                    text,
+                    PendingStatus::Pending.as_str(),
+                    now
+                ],
+            )
+            .map_err(|e| format!("insert pending upload: {e}"))?;
+        let id = connection.last_insert_rowid();
+        if let Err(err) = store_secrets(id, &secrets) {
+            // 密钥无法保存时记录无法恢复，不保留
+            connection
+                .execute("DELETE FROM pending_uploads WHERE id = ?1", params![id])
+                .map_err(|e| format!("delete pending upload {id}: {e}"))?;
+            return Err(err);
+        }
+        Ok(id)
+    }
+
+    fn set_status(
+        &self,
+        id: i64,
+        status: PendingStatus,
+        last_error: Option<&str>,
+        result: Option<&Value>,
+    ) -> Result<(), String> {
+        let connection = self.lock()?;
+        connection
+            .execute(
+                "UPDATE pending_uploads SET status = ?1, last_error = ?2, result = ?3, updated_at = ?4 WHERE id = ?5",
+                params![
+                    status.as_str(),
+                    last_error,
+                    result.map(|value| value.to_string()),
+                    Utc::now().to_rfc3339(),
+                    id
+                ],
+            )
+            .map_err(|e| format!("update pending upload {id}: {e}"))?;
+        Ok(())
+    }
+
+    /// 重新开始一次尝试：状态回到 pending 并累加尝试次数
+    fn begin_attempt(&self, id: i64) -> Result<(PendingKind, Value), String> {
+        let connection = self.lock()?;
+        let row: Option<(String, String)> = connection
+            .query_row(
+                "SELECT kind, payload FROM pending_uploads WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(|e| format!("load pending upload {id}: {e}"))?;
+        let (kind, payload) = row.ok_or_else(|| format!("pending upload not found: {id}"))?;
+        let kind =
+            PendingKind::parse(&kind).ok_or_else(|| format!("unknown pending kind: {kind}"))?;
+        let mut payload: Value = serde_json::from_str(&payload)
+            .map_err(|e| format!("parse pending payload {id}: {e}"))?;
+        if !kind.secret_fields().is_empty() {
+            merge_secrets(&mut payload, load_secrets(id)?);
+        }
+
+        connection
+            .execute(
+                "UPDATE pending_uploads SET status = ?1, attempts = attempts + 1, updated_at = ?2 WHERE id = ?3",
+                params![PendingStatus::Pending.as_str(), Utc::now().to_rfc3339(), id],
+            )
+            .map_err(|e| format!("update pending upload {id}: {e}"))?;
+        Ok((kind, payload))
+    }
+
+    fn remove(&self, id: i64) -> Result<(), String> {
+        self.remove_row(id)?;
+        forget_secrets(id);
+        Ok(())
+    }
+
+    /// 只删除记录本身（host_upload 记录没有钥匙串条目）
+    fn remove_row(&self, id: i64) -> Result<(), String> {
+        self.lock()?
+            .execute("DELETE FROM pending_uploads WHERE id = ?1", params![id])
+            .map_err(|e| format!("delete pending upload {id}: {e}"))?;
+        Ok(())
+    }
+
+    /// 登记后端上传流程中的文件
+    fn insert_host(&self, host: &str, job: &HostUploadJob) -> Result<i64, String> {
+        let payload =
+            serde_json::to_value(job).map_err(|e| format!("serialize host upload: {e}"))?;
+        let id = self.insert(PendingKind::HostUpload, &job.source, Some(host), payload)?;
+        self.set_stage(id, Stage::Compressed, None, None)?;
+        Ok(id)
+    }
+
+    fn set_stage(
+        &self,
+        id: i64,
+        stage: Stage,
+        bytes_sent: Option<u64>,
+        result: Option<&HostUploadResult>,
+    ) -> Result<(), String> {
+        self.lock()?
+            .execute(
+                "UPDATE pending_uploads SET stage = ?1, bytes_sent = COALESCE(?2, bytes_sent),
+                     url = COALESCE(?3, url), delete_id = COALESCE(?4, delete_id), updated_at = ?5
+                 WHERE id = ?6",
+                params![
+                    stage.as_str(),
+                    bytes_sent.map(|bytes| bytes as i64),
+                    result.map(|result| result.url.as_str()),
+                    result
+                        .map(|result| result.delete_id.as_str())
+                        .filter(|id| !id.is_empty()),
+                    Utc::now().to_rfc3339(),
+                    id
+                ],
+            )
+            .map_err(|e| format!("update pending upload {id}: {e}"))?;
+        Ok(())
+    }
+
+    /// 仍未结束的 host_upload 记录
+    fn host_entries(&self) -> Result<Vec<HostEntry>, String> {
+        let connection = self.lock()?;
+        let mut stmt = connection
+            .prepare(
+                "SELECT id, host, payload, stage, bytes_sent, url, delete_id
+                 FROM pending_uploads WHERE kind = ?1 AND status = ?2 ORDER BY id",
+            )
+            .map_err(|e| format!("query pending uploads: {e}"))?;
+        let rows = stmt
+            .query_map(
+                params![
+                    PendingKind::HostUpload.as_str(),
+                    PendingStatus::Pending.as_str()
+                ],
+                |row| {
+                    let payload: String = row.get(2)?;
+                    let stage: Option<String> = row.get(3)?;
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        payload,
+                        stage,
+                        row.get(4)?,
+                        row.get(5)?,
+                        row.get(6)?,
+                    ))
+                },
+            )
+            .map_err(|e| format!("query pending uploads: {e}"))?;
+        let mut entries = Vec::new();
+        for row in rows {
+            let (id, host, payload, stage, bytes_sent, url, delete_id) =
+                row.map_err(|e| format!("read pending uploads: {e}"))?;
+            let job = serde_json::from_str(&payload)
+                .map_err(|e| format!("parse pending payload {id}: {e}"))?;
+            entries.push(HostEntry {
+                id,
+                host: host.unwrap_or_default(),
+                job,
+                stage: Stage::parse(stage.as_deref().unwrap_or_default()),
+                bytes_sent,
+                url,
+                delete_id,
+            });
+        }
+        Ok(entries)
+    }
+
+    /// 删除超过保留期的 completed / failed 记录，返回删除的条数
+    fn prune(&self) -> Result<usize, String> {
+        let now = Utc::now();
+        let connection = self.lock()?;
+        let mut stmt = connection
+            .prepare(
+                "SELECT id FROM pending_uploads
+                 WHERE (status = ?1 AND updated_at < ?2) OR (status = ?3 AND updated_at < ?4)",
+            )
+            .map_err(|e| format!("query expired pending uploads: {e}"))?;
+        let ids = stmt
+            .query_map(
+                params![
+                    PendingStatus::Completed.as_str(),
+                    (now - Duration::days(COMPLETED_RETENTION_DAYS)).to_rfc3339(),
+                    PendingStatus::Failed.as_str(),
+                    (now - Duration::days(FAILED_RETENTION_DAYS)).to_rfc3339(),
+                ],
+                |row| row.get::<_, i64>(0),
+            )
+            .map_err(|e| format!("query expired pending uploads: {e}"))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("read expired pending uploads: {e}"))?;
+        for id in &ids {
+            connection
+                .execute("DELETE FROM pending_uploads WHERE id = ?1", params![id])
+                .map_err(|e| format!("delete pending upload {id}: {e}"))?;
+            forget_secrets(*id);
+        }
+        Ok(ids.len())
+    }
+
+    fn pending_ids(&self) -> Result<Vec<i64>, String> {
+        let connection = self.lock()?;
+        let mut stmt = connection
+            .prepare("SELECT id FROM pending_uploads WHERE status = ?1 AND kind != ?2 ORDER BY id")
+            .map_err(|e| format!("query pending uploads: {e}"))?;
+        let rows = stmt
+            .query_map(
+                params![
+                    PendingStatus::Pending.as_str(),
+                    PendingKind::HostUpload.as_str()
+                ],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("query pending uploads: {e}"))?;
+        rows.collect::<Result<Vec<i64>, _>>()
+            .map_err(|e| format!("read pending uploads: {e}"))
+    }
+
+    fn file_paths(&self) -> Result<Vec<String>, String> {
+        let mut paths = {
+            let connection = self.lock()?;
+            let mut stmt = connection
+                .prepare("SELECT DISTINCT file_path FROM pending_uploads WHERE status != ?1")
+                .map_err(|e| format!("query pending uploads: {e}"))?;
+            let rows = stmt
+                .query_map(params![PendingStatus::Completed.as_str()], |row| row.get(0))
+                .map_err(|e| format!("query pending uploads: {e}"))?;
+            rows.collect::<Result<Vec<String>, _>>()
+                .map_err(|e| format!("read pending uploads: {e}"))?
+        };
+        paths.extend(
+            self.host_entries()?
+                .into_iter()
+                .filter(|entry| entry.job.prepared != entry.job.source)
+                .map(|entry| entry.job.prepared),
+        );
+        Ok(paths)
+    }
+
+    fn list(&self) -> Result<Vec<PendingUpload>, String> {
+        let connection = self.lock()?;
+        let mut stmt = connection
+            .prepare(
+                "SELECT id, kind, file_path, host, config_hash, attempts, status, last_error, result, created_at, updated_at, stage
+                 FROM pending_uploads ORDER BY id",
+            )
+            .map_err(|e| format!("query pending uploads: {e}"))?;
+        let rows = stmt
+            .query_map([], |row| {
+                let kind: String = row.get(1)?;
+                let status: String = row.get(6)?;
+                let result: Option<String> = row.get(8)?;
+                Ok(PendingUpload {
+                    id: row.get(0)?,
+                    kind: PendingKind::parse(&kind).unwrap_or(PendingKind::UploadImage),
+                    file_path: row.get(2)?,
+                    host: row.get(3)?,
+                    config_hash: row.get(4)?,
+                    attempts: row.get(5)?,
+                    status: PendingStatus::parse(&status),
+                    stage: row.get(11)?,
+                    last_error: row.get(7)?,
+                    result: result.and_then(|text| serde_json::from_str(&text).ok()),
+                    created_at: row.get(9)?,
+                    updated_at: row.get(10)?,
+                })
+            })
+            .map_err(|e| format!("query pending uploads: {e}"))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("read pending uploads: {e}"))
+    }
+}
+
+/// 为 host_upload 记录补齐阶段相关的列（早期版本的表没有这些列）
+fn ensure_stage_columns(conn: &Connection) -> Result<(), String> {
+    let mut existing = Vec::new();
+    {
+        let mut stmt = conn
+            .prepare("PRAGMA table_info(pending_uploads)")
+            .map_err(|e| format!("query pending_uploads schema: {e}"))?;
+        let columns = stmt
+            .query_map([], |row| row.get::<_, String>(1))
+            .map_err(|e| format!("query pending_uploads schema: {e}"))?;
+        for column in columns {
+            existing.push(column.map_err(|e| format!("read pending_uploads schema: {e}"))?);
+        }
+    }
+    for (name, definition) in [
+        ("stage", "TEXT"),
+        ("bytes_sent", "INTEGER"),
+        ("url", "TEXT"),
+        ("delete_id", "TEXT"),
+    ] {
+        if !existing.iter().any(|column| column == name) {
+            conn.execute(
+                &format!("ALTER TABLE pending_uploads ADD COLUMN {name} {definition}"),
+                [],
+            )
+            .map_err(|e| format!("add pending_uploads column {name}: {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// 请求参数的 SHA256 摘要（前 16 个十六进制字符），用于识别相同配置的重复记录
+fn config_hash(payload: &str) -> String {
+    let digest = Sha256::digest(payload.as_bytes());
+    format!("{:x}", digest)[0..16].to_string()
+}
+
+fn store_from_app(app: &AppHandle) -> Result<&'static PendingUploadStore, String> {
+    if let Some(store) = PENDING_STORE.get() {
+        return Ok(store);
+    }
+
+    let app_data_dir = crate::portable::app_data_dir(&app)
+        .map_err(|err| format!("Failed to resolve app data dir: {err}"))?;
+    let store = PendingUploadStore::new(app_data_dir)?;
+
+    match PENDING_STORE.set(store) {
+        Ok(()) => Ok(PENDING_STORE.get().expect("store set just now")),
+        Err(_) => Ok(PENDING_STORE
+            .get()
+            .expect("store should be initialized by another thread")),
+    }
+}
+
+/// 尚未完成的记录引用的源文件与待上传的临时文件，清理临时目录时需保留
+pub(crate) fn referenced_file_paths(app: &AppHandle) -> Result<Vec<String>, String> {
+    store_from_app(app)?.file_paths()
+}
+
+/// 登记并执行一次上传：成功或取消后删除记录，失败则标记为 failed。
+/// 登记失败不影响上传本身，只记录日志。
+pub(crate) async fn run_tracked<T, J, F>(
+    app: &AppHandle,
+    kind: PendingKind,
+    host: Option<&str>,
+    job: &J,
+    file_path: &str,
+    task: F,
+) -> Result<T, String>
+where
+    J: Serialize,
+    F: Future<Output = Result<T, String>>,
+{
+    let tracked = serde_json::to_value(job)
+        .map_err(|e| format!("serialize pending upload: {e}"))
+        .and_then(|payload| {
+            let store = store_from_app(app)?;
+            let id = store.insert(kind, file_path, host, payload)?;
+            Ok((store, id))
+        });
+    let tracked = match tracked {
+        Ok(tracked) => Some(tracked),
+        Err(err) => {
+            warn!("failed to persist pending upload: {}", err);
+            None
+        }
+    };
+
+    let result = task.await;
+
+    if let Some((store, id)) = tracked {
+        let update = match &result {
+            Err(err) if err != crate::upload::CANCELLED_ERROR => {
+                store.set_status(id, PendingStatus::Failed, Some(err), None)
+            }
+            _ => store.remove(id),
+        };
+        if let Err(err) = update {
+            warn!("failed to update pending upload {}: {}", id, err);
+        }
+    }
+
+    result
+}
+
+/// 后端上传流程中一个文件的记录；登记失败时为空，后续操作均不执行
+pub(crate) struct Journaled(Option<(&'static PendingUploadStore, i64)>);
+
+impl Journaled {
+    fn update(&self, apply: impl FnOnce(&PendingUploadStore, i64) -> Result<(), String>) {
+        if let Some((store, id)) = self.0
+            && let Err(err) = apply(store, id)
+        {
+            warn!("failed to update pending upload {}: {}", id, err);
+        }
+    }
+
+    /// 开始发送文件
+    pub(crate) fn uploading(&self) {
+        self.update(|store, id| store.set_stage(id, Stage::Uploading, None, None));
+    }
+
+    /// 图床已返回链接（`bytes_sent` 为已发送的字节数），接下来写入图库
+    pub(crate) fn gallery_pending(&self, result: &HostUploadResult, bytes_sent: Option<u64>) {
+        self.update(|store, id| {
+            store.set_stage(id, Stage::GalleryPending, bytes_sent, Some(result))
+        });
+    }
+
+    /// 上传失败或全部完成
+    pub(crate) fn finish(self) {
+        self.update(|store, id| store.remove_row(id));
+    }
+}
+
+/// 登记后端上传流程即将上传的文件
+pub(crate) fn begin_host_upload(app: &AppHandle, host: &str, job: &HostUploadJob) -> Journaled {
+    match store_from_app(app).and_then(|store| Ok((store, store.insert_host(host, job)?))) {
+        Ok(tracked) => Journaled(Some(tracked)),
+        Err(err) => {
+            warn!("failed to persist host upload: {}", err);
+            Journaled(None)
+        }
+    }
+}
+
+fn remove_prepared(job: &HostUploadJob) {
+    if job.prepared != job.source {
+        let _ = fs::remove_file(&job.prepared);
+    }
+}
+
+/// 用记录中的链接补写图库
+fn complete_gallery(app: &AppHandle, entry: &HostEntry, url: &str) -> Result<(), String> {
+    if !entry.job.record_gallery {
+        return Ok(());
+    }
+    let item = crate::gallery::gallery_insert_item(
+        app.clone(),
+        NewGalleryItem {
+            file_name: entry.job.file_name.clone(),
+            url: url.to_string(),
+            host: entry.host.clone(),
+            delete_marker: entry.delete_id.clone(),
+            inserted_at: None,
+            filesize: entry.bytes_sent,
+            content_hash: entry.job.content_hash.clone(),
+        },
+    )?;
+    crate::ocr::index_in_background(app, item.id, entry.job.source.clone());
+    Ok(())
+}
+
+/// 处理上次退出时仍在进行的 host_upload 记录：补写图库、重新上传或回滚
+fn recover_host_uploads(app: &AppHandle, store: &'static PendingUploadStore) {
+    let entries = match store.host_entries() {
+        Ok(entries) => entries,
+        Err(err) => {
+            error!("resume_pending: failed to list host uploads: {}", err);
+            return;
+        }
+    };
+    if entries.is_empty() {
+        return;
+    }
+
+    info!("resume_pending: {} unfinished host uploads", entries.len());
+    for entry in entries {
+        match (entry.stage, entry.url.clone()) {
+            (Stage::GalleryPending, Some(url)) => {
+                match complete_gallery(app, &entry, &url) {
+                    Ok(()) => info!("resume_pending: gallery completed, url={}", url),
+                    Err(err) => warn!(
+                        "resume_pending: record gallery failed: url={}, error={}",
+                        url, err
+                    ),
+                }
+                remove_prepared(&entry.job);
+                if let Err(err) = store.remove_row(entry.id) {
+                    warn!("failed to update pending upload {}: {}", entry.id, err);
+                }
+            }
+            _ if Path::new(&entry.job.prepared).is_file() => {
+                info!(
+                    "resume_pending: resume upload, file={}, host={}",
+                    entry.job.source, entry.host
+                );
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    let options = PipelineOptions {
+                        record_gallery: entry.job.record_gallery,
+                        retries: 1,
+                        ..Default::default()
+                    };
+                    // 待上传文件已按当时的选项处理过，不再去除元数据
+                    let outcome = crate::host_upload::upload_prepared(
+                        &app,
+                        &entry.host,
+                        entry.job.source.clone(),
+                        entry.job.prepared.clone(),
+                        entry.job.content_hash.clone(),
+                        false,
+                        &options,
+                    )
+                    .await;
+                    if let Err(err) = &outcome.result {
+                        crate::notifier::upload_failed(&app, &entry.job.source, &entry.host, err);
+                    }
+                    // 重新上传时已登记新的记录，旧记录在结束后删除，期间临时文件不会被清理
+                    if let Err(err) = store.remove_row(entry.id) {
+                        warn!("failed to update pending upload {}: {}", entry.id, err);
+                    }
+                });
+            }
+            _ => {
+                info!(
+                    "resume_pending: roll back, prepared file missing: file={}",
+                    entry.job.source
+                );
+                if let Err(err) = store.remove_row(entry.id) {
+                    warn!("failed to update pending upload {}: {}", entry.id, err);
+                }
+            }
+        }
+    }
+}
+
+/// 根据记录中的请求参数重新执行上传（经过上传队列）
+async fn execute(app: &AppHandle, kind: PendingKind, payload: Value) -> Result<Value, String> {
+    match kind {
+        PendingKind::UploadImage => {
+            let job: UploadImageJob = serde_json::from_value(payload)
+                .map_err(|e| format!("invalid upload_image payload: {e}"))?;
+            let destination = job.config.url.clone();
+            let response = crate::upload_queue::run_queued(
+                app,
+                None,
+                Some(&destination),
+                crate::upload::upload_image_impl(job.file_path, job.format, job.config),
+            )
+            .await?;
+            serde_json::to_value(response).map_err(|e| format!("serialize result: {e}"))
+        }
+        PendingKind::S3Upload => {
+            let job: S3UploadJob = serde_json::from_value(payload)
+                .map_err(|e| format!("invalid s3_upload payload: {e}"))?;
+            let destination = job.options.endpoint.clone();
+            let result = crate::upload_queue::run_queued(
+                app,
+                None,
+                destination.as_deref(),
+                crate::s3::s3_upload_impl(job.file_path, job.original_file_name, job.options),
+            )
+            .await?;
+            serde_json::to_value(result).map_err(|e| format!("serialize result: {e}"))
+        }
+        // 后端上传流程的记录只在启动时恢复，手动重试会与仍在进行的上传重复
+        PendingKind::HostUpload => {
+            Err("host uploads are resumed automatically and cannot be retried".to_string())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletedEvent {
+    id: i64,
+    success: bool,
+    result: Option<Value>,
+    error: Option<String>,
+}
+
+/// 启动时恢复上次未完成的上传。结果保存在数据库中，前端处理（如写入图库）后再丢弃记录。
+pub(crate) fn resume_pending(app: &AppHandle) {
+    let store = match store_from_app(app) {
+        Ok(store) => store,
+        Err(err) => {
+            error!("resume_pending: failed to open store: {}", err);
+            return;
+        }
+    };
+    match store.prune() {
+        Ok(0) => {}
+        Ok(count) => info!("resume_pending: pruned {} expired records", count),
+        Err(err) => warn!("resume_pending: failed to prune records: {}", err),
+    }
+    recover_host_uploads(app, store);
+    let ids = match store.pending_ids() {
+        Ok(ids) => ids,
+        Err(err) => {
+            error!("resume_pending: failed to list pending uploads: {}", err);
+            return;
+        }
+    };
+    if ids.is_empty() {
+        return;
+    }
+
+    info!("resume_pending: resuming {} uploads", ids.len());
+    for id in ids {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let outcome = match store.begin_attempt(id) {
+                Ok((kind, payload)) => execute(&app, kind, payload).await,
+                Err(err) => Err(err),
+            };
+            let event = match outcome {
+                Ok(result) => {
+                    if let Err(err) =
+                        store.set_status(id, PendingStatus::Completed, None, Some(&result))
+                    {
+                        warn!("failed to update pending upload {}: {}", id, err);
+                    }
+                    // 已完成的记录不会再执行，密钥不再需要
+                    forget_secrets(id);
+                    CompletedEvent {
+                        id,
+                        success: true,
+                        result: Some(result),
+                        error: None,
+                    }
+                }
+                Err(err) => {
+                    warn!("resumed upload {} failed: {}", id, err);
+                    if let Err(update_err) =
+                        store.set_status(id, PendingStatus::Failed, Some(&err), None)
+                    {
+                        warn!("failed to update pending upload {}: {}", id, update_err);
+                    }
+                    CompletedEvent {
+                        id,
+                        success: false,
+                        result: None,
+                        error: Some(err),
+                    }
+                }
+            };
+            if let Err(err) = app.emit(COMPLETED_EVENT, event) {
+                warn!("failed to emit pending upload event: {}", err);
+            }
+        });
+    }
+}
+
+#[tauri::command]
+pub fn pending_uploads_list(app: AppHandle) -> Result<Vec<PendingUpload>, String> {
+    let store = store_from_app(&app)?;
+    store.list()
+}
+
+/// 手动重试一条记录；成功后删除记录并返回上传结果
+#[tauri::command]
+pub async fn pending_uploads_retry(app: AppHandle, id: i64) -> Result<Value, String> {
+    let store = store_from_app(&app)?;
+    let (kind, payload) = store.begin_attempt(id)?;
+    match execute(&app, kind, payload).await {
+        Ok(result) => {
+            store.remove(id)?;
+            info!("pending_uploads_retry success: id={}", id);
+            Ok(result)
+        }
+        Err(err) => {
+            store.set_status(id, PendingStatus::Failed, Some(&err), None)?;
+            error!("pending_uploads_retry failed: id={}, error={}", id, err);
+            Err(err)
+        }
+    }
+}
+
+#[tauri::command]
+pub fn pending_uploads_discard(app: AppHandle, id: i64) -> Result<(), String> {
+    let store = store_from_app(&app)?;
+    if let Some(entry) = store
+        .host_entries()?
+        .into_iter()
+        .find(|entry| entry.id == id)
+    {
+        remove_prepared(&entry.job);
+    }
+    store.remove(id)?;
+    info!("pending_uploads_discard: id={}", id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_split_and_merge_secrets() {
+        let original = json!({
+            "filePath": "/tmp/a.png",
+            "originalFileName": "a.png",
+            "options": {
+                "bucket": "images",
+                "accessKeyId": "AKID",
+                "secretAccessKey": "s3cr3t",
+                "sessionToken": null,
+            },
+        });
+        let mut payload = original.clone();
+        let secrets = split_secrets(PendingKind::S3Upload, &mut payload);
+        assert!(!payload.to_string().contains("s3cr3t"));
+        assert_eq!(payload["options"]["accessKeyId"], "AKID");
+        assert_eq!(secrets.len(), 1);
+
+        merge_secrets(&mut payload, secrets);
+        assert_eq!(payload["options"]["secretAccessKey"], "s3cr3t");
+    }
+
+    #[test]
+    fn test_host_upload_stages() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PendingUploadStore::new(dir.path()).unwrap();
+        let job = HostUploadJob {
+            source: "/photos/a.png".to_string(),
+            prepared: "/tmp/yana_a.webp".to_string(),
+            file_name: "a.webp".to_string(),
+            content_hash: Some("abc".to_string()),
+            record_gallery: true,
+        };
+        let id = store.insert_host("s3", &job).unwrap();
+        assert_eq!(store.host_entries().unwrap()[0].stage, Stage::Compressed);
+        // 后端上传流程的记录不参与启动时的重新执行
+        assert!(store.pending_ids().unwrap().is_empty());
+        assert!(
+            store
+                .file_paths()
+                .unwrap()
+                .contains(&"/tmp/yana_a.webp".to_string())
+        );
+
+        store.set_stage(id, Stage::Uploading, None, None).unwrap();
+        assert_eq!(store.host_entries().unwrap()[0].bytes_sent, None);
+        store
+            .set_stage(
+                id,
+                Stage::GalleryPending,
+                Some(1024),
+                Some(&HostUploadResult {
+                    url: "https://img.example.com/a.webp".to_string(),
+                    delete_id: String::new(),
+                    metadata: None,
+                }),
+            )
+            .unwrap();
+        let entry = store.host_entries().unwrap().remove(0);
+        assert_eq!(entry.stage, Stage::GalleryPending);
+        assert_eq!(entry.host, "s3");
+        assert_eq!(entry.job, job);
+        assert_eq!(entry.bytes_sent, Some(1024));
+        assert_eq!(entry.url.as_deref(), Some("https://img.example.com/a.webp"));
+        assert_eq!(entry.delete_id, None);
+
+        store.remove_row(id).unwrap();
+        assert!(store.host_entries().unwrap().is_empty());
+    }
+}
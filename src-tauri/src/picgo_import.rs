@@ -0,0 +1,396 @@
+/*
+从 PicGo 导入配置与上传记录：
+1) `import_picgo_config(path)` 接受 PicGo 配置目录或其中的 `config.json` / `data.json`，
+   同目录下的另一个文件会一并读取；
+2) 图床映射：smms → 内置 SM.MS，github → 内置 GitHub 仓库，aliyun / tcyun / aws-s3 → 内置 S3
+   （yana 只有一份 S3 配置，多个候选时优先 PicGo 当前使用的图床）；其他图床跳过并在结果中列出；
+3) 已有配置的图床默认不覆盖，`overwrite = true` 时覆盖；敏感字段按各插件约定写入钥匙串；
+4) `data.json` 中的上传记录写入图库，host 按上述映射换算，已存在相同链接的记录会跳过；
+   PicGo 没有可供 yana 使用的删除标记，导入的记录只能从图库中移除。
+*/
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{TimeZone, Utc};
+use log::{info, warn};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use tauri::AppHandle;
+
+use crate::gallery::{NewGalleryItem, store_from_app};
+use crate::image_hosts::{
+    SECRET_PLACEHOLDER, ensure_config_path, plugin_secret_id, read_settings, write_settings,
+};
+use crate::secrets::write_secret;
+
+const CONFIG_FILE: &str = "config.json";
+const DATA_FILE: &str = "data.json";
+/// 映射到 S3 的 PicGo 图床，按优先级排列
+const S3_UPLOADERS: &[&str] = &["aws-s3", "aliyun", "tcyun"];
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PicGoImportSummary {
+    /// 已导入配置的 yana 图床 id
+    pub hosts: Vec<String>,
+    /// 未导入的 PicGo 图床及原因
+    pub skipped: Vec<String>,
+    pub history: usize,
+    pub history_skipped: usize,
+}
+
+/// 一条映射后的图床配置：明文字段 + 需要写入钥匙串的字段
+struct MappedHost {
+    plugin_id: &'static str,
+    values: Map<String, Value>,
+    secrets: Vec<(&'static str, String)>,
+}
+
+fn text(config: &Value, key: &str) -> Option<String> {
+    match config.get(key)? {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn require(config: &Value, uploader: &str, key: &str) -> Result<String, String> {
+    text(config, key).ok_or_else(|| format!("{uploader}: 缺少 {key}"))
+}
+
+/// PicGo 的路径配置形如 `img/`，yana 使用不带首尾斜杠的前缀
+fn prefix(config: &Value, key: &str) -> Value {
+    text(config, key)
+        .map(|p| p.trim_matches('/').to_string())
+        .filter(|p| !p.is_empty())
+        .map(Value::String)
+        .unwrap_or(Value::Null)
+}
+
+fn map_uploader(uploader: &str, config: &Value) -> Result<MappedHost, String> {
+    let mut values = Map::new();
+    let mut secrets = Vec::new();
+    let plugin_id = match uploader {
+        "smms" => {
+            values.insert("token".into(), json!(require(config, uploader, "token")?));
+            "smms"
+        }
+        "github" => {
+            let repo = require(config, uploader, "repo")?;
+            let (owner, name) = repo
+                .split_once('/')
+                .ok_or_else(|| format!("github: 仓库格式应为 owner/repo: {repo}"))?;
+            values.insert("owner".into(), json!(owner));
+            values.insert("repo".into(), json!(name));
+            values.insert(
+                "branch".into(),
+                json!(text(config, "branch").unwrap_or_else(|| "main".into())),
+            );
+            values.insert("remoteDir".into(), prefix(config, "path"));
+            // PicGo 直接使用原文件名
+            values.insert("pathTemplate".into(), json!("{filename}"));
+            values.insert("cdn".into(), json!("raw"));
+            values.insert("publicUrl".into(), json!(text(config, "customUrl")));
+            secrets.push(("token", require(config, uploader, "token")?));
+            "github"
+        }
+        "aliyun" => {
+            let area = require(config, uploader, "area")?;
+            values.insert("bucket".into(), json!(require(config, uploader, "bucket")?));
+            values.insert(
+                "region".into(),
+                json!(area.trim_start_matches("oss-").to_string()),
+            );
+            values.insert(
+                "accessKeyId".into(),
+                json!(require(config, uploader, "accessKeyId")?),
+            );
+            values.insert(
+                "secretAccessKey".into(),
+                json!(require(config, uploader, "accessKeySecret")?),
+            );
+            values.insert(
+                "endpoint".into(),
+                json!(format!("https://{}.aliyuncs.com", area)),
+            );
+            values.insert("forcePathStyle".into(), json!(false));
+            values.insert("objectPrefix".into(), prefix(config, "path"));
+            values.insert("publicBaseUrl".into(), json!(text(config, "customUrl")));
+            "s3"
+        }
+        "tcyun" => {
+            if text(config, "version").as_deref() == Some("v4") {
+                return Err("tcyun: 仅支持 COS v5 配置".to_string());
+            }
+            let area = require(config, uploader, "area")?;
+            values.insert("bucket".into(), json!(require(config, uploader, "bucket")?));
+            values.insert("region".into(), json!(area));
+            values.insert(
+                "accessKeyId".into(),
+                json!(require(config, uploader, "secretId")?),
+            );
+            values.insert(
+                "secretAccessKey".into(),
+                json!(require(config, uploader, "secretKey")?),
+            );
+            values.insert(
+                "endpoint".into(),
+                json!(format!("https://cos.{}.myqcloud.com", area)),
+            );
+            values.insert("forcePathStyle".into(), json!(false));
+            values.insert("objectPrefix".into(), prefix(config, "path"));
+            values.insert("publicBaseUrl".into(), json!(text(config, "customUrl")));
+            "s3"
+        }
+        "aws-s3" => {
+            values.insert(
+                "bucket".into(),
+                json!(require(config, uploader, "bucketName")?),
+            );
+            values.insert(
+                "region".into(),
+                json!(text(config, "region").unwrap_or_else(|| "us-east-1".into())),
+            );
+            values.insert(
+                "accessKeyId".into(),
+                json!(require(config, uploader, "accessKeyID")?),
+            );
+            values.insert(
+                "secretAccessKey".into(),
+                json!(require(config, uploader, "secretAccessKey")?),
+            );
+            values.insert("endpoint".into(), json!(text(config, "endpoint")));
+            values.insert(
+                "forcePathStyle".into(),
+                json!(config.get("pathStyleAccess").and_then(Value::as_bool) == Some(true)),
+            );
+            values.insert("objectPrefix".into(), prefix(config, "uploadPath"));
+            if let Some(acl) = text(config, "acl") {
+                values.insert("acl".into(), json!(acl));
+            }
+            values.insert("publicBaseUrl".into(), json!(text(config, "urlPrefix")));
+            "s3"
+        }
+        other => return Err(format!("{other}: yana 暂不支持该图床")),
+    };
+    Ok(MappedHost {
+        plugin_id,
+        values,
+        secrets,
+    })
+}
+
+/// PicGo 图床类型对应的 yana host id，用于图库记录
+fn history_host(uploader: &str) -> String {
+    match uploader {
+        "smms" | "github" => uploader.to_string(),
+        u if S3_UPLOADERS.contains(&u) => "s3".to_string(),
+        other => format!("picgo:{other}"),
+    }
+}
+
+/// 收集需要读取的文件：选择的文件本身 + 同目录下的 config.json / data.json
+fn candidate_files(path: &Path) -> Vec<PathBuf> {
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(path)
+    };
+    let mut files = Vec::new();
+    if path.is_file() {
+        files.push(path.to_path_buf());
+    }
+    for name in [CONFIG_FILE, DATA_FILE] {
+        let candidate = dir.join(name);
+        if candidate.is_file() && !files.contains(&candidate) {
+            files.push(candidate);
+        }
+    }
+    files
+}
+
+fn import_hosts(
+    app: &AppHandle,
+    pic_bed: &Map<String, Value>,
+    overwrite: bool,
+    summary: &mut PicGoImportSummary,
+) -> Result<(), String> {
+    let current = pic_bed
+        .get("current")
+        .or_else(|| pic_bed.get("uploader"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    // 当前图床排在最前，使其在映射到同一 yana 图床时优先
+    let mut uploaders: Vec<&String> = pic_bed
+        .iter()
+        .filter(|(key, value)| value.is_object() && *key != "transformer")
+        .map(|(key, _)| key)
+        .collect();
+    uploaders.sort_by_key(|key| {
+        (
+            key.as_str() != current,
+            S3_UPLOADERS
+                .iter()
+                .position(|u| u == key)
+                .unwrap_or(usize::MAX),
+        )
+    });
+
+    let settings_path = ensure_config_path(app)?;
+    let mut file = read_settings(&settings_path)?;
+    let mut mapped: BTreeMap<&'static str, String> = BTreeMap::new();
+    for uploader in uploaders {
+        let host = match map_uploader(uploader, &pic_bed[uploader.as_str()]) {
+            Ok(host) => host,
+            Err(reason) => {
+                summary.skipped.push(reason);
+                continue;
+            }
+        };
+        if let Some(source) = mapped.get(host.plugin_id) {
+            summary
+                .skipped
+                .push(format!("{uploader}: {} 已由 {source} 导入", host.plugin_id));
+            continue;
+        }
+        if !overwrite && file.plugins.contains_key(host.plugin_id) {
+            summary
+                .skipped
+                .push(format!("{uploader}: {} 已有配置", host.plugin_id));
+            continue;
+        }
+
+        let mut values = host.values;
+        for (key, secret) in &host.secrets {
+            write_secret(&plugin_secret_id(host.plugin_id, key), secret)?;
+            values.insert((*key).to_string(), json!(SECRET_PLACEHOLDER));
+        }
+        file.plugins
+            .insert(host.plugin_id.to_string(), Value::Object(values));
+        mapped.insert(host.plugin_id, uploader.clone());
+        summary.hosts.push(host.plugin_id.to_string());
+    }
+    write_settings(&settings_path, &file)
+}
+
+fn import_history(
+    app: &AppHandle,
+    uploaded: &[Value],
+    summary: &mut PicGoImportSummary,
+) -> Result<(), String> {
+    let store = store_from_app(app)?;
+    for item in uploaded {
+        let Some(url) = text(item, "imgUrl") else {
+            summary.history_skipped += 1;
+            continue;
+        };
+        if store.contains_url(&url).map_err(|e| e.to_string())? {
+            summary.history_skipped += 1;
+            continue;
+        }
+        let file_name = text(item, "fileName")
+            .or_else(|| url.rsplit('/').next().map(str::to_string))
+            .unwrap_or_default();
+        let inserted_at = item
+            .get("createdAt")
+            .and_then(Value::as_i64)
+            .and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+            .map(|dt| dt.to_rfc3339());
+        let host = history_host(&text(item, "type").unwrap_or_else(|| "unknown".into()));
+        store
+            .insert(NewGalleryItem {
+                file_name,
+                url,
+                host,
+                delete_marker: None,
+                inserted_at,
+                filesize: None,
+                content_hash: None,
+            })
+            .map_err(|e| e.to_string())?;
+        summary.history += 1;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn import_picgo_config(
+    app: AppHandle,
+    path: String,
+    overwrite: Option<bool>,
+) -> Result<PicGoImportSummary, String> {
+    let files = candidate_files(Path::new(&path));
+    if files.is_empty() {
+        return Err(format!("未找到 PicGo 配置文件: {}", path));
+    }
+
+    let mut summary = PicGoImportSummary::default();
+    let mut recognized = false;
+    for file in files {
+        let text =
+            fs::read_to_string(&file).map_err(|e| format!("读取 {} 失败: {e}", file.display()))?;
+        let parsed: Value = match serde_json::from_str(&text) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                warn!("import_picgo_config skip {}: {}", file.display(), err);
+                continue;
+            }
+        };
+        if let Some(pic_bed) = parsed.get("picBed").and_then(Value::as_object) {
+            recognized = true;
+            import_hosts(&app, pic_bed, overwrite.unwrap_or(false), &mut summary)?;
+        }
+        if let Some(uploaded) = parsed.get("uploaded").and_then(Value::as_array) {
+            recognized = true;
+            import_history(&app, uploaded, &mut summary)?;
+        }
+    }
+    if !recognized {
+        return Err("所选文件不是 PicGo 的 config.json 或 data.json".to_string());
+    }
+
+    info!(
+        "import_picgo_config success: path={}, hosts={:?}, history={}, skipped={}",
+        path,
+        summary.hosts,
+        summary.history,
+        summary.skipped.len()
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_uploader() {
+        let aliyun = json!({
+            "accessKeyId": "id",
+            "accessKeySecret": "secret",
+            "bucket": "pics",
+            "area": "oss-cn-hangzhou",
+            "path": "img/",
+        });
+        let host = map_uploader("aliyun", &aliyun).unwrap();
+        assert_eq!(host.plugin_id, "s3");
+        assert_eq!(host.values["region"], "cn-hangzhou");
+        assert_eq!(
+            host.values["endpoint"],
+            "https://oss-cn-hangzhou.aliyuncs.com"
+        );
+        assert_eq!(host.values["objectPrefix"], "img");
+
+        let github = json!({ "repo": "alice/images", "token": "t" });
+        let host = map_uploader("github", &github).unwrap();
+        assert_eq!(host.values["owner"], "alice");
+        assert_eq!(host.secrets, vec![("token", "t".to_string())]);
+
+        assert!(map_uploader("qiniu", &json!({})).is_err());
+        assert_eq!(history_host("tcyun"), "s3");
+    }
+}
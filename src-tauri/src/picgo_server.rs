@@ -0,0 +1,620 @@
+/*
+兼容 PicGo Server 的本地 HTTP 服务（默认关闭）：
+1) 仅监听 127.0.0.1，端口取自设置 `picgoServerPort`（默认 36677，与 PicGo 一致）；
+2) `POST /upload`：
+   - JSON `{"list": ["/path/a.png", ...]}` 上传本地图片；
+   - `multipart/form-data` 上传表单中的文件；
+   - 无请求体或空列表时上传剪贴板中的图片；
+   成功返回 `{"success": true, "result": [url...]}`，失败返回 `{"success": false, "message": ...}`；
+3) `POST /heartbeat` 返回 `{"success": true, "result": "alive"}`；
+   `GET /feed.xml`、`GET /feed.json` 返回最近上传的订阅源（见 feed 模块），未设置令牌时不提供；
+4) 设置了 `picgoServerToken` 时需在查询参数 `key` 或 `Authorization: Bearer` 中携带，按常量时间比较；
+   令牌存放在系统钥匙串（settings_secrets），读取失败时拒绝全部请求而不是放开校验；
+   带 `Origin` 头的请求（来自浏览器网页）一律拒绝，`Host` 只接受 `127.0.0.1:<端口>`
+   与 `localhost:<端口>`（防止 DNS 重绑定），本地路径只接受存在的图片文件；
+5) 上传使用设置 `picgoServerHost` 指定的图床，按设置压缩并写入图库。
+*/
+
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, SocketAddr},
+    path::Path,
+    sync::Mutex,
+};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tauri::AppHandle;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::feed::{FeedFormat, FeedOptions};
+use crate::host_upload::{PipelineOptions, compress_and_upload};
+use crate::internal_hosts::sanitize_file_name;
+use crate::settings::SettingsPayload;
+
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024 * 1024;
+
+struct RunningServer {
+    port: u16,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+static SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+/// 访问令牌（空表示不校验）；None 表示钥匙串读取失败，拒绝全部请求
+static TOKEN: Mutex<Option<String>> = Mutex::new(Some(String::new()));
+/// 最近一次启动失败的原因（如端口被占用）
+static LAST_ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PicGoServerStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UploadList {
+    #[serde(default)]
+    list: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+/// 解析请求行与请求头，头部名称统一为小写
+fn parse_head(head: &str) -> Option<(String, String, HashMap<String, String>)> {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_ascii_uppercase();
+    let target = request_line.next()?.to_string();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    Some((method, target, headers))
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request, String> {
+    let mut buffer = Vec::with_capacity(4096);
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buffer.len() > MAX_HEAD_BYTES {
+            return Err("request head too large".to_string());
+        }
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("read request: {e}"))?;
+        if read == 0 {
+            return Err("connection closed".to_string());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..head_end]).into_owned();
+    let (method, target, headers) =
+        parse_head(&head).ok_or_else(|| "malformed request".to_string())?;
+    if headers
+        .get("transfer-encoding")
+        .is_some_and(|v| v.eq_ignore_ascii_case("chunked"))
+    {
+        return Err("chunked request body is not supported".to_string());
+    }
+    let length = headers
+        .get("content-length")
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return Err("request body too large".to_string());
+    }
+    if headers
+        .get("expect")
+        .is_some_and(|v| v.eq_ignore_ascii_case("100-continue"))
+    {
+        let _ = stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n").await;
+    }
+
+    let mut body = buffer[head_end + 4..].to_vec();
+    while body.len() < length {
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("read request body: {e}"))?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(length);
+
+    let url = url::Url::parse(&format!("http://127.0.0.1{}", target))
+        .map_err(|e| format!("invalid request target: {e}"))?;
+    Ok(Request {
+        method,
+        path: url.path().to_string(),
+        query: url.query_pairs().into_owned().collect(),
+        headers,
+        body,
+    })
+}
+
+async fn write_body(stream: &mut TcpStream, status: &str, content_type: &str, text: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        text.len(),
+        text
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn write_json(stream: &mut TcpStream, status: &str, body: &Value) {
+    write_body(
+        stream,
+        status,
+        "application/json; charset=utf-8",
+        &body.to_string(),
+    )
+    .await;
+}
+
+fn failure(message: impl Into<String>) -> Value {
+    json!({ "success": false, "message": message.into() })
+}
+
+/// 逐字节异或后汇总，耗时与第一个不同字节的位置无关
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn authorized(request: &Request, token: &str) -> bool {
+    if token.is_empty() {
+        return true;
+    }
+    let bearer = request
+        .header("authorization")
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+    let matches = |value: Option<&str>| {
+        value.is_some_and(|value| constant_time_eq(value.as_bytes(), token.as_bytes()))
+    };
+    // 两项都比较，不因查询参数已匹配而提前返回
+    let by_query = matches(request.query.get("key").map(String::as_str));
+    let by_header = matches(bearer);
+    by_query | by_header
+}
+
+/// Host 必须是本机地址加监听端口，拒绝经 DNS 重绑定指向 127.0.0.1 的域名
+fn host_allowed(request: &Request, port: u16) -> bool {
+    let Some(host) = request.header("host") else {
+        return false;
+    };
+    let host = host.trim().to_ascii_lowercase();
+    host == format!("127.0.0.1:{port}") || host == format!("localhost:{port}")
+}
+
+/// 从 `multipart/form-data; boundary=...` 中取出 boundary
+fn multipart_boundary(content_type: &str) -> Option<String> {
+    let (mime, params) = content_type.split_once(';')?;
+    if !mime.trim().eq_ignore_ascii_case("multipart/form-data") {
+        return None;
+    }
+    params
+        .split(';')
+        .filter_map(|param| param.trim().split_once('='))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case("boundary"))
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|pos| pos + from)
+}
+
+/// 解析 multipart 表单中的文件部分，返回（文件名, 内容）
+fn parse_multipart(body: &[u8], boundary: &str) -> Vec<(String, Vec<u8>)> {
+    let delimiter = format!("--{boundary}");
+    let delimiter = delimiter.as_bytes();
+    let mut files = Vec::new();
+    let Some(mut cursor) = find(body, delimiter, 0) else {
+        return files;
+    };
+    loop {
+        let part_start = cursor + delimiter.len();
+        // 结束分隔符 `--boundary--`
+        if body.get(part_start..part_start + 2) == Some(&b"--"[..]) {
+            break;
+        }
+        let Some(next) = find(body, delimiter, part_start) else {
+            break;
+        };
+        let part = &body[part_start..next];
+        let part = part.strip_prefix(b"\r\n").unwrap_or(part);
+        let part = part.strip_suffix(b"\r\n").unwrap_or(part);
+        if let Some(split) = find(part, b"\r\n\r\n", 0) {
+            let headers = String::from_utf8_lossy(&part[..split]);
+            let file_name = headers
+                .split("\r\n")
+                .filter(|line| {
+                    line.to_ascii_lowercase()
+                        .starts_with("content-disposition:")
+                })
+                .flat_map(|line| line.split(';'))
+                .filter_map(|param| param.trim().split_once('='))
+                .find(|(key, _)| key.eq_ignore_ascii_case("filename"))
+                .map(|(_, value)| value.trim_matches('"').to_string());
+            if let Some(file_name) = file_name.filter(|name| !name.is_empty()) {
+                files.push((file_name, part[split + 4..].to_vec()));
+            }
+        }
+        cursor = next;
+    }
+    files
+}
+
+/// 收集要上传的本地路径；multipart 文件写入临时目录（随返回的 TempDir 一起删除）
+async fn collect_paths(
+    app: &AppHandle,
+    request: &Request,
+) -> Result<(Vec<String>, Option<tempfile::TempDir>), String> {
+    if let Some(boundary) = request.header("content-type").and_then(multipart_boundary) {
+        let files = parse_multipart(&request.body, &boundary);
+        if files.is_empty() {
+            return Err("表单中没有文件".to_string());
+        }
+        let base = crate::portable::temp_dir();
+        std::fs::create_dir_all(&base)
+            .map_err(|e| format!("create app temp dir {}: {}", base.display(), e))?;
+        let dir = tempfile::Builder::new()
+            .prefix("yana_picgo_")
+            .tempdir_in(&base)
+            .map_err(|e| format!("tempdir_in: {}", e))?;
+        let mut paths = Vec::with_capacity(files.len());
+        for (index, (file_name, bytes)) in files.into_iter().enumerate() {
+            // 子目录避免同名文件互相覆盖
+            let path = dir
+                .path()
+                .join(index.to_string())
+                .join(sanitize_file_name(&file_name));
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| format!("create dir: {e}"))?;
+            }
+            std::fs::write(&path, bytes).map_err(|e| format!("write upload file: {e}"))?;
+            paths.push(path.to_string_lossy().into_owned());
+        }
+        return Ok((paths, Some(dir)));
+    }
+
+    let list = if request.body.iter().all(u8::is_ascii_whitespace) {
+        Vec::new()
+    } else {
+        serde_json::from_slice::<UploadList>(&request.body)
+            .map_err(|e| format!("无效的请求体: {e}"))?
+            .list
+    };
+    if list.is_empty() {
+        let app = app.clone();
+        let current = tauri::async_runtime::spawn_blocking(move || {
+            crate::clipboard_watcher::take_current(&app)
+        })
+        .await
+        .map_err(|e| format!("spawn_blocking error: {}", e))??;
+        return current
+            .map(|path| (vec![path], None))
+            .ok_or_else(|| "剪贴板中没有图片".to_string());
+    }
+    if let Some(invalid) = list
+        .iter()
+        .find(|path| !crate::upload::is_image_file(Path::new(path)))
+    {
+        return Err(format!("不是可上传的图片文件: {invalid}"));
+    }
+    Ok((list, None))
+}
+
+async fn handle_upload(app: &AppHandle, request: &Request) -> Value {
+    let host = crate::settings::current_settings(app).picgo_server_host;
+    if host.is_empty() {
+        return failure("未设置 PicGo 服务使用的图床");
+    }
+    let (paths, _temp_dir) = match collect_paths(app, request).await {
+        Ok(collected) => collected,
+        Err(err) => return failure(err),
+    };
+    info!("picgo server upload: host={}, count={}", host, paths.len());
+    let options = PipelineOptions {
+        record_gallery: true,
+        ..Default::default()
+    };
+    let outcomes = compress_and_upload(app, &host, paths, &options).await;
+    let mut urls = Vec::with_capacity(outcomes.len());
+    let mut errors = Vec::new();
+    for outcome in outcomes {
+        match outcome.result {
+            Ok(result) => urls.push(result.url),
+            Err(err) => errors.push(err),
+        }
+    }
+    if errors.is_empty() {
+        json!({ "success": true, "result": urls })
+    } else {
+        warn!("picgo server upload failed: {}", errors.join("; "));
+        json!({ "success": false, "result": urls, "message": errors.join("; ") })
+    }
+}
+
+async fn handle_connection(app: AppHandle, mut stream: TcpStream, port: u16) {
+    let request = match read_request(&mut stream).await {
+        Ok(request) => request,
+        Err(err) => {
+            write_json(&mut stream, "400 Bad Request", &failure(err)).await;
+            return;
+        }
+    };
+    if request.header("origin").is_some() {
+        write_json(
+            &mut stream,
+            "403 Forbidden",
+            &failure("不接受来自浏览器的请求"),
+        )
+        .await;
+        return;
+    }
+    if !host_allowed(&request, port) {
+        write_json(&mut stream, "403 Forbidden", &failure("invalid host")).await;
+        return;
+    }
+    let Some(token) = TOKEN.lock().ok().and_then(|t| t.clone()) else {
+        write_json(
+            &mut stream,
+            "503 Service Unavailable",
+            &failure("无法从钥匙串读取访问令牌"),
+        )
+        .await;
+        return;
+    };
+    if !authorized(&request, &token) {
+        write_json(&mut stream, "401 Unauthorized", &failure("unauthorized")).await;
+        return;
+    }
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/upload") => {
+            let body = handle_upload(&app, &request).await;
+            write_json(&mut stream, "200 OK", &body).await;
+        }
+        ("POST", "/heartbeat") => {
+            write_json(
+                &mut stream,
+                "200 OK",
+                &json!({ "success": true, "result": "alive" }),
+            )
+            .await;
+        }
+        ("GET", "/feed.xml" | "/feed.json") if token.is_empty() => {
+            write_json(
+                &mut stream,
+                "403 Forbidden",
+                &failure("订阅源需要先设置访问令牌"),
+            )
+            .await;
+        }
+        ("GET", "/feed.xml" | "/feed.json") => {
+            let format = if request.path == "/feed.json" {
+                FeedFormat::Json
+            } else {
+                FeedFormat::Rss
+            };
+            match crate::feed::build(&app, &FeedOptions::from_query(format, &request.query)) {
+                Ok((content, content_type)) => {
+                    write_body(&mut stream, "200 OK", content_type, &content).await;
+                }
+                Err(err) => {
+                    write_json(&mut stream, "500 Internal Server Error", &failure(err)).await;
+                }
+            }
+        }
+        _ => write_json(&mut stream, "404 Not Found", &failure("not found")).await,
+    }
+}
+
+async fn serve(app: AppHandle, listener: std::net::TcpListener, port: u16) {
+    let listener = match TcpListener::from_std(listener) {
+        Ok(listener) => listener,
+        Err(err) => {
+            warn!("picgo server listener failed: {}", err);
+            return;
+        }
+    };
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tauri::async_runtime::spawn(handle_connection(app.clone(), stream, port));
+            }
+            Err(err) => warn!("picgo server accept failed: {}", err),
+        }
+    }
+}
+
+fn set_last_error(error: Option<String>) {
+    if let Ok(mut last) = LAST_ERROR.lock() {
+        *last = error;
+    }
+}
+
+/// 按设置启动、停止或换端口重启服务；令牌变化即时生效无需重启
+pub(crate) fn apply(app: &AppHandle, settings: &SettingsPayload) {
+    let resolved = crate::settings_secrets::resolve_settings_secret(
+        crate::settings_secrets::PICGO_SERVER_TOKEN_KEY,
+        &settings.picgo_server_token,
+    )
+    .inspect_err(|err| warn!("picgo server token unavailable: {}", err))
+    .ok();
+    if let Ok(mut token) = TOKEN.lock() {
+        *token = resolved;
+    }
+    let Ok(mut server) = SERVER.lock() else {
+        return;
+    };
+    let wanted = settings
+        .picgo_server_enabled
+        .then_some(settings.picgo_server_port);
+    if server.as_ref().map(|running| running.port) == wanted {
+        return;
+    }
+    if let Some(running) = server.take() {
+        running.task.abort();
+        info!("picgo server stopped: port={}", running.port);
+    }
+    set_last_error(None);
+    let Some(port) = wanted else {
+        return;
+    };
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = match std::net::TcpListener::bind(addr)
+        .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+    {
+        Ok(listener) => listener,
+        Err(err) => {
+            warn!("picgo server bind {} failed: {}", addr, err);
+            set_last_error(Some(format!("监听 {} 失败: {}", addr, err)));
+            return;
+        }
+    };
+    let task = tauri::async_runtime::spawn(serve(app.clone(), listener, port));
+    info!("picgo server listening on {}", addr);
+    *server = Some(RunningServer { port, task });
+}
+
+#[tauri::command]
+pub fn picgo_server_status() -> PicGoServerStatus {
+    let port = SERVER
+        .lock()
+        .ok()
+        .and_then(|server| server.as_ref().map(|running| running.port));
+    PicGoServerStatus {
+        running: port.is_some(),
+        port,
+        error: LAST_ERROR.lock().ok().and_then(|last| last.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_head() {
+        let (method, target, headers) = parse_head(
+            "post /upload?key=abc HTTP/1.1\r\nHost: 127.0.0.1\r\nContent-Type: application/json",
+        )
+        .unwrap();
+        assert_eq!(method, "POST");
+        assert_eq!(target, "/upload?key=abc");
+        assert_eq!(headers.get("content-type").unwrap(), "application/json");
+        assert!(parse_head("").is_none());
+    }
+
+    #[test]
+    fn test_parse_multipart() {
+        assert_eq!(
+            multipart_boundary("multipart/form-data; boundary=\"XyZ\"").as_deref(),
+            Some("XyZ")
+        );
+        assert!(multipart_boundary("application/json").is_none());
+
+        let body = b"--XyZ\r\n\
+Content-Disposition: form-data; name=\"files\"; filename=\"a.png\"\r\n\
+Content-Type: image/png\r\n\r\n\
+\x89PNG\r\n\x1a\n\r\n\
+--XyZ\r\n\
+Content-Disposition: form-data; name=\"note\"\r\n\r\n\
+hello\r\n\
+--XyZ--\r\n";
+        let files = parse_multipart(body, "XyZ");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].0, "a.png");
+        assert_eq!(files[0].1, b"\x89PNG\r\n\x1a\n");
+    }
+
+    #[test]
+    fn test_authorized() {
+        let mut request = Request {
+            method: "POST".to_string(),
+            path: "/upload".to_string(),
+            query: HashMap::new(),
+            headers: HashMap::new(),
+            body: Vec::new(),
+        };
+        assert!(authorized(&request, ""));
+        assert!(!authorized(&request, "secret"));
+        request
+            .query
+            .insert("key".to_string(), "secret".to_string());
+        assert!(authorized(&request, "secret"));
+        request.query.clear();
+        request
+            .headers
+            .insert("authorization".to_string(), "Bearer secret".to_string());
+        assert!(authorized(&request, "secret"));
+        assert!(!authorized(&request, "secreT"));
+        assert!(!authorized(&request, "secret2"));
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"token1"));
+        assert!(constant_time_eq(b"", b""));
+    }
+
+    #[test]
+    fn test_host_allowed() {
+        let mut request = Request {
+            method: "GET".to_string(),
+            path: "/feed.xml".to_string(),
+            query: HashMap::new(),
+            headers: HashMap::new(),
+            body: Vec::new(),
+        };
+        assert!(!host_allowed(&request, 36677));
+        for (host, allowed) in [
+            ("127.0.0.1:36677", true),
+            ("LOCALHOST:36677", true),
+            ("127.0.0.1", false),
+            ("localhost:8080", false),
+            ("evil.example.com:36677", false),
+        ] {
+            request.headers.insert("host".to_string(), host.to_string());
+            assert_eq!(host_allowed(&request, 36677), allowed, "{host}");
+        }
+    }
+}
@@ -0,0 +1,96 @@
+/*
+插件权限声明：
+插件通过导出 `permissions` 声明所需能力，例如
+`export const permissions = { domains: ['sm.ms'], filesystem: 'none', clipboard: false }`。
+1) domains：允许访问的域名，`example.com` 同时匹配其子域名，`*` 表示不限制；
+2) filesystem：`none` 只能上传宿主交给插件的文件，`read` 允许读取任意本地文件（额外文件等）；
+3) clipboard：是否需要读取剪贴板。
+后端运行时的宿主函数按声明拦截越权请求；未声明权限的插件不能访问任何网络地址。
+*/
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilesystemAccess {
+    #[default]
+    None,
+    Read,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginPermissions {
+    #[serde(default)]
+    pub domains: Vec<String>,
+    #[serde(default)]
+    pub filesystem: FilesystemAccess,
+    #[serde(default)]
+    pub clipboard: bool,
+}
+
+fn domain_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim().trim_start_matches("*.").to_ascii_lowercase();
+    if pattern == "*" {
+        return true;
+    }
+    if pattern.is_empty() {
+        return false;
+    }
+    host == pattern || host.ends_with(&format!(".{}", pattern))
+}
+
+impl PluginPermissions {
+    /// 检查插件是否被允许访问该地址（仅允许 http / https）
+    pub fn check_url(&self, url: &str) -> Result<(), String> {
+        let parsed = url::Url::parse(url).map_err(|e| format!("invalid url {}: {e}", url))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(format!("plugin may not access scheme {}", parsed.scheme()));
+        }
+        let host = parsed
+            .host_str()
+            .ok_or_else(|| format!("url has no host: {}", url))?
+            .to_ascii_lowercase();
+        if self
+            .domains
+            .iter()
+            .any(|pattern| domain_matches(pattern, &host))
+        {
+            Ok(())
+        } else {
+            Err(format!("plugin is not permitted to access {}", host))
+        }
+    }
+
+    /// 检查插件是否被允许读取该文件；`allowed_file` 为宿主交给插件上传的文件
+    pub fn check_file(&self, path: &str, allowed_file: Option<&str>) -> Result<(), String> {
+        if self.filesystem == FilesystemAccess::Read || allowed_file == Some(path) {
+            Ok(())
+        } else {
+            Err(format!("plugin is not permitted to read {}", path))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_url() {
+        let permissions = PluginPermissions {
+            domains: vec!["sm.ms".to_string(), "*.example.com".to_string()],
+            ..Default::default()
+        };
+        assert!(permissions.check_url("https://sm.ms/api/v2/upload").is_ok());
+        assert!(permissions.check_url("https://cdn.sm.ms/a.png").is_ok());
+        assert!(permissions.check_url("https://a.example.com/").is_ok());
+        assert!(permissions.check_url("https://evil-sm.ms/").is_err());
+        assert!(permissions.check_url("file:///etc/passwd").is_err());
+        assert!(
+            PluginPermissions::default()
+                .check_url("https://sm.ms/")
+                .is_err()
+        );
+    }
+}
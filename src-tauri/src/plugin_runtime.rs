@@ -0,0 +1,584 @@
+/*
+后端内嵌 JS 运行时（boa）执行图床插件：
+1) 插件脚本按 ES Module 解析，调用其导出的 `upload` / `remove`，
+   传入与前端一致的运行时上下文（uploadViaBackend / httpRequest / logger）；
+2) 宿主能力由 Rust 实现：上传直接调用 `upload_image_impl`，HTTP 请求走 reqwest，日志写入应用日志；
+3) 运行时（Context）不是 Send，每次执行在独立的阻塞线程中创建，宿主函数在该线程内同步等待异步任务；
+4) 整个插件执行经过上传队列并支持 `cancel_upload` 取消：取消后宿主函数拒绝继续发起请求，
+   宿主函数中正在等待的上传 / HTTP 请求以派生的 upload_id 登记，随外层任务一并中止；
+   取消无法打断正在执行的脚本计算，死循环 / 无限递归由运行时限制（`RuntimeLimits`）抛错结束；
+5) 宿主函数按插件导出的 `permissions` 校验访问的域名与文件（见 plugin_permissions）；
+6) 读取权限同样会执行插件顶层代码，命令侧通过 `inspect_plugin_permissions` 在阻塞线程中执行。
+*/
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use boa_engine::{
+    Context, JsError, JsNativeError, JsResult, JsString, JsValue, Module, NativeFunction, Source,
+    builtins::promise::PromiseState, js_string, object::builtins::JsPromise,
+};
+use log::{debug, error, info, warn};
+use serde::Deserialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::image_hosts::{read_plugin_script, resolve_plugin_secrets};
+use crate::plugin_permissions::PluginPermissions;
+use crate::upload::{
+    CANCELLED_ERROR, UploadConfig, UploadFormat, cancel_upload, run_cancellable, upload_image_impl,
+};
+
+/// 单次 HTTP 请求的默认超时
+const HTTP_TIMEOUT_SECS: u64 = 60;
+/// 单个循环的最大迭代次数，超出时抛出 RuntimeLimit 错误
+const LOOP_ITERATION_LIMIT: u64 = 10_000_000;
+/// 最大函数递归深度
+const RECURSION_LIMIT: usize = 512;
+
+/// 构建传给插件的 context 对象，接口与前端 `createPluginRuntimeContext` 保持一致
+const PRELUDE: &str = r#"
+globalThis.__yanaContext = Object.freeze({
+  logger: Object.freeze({
+    debug: (message) => __yanaLog('debug', String(message)),
+    info: (message) => __yanaLog('info', String(message)),
+    warn: (message) => __yanaLog('warn', String(message)),
+    error: (message) => __yanaLog('error', String(message)),
+  }),
+  uploadViaBackend: async (options) => __yanaUpload(options),
+  httpRequest: async (input, init) => {
+    const request = init ?? {};
+    let body = request.body;
+    if (body != null && typeof body !== 'string') {
+      body = JSON.stringify(body);
+    }
+    const raw = __yanaHttp(String(input), {
+      method: request.method ?? 'GET',
+      headers: request.headers ?? {},
+      body: body ?? null,
+    });
+    const headers = new Map(raw.headers);
+    return {
+      ok: raw.status >= 200 && raw.status < 300,
+      status: raw.status,
+      statusText: raw.statusText,
+      url: raw.url,
+      headers: {
+        get: (name) => headers.get(String(name).toLowerCase()) ?? null,
+        has: (name) => headers.has(String(name).toLowerCase()),
+        entries: () => headers.entries(),
+      },
+      text: async () => raw.body,
+      json: async () => JSON.parse(raw.body),
+    };
+  },
+});
+"#;
+
+/// 当前线程正在执行的插件信息，供宿主函数读取
+struct HostState {
+    plugin_id: String,
+    cancelled: Arc<AtomicBool>,
+    /// 外层上传任务的 upload_id，宿主函数据此登记可取消的请求
+    upload_id: Option<String>,
+    permissions: PluginPermissions,
+    /// 宿主交给插件上传的文件，未声明文件系统权限时只能读取该文件
+    upload_file: Option<String>,
+}
+
+thread_local! {
+    static HOST: RefCell<Option<HostState>> = const { RefCell::new(None) };
+}
+
+fn host_plugin_id() -> String {
+    HOST.with(|host| {
+        host.borrow()
+            .as_ref()
+            .map(|state| state.plugin_id.clone())
+            .unwrap_or_default()
+    })
+}
+
+fn ensure_not_cancelled() -> JsResult<()> {
+    let cancelled = HOST.with(|host| {
+        host.borrow()
+            .as_ref()
+            .is_some_and(|state| state.cancelled.load(Ordering::SeqCst))
+    });
+    if cancelled {
+        return Err(js_error(CANCELLED_ERROR));
+    }
+    Ok(())
+}
+
+/// 宿主函数内发起的请求登记的 upload_id；宿主函数在单个线程中依次执行，同一时刻至多一个
+fn host_request_id(upload_id: &str) -> String {
+    format!("{}:plugin-host", upload_id)
+}
+
+/// 在插件线程中同步等待异步任务；插件执行带 upload_id 时经 `run_cancellable` 登记，
+/// 外层任务取消时请求被立即中止，而不是等到下一次调用宿主函数
+fn block_on_host<T>(task: impl Future<Output = Result<T, String>>) -> Result<T, String> {
+    let request_id = HOST.with(|host| {
+        host.borrow()
+            .as_ref()
+            .and_then(|state| state.upload_id.as_deref().map(host_request_id))
+    });
+    let result = tauri::async_runtime::block_on(run_cancellable(request_id, task));
+    // 取消发生在登记之前时请求可能已完成，仍以取消为准
+    ensure_not_cancelled().map_err(|_| CANCELLED_ERROR.to_string())?;
+    result
+}
+
+/// 按插件权限校验访问地址与读取的文件
+fn check_access(url: &str, files: &[&str]) -> JsResult<()> {
+    HOST.with(|host| {
+        let host = host.borrow();
+        let Some(state) = host.as_ref() else {
+            return Err(js_error("plugin host is not initialized"));
+        };
+        let result = state.permissions.check_url(url).and_then(|_| {
+            files.iter().try_for_each(|file| {
+                state
+                    .permissions
+                    .check_file(file, state.upload_file.as_deref())
+            })
+        });
+        result.map_err(|err| {
+            warn!("plugin {} denied: {}", state.plugin_id, err);
+            js_error(err)
+        })
+    })
+}
+
+fn js_error(message: impl Into<String>) -> JsError {
+    JsNativeError::error().with_message(message.into()).into()
+}
+
+fn arg(args: &[JsValue], index: usize) -> JsValue {
+    args.get(index).cloned().unwrap_or_default()
+}
+
+fn arg_json(args: &[JsValue], index: usize, ctx: &mut Context) -> JsResult<Value> {
+    let value = arg(args, index);
+    if value.is_undefined() || value.is_null() {
+        return Ok(Value::Null);
+    }
+    value.to_json(ctx)
+}
+
+fn arg_string(args: &[JsValue], index: usize, ctx: &mut Context) -> JsResult<String> {
+    Ok(arg(args, index).to_string(ctx)?.to_std_string_escaped())
+}
+
+/// 从 JS 异常值中提取可读信息（优先取 Error.message）
+fn js_value_message(value: &JsValue, ctx: &mut Context) -> String {
+    if let Some(object) = value.as_object()
+        && let Ok(message) = object.get(js_string!("message"), ctx)
+        && !message.is_undefined()
+        && let Ok(text) = message.to_string(ctx)
+    {
+        return text.to_std_string_escaped();
+    }
+    value.display().to_string()
+}
+
+fn js_error_message(err: JsError, ctx: &mut Context) -> String {
+    let value = err.to_opaque(ctx);
+    js_value_message(&value, ctx)
+}
+
+fn host_log(_this: &JsValue, args: &[JsValue], ctx: &mut Context) -> JsResult<JsValue> {
+    let level = arg_string(args, 0, ctx)?;
+    let message = arg_string(args, 1, ctx)?;
+    let plugin_id = host_plugin_id();
+    match level.as_str() {
+        "debug" => debug!("[plugin:{}] {}", plugin_id, message),
+        "warn" => warn!("[plugin:{}] {}", plugin_id, message),
+        "error" => error!("[plugin:{}] {}", plugin_id, message),
+        _ => info!("[plugin:{}] {}", plugin_id, message),
+    }
+    Ok(JsValue::undefined())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HostUploadOptions {
+    file_path: String,
+    format: UploadFormat,
+    config: UploadConfig,
+}
+
+fn host_upload(_this: &JsValue, args: &[JsValue], ctx: &mut Context) -> JsResult<JsValue> {
+    ensure_not_cancelled()?;
+    let options: HostUploadOptions = serde_json::from_value(arg_json(args, 0, ctx)?)
+        .map_err(|e| js_error(format!("invalid uploadViaBackend options: {e}")))?;
+    let mut files = vec![options.file_path.as_str()];
+    files.extend(
+        options
+            .config
+            .additional_files
+            .iter()
+            .map(|part| part.path.as_str()),
+    );
+    check_access(&options.config.url, &files)?;
+
+    let response = block_on_host(upload_image_impl(
+        options.file_path,
+        options.format,
+        options.config,
+    ))
+    .map_err(js_error)?;
+    let json = serde_json::to_value(&response)
+        .map_err(|e| js_error(format!("serialize upload response: {e}")))?;
+    JsValue::from_json(&json, ctx)
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HostHttpInit {
+    #[serde(default)]
+    method: Option<String>,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+/// 单次请求允许跟随的重定向次数
+const MAX_REDIRECTS: usize = 10;
+
+async fn perform_http(
+    url: String,
+    init: HostHttpInit,
+    permissions: PluginPermissions,
+) -> Result<Value, String> {
+    let method = init
+        .method
+        .as_deref()
+        .unwrap_or("GET")
+        .to_ascii_uppercase()
+        .parse::<reqwest::Method>()
+        .map_err(|e| format!("invalid http method: {e}"))?;
+    // 每一跳重定向都按插件权限重新校验，避免借助允许的域名跳转到未声明的地址
+    let redirect = reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if let Err(err) = permissions.check_url(attempt.url().as_str()) {
+            attempt.error(err)
+        } else {
+            attempt.follow()
+        }
+    });
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(HTTP_TIMEOUT_SECS))
+        .redirect(redirect)
+        .build()
+        .map_err(|e| format!("failed to build http client: {}", e))?;
+
+    let mut request = client.request(method, &url);
+    for (key, value) in &init.headers {
+        request = request.header(key.trim(), value);
+    }
+    if let Some(body) = init.body {
+        request = request.body(body);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("http request failed: {}", e))?;
+    let status = response.status();
+    let final_url = response.url().to_string();
+    let headers: Vec<Value> = response
+        .headers()
+        .iter()
+        .map(|(k, v)| {
+            serde_json::json!([k.as_str().to_ascii_lowercase(), v.to_str().unwrap_or("")])
+        })
+        .collect();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("failed to read response body: {}", e))?;
+
+    Ok(serde_json::json!({
+        "status": status.as_u16(),
+        "statusText": status.canonical_reason().unwrap_or(""),
+        "url": final_url,
+        "headers": headers,
+        "body": body,
+    }))
+}
+
+fn host_http(_this: &JsValue, args: &[JsValue], ctx: &mut Context) -> JsResult<JsValue> {
+    ensure_not_cancelled()?;
+    let url = arg_string(args, 0, ctx)?;
+    let init: HostHttpInit = match arg_json(args, 1, ctx)? {
+        Value::Null => HostHttpInit::default(),
+        value => serde_json::from_value(value)
+            .map_err(|e| js_error(format!("invalid httpRequest init: {e}")))?,
+    };
+    check_access(&url, &[])?;
+    let permissions = HOST.with(|host| {
+        host.borrow()
+            .as_ref()
+            .map(|state| state.permissions.clone())
+            .unwrap_or_default()
+    });
+    let result = block_on_host(perform_http(url, init, permissions)).map_err(js_error)?;
+    JsValue::from_json(&result, ctx)
+}
+
+/// 若值为 Promise，驱动任务队列直到其完成并返回结果
+fn settle(value: JsValue, ctx: &mut Context) -> Result<JsValue, String> {
+    let Some(object) = value.as_object().filter(|o| o.is_promise()).cloned() else {
+        return Ok(value);
+    };
+    let promise = JsPromise::from_object(object).map_err(|e| js_error_message(e, ctx))?;
+    ctx.run_jobs();
+    match promise.state() {
+        PromiseState::Fulfilled(result) => Ok(result),
+        PromiseState::Rejected(reason) => Err(js_value_message(&reason, ctx)),
+        PromiseState::Pending => Err("plugin promise never settled".to_string()),
+    }
+}
+
+/// 创建带循环 / 递归限制的运行时，避免插件代码无限占用阻塞线程
+fn new_context() -> Context {
+    let mut ctx = Context::default();
+    let limits = ctx.runtime_limits_mut();
+    limits.set_loop_iteration_limit(LOOP_ITERATION_LIMIT);
+    limits.set_recursion_limit(RECURSION_LIMIT);
+    ctx
+}
+
+fn load_module(source: &str, ctx: &mut Context) -> Result<Module, String> {
+    let module = Module::parse(Source::from_bytes(source.as_bytes()), None, ctx)
+        .map_err(|e| format!("plugin parse failed: {}", js_error_message(e, ctx)))?;
+    let evaluation = module.load_link_evaluate(ctx);
+    settle(evaluation.into(), ctx).map_err(|e| format!("plugin load failed: {}", e))?;
+    Ok(module)
+}
+
+/// 读取插件导出的成员：优先具名导出，其次 `export default { ... }` 对象上的同名属性。
+/// 返回值为（调用时的 this，成员值）。
+fn export_member(module: &Module, name: &str, ctx: &mut Context) -> JsResult<(JsValue, JsValue)> {
+    let namespace = module.namespace(ctx);
+    let value = namespace.get(JsString::from(name), ctx)?;
+    if !value.is_undefined() {
+        return Ok((JsValue::undefined(), value));
+    }
+    let default = namespace.get(js_string!("default"), ctx)?;
+    match default.as_object() {
+        Some(object) => {
+            let value = object.get(JsString::from(name), ctx)?;
+            Ok((default.clone(), value))
+        }
+        None => Ok((JsValue::undefined(), JsValue::undefined())),
+    }
+}
+
+/// 读取插件声明的权限；不注册任何宿主函数，插件顶层代码无法借此发起请求。
+/// 未声明时返回默认（无网络、仅可上传宿主给定的文件）。
+pub(crate) fn read_plugin_permissions(source: &str) -> Result<PluginPermissions, String> {
+    let mut ctx = new_context();
+    let module = load_module(source, &mut ctx)?;
+    let (_, value) = export_member(&module, "permissions", &mut ctx)
+        .map_err(|e| js_error_message(e, &mut ctx))?;
+    if value.is_undefined() || value.is_null() {
+        return Ok(PluginPermissions::default());
+    }
+    let json = value
+        .to_json(&mut ctx)
+        .map_err(|e| js_error_message(e, &mut ctx))?;
+    serde_json::from_value(json).map_err(|e| format!("invalid plugin permissions: {e}"))
+}
+
+/// 在阻塞线程中读取插件声明的权限，供命令与插件更新使用
+pub(crate) async fn inspect_plugin_permissions(
+    source: String,
+) -> Result<PluginPermissions, String> {
+    tauri::async_runtime::spawn_blocking(move || read_plugin_permissions(&source))
+        .await
+        .map_err(|e| format!("failed to join plugin task: {}", e))?
+}
+
+/// 调用插件导出的函数并返回 JSON 结果
+fn run_plugin_export(
+    source: &str,
+    export: &str,
+    build_args: impl FnOnce(&mut Context) -> JsResult<Vec<JsValue>>,
+) -> Result<Value, String> {
+    let mut ctx = new_context();
+    for (name, length, function) in [
+        (
+            "__yanaLog",
+            2,
+            host_log as fn(&JsValue, &[JsValue], &mut Context) -> JsResult<JsValue>,
+        ),
+        ("__yanaUpload", 1, host_upload),
+        ("__yanaHttp", 2, host_http),
+    ] {
+        ctx.register_global_callable(
+            JsString::from(name),
+            length,
+            NativeFunction::from_fn_ptr(function),
+        )
+        .map_err(|e| format!("register host function {}: {e}", name))?;
+    }
+    ctx.eval(Source::from_bytes(PRELUDE))
+        .map_err(|e| format!("plugin prelude failed: {e}"))?;
+
+    let module = load_module(source, &mut ctx)?;
+
+    let (this, function) =
+        export_member(&module, export, &mut ctx).map_err(|e| js_error_message(e, &mut ctx))?;
+    let Some(function) = function.as_callable().cloned() else {
+        return Err(format!("plugin does not export function `{}`", export));
+    };
+
+    let args = build_args(&mut ctx).map_err(|e| js_error_message(e, &mut ctx))?;
+    let result = match function.call(&this, &args, &mut ctx) {
+        Ok(value) => settle(value, &mut ctx)?,
+        Err(err) => return Err(js_error_message(err, &mut ctx)),
+    };
+    if result.is_undefined() {
+        return Ok(Value::Null);
+    }
+    result
+        .to_json(&mut ctx)
+        .map_err(|e| js_error_message(e, &mut ctx))
+}
+
+/// 取消标记：外层 future 被丢弃（取消）时置位，阻止插件继续发起请求，
+/// 并中止宿主函数中正在进行的请求
+struct CancelOnDrop {
+    cancelled: Arc<AtomicBool>,
+    upload_id: Option<String>,
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        if let Some(upload_id) = &self.upload_id {
+            let _ = cancel_upload(host_request_id(upload_id));
+        }
+    }
+}
+
+async fn execute_export<F>(
+    app: &AppHandle,
+    plugin_id: String,
+    export: &'static str,
+    upload_id: Option<String>,
+    upload_file: Option<String>,
+    build_args: F,
+) -> Result<Value, String>
+where
+    F: FnOnce(&mut Context) -> JsResult<Vec<JsValue>> + Send + 'static,
+{
+    let source = read_plugin_script(app, &plugin_id)?;
+
+    let upload_id = upload_id.filter(|id| !id.is_empty());
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let _guard = CancelOnDrop {
+        cancelled: cancelled.clone(),
+        upload_id: upload_id.clone(),
+    };
+    let id_for_thread = plugin_id.clone();
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let permissions = read_plugin_permissions(&source)?;
+        HOST.with(|host| {
+            *host.borrow_mut() = Some(HostState {
+                plugin_id: id_for_thread,
+                cancelled,
+                upload_id,
+                permissions,
+                upload_file,
+            })
+        });
+        let result = run_plugin_export(&source, export, build_args);
+        HOST.with(|host| host.borrow_mut().take());
+        result
+    })
+    .await
+    .map_err(|e| format!("failed to join plugin task: {}", e))?;
+
+    match &result {
+        Ok(_) => info!("plugin {} `{}` finished", plugin_id, export),
+        Err(err) => error!("plugin {} `{}` failed: {}", plugin_id, export, err),
+    }
+    result
+}
+
+/// 在后端运行时中执行插件的 `upload(filePath, originalFileName, params, context)`
+#[tauri::command]
+pub async fn execute_plugin_upload(
+    app: AppHandle,
+    plugin_id: String,
+    file_path: String,
+    original_file_name: Option<String>,
+    settings: Value,
+    upload_id: Option<String>,
+) -> Result<Value, String> {
+    let original_file_name = original_file_name.unwrap_or_else(|| {
+        std::path::Path::new(&file_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "upload.bin".to_string())
+    });
+
+    // 界面保存的配置中钥匙串字段为占位符，执行前解析为真实值
+    let settings = resolve_plugin_secrets(&plugin_id, settings)?;
+    let upload_file = Some(file_path.clone());
+    let build_args = move |ctx: &mut Context| -> JsResult<Vec<JsValue>> {
+        let context = ctx.global_object().get(js_string!("__yanaContext"), ctx)?;
+        Ok(vec![
+            JsValue::from(JsString::from(file_path.as_str())),
+            JsValue::from(JsString::from(original_file_name.as_str())),
+            JsValue::from_json(&settings, ctx)?,
+            context,
+        ])
+    };
+
+    let queued = crate::upload_queue::run_queued(
+        &app,
+        upload_id.as_deref(),
+        None,
+        execute_export(
+            &app,
+            plugin_id,
+            "upload",
+            upload_id.clone(),
+            upload_file,
+            build_args,
+        ),
+    );
+    run_cancellable(upload_id, queued).await
+}
+
+/// 在后端运行时中执行插件的 `remove(deleteId, context)`
+#[tauri::command]
+pub async fn execute_plugin_delete(
+    app: AppHandle,
+    plugin_id: String,
+    delete_id: String,
+) -> Result<Value, String> {
+    let build_args = move |ctx: &mut Context| -> JsResult<Vec<JsValue>> {
+        let context = ctx.global_object().get(js_string!("__yanaContext"), ctx)?;
+        Ok(vec![
+            JsValue::from(JsString::from(delete_id.as_str())),
+            context,
+        ])
+    };
+    execute_export(&app, plugin_id, "remove", None, None, build_args).await
+}
@@ -0,0 +1,373 @@
+/*
+用户插件更新：
+1) 插件脚本通过 `export const version = '1.2.0'` 与 `export const updateUrl = 'https://...'` 声明版本与更新地址；
+2) `check_plugin_updates` 拉取各插件的远程脚本并比较版本，有可用更新时发送 `image-hosts://updates-available` 事件；
+3) `update_plugin` 下载新脚本，将旧脚本备份到 `plugins/.backup/` 后原地替换；
+   新版本声明的权限与已安装版本不同时不写入，返回新权限供用户确认，
+   携带与之一致的 `approved` 再次调用才会安装（与 `add_image_host_plugin` 相同）；
+4) 更新地址及其重定向必须为 https，响应体边下载边计数，超过大小上限即中止。
+内置插件随应用发布，不参与更新。
+*/
+
+use std::{cmp::Ordering, fs, path::Path, time::Duration};
+
+use log::{error, info, warn};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::image_hosts::{
+    PluginEntryPayload, find_user_plugin, is_plugin_enabled, user_plugin_dir,
+};
+use crate::plugin_permissions::PluginPermissions;
+use crate::plugin_runtime::inspect_plugin_permissions;
+
+const UPDATES_EVENT: &str = "image-hosts://updates-available";
+const BACKUP_DIR_NAME: &str = ".backup";
+/// 远程脚本大小上限，防止误配置的地址返回超大内容
+const MAX_PLUGIN_SCRIPT_BYTES: usize = 2 * 1024 * 1024;
+
+/// 从脚本源码中解析出的插件元数据
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct PluginManifest {
+    pub version: Option<String>,
+    pub update_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginUpdateInfo {
+    pub id: String,
+    pub current_version: Option<String>,
+    pub latest_version: String,
+    pub update_url: String,
+}
+
+/// 读取 `export const <name> = '<value>'` 形式的字符串常量
+fn exported_string(source: &str, name: &str) -> Option<String> {
+    source.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("export const")?.trim_start();
+        let rest = rest.strip_prefix(name)?.trim_start();
+        let rest = rest.strip_prefix('=')?.trim_start();
+        let quote = rest
+            .chars()
+            .next()
+            .filter(|c| matches!(c, '\'' | '"' | '`'))?;
+        let value = &rest[1..];
+        let end = value.find(quote)?;
+        Some(value[..end].to_string())
+    })
+}
+
+pub(crate) fn parse_manifest(source: &str) -> PluginManifest {
+    PluginManifest {
+        version: exported_string(source, "version"),
+        update_url: exported_string(source, "updateUrl"),
+    }
+}
+
+/// 按点分数字比较版本号（`1.10.0` > `1.9.3`），非数字部分按字符串比较
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |v: &str| -> Vec<String> {
+        v.trim()
+            .trim_start_matches('v')
+            .split(['.', '-', '+'])
+            .map(str::to_string)
+            .collect()
+    };
+    let (left, right) = (parse(a), parse(b));
+    for i in 0..left.len().max(right.len()) {
+        let l = left.get(i).map(String::as_str).unwrap_or("0");
+        let r = right.get(i).map(String::as_str).unwrap_or("0");
+        let ordering = match (l.parse::<u64>(), r.parse::<u64>()) {
+            (Ok(l), Ok(r)) => l.cmp(&r),
+            _ => l.cmp(r),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+async fn fetch_script(url: &str) -> Result<String, String> {
+    let parsed =
+        reqwest::Url::parse(url).map_err(|e| format!("插件更新地址无效 {}: {}", url, e))?;
+    if parsed.scheme() != "https" {
+        return Err(format!("插件更新地址必须使用 https: {}", url));
+    }
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .redirect(reqwest::redirect::Policy::custom(|attempt| {
+            if attempt.url().scheme() != "https" {
+                attempt.error("插件更新地址重定向到了非 https 地址")
+            } else if attempt.previous().len() >= 10 {
+                attempt.stop()
+            } else {
+                attempt.follow()
+            }
+        }))
+        .build()
+        .map_err(|e| format!("failed to build http client: {}", e))?;
+    let mut response = client
+        .get(parsed)
+        .send()
+        .await
+        .map_err(|e| format!("下载插件失败 {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("下载插件失败 {}: HTTP {}", url, response.status()));
+    }
+    let too_large = |size: u64| format!("插件脚本过大: {} bytes", size);
+    if let Some(length) = response.content_length()
+        && length > MAX_PLUGIN_SCRIPT_BYTES as u64
+    {
+        return Err(too_large(length));
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("读取插件内容失败 {}: {}", url, e))?
+    {
+        if bytes.len() + chunk.len() > MAX_PLUGIN_SCRIPT_BYTES {
+            return Err(too_large((bytes.len() + chunk.len()) as u64));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    String::from_utf8(bytes).map_err(|_| format!("插件脚本不是有效的 UTF-8: {}", url))
+}
+
+/// 读取用户插件目录下的所有插件及其元数据
+fn user_plugins(app: &AppHandle) -> Result<Vec<(String, PluginManifest)>, String> {
+    let dir = user_plugin_dir(app)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(&dir).map_err(|e| format!("read_dir {}: {e}", dir.display()))?;
+    let mut plugins = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_script = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext == "js" || ext == "mjs");
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if !is_script || !path.is_file() {
+            continue;
+        }
+        match fs::read_to_string(&path) {
+            Ok(source) => plugins.push((id.to_string(), parse_manifest(&source))),
+            Err(err) => warn!("read plugin {} failed: {}", path.display(), err),
+        }
+    }
+    Ok(plugins)
+}
+
+async fn check_one(id: String, manifest: PluginManifest) -> Option<PluginUpdateInfo> {
+    let update_url = manifest.update_url?;
+    let source = match fetch_script(&update_url).await {
+        Ok(source) => source,
+        Err(err) => {
+            warn!("check update for plugin {} failed: {}", id, err);
+            return None;
+        }
+    };
+    let latest_version = parse_manifest(&source).version?;
+    let newer = match manifest.version.as_deref() {
+        Some(current) => compare_versions(&latest_version, current) == Ordering::Greater,
+        None => true,
+    };
+    newer.then_some(PluginUpdateInfo {
+        id,
+        current_version: manifest.version,
+        latest_version,
+        update_url,
+    })
+}
+
+/// 检查所有声明了 updateUrl 的用户插件是否有新版本
+#[tauri::command]
+pub async fn check_plugin_updates(app: AppHandle) -> Result<Vec<PluginUpdateInfo>, String> {
+    let plugins = user_plugins(&app)?;
+    let checks = plugins
+        .into_iter()
+        .map(|(id, manifest)| check_one(id, manifest));
+    let mut updates: Vec<PluginUpdateInfo> = futures::future::join_all(checks)
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+    updates.sort_by(|a, b| a.id.cmp(&b.id));
+
+    info!("check_plugin_updates: available={}", updates.len());
+    if !updates.is_empty()
+        && let Err(err) = app.emit(UPDATES_EVENT, &updates)
+    {
+        warn!("failed to emit plugin updates event: {}", err);
+    }
+    Ok(updates)
+}
+
+/// `update_plugin` 的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum PluginUpdateOutcome {
+    Updated {
+        plugin: PluginEntryPayload,
+    },
+    /// 新版本请求的权限与已安装版本不同，需用户确认后携带 `approved` 重新调用
+    #[serde(rename_all = "camelCase")]
+    PermissionsChanged {
+        current: Option<PluginPermissions>,
+        requested: PluginPermissions,
+    },
+}
+
+/// 权限变化时只有用户确认过的正是新版本声明的权限才允许安装
+fn needs_approval(
+    current: Option<&PluginPermissions>,
+    requested: &PluginPermissions,
+    approved: Option<&PluginPermissions>,
+) -> bool {
+    current != Some(requested) && approved != Some(requested)
+}
+
+fn backup_plugin(path: &Path, id: &str, version: Option<&str>) -> Result<(), String> {
+    let Some(parent) = path.parent() else {
+        return Ok(());
+    };
+    let backup_dir = parent.join(BACKUP_DIR_NAME);
+    fs::create_dir_all(&backup_dir)
+        .map_err(|e| format!("create_dir_all {}: {e}", backup_dir.display()))?;
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("js");
+    let stamp = chrono::Local::now().format("%Y%m%d%H%M%S");
+    let backup = backup_dir.join(format!(
+        "{}-{}-{}.{}",
+        id,
+        version.unwrap_or("unknown"),
+        stamp,
+        ext
+    ));
+    fs::copy(path, &backup).map_err(|e| format!("备份插件失败: {e}"))?;
+    Ok(())
+}
+
+/// 下载并安装插件的新版本，旧脚本备份到 plugins/.backup/。
+/// 权限有变化时返回 `PermissionsChanged`，`approved` 与新版本声明一致时才会安装。
+#[tauri::command]
+pub async fn update_plugin(
+    app: AppHandle,
+    id: String,
+    approved: Option<PluginPermissions>,
+) -> Result<PluginUpdateOutcome, String> {
+    let path = find_user_plugin(&app, &id)?
+        .ok_or_else(|| format!("插件 {} 不是用户添加的插件，无法更新", id))?;
+    let installed =
+        fs::read_to_string(&path).map_err(|e| format!("read {}: {e}", path.display()))?;
+    let current = parse_manifest(&installed);
+    let update_url = current
+        .update_url
+        .clone()
+        .ok_or_else(|| format!("插件 {} 未声明 updateUrl", id))?;
+
+    let source = fetch_script(&update_url).await?;
+    let latest = parse_manifest(&source);
+    let Some(latest_version) = latest.version else {
+        return Err("远程插件未声明 version".to_string());
+    };
+    if let Some(current_version) = current.version.as_deref()
+        && compare_versions(&latest_version, current_version) != Ordering::Greater
+    {
+        return Err(format!("插件 {} 已是最新版本 {}", id, current_version));
+    }
+
+    let permissions = inspect_plugin_permissions(source.clone())
+        .await
+        .map_err(|e| format!("远程插件的权限声明无效: {e}"))?;
+    let current_permissions = inspect_plugin_permissions(installed).await.ok();
+    if needs_approval(
+        current_permissions.as_ref(),
+        &permissions,
+        approved.as_ref(),
+    ) {
+        info!(
+            "update_plugin needs approval: id={}, permissions={:?}",
+            id, permissions
+        );
+        return Ok(PluginUpdateOutcome::PermissionsChanged {
+            current: current_permissions,
+            requested: permissions,
+        });
+    }
+
+    backup_plugin(&path, &id, current.version.as_deref())?;
+    let dir = path
+        .parent()
+        .ok_or_else(|| format!("invalid plugin path: {}", path.display()))?;
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)
+        .map_err(|e| format!("tempfile_in {}: {e}", dir.display()))?;
+    std::io::Write::write_all(&mut tmp, source.as_bytes())
+        .map_err(|e| format!("写入插件失败: {e}"))?;
+    tmp.persist(&path).map_err(|e| {
+        error!("update_plugin persist failed: id={}, error={}", id, e.error);
+        format!("替换插件失败: {}", e.error)
+    })?;
+
+    info!(
+        "update_plugin success: id={}, {} -> {}",
+        id,
+        current.version.as_deref().unwrap_or("unknown"),
+        latest_version
+    );
+    let enabled = is_plugin_enabled(&app, &id)?;
+    Ok(PluginUpdateOutcome::Updated {
+        plugin: PluginEntryPayload {
+            id,
+            script: path.to_string_lossy().to_string(),
+            enabled,
+            permissions: Some(permissions),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest_and_compare() {
+        let source = "export const name = 'Demo';\n\
+                      export const version = \"1.10.0\";\n\
+                      export const updateUrl = 'https://example.com/demo.js';\n";
+        let manifest = parse_manifest(source);
+        assert_eq!(manifest.version.as_deref(), Some("1.10.0"));
+        assert_eq!(
+            manifest.update_url.as_deref(),
+            Some("https://example.com/demo.js")
+        );
+
+        assert_eq!(compare_versions("1.10.0", "1.9.3"), Ordering::Greater);
+        assert_eq!(compare_versions("v2.0", "2.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0.0", "1.0.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_needs_approval() {
+        let narrow = PluginPermissions {
+            domains: vec!["sm.ms".to_string()],
+            ..Default::default()
+        };
+        let wide = PluginPermissions {
+            domains: vec!["*".to_string()],
+            filesystem: crate::plugin_permissions::FilesystemAccess::Read,
+            clipboard: false,
+        };
+        assert!(!needs_approval(Some(&narrow), &narrow, None));
+        assert!(needs_approval(Some(&narrow), &wide, None));
+        assert!(needs_approval(Some(&narrow), &wide, Some(&narrow)));
+        assert!(!needs_approval(Some(&narrow), &wide, Some(&wide)));
+        // 已安装版本的权限无法读取时同样需要确认
+        assert!(needs_approval(None, &narrow, None));
+    }
+}
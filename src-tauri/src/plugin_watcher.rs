@@ -0,0 +1,153 @@
+/*
+插件热重载：
+1) 启动时监听用户插件目录（app_config_dir/plugins），开发环境额外监听源码中的 `src/plugins`；
+2) 文件事件按 300ms 合并后发送 `plugins://changed` 事件，载荷为新增 / 修改 / 删除的插件 id；
+3) 仅关注 .js / .mjs 脚本，`.backup` 等子目录不参与监听。
+*/
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration,
+};
+
+use log::{debug, error, info, warn};
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::image_hosts::user_plugin_dir;
+
+const CHANGED_EVENT: &str = "plugins://changed";
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginsChangedPayload {
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl PluginsChangedPayload {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
+fn plugin_id(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_str()?;
+    if ext != "js" && ext != "mjs" {
+        return None;
+    }
+    Some(path.file_stem()?.to_str()?.to_string())
+}
+
+fn watch_dirs(app: &AppHandle) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    match user_plugin_dir(app) {
+        Ok(dir) => {
+            if let Err(err) = std::fs::create_dir_all(&dir) {
+                warn!("create plugin dir {} failed: {}", dir.display(), err);
+            }
+            dirs.push(dir);
+        }
+        Err(err) => warn!("resolve user plugin dir failed: {}", err),
+    }
+
+    #[cfg(debug_assertions)]
+    {
+        let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let workspace_root = manifest_dir.parent().unwrap_or(&manifest_dir);
+        dirs.push(workspace_root.join("src").join("plugins"));
+    }
+
+    dirs.into_iter().filter(|dir| dir.is_dir()).collect()
+}
+
+/// 将一批文件事件归并为插件变更；以文件最终是否存在区分删除与新增 / 修改
+fn summarize(events: &BTreeMap<PathBuf, bool>) -> PluginsChangedPayload {
+    let mut added = BTreeSet::new();
+    let mut modified = BTreeSet::new();
+    let mut removed = BTreeSet::new();
+    for (path, created) in events {
+        let Some(id) = plugin_id(path) else {
+            continue;
+        };
+        if !path.exists() {
+            removed.insert(id);
+        } else if *created {
+            added.insert(id);
+        } else {
+            modified.insert(id);
+        }
+    }
+    // 同一 id 的 .js 被删除而 .mjs 仍存在时不视为删除
+    removed.retain(|id| !added.contains(id) && !modified.contains(id));
+    PluginsChangedPayload {
+        added: added.into_iter().collect(),
+        modified: modified.into_iter().collect(),
+        removed: removed.into_iter().collect(),
+    }
+}
+
+/// 启动插件目录监听线程，监听器随线程存活至应用退出
+pub fn init(app: &AppHandle) {
+    let dirs = watch_dirs(app);
+    if dirs.is_empty() {
+        return;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            error!("create plugin watcher failed: {}", err);
+            return;
+        }
+    };
+    for dir in &dirs {
+        match watcher.watch(dir, RecursiveMode::NonRecursive) {
+            Ok(()) => info!("watching plugin dir {}", dir.display()),
+            Err(err) => warn!("watch plugin dir {} failed: {}", dir.display(), err),
+        }
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let _watcher = watcher;
+        // 路径 → 本批次内是否出现过创建事件
+        let mut pending: BTreeMap<PathBuf, bool> = BTreeMap::new();
+        loop {
+            let received = if pending.is_empty() {
+                rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected)
+            } else {
+                rx.recv_timeout(DEBOUNCE)
+            };
+            match received {
+                Ok(Ok(event)) => {
+                    let created = matches!(event.kind, EventKind::Create(_));
+                    if matches!(event.kind, EventKind::Access(_)) {
+                        continue;
+                    }
+                    for path in event.paths {
+                        *pending.entry(path).or_default() |= created;
+                    }
+                }
+                Ok(Err(err)) => warn!("plugin watcher error: {}", err),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    let payload = summarize(&std::mem::take(&mut pending));
+                    if payload.is_empty() {
+                        continue;
+                    }
+                    debug!("plugins changed: {:?}", payload);
+                    if let Err(err) = app.emit(CHANGED_EVENT, &payload) {
+                        warn!("failed to emit plugins changed event: {}", err);
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+}
@@ -0,0 +1,84 @@
+/*
+便携模式：
+1) 可执行文件旁存在 `portable` 标记文件，或设置环境变量 `YANA_PORTABLE=1` 时启用；
+2) 启用后配置、图库数据库、缩略图缓存、日志与临时文件都放在可执行文件旁的 `data/` 目录
+   （`data/config`、`data/data`、`data/logs`、`data/temp`），不写入系统应用目录，可从 U 盘直接运行；
+3) 钥匙串中的敏感字段仍由系统管理，换机后需重新填写或通过配置导出携带。
+*/
+
+use std::{path::PathBuf, sync::OnceLock};
+
+use tauri::{AppHandle, Manager};
+
+const PORTABLE_MARKER: &str = "portable";
+const PORTABLE_ENV: &str = "YANA_PORTABLE";
+const DATA_DIR_NAME: &str = "data";
+/// 非便携模式下系统临时目录中的子目录名
+const TEMP_DIR_NAME: &str = "com.yana.dev";
+
+static PORTABLE_ROOT: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+fn env_enabled() -> bool {
+    std::env::var(PORTABLE_ENV)
+        .map(|value| matches!(value.trim(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+fn detect() -> Option<PathBuf> {
+    let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+    if env_enabled() || exe_dir.join(PORTABLE_MARKER).exists() {
+        Some(exe_dir.join(DATA_DIR_NAME))
+    } else {
+        None
+    }
+}
+
+/// 便携模式下的数据根目录，未启用时为 None
+pub(crate) fn portable_root() -> Option<&'static PathBuf> {
+    PORTABLE_ROOT.get_or_init(detect).as_ref()
+}
+
+pub(crate) fn app_config_dir(app: &AppHandle) -> tauri::Result<PathBuf> {
+    match portable_root() {
+        Some(root) => Ok(root.join("config")),
+        None => app.path().app_config_dir(),
+    }
+}
+
+pub(crate) fn app_data_dir(app: &AppHandle) -> tauri::Result<PathBuf> {
+    match portable_root() {
+        Some(root) => Ok(root.join("data")),
+        None => app.path().app_data_dir(),
+    }
+}
+
+pub(crate) fn app_log_dir(app: &AppHandle) -> tauri::Result<PathBuf> {
+    match portable_root() {
+        Some(root) => Ok(root.join("logs")),
+        None => app.path().app_log_dir(),
+    }
+}
+
+/// 应用临时目录：便携模式为 `data/temp`，否则为系统临时目录下的 com.yana.dev
+pub(crate) fn temp_dir() -> PathBuf {
+    match portable_root() {
+        Some(root) => root.join("temp"),
+        None => std::env::temp_dir().join(TEMP_DIR_NAME),
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortableStatus {
+    pub enabled: bool,
+    pub data_dir: Option<String>,
+}
+
+#[tauri::command]
+pub fn get_portable_status() -> PortableStatus {
+    let root = portable_root();
+    PortableStatus {
+        enabled: root.is_some(),
+        data_dir: root.map(|root| root.to_string_lossy().into_owned()),
+    }
+}
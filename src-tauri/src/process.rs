@@ -12,13 +12,26 @@
     - 动画 WebP：暂时原样透传（保持动画）；若目标为 WebP 同样透传。
 5) 输出：使用 tempfile 在系统临时目录生成输出文件，返回绝对路径（顺序与输入一致）。
 6) 并行：使用 rayon 并发处理，最后按原始索引恢复顺序。
+7) 自动画质：传入 target_ssim 时，JPEG、WebP 与有损 PNG 静态图不使用 quality，
+   而是查找 SSIM 不低于目标的最低画质（quality_metrics::auto_encode），使不同图片的观感一致。
+8) 读取：应用临时目录中不小于 `MMAP_THRESHOLD` 的文件（应用自己生成的副本）以内存映射读取，
+   格式检测、解码与透传写出直接使用映射内容，不再复制整个文件；解码完成后立即释放输入，
+   编码期间只保留解码后的图像，大尺寸 TIFF/PNG 的峰值内存约减半。
+   用户目录中的文件不做映射（映射期间文件被其他程序截断会使进程收到 SIGBUS，Windows 为访问异常，
+   直接崩溃而不是返回错误）：不小于 `MMAP_THRESHOLD` 的静态图只读取文件头判定格式，
+   再经 `BufReader<File>` 流式解码，同样不在内存中保留整个文件；
+   HEIC、GIF 与 WebP 需要完整内容判断动图或原样透传，仍整体读入内存。
+9) 流水线：`compress_each` 在专用 rayon 线程池中逐个接收文件，每个文件完成后立即经有界通道发出，
+   供上传流程边压缩边上传（host_upload::prepare_pipelined）。
+10) HEIC：按文件头识别后交给 heic 模块解码，静态图在原格式模式下输出 JPEG；
+    图像序列按设置取主图或转为动画 WebP / GIF。
 
 注意：image 目前对动画 WebP 的编码支持有限，因此 WebP 动画暂未重编码，仅保留原样或退化为首帧静态图。
 */
 
 use std::fs::File;
-use std::io::{Cursor, Read, Write};
-use std::path::PathBuf;
+use std::io::{BufReader, Cursor, Read, Seek, Write};
+use std::path::{Path, PathBuf};
 
 use image::GenericImageView;
 use image::codecs::gif::GifDecoder;
@@ -30,6 +43,7 @@ use image::{
     self, AnimationDecoder, ColorType, DynamicImage, ImageEncoder, ImageFormat, ImageReader,
 };
 use log::{debug, error, info};
+use memmap2::Mmap;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use tauri_plugin_shell::ShellExt;
@@ -37,14 +51,11 @@ use tauri_plugin_shell::process::CommandEvent;
 use tempfile::Builder as TempFileBuilder;
 use webp::{Encoder as WebpEncoder, PixelLayout}; // adjustable-quality webp
 
-// Helper: application-specific temp directory inside system temp
+use crate::heic::HeicSequenceMode;
+
+// Helper: application-specific temp directory (system temp, or data/temp in portable mode)
 fn app_temp_dir() -> Result<PathBuf, String> {
-    let mut dir = std::env::temp_dir();
-    // prefer identifier from tauri.conf.json (compile-time include) and fallback to cargo package name
-    // use fixed app identifier to avoid parsing config at compile time
-    let identifier = "com.yana.dev".to_string();
-    dir.push(identifier);
-    Ok(dir)
+    Ok(crate::portable::temp_dir())
 }
 
 fn ensure_app_temp_dir() -> Result<PathBuf, String> {
@@ -54,30 +65,6 @@ fn ensure_app_temp_dir() -> Result<PathBuf, String> {
     Ok(dir)
 }
 
-fn cleanup_app_temp_dir_internal() -> Result<(), String> {
-    let dir = app_temp_dir()?;
-    if dir.exists() {
-        std::fs::remove_dir_all(&dir)
-            .map_err(|e| format!("remove app temp dir {}: {}", dir.display(), e))?;
-    }
-    Ok(())
-}
-
-#[tauri::command]
-pub async fn clean_app_temp_dir() -> Result<(), String> {
-    // 将文件系统操作委托给 tokio blocking 线程
-    tokio::task::spawn_blocking(move || {
-        info!("clean_app_temp_dir start");
-        cleanup_app_temp_dir_internal()?;
-        // recreate empty dir so subsequent tempfile_in calls succeed
-        ensure_app_temp_dir()?;
-        info!("clean_app_temp_dir done");
-        Ok(())
-    })
-    .await
-    .map_err(|e| format!("spawn_blocking error: {}", e))?
-}
-
 #[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[allow(non_camel_case_types)]
@@ -123,15 +110,122 @@ enum DetectedKind {
     Animated(ImageFormat),
 }
 
-fn read_all_bytes(path: &str) -> Result<Vec<u8>, String> {
+/// 不小于该大小的应用临时文件以内存映射读取
+const MMAP_THRESHOLD: u64 = 4 * 1024 * 1024;
+
+/// 用户目录中的大文件判定格式时读取的文件头长度
+const HEADER_LEN: u64 = 64 * 1024;
+
+/// 输入文件内容：小文件读入内存，大文件为只读内存映射
+enum InputBytes {
+    Owned(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl std::ops::Deref for InputBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Owned(buf) => buf,
+            Self::Mapped(map) => map,
+        }
+    }
+}
+
+/// 是否位于应用临时目录（只有这里的文件在处理期间不会被其他程序截断）
+fn is_app_temp_file(path: &str) -> bool {
+    let Ok(dir) = app_temp_dir().and_then(|dir| {
+        dir.canonicalize()
+            .map_err(|e| format!("canonicalize {}: {}", dir.display(), e))
+    }) else {
+        return false;
+    };
+    Path::new(path)
+        .canonicalize()
+        .is_ok_and(|file| file.starts_with(&dir))
+}
+
+fn read_input(path: &str) -> Result<InputBytes, String> {
     // 直接以字节读取，后续用 guess_format 基于 header 判定真实格式
-    debug!("read_all_bytes start: path={}", path);
+    debug!("read_input start: path={}", path);
     let mut f = File::open(path).map_err(|e| format!("open {}: {}", path, e))?;
-    let mut buf = Vec::new();
+    let len = f
+        .metadata()
+        .map_err(|e| format!("metadata {}: {}", path, e))?
+        .len();
+    if len >= MMAP_THRESHOLD && is_app_temp_file(path) {
+        // SAFETY: 映射的文件被截断后访问越界页会触发 SIGBUS，无法作为错误处理；
+        // 这里只映射应用临时目录中由应用自己写出的文件，处理期间应用不会改动它们，
+        // 其他程序改写应用临时文件不在支持范围内
+        match unsafe { Mmap::map(&f) } {
+            Ok(map) => {
+                debug!("read_input mapped: path={}, bytes={}", path, map.len());
+                return Ok(InputBytes::Mapped(map));
+            }
+            Err(e) => debug!("mmap failed, read into memory: path={}, error={}", path, e),
+        }
+    }
+    let mut buf = Vec::with_capacity(len as usize);
     f.read_to_end(&mut buf)
         .map_err(|e| format!("read {}: {}", path, e))?;
-    debug!("read_all_bytes done: path={}, bytes={}", path, buf.len());
-    Ok(buf)
+    debug!("read_input done: path={}, bytes={}", path, buf.len());
+    Ok(InputBytes::Owned(buf))
+}
+
+/// 待处理的输入：已读入（或映射）的内容，或只读取了文件头、解码时再流式读取的用户文件
+enum Input {
+    Loaded(InputBytes),
+    Stream { file: File, format: ImageFormat },
+}
+
+impl Input {
+    /// 完整内容；流式输入只会是静态图，不经过需要完整内容的分支
+    fn loaded(&self) -> Result<&[u8], String> {
+        match self {
+            Self::Loaded(bytes) => Ok(bytes),
+            Self::Stream { .. } => Err("input is not loaded into memory".to_string()),
+        }
+    }
+}
+
+/// 打开输入：用户目录中不小于 `MMAP_THRESHOLD` 的静态图只读取文件头，其余按 `read_input` 读取
+fn open_input(path: &str) -> Result<Input, String> {
+    let mut file = File::open(path).map_err(|e| format!("open {}: {}", path, e))?;
+    let len = file
+        .metadata()
+        .map_err(|e| format!("metadata {}: {}", path, e))?
+        .len();
+    if len >= MMAP_THRESHOLD && !is_app_temp_file(path) {
+        let mut header = Vec::with_capacity(HEADER_LEN as usize);
+        (&mut file)
+            .take(HEADER_LEN)
+            .read_to_end(&mut header)
+            .map_err(|e| format!("read {}: {}", path, e))?;
+        if !crate::heic::is_heic(&header)
+            && let Ok(format) = image::guess_format(&header)
+            && !matches!(format, ImageFormat::Gif | ImageFormat::WebP)
+        {
+            file.rewind().map_err(|e| format!("seek {}: {}", path, e))?;
+            debug!("open_input stream: path={}, format={:?}", path, format);
+            return Ok(Input::Stream { file, format });
+        }
+    }
+    read_input(path).map(Input::Loaded)
+}
+
+/// 解码输入，解码完成后释放输入（大文件为解除映射或关闭文件）
+fn decode_input(input: Input) -> Result<DynamicImage, String> {
+    let img = match input {
+        Input::Loaded(bytes) => ImageReader::new(Cursor::new(&*bytes))
+            .with_guessed_format()
+            .map_err(|e| format!("reader: {}", e))?
+            .decode(),
+        Input::Stream { file, format } => {
+            ImageReader::with_format(BufReader::new(file), format).decode()
+        }
+    };
+    img.map_err(|e| format!("decode: {}", e))
 }
 
 fn detect_format_and_kind(bytes: &[u8]) -> Result<DetectedKind, String> {
@@ -167,7 +261,7 @@ fn detect_format_and_kind(bytes: &[u8]) -> Result<DetectedKind, String> {
 
 // ---------- Static encoders ----------
 
-fn encode_png(
+pub(crate) fn encode_png(
     img: &DynamicImage,
     quality: u8,
     mode: PngCompressionMode,
@@ -220,7 +314,7 @@ fn quantize_channel(value: u8, step: u8) -> u8 {
     rounded.min(255) as u8
 }
 
-fn encode_jpeg(img: &DynamicImage, quality: u8) -> Result<Vec<u8>, String> {
+pub(crate) fn encode_jpeg(img: &DynamicImage, quality: u8) -> Result<Vec<u8>, String> {
     // JPEG 为有损：quality 直接决定画质（0-100）
     let mut cursor = Cursor::new(Vec::new());
     let encoder = JpegEncoder::new_with_quality(&mut cursor, quality);
@@ -264,7 +358,7 @@ fn encode_jpeg(img: &DynamicImage, quality: u8) -> Result<Vec<u8>, String> {
     Ok(cursor.into_inner())
 }
 
-fn encode_webp_static(img: &DynamicImage, quality: u8) -> Result<Vec<u8>, String> {
+pub(crate) fn encode_webp_static(img: &DynamicImage, quality: u8) -> Result<Vec<u8>, String> {
     let has_alpha = img.color().has_alpha();
 
     let layout = if has_alpha {
@@ -393,24 +487,105 @@ fn convert_gif_to_webp(
 
 // ---------- Orchestrator ----------
 
-fn process_one(
-    app: &tauri::AppHandle,
+/// 自动画质只对有损编码生效：JPEG、WebP 与有损 PNG
+fn supports_auto_quality(format: ImageFormat, png_mode: PngCompressionMode) -> bool {
+    match format {
+        ImageFormat::Jpeg | ImageFormat::WebP => true,
+        ImageFormat::Png => png_mode == PngCompressionMode::Lossy,
+        _ => false,
+    }
+}
+
+/// 按固定画质编码，或在指定 target_ssim 时按自动画质编码
+fn encode_static(
     path: &str,
     quality: u8,
-    mode: Mode,
-    png_mode: PngCompressionMode,
-    png_optimization: PngOptimizationLevel,
-) -> Result<PathBuf, String> {
+    target_ssim: Option<f64>,
+    img: &DynamicImage,
+    encode: impl Fn(u8) -> Result<Vec<u8>, String>,
+) -> Result<Vec<u8>, String> {
+    let Some(target) = target_ssim else {
+        return encode(quality);
+    };
+    let (chosen, out) = crate::quality_metrics::auto_encode(img, target, encode)?;
+    info!(
+        "auto quality: path={}, target_ssim={}, quality={}",
+        path, target, chosen
+    );
+    Ok(out)
+}
+
+/// 在应用临时目录写出结果文件
+fn persist_output(extension: &str, data: &[u8]) -> Result<PathBuf, String> {
+    let mut tmp = TempFileBuilder::new()
+        .prefix("yana_")
+        .suffix(extension)
+        .tempfile_in(ensure_app_temp_dir()?)
+        .map_err(|e| format!("tempfile_in: {}", e))?;
+    tmp.write_all(data).map_err(|e| format!("write: {}", e))?;
+    tmp.into_temp_path()
+        .keep()
+        .map_err(|e| format!("keep temp: {}", e))
+}
+
+/// HEIC 输入：静态图按目标模式编码（原格式模式输出 JPEG），图像序列按设置输出
+fn process_heic(path: &str, bytes: InputBytes, params: &CompressParams) -> Result<PathBuf, String> {
+    let decoded = crate::heic::decode(&bytes, params.heic_sequence, params.quality)?;
+    drop(bytes);
+    let (extension, out) = match decoded {
+        crate::heic::HeicImage::Animated(out, extension) => (extension, out),
+        crate::heic::HeicImage::Still(img) => match params.mode {
+            Mode::original_format => (
+                ".jpg",
+                encode_static(path, params.quality, params.target_ssim, &img, |q| {
+                    encode_jpeg(&img, q)
+                })?,
+            ),
+            Mode::webp => (
+                ".webp",
+                encode_static(path, params.quality, params.target_ssim, &img, |q| {
+                    encode_webp_static(&img, q)
+                })?,
+            ),
+        },
+    };
+    let path_buf = persist_output(extension, &out)?;
     info!(
-        "process_one start: path={}, quality={}, mode={:?}, png_mode={:?}, png_optimization={:?}",
-        path, quality, mode, png_mode, png_optimization
+        "process_one done: path={}, output={}",
+        path,
+        path_buf.display()
     );
+    Ok(path_buf)
+}
+
+fn process_one(
+    app: &tauri::AppHandle,
+    path: &str,
+    params: &CompressParams,
+) -> Result<PathBuf, String> {
+    let CompressParams {
+        quality,
+        mode,
+        png_mode,
+        png_optimization,
+        target_ssim,
+        ..
+    } = *params;
+    info!("process_one start: path={}, params={:?}", path, params);
     // 检测是否在 Android 平台
     let is_android = cfg!(target_os = "android");
 
     // 读取并判定格式/动图属性
-    let bytes = read_all_bytes(path)?;
-    let kind = detect_format_and_kind(&bytes)?;
+    let input = match open_input(path)? {
+        Input::Loaded(bytes) if crate::heic::is_heic(&bytes) => {
+            return process_heic(path, bytes, params);
+        }
+        input => input,
+    };
+    let kind = match &input {
+        Input::Loaded(bytes) => detect_format_and_kind(bytes)?,
+        Input::Stream { format, .. } => DetectedKind::Static(*format),
+    };
 
     // 在应用专属临时目录创建输出文件（实际路径在 keep() 之后可被持久化）
     let tmp_dir = ensure_app_temp_dir()?;
@@ -463,42 +638,41 @@ fn process_one(
 
     match (kind, mode) {
         (DetectedKind::Static(fmt), Mode::original_format) => {
-            let img = ImageReader::new(Cursor::new(bytes))
-                .with_guessed_format()
-                .map_err(|e| format!("reader: {}", e))?
-                .decode()
-                .map_err(|e| format!("decode: {}", e))?;
-            let out = encode_to_format(&img, fmt, quality, png_mode, png_optimization)?;
+            let img = decode_input(input)?;
+            let target = target_ssim.filter(|_| supports_auto_quality(fmt, png_mode));
+            let out = encode_static(path, quality, target, &img, |q| {
+                encode_to_format(&img, fmt, q, png_mode, png_optimization)
+            })?;
             tmp.write_all(&out).map_err(|e| format!("write: {}", e))?;
         }
         (DetectedKind::Static(_), Mode::webp) => {
-            let img = ImageReader::new(Cursor::new(bytes))
-                .with_guessed_format()
-                .map_err(|e| format!("reader: {}", e))?
-                .decode()
-                .map_err(|e| format!("decode: {}", e))?;
-            let out = encode_webp_static(&img, quality)?;
+            let img = decode_input(input)?;
+            let out = encode_static(path, quality, target_ssim, &img, |q| {
+                encode_webp_static(&img, q)
+            })?;
             tmp.write_all(&out).map_err(|e| format!("write: {}", e))?;
         }
         (DetectedKind::Animated(_), Mode::original_format) => {
             // 透传
-            tmp.write_all(&bytes).map_err(|e| format!("write: {}", e))?;
+            tmp.write_all(input.loaded()?)
+                .map_err(|e| format!("write: {}", e))?;
         }
         (DetectedKind::Animated(fmt), Mode::webp) => {
+            let bytes = input.loaded()?;
             // Android 平台禁用动图转换
             if is_android {
                 info!("Android platform: animated image conversion disabled, passthrough original");
-                tmp.write_all(&bytes).map_err(|e| format!("write: {}", e))?;
+                tmp.write_all(bytes).map_err(|e| format!("write: {}", e))?;
             } else {
                 match fmt {
                     ImageFormat::Gif => {
                         // 转为 WebP：调用 convert_animated_to_webp
-                        let out = convert_gif_to_webp(&app, &bytes, quality)?;
+                        let out = convert_gif_to_webp(&app, bytes, quality)?;
                         tmp.write_all(&out).map_err(|e| format!("write: {}", e))?;
                     }
                     _ => {
                         // 其他动画格式（如动画 WebP）：透传
-                        tmp.write_all(&bytes).map_err(|e| format!("write: {}", e))?;
+                        tmp.write_all(bytes).map_err(|e| format!("write: {}", e))?;
                     }
                 }
             }
@@ -518,6 +692,89 @@ fn process_one(
     Ok(path_buf)
 }
 
+/// 一次压缩使用的参数（quality 与 target_ssim 已限制范围）
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CompressParams {
+    pub quality: u8,
+    pub mode: Mode,
+    pub png_mode: PngCompressionMode,
+    pub png_optimization: PngOptimizationLevel,
+    pub target_ssim: Option<f64>,
+    pub heic_sequence: HeicSequenceMode,
+}
+
+impl CompressParams {
+    pub(crate) fn new(
+        quality: u8,
+        mode: Mode,
+        png_mode: PngCompressionMode,
+        png_optimization: PngOptimizationLevel,
+        target_ssim: Option<f64>,
+        heic_sequence: HeicSequenceMode,
+    ) -> Self {
+        Self {
+            // 统一限制质量范围到 0..=100
+            quality: quality.min(100),
+            mode,
+            png_mode,
+            png_optimization,
+            target_ssim: target_ssim.map(crate::settings::clamp_target_ssim),
+            heic_sequence,
+        }
+    }
+}
+
+/// 压缩单个文件，失败时记录日志并回退为原图路径
+fn compress_file(
+    app: &tauri::AppHandle,
+    index: usize,
+    path: String,
+    params: &CompressParams,
+) -> String {
+    match process_one(app, &path, params) {
+        Ok(pb) => pb.to_string_lossy().to_string(),
+        Err(e) => {
+            error!(
+                "compress failed, fallback to original path: index={}, path={}, error={}",
+                index, path, e
+            );
+            path
+        }
+    }
+}
+
+/// 流水线压缩（阻塞，需在 blocking 线程中调用）：从 `files` 逐个取（索引, 路径），
+/// 在专用线程池中并行压缩，每个文件完成后立即发送（索引, 输出路径）。
+/// `output` 为有界通道，接收端处理不过来时压缩线程等待；接收端关闭后不再处理剩余文件。
+/// 使用专用线程池，避免等待发送时占满全局线程池、阻塞界面的压缩命令
+pub(crate) fn compress_each(
+    app: &tauri::AppHandle,
+    mut files: impl Iterator<Item = (usize, String)> + Send,
+    params: CompressParams,
+    output: tokio::sync::mpsc::Sender<(usize, String)>,
+) {
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(2);
+    let send = |(index, path): (usize, String)| -> Result<(), ()> {
+        let out = compress_file(app, index, path, &params);
+        output.blocking_send((index, out)).map_err(|_| ())
+    };
+    let result = match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+        Ok(pool) => pool.install(|| files.par_bridge().try_for_each(send)),
+        Err(err) => {
+            error!(
+                "build compress thread pool failed, compress serially: {}",
+                err
+            );
+            files.try_for_each(send)
+        }
+    };
+    if result.is_err() {
+        info!("compress pipeline receiver closed, stop compressing");
+    }
+}
+
 #[tauri::command]
 pub async fn compress_images(
     app: tauri::AppHandle,
@@ -526,33 +783,29 @@ pub async fn compress_images(
     mode: Mode,
     png_mode: PngCompressionMode,
     png_optimization: PngOptimizationLevel,
+    target_ssim: Option<f64>,
 ) -> Result<Vec<String>, String> {
     // 将 CPU 密集工作委托给 tokio blocking 线程
     tokio::task::spawn_blocking(move || {
-        // 统一限制质量范围到 0..=100
-        let q = quality.min(100);
+        let heic_sequence = crate::settings::current_settings(&app).heic_sequence_mode;
+        let params = CompressParams::new(
+            quality,
+            mode,
+            png_mode,
+            png_optimization,
+            target_ssim,
+            heic_sequence,
+        );
         let count = paths.len();
         info!(
-            "compress_images start: count={}, quality={}, mode={:?}, png_mode={:?}, png_optimization={:?}",
-            count, q, mode, png_mode, png_optimization
+            "compress_images start: count={}, params={:?}",
+            count, params
         );
         // 并行处理但保持顺序：记录原始索引 -> 并行处理；对每项错误记录日志并回退为原图路径
         let indexed: Vec<(usize, String)> = paths.into_iter().enumerate().collect();
         let mut v: Vec<(usize, String)> = indexed
             .into_par_iter()
-            .map(|(i, p)| {
-                match process_one(&app, &p, q, mode, png_mode, png_optimization) {
-                    Ok(pb) => (i, pb.to_string_lossy().to_string()),
-                    Err(e) => {
-                        error!(
-                            "compress failed, fallback to original path: index={}, path={}, error={}",
-                            i, p, e
-                        );
-                        // 回退：返回原图路径，保证顺序与长度不变
-                        (i, p)
-                    }
-                }
-            })
+            .map(|(i, p)| (i, compress_file(&app, i, p, &params)))
             .collect();
 
         v.sort_by_key(|(i, _)| *i);
@@ -564,6 +817,22 @@ pub async fn compress_images(
     .map_err(|e| format!("spawn_blocking error: {}", e))?
 }
 
+/// 设置了文件名模板时按模板重命名目标文件，目录保持不变
+fn templated_dest(src: &str, dst: String) -> String {
+    if !crate::filename_template::is_enabled() {
+        return dst;
+    }
+    let dst_path = Path::new(&dst);
+    let (Some(name), Ok(bytes)) = (dst_path.file_name(), std::fs::read(src)) else {
+        return dst;
+    };
+    let renamed = crate::filename_template::apply(&name.to_string_lossy(), &bytes);
+    dst_path
+        .with_file_name(renamed)
+        .to_string_lossy()
+        .into_owned()
+}
+
 /// 将源文件复制到目标路径（逐一对应）。
 /// 注意：此命令在后端执行文件系统复制，避免前端 FS 插件对系统临时目录的访问限制。
 #[tauri::command]
@@ -578,6 +847,7 @@ pub async fn save_files(sources: Vec<String>, dests: Vec<String>) -> Result<usiz
 
     let mut ok = 0usize;
     for (src, dst) in sources.into_iter().zip(dests.into_iter()) {
+        let dst = templated_dest(&src, dst);
         match std::fs::copy(&src, &dst) {
             Ok(_) => {
                 ok += 1;
@@ -591,6 +861,48 @@ pub async fn save_files(sources: Vec<String>, dests: Vec<String>) -> Result<usiz
     Ok(ok)
 }
 
+/// 将处理后的文件保存到设置中的默认保存目录（桌面端对应 Android 的 `save_to_download_dir`），
+/// 文件名套用文件名模板，同名时追加 `-N` 后缀。`names` 可为每个文件指定原始文件名，返回保存后的路径
+#[tauri::command]
+pub async fn save_processed_to_default(
+    app: tauri::AppHandle,
+    paths: Vec<String>,
+    names: Option<Vec<String>>,
+) -> Result<Vec<String>, String> {
+    let dir = crate::settings::current_settings(&app).default_save_dir;
+    let dir = dir.trim();
+    if dir.is_empty() {
+        return Err("未设置默认保存目录".to_string());
+    }
+    let dir = PathBuf::from(dir);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("create_dir_all {}: {}", dir.display(), e))?;
+
+    let names = names.unwrap_or_default();
+    let mut saved = Vec::with_capacity(paths.len());
+    for (index, src) in paths.iter().enumerate() {
+        let original = match names.get(index) {
+            Some(name) if !name.trim().is_empty() => name.trim().to_string(),
+            _ => Path::new(src)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .ok_or_else(|| format!("invalid source path: {}", src))?,
+        };
+        let bytes = std::fs::read(src).map_err(|e| format!("read {}: {}", src, e))?;
+        let file_name = crate::filename_template::apply(&original, &bytes);
+        let file_name = crate::local_folder::unique_relative_path(&dir, &file_name)?;
+        let dest = dir.join(&file_name);
+        std::fs::write(&dest, &bytes).map_err(|e| format!("write {}: {}", dest.display(), e))?;
+        info!(
+            "save_processed_to_default: copied from {} to {}",
+            src,
+            dest.display()
+        );
+        saved.push(dest.to_string_lossy().into_owned());
+    }
+    Ok(saved)
+}
+
 fn process_data(
     app: &tauri::AppHandle,
     data: Vec<u8>,
@@ -802,3 +1114,65 @@ pub fn get_file_sizes(paths: Vec<String>) -> Result<Vec<u64>, String> {
     info!("get_file_sizes done: count={}", sizes.len());
     Ok(sizes)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_input() {
+        let dir = tempfile::tempdir_in(ensure_app_temp_dir().unwrap()).unwrap();
+        let small = dir.path().join("small.bin");
+        let large = dir.path().join("large.bin");
+        std::fs::write(&small, b"small").unwrap();
+        let content: Vec<u8> = (0..MMAP_THRESHOLD as usize).map(|i| i as u8).collect();
+        std::fs::write(&large, &content).unwrap();
+
+        let bytes = read_input(small.to_str().unwrap()).unwrap();
+        assert!(matches!(bytes, InputBytes::Owned(_)));
+        assert_eq!(&*bytes, b"small");
+        let bytes = read_input(large.to_str().unwrap()).unwrap();
+        assert!(matches!(bytes, InputBytes::Mapped(_)));
+        assert_eq!(&*bytes, content.as_slice());
+
+        // 应用临时目录以外的大文件不映射
+        let outside = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(outside.path(), &content).unwrap();
+        let bytes = read_input(outside.path().to_str().unwrap()).unwrap();
+        assert!(matches!(bytes, InputBytes::Owned(_)));
+        assert_eq!(&*bytes, content.as_slice());
+    }
+
+    #[test]
+    fn test_open_input_streams_large_user_files() {
+        // 伪随机噪声无法压缩，使 PNG 不小于 MMAP_THRESHOLD
+        let mut state = 0x2545_f491_u32;
+        let img = image::RgbImage::from_fn(1400, 1400, |_, _| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            image::Rgb([state as u8, (state >> 8) as u8, (state >> 16) as u8])
+        });
+        let large = tempfile::Builder::new().suffix(".png").tempfile().unwrap();
+        img.save(large.path()).unwrap();
+        assert!(std::fs::metadata(large.path()).unwrap().len() >= MMAP_THRESHOLD);
+        let large_path = large.path().to_str().unwrap();
+
+        let input = open_input(large_path).unwrap();
+        assert!(matches!(
+            input,
+            Input::Stream {
+                format: ImageFormat::Png,
+                ..
+            }
+        ));
+        assert!(input.loaded().is_err());
+        assert_eq!(decode_input(input).unwrap().to_rgb8(), img);
+
+        // 小文件与非图片内容整体读入
+        let small = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(small.path(), b"small").unwrap();
+        let input = open_input(small.path().to_str().unwrap()).unwrap();
+        assert_eq!(input.loaded().unwrap(), b"small");
+    }
+}
@@ -0,0 +1,337 @@
+/*
+压缩质量的客观指标（调整画质时参考）：
+1) `compare_quality(original_path, compressed_path)` 解码两张图片（动图取首帧），
+   带透明通道的先合成到白色背景，尺寸不同时把压缩图缩放到原图尺寸再比较；
+2) SSIM 在亮度（BT.601）上按 `WINDOW`×`WINDOW` 窗口、步长 `STEP` 计算后取平均，1 表示完全一致；
+3) PSNR 按 RGB 三通道的均方误差计算（dB），两图完全相同时为 null（无穷大）；
+4) 同时返回两者的文件大小、差值与比例；
+5) 自动画质（`auto_encode`）在 `AUTO_QUALITY_MIN`-`AUTO_QUALITY_MAX` 间二分查找 SSIM 不低于目标的最低画质，
+   供 process 压缩静态图时使用。
+*/
+
+use std::path::Path;
+
+use image::{DynamicImage, Rgb, RgbImage, imageops::FilterType};
+use log::info;
+use rayon::prelude::*;
+use serde::Serialize;
+
+/// 自动画质搜索的画质区间
+pub(crate) const AUTO_QUALITY_MIN: u8 = 30;
+pub(crate) const AUTO_QUALITY_MAX: u8 = 95;
+/// 自动画质 SSIM 目标的取值范围
+pub(crate) const MIN_TARGET_SSIM: f64 = 0.9;
+pub(crate) const MAX_TARGET_SSIM: f64 = 0.999;
+
+const WINDOW: u32 = 8;
+const STEP: u32 = 4;
+/// SSIM 稳定常数：(0.01 × 255)² 与 (0.03 × 255)²
+const C1: f64 = 6.5025;
+const C2: f64 = 58.5225;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QualityReport {
+    pub ssim: f64,
+    /// 完全相同时为 None
+    pub psnr: Option<f64>,
+    pub original_size: u64,
+    pub compressed_size: u64,
+    /// 压缩后减少为负数
+    pub size_delta: i64,
+    /// 压缩后 / 原图
+    pub size_ratio: f64,
+    pub width: u32,
+    pub height: u32,
+    /// 压缩图尺寸与原图不同，已缩放后比较
+    pub resized: bool,
+}
+
+/// 合成到白色背景的 RGB 图
+pub(crate) fn flatten(image: &DynamicImage) -> RgbImage {
+    let rgba = image.to_rgba8();
+    let mut rgb = RgbImage::new(rgba.width(), rgba.height());
+    for (x, y, pixel) in rgba.enumerate_pixels() {
+        let alpha = pixel[3] as f32 / 255.0;
+        let blend = |channel: u8| (channel as f32 * alpha + 255.0 * (1.0 - alpha)).round() as u8;
+        rgb.put_pixel(
+            x,
+            y,
+            Rgb([blend(pixel[0]), blend(pixel[1]), blend(pixel[2])]),
+        );
+    }
+    rgb
+}
+
+fn luma(image: &RgbImage) -> Vec<f64> {
+    image
+        .pixels()
+        .map(|p| 0.299 * p[0] as f64 + 0.587 * p[1] as f64 + 0.114 * p[2] as f64)
+        .collect()
+}
+
+/// 窗口起点，覆盖到图像边缘
+fn window_starts(len: u32) -> Vec<u32> {
+    if len <= WINDOW {
+        return vec![0];
+    }
+    let mut starts: Vec<u32> = (0..=len - WINDOW).step_by(STEP as usize).collect();
+    if starts.last() != Some(&(len - WINDOW)) {
+        starts.push(len - WINDOW);
+    }
+    starts
+}
+
+fn window_ssim(a: &[f64], b: &[f64], width: u32, height: u32, x0: u32, y0: u32) -> f64 {
+    let (w, h) = (WINDOW.min(width), WINDOW.min(height));
+    let n = (w * h) as f64;
+    let (mut sum_a, mut sum_b, mut sum_aa, mut sum_bb, mut sum_ab) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for y in y0..y0 + h {
+        let row = (y * width) as usize;
+        for x in x0..x0 + w {
+            let (pa, pb) = (a[row + x as usize], b[row + x as usize]);
+            sum_a += pa;
+            sum_b += pb;
+            sum_aa += pa * pa;
+            sum_bb += pb * pb;
+            sum_ab += pa * pb;
+        }
+    }
+    let (mean_a, mean_b) = (sum_a / n, sum_b / n);
+    let var_a = sum_aa / n - mean_a * mean_a;
+    let var_b = sum_bb / n - mean_b * mean_b;
+    let cov = sum_ab / n - mean_a * mean_b;
+    ((2.0 * mean_a * mean_b + C1) * (2.0 * cov + C2))
+        / ((mean_a * mean_a + mean_b * mean_b + C1) * (var_a + var_b + C2))
+}
+
+pub(crate) fn ssim(a: &RgbImage, b: &RgbImage) -> f64 {
+    let (width, height) = a.dimensions();
+    let (la, lb) = (luma(a), luma(b));
+    let xs = window_starts(width);
+    let ys = window_starts(height);
+    let (sum, count) = ys
+        .par_iter()
+        .map(|&y| {
+            xs.iter().fold((0.0, 0usize), |(sum, count), &x| {
+                (sum + window_ssim(&la, &lb, width, height, x, y), count + 1)
+            })
+        })
+        .reduce(|| (0.0, 0), |(s1, c1), (s2, c2)| (s1 + s2, c1 + c2));
+    if count == 0 { 1.0 } else { sum / count as f64 }
+}
+
+fn psnr(a: &RgbImage, b: &RgbImage) -> Option<f64> {
+    let squared: f64 = a
+        .as_raw()
+        .par_iter()
+        .zip(b.as_raw().par_iter())
+        .map(|(&pa, &pb)| {
+            let diff = pa as f64 - pb as f64;
+            diff * diff
+        })
+        .sum();
+    let mse = squared / a.as_raw().len().max(1) as f64;
+    (mse > 0.0).then(|| 10.0 * (255.0 * 255.0 / mse).log10())
+}
+
+/// 在 [min, max] 中二分查找得分不低于 target 的最低画质（画质越高得分越高），
+/// 区间内都达不到时使用 max 的结果
+fn search<T>(
+    min: u8,
+    max: u8,
+    target: f64,
+    mut attempt: impl FnMut(u8) -> Result<(f64, T), String>,
+) -> Result<(u8, T), String> {
+    let (mut lo, mut hi) = (min, max);
+    let mut best = None;
+    let mut at_max = None;
+    while lo <= hi {
+        let mid = lo + (hi - lo) / 2;
+        let (score, value) = attempt(mid)?;
+        if score >= target {
+            best = Some((mid, value));
+            if mid == min {
+                break;
+            }
+            hi = mid - 1;
+        } else {
+            if mid == max {
+                at_max = Some((mid, value));
+            }
+            lo = mid + 1;
+        }
+    }
+    match best.or(at_max) {
+        Some(found) => Ok(found),
+        None => attempt(max).map(|(_, value)| (max, value)),
+    }
+}
+
+/// 自动画质：返回 SSIM 不低于 target 的最低画质及其编码结果
+pub(crate) fn auto_encode(
+    img: &DynamicImage,
+    target: f64,
+    encode: impl Fn(u8) -> Result<Vec<u8>, String>,
+) -> Result<(u8, Vec<u8>), String> {
+    let reference = flatten(img);
+    let (width, height) = reference.dimensions();
+    search(AUTO_QUALITY_MIN, AUTO_QUALITY_MAX, target, |quality| {
+        let encoded = encode(quality)?;
+        let decoded =
+            image::load_from_memory(&encoded).map_err(|e| format!("decode encoded: {e}"))?;
+        let mut candidate = flatten(&decoded);
+        if candidate.dimensions() != (width, height) {
+            candidate =
+                crate::fast_resize::resize_rgb(&candidate, width, height, FilterType::Triangle);
+        }
+        Ok((ssim(&reference, &candidate), encoded))
+    })
+}
+
+pub(crate) fn decode(path: &str) -> Result<DynamicImage, String> {
+    image::ImageReader::open(path)
+        .map_err(|e| format!("打开 {path} 失败: {e}"))?
+        .with_guessed_format()
+        .map_err(|e| format!("识别 {path} 格式失败: {e}"))?
+        .decode()
+        .map_err(|e| format!("解码 {path} 失败: {e}"))
+}
+
+fn file_size(path: &str) -> Result<u64, String> {
+    std::fs::metadata(Path::new(path))
+        .map(|meta| meta.len())
+        .map_err(|e| format!("读取 {path} 失败: {e}"))
+}
+
+fn compare(original_path: &str, compressed_path: &str) -> Result<QualityReport, String> {
+    let original = flatten(&decode(original_path)?);
+    let mut compressed = flatten(&decode(compressed_path)?);
+    let (width, height) = original.dimensions();
+    let resized = compressed.dimensions() != (width, height);
+    if resized {
+        compressed =
+            crate::fast_resize::resize_rgb(&compressed, width, height, FilterType::Triangle);
+    }
+    let original_size = file_size(original_path)?;
+    let compressed_size = file_size(compressed_path)?;
+    Ok(QualityReport {
+        ssim: ssim(&original, &compressed),
+        psnr: psnr(&original, &compressed),
+        original_size,
+        compressed_size,
+        size_delta: compressed_size as i64 - original_size as i64,
+        size_ratio: if original_size == 0 {
+            1.0
+        } else {
+            compressed_size as f64 / original_size as f64
+        },
+        width,
+        height,
+        resized,
+    })
+}
+
+/// 计算压缩前后的 SSIM、PSNR 与文件大小变化
+#[tauri::command]
+pub async fn compare_quality(
+    original_path: String,
+    compressed_path: String,
+) -> Result<QualityReport, String> {
+    let report =
+        tauri::async_runtime::spawn_blocking(move || compare(&original_path, &compressed_path))
+            .await
+            .map_err(|e| format!("compare task failed: {e}"))??;
+    info!(
+        "compare quality: ssim={:.4}, psnr={:?}, ratio={:.3}",
+        report.ssim, report.psnr, report.size_ratio
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(width: u32, height: u32) -> RgbImage {
+        RgbImage::from_fn(width, height, |x, y| {
+            Rgb([
+                (x * 7 % 256) as u8,
+                (y * 5 % 256) as u8,
+                ((x + y) % 256) as u8,
+            ])
+        })
+    }
+
+    #[test]
+    fn test_identical() {
+        let image = gradient(37, 21);
+        assert!((ssim(&image, &image) - 1.0).abs() < 1e-9);
+        assert_eq!(psnr(&image, &image), None);
+    }
+
+    #[test]
+    fn test_psnr_uniform_offset() {
+        let a = RgbImage::from_pixel(16, 16, Rgb([100, 100, 100]));
+        let b = RgbImage::from_pixel(16, 16, Rgb([110, 110, 110]));
+        // MSE = 100 → 10 × log10(65025 / 100)
+        assert!((psnr(&a, &b).unwrap() - 28.1308).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_ssim_degrades_with_noise() {
+        let a = gradient(64, 64);
+        let mut b = a.clone();
+        for (i, pixel) in b.pixels_mut().enumerate() {
+            let noise = if i % 2 == 0 { 40 } else { 0 };
+            pixel[0] = pixel[0].saturating_add(noise);
+            pixel[1] = pixel[1].saturating_sub(noise);
+        }
+        let score = ssim(&a, &b);
+        assert!(score < 0.95 && score > 0.0, "ssim = {score}");
+    }
+
+    #[test]
+    fn test_search() {
+        let mut attempts = Vec::new();
+        let (quality, score) = search(30, 95, 0.9, |q| {
+            attempts.push(q);
+            let score = q as f64 / 100.0;
+            Ok((score, score))
+        })
+        .unwrap();
+        assert_eq!(quality, 90);
+        assert_eq!(score, 0.9);
+        assert!(attempts.len() <= 7);
+
+        // 最高画质也达不到时使用最高画质，不重复编码
+        let mut count = 0;
+        let (quality, _) = search(30, 95, 2.0, |q| {
+            count += 1;
+            Ok((q as f64 / 100.0, ()))
+        })
+        .unwrap();
+        assert_eq!(quality, 95);
+        assert_eq!(count, 7);
+        assert_eq!(search(30, 95, 0.0, |q| Ok((1.0, q))).unwrap(), (30, 30));
+    }
+
+    #[test]
+    fn test_auto_encode_jpeg() {
+        let image = DynamicImage::ImageRgb8(gradient(96, 64));
+        let (quality, encoded) =
+            auto_encode(&image, 0.97, |q| crate::process::encode_jpeg(&image, q)).unwrap();
+        assert!((AUTO_QUALITY_MIN..=AUTO_QUALITY_MAX).contains(&quality));
+        let decoded = flatten(&image::load_from_memory(&encoded).unwrap());
+        if quality < AUTO_QUALITY_MAX {
+            assert!(ssim(&flatten(&image), &decoded) >= 0.97);
+        }
+    }
+
+    #[test]
+    fn test_window_starts() {
+        assert_eq!(window_starts(5), vec![0]);
+        assert_eq!(window_starts(8), vec![0]);
+        assert_eq!(window_starts(14), vec![0, 4, 6]);
+    }
+}
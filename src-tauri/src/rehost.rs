@@ -0,0 +1,196 @@
+/*
+从链接转存（替换旧文章中的外链图片）：
+1) `rehost_url(url, host, options)` 用缩略图模块的下载器（重试、50MB 上限、魔数校验）把远程图片下载到临时目录；
+2) 文件名取链接路径的最后一段，扩展名与内容不符时按内容修正，图库中的文件名与上传文件名均沿用该名称；
+3) 之后与本地文件一样经过重复检查、隐私策略、压缩、上传与写入图库（host_upload），临时文件随后删除。
+*/
+
+use std::path::{Path, PathBuf};
+
+use log::info;
+use serde::Serialize;
+use tauri::AppHandle;
+use url::Url;
+
+use crate::batch_upload::BatchUploadOptions;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RehostResult {
+    pub source_url: String,
+    pub file_name: String,
+    pub url: String,
+    pub delete_id: Option<String>,
+    pub gallery_id: Option<i64>,
+    /// 内容已上传过，沿用图库中的链接
+    pub duplicate: bool,
+}
+
+fn parse_url(input: &str) -> Result<Url, String> {
+    let url = Url::parse(input.trim()).map_err(|err| format!("无效的链接 {input}: {err}"))?;
+    match url.scheme() {
+        "http" | "https" => Ok(url),
+        scheme => Err(format!("不支持的链接协议: {scheme}")),
+    }
+}
+
+pub(crate) fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let hex = bytes
+            .get(pos + 1..pos + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) if bytes[pos] == b'%' => {
+                out.push(byte);
+                pos += 3;
+            }
+            _ => {
+                out.push(bytes[pos]);
+                pos += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// 扩展名与下载内容的实际格式不符（或缺失）时替换为对应格式的扩展名
+pub(crate) fn fix_extension(name: &str, bytes: &[u8]) -> String {
+    let Some(format) = image::guess_format(bytes).ok() else {
+        return name.to_string();
+    };
+    let extensions = format.extensions_str();
+    let path = Path::new(name);
+    let matches = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.contains(&ext.to_ascii_lowercase().as_str()));
+    if matches {
+        return name.to_string();
+    }
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| name.to_string());
+    match extensions.first() {
+        Some(ext) => format!("{stem}.{ext}"),
+        None => name.to_string(),
+    }
+}
+
+/// 按链接路径的最后一段与下载内容确定文件名
+fn file_name_for(url: &Url, bytes: &[u8]) -> String {
+    let segment = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .map(percent_decode)
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| "image".to_string());
+    fix_extension(&crate::internal_hosts::sanitize_file_name(&segment), bytes)
+}
+
+/// 在应用临时目录下创建带前缀的临时目录，离开作用域时删除
+pub(crate) fn temp_dir(prefix: &str) -> Result<tempfile::TempDir, String> {
+    let base = crate::portable::temp_dir();
+    std::fs::create_dir_all(&base)
+        .map_err(|e| format!("create app temp dir {}: {}", base.display(), e))?;
+    tempfile::Builder::new()
+        .prefix(prefix)
+        .tempdir_in(&base)
+        .map_err(|e| format!("tempdir_in: {}", e))
+}
+
+/// 把远程图片下载到 dir 中，返回文件路径与文件名
+pub(crate) async fn download_to(dir: &Path, source: &Url) -> Result<(PathBuf, String), String> {
+    let download = dir.join("download");
+    crate::thumbnail::download_image(source.as_str(), &download).await?;
+
+    let bytes = std::fs::read(&download).map_err(|e| format!("读取下载文件失败: {e}"))?;
+    let file_name = file_name_for(source, &bytes);
+    let path = dir.join(&file_name);
+    std::fs::rename(&download, &path).map_err(|e| format!("重命名下载文件失败: {e}"))?;
+    info!(
+        "downloaded {}: file={}, bytes={}",
+        source,
+        file_name,
+        bytes.len()
+    );
+    Ok((path, file_name))
+}
+
+/// 下载远程图片并上传到指定图床，写入图库（可在 options 中关闭）
+#[tauri::command]
+pub async fn rehost_url(
+    app: AppHandle,
+    url: String,
+    host: String,
+    options: Option<BatchUploadOptions>,
+) -> Result<RehostResult, String> {
+    let host = host.trim().to_string();
+    if host.is_empty() {
+        return Err("未指定图床".to_string());
+    }
+    let source = parse_url(&url)?;
+
+    let dir = temp_dir("yana_rehost_")?;
+    let (path, file_name) = download_to(dir.path(), &source).await?;
+    info!(
+        "rehost url: url={}, host={}, file={}",
+        source, host, file_name
+    );
+
+    let options = options.unwrap_or_default().pipeline();
+    let outcome = crate::host_upload::compress_and_upload(
+        &app,
+        &host,
+        vec![path.to_string_lossy().into_owned()],
+        &options,
+    )
+    .await
+    .pop()
+    .ok_or_else(|| "上传未返回结果".to_string())?;
+    let result = outcome.result?;
+    Ok(RehostResult {
+        source_url: source.to_string(),
+        file_name,
+        url: result.url,
+        delete_id: Some(result.delete_id).filter(|id| !id.is_empty()),
+        gallery_id: outcome.gallery_id,
+        duplicate: outcome.duplicate,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    #[test]
+    fn test_parse_url() {
+        assert!(parse_url(" https://example.com/a.png ").is_ok());
+        assert!(parse_url("file:///etc/passwd").is_err());
+        assert!(parse_url("not a url").is_err());
+    }
+
+    #[test]
+    fn test_file_name_for() {
+        let name = |url: &str, bytes: &[u8]| file_name_for(&Url::parse(url).unwrap(), bytes);
+        assert_eq!(name("https://example.com/img/cat.png?x=1", PNG), "cat.png");
+        assert_eq!(name("https://example.com/my%20cat.PNG", PNG), "my_cat.PNG");
+        // 扩展名与内容不符或缺失时按内容修正
+        assert_eq!(name("https://example.com/cat.jpg", PNG), "cat.png");
+        assert_eq!(name("https://example.com/image", PNG), "image.png");
+        assert_eq!(name("https://example.com/", PNG), "image.png");
+        assert_eq!(name("https://example.com/a.svg", b"<svg/>"), "a.svg");
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("a%20b%2"), "a b%2");
+        assert_eq!(percent_decode("%E7%8C%AB.png"), "猫.png");
+    }
+}
@@ -1,13 +1,49 @@
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::time::Duration;
 
+use bytes::Bytes;
 use chrono::Utc;
+use futures::StreamExt;
+use log::{info, warn};
 use mime_guess::MimeGuess;
+use reqwest::{RequestBuilder, Response, StatusCode};
 use rusty_s3::{Bucket, Credentials, S3Action};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+use time::OffsetDateTime;
+use tokio::io::AsyncWriteExt;
 use uuid::Uuid;
 
+use crate::cdn_purge::CdnPurgeConfig;
+use crate::http_client::{HttpClientOptions, apply_http_options};
+use crate::pending_uploads::{PendingKind, S3UploadJob, run_tracked};
+use crate::throttle::Throttle;
+use crate::tls::{ClientCertificate, apply_client_certificate};
+
+/// S3 对象标签上限：最多 10 个，键 128 字符，值 256 字符
+const MAX_OBJECT_TAGS: usize = 10;
+const MAX_TAG_KEY_LEN: usize = 128;
+const MAX_TAG_VALUE_LEN: usize = 256;
+
+const DEFAULT_DIR_UPLOAD_CONCURRENCY: usize = 4;
+const MAX_DIR_UPLOAD_CONCURRENCY: usize = 16;
+const DIR_UPLOAD_PROGRESS_EVENT: &str = "s3://upload-dir-progress";
+const DIR_UPLOAD_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "webp", "bmp", "tif", "tiff", "avif", "svg", "ico",
+];
+
+const DEFAULT_PRESIGN_EXPIRY_SECS: u64 = 900;
+/// SigV4 预签名 URL 的最长有效期为 7 天
+const MAX_PRESIGN_EXPIRY_SECS: u64 = 7 * 24 * 3600;
+/// 本地时钟与服务器相差超过该值时视为时钟偏差并重新签名
+const MAX_TOLERATED_SKEW_SECS: i64 = 30;
+
+/// 已探测到的服务器时间与本地时间之差（秒），签名时用于校正本地时钟
+static CLOCK_SKEW_SECS: AtomicI64 = AtomicI64::new(0);
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct S3UploadResult {
@@ -23,6 +59,144 @@ pub struct S3DeleteResult {
     pub message: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3HeadResult {
+    pub exists: bool,
+    pub size: Option<u64>,
+    pub content_type: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3DownloadResult {
+    pub path: String,
+    pub size: u64,
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3DirUploadItem {
+    /// 相对于上传目录的路径（使用 `/` 分隔）
+    pub relative_path: String,
+    pub key: String,
+    pub result: Option<S3UploadResult>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct S3DirUploadProgress {
+    total: usize,
+    completed: usize,
+    relative_path: String,
+    success: bool,
+}
+
+/// 前端传入的凭据参数：可直接给出密钥，也可只给出钥匙串中的 credential id
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3CredentialInput {
+    #[serde(default)]
+    pub access_key_id: Option<String>,
+    #[serde(default)]
+    pub secret_access_key: Option<String>,
+    #[serde(default)]
+    pub session_token: Option<String>,
+    #[serde(default)]
+    pub credential_id: Option<String>,
+    #[serde(default)]
+    pub presign_expiry_secs: Option<u64>,
+    /// 端点位于 mTLS 反向代理之后时使用的客户端证书
+    #[serde(default)]
+    pub client_certificate: Option<ClientCertificate>,
+    /// 重定向策略与 DNS 覆盖，上传、删除、HEAD 与下载共用
+    #[serde(default)]
+    pub http_options: Option<HttpClientOptions>,
+}
+
+/// 目录上传等新命令使用的 S3 上传配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3UploadOptions {
+    pub bucket: String,
+    pub region: String,
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub force_path_style: Option<bool>,
+    #[serde(default)]
+    pub object_prefix: Option<String>,
+    #[serde(default)]
+    pub acl: Option<String>,
+    #[serde(default)]
+    pub public_base_url: Option<String>,
+    /// 完整的公开地址模板（如 `https://cdn.example.com/{key}?format=auto`），优先于 public_base_url
+    #[serde(default)]
+    pub public_url_template: Option<String>,
+    #[serde(default)]
+    pub tags: Option<BTreeMap<String, String>>,
+    /// 目录上传时的并发数，默认 4
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    /// 该端点的上传限速（KB/s），与全局限速同时生效
+    #[serde(default)]
+    pub rate_limit_kbps: Option<u32>,
+    #[serde(flatten)]
+    pub credentials: S3CredentialInput,
+    /// 覆盖同名对象后清除 CDN 缓存
+    #[serde(flatten)]
+    pub cdn: CdnPurgeConfig,
+}
+
+impl S3UploadOptions {
+    fn prepare(self) -> Result<PreparedS3Upload, String> {
+        let S3CredentialInput {
+            access_key_id,
+            secret_access_key,
+            session_token,
+            credential_id,
+            presign_expiry_secs,
+            client_certificate,
+            http_options,
+        } = self.credentials;
+        let secrets = resolve_credentials(
+            access_key_id,
+            secret_access_key,
+            session_token,
+            credential_id,
+        )?;
+
+        let options = S3ConfigOptions {
+            region: self.region,
+            // default to path style when custom endpoint (e.g., Cloudflare R2) is used
+            force_path_style: self.force_path_style.unwrap_or(self.endpoint.is_some()),
+            endpoint: self.endpoint,
+            access_key_id: secrets.access_key_id,
+            secret_access_key: secrets.secret_access_key,
+            session_token: secrets.session_token,
+        };
+
+        Ok(PreparedS3Upload {
+            options,
+            bucket: self.bucket,
+            acl_header: map_acl(self.acl.as_deref())?,
+            tagging_header: build_tagging_header(self.tags.as_ref())?,
+            expires_in: presign_expiry(presign_expiry_secs),
+            public_base_url: self.public_base_url,
+            public_url_template: validate_public_url_template(
+                self.public_url_template.as_deref(),
+            )?,
+            client_certificate,
+            rate_limit_kbps: self.rate_limit_kbps,
+            http_options,
+            cdn: self.cdn,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct S3DeleteMarker {
@@ -33,6 +207,17 @@ struct S3DeleteMarker {
     force_path_style: bool,
 }
 
+/// 钥匙串中保存的 S3 凭据（以 JSON 形式存放在 credential id 下）
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredS3Credentials {
+    access_key_id: String,
+    secret_access_key: String,
+    /// STS / SSO 临时凭据附带的会话令牌
+    #[serde(default)]
+    session_token: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 struct S3ConfigOptions {
     region: String,
@@ -40,6 +225,22 @@ struct S3ConfigOptions {
     force_path_style: bool,
     access_key_id: String,
     secret_access_key: String,
+    session_token: Option<String>,
+}
+
+/// 规范化自定义 endpoint。
+/// 用户常把存储桶名一并粘贴进 endpoint，此时只保留 scheme 和 host；
+/// 其余路径（如 Supabase 的 `/storage/v1/s3`）是 S3 API 的一部分，需保留并补齐结尾斜杠，
+/// 否则路径风格下拼接存储桶时会覆盖最后一段。
+fn normalize_endpoint(custom_endpoint: &str, bucket_name: &str) -> String {
+    let mut parts = custom_endpoint.trim().splitn(4, '/');
+    let base = parts.by_ref().take(3).collect::<Vec<_>>().join("/");
+    let path = parts.next().unwrap_or("").trim_matches('/');
+    if path.is_empty() || path == bucket_name {
+        base
+    } else {
+        format!("{}/{}/", base, path)
+    }
 }
 
 fn build_bucket_and_credentials(
@@ -48,13 +249,7 @@ fn build_bucket_and_credentials(
 ) -> Result<(Bucket, Credentials), String> {
     // 构建 endpoint
     let endpoint = if let Some(custom_endpoint) = &options.endpoint {
-        // 去除路径部分，只保留 scheme 和 host
-        let trimmed = custom_endpoint
-            .splitn(4, '/')
-            .take(3)
-            .collect::<Vec<_>>()
-            .join("/");
-        trimmed
+        normalize_endpoint(custom_endpoint, bucket_name)
     } else {
         // 对于 AWS S3，使用标准的 endpoint 格式
         if options.force_path_style {
@@ -83,15 +278,163 @@ fn build_bucket_and_credentials(
     )
     .map_err(|err| format!("failed to create bucket: {}", err))?;
 
-    // 创建 credentials
-    let credentials = Credentials::new(
-        options.access_key_id.clone(),
-        options.secret_access_key.clone(),
-    );
+    // 创建 credentials；临时凭据会在预签名时附加 X-Amz-Security-Token
+    let credentials = match options
+        .session_token
+        .as_ref()
+        .filter(|token| !token.trim().is_empty())
+    {
+        Some(token) => Credentials::new_with_token(
+            options.access_key_id.clone(),
+            options.secret_access_key.clone(),
+            token.clone(),
+        ),
+        None => Credentials::new(
+            options.access_key_id.clone(),
+            options.secret_access_key.clone(),
+        ),
+    };
 
     Ok((bucket, credentials))
 }
 
+/// 图床配置命名空间形式的 credential id（`image-host:<插件 id>`）：
+/// Secret Access Key / Session Token 为钥匙串中该命名空间下的字段，Access Key ID 不是敏感字段，由调用方传入
+const HOST_CREDENTIAL_PREFIX: &str = "image-host:";
+
+/// 优先使用 credential_id 从系统钥匙串读取凭据，未提供时回退到明文参数
+fn resolve_credentials(
+    access_key_id: Option<String>,
+    secret_access_key: Option<String>,
+    session_token: Option<String>,
+    credential_id: Option<String>,
+) -> Result<StoredS3Credentials, String> {
+    if let Some(id) = credential_id.as_deref().map(str::trim)
+        && let Some(plugin_id) = id.strip_prefix(HOST_CREDENTIAL_PREFIX)
+        && !plugin_id.contains(':')
+    {
+        let access_key_id = access_key_id
+            .filter(|key| !key.trim().is_empty())
+            .ok_or_else(|| format!("accessKeyId is required with credential {id}"))?;
+        let field = |key: &str| {
+            crate::secrets::read_secret(&crate::image_hosts::plugin_secret_id(plugin_id, key))
+        };
+        let secret_access_key = field("secretAccessKey")?
+            .filter(|secret| !secret.is_empty())
+            .ok_or_else(|| format!("secretAccessKey not found in keyring for {id}"))?;
+        let session_token = match session_token {
+            Some(token) => Some(token),
+            None => field("sessionToken")?.filter(|token| !token.is_empty()),
+        };
+        return Ok(StoredS3Credentials {
+            access_key_id,
+            secret_access_key,
+            session_token,
+        });
+    }
+
+    if let Some(id) = credential_id.filter(|id| !id.trim().is_empty()) {
+        let raw = crate::secrets::read_secret(&id)?
+            .ok_or_else(|| format!("credential not found in keyring: {id}"))?;
+        let mut stored: StoredS3Credentials = serde_json::from_str(&raw)
+            .map_err(|err| format!("invalid credential payload for {id}: {err}"))?;
+        // 显式传入的会话令牌优先（临时凭据通常比钥匙串中的条目更新）
+        if session_token.is_some() {
+            stored.session_token = session_token;
+        }
+        return Ok(stored);
+    }
+
+    match (access_key_id, secret_access_key) {
+        (Some(access_key_id), Some(secret_access_key)) => Ok(StoredS3Credentials {
+            access_key_id,
+            secret_access_key,
+            session_token,
+        }),
+        _ => Err("either credentialId or accessKeyId/secretAccessKey is required".to_string()),
+    }
+}
+
+/// 根据删除标记中记录的存储桶信息构建 bucket 与 credentials
+fn bucket_for_marker(
+    marker: &S3DeleteMarker,
+    secrets: StoredS3Credentials,
+) -> Result<(Bucket, Credentials), String> {
+    let options = S3ConfigOptions {
+        region: marker.region.clone(),
+        endpoint: marker.endpoint.clone(),
+        force_path_style: marker.force_path_style,
+        access_key_id: secrets.access_key_id,
+        secret_access_key: secrets.secret_access_key,
+        session_token: secrets.session_token,
+    };
+
+    build_bucket_and_credentials(&options, &marker.bucket)
+        .map_err(|err| format!("failed to build bucket and credentials: {}", err))
+}
+
+fn presign_expiry(expiry_secs: Option<u64>) -> Duration {
+    Duration::from_secs(
+        expiry_secs
+            .unwrap_or(DEFAULT_PRESIGN_EXPIRY_SECS)
+            .clamp(1, MAX_PRESIGN_EXPIRY_SECS),
+    )
+}
+
+fn signing_time() -> OffsetDateTime {
+    OffsetDateTime::now_utc() + time::Duration::seconds(CLOCK_SKEW_SECS.load(Ordering::Relaxed))
+}
+
+/// 根据错误响应的 `Date` 头判断是否由时钟偏差导致（RequestTimeTooSkewed / Request has expired）。
+/// 返回新的偏差值（秒）；若偏差在容忍范围内则返回 None。
+fn detect_clock_skew(response: &Response) -> Option<i64> {
+    if !matches!(
+        response.status(),
+        StatusCode::FORBIDDEN | StatusCode::BAD_REQUEST
+    ) {
+        return None;
+    }
+    let date = response.headers().get(reqwest::header::DATE)?.to_str().ok()?;
+    let server_time = chrono::DateTime::parse_from_rfc2822(date)
+        .ok()?
+        .with_timezone(&Utc);
+    let skew = (server_time - Utc::now()).num_seconds();
+    let current = CLOCK_SKEW_SECS.load(Ordering::Relaxed);
+    if (skew - current).abs() > MAX_TOLERATED_SKEW_SECS {
+        Some(skew)
+    } else {
+        None
+    }
+}
+
+/// 预签名并发送请求；若服务器因时钟偏差拒绝，则按服务器时间重新签名并重试一次
+async fn send_presigned<'a, A, F>(
+    action: &A,
+    expires_in: Duration,
+    build_request: F,
+) -> Result<Response, reqwest::Error>
+where
+    A: S3Action<'a>,
+    F: Fn(&str) -> RequestBuilder,
+{
+    let presigned_url = action.sign_with_time(expires_in, &signing_time());
+    let response = build_request(presigned_url.as_str()).send().await;
+    crate::network_status::report_send_result(&response);
+    let response = response?;
+
+    let Some(skew) = detect_clock_skew(&response) else {
+        return Ok(response);
+    };
+
+    warn!(
+        "S3 request rejected with clock skew of {}s, re-signing with server time",
+        skew
+    );
+    CLOCK_SKEW_SECS.store(skew, Ordering::Relaxed);
+    let presigned_url = action.sign_with_time(expires_in, &signing_time());
+    build_request(presigned_url.as_str()).send().await
+}
+
 fn sanitize_file_name(input: &str) -> String {
     let trimmed = input.trim();
     let fallback = "upload.bin";
@@ -109,9 +452,13 @@ fn sanitize_file_name(input: &str) -> String {
         .collect()
 }
 
-fn generate_object_key(prefix: Option<&str>, original_name: &str) -> String {
-    let sanitized = sanitize_file_name(original_name);
-    let uuid = Uuid::new_v4();
+/// 对象键为 `<前缀>/<年>/<月>/<日>/<文件名>`；未设置文件名模板时文件名为 `<uuid>-<原文件名>`
+fn generate_object_key(prefix: Option<&str>, original_name: &str, bytes: &[u8]) -> String {
+    let file_name = if crate::filename_template::is_enabled() {
+        sanitize_file_name(&crate::filename_template::apply(original_name, bytes))
+    } else {
+        format!("{}-{}", Uuid::new_v4(), sanitize_file_name(original_name))
+    };
     let date_prefix = Utc::now().format("%Y/%m/%d");
     let mut segments = Vec::new();
     if let Some(custom_prefix) = prefix {
@@ -121,7 +468,7 @@ fn generate_object_key(prefix: Option<&str>, original_name: &str) -> String {
         }
     }
     segments.push(date_prefix.to_string());
-    segments.push(format!("{}-{}", uuid, sanitized));
+    segments.push(file_name);
     segments.join("/")
 }
 
@@ -133,7 +480,45 @@ fn resolve_content_type(file_name: &str) -> Option<String> {
         .filter(|mime| !mime.is_empty())
 }
 
+/// 渲染公开访问地址模板，支持的占位符：
+/// `{key}` 完整对象键、`{filename}` 对象键最后一段、`{dir}` 对象键目录部分、
+/// `{bucket}`、`{region}`、`{endpoint}`（去除结尾斜杠）。
+fn render_public_url_template(
+    template: &str,
+    endpoint: Option<&str>,
+    bucket: &str,
+    region: &str,
+    key: &str,
+) -> String {
+    let (dir, filename) = match key.rsplit_once('/') {
+        Some((dir, filename)) => (dir, filename),
+        None => ("", key),
+    };
+    template
+        .replace("{key}", key)
+        .replace("{filename}", filename)
+        .replace("{dir}", dir)
+        .replace("{bucket}", bucket)
+        .replace("{region}", region)
+        .replace("{endpoint}", endpoint.unwrap_or("").trim_end_matches('/'))
+}
+
+fn validate_public_url_template(template: Option<&str>) -> Result<Option<String>, String> {
+    let Some(raw) = template else {
+        return Ok(None);
+    };
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    if !trimmed.contains("{key}") && !trimmed.contains("{filename}") {
+        return Err("public URL template must contain {key} or {filename}".to_string());
+    }
+    Ok(Some(trimmed.to_string()))
+}
+
 fn build_public_url(
+    public_template: Option<&str>,
     public_base: Option<&str>,
     endpoint: Option<&str>,
     bucket: &str,
@@ -141,6 +526,13 @@ fn build_public_url(
     key: &str,
     force_path_style: bool,
 ) -> String {
+    // 与请求使用同一 endpoint，粘贴进来的存储桶名不会在链接中重复出现
+    let endpoint = endpoint.map(|endpoint| normalize_endpoint(endpoint, bucket));
+    let endpoint = endpoint.as_deref();
+    if let Some(template) = public_template {
+        return render_public_url_template(template, endpoint, bucket, region, key);
+    }
+
     if let Some(base) = public_base {
         let trimmed = base.trim_end_matches('/');
         return format!("{}/{}", trimmed, key);
@@ -185,19 +577,254 @@ fn map_acl(value: Option<&str>) -> Result<Option<String>, String> {
     Ok(Some(acl.to_string()))
 }
 
+/// 将标签编码为 `x-amz-tagging` 请求头的值（URL 查询串格式，如 `app=yana&project=blog`）。
+/// 空格编码为 `%20` 而非表单格式的 `+`：部分 S3 兼容服务会把 `+` 当作字面字符保存。
+fn build_tagging_header(tags: Option<&BTreeMap<String, String>>) -> Result<Option<String>, String> {
+    let Some(tags) = tags else {
+        return Ok(None);
+    };
+    if tags.is_empty() {
+        return Ok(None);
+    }
+    if tags.len() > MAX_OBJECT_TAGS {
+        return Err(format!(
+            "too many object tags: {} (max {})",
+            tags.len(),
+            MAX_OBJECT_TAGS
+        ));
+    }
+
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    for (key, value) in tags {
+        let key = key.trim();
+        if key.is_empty() {
+            return Err("object tag key must not be empty".to_string());
+        }
+        if key.chars().count() > MAX_TAG_KEY_LEN {
+            return Err(format!("object tag key too long: {key}"));
+        }
+        if value.chars().count() > MAX_TAG_VALUE_LEN {
+            return Err(format!("object tag value too long for key: {key}"));
+        }
+        serializer.append_pair(key, value);
+    }
+    // 字面的 `+` 已被编码为 `%2B`，剩余的 `+` 都来自空格
+    Ok(Some(serializer.finish().replace('+', "%20")))
+}
+
+/// 构建 S3 请求使用的 HTTP 客户端，按需加载 mTLS 客户端证书与重定向 / DNS 覆盖设置
+fn s3_http_client(
+    certificate: Option<&ClientCertificate>,
+    http_options: Option<&HttpClientOptions>,
+) -> Result<reqwest::Client, String> {
+    let builder = apply_http_options(reqwest::Client::builder(), http_options)?;
+    apply_client_certificate(builder, certificate)?
+        .build()
+        .map_err(|err| format!("failed to build http client: {err}"))
+}
+
+/// 一次上传任务共享的存储桶配置与请求头，单文件与目录上传共用
+struct PreparedS3Upload {
+    options: S3ConfigOptions,
+    bucket: String,
+    acl_header: Option<String>,
+    tagging_header: Option<String>,
+    expires_in: Duration,
+    public_base_url: Option<String>,
+    public_url_template: Option<String>,
+    client_certificate: Option<ClientCertificate>,
+    rate_limit_kbps: Option<u32>,
+    http_options: Option<HttpClientOptions>,
+    cdn: CdnPurgeConfig,
+}
+
+impl PreparedS3Upload {
+    fn client(&self) -> Result<reqwest::Client, String> {
+        s3_http_client(self.client_certificate.as_ref(), self.http_options.as_ref())
+    }
+
+    async fn put(
+        &self,
+        client: &reqwest::Client,
+        object_key: &str,
+        body: Bytes,
+        file_name: &str,
+    ) -> Result<S3UploadResult, String> {
+        let (bucket_obj, credentials) = build_bucket_and_credentials(&self.options, &self.bucket)
+            .map_err(|err| format!("failed to build bucket and credentials: {}", err))?;
+
+        // 开启 CDN 自动清除时先检查是否会覆盖已有对象
+        let overwrites = if self.cdn.auto_purge_enabled() {
+            let head = bucket_obj.head_object(Some(&credentials), object_key);
+            send_presigned(&head, self.expires_in, |url| client.head(url))
+                .await
+                .is_ok_and(|response| response.status().is_success())
+        } else {
+            false
+        };
+
+        // 创建 PUT 操作
+        let action = bucket_obj.put_object(Some(&credentials), object_key);
+
+        // 预签名时会由 `sign_with_time` 添加过期参数，避免重复插入
+
+        // 不将可变请求头加入到签名内（避免因 header 值或大小写差异导致 SignatureDoesNotMatch）。
+        // 我们将在发起 HTTP 请求时，将 Content-Type、x-amz-acl 与 x-amz-tagging 附加到 reqwest 请求头中。
+        let content_type_header = resolve_content_type(file_name);
+
+        // 使用 reqwest 执行上传（预签名 URL 在发送前生成）
+        let content_length = body.len();
+        let response = send_presigned(&action, self.expires_in, |url| {
+            // 限速时请求体为流，需显式给出长度（S3 预签名 PUT 不接受分块传输）
+            let throttle = Throttle::for_url(url, self.rate_limit_kbps);
+            let mut req = client
+                .put(url)
+                .header("Content-Length", content_length)
+                .body(throttle.body(body.clone()));
+            if let Some(ct) = &content_type_header {
+                req = req.header("Content-Type", ct);
+            }
+            if let Some(acl_val) = &self.acl_header {
+                req = req.header("x-amz-acl", acl_val);
+            }
+            if let Some(tagging) = &self.tagging_header {
+                req = req.header("x-amz-tagging", tagging);
+            }
+            req
+        })
+        .await
+        .map_err(|err| format!("failed to upload file: {}", err))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!(
+                "upload failed with status {}: {}",
+                status, error_text
+            ));
+        }
+
+        let delete_marker = S3DeleteMarker {
+            bucket: self.bucket.clone(),
+            region: self.options.region.clone(),
+            key: object_key.to_string(),
+            endpoint: self.options.endpoint.clone(),
+            force_path_style: self.options.force_path_style,
+        };
+
+        // 对于 rusty-s3，我们无法直接从响应中获取 ETag 和 VersionId
+        // 你可以选择从响应头中提取，或者省略这些元数据
+        let metadata = None;
+
+        let public_url = build_public_url(
+            self.public_url_template.as_deref(),
+            self.public_base_url.as_deref(),
+            delete_marker.endpoint.as_deref(),
+            &delete_marker.bucket,
+            &delete_marker.region,
+            &delete_marker.key,
+            delete_marker.force_path_style,
+        );
+
+        let delete_id = serde_json::to_string(&delete_marker)
+            .map_err(|err| format!("failed to serialize delete marker: {err}"))?;
+
+        if overwrites {
+            let (cdn, urls) = (self.cdn.clone(), vec![public_url.clone()]);
+            tauri::async_runtime::spawn(async move {
+                if let Err(err) = crate::cdn_purge::purge(&cdn, &urls).await {
+                    warn!("purge overwritten object failed: {}", err);
+                }
+            });
+        }
+
+        Ok(S3UploadResult {
+            url: public_url,
+            delete_id,
+            metadata,
+        })
+    }
+}
+
 #[tauri::command]
 pub async fn s3_upload(
+    app: AppHandle,
     file_path: String,
     original_file_name: String,
     bucket: String,
     region: String,
-    access_key_id: String,
-    secret_access_key: String,
+    access_key_id: Option<String>,
+    secret_access_key: Option<String>,
     endpoint: Option<String>,
     force_path_style: Option<bool>,
     object_prefix: Option<String>,
     acl: Option<String>,
     public_base_url: Option<String>,
+    credential_id: Option<String>,
+    session_token: Option<String>,
+    presign_expiry_secs: Option<u64>,
+    tags: Option<BTreeMap<String, String>>,
+    public_url_template: Option<String>,
+    upload_id: Option<String>,
+    client_certificate: Option<ClientCertificate>,
+    rate_limit_kbps: Option<u32>,
+    http_options: Option<HttpClientOptions>,
+    cdn: Option<CdnPurgeConfig>,
+) -> Result<S3UploadResult, String> {
+    let options = S3UploadOptions {
+        bucket,
+        region,
+        endpoint,
+        force_path_style,
+        object_prefix,
+        acl,
+        public_base_url,
+        public_url_template,
+        tags,
+        concurrency: None,
+        rate_limit_kbps,
+        credentials: S3CredentialInput {
+            access_key_id,
+            secret_access_key,
+            session_token,
+            credential_id,
+            presign_expiry_secs,
+            client_certificate,
+            http_options,
+        },
+        cdn: cdn.unwrap_or_default(),
+    };
+    let job = S3UploadJob {
+        file_path: file_path.clone(),
+        original_file_name: original_file_name.clone(),
+        options: options.clone(),
+    };
+    let destination = options.endpoint.clone();
+    let queued = crate::upload_queue::run_queued(
+        &app,
+        upload_id.as_deref(),
+        destination.as_deref(),
+        s3_upload_impl(file_path, original_file_name, options),
+    );
+    let cancellable = crate::upload::run_cancellable(upload_id.clone(), queued);
+    run_tracked(
+        &app,
+        PendingKind::S3Upload,
+        Some("s3"),
+        &job,
+        &job.file_path,
+        cancellable,
+    )
+    .await
+}
+
+pub(crate) async fn s3_upload_impl(
+    file_path: String,
+    original_file_name: String,
+    options: S3UploadOptions,
 ) -> Result<S3UploadResult, String> {
     let path = Path::new(&file_path);
     if !path.is_absolute() || !path.exists() {
@@ -210,47 +837,57 @@ pub async fn s3_upload(
             .await
             .map_err(|err| format!("failed to join file read task: {err}"))?
             .map_err(|err| format!("failed to read file: {err}"))?;
+    // 时钟偏差重试时需要重新构建请求，使用 Bytes 避免复制文件内容
+    let file_bytes = Bytes::from(file_bytes);
 
-    let options = S3ConfigOptions {
-        region: region.clone(),
-        endpoint: endpoint.clone(),
-        // default to path style when custom endpoint (e.g., Cloudflare R2) is used
-        force_path_style: force_path_style.unwrap_or(endpoint.is_some()),
-        access_key_id,
-        secret_access_key,
-    };
+    let object_key = generate_object_key(
+        options.object_prefix.as_deref(),
+        &original_file_name,
+        &file_bytes,
+    );
+    let prepared = options.prepare()?;
 
-    let (bucket_obj, credentials) = build_bucket_and_credentials(&options, &bucket)
-        .map_err(|err| format!("failed to build bucket and credentials: {}", err))?;
+    let client = prepared.client()?;
+    prepared
+        .put(&client, &object_key, file_bytes, &original_file_name)
+        .await
+}
 
-    let object_key = generate_object_key(object_prefix.as_deref(), &original_file_name);
+#[tauri::command]
+pub async fn s3_delete(
+    delete_id: String,
+    access_key_id: Option<String>,
+    secret_access_key: Option<String>,
+    credential_id: Option<String>,
+    session_token: Option<String>,
+    presign_expiry_secs: Option<u64>,
+    client_certificate: Option<ClientCertificate>,
+    http_options: Option<HttpClientOptions>,
+) -> Result<S3DeleteResult, String> {
+    let marker: S3DeleteMarker = serde_json::from_str(&delete_id)
+        .map_err(|err| format!("invalid deleteId payload: {err}"))?;
 
-    // 创建 PUT 操作
-    let action = bucket_obj.put_object(Some(&credentials), &object_key);
+    let secrets = resolve_credentials(
+        access_key_id,
+        secret_access_key,
+        session_token,
+        credential_id,
+    )?;
 
-    // 预签名时会由 `sign(Duration)` 添加过期参数，避免重复插入
+    let (bucket_obj, credentials) = bucket_for_marker(&marker, secrets)?;
 
-    // 不将可变请求头加入到签名内（避免因 header 值或大小写差异导致 SignatureDoesNotMatch）。
-    // 我们将在发起 HTTP 请求时，将 Content-Type 与 x-amz-acl 附加到 reqwest 请求头中。
-    let content_type_header = resolve_content_type(&original_file_name);
-    let acl_header = map_acl(acl.as_deref())?;
+    // 创建 DELETE 操作
+    let action = bucket_obj.delete_object(Some(&credentials), &marker.key);
 
-    // 生成预签名 URL
-    let presigned_url = action.sign(Duration::from_secs(900));
+    // 预签名时会由 `sign_with_time` 添加过期参数，避免重复插入
 
-    // 使用 reqwest 执行上传
-    let client = reqwest::Client::new();
-    let mut req = client.put(presigned_url.as_str()).body(file_bytes);
-    if let Some(ct) = content_type_header {
-        req = req.header("Content-Type", ct);
-    }
-    if let Some(acl_val) = acl_header {
-        req = req.header("x-amz-acl", acl_val);
-    }
-    let response = req
-        .send()
-        .await
-        .map_err(|err| format!("failed to upload file: {}", err))?;
+    // 使用 reqwest 执行删除
+    let client = s3_http_client(client_certificate.as_ref(), http_options.as_ref())?;
+    let response = send_presigned(&action, presign_expiry(presign_expiry_secs), |url| {
+        client.delete(url)
+    })
+    .await
+    .map_err(|err| format!("failed to delete object: {}", err))?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -259,77 +896,144 @@ pub async fn s3_upload(
             .await
             .unwrap_or_else(|_| "Unknown error".to_string());
         return Err(format!(
-            "upload failed with status {}: {}",
+            "delete failed with status {}: {}",
             status, error_text
         ));
     }
 
-    let delete_marker = S3DeleteMarker {
-        bucket: bucket.clone(),
-        region: region.clone(),
-        key: object_key.clone(),
-        endpoint,
-        force_path_style: options.force_path_style,
-    };
-
-    // 对于 rusty-s3，我们无法直接从响应中获取 ETag 和 VersionId
-    // 你可以选择从响应头中提取，或者省略这些元数据
-    let metadata = None;
-
-    let public_url = build_public_url(
-        public_base_url.as_deref(),
-        delete_marker.endpoint.as_deref(),
-        &delete_marker.bucket,
-        &delete_marker.region,
-        &delete_marker.key,
-        delete_marker.force_path_style,
-    );
-
-    let delete_id = serde_json::to_string(&delete_marker)
-        .map_err(|err| format!("failed to serialize delete marker: {err}"))?;
-
-    Ok(S3UploadResult {
-        url: public_url,
-        delete_id,
-        metadata,
+    Ok(S3DeleteResult {
+        success: true,
+        message: Some("对象已从 S3 删除".to_string()),
     })
 }
 
+/// 通过 HEAD 请求检查对象是否存在，并返回大小、类型与最后修改时间。
+/// 私有桶无法公开 GET，因此链接健康检查需要走带签名的 HEAD。
 #[tauri::command]
-pub async fn s3_delete(
+pub async fn s3_head_object(
     delete_id: String,
-    access_key_id: String,
-    secret_access_key: String,
-) -> Result<S3DeleteResult, String> {
+    access_key_id: Option<String>,
+    secret_access_key: Option<String>,
+    credential_id: Option<String>,
+    session_token: Option<String>,
+    presign_expiry_secs: Option<u64>,
+    client_certificate: Option<ClientCertificate>,
+    http_options: Option<HttpClientOptions>,
+) -> Result<S3HeadResult, String> {
     let marker: S3DeleteMarker = serde_json::from_str(&delete_id)
         .map_err(|err| format!("invalid deleteId payload: {err}"))?;
 
-    let options = S3ConfigOptions {
-        region: marker.region.clone(),
-        endpoint: marker.endpoint.clone(),
-        force_path_style: marker.force_path_style,
+    let secrets = resolve_credentials(
         access_key_id,
         secret_access_key,
-    };
+        session_token,
+        credential_id,
+    )?;
 
-    let (bucket_obj, credentials) = build_bucket_and_credentials(&options, &marker.bucket)
-        .map_err(|err| format!("failed to build bucket and credentials: {}", err))?;
+    let (bucket_obj, credentials) = bucket_for_marker(&marker, secrets)?;
 
-    // 创建 DELETE 操作
-    let action = bucket_obj.delete_object(Some(&credentials), &marker.key);
+    let action = bucket_obj.head_object(Some(&credentials), &marker.key);
 
-    // 预签名时会由 `sign(Duration)` 添加过期参数，避免重复插入
+    let client = s3_http_client(client_certificate.as_ref(), http_options.as_ref())?;
+    let response = send_presigned(&action, presign_expiry(presign_expiry_secs), |url| {
+        client.head(url)
+    })
+    .await
+    .map_err(|err| format!("failed to head object: {}", err))?;
 
-    // 生成预签名 URL
-    let presigned_url = action.sign(Duration::from_secs(900));
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND {
+        return Ok(S3HeadResult {
+            exists: false,
+            size: None,
+            content_type: None,
+            last_modified: None,
+        });
+    }
 
-    // 使用 reqwest 执行删除
-    let client = reqwest::Client::new();
-    let response = client
-        .delete(presigned_url.as_str())
-        .send()
+    if !status.is_success() {
+        // HEAD 响应没有响应体，只能返回状态码
+        return Err(format!("head failed with status {}", status));
+    }
+
+    let headers = response.headers();
+    let header_str = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string())
+    };
+
+    // HEAD 请求的 content_length() 可能为 0，直接读取响应头
+    let size = header_str("content-length").and_then(|value| value.parse::<u64>().ok());
+    let content_type = header_str("content-type");
+    let last_modified = header_str("last-modified").map(|value| {
+        chrono::DateTime::parse_from_rfc2822(&value)
+            .map(|dt| dt.with_timezone(&Utc).to_rfc3339())
+            .unwrap_or(value)
+    });
+
+    Ok(S3HeadResult {
+        exists: true,
+        size,
+        content_type,
+        last_modified,
+    })
+}
+
+/// 通过预签名 GET 将对象下载到本地文件（流式写入，先写临时文件再重命名）。
+/// 用于“迁移图床”和“重新编辑原图”等本地副本已丢失的场景。
+#[tauri::command]
+pub async fn s3_download(
+    delete_id: String,
+    dest_path: String,
+    credentials: S3CredentialInput,
+) -> Result<S3DownloadResult, String> {
+    let dest = PathBuf::from(&dest_path);
+    if !dest.is_absolute() {
+        return Err("destination path must be absolute".to_string());
+    }
+    if dest
+        .components()
+        .any(|component| matches!(component, std::path::Component::ParentDir))
+    {
+        return Err("parent directory segments are not allowed in destination path".to_string());
+    }
+    let parent = dest
+        .parent()
+        .ok_or_else(|| "destination path has no parent directory".to_string())?;
+    tokio::fs::create_dir_all(parent)
         .await
-        .map_err(|err| format!("failed to delete object: {}", err))?;
+        .map_err(|err| format!("create_dir_all {}: {err}", parent.display()))?;
+
+    let marker: S3DeleteMarker = serde_json::from_str(&delete_id)
+        .map_err(|err| format!("invalid deleteId payload: {err}"))?;
+
+    let S3CredentialInput {
+        access_key_id,
+        secret_access_key,
+        session_token,
+        credential_id,
+        presign_expiry_secs,
+        client_certificate,
+        http_options,
+    } = credentials;
+    let secrets = resolve_credentials(
+        access_key_id,
+        secret_access_key,
+        session_token,
+        credential_id,
+    )?;
+
+    let (bucket_obj, credentials) = bucket_for_marker(&marker, secrets)?;
+    let action = bucket_obj.get_object(Some(&credentials), &marker.key);
+
+    let client = s3_http_client(client_certificate.as_ref(), http_options.as_ref())?;
+    let mut response = send_presigned(&action, presign_expiry(presign_expiry_secs), |url| {
+        client.get(url)
+    })
+    .await
+    .map_err(|err| format!("failed to download object: {}", err))?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -338,13 +1042,280 @@ pub async fn s3_delete(
             .await
             .unwrap_or_else(|_| "Unknown error".to_string());
         return Err(format!(
-            "delete failed with status {}: {}",
+            "download failed with status {}: {}",
             status, error_text
         ));
     }
 
-    Ok(S3DeleteResult {
-        success: true,
-        message: Some("对象已从 S3 删除".to_string()),
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+
+    // 临时文件的创建与重命名放到阻塞线程池，写入走 tokio::fs，避免阻塞异步运行时
+    let tmp_dir = parent.to_path_buf();
+    let (file, tmp_path) = tauri::async_runtime::spawn_blocking(move || {
+        tempfile::Builder::new()
+            .prefix(".yana_download_")
+            .tempfile_in(&tmp_dir)
+            .map_err(|err| format!("tempfile_in {}: {err}", tmp_dir.display()))
+    })
+    .await
+    .map_err(|err| format!("failed to join tempfile task: {err}"))??
+    .into_parts();
+    let mut file = tokio::fs::File::from_std(file);
+
+    let mut size = 0u64;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|err| format!("failed to read response body: {err}"))?
+    {
+        file.write_all(&chunk)
+            .await
+            .map_err(|err| format!("failed to write {}: {err}", dest.display()))?;
+        size += chunk.len() as u64;
+    }
+    file.flush()
+        .await
+        .map_err(|err| format!("failed to flush {}: {err}", dest.display()))?;
+    drop(file);
+
+    let persist_dest = dest.clone();
+    tauri::async_runtime::spawn_blocking(move || tmp_path.persist(&persist_dest))
+        .await
+        .map_err(|err| format!("failed to join persist task: {err}"))?
+        .map_err(|err| format!("failed to persist {}: {}", dest.display(), err.error))?;
+
+    Ok(S3DownloadResult {
+        path: dest.to_string_lossy().to_string(),
+        size,
+        content_type,
+    })
+}
+
+/// 递归收集目录下的图片文件，返回（绝对路径，相对路径）；不跟随符号链接以避免循环
+fn collect_dir_images(
+    root: &Path,
+    dir: &Path,
+    collected: &mut Vec<(PathBuf, String)>,
+) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("read_dir {}: {e}", dir.display()))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("read_dir entry {}: {e}", dir.display()))?;
+        let file_type = entry
+            .file_type()
+            .map_err(|e| format!("file_type {}: {e}", entry.path().display()))?;
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_dir_images(root, &path, collected)?;
+            continue;
+        }
+        if !file_type.is_file() {
+            continue;
+        }
+
+        let is_image = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| DIR_UPLOAD_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+            .unwrap_or(false);
+        if !is_image {
+            continue;
+        }
+
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let relative = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        collected.push((path, relative));
+    }
+    Ok(())
+}
+
+fn dir_object_key(prefix: Option<&str>, relative_path: &str) -> String {
+    match prefix.map(|p| p.trim_matches('/')).filter(|p| !p.is_empty()) {
+        Some(prefix) => format!("{}/{}", prefix, relative_path),
+        None => relative_path.to_string(),
+    }
+}
+
+/// 上传整个目录中的图片，按相对路径保留目录结构（不追加日期与 UUID）。
+/// 每个文件经全局上传队列执行，完成时发送一次 `s3://upload-dir-progress` 事件，
+/// 返回逐文件结果（顺序与遍历顺序一致）。
+#[tauri::command]
+pub async fn s3_upload_dir(
+    app: AppHandle,
+    dir_path: String,
+    options: S3UploadOptions,
+) -> Result<Vec<S3DirUploadItem>, String> {
+    let root = PathBuf::from(&dir_path);
+    if !root.is_absolute() || !root.is_dir() {
+        return Err("dir path must be an existing absolute directory".to_string());
+    }
+
+    let root_for_walk = root.clone();
+    let mut files = tauri::async_runtime::spawn_blocking(move || {
+        let mut collected = Vec::new();
+        collect_dir_images(&root_for_walk, &root_for_walk, &mut collected).map(|_| collected)
     })
+    .await
+    .map_err(|err| format!("failed to join directory walk task: {err}"))??;
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+
+    let total = files.len();
+    let concurrency = options
+        .concurrency
+        .unwrap_or(DEFAULT_DIR_UPLOAD_CONCURRENCY)
+        .clamp(1, MAX_DIR_UPLOAD_CONCURRENCY);
+    let prefix = options.object_prefix.clone();
+    let destination = options.endpoint.clone();
+    let prepared = options.prepare()?;
+    info!(
+        "s3_upload_dir start: dir={}, files={}, concurrency={}",
+        dir_path, total, concurrency
+    );
+
+    let client = prepared.client()?;
+    let completed = std::sync::atomic::AtomicUsize::new(0);
+    let mut results: Vec<(usize, S3DirUploadItem)> =
+        futures::stream::iter(files.into_iter().enumerate())
+            .map(|(index, (path, relative_path))| {
+                let prepared = &prepared;
+                let client = &client;
+                let completed = &completed;
+                let app = &app;
+                let destination = destination.as_deref();
+                let key = dir_object_key(prefix.as_deref(), &relative_path);
+                async move {
+                    // 每个文件都经过全局上传队列，遵守并发上限与暂停 / 离线状态
+                    let outcome = crate::upload_queue::run_queued(app, None, destination, async {
+                        let bytes = tauri::async_runtime::spawn_blocking(move || std::fs::read(path))
+                            .await
+                            .map_err(|err| format!("failed to join file read task: {err}"))?
+                            .map_err(|err| format!("failed to read file: {err}"))?;
+                        prepared
+                            .put(client, &key, Bytes::from(bytes), &relative_path)
+                            .await
+                    })
+                    .await;
+
+                    let done = completed.fetch_add(1, Ordering::Relaxed) + 1;
+                    let progress = S3DirUploadProgress {
+                        total,
+                        completed: done,
+                        relative_path: relative_path.clone(),
+                        success: outcome.is_ok(),
+                    };
+                    if let Err(err) = app.emit(DIR_UPLOAD_PROGRESS_EVENT, progress) {
+                        warn!("failed to emit s3 dir upload progress: {}", err);
+                    }
+
+                    let item = match outcome {
+                        Ok(result) => S3DirUploadItem {
+                            relative_path,
+                            key,
+                            result: Some(result),
+                            error: None,
+                        },
+                        Err(err) => {
+                            warn!("s3_upload_dir item failed: {}: {}", relative_path, err);
+                            S3DirUploadItem {
+                                relative_path,
+                                key,
+                                result: None,
+                                error: Some(err),
+                            }
+                        }
+                    };
+                    (index, item)
+                }
+            })
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+
+    results.sort_by_key(|(index, _)| *index);
+    let items: Vec<S3DirUploadItem> = results.into_iter().map(|(_, item)| item).collect();
+    info!(
+        "s3_upload_dir done: files={}, failed={}",
+        items.len(),
+        items.iter().filter(|item| item.error.is_some()).count()
+    );
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_public_url_template() {
+        let url = render_public_url_template(
+            "https://cdn.example.com/{key}?format=auto",
+            None,
+            "images",
+            "us-east-1",
+            "blog/2024/01/01/a.png",
+        );
+        assert_eq!(
+            url,
+            "https://cdn.example.com/blog/2024/01/01/a.png?format=auto"
+        );
+
+        let url = render_public_url_template(
+            "{endpoint}/{bucket}/{dir}/thumb-{filename}",
+            Some("https://s3.example.com/"),
+            "images",
+            "us-east-1",
+            "blog/a.png",
+        );
+        assert_eq!(url, "https://s3.example.com/images/blog/thumb-a.png");
+
+        assert!(validate_public_url_template(Some("https://cdn.example.com/")).is_err());
+        assert_eq!(validate_public_url_template(Some("  ")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_build_tagging_header() {
+        let tags = BTreeMap::from([
+            ("app".to_string(), "yana".to_string()),
+            ("project".to_string(), "my blog+notes".to_string()),
+        ]);
+        assert_eq!(
+            build_tagging_header(Some(&tags)).unwrap().as_deref(),
+            Some("app=yana&project=my%20blog%2Bnotes")
+        );
+        assert_eq!(build_tagging_header(Some(&BTreeMap::new())).unwrap(), None);
+    }
+
+    #[test]
+    fn test_build_public_url_normalizes_endpoint() {
+        let url = build_public_url(
+            None,
+            None,
+            Some("https://minio.example.com/images"),
+            "images",
+            "us-east-1",
+            "a.png",
+            true,
+        );
+        assert_eq!(url, "https://minio.example.com/images/a.png");
+
+        let url = build_public_url(
+            None,
+            None,
+            Some("https://ref.supabase.co/storage/v1/s3"),
+            "images",
+            "us-east-1",
+            "a.png",
+            true,
+        );
+        assert_eq!(url, "https://ref.supabase.co/storage/v1/s3/images/a.png");
+    }
 }
@@ -0,0 +1,138 @@
+/*
+S3 兼容服务商预设：
+为设置界面提供常见服务商的 endpoint 格式、路径风格默认值、region 约定与公开访问地址模板。
+模板中的占位符使用 `{name}` 形式，由前端根据 `placeholders` 提示用户填写；
+`{bucket}`、`{region}`、`{key}` 为保留占位符，分别由存储桶、区域和对象键填充。
+*/
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3ProviderPreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// endpoint 模板，None 表示使用 AWS 默认地址
+    pub endpoint_template: Option<&'static str>,
+    /// 模板中需要用户额外填写的占位符（不含保留占位符）
+    pub placeholders: Vec<&'static str>,
+    pub force_path_style: bool,
+    /// 默认 region；部分服务商固定为某个值（如 R2 的 auto）
+    pub default_region: Option<&'static str>,
+    /// region 是否固定不可修改
+    pub region_locked: bool,
+    /// 是否支持 x-amz-acl 请求头
+    pub supports_acl: bool,
+    /// 公开访问地址模板
+    pub public_url_template: Option<&'static str>,
+    /// 额外注意事项，直接展示在设置界面
+    pub notes: Option<&'static str>,
+}
+
+fn builtin_presets() -> Vec<S3ProviderPreset> {
+    vec![
+        S3ProviderPreset {
+            id: "aws",
+            name: "Amazon S3",
+            endpoint_template: None,
+            placeholders: vec![],
+            force_path_style: false,
+            default_region: Some("us-east-1"),
+            region_locked: false,
+            supports_acl: true,
+            public_url_template: Some("https://{bucket}.s3.{region}.amazonaws.com/{key}"),
+            notes: None,
+        },
+        S3ProviderPreset {
+            id: "minio",
+            name: "MinIO",
+            endpoint_template: Some("http://{host}:9000"),
+            placeholders: vec!["host"],
+            force_path_style: true,
+            default_region: Some("us-east-1"),
+            region_locked: false,
+            supports_acl: false,
+            public_url_template: Some("http://{host}:9000/{bucket}/{key}"),
+            notes: Some("公开访问需要为存储桶设置 anonymous 下载策略"),
+        },
+        S3ProviderPreset {
+            id: "cloudflare_r2",
+            name: "Cloudflare R2",
+            endpoint_template: Some("https://{accountId}.r2.cloudflarestorage.com"),
+            placeholders: vec!["accountId", "publicDomain"],
+            force_path_style: true,
+            default_region: Some("auto"),
+            region_locked: true,
+            supports_acl: false,
+            public_url_template: Some("https://{publicDomain}/{key}"),
+            notes: Some("R2 不支持 ACL，公开访问需绑定自定义域名或启用 r2.dev 子域"),
+        },
+        S3ProviderPreset {
+            id: "backblaze_b2",
+            name: "Backblaze B2",
+            endpoint_template: Some("https://s3.{region}.backblazeb2.com"),
+            placeholders: vec![],
+            force_path_style: false,
+            default_region: Some("us-west-004"),
+            region_locked: false,
+            supports_acl: false,
+            public_url_template: Some("https://{bucket}.s3.{region}.backblazeb2.com/{key}"),
+            notes: Some("region 取自存储桶 endpoint，例如 us-west-004；存储桶需设为 Public"),
+        },
+        S3ProviderPreset {
+            id: "aliyun_oss",
+            name: "阿里云 OSS",
+            endpoint_template: Some("https://oss-{region}.aliyuncs.com"),
+            placeholders: vec![],
+            force_path_style: false,
+            default_region: Some("cn-hangzhou"),
+            region_locked: false,
+            supports_acl: true,
+            public_url_template: Some("https://{bucket}.oss-{region}.aliyuncs.com/{key}"),
+            notes: Some("OSS 仅支持虚拟主机风格；region 填写不带 oss- 前缀的地域 ID"),
+        },
+        S3ProviderPreset {
+            id: "tencent_cos",
+            name: "腾讯云 COS",
+            endpoint_template: Some("https://cos.{region}.myqcloud.com"),
+            placeholders: vec![],
+            force_path_style: false,
+            default_region: Some("ap-guangzhou"),
+            region_locked: false,
+            supports_acl: true,
+            public_url_template: Some("https://{bucket}.cos.{region}.myqcloud.com/{key}"),
+            notes: Some("存储桶名称需包含 APPID 后缀，例如 images-1250000000"),
+        },
+        S3ProviderPreset {
+            id: "wasabi",
+            name: "Wasabi",
+            endpoint_template: Some("https://s3.{region}.wasabisys.com"),
+            placeholders: vec![],
+            force_path_style: false,
+            default_region: Some("us-east-1"),
+            region_locked: false,
+            supports_acl: true,
+            public_url_template: Some("https://s3.{region}.wasabisys.com/{bucket}/{key}"),
+            notes: Some("新账户默认禁止公开访问，需要联系 Wasabi 开启"),
+        },
+        S3ProviderPreset {
+            id: "supabase",
+            name: "Supabase Storage",
+            endpoint_template: Some("https://{projectRef}.supabase.co/storage/v1/s3"),
+            placeholders: vec!["projectRef"],
+            force_path_style: true,
+            default_region: Some("us-east-1"),
+            region_locked: false,
+            supports_acl: false,
+            public_url_template: Some(
+                "https://{projectRef}.supabase.co/storage/v1/object/public/{bucket}/{key}",
+            ),
+            notes: Some("region 需与项目所在区域一致；公开地址要求存储桶为 Public bucket"),
+        },
+    ]
+}
+
+#[tauri::command]
+pub fn s3_list_presets() -> Vec<S3ProviderPreset> {
+    builtin_presets()
+}
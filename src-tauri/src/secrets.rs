@@ -0,0 +1,237 @@
+/*
+系统钥匙串（Windows Credential Manager / macOS Keychain / Secret Service）封装：
+前端只保存凭据 id，敏感值存放在系统钥匙串中，需要时由后端按 id 读取。
+命令以「命名空间 + 键」定位条目（`secret_set` / `secret_get` / `secret_delete`），
+条目 id 为 `<namespace>:<key>`；早期的 `secret_store(id, value)` / `secret_get(id)` 仍然可用。
+Android 等没有系统钥匙串的平台上 keyring 只有进程内的模拟存储，重启即丢失：
+`keyring_available` 为 false 时图床配置继续以明文保存（不迁移、不写占位符），
+其余条目（OAuth 令牌、导入的凭据等）改存应用配置目录下的 secrets.json（明文）。
+*/
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use log::{error, info, warn};
+use tauri::AppHandle;
+
+const KEYRING_SERVICE: &str = "com.yana.dev";
+const FALLBACK_STORE_FILE: &str = "secrets.json";
+
+/// 没有系统钥匙串时使用的明文存储文件，启动时由 `init` 设置
+static FALLBACK_STORE: OnceLock<PathBuf> = OnceLock::new();
+/// 串行化明文存储的读改写
+static FALLBACK_LOCK: Mutex<()> = Mutex::new(());
+
+/// 当前平台是否有持久化的系统钥匙串（与 Cargo.toml 中启用的 keyring 后端一致）
+pub(crate) fn keyring_available() -> bool {
+    cfg!(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "freebsd"
+    ))
+}
+
+/// 没有系统钥匙串的平台上确定明文存储文件的位置
+pub(crate) fn init(app: &AppHandle) {
+    if keyring_available() {
+        return;
+    }
+    match crate::portable::app_config_dir(app) {
+        Ok(dir) => {
+            let _ = FALLBACK_STORE.set(dir.join(FALLBACK_STORE_FILE));
+            warn!("system keyring unavailable, secrets are stored in plaintext");
+        }
+        Err(err) => error!("resolve secret store dir failed: {}", err),
+    }
+}
+
+fn fallback_path() -> Result<&'static PathBuf, String> {
+    FALLBACK_STORE
+        .get()
+        .ok_or_else(|| "secret store is not initialized".to_string())
+}
+
+fn read_fallback() -> Result<BTreeMap<String, String>, String> {
+    let path = fallback_path()?;
+    match std::fs::read_to_string(path) {
+        Ok(text) => {
+            serde_json::from_str(&text).map_err(|e| format!("parse {}: {e}", path.display()))
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(err) => Err(format!("read {}: {err}", path.display())),
+    }
+}
+
+fn update_fallback(update: impl FnOnce(&mut BTreeMap<String, String>)) -> Result<(), String> {
+    let _lock = FALLBACK_LOCK
+        .lock()
+        .map_err(|_| "secret store lock poisoned".to_string())?;
+    let mut entries = read_fallback()?;
+    update(&mut entries);
+    let path = fallback_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("create_dir_all {}: {e}", parent.display()))?;
+    }
+    let text = serde_json::to_string_pretty(&entries)
+        .map_err(|e| format!("serialize secret store: {e}"))?;
+    std::fs::write(path, text).map_err(|e| format!("write {}: {e}", path.display()))
+}
+
+fn entry_for(id: &str) -> Result<keyring::Entry, String> {
+    let trimmed = id.trim();
+    if trimmed.is_empty() {
+        return Err("secret id must not be empty".to_string());
+    }
+    keyring::Entry::new(KEYRING_SERVICE, trimmed)
+        .map_err(|e| format!("open keyring entry {}: {e}", trimmed))
+}
+
+/// 读取指定 id 的密钥，不存在时返回 None
+pub(crate) fn read_secret(id: &str) -> Result<Option<String>, String> {
+    if !keyring_available() {
+        return Ok(read_fallback()?.remove(id.trim()));
+    }
+    let entry = entry_for(id)?;
+    match entry.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(format!("read keyring entry {}: {err}", id)),
+    }
+}
+
+pub(crate) fn write_secret(id: &str, value: &str) -> Result<(), String> {
+    if !keyring_available() {
+        return update_fallback(|entries| {
+            entries.insert(id.trim().to_string(), value.to_string());
+        });
+    }
+    let entry = entry_for(id)?;
+    entry
+        .set_password(value)
+        .map_err(|e| format!("write keyring entry {}: {e}", id))
+}
+
+/// 删除指定 id 的密钥，条目不存在时视为成功
+pub(crate) fn delete_secret(id: &str) -> Result<(), String> {
+    if !keyring_available() {
+        return update_fallback(|entries| {
+            entries.remove(id.trim());
+        });
+    }
+    let entry = entry_for(id)?;
+    match entry.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(err) => Err(format!("delete keyring entry {}: {err}", id)),
+    }
+}
+
+/// 由命名空间与键组成钥匙串条目 id；图床插件配置使用 `image-host:<插件 id>` 命名空间
+pub(crate) fn secret_id(namespace: &str, key: &str) -> Result<String, String> {
+    let (namespace, key) = (namespace.trim(), key.trim());
+    if namespace.is_empty() || key.is_empty() {
+        return Err("secret namespace and key must not be empty".to_string());
+    }
+    Ok(format!("{}:{}", namespace, key))
+}
+
+/// 钥匙串调用可能阻塞（Secret Service 经 D-Bus、macOS 可能弹出授权），命令中放到阻塞线程执行
+async fn blocking<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|e| format!("keyring task failed: {e}"))?
+}
+
+/// 以完整 id 写入敏感值；value 为 None 时删除该条目
+#[tauri::command]
+pub async fn secret_store(id: String, value: Option<String>) -> Result<(), String> {
+    let target = id.clone();
+    let result = blocking(move || match value {
+        Some(value) => write_secret(&target, &value),
+        None => delete_secret(&target),
+    })
+    .await;
+    match result {
+        Ok(()) => {
+            info!("secret_store success: id={}", id.trim());
+            Ok(())
+        }
+        Err(err) => {
+            error!("secret_store failed: id={}, error={}", id.trim(), err);
+            Err(err)
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn secret_set(namespace: String, key: String, value: String) -> Result<(), String> {
+    let id = secret_id(&namespace, &key)?;
+    let target = id.clone();
+    match blocking(move || write_secret(&target, &value)).await {
+        Ok(()) => {
+            info!("secret_set success: id={}", id);
+            Ok(())
+        }
+        Err(err) => {
+            error!("secret_set failed: id={}, error={}", id, err);
+            Err(err)
+        }
+    }
+}
+
+/// 读取条目：传入完整 `id`，或 `namespace` + `key`
+#[tauri::command]
+pub async fn secret_get(
+    id: Option<String>,
+    namespace: Option<String>,
+    key: Option<String>,
+) -> Result<Option<String>, String> {
+    let id = match (id, namespace, key) {
+        (Some(id), _, _) => id,
+        (None, Some(namespace), Some(key)) => secret_id(&namespace, &key)?,
+        _ => return Err("either id or namespace and key is required".to_string()),
+    };
+    let target = id.clone();
+    blocking(move || read_secret(&target))
+        .await
+        .inspect_err(|err| {
+            error!("secret_get failed: id={}, error={}", id, err);
+        })
+}
+
+/// 删除指定条目，不存在时视为成功
+#[tauri::command]
+pub async fn secret_delete(namespace: String, key: String) -> Result<(), String> {
+    let id = secret_id(&namespace, &key)?;
+    let target = id.clone();
+    match blocking(move || delete_secret(&target)).await {
+        Ok(()) => {
+            info!("secret_delete success: id={}", id);
+            Ok(())
+        }
+        Err(err) => {
+            error!("secret_delete failed: id={}, error={}", id, err);
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_id() {
+        assert_eq!(
+            secret_id("image-host:s3", " secretAccessKey ").unwrap(),
+            "image-host:s3:secretAccessKey"
+        );
+        assert!(secret_id("", "token").is_err());
+        assert!(secret_id("oauth", "  ").is_err());
+    }
+}
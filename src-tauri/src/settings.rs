@@ -1,13 +1,21 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::heic::HeicSequenceMode;
+use crate::link_format::LinkTemplate;
+use crate::metadata_privacy::PrivacyPolicy;
 use crate::process::{PngCompressionMode, PngOptimizationLevel};
-use log::{error, info};
+use crate::telegram::TelegramPost;
+use crate::upload_queue::UploadQueue;
+use crate::webhooks::Webhook;
+use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
-use tauri::Manager;
+use serde_json::Value;
+use tauri::{Emitter, Manager};
 use tauri_plugin_opener::OpenerExt;
 
 const SETTINGS_FILE: &str = "settings.json";
+pub(crate) const SETTINGS_CHANGED_EVENT: &str = "settings://changed";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -19,12 +27,145 @@ pub struct SettingsPayload {
     pub png_compression_mode: PngCompressionMode,
     #[serde(default)]
     pub png_optimization: PngOptimizationLevel,
+    /// 自动画质：按 SSIM 目标为每张图片选择最低画质，quality 不再生效
+    #[serde(default)]
+    pub auto_quality: bool,
+    /// 自动画质的 SSIM 下限
+    #[serde(default = "default_auto_quality_ssim")]
+    pub auto_quality_ssim: f64,
+    /// HEIC 图像序列（连拍、Live Photo）取主图或转为动画
+    #[serde(default)]
+    pub heic_sequence_mode: HeicSequenceMode,
     #[serde(default)]
     pub enable_upload_compression: bool,
     #[serde(default = "default_max_concurrent_uploads")]
     pub max_concurrent_uploads: u8,
     #[serde(default)]
     pub enable_thumbnail_cache: bool,
+    /// 全局上传限速（KB/s），0 表示不限速
+    #[serde(default)]
+    pub upload_rate_limit_kbps: u32,
+    /// 输出文件名模板，空字符串表示保持原文件名
+    #[serde(default)]
+    pub file_name_template: String,
+    /// 默认保存目录，空字符串表示每次保存时选择位置
+    #[serde(default)]
+    pub default_save_dir: String,
+    /// 日志级别（error / warn / info / debug / trace），空字符串表示默认级别
+    #[serde(default)]
+    pub log_level: String,
+    /// 退出时清空应用临时目录（待上传记录引用的文件除外）
+    #[serde(default = "default_true")]
+    pub clean_temp_on_exit: bool,
+    /// 启动时删除超过该时长（小时）的临时文件，0 表示启动时不清理
+    #[serde(default = "default_temp_max_age_hours")]
+    pub temp_max_age_hours: u32,
+    /// 监听剪贴板中的新图片
+    #[serde(default)]
+    pub clipboard_watch: bool,
+    /// 检测到剪贴板图片后自动压缩并上传
+    #[serde(default)]
+    pub clipboard_auto_upload: bool,
+    /// 全局快捷键：上传剪贴板图片，空字符串表示不注册
+    #[serde(default = "default_hotkey_upload_clipboard")]
+    pub hotkey_upload_clipboard: String,
+    /// 全局快捷键：上传最近一张截图
+    #[serde(default = "default_hotkey_upload_screenshot")]
+    pub hotkey_upload_screenshot: String,
+    /// 全局快捷键：打开快速投递窗口
+    #[serde(default = "default_hotkey_quick_drop")]
+    pub hotkey_quick_drop: String,
+    /// 启用兼容 PicGo Server 的本地上传服务
+    #[serde(default)]
+    pub picgo_server_enabled: bool,
+    /// 本地上传服务端口
+    #[serde(default = "default_picgo_server_port")]
+    pub picgo_server_port: u16,
+    /// 本地上传服务令牌，空字符串表示不校验
+    #[serde(default)]
+    pub picgo_server_token: String,
+    /// 本地上传服务使用的图床 id
+    #[serde(default)]
+    pub picgo_server_host: String,
+    /// 自定义链接格式，与内置格式同 id 时覆盖内置格式
+    #[serde(default)]
+    pub link_templates: Vec<LinkTemplate>,
+    /// 托盘等入口复制链接时使用的格式 id
+    #[serde(default = "default_link_format")]
+    pub default_link_format: String,
+    /// 上传写入图库后识别图片文字（需安装 Tesseract）
+    #[serde(default)]
+    pub ocr_auto_index: bool,
+    /// 文字识别语言（Tesseract 写法，如 chi_sim+eng）
+    #[serde(default = "default_ocr_language")]
+    pub ocr_language: String,
+    /// Tesseract 可执行文件路径，空字符串表示从 PATH 查找
+    #[serde(default)]
+    pub tesseract_path: String,
+    /// 在后台定时复查图库链接是否失效
+    #[serde(default)]
+    pub link_check_enabled: bool,
+    /// 链接复查间隔（小时）
+    #[serde(default = "default_link_check_interval_hours")]
+    pub link_check_interval_hours: u32,
+    /// 每次复查的链接数，按最久未检查的顺序轮换
+    #[serde(default = "default_link_check_batch_size")]
+    pub link_check_batch_size: u32,
+    /// 仅在接通电源时复查
+    #[serde(default = "default_true")]
+    pub link_check_require_ac: bool,
+    /// 处于计费网络（按流量计费）时暂停上传，切换到非计费网络后自动继续
+    #[serde(default)]
+    pub pause_uploads_on_metered: bool,
+    /// 断网时挂起上传，联网后自动继续；关闭后照常发起上传
+    #[serde(default = "default_true")]
+    pub pause_uploads_when_offline: bool,
+    /// 内容已上传到同一图床时跳过上传，直接使用已有链接
+    #[serde(default = "default_true")]
+    pub skip_duplicate_uploads: bool,
+    /// 从图库删除后可撤销的时长（秒），期间记录留在回收站、暂不删除图床上的文件；0 表示立即删除
+    #[serde(default = "default_delete_undo_seconds")]
+    pub delete_undo_seconds: u32,
+    /// 加密上传的解密页面地址，设置后分享链接指向该页面；为空时分享带密钥片段的密文链接
+    #[serde(default)]
+    pub encrypted_viewer_url: String,
+    /// 同意将错误报告发送到 `crash_report_endpoint`
+    #[serde(default)]
+    pub crash_reporting_enabled: bool,
+    /// 错误报告接收地址（HTTP POST JSON）
+    #[serde(default)]
+    pub crash_report_endpoint: String,
+    /// 监听文件夹，新出现的图片自动上传到对应图床
+    #[serde(default)]
+    pub watch_folders: Vec<WatchFolder>,
+    /// 监听文件夹上传成功后复制链接
+    #[serde(default = "default_true")]
+    pub watch_folder_copy_link: bool,
+    /// 图片含 GPS 或设备序列号时：warn 阻止上传并提示，strip 自动去除元数据，never 不检查
+    #[serde(default)]
+    pub metadata_privacy_policy: PrivacyPolicy,
+    /// 上传成功或失败后通知的 Webhook
+    #[serde(default)]
+    pub webhooks: Vec<Webhook>,
+    /// 指定图床上传成功后发布到 Telegram 聊天或 Telegraph 页面
+    #[serde(default)]
+    pub telegram_posts: Vec<TelegramPost>,
+    /// 批量上传结束、上传失败与监听文件夹上传后发送系统通知
+    #[serde(default = "default_true")]
+    pub desktop_notifications: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchFolder {
+    pub path: String,
+    /// 上传使用的图床 id
+    pub host: String,
+    /// 是否包含子文件夹
+    #[serde(default)]
+    pub recursive: bool,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
 }
 
 impl Default for SettingsPayload {
@@ -34,9 +175,49 @@ impl Default for SettingsPayload {
             convert_to_webp: false,
             png_compression_mode: PngCompressionMode::default(),
             png_optimization: PngOptimizationLevel::default(),
+            auto_quality: false,
+            auto_quality_ssim: default_auto_quality_ssim(),
+            heic_sequence_mode: HeicSequenceMode::default(),
             enable_upload_compression: false,
             max_concurrent_uploads: default_max_concurrent_uploads(),
             enable_thumbnail_cache: true,
+            upload_rate_limit_kbps: 0,
+            file_name_template: String::new(),
+            default_save_dir: String::new(),
+            log_level: String::new(),
+            clean_temp_on_exit: true,
+            temp_max_age_hours: default_temp_max_age_hours(),
+            clipboard_watch: false,
+            clipboard_auto_upload: false,
+            hotkey_upload_clipboard: default_hotkey_upload_clipboard(),
+            hotkey_upload_screenshot: default_hotkey_upload_screenshot(),
+            hotkey_quick_drop: default_hotkey_quick_drop(),
+            picgo_server_enabled: false,
+            picgo_server_port: default_picgo_server_port(),
+            picgo_server_token: String::new(),
+            picgo_server_host: String::new(),
+            link_templates: Vec::new(),
+            default_link_format: default_link_format(),
+            ocr_auto_index: false,
+            ocr_language: default_ocr_language(),
+            tesseract_path: String::new(),
+            link_check_enabled: false,
+            link_check_interval_hours: default_link_check_interval_hours(),
+            link_check_batch_size: default_link_check_batch_size(),
+            link_check_require_ac: true,
+            pause_uploads_on_metered: false,
+            pause_uploads_when_offline: true,
+            skip_duplicate_uploads: true,
+            delete_undo_seconds: default_delete_undo_seconds(),
+            encrypted_viewer_url: String::new(),
+            crash_reporting_enabled: false,
+            crash_report_endpoint: String::new(),
+            watch_folders: Vec::new(),
+            watch_folder_copy_link: true,
+            metadata_privacy_policy: PrivacyPolicy::default(),
+            webhooks: Vec::new(),
+            telegram_posts: Vec::new(),
+            desktop_notifications: true,
         }
     }
 }
@@ -48,29 +229,232 @@ impl SettingsPayload {
             convert_to_webp: self.convert_to_webp,
             png_compression_mode: self.png_compression_mode,
             png_optimization: self.png_optimization,
+            auto_quality: self.auto_quality,
+            auto_quality_ssim: clamp_target_ssim(self.auto_quality_ssim),
+            heic_sequence_mode: self.heic_sequence_mode,
             enable_upload_compression: self.enable_upload_compression,
             max_concurrent_uploads: self
                 .max_concurrent_uploads
                 .clamp(1, default_max_concurrent_uploads()),
             enable_thumbnail_cache: self.enable_thumbnail_cache,
+            upload_rate_limit_kbps: self.upload_rate_limit_kbps,
+            file_name_template: self.file_name_template.trim().to_string(),
+            default_save_dir: self.default_save_dir.trim().to_string(),
+            log_level: self.log_level.trim().to_ascii_lowercase(),
+            clean_temp_on_exit: self.clean_temp_on_exit,
+            temp_max_age_hours: self.temp_max_age_hours.min(MAX_TEMP_AGE_HOURS),
+            clipboard_watch: self.clipboard_watch,
+            clipboard_auto_upload: self.clipboard_auto_upload,
+            hotkey_upload_clipboard: self.hotkey_upload_clipboard.trim().to_string(),
+            hotkey_upload_screenshot: self.hotkey_upload_screenshot.trim().to_string(),
+            hotkey_quick_drop: self.hotkey_quick_drop.trim().to_string(),
+            picgo_server_enabled: self.picgo_server_enabled,
+            picgo_server_port: if self.picgo_server_port == 0 {
+                default_picgo_server_port()
+            } else {
+                self.picgo_server_port
+            },
+            picgo_server_token: self.picgo_server_token.trim().to_string(),
+            picgo_server_host: self.picgo_server_host.trim().to_string(),
+            link_templates: self
+                .link_templates
+                .into_iter()
+                .map(|template| LinkTemplate {
+                    id: template.id.trim().to_string(),
+                    name: template.name.trim().to_string(),
+                    template: template.template,
+                })
+                .filter(|template| !template.id.is_empty() && !template.template.is_empty())
+                .collect(),
+            default_link_format: match self.default_link_format.trim() {
+                "" => default_link_format(),
+                format => format.to_string(),
+            },
+            ocr_auto_index: self.ocr_auto_index,
+            ocr_language: match self.ocr_language.trim() {
+                "" => default_ocr_language(),
+                lang => lang.to_string(),
+            },
+            tesseract_path: self.tesseract_path.trim().to_string(),
+            link_check_enabled: self.link_check_enabled,
+            link_check_interval_hours: self
+                .link_check_interval_hours
+                .clamp(1, MAX_LINK_CHECK_INTERVAL_HOURS),
+            link_check_batch_size: self
+                .link_check_batch_size
+                .clamp(1, MAX_LINK_CHECK_BATCH_SIZE),
+            link_check_require_ac: self.link_check_require_ac,
+            pause_uploads_on_metered: self.pause_uploads_on_metered,
+            pause_uploads_when_offline: self.pause_uploads_when_offline,
+            skip_duplicate_uploads: self.skip_duplicate_uploads,
+            delete_undo_seconds: self.delete_undo_seconds.min(MAX_DELETE_UNDO_SECONDS),
+            encrypted_viewer_url: self.encrypted_viewer_url.trim().to_string(),
+            crash_reporting_enabled: self.crash_reporting_enabled,
+            crash_report_endpoint: self.crash_report_endpoint.trim().to_string(),
+            watch_folders: self
+                .watch_folders
+                .into_iter()
+                .map(|folder| WatchFolder {
+                    path: folder.path.trim().to_string(),
+                    host: folder.host.trim().to_string(),
+                    ..folder
+                })
+                .filter(|folder| !folder.path.is_empty())
+                .collect(),
+            watch_folder_copy_link: self.watch_folder_copy_link,
+            metadata_privacy_policy: self.metadata_privacy_policy,
+            webhooks: self
+                .webhooks
+                .into_iter()
+                .map(|hook| Webhook {
+                    id: hook.id.trim().to_string(),
+                    url: hook.url.trim().to_string(),
+                    secret: hook.secret.trim().to_string(),
+                    ..hook
+                })
+                .filter(|hook| !hook.url.is_empty())
+                .collect(),
+            telegram_posts: self
+                .telegram_posts
+                .into_iter()
+                .map(|post| TelegramPost {
+                    id: post.id.trim().to_string(),
+                    host: post.host.trim().to_string(),
+                    token: post.token.trim().to_string(),
+                    chat_id: post.chat_id.trim().to_string(),
+                    page_path: post.page_path.trim().to_string(),
+                    ..post
+                })
+                .filter(|post| !post.host.is_empty() && !post.token.is_empty())
+                .collect(),
+            desktop_notifications: self.desktop_notifications,
         }
     }
 }
 
+/// `settings://changed` 事件负载：`scope` 区分全局设置与图床配置
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "scope", rename_all = "camelCase")]
+pub enum SettingsChange {
+    App {
+        settings: SettingsPayload,
+    },
+    /// `values` 为写入配置文件的内容（敏感字段为占位符），删除配置时为 null
+    #[serde(rename_all = "camelCase")]
+    ImageHost {
+        plugin_id: String,
+        values: Option<Value>,
+    },
+}
+
+/// 广播设置变更，供其他窗口与后端子系统即时响应
+pub(crate) fn broadcast_settings_change(app: &tauri::AppHandle, change: SettingsChange) {
+    if let Err(err) = app.emit(SETTINGS_CHANGED_EVENT, change) {
+        warn!("emit {} failed: {}", SETTINGS_CHANGED_EVENT, err);
+    }
+}
+
+/// 将设置应用到后端子系统：上传队列并发数、全局限速、文件名模板、日志级别、剪贴板监听、
+/// 计费网络暂停、全局快捷键、PicGo 兼容服务与文件夹监听
+pub(crate) fn apply_runtime_settings(app: &tauri::AppHandle, settings: &SettingsPayload) {
+    if let Some(queue) = app.try_state::<UploadQueue>() {
+        queue.set_max_concurrent(settings.max_concurrent_uploads as usize);
+    }
+    crate::throttle::set_global_limit(settings.upload_rate_limit_kbps);
+    crate::filename_template::set_global_template(&settings.file_name_template);
+    crate::logging::apply_level(&settings.log_level);
+    crate::clipboard_watcher::apply(settings.clipboard_watch, settings.clipboard_auto_upload);
+    crate::network_status::apply(app, settings);
+    #[cfg(desktop)]
+    {
+        crate::hotkeys::apply(app, settings);
+        crate::picgo_server::apply(app, settings);
+        crate::watch_folder::apply(app, settings);
+    }
+}
+
 const fn default_max_concurrent_uploads() -> u8 {
     5
 }
 
-fn ensure_config_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let dir = app
-        .path()
-        .app_config_dir()
-        .map_err(|e| format!("app_config_dir: {e}"))?;
+const fn default_true() -> bool {
+    true
+}
+
+/// 临时文件最长保留一年
+pub(crate) const MAX_TEMP_AGE_HOURS: u32 = 24 * 365;
+
+const fn default_temp_max_age_hours() -> u32 {
+    24
+}
+
+fn default_hotkey_upload_clipboard() -> String {
+    "CmdOrCtrl+Shift+U".to_string()
+}
+
+fn default_hotkey_upload_screenshot() -> String {
+    "CmdOrCtrl+Shift+S".to_string()
+}
+
+fn default_hotkey_quick_drop() -> String {
+    "CmdOrCtrl+Shift+D".to_string()
+}
+
+fn default_ocr_language() -> String {
+    "eng".to_string()
+}
+
+/// 链接复查间隔最长 30 天
+pub(crate) const MAX_LINK_CHECK_INTERVAL_HOURS: u32 = 24 * 30;
+pub(crate) const MAX_LINK_CHECK_BATCH_SIZE: u32 = 500;
+
+const fn default_link_check_interval_hours() -> u32 {
+    24
+}
+
+const fn default_link_check_batch_size() -> u32 {
+    50
+}
+
+const fn default_auto_quality_ssim() -> f64 {
+    0.98
+}
+
+/// 限制自动画质的 SSIM 目标范围，非法值回退为默认值
+pub(crate) fn clamp_target_ssim(value: f64) -> f64 {
+    if value.is_finite() {
+        value.clamp(
+            crate::quality_metrics::MIN_TARGET_SSIM,
+            crate::quality_metrics::MAX_TARGET_SSIM,
+        )
+    } else {
+        default_auto_quality_ssim()
+    }
+}
+
+/// 删除撤销窗口最长 5 分钟
+pub(crate) const MAX_DELETE_UNDO_SECONDS: u32 = 300;
+
+const fn default_delete_undo_seconds() -> u32 {
+    15
+}
+
+fn default_link_format() -> String {
+    "link".to_string()
+}
+
+/// 与 PicGo Server 默认端口一致，编辑器无需额外配置
+const fn default_picgo_server_port() -> u16 {
+    36677
+}
+
+pub(crate) fn ensure_config_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = crate::portable::app_config_dir(&app).map_err(|e| format!("app_config_dir: {e}"))?;
     fs::create_dir_all(&dir).map_err(|e| format!("create_dir_all {}: {e}", dir.display()))?;
     Ok(dir.join(SETTINGS_FILE))
 }
 
-fn read_payload(path: &Path) -> Result<SettingsPayload, String> {
+pub(crate) fn read_payload(path: &Path) -> Result<SettingsPayload, String> {
     if !path.exists() {
         return Ok(SettingsPayload::default());
     }
@@ -80,7 +464,7 @@ fn read_payload(path: &Path) -> Result<SettingsPayload, String> {
     Ok(parsed.clamped())
 }
 
-fn write_payload(path: &Path, payload: SettingsPayload) -> Result<(), String> {
+pub(crate) fn write_payload(path: &Path, payload: SettingsPayload) -> Result<(), String> {
     let sanitized = payload.clamped();
     let text =
         serde_json::to_string_pretty(&sanitized).map_err(|e| format!("serialize settings: {e}"))?;
@@ -88,6 +472,17 @@ fn write_payload(path: &Path, payload: SettingsPayload) -> Result<(), String> {
     Ok(())
 }
 
+/// 供后端子系统读取当前设置，读取失败时回退到默认值
+pub(crate) fn current_settings(app: &tauri::AppHandle) -> SettingsPayload {
+    match ensure_config_path(app).and_then(|path| read_payload(&path)) {
+        Ok(payload) => payload,
+        Err(err) => {
+            error!("current_settings failed, using defaults: {}", err);
+            SettingsPayload::default()
+        }
+    }
+}
+
 #[tauri::command]
 pub fn load_settings(app: tauri::AppHandle) -> Result<SettingsPayload, String> {
     let path = ensure_config_path(&app)?;
@@ -110,9 +505,22 @@ pub fn load_settings(app: tauri::AppHandle) -> Result<SettingsPayload, String> {
 #[tauri::command]
 pub fn save_settings(app: tauri::AppHandle, settings: SettingsPayload) -> Result<(), String> {
     let path = ensure_config_path(&app)?;
-    match write_payload(&path, settings) {
+    let previous = read_payload(&path).unwrap_or_default();
+    // 敏感字段写入钥匙串，settings.json 与广播中只保留占位符
+    let mut sanitized = settings.clamped();
+    let result = crate::settings_secrets::store_settings_secrets(&previous, &mut sanitized)
+        .and_then(|()| write_payload(&path, sanitized.clone()));
+    match result {
         Ok(()) => {
             info!("save_settings success: path={}", path.display());
+            crate::settings_watcher::record_app_save(&sanitized);
+            apply_runtime_settings(&app, &sanitized);
+            broadcast_settings_change(
+                &app,
+                SettingsChange::App {
+                    settings: sanitized,
+                },
+            );
             Ok(())
         }
         Err(err) => {
@@ -128,10 +536,7 @@ pub fn save_settings(app: tauri::AppHandle, settings: SettingsPayload) -> Result
 
 #[tauri::command]
 pub fn open_log_dir(app: tauri::AppHandle) -> Result<(), String> {
-    let path = app
-        .path()
-        .app_log_dir()
-        .map_err(|e| format!("app_log_dir: {e}"))?;
+    let path = crate::portable::app_log_dir(&app).map_err(|e| format!("app_log_dir: {e}"))?;
 
     if let Err(err) = fs::create_dir_all(&path) {
         error!("open_log_dir create_dir_all failed: {}", err);
@@ -165,6 +570,18 @@ mod tests {
             enable_upload_compression: true,
             max_concurrent_uploads: 3,
             enable_thumbnail_cache: true,
+            upload_rate_limit_kbps: 0,
+            file_name_template: String::new(),
+            default_save_dir: String::new(),
+            log_level: String::new(),
+            clean_temp_on_exit: true,
+            temp_max_age_hours: 24,
+            clipboard_watch: false,
+            clipboard_auto_upload: false,
+            hotkey_upload_clipboard: String::new(),
+            hotkey_upload_screenshot: String::new(),
+            hotkey_quick_drop: String::new(),
+            ..Default::default()
         };
 
         let json = serde_json::to_string_pretty(&settings).unwrap();
@@ -0,0 +1,188 @@
+/*
+全局设置中的敏感字段（Telegram token、Webhook 签名密钥、PicGo Server 访问令牌）存放在系统钥匙串：
+1) settings.json、`load_settings` 与 `settings://changed` 中这些字段以 `SECRET_PLACEHOLDER` 表示，
+   保存时字段值仍为占位符代表未修改，为空代表删除；
+2) 列表中的条目以 `id` 区分钥匙串条目（`settings:<类型>:<id>`），新条目保存时分配 id，
+   条目被删除后对应的钥匙串条目一并删除；单个字段以字段名为键（如 `settings:picgoServerToken`）；
+3) 需要真实值的子系统（发布、测试等）通过 `resolve_settings_secret` 按需读取；
+4) 启动时把早期版本以明文保存的字段迁移到钥匙串；没有系统钥匙串的平台（Android）保持明文。
+*/
+
+use std::collections::BTreeSet;
+
+use log::{info, warn};
+
+use crate::image_hosts::SECRET_PLACEHOLDER;
+use crate::secrets::{delete_secret, keyring_available, read_secret, secret_id, write_secret};
+use crate::settings::SettingsPayload;
+
+const NAMESPACE: &str = "settings";
+
+/// PicGo Server 的访问令牌
+pub(crate) const PICGO_SERVER_TOKEN_KEY: &str = "picgoServerToken";
+
+/// Telegram / Telegraph 发布的 token
+pub(crate) fn telegram_key(id: &str) -> String {
+    format!("telegram:{}", id)
+}
+
+/// Webhook 的签名密钥
+pub(crate) fn webhook_key(id: &str) -> String {
+    format!("webhook:{}", id)
+}
+
+/// 为尚未分配 id 的列表条目生成 id
+fn assign_ids(payload: &mut SettingsPayload) {
+    let ids = payload
+        .telegram_posts
+        .iter_mut()
+        .map(|post| &mut post.id)
+        .chain(payload.webhooks.iter_mut().map(|hook| &mut hook.id));
+    for id in ids {
+        if id.trim().is_empty() {
+            *id = uuid::Uuid::new_v4().simple().to_string();
+        }
+    }
+}
+
+/// 设置中所有敏感字段：(钥匙串键, 字段值)
+fn secret_fields(payload: &mut SettingsPayload) -> Vec<(String, &mut String)> {
+    std::iter::once((
+        PICGO_SERVER_TOKEN_KEY.to_string(),
+        &mut payload.picgo_server_token,
+    ))
+    .chain(
+        payload
+            .telegram_posts
+            .iter_mut()
+            .map(|post| (telegram_key(&post.id), &mut post.token)),
+    )
+    .chain(
+        payload
+            .webhooks
+            .iter_mut()
+            .map(|hook| (webhook_key(&hook.id), &mut hook.secret)),
+    )
+    .collect()
+}
+
+fn secret_keys(payload: &SettingsPayload) -> BTreeSet<String> {
+    let mut payload = payload.clone();
+    secret_fields(&mut payload)
+        .into_iter()
+        .filter(|(_, value)| value.as_str() == SECRET_PLACEHOLDER)
+        .map(|(key, _)| key)
+        .collect()
+}
+
+/// 保存前把敏感字段写入钥匙串，字段改为占位符；`previous` 中不再使用的条目从钥匙串删除
+pub(crate) fn store_settings_secrets(
+    previous: &SettingsPayload,
+    payload: &mut SettingsPayload,
+) -> Result<(), String> {
+    if !keyring_available() {
+        return Ok(());
+    }
+    assign_ids(payload);
+    for (key, value) in secret_fields(payload) {
+        let id = secret_id(NAMESPACE, &key)?;
+        if value.as_str() == SECRET_PLACEHOLDER {
+            continue;
+        }
+        if value.is_empty() {
+            delete_secret(&id)?;
+            continue;
+        }
+        write_secret(&id, value)?;
+        *value = SECRET_PLACEHOLDER.to_string();
+    }
+    let current = secret_keys(payload);
+    for key in secret_keys(previous).difference(&current) {
+        if let Err(err) = delete_secret(&secret_id(NAMESPACE, key)?) {
+            warn!("delete settings secret {} failed: {}", key, err);
+        }
+    }
+    Ok(())
+}
+
+/// 返回敏感字段的真实值；`value` 不是占位符时原样返回
+pub(crate) fn resolve_settings_secret(key: &str, value: &str) -> Result<String, String> {
+    if value != SECRET_PLACEHOLDER {
+        return Ok(value.to_string());
+    }
+    read_secret(&secret_id(NAMESPACE, key)?)?
+        .ok_or_else(|| format!("钥匙串中缺少设置项 {}，请重新填写", key))
+}
+
+/// 把全部敏感字段替换为真实值（带密钥导出配置时使用）
+pub(crate) fn resolve_all_settings_secrets(
+    mut payload: SettingsPayload,
+) -> Result<SettingsPayload, String> {
+    for (key, value) in secret_fields(&mut payload) {
+        *value = resolve_settings_secret(&key, value)?;
+    }
+    Ok(payload)
+}
+
+/// 启动时将 settings.json 中仍为明文的敏感字段迁移到钥匙串，返回迁移的字段数
+pub(crate) fn migrate_plaintext_settings_secrets(app: &tauri::AppHandle) -> Result<usize, String> {
+    if !keyring_available() {
+        return Ok(0);
+    }
+    let path = crate::settings::ensure_config_path(app)?;
+    if !path.exists() {
+        return Ok(0);
+    }
+    let mut payload = crate::settings::read_payload(&path)?;
+    let before = payload.clone();
+    let plaintext = secret_fields(&mut payload)
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty() && value.as_str() != SECRET_PLACEHOLDER)
+        .count();
+    if plaintext == 0 {
+        return Ok(0);
+    }
+    store_settings_secrets(&before, &mut payload)?;
+    crate::settings::write_payload(&path, payload)?;
+    info!(
+        "migrate_plaintext_settings_secrets success: migrated={}",
+        plaintext
+    );
+    Ok(plaintext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::TelegramPost;
+
+    fn post(id: &str, token: &str) -> TelegramPost {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "host": "s3",
+            "token": token,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_secret_keys_and_ids() {
+        let mut payload = SettingsPayload {
+            telegram_posts: vec![post("a", SECRET_PLACEHOLDER), post("", "1:abc")],
+            picgo_server_token: SECRET_PLACEHOLDER.to_string(),
+            ..Default::default()
+        };
+        assign_ids(&mut payload);
+        assert_eq!(payload.telegram_posts[0].id, "a");
+        assert!(!payload.telegram_posts[1].id.is_empty());
+        // 只有占位符字段对应钥匙串条目
+        assert_eq!(
+            secret_keys(&payload).into_iter().collect::<Vec<_>>(),
+            vec![PICGO_SERVER_TOKEN_KEY.to_string(), "telegram:a".to_string()]
+        );
+        assert_eq!(
+            resolve_settings_secret("telegram:a", "1:abc").unwrap(),
+            "1:abc"
+        );
+    }
+}
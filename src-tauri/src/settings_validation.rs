@@ -0,0 +1,732 @@
+/*
+设置校验：
+1) `validate_settings` 接收原始 JSON（允许只包含部分字段），逐字段检查类型与取值范围，
+   返回带字段名与错误码的结构化结果，供设置页内联提示，而不是等到保存时失败；
+2) `error` 级别表示保存时会被拒绝或被强制修正的值，`warning` 级别表示可以保存但可能不符合预期；
+3) 未知字段给出警告，便于发现拼写错误或旧版本遗留字段。
+*/
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::filename_template::{PLACEHOLDERS, unknown_placeholders};
+use crate::link_format::{self, LinkTemplate};
+use crate::metadata_privacy::PrivacyPolicy;
+use crate::process::{PngCompressionMode, PngOptimizationLevel};
+use crate::settings::{SettingsPayload, WatchFolder};
+
+const MAX_CONCURRENT_UPLOADS: u64 = 10;
+/// 低于该值的限速通常是误填（如把 MB/s 当作 KB/s）
+const LOW_RATE_LIMIT_KBPS: u64 = 16;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsIssue {
+    pub field: String,
+    pub severity: IssueSeverity,
+    /// 机器可读的错误码，如 `outOfRange`、`invalidType`
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsValidation {
+    /// 不存在 error 级别问题时为 true
+    pub valid: bool,
+    pub issues: Vec<SettingsIssue>,
+}
+
+#[derive(Default)]
+struct Issues(Vec<SettingsIssue>);
+
+impl Issues {
+    fn push(&mut self, field: &str, severity: IssueSeverity, code: &str, message: String) {
+        self.0.push(SettingsIssue {
+            field: field.to_string(),
+            severity,
+            code: code.to_string(),
+            message,
+        });
+    }
+
+    fn error(&mut self, field: &str, code: &str, message: String) {
+        self.push(field, IssueSeverity::Error, code, message);
+    }
+
+    fn warning(&mut self, field: &str, code: &str, message: String) {
+        self.push(field, IssueSeverity::Warning, code, message);
+    }
+
+    fn integer(
+        &mut self,
+        map: &Map<String, Value>,
+        field: &str,
+        min: u64,
+        max: u64,
+    ) -> Option<u64> {
+        let value = map.get(field)?;
+        match value.as_u64() {
+            Some(n) if (min..=max).contains(&n) => Some(n),
+            Some(_) => {
+                self.error(field, "outOfRange", format!("取值须在 {min}-{max} 之间"));
+                None
+            }
+            None if value.is_number() => {
+                self.error(
+                    field,
+                    "outOfRange",
+                    format!("取值须为 {min}-{max} 之间的整数"),
+                );
+                None
+            }
+            None => {
+                self.error(field, "invalidType", "须为整数".to_string());
+                None
+            }
+        }
+    }
+
+    fn number(&mut self, map: &Map<String, Value>, field: &str, min: f64, max: f64) {
+        let Some(value) = map.get(field) else {
+            return;
+        };
+        match value.as_f64() {
+            Some(n) if (min..=max).contains(&n) => {}
+            Some(_) => self.error(field, "outOfRange", format!("取值须在 {min}-{max} 之间")),
+            None => self.error(field, "invalidType", "须为数字".to_string()),
+        }
+    }
+
+    fn boolean(&mut self, map: &Map<String, Value>, field: &str) {
+        if map.get(field).is_some_and(|value| !value.is_boolean()) {
+            self.error(field, "invalidType", "须为布尔值".to_string());
+        }
+    }
+
+    fn variant<T: serde::de::DeserializeOwned>(
+        &mut self,
+        map: &Map<String, Value>,
+        field: &str,
+        allowed: &str,
+    ) {
+        if let Some(value) = map.get(field)
+            && serde_json::from_value::<T>(value.clone()).is_err()
+        {
+            self.error(field, "invalidValue", format!("可选值: {allowed}"));
+        }
+    }
+}
+
+fn validate_file_name_template(issues: &mut Issues, value: &Value) {
+    const FIELD: &str = "fileNameTemplate";
+    let Some(template) = value.as_str() else {
+        issues.error(FIELD, "invalidType", "须为字符串".to_string());
+        return;
+    };
+    let template = template.trim();
+    if template.is_empty() {
+        return;
+    }
+    let unknown = unknown_placeholders(template);
+    if !unknown.is_empty() {
+        issues.warning(
+            FIELD,
+            "unknownPlaceholder",
+            format!(
+                "不支持的占位符会原样保留: {{{}}}；可用: {}",
+                unknown.join("}, {"),
+                PLACEHOLDERS.join(", ")
+            ),
+        );
+    }
+    if template.contains(['/', '\\']) {
+        issues.warning(
+            FIELD,
+            "pathSeparator",
+            "模板只决定文件名，路径分隔符会被替换为 _".to_string(),
+        );
+    }
+    let distinct = ["{uuid}", "{random", "{hash", "{original}", "{time}"];
+    if !distinct.iter().any(|token| template.contains(token)) {
+        issues.warning(
+            FIELD,
+            "notUnique",
+            "模板不含 {uuid}、{random}、{hash}、{original} 或 {time}，多个文件可能同名".to_string(),
+        );
+    }
+}
+
+fn validate_default_save_dir(issues: &mut Issues, value: &Value) {
+    const FIELD: &str = "defaultSaveDir";
+    let Some(dir) = value.as_str() else {
+        issues.error(FIELD, "invalidType", "须为字符串".to_string());
+        return;
+    };
+    let dir = std::path::Path::new(dir.trim());
+    if dir.as_os_str().is_empty() {
+        return;
+    }
+    if !dir.is_absolute() {
+        issues.error(FIELD, "notAbsolute", "须为绝对路径".to_string());
+    } else if !dir.is_dir() {
+        issues.warning(
+            FIELD,
+            "notFound",
+            "目录不存在，保存时会自动创建".to_string(),
+        );
+    }
+}
+
+#[cfg(desktop)]
+fn validate_hotkeys(issues: &mut Issues, map: &Map<String, Value>) {
+    let mut seen: Vec<(u32, &str)> = Vec::new();
+    for field in [
+        "hotkeyUploadClipboard",
+        "hotkeyUploadScreenshot",
+        "hotkeyQuickDrop",
+    ] {
+        let Some(value) = map.get(field) else {
+            continue;
+        };
+        let Some(text) = value.as_str() else {
+            issues.error(field, "invalidType", "须为字符串".to_string());
+            continue;
+        };
+        match crate::hotkeys::parse_shortcut(text) {
+            Ok(Some(shortcut)) => {
+                let key = shortcut.id();
+                if let Some((_, other)) = seen.iter().find(|(seen_key, _)| *seen_key == key) {
+                    issues.error(field, "duplicate", format!("与 {other} 使用了相同的快捷键"));
+                } else {
+                    seen.push((key, field));
+                }
+            }
+            Ok(None) => {}
+            Err(err) => issues.error(field, "invalidValue", err),
+        }
+    }
+}
+
+fn validate_picgo_server(issues: &mut Issues, map: &Map<String, Value>) {
+    issues.integer(map, "picgoServerPort", 1, u16::MAX as u64);
+    for field in ["picgoServerToken", "picgoServerHost"] {
+        if map.get(field).is_some_and(|value| !value.is_string()) {
+            issues.error(field, "invalidType", "须为字符串".to_string());
+        }
+    }
+    let enabled = map.get("picgoServerEnabled").and_then(Value::as_bool) == Some(true);
+    let host_empty = map
+        .get("picgoServerHost")
+        .and_then(Value::as_str)
+        .is_none_or(|host| host.trim().is_empty());
+    if enabled && host_empty {
+        issues.warning(
+            "picgoServerHost",
+            "missing",
+            "未选择图床，本地服务收到的上传请求将失败".to_string(),
+        );
+    }
+}
+
+fn validate_link_templates(issues: &mut Issues, map: &Map<String, Value>) {
+    const FIELD: &str = "linkTemplates";
+    let mut custom = Vec::new();
+    if let Some(value) = map.get(FIELD) {
+        match serde_json::from_value::<Vec<LinkTemplate>>(value.clone()) {
+            Ok(templates) => custom = templates,
+            Err(_) => issues.error(
+                FIELD,
+                "invalidType",
+                "须为 { id, name, template } 数组".to_string(),
+            ),
+        }
+    }
+    let mut seen: Vec<&str> = Vec::new();
+    for template in &custom {
+        let id = template.id.trim();
+        if id.is_empty() || template.template.is_empty() {
+            issues.error(FIELD, "missing", "格式 id 与模板不能为空".to_string());
+            continue;
+        }
+        if seen.contains(&id) {
+            issues.error(FIELD, "duplicate", format!("格式 id 重复: {id}"));
+            continue;
+        }
+        seen.push(id);
+        let unknown = link_format::unknown_placeholders(&template.template);
+        if !unknown.is_empty() {
+            issues.warning(
+                FIELD,
+                "unknownPlaceholder",
+                format!(
+                    "格式 {id} 中不支持的占位符会原样保留: {{{}}}；可用: {}",
+                    unknown.join("}, {"),
+                    link_format::PLACEHOLDERS.join(", ")
+                ),
+            );
+        }
+    }
+
+    if let Some(value) = map.get("defaultLinkFormat") {
+        match value.as_str() {
+            Some(format) => {
+                let format = format.trim();
+                if !format.is_empty()
+                    && !link_format::templates(&custom)
+                        .iter()
+                        .any(|template| template.id == format)
+                {
+                    issues.error(
+                        "defaultLinkFormat",
+                        "invalidValue",
+                        format!("未知的链接格式: {format}"),
+                    );
+                }
+            }
+            None => issues.error("defaultLinkFormat", "invalidType", "须为字符串".to_string()),
+        }
+    }
+}
+
+fn validate_ocr(issues: &mut Issues, map: &Map<String, Value>) {
+    if let Some(value) = map.get("ocrLanguage") {
+        match value.as_str() {
+            Some(lang) => {
+                let lang = lang.trim();
+                if !lang.is_empty()
+                    && let Err(err) = crate::ocr::validate_language(lang)
+                {
+                    issues.error("ocrLanguage", "invalidValue", err);
+                }
+            }
+            None => issues.error("ocrLanguage", "invalidType", "须为字符串".to_string()),
+        }
+    }
+    if let Some(value) = map.get("tesseractPath") {
+        match value.as_str() {
+            Some(path) => {
+                let path = path.trim();
+                if !path.is_empty() && !std::path::Path::new(path).is_file() {
+                    issues.warning("tesseractPath", "notFound", format!("文件不存在: {path}"));
+                }
+            }
+            None => issues.error("tesseractPath", "invalidType", "须为字符串".to_string()),
+        }
+    }
+}
+
+fn validate_encrypted_viewer(issues: &mut Issues, map: &Map<String, Value>) {
+    const FIELD: &str = "encryptedViewerUrl";
+    let Some(value) = map.get(FIELD) else {
+        return;
+    };
+    let Some(viewer) = value.as_str() else {
+        issues.error(FIELD, "invalidType", "须为字符串".to_string());
+        return;
+    };
+    let viewer = viewer.trim();
+    if viewer.is_empty() {
+        return;
+    }
+    match url::Url::parse(viewer) {
+        Ok(url) if url.scheme() == "https" => {}
+        Ok(url) if url.scheme() == "http" => issues.warning(
+            FIELD,
+            "insecure",
+            "解密页面未使用 HTTPS，浏览器可能无法使用 WebCrypto 解密".to_string(),
+        ),
+        _ => issues.error(
+            FIELD,
+            "invalidValue",
+            format!("无效的地址: {viewer}，须为 http(s) 地址"),
+        ),
+    }
+}
+
+fn validate_crash_reporting(issues: &mut Issues, map: &Map<String, Value>) {
+    const FIELD: &str = "crashReportEndpoint";
+    let endpoint = match map.get(FIELD) {
+        Some(value) => match value.as_str() {
+            Some(endpoint) => endpoint.trim(),
+            None => {
+                issues.error(FIELD, "invalidType", "须为字符串".to_string());
+                return;
+            }
+        },
+        None => "",
+    };
+    if !endpoint.is_empty() {
+        match url::Url::parse(endpoint) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            _ => issues.error(
+                FIELD,
+                "invalidValue",
+                format!("无效的地址: {endpoint}，须为 http(s) 地址"),
+            ),
+        }
+    }
+    let enabled = map.get("crashReportingEnabled").and_then(Value::as_bool) == Some(true);
+    if enabled && endpoint.is_empty() && map.contains_key(FIELD) {
+        issues.warning(
+            FIELD,
+            "missing",
+            "未设置接收地址，错误报告只保存在本地".to_string(),
+        );
+    }
+}
+
+fn validate_watch_folders(issues: &mut Issues, map: &Map<String, Value>) {
+    const FIELD: &str = "watchFolders";
+    let Some(value) = map.get(FIELD) else {
+        return;
+    };
+    let Ok(folders) = serde_json::from_value::<Vec<WatchFolder>>(value.clone()) else {
+        issues.error(
+            FIELD,
+            "invalidType",
+            "须为 { path, host, recursive, enabled } 数组".to_string(),
+        );
+        return;
+    };
+    let mut seen: Vec<&str> = Vec::new();
+    for folder in folders.iter().filter(|folder| folder.enabled) {
+        let path = folder.path.trim();
+        if path.is_empty() {
+            issues.error(FIELD, "missing", "监听文件夹路径不能为空".to_string());
+            continue;
+        }
+        if seen.contains(&path) {
+            issues.error(FIELD, "duplicate", format!("重复的监听文件夹: {path}"));
+            continue;
+        }
+        seen.push(path);
+        let dir = std::path::Path::new(path);
+        if !dir.is_absolute() {
+            issues.error(FIELD, "notAbsolute", format!("须为绝对路径: {path}"));
+        } else if !dir.is_dir() {
+            issues.warning(FIELD, "notFound", format!("文件夹不存在: {path}"));
+        }
+        if folder.host.trim().is_empty() {
+            issues.error(FIELD, "missing", format!("未选择 {path} 使用的图床"));
+        }
+    }
+}
+
+fn validate_webhooks(issues: &mut Issues, map: &Map<String, Value>) {
+    const FIELD: &str = "webhooks";
+    let Some(value) = map.get(FIELD) else {
+        return;
+    };
+    let Ok(hooks) = serde_json::from_value::<Vec<crate::webhooks::Webhook>>(value.clone()) else {
+        issues.error(
+            FIELD,
+            "invalidType",
+            "须为 { url, secret, events, enabled } 数组，events 可选 success、failure".to_string(),
+        );
+        return;
+    };
+    for hook in hooks.iter().filter(|hook| hook.enabled) {
+        let url = hook.url.trim();
+        match url::Url::parse(url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => {}
+            _ => issues.error(
+                FIELD,
+                "invalidValue",
+                format!("无效的地址: {url}，须为 http(s) 地址"),
+            ),
+        }
+        if hook.events.is_empty() {
+            issues.warning(FIELD, "noEvents", format!("{url} 未订阅任何事件"));
+        }
+    }
+}
+
+fn validate_telegram_posts(issues: &mut Issues, map: &Map<String, Value>) {
+    use crate::image_hosts::SECRET_PLACEHOLDER;
+    use crate::telegram::{PostTarget, TelegramPost, is_bot_token};
+    const FIELD: &str = "telegramPosts";
+    let Some(value) = map.get(FIELD) else {
+        return;
+    };
+    let Ok(posts) = serde_json::from_value::<Vec<TelegramPost>>(value.clone()) else {
+        issues.error(
+            FIELD,
+            "invalidType",
+            "须为 { host, target, token, chatId, content, pagePath, enabled } 数组，target 可选 chat、telegraph，content 可选 link、photo"
+                .to_string(),
+        );
+        return;
+    };
+    for post in posts.iter().filter(|post| post.enabled) {
+        let host = post.host.trim();
+        if host.is_empty() {
+            issues.error(FIELD, "missing", "未选择触发发布的图床".to_string());
+        }
+        if post.token.trim().is_empty() {
+            issues.error(FIELD, "missing", format!("{host} 的发布未填写 token"));
+            continue;
+        }
+        if post.target == PostTarget::Chat {
+            // 已存入钥匙串的 token 以占位符表示，无法校验格式
+            if post.token != SECRET_PLACEHOLDER && !is_bot_token(&post.token) {
+                issues.error(
+                    FIELD,
+                    "invalidValue",
+                    format!("{host} 的 Bot token 格式应为 123456:ABC-DEF"),
+                );
+            }
+            if post.chat_id.trim().is_empty() {
+                issues.error(FIELD, "missing", format!("{host} 的发布未填写 chat_id"));
+            }
+        }
+    }
+}
+
+/// 校验设置 JSON，返回字段级问题列表
+pub(crate) fn validate(payload: &Value) -> SettingsValidation {
+    let mut issues = Issues::default();
+    let Some(map) = payload.as_object() else {
+        issues.error("", "invalidType", "设置须为 JSON 对象".to_string());
+        return SettingsValidation {
+            valid: false,
+            issues: issues.0,
+        };
+    };
+
+    issues.integer(map, "quality", 0, 100);
+    issues.integer(map, "maxConcurrentUploads", 1, MAX_CONCURRENT_UPLOADS);
+    if let Some(kbps) = issues.integer(map, "uploadRateLimitKbps", 0, u32::MAX as u64)
+        && kbps > 0
+        && kbps < LOW_RATE_LIMIT_KBPS
+    {
+        issues.warning(
+            "uploadRateLimitKbps",
+            "veryLow",
+            format!("限速 {kbps} KB/s 过低，上传可能非常缓慢"),
+        );
+    }
+    issues.integer(
+        map,
+        "tempMaxAgeHours",
+        0,
+        crate::settings::MAX_TEMP_AGE_HOURS as u64,
+    );
+    issues.integer(
+        map,
+        "linkCheckIntervalHours",
+        1,
+        crate::settings::MAX_LINK_CHECK_INTERVAL_HOURS as u64,
+    );
+    issues.integer(
+        map,
+        "linkCheckBatchSize",
+        1,
+        crate::settings::MAX_LINK_CHECK_BATCH_SIZE as u64,
+    );
+    issues.integer(
+        map,
+        "deleteUndoSeconds",
+        0,
+        crate::settings::MAX_DELETE_UNDO_SECONDS as u64,
+    );
+    for field in [
+        "convertToWebp",
+        "enableUploadCompression",
+        "enableThumbnailCache",
+        "cleanTempOnExit",
+        "clipboardWatch",
+        "clipboardAutoUpload",
+        "picgoServerEnabled",
+        "ocrAutoIndex",
+        "linkCheckEnabled",
+        "linkCheckRequireAc",
+        "pauseUploadsOnMetered",
+        "pauseUploadsWhenOffline",
+        "skipDuplicateUploads",
+        "crashReportingEnabled",
+        "watchFolderCopyLink",
+        "desktopNotifications",
+        "autoQuality",
+    ] {
+        issues.boolean(map, field);
+    }
+    issues.number(
+        map,
+        "autoQualitySsim",
+        crate::quality_metrics::MIN_TARGET_SSIM,
+        crate::quality_metrics::MAX_TARGET_SSIM,
+    );
+    let png_mode_field = if map.contains_key("pngMode") {
+        "pngMode"
+    } else {
+        "pngCompressionMode"
+    };
+    issues.variant::<PngCompressionMode>(map, png_mode_field, "lossy, lossless");
+    issues.variant::<PngOptimizationLevel>(map, "pngOptimization", "best, default, fast");
+    issues.variant::<crate::heic::HeicSequenceMode>(map, "heicSequenceMode", "still, webp, gif");
+    issues.variant::<PrivacyPolicy>(map, "metadataPrivacyPolicy", "warn, strip, never");
+    if let Some(value) = map.get("fileNameTemplate") {
+        validate_file_name_template(&mut issues, value);
+    }
+
+    if let Some(value) = map.get("logLevel") {
+        match value.as_str() {
+            Some(level) => {
+                if let Err(err) = crate::logging::parse_level(level) {
+                    issues.error("logLevel", "invalidValue", err);
+                }
+            }
+            None => issues.error("logLevel", "invalidType", "须为字符串".to_string()),
+        }
+    }
+    if let Some(value) = map.get("defaultSaveDir") {
+        validate_default_save_dir(&mut issues, value);
+    }
+    #[cfg(desktop)]
+    validate_hotkeys(&mut issues, map);
+    validate_picgo_server(&mut issues, map);
+    validate_link_templates(&mut issues, map);
+    validate_ocr(&mut issues, map);
+    validate_encrypted_viewer(&mut issues, map);
+    validate_crash_reporting(&mut issues, map);
+    validate_watch_folders(&mut issues, map);
+    validate_webhooks(&mut issues, map);
+    validate_telegram_posts(&mut issues, map);
+
+    let known = serde_json::to_value(SettingsPayload::default()).unwrap_or_default();
+    for key in map.keys() {
+        if key != "pngMode" && known.get(key).is_none() {
+            issues.warning(key, "unknownField", "未知字段，保存时会被忽略".to_string());
+        }
+    }
+
+    SettingsValidation {
+        valid: !issues
+            .0
+            .iter()
+            .any(|issue| issue.severity == IssueSeverity::Error),
+        issues: issues.0,
+    }
+}
+
+#[tauri::command]
+pub fn validate_settings(payload: Value) -> SettingsValidation {
+    validate(&payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_settings() {
+        let result = validate(&json!({
+            "quality": 150,
+            "maxConcurrentUploads": 0,
+            "pngCompressionMode": "lossy",
+            "pngOptimization": "ultra",
+            "autoQualitySsim": 0.5,
+            "fileNameTemplate": "{date}-{foo}",
+            "metadataPrivacyPolicy": "ask",
+            "proxy": "http://127.0.0.1:8080"
+        }));
+        assert!(!result.valid);
+        let codes: Vec<(&str, &str)> = result
+            .issues
+            .iter()
+            .map(|issue| (issue.field.as_str(), issue.code.as_str()))
+            .collect();
+        assert!(codes.contains(&("quality", "outOfRange")));
+        assert!(codes.contains(&("maxConcurrentUploads", "outOfRange")));
+        assert!(codes.contains(&("pngOptimization", "invalidValue")));
+        assert!(codes.contains(&("autoQualitySsim", "outOfRange")));
+        assert!(codes.contains(&("metadataPrivacyPolicy", "invalidValue")));
+        assert!(codes.contains(&("fileNameTemplate", "unknownPlaceholder")));
+        assert!(codes.contains(&("fileNameTemplate", "notUnique")));
+        assert!(codes.contains(&("proxy", "unknownField")));
+        assert!(
+            !codes
+                .iter()
+                .any(|(field, _)| *field == "pngCompressionMode")
+        );
+
+        assert!(validate(&json!({ "quality": 80 })).issues.is_empty());
+
+        let result = validate(&json!({
+            "linkTemplates": [
+                { "id": "wiki", "name": "Wiki", "template": "[[File:{name}|{foo}]]" },
+                { "id": "wiki", "name": "Wiki 2", "template": "{url}" }
+            ],
+            "defaultLinkFormat": "textile"
+        }));
+        let codes: Vec<(&str, &str)> = result
+            .issues
+            .iter()
+            .map(|issue| (issue.field.as_str(), issue.code.as_str()))
+            .collect();
+        assert!(codes.contains(&("linkTemplates", "unknownPlaceholder")));
+        assert!(codes.contains(&("linkTemplates", "duplicate")));
+        assert!(codes.contains(&("defaultLinkFormat", "invalidValue")));
+        assert!(validate(&json!({ "defaultLinkFormat": "markdown" })).valid);
+
+        assert!(!validate(&json!({ "crashReportEndpoint": "ftp://example.com" })).valid);
+        let result = validate(&json!({
+            "crashReportingEnabled": true,
+            "crashReportEndpoint": ""
+        }));
+        assert!(result.valid);
+        assert_eq!(result.issues[0].code, "missing");
+        assert!(validate(&json!({ "crashReportEndpoint": "https://example.com/report" })).valid);
+        assert!(!validate(&json!({ "encryptedViewerUrl": "viewer.html" })).valid);
+        assert!(!validate(&json!({ "webhooks": [{ "url": "hooks.example.com" }] })).valid);
+        assert!(
+            !validate(&json!({ "webhooks": [{ "url": "https://a", "events": ["done"] }] })).valid
+        );
+        assert!(
+            validate(&json!({ "webhooks": [{ "url": "https://hooks.example.com/x", "events": ["success", "failure"] }] }))
+                .valid
+        );
+        assert!(validate(&json!({ "encryptedViewerUrl": "https://example.com/d.html" })).valid);
+        assert!(
+            !validate(
+                &json!({ "telegramPosts": [{ "host": "s3", "token": "bad", "chatId": "1" }] })
+            )
+            .valid
+        );
+        assert!(!validate(&json!({ "telegramPosts": [{ "host": "s3", "token": "1:a" }] })).valid);
+        assert!(
+            validate(
+                &json!({ "telegramPosts": [{ "host": "s3", "token": "1:a", "chatId": "@c" }] })
+            )
+            .valid
+        );
+        assert!(
+            validate(&json!({ "telegramPosts": [{ "host": "s3", "target": "telegraph", "token": "abc" }] }))
+                .valid
+        );
+
+        let dir = std::env::temp_dir();
+        let dir = dir.to_string_lossy();
+        let result = validate(&json!({
+            "watchFolders": [
+                { "path": dir, "host": "s3" },
+                { "path": dir, "host": "webdav" },
+                { "path": "shots", "host": "" },
+                { "path": "", "host": "s3", "enabled": false }
+            ]
+        }));
+        let codes: Vec<&str> = result.issues.iter().map(|i| i.code.as_str()).collect();
+        assert_eq!(codes, vec!["duplicate", "notAbsolute", "missing"]);
+        assert!(validate(&json!({ "watchFolders": [{ "path": dir, "host": "s3" }] })).valid);
+        assert!(!validate(&json!({ "watchFolders": "shots" })).valid);
+    }
+}
@@ -0,0 +1,268 @@
+/*
+设置文件热更新：
+1) 监听配置目录中的 `settings.json` 与 `image-hosts.json`，手动编辑或同步工具（Syncthing / Dropbox）
+   写入后即时生效，避免被下次保存静默覆盖；
+2) 文件事件按 300ms 合并，重新读取并校验：全局设置经 `clamped` 修正，图床配置须为对象，
+   解析失败（如同步工具写入到一半）时保留当前状态，等待下一次事件；
+3) 与上次已知内容比较，仅在实际变化时应用到后端子系统并广播 `settings://changed`；
+   应用自身保存（`save_settings` / `save_image_host_settings`）时已广播，并通过 `record_app_save` /
+   `record_host_save` 同步更新已知内容，随后的文件事件不会再次广播。
+*/
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock, mpsc},
+    time::Duration,
+};
+
+use log::{debug, error, info, warn};
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::image_hosts::read_settings;
+use crate::settings::{
+    SettingsChange, SettingsPayload, apply_runtime_settings, broadcast_settings_change,
+    read_payload,
+};
+
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 监听线程与应用保存路径共享的已知内容；未启动监听时为空
+static SNAPSHOT: OnceLock<Mutex<Snapshot>> = OnceLock::new();
+
+/// 上次已知的文件内容，用于过滤自身保存与无实际变化的事件
+struct Snapshot {
+    app_path: PathBuf,
+    hosts_path: PathBuf,
+    app: Option<SettingsPayload>,
+    hosts: HashMap<String, Value>,
+}
+
+/// 比较两份图床配置，返回发生变化的插件及其新值（被删除时为 None）
+fn diff_hosts(
+    previous: &HashMap<String, Value>,
+    current: &HashMap<String, Value>,
+) -> BTreeMap<String, Option<Value>> {
+    let mut changes = BTreeMap::new();
+    for (id, value) in current {
+        if previous.get(id) != Some(value) {
+            changes.insert(id.clone(), Some(value.clone()));
+        }
+    }
+    for id in previous.keys() {
+        if !current.contains_key(id) {
+            changes.insert(id.clone(), None);
+        }
+    }
+    changes
+}
+
+impl Snapshot {
+    fn load(app_path: PathBuf, hosts_path: PathBuf) -> Self {
+        let app = read_payload(&app_path).ok();
+        let hosts = read_settings(&hosts_path)
+            .map(|file| file.plugins)
+            .unwrap_or_default();
+        Self {
+            app_path,
+            hosts_path,
+            app,
+            hosts,
+        }
+    }
+
+    /// 重新读取全局设置，内容与已知不同时返回新设置
+    fn reload_app(&mut self) -> Option<SettingsPayload> {
+        let payload = match read_payload(&self.app_path) {
+            Ok(payload) => payload,
+            Err(err) => {
+                warn!("settings watcher: ignore invalid settings file: {}", err);
+                return None;
+            }
+        };
+        let unchanged = self.app.as_ref().is_some_and(|previous| {
+            serde_json::to_value(previous).ok() == serde_json::to_value(&payload).ok()
+        });
+        if unchanged {
+            return None;
+        }
+        info!(
+            "settings file changed externally: path={}",
+            self.app_path.display()
+        );
+        self.app = Some(payload.clone());
+        Some(payload)
+    }
+
+    /// 重新读取图床配置，返回发生变化的插件
+    fn reload_hosts(&mut self) -> BTreeMap<String, Option<Value>> {
+        let mut current = match read_settings(&self.hosts_path) {
+            Ok(file) => file.plugins,
+            Err(err) => {
+                warn!("settings watcher: ignore invalid image host file: {}", err);
+                return BTreeMap::new();
+            }
+        };
+        // 非对象的配置视为无效，沿用上次已知的值
+        let invalid: Vec<String> = current
+            .iter()
+            .filter(|(_, value)| !value.is_object())
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in invalid {
+            warn!(
+                "settings watcher: image host config {} is not an object",
+                id
+            );
+            match self.hosts.get(&id) {
+                Some(previous) => {
+                    current.insert(id, previous.clone());
+                }
+                None => {
+                    current.remove(&id);
+                }
+            }
+        }
+        let changes = diff_hosts(&self.hosts, &current);
+        self.hosts = current;
+        changes
+    }
+}
+
+fn with_snapshot<T>(f: impl FnOnce(&mut Snapshot) -> T) -> Option<T> {
+    let snapshot = SNAPSHOT.get()?;
+    let mut snapshot = snapshot.lock().unwrap_or_else(|e| e.into_inner());
+    Some(f(&mut snapshot))
+}
+
+/// 应用自身保存全局设置后调用，保存的内容已广播，监听线程不再重复处理
+pub(crate) fn record_app_save(payload: &SettingsPayload) {
+    with_snapshot(|snapshot| snapshot.app = Some(payload.clone()));
+}
+
+/// 应用自身保存图床配置后调用；`values` 为 None 表示配置已删除
+pub(crate) fn record_host_save(plugin_id: &str, values: Option<&Value>) {
+    with_snapshot(|snapshot| match values {
+        Some(values) => {
+            snapshot.hosts.insert(plugin_id.to_string(), values.clone());
+        }
+        None => {
+            snapshot.hosts.remove(plugin_id);
+        }
+    });
+}
+
+fn apply_app_change(app: &AppHandle) {
+    let Some(payload) = with_snapshot(Snapshot::reload_app).flatten() else {
+        return;
+    };
+    apply_runtime_settings(app, &payload);
+    broadcast_settings_change(app, SettingsChange::App { settings: payload });
+}
+
+fn apply_host_changes(app: &AppHandle) {
+    for (plugin_id, values) in with_snapshot(Snapshot::reload_hosts).unwrap_or_default() {
+        debug!(
+            "image host settings changed externally: plugin_id={}",
+            plugin_id
+        );
+        broadcast_settings_change(app, SettingsChange::ImageHost { plugin_id, values });
+    }
+}
+
+/// 启动设置文件监听线程，监听器随线程存活至应用退出
+pub fn init(app: &AppHandle) {
+    let (app_path, hosts_path) = match (
+        crate::settings::ensure_config_path(app),
+        crate::image_hosts::ensure_config_path(app),
+    ) {
+        (Ok(app_path), Ok(hosts_path)) => (app_path, hosts_path),
+        (Err(err), _) | (_, Err(err)) => {
+            warn!("resolve settings path failed: {}", err);
+            return;
+        }
+    };
+    let Some(dir) = app_path.parent().map(Path::to_path_buf) else {
+        return;
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            error!("create settings watcher failed: {}", err);
+            return;
+        }
+    };
+    // 监听所在目录而非文件本身：同步工具常以"写临时文件再重命名"的方式替换文件
+    if let Err(err) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+        warn!("watch settings dir {} failed: {}", dir.display(), err);
+        return;
+    }
+    info!("watching settings dir {}", dir.display());
+
+    let app = app.clone();
+    let snapshot = Snapshot::load(app_path.clone(), hosts_path.clone());
+    if SNAPSHOT.set(Mutex::new(snapshot)).is_err() {
+        warn!("settings watcher already initialized");
+        return;
+    }
+    std::thread::spawn(move || {
+        let _watcher = watcher;
+        let (mut app_dirty, mut hosts_dirty) = (false, false);
+        loop {
+            let received = if !app_dirty && !hosts_dirty {
+                rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected)
+            } else {
+                rx.recv_timeout(DEBOUNCE)
+            };
+            match received {
+                Ok(Ok(event)) => {
+                    if matches!(event.kind, EventKind::Access(_)) {
+                        continue;
+                    }
+                    for path in &event.paths {
+                        app_dirty |= path.file_name() == app_path.file_name();
+                        hosts_dirty |= path.file_name() == hosts_path.file_name();
+                    }
+                }
+                Ok(Err(err)) => warn!("settings watcher error: {}", err),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    if std::mem::take(&mut app_dirty) {
+                        apply_app_change(&app);
+                    }
+                    if std::mem::take(&mut hosts_dirty) {
+                        apply_host_changes(&app);
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_hosts() {
+        let previous = HashMap::from([
+            ("s3".to_string(), json!({ "bucket": "a" })),
+            ("imgur".to_string(), json!({ "clientId": "x" })),
+        ]);
+        let current = HashMap::from([
+            ("s3".to_string(), json!({ "bucket": "b" })),
+            ("github".to_string(), json!({ "repo": "r" })),
+        ]);
+        let changes = diff_hosts(&previous, &current);
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes["s3"], Some(json!({ "bucket": "b" })));
+        assert_eq!(changes["github"], Some(json!({ "repo": "r" })));
+        assert_eq!(changes["imgur"], None);
+        assert!(diff_hosts(&current, &current).is_empty());
+    }
+}
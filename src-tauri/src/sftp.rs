@@ -0,0 +1,397 @@
+/*
+内置 SFTP 图床（自建 VPS + Web 服务器）：
+1) 支持密码与私钥（可带口令）两种认证方式；认证前总会校验服务器公钥：填写了 SHA256 指纹时
+   按指纹比对，否则首次连接时把公钥记入应用数据目录的 `sftp_known_hosts`（OpenSSH 格式），
+   之后公钥变化即拒绝连接，避免密码被中间人截获；
+2) 文件上传到 `remoteDir` 下按路径模板生成的位置，缺失的目录逐级创建；
+3) 返回链接为 `publicUrl` 拼接相对路径（即 remoteDir 对应 Web 根目录）；
+4) 删除时 deleteId 中记录主机、端口与远程绝对路径，凭据从已保存的配置中读取，
+   因此只删除与当前配置同一主机和端口上的文件；
+5) 连接阶段（解析、TCP 连接、SSH 握手）失败时按 `retry` 策略重试，上传可通过 `cancel_upload` 取消。
+libssh2 为阻塞接口，所有操作都在 spawn_blocking 中执行；取消只丢弃等待结果的 future，
+已开始的阻塞写入会在后台线程中继续到结束。限速、客户端证书与 HTTP 选项只适用于 HTTP 图床。
+*/
+
+use std::{
+    fmt,
+    io::Write,
+    net::TcpStream,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD_NO_PAD as BASE64};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use ssh2::{CheckResult, HashType, KnownHostFileKind, Session};
+use tauri::AppHandle;
+
+use crate::internal_hosts::{
+    HostDeleteResult, HostUploadResult, build_public_url, decode_delete_marker,
+    encode_delete_marker, load_host_settings, read_upload_file, render_path_template,
+};
+use crate::upload::{RetryPolicy, run_cancellable};
+
+const PLUGIN_ID: &str = "sftp";
+const DEFAULT_PORT: u16 = 22;
+const DEFAULT_TIMEOUT_MS: u64 = 30_000;
+const KNOWN_HOSTS_FILE: &str = "sftp_known_hosts";
+
+/// 串行化 known_hosts 文件的读取、比对与写入，避免并发上传互相覆盖
+static KNOWN_HOSTS_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpConfig {
+    pub host: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    pub username: String,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// 私钥文件路径；设置后优先使用私钥认证
+    #[serde(default)]
+    pub private_key_path: Option<String>,
+    #[serde(default)]
+    pub private_key_passphrase: Option<String>,
+    /// 服务器公钥的 SHA256 指纹（base64，可带 `SHA256:` 前缀），留空则首次连接时记录公钥
+    #[serde(default)]
+    pub host_fingerprint: Option<String>,
+    /// 远程根目录，通常为 Web 服务器的站点目录，例如 /var/www/img
+    pub remote_dir: String,
+    #[serde(default)]
+    pub path_template: Option<String>,
+    /// remoteDir 对应的访问地址，例如 https://img.example.com
+    pub public_url: String,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// 连接失败时的重试策略，默认不重试
+    #[serde(default)]
+    pub retry: RetryPolicy,
+}
+
+/// SFTP 操作失败：连接阶段的失败可以重试，认证与文件操作的失败直接返回
+#[derive(Debug)]
+enum SftpError {
+    Connect(String),
+    Other(String),
+}
+
+impl fmt::Display for SftpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connect(message) | Self::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for SftpError {
+    fn from(message: String) -> Self {
+        Self::Other(message)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SftpDeleteMarker {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn known_hosts_path(app: &AppHandle) -> Result<PathBuf, String> {
+    crate::portable::app_data_dir(app)
+        .map(|dir| dir.join(KNOWN_HOSTS_FILE))
+        .map_err(|e| format!("app_data_dir: {e}"))
+}
+
+/// known_hosts 中的主机名：非默认端口写作 `[host]:port`
+fn known_host_name(host: &str, port: u16) -> String {
+    if port == DEFAULT_PORT {
+        host.to_string()
+    } else {
+        format!("[{}]:{}", host, port)
+    }
+}
+
+/// 未配置指纹时按 known_hosts 校验服务器公钥，首次连接的主机记录其公钥
+fn verify_known_host(
+    session: &Session,
+    host: &str,
+    port: u16,
+    known_hosts: &Path,
+) -> Result<(), SftpError> {
+    let _guard = KNOWN_HOSTS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let (key, key_type) = session
+        .host_key()
+        .ok_or_else(|| "无法获取服务器公钥".to_string())?;
+    let mut known = session
+        .known_hosts()
+        .map_err(|e| format!("init known hosts: {e}"))?;
+    if known_hosts.exists() {
+        known
+            .read_file(known_hosts, KnownHostFileKind::OpenSSH)
+            .map_err(|e| format!("读取 {} 失败: {e}", known_hosts.display()))?;
+    }
+    match known.check_port(host, port, key) {
+        CheckResult::Match => Ok(()),
+        CheckResult::NotFound => {
+            let name = known_host_name(host, port);
+            known
+                .add(&name, key, "added by yana", key_type.into())
+                .map_err(|e| format!("记录服务器公钥失败: {e}"))?;
+            if let Some(parent) = known_hosts.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("failed to create dir {}: {e}", parent.display()))?;
+            }
+            known
+                .write_file(known_hosts, KnownHostFileKind::OpenSSH)
+                .map_err(|e| format!("写入 {} 失败: {e}", known_hosts.display()))?;
+            let fingerprint = session
+                .host_key_hash(HashType::Sha256)
+                .map(|hash| BASE64.encode(hash))
+                .unwrap_or_default();
+            warn!(
+                "sftp trusting new host key on first use: host={}, fingerprint=SHA256:{}",
+                name, fingerprint
+            );
+            Ok(())
+        }
+        CheckResult::Mismatch => Err(SftpError::Other(format!(
+            "{} 的服务器公钥与首次连接时记录的不一致，可能遭到中间人攻击；\
+             如确认服务器已更换密钥，请在设置中填写新的公钥指纹或删除 {} 中的对应记录",
+            known_host_name(host, port),
+            known_hosts.display()
+        ))),
+        CheckResult::Failure => Err(SftpError::Other("校验服务器公钥失败".to_string())),
+    }
+}
+
+impl SftpConfig {
+    fn port(&self) -> u16 {
+        self.port.unwrap_or(DEFAULT_PORT)
+    }
+
+    fn connect(&self, host: &str, port: u16, known_hosts: &Path) -> Result<Session, SftpError> {
+        let timeout = Duration::from_millis(self.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+        let address = std::net::ToSocketAddrs::to_socket_addrs(&(host, port))
+            .map_err(|e| SftpError::Connect(format!("解析 {}:{} 失败: {e}", host, port)))?
+            .next()
+            .ok_or_else(|| SftpError::Connect(format!("无法解析主机 {}", host)))?;
+        let tcp = TcpStream::connect_timeout(&address, timeout)
+            .map_err(|e| SftpError::Connect(format!("连接 {}:{} 失败: {e}", host, port)))?;
+        let mut session = Session::new().map_err(|e| format!("create ssh session: {e}"))?;
+        session.set_timeout(timeout.as_millis() as u32);
+        session.set_tcp_stream(tcp);
+        session
+            .handshake()
+            .map_err(|e| SftpError::Connect(format!("SSH 握手失败: {e}")))?;
+
+        if let Some(expected) = non_empty(self.host_fingerprint.as_deref()) {
+            let actual = session
+                .host_key_hash(HashType::Sha256)
+                .map(|hash| BASE64.encode(hash))
+                .ok_or_else(|| "无法获取服务器公钥指纹".to_string())?;
+            let expected = expected.trim_start_matches("SHA256:").trim_end_matches('=');
+            if actual != expected {
+                return Err(SftpError::Other(format!(
+                    "服务器公钥指纹不匹配: 期望 SHA256:{}，实际 SHA256:{}",
+                    expected, actual
+                )));
+            }
+        } else {
+            verify_known_host(&session, host, port, known_hosts)?;
+        }
+
+        match non_empty(self.private_key_path.as_deref()) {
+            Some(key_path) => session
+                .userauth_pubkey_file(
+                    &self.username,
+                    None,
+                    Path::new(key_path),
+                    non_empty(self.private_key_passphrase.as_deref()),
+                )
+                .map_err(|e| format!("私钥认证失败: {e}"))?,
+            None => session
+                .userauth_password(&self.username, self.password.as_deref().unwrap_or(""))
+                .map_err(|e| format!("密码认证失败: {e}"))?,
+        }
+        if !session.authenticated() {
+            return Err(SftpError::Other("SSH 认证失败".to_string()));
+        }
+        Ok(session)
+    }
+
+    /// 在阻塞线程中执行 SFTP 操作，连接阶段失败时按重试策略重试
+    async fn run_with_retry<T, F>(&self, context: &str, op: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: Fn(&SftpConfig) -> Result<T, SftpError> + Clone + Send + 'static,
+    {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let (config, task) = (self.clone(), op.clone());
+            let outcome = tokio::task::spawn_blocking(move || task(&config))
+                .await
+                .map_err(|e| format!("failed to join sftp task: {}", e))?;
+            match outcome {
+                Err(SftpError::Connect(err))
+                    if attempt < max_attempts && self.retry.retry_on_connection_error =>
+                {
+                    let wait = self.retry.backoff(attempt);
+                    warn!(
+                        "{} attempt {}/{} failed ({}), retrying in {}ms",
+                        context,
+                        attempt,
+                        max_attempts,
+                        err,
+                        wait.as_millis()
+                    );
+                    tokio::time::sleep(wait).await;
+                    attempt += 1;
+                }
+                other => return other.map_err(|err| err.to_string()),
+            }
+        }
+    }
+}
+
+/// 逐级创建远程目录，已存在的目录跳过
+fn ensure_remote_dirs(sftp: &ssh2::Sftp, dir: &Path) -> Result<(), String> {
+    let mut current = PathBuf::new();
+    for component in dir.components() {
+        current.push(component);
+        if sftp.stat(&current).is_ok() {
+            continue;
+        }
+        sftp.mkdir(&current, 0o755)
+            .map_err(|e| format!("创建远程目录 {} 失败: {e}", current.display()))?;
+    }
+    Ok(())
+}
+
+fn upload_blocking(
+    config: &SftpConfig,
+    known_hosts: &Path,
+    bytes: &[u8],
+    remote_path: &str,
+) -> Result<(), SftpError> {
+    let session = config.connect(config.host.trim(), config.port(), known_hosts)?;
+    let sftp = session.sftp().map_err(|e| format!("open sftp: {e}"))?;
+    let remote = Path::new(remote_path);
+    if let Some(parent) = remote.parent() {
+        ensure_remote_dirs(&sftp, parent)?;
+    }
+    let mut file = sftp
+        .create(remote)
+        .map_err(|e| format!("创建远程文件 {} 失败: {e}", remote_path))?;
+    file.write_all(bytes)
+        .map_err(|e| format!("写入远程文件 {} 失败: {e}", remote_path))?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn sftp_upload(
+    app: AppHandle,
+    file_path: String,
+    original_file_name: Option<String>,
+    config: SftpConfig,
+    upload_id: Option<String>,
+) -> Result<HostUploadResult, String> {
+    let known_hosts = known_hosts_path(&app)?;
+    run_cancellable(
+        upload_id,
+        upload(known_hosts, file_path, original_file_name, config),
+    )
+    .await
+}
+
+async fn upload(
+    known_hosts: PathBuf,
+    file_path: String,
+    original_file_name: Option<String>,
+    config: SftpConfig,
+) -> Result<HostUploadResult, String> {
+    let (file_name, bytes) = read_upload_file(&file_path, original_file_name).await?;
+    let relative = render_path_template(config.path_template.as_deref(), &file_name);
+    let remote_path = format!("{}/{}", config.remote_dir.trim_end_matches('/'), relative);
+    let size = bytes.len();
+
+    let task_path = remote_path.clone();
+    config
+        .run_with_retry("sftp upload", move |config| {
+            upload_blocking(config, &known_hosts, &bytes, &task_path)
+        })
+        .await
+        .inspect_err(|err| {
+            error!("sftp_upload failed: path={}, error={}", remote_path, err);
+        })?;
+
+    let delete_id = encode_delete_marker(&SftpDeleteMarker {
+        host: config.host.trim().to_string(),
+        port: config.port(),
+        path: remote_path.clone(),
+    })?;
+    info!("sftp_upload success: path={}, size={}", remote_path, size);
+    Ok(HostUploadResult {
+        url: build_public_url(config.public_url.trim(), &relative),
+        delete_id,
+        metadata: Some(serde_json::json!({ "path": remote_path, "size": size })),
+    })
+}
+
+#[tauri::command]
+pub async fn sftp_delete(app: AppHandle, delete_id: String) -> Result<HostDeleteResult, String> {
+    let marker: SftpDeleteMarker = decode_delete_marker(&delete_id)?;
+    let config: SftpConfig = load_host_settings(&app, PLUGIN_ID)?;
+    // 凭据来自当前配置，不能发送给 deleteId 中记录的其他主机
+    if !marker.host.eq_ignore_ascii_case(config.host.trim()) || marker.port != config.port() {
+        return Err(format!(
+            "该文件位于 {}:{}，与当前 SFTP 配置的服务器不一致，无法删除",
+            marker.host, marker.port
+        ));
+    }
+    let known_hosts = known_hosts_path(&app)?;
+
+    let task_marker = marker.clone();
+    let removed = config
+        .run_with_retry("sftp delete", move |config| {
+            let session = config.connect(&task_marker.host, task_marker.port, &known_hosts)?;
+            let sftp = session.sftp().map_err(|e| format!("open sftp: {e}"))?;
+            let path = Path::new(&task_marker.path);
+            if sftp.stat(path).is_err() {
+                return Ok(false);
+            }
+            sftp.unlink(path)
+                .map_err(|e| format!("删除远程文件 {} 失败: {e}", task_marker.path))?;
+            Ok(true)
+        })
+        .await?;
+
+    info!(
+        "sftp_delete success: path={}, existed={}",
+        marker.path, removed
+    );
+    Ok(HostDeleteResult::deleted(if removed {
+        "文件已从服务器删除"
+    } else {
+        "文件已不存在"
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_host_name() {
+        assert_eq!(known_host_name("example.com", 22), "example.com");
+        assert_eq!(known_host_name("example.com", 2222), "[example.com]:2222");
+    }
+}
@@ -0,0 +1,118 @@
+/*
+Android 分享目标（在其他应用中把图片分享到 yana）：
+1) MainActivity 处理 `ACTION_SEND` / `ACTION_SEND_MULTIPLE`，把图片的 content URI 经 JNI 交给 `receive_uris`；
+2) 应用初始化完成前收到的 URI 先暂存，`init` 时一并处理；
+3) 通过 android-fs 插件把 URI 的内容复制到私有缓存目录，完成后发送 `share-target://received`
+   （负载为本地路径），路径保留到前端调用 `take_shared_images` 取走为止，
+   冷启动时页面尚未开始监听也不会丢失。
+*/
+
+use std::sync::{Mutex, OnceLock};
+
+use log::{info, warn};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_android_fs::FileUri;
+
+const RECEIVED_EVENT: &str = "share-target://received";
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+/// 应用初始化前收到的 URI
+static PENDING_URIS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+/// 已复制到缓存、尚未被前端取走的本地路径
+static RECEIVED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn file_uri(uri: &str) -> Result<FileUri, String> {
+    serde_json::from_value(serde_json::json!({
+        "uri": uri,
+        "documentTopTreeUri": null,
+    }))
+    .map_err(|err| format!("无效的分享链接 {uri}: {err}"))
+}
+
+async fn import(app: AppHandle, uris: Vec<String>) {
+    let files: Vec<FileUri> = uris
+        .iter()
+        .filter_map(|uri| {
+            file_uri(uri)
+                .inspect_err(|err| warn!("share target: {}", err))
+                .ok()
+        })
+        .collect();
+    let paths = match crate::file_picker::copy_to_cache(&app, files).await {
+        Ok(paths) if !paths.is_empty() => paths,
+        Ok(_) => return,
+        Err(err) => {
+            warn!("copy shared images failed: {}", err);
+            return;
+        }
+    };
+    info!("received {} shared image(s)", paths.len());
+    if let Ok(mut received) = RECEIVED.lock() {
+        received.extend(paths.iter().cloned());
+    }
+    if let Err(err) = app.emit(RECEIVED_EVENT, &paths) {
+        warn!("emit {} failed: {}", RECEIVED_EVENT, err);
+    }
+}
+
+/// 收到分享的 content URI；应用尚未初始化时暂存
+pub(crate) fn receive_uris(uris: Vec<String>) {
+    let uris: Vec<String> = uris.into_iter().filter(|uri| !uri.is_empty()).collect();
+    if uris.is_empty() {
+        return;
+    }
+    // 持有暂存锁再检查初始化状态，避免与 init 交错时丢失
+    let Ok(mut pending) = PENDING_URIS.lock() else {
+        return;
+    };
+    match APP.get() {
+        Some(app) => {
+            tauri::async_runtime::spawn(import(app.clone(), uris));
+        }
+        None => pending.extend(uris),
+    }
+}
+
+pub(crate) fn init(app: &AppHandle) {
+    let Ok(mut pending) = PENDING_URIS.lock() else {
+        return;
+    };
+    if APP.set(app.clone()).is_err() {
+        return;
+    }
+    let pending = std::mem::take(&mut *pending);
+    if !pending.is_empty() {
+        tauri::async_runtime::spawn(import(app.clone(), pending));
+    }
+}
+
+/// 取走已接收的分享图片路径（取走后清空）
+#[tauri::command]
+pub fn take_shared_images() -> Result<Vec<String>, String> {
+    RECEIVED
+        .lock()
+        .map(|mut received| std::mem::take(&mut *received))
+        .map_err(|_| "share target state poisoned".to_string())
+}
+
+/// MainActivity 中 `external fun onSharedUris(uris: Array<String>)` 的实现
+#[cfg(target_os = "android")]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_yana_dev_MainActivity_onSharedUris(
+    mut env: jni::JNIEnv,
+    _activity: jni::objects::JObject,
+    uris: jni::objects::JObjectArray,
+) {
+    let len = env.get_array_length(&uris).unwrap_or(0);
+    let mut list = Vec::with_capacity(len.max(0) as usize);
+    for index in 0..len {
+        let Ok(element) = env.get_object_array_element(&uris, index) else {
+            continue;
+        };
+        let uri = jni::objects::JString::from(element);
+        if let Ok(value) = env.get_string(&uri) {
+            list.push(value.into());
+        }
+    }
+    receive_uris(list);
+}
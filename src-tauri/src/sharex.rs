@@ -0,0 +1,188 @@
+/*
+ShareX 自定义上传器（.sxcu）导入 / 导出：
+1) 仅针对声明式配置的自定义 HTTP 图床（`custom`），字段与 .sxcu 一一对应；
+2) `export_sharex_uploader` 读取已保存的配置（钥匙串字段会解析为真实值写入文件，请注意分享范围）；
+3) `import_sharex_uploader` 解析 .sxcu 写入 `custom` 图床配置，请求头存入钥匙串；
+   仅支持 MultipartFormData / Binary 请求体，其他类型返回错误。
+*/
+
+use std::{collections::BTreeMap, fs};
+
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use tauri::AppHandle;
+
+use crate::custom_http::{CustomBody, CustomHttpConfig, PLUGIN_ID, parse_string_map};
+use crate::image_hosts::{
+    SECRET_PLACEHOLDER, ensure_config_path, plugin_secret_id, read_settings, write_settings,
+};
+use crate::internal_hosts::load_host_settings;
+use crate::secrets::write_secret;
+
+const SHAREX_VERSION: &str = "15.0.0";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+struct SharexUploader {
+    version: Option<String>,
+    name: Option<String>,
+    destination_type: Option<String>,
+    request_method: Option<String>,
+    #[serde(rename = "RequestURL")]
+    request_url: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    parameters: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    headers: BTreeMap<String, String>,
+    body: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    arguments: BTreeMap<String, String>,
+    file_form_name: Option<String>,
+    #[serde(rename = "URL")]
+    url: Option<String>,
+    #[serde(rename = "DeletionURL", skip_serializing_if = "Option::is_none")]
+    deletion_url: Option<String>,
+}
+
+fn to_json_text(map: &BTreeMap<String, String>) -> Value {
+    if map.is_empty() {
+        Value::Null
+    } else {
+        Value::String(serde_json::to_string_pretty(map).unwrap_or_default())
+    }
+}
+
+#[tauri::command]
+pub fn export_sharex_uploader(app: AppHandle, path: String) -> Result<(), String> {
+    let config: CustomHttpConfig = load_host_settings(&app, PLUGIN_ID)?;
+    let uploader = SharexUploader {
+        version: Some(SHAREX_VERSION.to_string()),
+        name: Some(
+            config
+                .name
+                .clone()
+                .filter(|n| !n.trim().is_empty())
+                .unwrap_or_else(|| "yana".to_string()),
+        ),
+        destination_type: Some("ImageUploader".to_string()),
+        request_method: Some(
+            config
+                .method
+                .clone()
+                .filter(|m| !m.trim().is_empty())
+                .unwrap_or_else(|| "POST".to_string())
+                .to_ascii_uppercase(),
+        ),
+        request_url: config.request_url.trim().to_string(),
+        parameters: parse_string_map("查询参数", config.parameters.as_deref())?,
+        headers: parse_string_map("请求头", config.headers.as_deref())?,
+        body: Some(
+            match config.body {
+                CustomBody::MultipartFormData => "MultipartFormData",
+                CustomBody::Binary => "Binary",
+            }
+            .to_string(),
+        ),
+        arguments: parse_string_map("表单字段", config.arguments.as_deref())?,
+        file_form_name: match config.body {
+            CustomBody::MultipartFormData => Some(
+                config
+                    .file_form_name
+                    .clone()
+                    .filter(|f| !f.trim().is_empty())
+                    .unwrap_or_else(|| "file".to_string()),
+            ),
+            CustomBody::Binary => None,
+        },
+        url: config.url_template.clone().filter(|u| !u.trim().is_empty()),
+        deletion_url: config
+            .deletion_url_template
+            .clone()
+            .filter(|u| !u.trim().is_empty()),
+    };
+    let text =
+        serde_json::to_string_pretty(&uploader).map_err(|e| format!("serialize sxcu: {e}"))?;
+    fs::write(&path, text).map_err(|e| format!("写入 {} 失败: {e}", path))?;
+    info!("export_sharex_uploader success: path={}", path);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn import_sharex_uploader(app: AppHandle, path: String) -> Result<String, String> {
+    let text = fs::read_to_string(&path).map_err(|e| format!("读取 {} 失败: {e}", path))?;
+    // .sxcu 文件可能带 BOM
+    let uploader: SharexUploader = serde_json::from_str(text.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("不是有效的 ShareX 上传器配置: {e}"))?;
+    if uploader.request_url.trim().is_empty() {
+        return Err("ShareX 配置缺少 RequestURL".to_string());
+    }
+    let body = match uploader.body.as_deref().unwrap_or("MultipartFormData") {
+        "MultipartFormData" => "multipart_form_data",
+        "Binary" => "binary",
+        other => return Err(format!("暂不支持 {} 类型的请求体", other)),
+    };
+
+    let name = uploader
+        .name
+        .clone()
+        .unwrap_or_else(|| "ShareX 上传器".to_string());
+    let mut values = Map::new();
+    values.insert("name".into(), json!(name));
+    values.insert("requestUrl".into(), json!(uploader.request_url.trim()));
+    values.insert(
+        "method".into(),
+        json!(uploader.request_method.as_deref().unwrap_or("POST")),
+    );
+    values.insert("body".into(), json!(body));
+    values.insert("fileFormName".into(), json!(uploader.file_form_name));
+    values.insert("parameters".into(), to_json_text(&uploader.parameters));
+    values.insert("arguments".into(), to_json_text(&uploader.arguments));
+    values.insert("urlTemplate".into(), json!(uploader.url));
+    values.insert("deletionUrlTemplate".into(), json!(uploader.deletion_url));
+    // 请求头通常包含 API Key，与设置页一致存入钥匙串
+    if uploader.headers.is_empty() {
+        values.insert("headers".into(), Value::Null);
+    } else {
+        let headers = serde_json::to_string_pretty(&uploader.headers)
+            .map_err(|e| format!("serialize headers: {e}"))?;
+        write_secret(&plugin_secret_id(PLUGIN_ID, "headers"), &headers)?;
+        values.insert("headers".into(), json!(SECRET_PLACEHOLDER));
+    }
+
+    let settings_path = ensure_config_path(&app)?;
+    let mut file = read_settings(&settings_path)?;
+    file.plugins
+        .insert(PLUGIN_ID.to_string(), Value::Object(values));
+    write_settings(&settings_path, &file)?;
+    info!(
+        "import_sharex_uploader success: path={}, name={}",
+        path, name
+    );
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sxcu() {
+        let text = r#"{
+            "Version": "14.1.0",
+            "Name": "example",
+            "DestinationType": "ImageUploader",
+            "RequestMethod": "POST",
+            "RequestURL": "https://example.com/upload",
+            "Headers": { "Authorization": "Bearer x" },
+            "Body": "MultipartFormData",
+            "FileFormName": "image",
+            "URL": "{json:data.url}"
+        }"#;
+        let uploader: SharexUploader = serde_json::from_str(text).unwrap();
+        assert_eq!(uploader.request_url, "https://example.com/upload");
+        assert_eq!(uploader.headers["Authorization"], "Bearer x");
+        assert_eq!(uploader.url.as_deref(), Some("{json:data.url}"));
+        assert!(uploader.deletion_url.is_none());
+    }
+}
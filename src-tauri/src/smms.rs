@@ -0,0 +1,327 @@
+/*
+内置 SM.MS 图床（取代原先的 smms.js 插件，Android 上不再依赖资源包内的脚本）：
+1) 上传：POST `{apiBase}/upload`，`Authorization: <token>`，文件字段 `smfile`；
+   图片已存在（code = image_repeated）时直接返回已有链接，此时没有删除标识；
+2) deleteId 沿用插件时期的 `{ hash, token }` 格式，旧记录可直接删除；缺少 token 时使用已保存的配置；
+3) 删除：GET `{apiBase}/delete/{hash}`；
+4) `fetch_profile` 调用 `{apiBase}/profile` 查询已用空间与容量上限，
+   `host_usage_stats` 据此补充 SM.MS 的剩余空间，设置页也可通过 `smms_profile` 手动查询。
+*/
+
+use log::{error, info, warn};
+use reqwest::multipart;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tauri::AppHandle;
+
+use crate::image_hosts::resolve_plugin_secrets;
+use crate::internal_hosts::{
+    HostDeleteResult, HostHttpConfig, HostUploadResult, decode_delete_marker, encode_delete_marker,
+    load_host_settings, read_upload_file,
+};
+use crate::upload::run_cancellable;
+
+pub(crate) const PLUGIN_ID: &str = "smms";
+const DEFAULT_API_BASE: &str = "https://sm.ms/api/v2";
+const DEFAULT_TIMEOUT_MS: u64 = 60_000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmmsConfig {
+    pub token: String,
+    /// 部分地区需改用镜像 https://smms.app/api/v2
+    #[serde(default)]
+    pub api_base: Option<String>,
+    #[serde(flatten)]
+    pub http: HostHttpConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SmmsDeleteMarker {
+    hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmmsProfile {
+    pub username: Option<String>,
+    pub role: Option<String>,
+    pub disk_usage_bytes: u64,
+    pub disk_limit_bytes: u64,
+}
+
+impl SmmsProfile {
+    pub(crate) fn remaining_bytes(&self) -> u64 {
+        self.disk_limit_bytes.saturating_sub(self.disk_usage_bytes)
+    }
+}
+
+impl SmmsConfig {
+    fn api_base(&self) -> String {
+        self.api_base
+            .as_deref()
+            .map(str::trim)
+            .filter(|base| !base.is_empty())
+            .unwrap_or(DEFAULT_API_BASE)
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    fn token(&self) -> Result<&str, String> {
+        let token = self.token.trim();
+        if token.is_empty() {
+            return Err("SM.MS 需要有效的 API Token，请先在插件设置中填写".to_string());
+        }
+        Ok(token)
+    }
+
+    fn client(&self) -> Result<reqwest::Client, String> {
+        self.http.client(DEFAULT_TIMEOUT_MS)
+    }
+}
+
+/// 解析 SM.MS 统一的 `{ success, code, message, data }` 响应
+async fn parse_response(response: reqwest::Response) -> Result<Value, String> {
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    serde_json::from_str(&text)
+        .map_err(|_| format!("SM.MS 返回了无法解析的响应: HTTP {} {}", status, text))
+}
+
+fn response_message(payload: &Value) -> String {
+    payload
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or("未知错误")
+        .to_string()
+}
+
+/// profile 接口的容量字段：优先取 `*_raw` 字节数
+fn profile_from(data: &Value) -> SmmsProfile {
+    let field = |key: &str| data.get(key).and_then(Value::as_str).map(str::to_string);
+    let bytes = |key: &str| {
+        data.get(key)
+            .and_then(|v| v.as_u64().or_else(|| v.as_str()?.parse().ok()))
+            .unwrap_or(0)
+    };
+    SmmsProfile {
+        username: field("username"),
+        role: field("role"),
+        disk_usage_bytes: bytes("disk_usage_raw"),
+        disk_limit_bytes: bytes("disk_limit_raw"),
+    }
+}
+
+#[tauri::command]
+pub async fn smms_upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: SmmsConfig,
+    upload_id: Option<String>,
+) -> Result<HostUploadResult, String> {
+    run_cancellable(upload_id, upload(file_path, original_file_name, config)).await
+}
+
+async fn upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: SmmsConfig,
+) -> Result<HostUploadResult, String> {
+    let token = config.token()?;
+    let (file_name, bytes) = read_upload_file(&file_path, original_file_name).await?;
+
+    let mime = mime_guess::from_path(&file_name)
+        .first_or_octet_stream()
+        .to_string();
+    let client = config.client()?;
+    let url = format!("{}/upload", config.api_base());
+    let response = config
+        .http
+        .send("SM.MS upload", || {
+            let part = config
+                .http
+                .file_part(&url, &bytes, &file_name)
+                .mime_str(&mime)
+                .map_err(|e| format!("invalid content type {}: {}", mime, e))?;
+            let form = multipart::Form::new()
+                .text("format", "json")
+                .part("smfile", part);
+            Ok(client
+                .post(&url)
+                .header(reqwest::header::AUTHORIZATION, token)
+                .multipart(form))
+        })
+        .await
+        .map_err(|e| format!("SM.MS 上传失败: {}", e))?;
+    let payload = parse_response(response).await?;
+
+    if payload.get("success").and_then(Value::as_bool) != Some(true) {
+        // 重复上传时 images 字段为已有图片的链接
+        if payload.get("code").and_then(Value::as_str) == Some("image_repeated")
+            && let Some(url) = payload.get("images").and_then(Value::as_str)
+        {
+            info!("smms_upload repeated: file={}", file_name);
+            return Ok(HostUploadResult {
+                url: url.to_string(),
+                delete_id: String::new(),
+                metadata: Some(json!({ "repeated": true })),
+            });
+        }
+        let message = response_message(&payload);
+        error!("smms_upload failed: file={}, error={}", file_name, message);
+        return Err(format!("SM.MS 上传失败: {}", message));
+    }
+
+    let data = payload.get("data").cloned().unwrap_or(Value::Null);
+    let field = |key: &str| data.get(key).and_then(Value::as_str).map(str::to_string);
+    let url = field("url").ok_or_else(|| format!("SM.MS 响应缺少 url: {}", payload))?;
+    let hash = field("hash").ok_or_else(|| format!("SM.MS 响应缺少 hash: {}", payload))?;
+    let delete_id = encode_delete_marker(&SmmsDeleteMarker {
+        hash: hash.clone(),
+        token: Some(token.to_string()),
+    })?;
+
+    info!("smms_upload success: hash={}", hash);
+    Ok(HostUploadResult {
+        url,
+        delete_id,
+        metadata: Some(json!({
+            "page": data.get("page"),
+            "size": data.get("size"),
+            "width": data.get("width"),
+            "height": data.get("height"),
+        })),
+    })
+}
+
+#[tauri::command]
+pub async fn smms_delete(app: AppHandle, delete_id: String) -> Result<HostDeleteResult, String> {
+    let marker: SmmsDeleteMarker = decode_delete_marker(&delete_id)?;
+    // 旧记录自带 token，即使尚未保存配置也能删除
+    let marker_token = marker
+        .token
+        .as_deref()
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(str::to_string);
+    let config = match (
+        load_host_settings::<SmmsConfig>(&app, PLUGIN_ID),
+        marker_token,
+    ) {
+        (Ok(config), Some(token)) => SmmsConfig { token, ..config },
+        (Ok(config), None) => config,
+        (Err(_), Some(token)) => SmmsConfig {
+            token,
+            api_base: None,
+            http: Default::default(),
+        },
+        (Err(err), None) => return Err(err),
+    };
+    let token = config.token()?;
+
+    let mut url = url::Url::parse(&format!("{}/delete", config.api_base()))
+        .map_err(|e| format!("invalid SM.MS api base: {e}"))?;
+    url.path_segments_mut()
+        .map_err(|_| "invalid SM.MS api base".to_string())?
+        .push(&marker.hash);
+    let client = config.client()?;
+    let response = config
+        .http
+        .send("SM.MS delete", || {
+            Ok(client
+                .get(url.clone())
+                .header(reqwest::header::AUTHORIZATION, token))
+        })
+        .await
+        .map_err(|e| format!("SM.MS 删除失败: {}", e))?;
+    let payload = parse_response(response).await?;
+    if payload.get("success").and_then(Value::as_bool) != Some(true) {
+        let message = response_message(&payload);
+        warn!(
+            "smms_delete failed: hash={}, error={}",
+            marker.hash, message
+        );
+        return Ok(HostDeleteResult {
+            success: false,
+            message: Some(format!("SM.MS 删除失败: {}", message)),
+        });
+    }
+
+    info!("smms_delete success: hash={}", marker.hash);
+    Ok(HostDeleteResult::deleted("图片已从 SM.MS 删除"))
+}
+
+/// 查询账号的已用空间与容量上限
+pub(crate) async fn fetch_profile(config: &SmmsConfig) -> Result<SmmsProfile, String> {
+    let client = config.client()?;
+    let url = format!("{}/profile", config.api_base());
+    let token = config.token()?;
+    let response = config
+        .http
+        .send("SM.MS profile", || {
+            Ok(client
+                .post(&url)
+                .header(reqwest::header::AUTHORIZATION, token))
+        })
+        .await
+        .map_err(|e| format!("SM.MS 查询账号失败: {}", e))?;
+    let payload = parse_response(response).await?;
+    if payload.get("success").and_then(Value::as_bool) != Some(true) {
+        return Err(format!(
+            "SM.MS 查询账号失败: {}",
+            response_message(&payload)
+        ));
+    }
+    Ok(profile_from(payload.get("data").unwrap_or(&Value::Null)))
+}
+
+/// 读取已保存的配置查询容量；未配置 SM.MS 时返回 None
+pub(crate) async fn saved_profile(app: &AppHandle) -> Result<Option<SmmsProfile>, String> {
+    let Ok(config) = load_host_settings::<SmmsConfig>(app, PLUGIN_ID) else {
+        return Ok(None);
+    };
+    if config.token.trim().is_empty() {
+        return Ok(None);
+    }
+    fetch_profile(&config).await.map(Some)
+}
+
+/// 设置页查询账号信息（传入的配置中 Token 可能是钥匙串占位符）
+#[tauri::command]
+pub async fn smms_profile(config: Value) -> Result<SmmsProfile, String> {
+    let config: SmmsConfig = serde_json::from_value(resolve_plugin_secrets(PLUGIN_ID, config)?)
+        .map_err(|e| format!("invalid smms settings: {e}"))?;
+    fetch_profile(&config).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_from() {
+        let profile = profile_from(&json!({
+            "username": "alice",
+            "role": "user",
+            "disk_usage": "69.86 MB",
+            "disk_usage_raw": 73255644,
+            "disk_limit": "5.00 GB",
+            "disk_limit_raw": "5368709120",
+        }));
+        assert_eq!(profile.username.as_deref(), Some("alice"));
+        assert_eq!(profile.disk_usage_bytes, 73_255_644);
+        assert_eq!(profile.disk_limit_bytes, 5_368_709_120);
+        assert_eq!(profile.remaining_bytes(), 5_368_709_120 - 73_255_644);
+    }
+
+    #[test]
+    fn test_legacy_delete_marker() {
+        // 插件时期的 deleteId
+        let marker: SmmsDeleteMarker =
+            decode_delete_marker(r#"{"hash":"abc","token":"t0k"}"#).unwrap();
+        assert_eq!(marker.hash, "abc");
+        assert_eq!(marker.token.as_deref(), Some("t0k"));
+    }
+}
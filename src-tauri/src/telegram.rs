@@ -0,0 +1,443 @@
+/*
+上传后发布到 Telegram / Telegraph：
+1) 设置 `telegramPosts` 中每项绑定一个图床，该图床的文件上传成功后执行：
+   - chat：通过 Bot API 发到 `chatId`（用户、群组或 `@频道`），`content = link` 发送链接，
+     `photo` 发送图片本身（上传的文件不超过 `PHOTO_UPLOAD_LIMIT` 时直接上传，否则让 Telegram 按链接抓取；GIF 按动画发送）；
+   - telegraph：以 access token 调用 Telegraph API，`pagePath` 为空时每张图新建一个页面，
+     否则把图片追加到该页面末尾（接受路径或完整的 telegra.ph 链接）；
+2) 发布在后台执行，失败只记录日志，不影响上传结果；发送图片时读取的是实际上传的文件（已压缩、已去除元数据），
+   临时文件随后会被删除，因此在发起发布前读入内存；
+   追加到同一 Telegraph 页面需先读取再整体写回，`TELEGRAPH_LOCK` 保证并发上传时不丢条目；
+3) `test_telegram_post(post)` 发送一条测试消息（Telegraph 为查询账号），供设置页校验配置；
+4) token 保存在钥匙串中（见 settings_secrets），发布前按条目 id 读取；
+   Bot API 地址包含 token，请求错误一律去掉地址后再记录或返回，避免写入日志与崩溃报告。
+*/
+
+use std::time::Duration;
+
+use log::{info, warn};
+use reqwest::multipart;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tauri::AppHandle;
+
+use crate::settings_secrets::{resolve_settings_secret, telegram_key};
+
+const BOT_API: &str = "https://api.telegram.org";
+const TELEGRAPH_API: &str = "https://api.telegra.ph";
+const TELEGRAPH_HOSTS: &[&str] = &["telegra.ph", "graph.org"];
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// Bot API 直接上传图片的大小上限
+const PHOTO_UPLOAD_LIMIT: u64 = 10 * 1024 * 1024;
+
+static TELEGRAPH_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PostTarget {
+    #[default]
+    Chat,
+    Telegraph,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PostContent {
+    #[default]
+    Link,
+    Photo,
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelegramPost {
+    /// 钥匙串中 token 条目的标识，保存设置时分配
+    #[serde(default)]
+    pub id: String,
+    /// 触发发布的图床 id
+    pub host: String,
+    #[serde(default)]
+    pub target: PostTarget,
+    /// Bot token 或 Telegraph access token；保存后为钥匙串占位符
+    pub token: String,
+    /// chat：接收消息的 chat_id 或 @频道用户名
+    #[serde(default)]
+    pub chat_id: String,
+    /// chat：发送链接或图片本身
+    #[serde(default)]
+    pub content: PostContent,
+    /// telegraph：追加到的页面，为空时每次新建页面
+    #[serde(default)]
+    pub page_path: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+impl TelegramPost {
+    /// 页面路径：去掉 telegra.ph 链接的协议与域名
+    fn page_path(&self) -> Option<String> {
+        let raw = self.page_path.trim();
+        let path = match url::Url::parse(raw) {
+            Ok(url)
+                if url
+                    .host_str()
+                    .is_some_and(|host| TELEGRAPH_HOSTS.contains(&host)) =>
+            {
+                url.path().to_string()
+            }
+            _ => raw.to_string(),
+        };
+        let path = path.trim_matches('/');
+        (!path.is_empty()).then(|| path.to_string())
+    }
+}
+
+/// Bot token 形如 `123456:ABC-DEF...`
+pub(crate) fn is_bot_token(token: &str) -> bool {
+    let Some((id, secret)) = token.trim().split_once(':') else {
+        return false;
+    };
+    !id.is_empty()
+        && id.chars().all(|c| c.is_ascii_digit())
+        && !secret.is_empty()
+        && secret
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("failed to build http client: {e}"))
+}
+
+/// 解析 Bot API / Telegraph 统一的 `{ ok, result, description | error }` 响应
+async fn api_result(response: reqwest::Response) -> Result<Value, String> {
+    let status = response.status();
+    let text = response.text().await.unwrap_or_default();
+    let payload: Value = serde_json::from_str(&text)
+        .map_err(|_| format!("返回了无法解析的响应: HTTP {status} {text}"))?;
+    if payload.get("ok").and_then(Value::as_bool) == Some(true) {
+        return Ok(payload.get("result").cloned().unwrap_or(Value::Null));
+    }
+    let message = payload
+        .get("description")
+        .or_else(|| payload.get("error"))
+        .and_then(Value::as_str)
+        .unwrap_or(&text)
+        .to_string();
+    Err(format!("HTTP {status} {message}"))
+}
+
+/// 请求错误说明，去掉其中带 token 的请求地址
+fn request_error(context: &str, err: reqwest::Error) -> String {
+    format!("{context}: {}", err.without_url())
+}
+
+fn bot_url(token: &str, method: &str) -> String {
+    format!("{BOT_API}/bot{}/{method}", token.trim())
+}
+
+async fn send_message(
+    client: &reqwest::Client,
+    post: &TelegramPost,
+    text: &str,
+) -> Result<(), String> {
+    let response = client
+        .post(bot_url(&post.token, "sendMessage"))
+        .json(&json!({ "chat_id": post.chat_id.trim(), "text": text }))
+        .send()
+        .await
+        .map_err(|e| request_error("发送 Telegram 消息失败", e))?;
+    api_result(response).await.map(|_| ())
+}
+
+/// 读取要直接发送的图片，超过上限或读取失败时返回 None（改为按链接发送）
+async fn read_photo(path: &str) -> Option<Vec<u8>> {
+    let size = tokio::fs::metadata(path).await.ok()?.len();
+    if size > PHOTO_UPLOAD_LIMIT {
+        return None;
+    }
+    tokio::fs::read(path).await.ok()
+}
+
+/// 从钥匙串读取 token，返回可直接使用的配置
+fn with_token(mut post: TelegramPost) -> Result<TelegramPost, String> {
+    post.token = resolve_settings_secret(&telegram_key(&post.id), &post.token)?;
+    Ok(post)
+}
+
+async fn send_photo(
+    client: &reqwest::Client,
+    post: &TelegramPost,
+    url: &str,
+    file_name: &str,
+    photo: Option<&[u8]>,
+) -> Result<(), String> {
+    let animated = file_name.to_ascii_lowercase().ends_with(".gif");
+    let (method, field) = if animated {
+        ("sendAnimation", "animation")
+    } else {
+        ("sendPhoto", "photo")
+    };
+    let request = client.post(bot_url(&post.token, method));
+    let request = match photo {
+        Some(bytes) => {
+            let form = multipart::Form::new()
+                .text("chat_id", post.chat_id.trim().to_string())
+                .text("caption", url.to_string())
+                .part(
+                    field,
+                    multipart::Part::bytes(bytes.to_vec()).file_name(file_name.to_string()),
+                );
+            request.multipart(form)
+        }
+        // 文件过大或读取失败时由 Telegram 按链接抓取
+        None => request.json(&json!({
+            "chat_id": post.chat_id.trim(),
+            field: url,
+            "caption": url,
+        })),
+    };
+    let response = request
+        .send()
+        .await
+        .map_err(|e| request_error("发送 Telegram 图片失败", e))?;
+    api_result(response).await.map(|_| ())
+}
+
+/// Telegraph 页面中的一张图片（带文件名说明）
+fn telegraph_figure(url: &str, file_name: &str) -> Value {
+    json!({
+        "tag": "figure",
+        "children": [
+            { "tag": "img", "attrs": { "src": url } },
+            { "tag": "figcaption", "children": [file_name] },
+        ],
+    })
+}
+
+/// 发布到 Telegraph，返回页面链接
+async fn post_telegraph(
+    client: &reqwest::Client,
+    post: &TelegramPost,
+    url: &str,
+    file_name: &str,
+) -> Result<String, String> {
+    let figure = telegraph_figure(url, file_name);
+    let token = post.token.trim();
+    let Some(path) = post.page_path() else {
+        let content = json!([figure]).to_string();
+        let response = client
+            .post(format!("{TELEGRAPH_API}/createPage"))
+            .form(&[
+                ("access_token", token),
+                ("title", file_name),
+                ("content", content.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| request_error("创建 Telegraph 页面失败", e))?;
+        let page = api_result(response).await?;
+        return Ok(page
+            .get("url")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string());
+    };
+
+    let _guard = TELEGRAPH_LOCK.lock().await;
+    let response = client
+        .get(format!("{TELEGRAPH_API}/getPage/{path}"))
+        .query(&[("return_content", "true")])
+        .send()
+        .await
+        .map_err(|e| request_error("读取 Telegraph 页面失败", e))?;
+    let page = api_result(response).await?;
+    let title = page
+        .get("title")
+        .and_then(Value::as_str)
+        .unwrap_or(file_name)
+        .to_string();
+    let mut content = page
+        .get("content")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    content.push(figure);
+    let content = Value::Array(content).to_string();
+    let response = client
+        .post(format!("{TELEGRAPH_API}/editPage/{path}"))
+        .form(&[
+            ("access_token", token),
+            ("title", title.as_str()),
+            ("content", content.as_str()),
+        ])
+        .send()
+        .await
+        .map_err(|e| request_error("更新 Telegraph 页面失败", e))?;
+    let page = api_result(response).await?;
+    Ok(page
+        .get("url")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string())
+}
+
+async fn publish(
+    post: &TelegramPost,
+    url: &str,
+    file_name: &str,
+    photo: Option<&[u8]>,
+) -> Result<(), String> {
+    let client = client()?;
+    match (post.target, post.content) {
+        (PostTarget::Chat, PostContent::Link) => send_message(&client, post, url).await,
+        (PostTarget::Chat, PostContent::Photo) => {
+            send_photo(&client, post, url, file_name, photo).await
+        }
+        (PostTarget::Telegraph, _) => {
+            let page = post_telegraph(&client, post, url, file_name).await?;
+            info!("telegraph post success: file={}, page={}", file_name, page);
+            Ok(())
+        }
+    }
+}
+
+/// 按设置把该图床上传成功的链接发布到 Telegram / Telegraph（后台执行），`uploaded_path` 为实际上传的文件
+pub(crate) async fn post(
+    app: &AppHandle,
+    host: &str,
+    url: &str,
+    file_name: &str,
+    uploaded_path: &str,
+) {
+    let posts: Vec<TelegramPost> = crate::settings::current_settings(app)
+        .telegram_posts
+        .into_iter()
+        .filter(|post| post.enabled && post.host == host)
+        .filter_map(|post| {
+            with_token(post)
+                .inspect_err(|err| warn!("telegram post skipped: host={}, error={}", host, err))
+                .ok()
+        })
+        .collect();
+    if posts.is_empty() {
+        return;
+    }
+    let photo = if posts
+        .iter()
+        .any(|post| post.target == PostTarget::Chat && post.content == PostContent::Photo)
+    {
+        read_photo(uploaded_path).await
+    } else {
+        None
+    };
+    let (url, file_name) = (url.to_string(), file_name.to_string());
+    tauri::async_runtime::spawn(async move {
+        for post in posts {
+            if let Err(err) = publish(&post, &url, &file_name, photo.as_deref()).await {
+                warn!(
+                    "telegram post failed: host={}, target={:?}, error={}",
+                    post.host, post.target, err
+                );
+            }
+        }
+    });
+}
+
+/// 发送测试消息（Telegraph 查询账号），返回结果说明
+#[tauri::command]
+pub async fn test_telegram_post(post: TelegramPost) -> Result<String, String> {
+    if post.token.trim().is_empty() {
+        return Err("未填写 token".to_string());
+    }
+    let post = with_token(post)?;
+    let client = client()?;
+    match post.target {
+        PostTarget::Chat => {
+            if post.chat_id.trim().is_empty() {
+                return Err("未填写 chat_id".to_string());
+            }
+            send_message(&client, &post, "yana 测试消息").await?;
+            info!("test telegram post success: chat={}", post.chat_id.trim());
+            Ok(format!("已发送测试消息到 {}", post.chat_id.trim()))
+        }
+        PostTarget::Telegraph => {
+            let response = client
+                .post(format!("{TELEGRAPH_API}/getAccountInfo"))
+                .form(&[("access_token", post.token.trim())])
+                .send()
+                .await
+                .map_err(|e| request_error("查询 Telegraph 账号失败", e))?;
+            let account = api_result(response).await?;
+            let name = account
+                .get("short_name")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            Ok(format!("Telegraph 账号：{name}"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn telegraph(page_path: &str) -> TelegramPost {
+        TelegramPost {
+            host: "s3".to_string(),
+            id: "t1".to_string(),
+            target: PostTarget::Telegraph,
+            token: "abc".to_string(),
+            chat_id: String::new(),
+            content: PostContent::Link,
+            page_path: page_path.to_string(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_page_path() {
+        assert_eq!(
+            telegraph("https://telegra.ph/My-Album-05-01")
+                .page_path()
+                .as_deref(),
+            Some("My-Album-05-01")
+        );
+        assert_eq!(
+            telegraph("/My-Album-05-01/").page_path().as_deref(),
+            Some("My-Album-05-01")
+        );
+        assert_eq!(telegraph("  ").page_path(), None);
+    }
+
+    #[test]
+    fn test_is_bot_token() {
+        assert!(is_bot_token("123456:ABC-def_42"));
+        assert!(!is_bot_token("ABC-def"));
+        assert!(!is_bot_token("12a:ABC"));
+        assert!(!is_bot_token("123456:"));
+    }
+
+    #[test]
+    fn test_post_defaults() {
+        let post: TelegramPost =
+            serde_json::from_str(r#"{"host":"smms","token":"1:a","chatId":"@channel"}"#).unwrap();
+        assert_eq!(post.target, PostTarget::Chat);
+        assert_eq!(post.content, PostContent::Link);
+        assert!(post.enabled);
+
+        let figure = telegraph_figure("https://i.example.com/a.png", "a.png");
+        assert_eq!(
+            figure["children"][0]["attrs"]["src"],
+            "https://i.example.com/a.png"
+        );
+        assert_eq!(figure["children"][1]["children"][0], "a.png");
+    }
+}
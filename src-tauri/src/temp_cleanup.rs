@@ -0,0 +1,185 @@
+/*
+临时目录清理策略：
+1) 启动时删除应用临时目录中超过 `tempMaxAgeHours` 的文件（0 表示启动时不清理）；
+2) 设置 `cleanTempOnExit` 时，应用退出前清空临时目录；
+3) 待上传队列中仍引用的文件始终保留，避免崩溃恢复时源文件已被删除；
+4) 清理后保留空的临时目录本身，后续 tempfile_in 调用无需重新创建。
+*/
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use log::{info, warn};
+use serde::Serialize;
+use tauri::AppHandle;
+
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TempCleanupReport {
+    pub removed_files: u64,
+    pub freed_bytes: u64,
+    /// 因未过期或仍被待上传记录引用而保留的文件数
+    pub kept_files: u64,
+}
+
+fn is_expired(path: &Path, max_age: Option<Duration>, now: SystemTime) -> bool {
+    let Some(max_age) = max_age else {
+        return true;
+    };
+    fs::symlink_metadata(path)
+        .and_then(|meta| meta.modified())
+        .map(|modified| now.duration_since(modified).unwrap_or_default() >= max_age)
+        .unwrap_or(true)
+}
+
+fn clean_dir(
+    dir: &Path,
+    max_age: Option<Duration>,
+    keep: &HashSet<PathBuf>,
+    now: SystemTime,
+    report: &mut TempCleanupReport,
+) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("read_dir {}: {e}", dir.display()))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            clean_dir(&path, max_age, keep, now, report)?;
+            // 子目录清空后一并删除，仍有保留文件时删除会失败，忽略即可
+            let _ = fs::remove_dir(&path);
+            continue;
+        }
+        if keep.contains(&path) || !is_expired(&path, max_age, now) {
+            report.kept_files += 1;
+            continue;
+        }
+        let size = entry.metadata().map(|meta| meta.len()).unwrap_or(0);
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                report.removed_files += 1;
+                report.freed_bytes += size;
+            }
+            // 文件可能仍被占用（Windows），保留到下次清理
+            Err(err) => {
+                warn!("remove temp file {} failed: {}", path.display(), err);
+                report.kept_files += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 清理临时目录：`max_age` 为 None 时删除全部文件，否则只删除修改时间早于该时长的文件
+pub(crate) fn clean(
+    dir: &Path,
+    max_age: Option<Duration>,
+    keep: &HashSet<PathBuf>,
+) -> Result<TempCleanupReport, String> {
+    let mut report = TempCleanupReport::default();
+    if dir.exists() {
+        clean_dir(dir, max_age, keep, SystemTime::now(), &mut report)?;
+    }
+    fs::create_dir_all(dir).map_err(|e| format!("create app temp dir {}: {e}", dir.display()))?;
+    Ok(report)
+}
+
+/// 上传日志（pending_uploads）中仍引用的文件
+fn referenced_paths(app: &AppHandle) -> HashSet<PathBuf> {
+    crate::pending_uploads::referenced_file_paths(app)
+        .unwrap_or_else(|err| {
+            warn!("list pending upload files failed: {}", err);
+            Vec::new()
+        })
+        .into_iter()
+        .map(PathBuf::from)
+        .collect()
+}
+
+pub(crate) fn clean_app_temp(
+    app: &AppHandle,
+    max_age: Option<Duration>,
+) -> Result<TempCleanupReport, String> {
+    let dir = crate::portable::temp_dir();
+    let report = clean(&dir, max_age, &referenced_paths(app))?;
+    info!(
+        "clean app temp dir: dir={}, removed={}, freed={} bytes, kept={}",
+        dir.display(),
+        report.removed_files,
+        report.freed_bytes,
+        report.kept_files
+    );
+    Ok(report)
+}
+
+fn hours(value: u32) -> Duration {
+    Duration::from_secs(value as u64 * 3600)
+}
+
+/// 启动时按 `tempMaxAgeHours` 清理过期文件，在后台线程执行不阻塞启动
+pub(crate) fn on_startup(app: &AppHandle) {
+    let max_age_hours = crate::settings::current_settings(app).temp_max_age_hours;
+    if max_age_hours == 0 {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(err) = clean_app_temp(&app, Some(hours(max_age_hours))) {
+            warn!("startup temp cleanup failed: {}", err);
+        }
+    });
+}
+
+/// 退出时按 `cleanTempOnExit` 清空临时目录
+pub(crate) fn on_exit(app: &AppHandle) {
+    if !crate::settings::current_settings(app).clean_temp_on_exit {
+        return;
+    }
+    if let Err(err) = clean_app_temp(app, None) {
+        warn!("exit temp cleanup failed: {}", err);
+    }
+}
+
+/// 手动清理临时目录；`max_age_hours` 为空或 0 时删除全部未被引用的文件
+#[tauri::command]
+pub async fn clean_app_temp_dir(
+    app: AppHandle,
+    max_age_hours: Option<u32>,
+) -> Result<TempCleanupReport, String> {
+    let max_age = max_age_hours.filter(|h| *h > 0).map(hours);
+    tokio::task::spawn_blocking(move || clean_app_temp(&app, max_age))
+        .await
+        .map_err(|e| format!("spawn_blocking error: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_keeps_referenced_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("temp");
+        fs::create_dir_all(root.join("nested")).unwrap();
+        let keep_path = root.join("pending.png");
+        fs::write(&keep_path, b"keep").unwrap();
+        fs::write(root.join("a.webp"), b"12345").unwrap();
+        fs::write(root.join("nested").join("b.png"), b"123").unwrap();
+
+        let keep = HashSet::from([keep_path.clone()]);
+        let fresh = clean(&root, Some(Duration::from_secs(3600)), &keep).unwrap();
+        assert_eq!(fresh.removed_files, 0);
+        assert_eq!(fresh.kept_files, 3);
+
+        let report = clean(&root, None, &keep).unwrap();
+        assert_eq!(report.removed_files, 2);
+        assert_eq!(report.freed_bytes, 8);
+        assert!(keep_path.exists());
+        assert!(!root.join("nested").exists());
+    }
+}
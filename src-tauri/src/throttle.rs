@@ -0,0 +1,187 @@
+/*
+上传限速：
+1) 全局限速来自设置 `upload_rate_limit_kbps`（0 表示不限速），所有上传共享同一令牌桶；
+2) 单个图床可在配置中指定 `rate_limit_kbps`，同一主机的并发上传共享该主机的令牌桶；
+3) 启用限速时请求体按小块流式发送，每块发送前从各令牌桶中扣除相应字节数；
+4) 在上传队列任务中构建的请求体同样按块发送，并向队列上报已发送的字节数。
+*/
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use log::info;
+
+use crate::upload_queue::{PROGRESS_INTERVAL, ProgressReporter};
+
+/// 每次发送的块大小
+const CHUNK_SIZE: usize = 16 * 1024;
+
+static GLOBAL_BUCKET: OnceLock<Arc<TokenBucket>> = OnceLock::new();
+static HOST_BUCKETS: OnceLock<Mutex<HashMap<String, Arc<TokenBucket>>>> = OnceLock::new();
+
+struct BucketState {
+    /// 速率（字节/秒），0 表示不限速
+    rate: u64,
+    /// 可用令牌，允许为负（表示需要等待偿还）
+    available: f64,
+    last_refill: Instant,
+}
+
+pub(crate) struct TokenBucket {
+    state: Mutex<BucketState>,
+}
+
+impl TokenBucket {
+    fn new(rate: u64) -> Self {
+        Self {
+            state: Mutex::new(BucketState {
+                rate,
+                available: rate as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    fn set_rate(&self, rate: u64) {
+        if let Ok(mut state) = self.state.lock()
+            && state.rate != rate
+        {
+            state.rate = rate;
+            state.available = state.available.min(rate as f64);
+            state.last_refill = Instant::now();
+        }
+    }
+
+    fn is_limited(&self) -> bool {
+        self.state.lock().map(|s| s.rate > 0).unwrap_or(false)
+    }
+
+    /// 扣除令牌；不足时按欠额等待，突发上限为 1 秒的配额
+    async fn consume(&self, amount: usize) {
+        let wait = {
+            let Ok(mut state) = self.state.lock() else {
+                return;
+            };
+            if state.rate == 0 {
+                return;
+            }
+            let now = Instant::now();
+            let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+            let rate = state.rate as f64;
+            state.available = (state.available + elapsed * rate).min(rate);
+            state.last_refill = now;
+            state.available -= amount as f64;
+            if state.available < 0.0 {
+                Duration::from_secs_f64(-state.available / rate)
+            } else {
+                Duration::ZERO
+            }
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+fn kbps_to_rate(kbps: u32) -> u64 {
+    kbps as u64 * 1024
+}
+
+fn global_bucket() -> &'static Arc<TokenBucket> {
+    GLOBAL_BUCKET.get_or_init(|| Arc::new(TokenBucket::new(0)))
+}
+
+/// 更新全局限速（KB/s），0 表示不限速
+pub(crate) fn set_global_limit(kbps: u32) {
+    global_bucket().set_rate(kbps_to_rate(kbps));
+    info!("global upload rate limit set to {} KB/s", kbps);
+}
+
+fn host_bucket(url: &str, kbps: u32) -> Option<Arc<TokenBucket>> {
+    let host = reqwest::Url::parse(url).ok()?.host_str()?.to_string();
+    let buckets = HOST_BUCKETS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut buckets = buckets.lock().ok()?;
+    let bucket = buckets
+        .entry(host)
+        .or_insert_with(|| Arc::new(TokenBucket::new(kbps_to_rate(kbps))))
+        .clone();
+    bucket.set_rate(kbps_to_rate(kbps));
+    Some(bucket)
+}
+
+/// 一次上传适用的限速器集合
+#[derive(Clone, Default)]
+pub(crate) struct Throttle {
+    buckets: Vec<Arc<TokenBucket>>,
+}
+
+impl Throttle {
+    /// 组合全局限速与目标主机的限速
+    pub(crate) fn for_url(url: &str, host_limit_kbps: Option<u32>) -> Self {
+        let mut buckets = Vec::new();
+        if global_bucket().is_limited() {
+            buckets.push(global_bucket().clone());
+        }
+        if let Some(bucket) = host_limit_kbps
+            .filter(|kbps| *kbps > 0)
+            .and_then(|kbps| host_bucket(url, kbps))
+        {
+            buckets.push(bucket);
+        }
+        Self { buckets }
+    }
+
+    pub(crate) fn is_active(&self) -> bool {
+        !self.buckets.is_empty()
+    }
+
+    /// 构建请求体；未限速且不在队列任务中时直接使用内存中的字节，否则按块流式发送
+    pub(crate) fn body(&self, bytes: Bytes) -> reqwest::Body {
+        let progress = ProgressReporter::current();
+        if !self.is_active() && progress.is_none() {
+            return reqwest::Body::from(bytes);
+        }
+        let total = bytes.len() as u64;
+        let state = BodyState {
+            rest: bytes,
+            buckets: self.buckets.clone(),
+            progress,
+            sent: 0,
+            last_report: None,
+        };
+        let stream = futures::stream::unfold(state, move |mut state| async move {
+            if state.rest.is_empty() {
+                return None;
+            }
+            let chunk = state.rest.split_to(CHUNK_SIZE.min(state.rest.len()));
+            for bucket in &state.buckets {
+                bucket.consume(chunk.len()).await;
+            }
+            state.sent += chunk.len() as u64;
+            if let Some(progress) = &state.progress {
+                let due = state
+                    .last_report
+                    .is_none_or(|last| last.elapsed() >= PROGRESS_INTERVAL);
+                if due || state.rest.is_empty() {
+                    progress.report(state.sent, total);
+                    state.last_report = Some(Instant::now());
+                }
+            }
+            Some((Ok::<Bytes, std::io::Error>(chunk), state))
+        });
+        reqwest::Body::wrap_stream(stream)
+    }
+}
+
+/// 分块发送的请求体状态
+struct BodyState {
+    rest: Bytes,
+    buckets: Vec<Arc<TokenBucket>>,
+    progress: Option<ProgressReporter>,
+    sent: u64,
+    last_report: Option<Instant>,
+}
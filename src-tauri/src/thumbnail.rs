@@ -8,7 +8,7 @@
 6) 返回缩略图文件的本地路径数组。
 
 设计说明：
-- 临时文件存储在系统临时目录 (std::env::temp_dir()/com.yana.dev)
+- 临时文件存储在系统临时目录 (std::env::temp_dir()/com.yana.dev)，便携模式下为 data/temp
 - 网络下载部分使用异步（I/O 密集，使用 futures::join_all 并发）
 - 图片压缩部分在异步上下文中直接执行（同步 CPU 密集）
 - 参考 process.rs 的架构模式
@@ -21,7 +21,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 
 use log::{debug, error, info, warn};
 use sha2::{Digest, Sha256};
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 
 // 全局生成缩略图互斥锁：确保同时只有一个任务在执行
 // 防止频繁切回导致的任务堆积
@@ -31,11 +31,9 @@ const CACHE_DIR_NAME: &str = "cache";
 const THUMBNAIL_WIDTH: u32 = 320;
 const THUMBNAIL_HEIGHT: u32 = 225; // 320 * 0.70 ≈ 224，与前端 70% padding-top 对应
 
-/// 获取应用临时目录（系统 temp 下的 com.yana.dev）
+/// 获取应用临时目录（系统 temp 下的 com.yana.dev，便携模式下为 data/temp）
 fn app_temp_dir() -> Result<PathBuf, String> {
-    let mut dir = std::env::temp_dir();
-    dir.push("com.yana.dev");
-    Ok(dir)
+    Ok(crate::portable::temp_dir())
 }
 
 /// 确保应用临时目录存在
@@ -48,9 +46,7 @@ fn ensure_app_temp_dir() -> Result<PathBuf, String> {
 
 /// 获取应用数据目录下的缓存文件夹路径
 fn get_cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
+    let app_data_dir = crate::portable::app_data_dir(&app)
         .map_err(|err| format!("Failed to resolve app data dir: {err}"))?;
 
     let cache_dir = app_data_dir.join(CACHE_DIR_NAME);
@@ -95,7 +91,7 @@ fn generate_cache_path(cache_dir: &PathBuf, url: &str) -> PathBuf {
 }
 
 /// 下载图片到指定路径（异步 I/O，带重试机制和自适应策略）
-async fn download_image(url: &str, dest_path: &PathBuf) -> Result<u64, String> {
+pub(crate) async fn download_image(url: &str, dest_path: &PathBuf) -> Result<u64, String> {
     const MAX_RETRIES: u32 = 3;
     const INITIAL_TIMEOUT_SECS: u64 = 30;
     const MAX_SIZE: u64 = 50 * 1024 * 1024; // 50MB 限制
@@ -348,8 +344,13 @@ fn compress_to_thumbnail(input_path: &PathBuf, output_path: &PathBuf) -> Result<
     let img = image::open(input_path)
         .map_err(|e| format!("Failed to open image {}: {}", input_path.display(), e))?;
 
-    // 按照缩略图尺寸调整大小，使用 Lanczos3 过滤（高质量）
-    let thumbnail = img.thumbnail(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT);
+    // 按照缩略图尺寸等比缩放，使用 Lanczos3 过滤（高质量，SIMD 加速）
+    let thumbnail = crate::fast_resize::fit_within(
+        &img,
+        THUMBNAIL_WIDTH,
+        THUMBNAIL_HEIGHT,
+        image::imageops::FilterType::Lanczos3,
+    );
 
     // 转换为 WebP 格式以获得更好的压缩比
     thumbnail
@@ -539,6 +540,12 @@ async fn generate_thumbnails_impl(
     Ok(output)
 }
 
+/// 缓存中已有的缩略图路径（导出静态图库等功能复用）
+pub(crate) fn cached_thumbnail(app: &AppHandle, url: &str) -> Result<Option<PathBuf>, String> {
+    let cache_path = generate_cache_path(&get_cache_dir(app)?, url);
+    Ok(cache_path.exists().then_some(cache_path))
+}
+
 /// 获取单个图片的缩略图本地路径（如果存在）
 #[tauri::command]
 pub fn get_thumbnail_path(app: AppHandle, url: String) -> Result<Option<String>, String> {
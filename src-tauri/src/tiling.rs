@@ -0,0 +1,297 @@
+/*
+超限图片切分上传（如很长的整页截图）：
+1) `upload_image_tiles(path, host, options)` 把图片按高度均分为若干纵向切片，
+   每片编码后不超过单文件大小上限（`max_bytes`，未指定时按图床的已知上限，未知图床为 5MB），
+   也可用 `max_height` 限制单片高度；
+2) 切片数先按文件大小估算，编码后仍有切片超限时增加切片数重新切分；
+   JPEG 切片仍编码为 JPEG，其他格式编码为 PNG（无损）；
+3) 图片本身未超限时不切分，直接上传原文件；
+4) 切片按顺序经 host_upload 上传，返回有序的链接列表与依次拼接的 Markdown 片段
+   （每片一行，渲染后上下相接）。
+*/
+
+use std::io::Cursor;
+use std::path::Path;
+
+use image::{DynamicImage, GenericImageView, ImageFormat};
+use log::info;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::batch_upload::BatchUploadOptions;
+
+const MB: u64 = 1024 * 1024;
+/// 未知图床的单文件上限
+const DEFAULT_MAX_BYTES: u64 = 5 * MB;
+/// 编码后仍超限时最多重新切分的次数
+const MAX_ATTEMPTS: usize = 8;
+const JPEG_QUALITY: u8 = 90;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TileOptions {
+    /// 单个切片的最大字节数
+    pub max_bytes: Option<u64>,
+    /// 单个切片的最大高度（像素）
+    pub max_height: Option<u32>,
+    #[serde(flatten)]
+    pub upload: BatchUploadOptions,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TileUpload {
+    pub index: usize,
+    /// 切片在原图中的纵向位置
+    pub y: u32,
+    pub height: u32,
+    pub size: u64,
+    pub url: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TileUploadResult {
+    pub width: u32,
+    pub height: u32,
+    pub tiles: Vec<TileUpload>,
+    /// 按顺序排列的链接（全部切片上传成功时才完整）
+    pub urls: Vec<String>,
+    /// 全部切片上传成功时依次拼接的 Markdown
+    pub markdown: Option<String>,
+}
+
+/// 内置图床的已知单文件上限
+fn host_limit(host: &str) -> Option<u64> {
+    match host {
+        "imgur" => Some(20 * MB),
+        "cloudinary" => Some(10 * MB),
+        "github" => Some(25 * MB),
+        "smms" => Some(5 * MB),
+        _ => None,
+    }
+}
+
+/// 把高度均分为 count 段，返回每段的 (y, height)，余数分给前面的切片
+fn tile_ranges(height: u32, count: u32) -> Vec<(u32, u32)> {
+    let count = count.clamp(1, height.max(1));
+    let (base, rest) = (height / count, height % count);
+    let mut y = 0;
+    (0..count)
+        .map(|index| {
+            let h = base + u32::from(index < rest);
+            let range = (y, h);
+            y += h;
+            range
+        })
+        .collect()
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>, String> {
+    if format == ImageFormat::Jpeg {
+        return crate::process::encode_jpeg(image, JPEG_QUALITY);
+    }
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| format!("encode tile png: {e}"))?;
+    Ok(bytes)
+}
+
+/// 切分并编码，直到每片都不超过 max_bytes；返回 (y, height, 编码结果)
+fn split(
+    image: &DynamicImage,
+    format: ImageFormat,
+    file_size: u64,
+    max_bytes: u64,
+    max_height: Option<u32>,
+) -> Result<Vec<(u32, u32, Vec<u8>)>, String> {
+    let (width, height) = image.dimensions();
+    // 单独编码的切片比整图略大，估算时留出余量
+    let by_size = (file_size as f64 * 1.1 / max_bytes as f64).ceil() as u32;
+    let by_height = max_height.map_or(1, |max| height.div_ceil(max.max(1)));
+    let mut count = by_size.max(by_height).max(2);
+    for _ in 0..MAX_ATTEMPTS {
+        if count > height {
+            break;
+        }
+        let tiles = tile_ranges(height, count)
+            .into_iter()
+            .map(|(y, h)| Ok((y, h, encode(&image.crop_imm(0, y, width, h), format)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        let largest = tiles.iter().map(|(_, _, bytes)| bytes.len() as u64).max();
+        match largest {
+            Some(largest) if largest > max_bytes => {
+                let scaled = (count as f64 * largest as f64 / max_bytes as f64 * 1.1).ceil();
+                count = (scaled as u32).max(count + 1);
+            }
+            _ => return Ok(tiles),
+        }
+    }
+    Err(format!(
+        "无法把图片切分到每片 {:.1} MB 以内",
+        max_bytes as f64 / MB as f64
+    ))
+}
+
+fn markdown(stem: &str, urls: &[String]) -> String {
+    urls.iter()
+        .enumerate()
+        .map(|(index, url)| format!("![{stem} {}/{}]({url})", index + 1, urls.len()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 切分超限图片并按顺序上传到指定图床
+#[tauri::command]
+pub async fn upload_image_tiles(
+    app: AppHandle,
+    path: String,
+    host: String,
+    options: Option<TileOptions>,
+) -> Result<TileUploadResult, String> {
+    let host = host.trim().to_string();
+    if host.is_empty() {
+        return Err("未指定图床".to_string());
+    }
+    let options = options.unwrap_or_default();
+    let max_bytes = options
+        .max_bytes
+        .filter(|max| *max > 0)
+        .or_else(|| host_limit(&host))
+        .unwrap_or(DEFAULT_MAX_BYTES);
+    let source = Path::new(&path);
+    let stem = source
+        .file_stem()
+        .map(|stem| crate::internal_hosts::sanitize_file_name(&stem.to_string_lossy()))
+        .unwrap_or_else(|| "image".to_string());
+    let file_size = std::fs::metadata(source)
+        .map_err(|e| format!("读取图片 {path} 失败: {e}"))?
+        .len();
+    let image = image::open(source).map_err(|e| format!("读取图片 {path} 失败: {e}"))?;
+    let (width, height) = image.dimensions();
+
+    let fits = file_size <= max_bytes && options.max_height.is_none_or(|max| height <= max);
+    let dir = crate::rehost::temp_dir("yana_tiles_")?;
+    let mut ranges = Vec::new();
+    let mut paths = Vec::new();
+    if fits {
+        ranges.push((0, height, file_size));
+        paths.push(path.clone());
+    } else {
+        let format = match ImageFormat::from_path(source) {
+            Ok(ImageFormat::Jpeg) => ImageFormat::Jpeg,
+            _ => ImageFormat::Png,
+        };
+        let max_height = options.max_height;
+        let tiles = tokio::task::spawn_blocking(move || {
+            split(&image, format, file_size, max_bytes, max_height)
+        })
+        .await
+        .map_err(|e| format!("spawn_blocking error: {}", e))??;
+        let ext = format.extensions_str().first().copied().unwrap_or("png");
+        let digits = tiles.len().to_string().len().max(2);
+        for (index, (y, h, bytes)) in tiles.into_iter().enumerate() {
+            let tile = dir
+                .path()
+                .join(format!("{stem}_{:0digits$}.{ext}", index + 1));
+            std::fs::write(&tile, &bytes).map_err(|e| format!("写入切片失败: {e}"))?;
+            ranges.push((y, h, bytes.len() as u64));
+            paths.push(tile.to_string_lossy().into_owned());
+        }
+    }
+    info!(
+        "upload image tiles: path={}, host={}, size={}x{}, bytes={}, tiles={}",
+        path,
+        host,
+        width,
+        height,
+        file_size,
+        paths.len()
+    );
+
+    let outcomes =
+        crate::host_upload::compress_and_upload(&app, &host, paths, &options.upload.pipeline())
+            .await;
+    let tiles: Vec<TileUpload> = ranges
+        .into_iter()
+        .zip(outcomes)
+        .enumerate()
+        .map(|(index, ((y, height, size), outcome))| {
+            let (url, error) = match outcome.result {
+                Ok(result) => (Some(result.url), None),
+                Err(error) => (None, Some(error)),
+            };
+            TileUpload {
+                index,
+                y,
+                height,
+                size,
+                url,
+                error,
+            }
+        })
+        .collect();
+    let urls: Vec<String> = tiles.iter().filter_map(|tile| tile.url.clone()).collect();
+    let markdown = (urls.len() == tiles.len()).then(|| markdown(&stem, &urls));
+    Ok(TileUploadResult {
+        width,
+        height,
+        tiles,
+        urls,
+        markdown,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_tile_ranges() {
+        assert_eq!(tile_ranges(10, 3), vec![(0, 4), (4, 3), (7, 3)]);
+        assert_eq!(tile_ranges(5, 1), vec![(0, 5)]);
+        // 切片数不超过像素行数
+        assert_eq!(tile_ranges(2, 5), vec![(0, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn test_split_under_limit() {
+        // 噪点图几乎无法压缩，便于构造超限的图片
+        let mut seed: u32 = 1;
+        let image = RgbImage::from_fn(64, 400, |_, _| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let [r, g, b, _] = seed.to_le_bytes();
+            Rgb([r, g, b])
+        });
+        let image = DynamicImage::ImageRgb8(image);
+        let full = encode(&image, ImageFormat::Png).unwrap().len() as u64;
+        let max_bytes = full / 3;
+        let tiles = split(&image, ImageFormat::Png, full, max_bytes, None).unwrap();
+        assert!(tiles.len() >= 3);
+        assert!(
+            tiles
+                .iter()
+                .all(|(_, _, bytes)| bytes.len() as u64 <= max_bytes)
+        );
+        let total: u32 = tiles.iter().map(|(_, h, _)| h).sum();
+        assert_eq!(total, 400);
+
+        let tiles = split(&image, ImageFormat::Png, full, full * 2, Some(100)).unwrap();
+        assert_eq!(tiles.len(), 4);
+        assert!(split(&image, ImageFormat::Png, full, 16, None).is_err());
+    }
+
+    #[test]
+    fn test_markdown() {
+        let urls = vec!["https://a/1.png".to_string(), "https://a/2.png".to_string()];
+        assert_eq!(
+            markdown("page", &urls),
+            "![page 1/2](https://a/1.png)\n![page 2/2](https://a/2.png)"
+        );
+        assert_eq!(host_limit("imgur"), Some(20 * MB));
+        assert_eq!(host_limit("my-plugin"), None);
+    }
+}
@@ -0,0 +1,127 @@
+/*
+双向 TLS（mTLS）客户端证书：
+图床位于要求客户端证书的反向代理之后时，由配置指定证书与私钥，构建 HTTP 客户端时加载。
+1) PEM：证书（链）与私钥可在同一文件中，也可分别指定；
+2) PKCS#12（.p12 / .pfx）：解析出私钥与证书链后转为 PEM 交给 rustls；
+   密码不写入配置，保存在系统钥匙串中（`secret_store`），配置中只记录条目 id；
+3) 可额外信任自签名代理的 CA 证书（PEM）。
+*/
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use log::debug;
+use reqwest::{Certificate, ClientBuilder, Identity};
+use serde::{Deserialize, Serialize};
+
+/// 证书文件格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CertificateFormat {
+    #[default]
+    Pem,
+    Pkcs12,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientCertificate {
+    /// PEM 证书（链）文件路径；若私钥也在该文件中可省略 key_path。
+    /// format 为 pkcs12 时为 .p12 / .pfx 文件路径
+    pub cert_path: String,
+    #[serde(default)]
+    pub format: CertificateFormat,
+    /// PEM 私钥文件路径（PKCS#8 / RSA / EC），仅 PEM 格式使用
+    #[serde(default)]
+    pub key_path: Option<String>,
+    /// PKCS#12 密码在钥匙串中的条目 id；为空表示文件没有密码
+    #[serde(default)]
+    pub password_secret_id: Option<String>,
+    /// 额外信任的 CA 证书（PEM），用于自签名的代理
+    #[serde(default)]
+    pub ca_cert_path: Option<String>,
+}
+
+fn read_pem(path: &str, label: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("failed to read {} {}: {e}", label, path))
+}
+
+/// 把 DER 内容编码为 PEM 块
+fn der_to_pem(label: &str, der: &[u8]) -> String {
+    let encoded = BASE64.encode(der);
+    let mut pem = format!("-----BEGIN {label}-----\n");
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(&String::from_utf8_lossy(line));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {label}-----\n"));
+    pem
+}
+
+/// 解析 PKCS#12 内容，返回 PEM 格式的证书链与 PKCS#8 私钥
+fn pkcs12_to_pem(data: &[u8], password: &str) -> Result<Vec<u8>, String> {
+    let keystore = p12_keystore::KeyStore::from_pkcs12(data, password)
+        .map_err(|e| format!("invalid PKCS#12 file or password: {e}"))?;
+    let (_, chain) = keystore
+        .private_key_chain()
+        .ok_or_else(|| "PKCS#12 file contains no private key".to_string())?;
+    if chain.chain().is_empty() {
+        return Err("PKCS#12 file contains no certificate".to_string());
+    }
+    let mut pem = String::new();
+    for certificate in chain.chain() {
+        pem.push_str(&der_to_pem("CERTIFICATE", certificate.as_der()));
+    }
+    pem.push_str(&der_to_pem("PRIVATE KEY", chain.key()));
+    Ok(pem.into_bytes())
+}
+
+impl ClientCertificate {
+    fn identity(&self) -> Result<Identity, String> {
+        if self.format == CertificateFormat::Pkcs12 {
+            let data = read_pem(&self.cert_path, "PKCS#12 file")?;
+            let password = match self
+                .password_secret_id
+                .as_deref()
+                .filter(|id| !id.trim().is_empty())
+            {
+                Some(id) => crate::secrets::read_secret(id)?
+                    .ok_or_else(|| format!("PKCS#12 password not found in keyring: {id}"))?,
+                None => String::new(),
+            };
+            let pem = pkcs12_to_pem(&data, &password)?;
+            return Identity::from_pem(&pem)
+                .map_err(|e| format!("invalid client certificate or key: {e}"));
+        }
+
+        let mut pem = read_pem(&self.cert_path, "client certificate")?;
+        if let Some(key_path) = self.key_path.as_deref().filter(|p| !p.trim().is_empty()) {
+            if !pem.ends_with(b"\n") {
+                pem.push(b'\n');
+            }
+            pem.extend_from_slice(&read_pem(key_path, "client key")?);
+        }
+        Identity::from_pem(&pem).map_err(|e| format!("invalid client certificate or key: {e}"))
+    }
+}
+
+/// 按需为客户端加载客户端证书与额外的 CA 证书
+pub(crate) fn apply_client_certificate(
+    builder: ClientBuilder,
+    certificate: Option<&ClientCertificate>,
+) -> Result<ClientBuilder, String> {
+    let Some(certificate) = certificate else {
+        return Ok(builder);
+    };
+
+    let mut builder = builder.identity(certificate.identity()?);
+    if let Some(ca_path) = certificate
+        .ca_cert_path
+        .as_deref()
+        .filter(|p| !p.trim().is_empty())
+    {
+        let ca = Certificate::from_pem(&read_pem(ca_path, "CA certificate")?)
+            .map_err(|e| format!("invalid CA certificate {}: {e}", ca_path))?;
+        builder = builder.add_root_certificate(ca);
+    }
+    debug!("client certificate loaded: {}", certificate.cert_path);
+    Ok(builder)
+}
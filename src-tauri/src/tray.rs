@@ -0,0 +1,261 @@
+/*
+系统托盘：
+1) 菜单提供「上传剪贴板图片」「截图并上传」、暂停 / 恢复上传队列、最近五条上传链接（点击按默认链接格式复制）、
+   显示主窗口与退出；
+2) 最近上传在启动时从图库读取，之后由 `gallery_insert_item` 写入时更新，
+   变化时重建菜单并发送 `recent-uploads://changed` 事件；
+3) 上传队列暂停状态变化时同步刷新菜单文字。
+*/
+
+use std::{collections::VecDeque, sync::Mutex};
+
+use log::{info, warn};
+use tauri::{
+    AppHandle, Emitter, Manager, Wry,
+    menu::{IsMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
+    tray::TrayIconBuilder,
+};
+
+use crate::gallery::GalleryQuery;
+use crate::upload_queue::UploadQueue;
+
+const TRAY_ID: &str = "main";
+const RECENT_LIMIT: usize = 5;
+const RECENT_CHANGED_EVENT: &str = "recent-uploads://changed";
+const RECENT_PREFIX: &str = "recent:";
+/// 菜单中链接显示的最大字符数
+const LABEL_MAX_CHARS: usize = 48;
+
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+fn recent_snapshot() -> Vec<String> {
+    RECENT
+        .lock()
+        .map(|recent| recent.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// 过长的链接保留首尾，中间以省略号代替
+fn shorten(url: &str) -> String {
+    let chars: Vec<char> = url.chars().collect();
+    if chars.len() <= LABEL_MAX_CHARS {
+        return url.to_string();
+    }
+    let head = LABEL_MAX_CHARS / 2;
+    let tail = LABEL_MAX_CHARS - head - 1;
+    let start: String = chars[..head].iter().collect();
+    let end: String = chars[chars.len() - tail..].iter().collect();
+    format!("{start}…{end}")
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let status = app.try_state::<UploadQueue>().map(|queue| queue.status());
+    let paused = status.is_some_and(|status| status.paused);
+    let offline = status.is_some_and(|status| status.offline);
+    let upload_clipboard = MenuItem::with_id(
+        app,
+        "upload_clipboard",
+        "上传剪贴板图片",
+        true,
+        None::<&str>,
+    )?;
+    let capture_upload =
+        MenuItem::with_id(app, "capture_upload", "截图并上传", true, None::<&str>)?;
+    let toggle_queue = MenuItem::with_id(
+        app,
+        "toggle_queue",
+        if paused {
+            "恢复上传队列"
+        } else if offline {
+            "暂停上传队列（离线，联网后继续）"
+        } else {
+            "暂停上传队列"
+        },
+        true,
+        None::<&str>,
+    )?;
+
+    let recent = recent_snapshot();
+    let mut recent_items = Vec::with_capacity(recent.len().max(1));
+    if recent.is_empty() {
+        recent_items.push(MenuItem::with_id(
+            app,
+            "recent_empty",
+            "暂无上传记录",
+            false,
+            None::<&str>,
+        )?);
+    }
+    for (index, url) in recent.iter().enumerate() {
+        recent_items.push(MenuItem::with_id(
+            app,
+            format!("{RECENT_PREFIX}{index}"),
+            shorten(url),
+            true,
+            None::<&str>,
+        )?);
+    }
+    let recent_refs: Vec<&dyn IsMenuItem<Wry>> = recent_items
+        .iter()
+        .map(|item| item as &dyn IsMenuItem<Wry>)
+        .collect();
+    let recent_menu = Submenu::with_items(app, "最近上传（点击复制）", true, &recent_refs)?;
+
+    let show = MenuItem::with_id(app, "show", "显示主窗口", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
+    Menu::with_items(
+        app,
+        &[
+            &upload_clipboard,
+            &capture_upload,
+            &PredefinedMenuItem::separator(app)?,
+            &toggle_queue,
+            &PredefinedMenuItem::separator(app)?,
+            &recent_menu,
+            &PredefinedMenuItem::separator(app)?,
+            &show,
+            &quit,
+        ],
+    )
+}
+
+/// 重建托盘菜单（队列状态或最近上传变化后调用）
+pub(crate) fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app) {
+        Ok(menu) => {
+            if let Err(err) = tray.set_menu(Some(menu)) {
+                warn!("update tray menu failed: {}", err);
+            }
+        }
+        Err(err) => warn!("build tray menu failed: {}", err),
+    }
+}
+
+/// 记录一次成功上传的链接
+pub(crate) fn record_upload(app: &AppHandle, url: &str) {
+    if let Ok(mut recent) = RECENT.lock() {
+        recent.retain(|existing| existing != url);
+        recent.push_front(url.to_string());
+        recent.truncate(RECENT_LIMIT);
+    }
+    if let Err(err) = app.emit(RECENT_CHANGED_EVENT, recent_snapshot()) {
+        warn!("emit {} failed: {}", RECENT_CHANGED_EVENT, err);
+    }
+    refresh(app);
+}
+
+fn load_recent(app: &AppHandle) {
+    let items = crate::gallery::store_from_app(app).and_then(|store| {
+        store
+            .query(GalleryQuery::default())
+            .map_err(|err| err.to_string())
+    });
+    match items {
+        Ok(items) => {
+            if let Ok(mut recent) = RECENT.lock() {
+                *recent = items
+                    .into_iter()
+                    .map(|item| item.url)
+                    .take(RECENT_LIMIT)
+                    .collect();
+            }
+        }
+        Err(err) => warn!("load recent uploads failed: {}", err),
+    }
+}
+
+fn copy_recent(app: &AppHandle, index: usize) {
+    let Some(url) = recent_snapshot().into_iter().nth(index) else {
+        return;
+    };
+    match crate::link_format::copy_url_as_default(app, &url) {
+        Ok(_) => info!("tray copied recent upload: {}", url),
+        Err(err) => warn!("tray copy recent upload failed: {}", err),
+    }
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    let id = event.id().as_ref();
+    match id {
+        "upload_clipboard" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                match crate::clipboard_watcher::take_current(&app) {
+                    Ok(Some(path)) => crate::upload::request_upload(&app, vec![path], "tray"),
+                    Ok(None) => info!("tray upload clipboard: no image in clipboard"),
+                    Err(err) => warn!("tray upload clipboard failed: {}", err),
+                }
+            });
+        }
+        "capture_upload" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Err(err) = crate::capture::capture_and_emit(
+                    &app,
+                    crate::capture::CaptureMode::Fullscreen,
+                    None,
+                    None,
+                    true,
+                ) {
+                    warn!("tray capture failed: {}", err);
+                }
+            });
+        }
+        "toggle_queue" => {
+            if let Some(queue) = app.try_state::<UploadQueue>() {
+                let paused = !queue.status().paused;
+                queue.set_paused(paused);
+                info!("tray set upload queue paused={}", paused);
+            }
+            refresh(app);
+        }
+        "show" => crate::show_main_window(app),
+        "quit" => app.exit(0),
+        _ => {
+            if let Some(index) = id
+                .strip_prefix(RECENT_PREFIX)
+                .and_then(|index| index.parse::<usize>().ok())
+            {
+                copy_recent(app, index);
+            }
+        }
+    }
+}
+
+pub(crate) fn init(app: &AppHandle) -> tauri::Result<()> {
+    load_recent(app);
+    let menu = build_menu(app)?;
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("yana")
+        .menu(&menu)
+        .on_menu_event(on_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+/// 最近上传的链接（最新在前）
+#[tauri::command]
+pub fn recent_uploads() -> Vec<String> {
+    recent_snapshot()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shorten() {
+        assert_eq!(shorten("https://a.b/c.png"), "https://a.b/c.png");
+        let long = format!("https://example.com/{}/image.png", "x".repeat(80));
+        let short = shorten(&long);
+        assert_eq!(short.chars().count(), LABEL_MAX_CHARS);
+        assert!(short.starts_with("https://example.com/"));
+        assert!(short.ends_with("image.png"));
+    }
+}
@@ -1,11 +1,30 @@
-use std::{collections::HashMap, path::Path, time::Duration};
+use std::{
+    cell::OnceCell,
+    collections::HashMap,
+    future::Future,
+    path::Path,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
 
 use base64::{Engine as _, engine::general_purpose};
+use bytes::Bytes;
+use futures::future::{AbortHandle, Abortable};
+use log::{info, warn};
 use reqwest::{
-    Client, Response,
-    header::{CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue},
+    Client, RequestBuilder, Response, StatusCode,
+    header::{
+        CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue,
+        RETRY_AFTER,
+    },
 };
 use serde::{Deserialize, Serialize};
+use sha2::Digest;
+
+use crate::http_client::{HttpClientOptions, apply_http_options};
+use crate::pending_uploads::{PendingKind, UploadImageJob, run_tracked};
+use crate::throttle::Throttle;
+use crate::tls::{ClientCertificate, apply_client_certificate};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -18,11 +37,52 @@ pub enum UploadFormat {
     Base64,
 }
 
+/// form-data 模式下随主文件一同发送的额外文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormFilePart {
+    /// 文件字段名，可与其他部分重复（如 `files[]`）
+    pub field_name: String,
+    /// 文件的绝对路径
+    pub path: String,
+    /// 自定义文件名，默认使用路径中的文件名
+    #[serde(default)]
+    pub file_name: Option<String>,
+    #[serde(default)]
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum UploadMethod {
+    #[default]
+    #[serde(alias = "post")]
+    Post,
+    /// 预签名地址等要求 PUT 的接口
+    #[serde(alias = "put")]
+    Put,
+    #[serde(alias = "patch")]
+    Patch,
+}
+
+impl UploadMethod {
+    pub(crate) fn as_reqwest(self) -> reqwest::Method {
+        match self {
+            Self::Post => reqwest::Method::POST,
+            Self::Put => reqwest::Method::PUT,
+            Self::Patch => reqwest::Method::PATCH,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UploadConfig {
     /// 上传目标地址
     pub url: String,
+    /// 请求方法，默认 POST
+    #[serde(default)]
+    pub method: UploadMethod,
     /// 自定义请求头
     #[serde(default)]
     pub headers: HashMap<String, String>,
@@ -32,6 +92,9 @@ pub struct UploadConfig {
     /// form-data 模式下的额外文本字段
     #[serde(default)]
     pub additional_fields: HashMap<String, String>,
+    /// form-data 模式下的额外文件，与主文件合并为一个 multipart 请求
+    #[serde(default)]
+    pub additional_files: Vec<FormFilePart>,
     /// base64 模式下图片字段的键名，默认 image
     #[serde(default)]
     pub json_key: Option<String>,
@@ -44,11 +107,159 @@ pub struct UploadConfig {
     /// 指定 Content-Type，不指定则默认为 application/octet-stream
     #[serde(default)]
     pub content_type: Option<String>,
-    /// 请求超时时间，单位毫秒，默认 30 秒
+    /// 整个请求的总时长上限（毫秒），默认不限制，仅由连接与读取超时约束
     #[serde(default)]
     pub timeout_ms: Option<u64>,
+    /// 建立连接的超时时间（毫秒），默认 10 秒
+    #[serde(default)]
+    pub connect_timeout_ms: Option<u64>,
+    /// 读取空闲超时（毫秒）：连续这么久没有收到数据即失败，默认 30 秒
+    #[serde(default)]
+    pub read_timeout_ms: Option<u64>,
+    /// 重试策略，默认不重试
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// 从 JSON 响应中提取图片地址的路径（如 `data.url`、`$.data.links[0].url`）
+    #[serde(default)]
+    pub url_path: Option<String>,
+    /// 从 JSON 响应中提取删除标识的路径
+    #[serde(default)]
+    pub delete_path: Option<String>,
+    /// 从 JSON 响应中提取错误信息的路径，用于生成更可读的失败原因
+    #[serde(default)]
+    pub error_path: Option<String>,
+    /// 是否携带并保存该主机的会话 Cookie（配合 `host_login` 使用）
+    #[serde(default)]
+    pub use_cookies: bool,
+    /// 目标位于 mTLS 反向代理之后时使用的客户端证书
+    #[serde(default)]
+    pub client_certificate: Option<ClientCertificate>,
+    /// 该主机的上传限速（KB/s），与全局限速同时生效
+    #[serde(default)]
+    pub rate_limit_kbps: Option<u32>,
+    /// 重定向策略与 DNS 覆盖
+    #[serde(default)]
+    pub http_options: Option<HttpClientOptions>,
+    /// 压缩请求体（设置 Content-Encoding），仅对 binary / base64 格式生效，需图床支持
+    #[serde(default)]
+    pub body_compression: Option<BodyCompression>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyCompression {
+    Gzip,
+    Deflate,
+}
+
+impl BodyCompression {
+    fn content_encoding(self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Deflate => "deflate",
+        }
+    }
+
+    fn compress(self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        use std::io::Write;
+        match self {
+            Self::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            // HTTP 的 deflate 编码指 zlib 格式
+            Self::Deflate => {
+                let mut encoder =
+                    flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+/// 上传重试策略，逻辑与 thumbnail.rs 下载重试一致：指数退避，仅对临时性错误重试
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryPolicy {
+    /// 最大尝试次数（含首次），1 表示不重试
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// 首次重试前的等待时间，之后按 2 的幂递增
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// 是否对 5xx 响应重试
+    #[serde(default = "default_true")]
+    pub retry_on_server_error: bool,
+    /// 是否对 408 / 429 响应重试
+    #[serde(default = "default_true")]
+    pub retry_on_throttle: bool,
+    /// 是否对连接失败 / 超时重试
+    #[serde(default = "default_true")]
+    pub retry_on_connection_error: bool,
+    /// 是否遵循 Retry-After 响应头
+    #[serde(default = "default_true")]
+    pub honor_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            retry_on_server_error: true,
+            retry_on_throttle: true,
+            retry_on_connection_error: true,
+            honor_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn should_retry_status(&self, status: StatusCode) -> bool {
+        if status.is_server_error() {
+            return self.retry_on_server_error;
+        }
+        matches!(
+            status,
+            StatusCode::REQUEST_TIMEOUT | StatusCode::TOO_MANY_REQUESTS
+        ) && self.retry_on_throttle
+    }
+
+    /// 第 attempt 次失败后的退避时间：initial * 2^(attempt-1)，不超过 max_backoff_ms
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2_u64.saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(
+            self.initial_backoff_ms
+                .saturating_mul(factor)
+                .min(self.max_backoff_ms),
+        )
+    }
+}
+
+const fn default_max_attempts() -> u32 {
+    1
+}
+
+const fn default_initial_backoff_ms() -> u64 {
+    1000
+}
+
+const fn default_max_backoff_ms() -> u64 {
+    30_000
 }
 
+const fn default_true() -> bool {
+    true
+}
+
+const MAX_RETRY_AFTER_SECS: u64 = 300;
+
 fn default_field_name() -> String {
     "file".to_string()
 }
@@ -60,96 +271,325 @@ pub struct UploadResponse {
     pub headers: Vec<(String, String)>,
     pub body: serde_json::Value,
     pub raw_text: String,
+    /// 按 `url_path` 提取的图片地址
+    pub url: Option<String>,
+    /// 按 `delete_path` 提取的删除标识
+    pub delete_id: Option<String>,
+}
+
+/// 配置未指定超时时使用的连接与读取超时（毫秒）
+const DEFAULT_CONNECT_TIMEOUT_MS: u64 = 10_000;
+const DEFAULT_READ_TIMEOUT_MS: u64 = 30_000;
+
+/// 上传被 `cancel_upload` 中止时返回的错误信息
+pub(crate) const CANCELLED_ERROR: &str = "upload cancelled";
+
+/// 进行中的上传任务：upload_id -> 中止句柄
+static IN_FLIGHT_UPLOADS: OnceLock<Mutex<HashMap<String, AbortHandle>>> = OnceLock::new();
+
+fn in_flight_uploads() -> &'static Mutex<HashMap<String, AbortHandle>> {
+    IN_FLIGHT_UPLOADS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 任务结束（包括被中止）时从登记表移除
+struct InFlightGuard {
+    upload_id: String,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if let Ok(mut uploads) = in_flight_uploads().lock() {
+            uploads.remove(&self.upload_id);
+        }
+    }
+}
+
+/// 以可取消的方式执行上传任务。提供 upload_id 时登记中止句柄，
+/// `cancel_upload` 会直接丢弃底层 future，从而中断正在进行的 reqwest 请求。
+pub(crate) async fn run_cancellable<T, F>(upload_id: Option<String>, task: F) -> Result<T, String>
+where
+    F: Future<Output = Result<T, String>>,
+{
+    let Some(upload_id) = upload_id.filter(|id| !id.is_empty()) else {
+        return task.await;
+    };
+
+    let (handle, registration) = AbortHandle::new_pair();
+    {
+        let mut uploads = in_flight_uploads()
+            .lock()
+            .map_err(|_| "upload registry poisoned".to_string())?;
+        if uploads.contains_key(&upload_id) {
+            return Err(format!("upload id already in use: {upload_id}"));
+        }
+        uploads.insert(upload_id.clone(), handle);
+    }
+    let _guard = InFlightGuard {
+        upload_id: upload_id.clone(),
+    };
+
+    match Abortable::new(task, registration).await {
+        Ok(result) => result,
+        Err(_) => {
+            info!("upload cancelled: upload_id={}", upload_id);
+            Err(CANCELLED_ERROR.to_string())
+        }
+    }
+}
+
+/// 取消进行中的上传，返回是否找到对应任务
+#[tauri::command]
+pub fn cancel_upload(upload_id: String) -> Result<bool, String> {
+    let uploads = in_flight_uploads()
+        .lock()
+        .map_err(|_| "upload registry poisoned".to_string())?;
+    match uploads.get(&upload_id) {
+        Some(handle) => {
+            handle.abort();
+            info!("cancel_upload: upload_id={}", upload_id);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+pub(crate) const UPLOAD_REQUEST_EVENT: &str = "upload://request";
+
+/// 外部入口（剪贴板、深层链接、本地服务）接受的图片扩展名
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "webp", "gif", "bmp", "tif", "tiff", "avif", "ico", "heic", "heif",
+];
+
+/// 是否为存在的图片文件（按扩展名判断），外部入口借此拒绝上传任意文件
+pub(crate) fn is_image_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        && path.is_file()
+}
+
+/// `upload://request` 事件负载：后端入口（快捷键、托盘等）请求上传本地文件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadRequest {
+    pub paths: Vec<String>,
+    /// 触发来源，如 `hotkey`、`tray`
+    pub source: String,
+}
+
+/// 请求前端按当前选中的图床压缩并上传指定文件
+pub(crate) fn request_upload(app: &tauri::AppHandle, paths: Vec<String>, source: &str) {
+    use tauri::Emitter;
+
+    info!("request upload: source={}, count={}", source, paths.len());
+    let request = UploadRequest {
+        paths,
+        source: source.to_string(),
+    };
+    if let Err(err) = app.emit(UPLOAD_REQUEST_EVENT, request) {
+        warn!("emit {} failed: {}", UPLOAD_REQUEST_EVENT, err);
+    }
 }
 
 #[tauri::command]
 pub async fn upload_image(
+    app: tauri::AppHandle,
+    file_path: String,
+    format: UploadFormat,
+    config: UploadConfig,
+    upload_id: Option<String>,
+    host: Option<String>,
+) -> Result<UploadResponse, String> {
+    let job = UploadImageJob {
+        file_path: file_path.clone(),
+        format: format.clone(),
+        config: config.clone(),
+    };
+    let destination = config.url.clone();
+    let queued = crate::upload_queue::run_queued(
+        &app,
+        upload_id.as_deref(),
+        Some(&destination),
+        upload_image_impl(file_path, format, config),
+    );
+    let cancellable = run_cancellable(upload_id.clone(), queued);
+    run_tracked(
+        &app,
+        PendingKind::UploadImage,
+        host.as_deref(),
+        &job,
+        &job.file_path,
+        cancellable,
+    )
+    .await
+}
+
+pub(crate) async fn upload_image_impl(
     file_path: String,
     format: UploadFormat,
     config: UploadConfig,
 ) -> Result<UploadResponse, String> {
     let path = Path::new(&file_path);
-    if !path.is_absolute() || !path.exists() {
-        return Err("file path must be an existing absolute path".to_string());
-    }
+    validate_upload_path(path)?;
+    let default_name = default_file_name(path);
+    let file_bytes = read_file_async(file_path).await?;
 
-    if path
-        .components()
-        .any(|component| matches!(component, std::path::Component::ParentDir))
-    {
-        return Err("parent directory segments are not allowed in file path".to_string());
+    upload_bytes(file_bytes, default_name, format, config).await
+}
+
+/// 直接上传内存中的图片数据（如剪贴板截图），无需先写入临时文件。
+/// 数据不落盘，因此不会登记到持久化上传队列中。
+#[tauri::command]
+pub async fn upload_image_data(
+    app: tauri::AppHandle,
+    data: Vec<u8>,
+    format: UploadFormat,
+    config: UploadConfig,
+    upload_id: Option<String>,
+) -> Result<UploadResponse, String> {
+    if data.is_empty() {
+        return Err("image data is empty".to_string());
     }
+    info!("upload_image_data start: data_len={}", data.len());
 
-    let default_name = path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or("upload.bin")
-        .to_string();
+    let default_name = default_data_file_name(&data);
+    let destination = config.url.clone();
+    let queued = crate::upload_queue::run_queued(
+        &app,
+        upload_id.as_deref(),
+        Some(&destination),
+        upload_bytes(data, default_name, format, config),
+    );
+    run_cancellable(upload_id, queued).await
+}
+
+/// 内存数据的默认文件名：按图片头部识别扩展名，无法识别时使用 png
+fn default_data_file_name(data: &[u8]) -> String {
+    let ext = image::guess_format(data)
+        .ok()
+        .and_then(|format| format.extensions_str().first().copied())
+        .unwrap_or("png");
+    format!(
+        "clipboard_{}.{}",
+        chrono::Local::now().format("%Y%m%d_%H%M%S"),
+        ext
+    )
+}
 
+async fn upload_bytes(
+    file_bytes: Vec<u8>,
+    default_name: String,
+    format: UploadFormat,
+    config: UploadConfig,
+) -> Result<UploadResponse, String> {
     let UploadConfig {
         url,
+        method,
         headers,
         field_name,
         additional_fields,
+        additional_files,
         json_key,
         additional_json,
         file_name,
         content_type,
         timeout_ms,
+        connect_timeout_ms,
+        read_timeout_ms,
+        retry,
+        url_path,
+        delete_path,
+        error_path,
+        use_cookies,
+        client_certificate,
+        rate_limit_kbps,
+        http_options,
+        body_compression,
     } = config;
+    let extraction = ResponseExtraction {
+        url_path,
+        delete_path,
+        error_path,
+    };
 
-    let effective_file_name = file_name.unwrap_or(default_name);
-
-    let file_path_for_read = file_path.clone();
-    let file_bytes =
-        tauri::async_runtime::spawn_blocking(move || std::fs::read(file_path_for_read))
-            .await
-            .map_err(|e| format!("failed to join file read task: {}", e))
-            .and_then(|res| res.map_err(|e| format!("failed to read file: {}", e)))?;
+    // 请求头、表单字段与 JSON 字段中的 {md5} / {sha256} 等占位符在发送前替换；
+    // 摘要需要遍历整个文件，放到阻塞线程池计算
+    let (file_bytes, effective_file_name, headers, additional_fields, additional_json) =
+        tauri::async_runtime::spawn_blocking(move || {
+            let effective_file_name =
+                crate::filename_template::apply(&file_name.unwrap_or(default_name), &file_bytes);
+            let placeholders = FilePlaceholders::new(&file_bytes, &effective_file_name);
+            let headers = placeholders.render_map(headers);
+            let additional_fields = placeholders.render_map(additional_fields);
+            let additional_json: HashMap<String, serde_json::Value> = additional_json
+                .into_iter()
+                .map(|(k, v)| (k, placeholders.render_json(v)))
+                .collect();
+            (
+                file_bytes,
+                effective_file_name,
+                headers,
+                additional_fields,
+                additional_json,
+            )
+        })
+        .await
+        .map_err(|e| format!("failed to join placeholder task: {}", e))?;
 
-    let timeout = timeout_ms.unwrap_or(30_000);
-    let client = Client::builder()
-        .timeout(Duration::from_millis(timeout))
+    let mut builder = Client::builder()
+        .connect_timeout(Duration::from_millis(
+            connect_timeout_ms.unwrap_or(DEFAULT_CONNECT_TIMEOUT_MS),
+        ))
+        .read_timeout(Duration::from_millis(
+            read_timeout_ms.unwrap_or(DEFAULT_READ_TIMEOUT_MS),
+        ));
+    if let Some(total) = timeout_ms.filter(|ms| *ms > 0) {
+        builder = builder.timeout(Duration::from_millis(total));
+    }
+    builder = apply_client_certificate(builder, client_certificate.as_ref())?;
+    builder = apply_http_options(builder, http_options.as_ref())?;
+    if use_cookies {
+        builder = builder.cookie_provider(crate::cookies::jar_for_url(&url)?);
+    }
+    let client = builder
         .build()
         .map_err(|e| format!("failed to build http client: {}", e))?;
 
     let header_map = build_header_map(&headers)?;
+    let throttle = Throttle::for_url(&url, rate_limit_kbps);
+    let http_method = method.as_reqwest();
 
-    let response = match format {
-        UploadFormat::Binary => {
-            let mut request = client.post(&url).headers(header_map.clone());
-            if let Some(ct) = &content_type {
-                request = request.header(CONTENT_TYPE, ct);
-            } else {
-                request = request.header(CONTENT_TYPE, "application/octet-stream");
-            }
-            request
-                .body(file_bytes)
-                .send()
-                .await
-                .map_err(|e| format!("failed to send binary upload request: {}", e))?
-        }
+    // 请求体只准备一次，重试时仅重新构建请求（Bytes 克隆不会复制文件内容）
+    let body = match format {
+        UploadFormat::Binary => PreparedBody::Binary {
+            bytes: Bytes::from(file_bytes),
+            content_type: content_type.unwrap_or_else(|| "application/octet-stream".to_string()),
+        },
         UploadFormat::Form => {
-            let mut part =
-                reqwest::multipart::Part::bytes(file_bytes).file_name(effective_file_name.clone());
-            if let Some(ct) = &content_type {
-                part = part
-                    .mime_str(ct)
-                    .map_err(|e| format!("invalid content type `{}`: {}", ct, e))?;
+            let mut parts = vec![FilePart {
+                field_name,
+                file_name: effective_file_name,
+                content_type,
+                bytes: Bytes::from(file_bytes),
+            }];
+            for extra in additional_files {
+                let extra_path = Path::new(&extra.path);
+                validate_upload_path(extra_path)?;
+                let file_name = extra
+                    .file_name
+                    .unwrap_or_else(|| default_file_name(extra_path));
+                let bytes = read_file_async(extra.path.clone()).await?;
+                parts.push(FilePart {
+                    field_name: extra.field_name,
+                    file_name,
+                    content_type: extra.content_type,
+                    bytes: Bytes::from(bytes),
+                });
             }
-
-            let mut form = reqwest::multipart::Form::new().part(field_name, part);
-            for (key, value) in additional_fields {
-                form = form.text(key, value);
+            PreparedBody::Form {
+                parts,
+                fields: additional_fields,
             }
-
-            client
-                .post(&url)
-                .headers(header_map.clone())
-                .multipart(form)
-                .send()
-                .await
-                .map_err(|e| format!("failed to send form upload request: {}", e))?
         }
         UploadFormat::Base64 => {
             let encoded = general_purpose::STANDARD.encode(&file_bytes);
@@ -161,19 +601,264 @@ pub async fn upload_image(
                 payload.insert(k, v);
             }
 
-            let request_body = serde_json::Value::Object(payload);
+            PreparedBody::Json(serde_json::Value::Object(payload))
+        }
+    };
+    let body = match body_compression {
+        Some(compression) => body.compress(compression).await?,
+        None => body,
+    };
 
+    let context = format!("{} upload", body.label());
+    let response = send_with_retry(&retry, &context, || {
+        body.attach(
             client
-                .post(&url)
-                .headers(header_map.clone())
-                .json(&request_body)
-                .send()
-                .await
-                .map_err(|e| format!("failed to send base64 upload request: {}", e))?
+                .request(http_method.clone(), &url)
+                .headers(header_map.clone()),
+            &throttle,
+        )
+    })
+    .await?;
+    finalize_response(response, &extraction).await
+}
+
+/// 按重试策略发送请求：每次尝试都重新构建请求，5xx / 408 / 429 与连接失败时按退避时间重试。
+/// `context` 用于日志与错误信息（如 `binary upload`）
+pub(crate) async fn send_with_retry<F>(
+    retry: &RetryPolicy,
+    context: &str,
+    mut build: F,
+) -> Result<reqwest::Response, String>
+where
+    F: FnMut() -> Result<RequestBuilder, String>,
+{
+    let max_attempts = retry.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        let outcome = build()?.send().await;
+        crate::network_status::report_send_result(&outcome);
+
+        let wait = match &outcome {
+            Ok(response)
+                if attempt < max_attempts && retry.should_retry_status(response.status()) =>
+            {
+                let retry_after = if retry.honor_retry_after {
+                    parse_retry_after(response.headers())
+                } else {
+                    None
+                };
+                Some(retry_after.unwrap_or_else(|| retry.backoff(attempt)))
+            }
+            Err(err)
+                if attempt < max_attempts
+                    && retry.retry_on_connection_error
+                    && (err.is_connect() || err.is_timeout() || err.is_request()) =>
+            {
+                Some(retry.backoff(attempt))
+            }
+            _ => None,
+        };
+
+        match wait {
+            Some(wait) => {
+                match &outcome {
+                    Ok(response) => warn!(
+                        "{} attempt {}/{} got status {}, retrying in {}ms",
+                        context,
+                        attempt,
+                        max_attempts,
+                        response.status().as_u16(),
+                        wait.as_millis()
+                    ),
+                    Err(err) => warn!(
+                        "{} attempt {}/{} failed ({}), retrying in {}ms",
+                        context,
+                        attempt,
+                        max_attempts,
+                        err,
+                        wait.as_millis()
+                    ),
+                }
+                tokio::time::sleep(wait).await;
+                attempt += 1;
+            }
+            None => {
+                return outcome.map_err(|e| format!("failed to send {} request: {}", context, e));
+            }
         }
-    };
+    }
+}
+
+fn validate_upload_path(path: &Path) -> Result<(), String> {
+    if !path.is_absolute() || !path.exists() {
+        return Err("file path must be an existing absolute path".to_string());
+    }
 
-    finalize_response(response).await
+    if path
+        .components()
+        .any(|component| matches!(component, std::path::Component::ParentDir))
+    {
+        return Err("parent directory segments are not allowed in file path".to_string());
+    }
+    Ok(())
+}
+
+fn default_file_name(path: &Path) -> String {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("upload.bin")
+        .to_string()
+}
+
+async fn read_file_async(file_path: String) -> Result<Vec<u8>, String> {
+    tauri::async_runtime::spawn_blocking(move || std::fs::read(file_path))
+        .await
+        .map_err(|e| format!("failed to join file read task: {}", e))
+        .and_then(|res| res.map_err(|e| format!("failed to read file: {}", e)))
+}
+
+/// 预先准备好的请求体，可在重试时重复构建请求
+enum PreparedBody {
+    Binary {
+        bytes: Bytes,
+        content_type: String,
+    },
+    Form {
+        parts: Vec<FilePart>,
+        fields: HashMap<String, String>,
+    },
+    Json(serde_json::Value),
+    /// 已压缩的 binary / base64 请求体
+    Encoded {
+        bytes: Bytes,
+        content_type: String,
+        encoding: BodyCompression,
+        label: &'static str,
+    },
+}
+
+struct FilePart {
+    field_name: String,
+    file_name: String,
+    content_type: Option<String>,
+    bytes: Bytes,
+}
+
+impl PreparedBody {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Binary { .. } => "binary",
+            Self::Form { .. } => "form",
+            Self::Json(_) => "base64",
+            Self::Encoded { label, .. } => *label,
+        }
+    }
+
+    /// 在阻塞线程中压缩请求体；form-data 不支持整体编码，原样返回
+    async fn compress(self, compression: BodyCompression) -> Result<Self, String> {
+        let label = self.label();
+        let (raw, content_type) = match self {
+            Self::Binary {
+                bytes,
+                content_type,
+            } => (bytes, content_type),
+            Self::Json(payload) => {
+                let encoded = serde_json::to_vec(&payload)
+                    .map_err(|e| format!("failed to serialize json body: {}", e))?;
+                (Bytes::from(encoded), "application/json".to_string())
+            }
+            other => {
+                warn!("body compression is not supported for {} uploads", label);
+                return Ok(other);
+            }
+        };
+
+        let original_len = raw.len();
+        let compressed = tauri::async_runtime::spawn_blocking(move || compression.compress(&raw))
+            .await
+            .map_err(|e| format!("failed to join compression task: {}", e))?
+            .map_err(|e| format!("failed to compress request body: {}", e))?;
+        info!(
+            "request body compressed with {}: {} -> {} bytes",
+            compression.content_encoding(),
+            original_len,
+            compressed.len()
+        );
+        Ok(Self::Encoded {
+            bytes: Bytes::from(compressed),
+            content_type,
+            encoding: compression,
+            label,
+        })
+    }
+
+    fn attach(
+        &self,
+        request: RequestBuilder,
+        throttle: &Throttle,
+    ) -> Result<RequestBuilder, String> {
+        match self {
+            Self::Binary {
+                bytes,
+                content_type,
+            } => Ok(request
+                .header(CONTENT_TYPE, content_type)
+                .header(CONTENT_LENGTH, bytes.len())
+                .body(throttle.body(bytes.clone()))),
+            Self::Form { parts, fields } => {
+                let mut form = reqwest::multipart::Form::new();
+                for file in parts {
+                    let mut part = reqwest::multipart::Part::stream_with_length(
+                        throttle.body(file.bytes.clone()),
+                        file.bytes.len() as u64,
+                    )
+                    .file_name(file.file_name.clone());
+                    if let Some(ct) = &file.content_type {
+                        part = part
+                            .mime_str(ct)
+                            .map_err(|e| format!("invalid content type `{}`: {}", ct, e))?;
+                    }
+                    form = form.part(file.field_name.clone(), part);
+                }
+                for (key, value) in fields {
+                    form = form.text(key.clone(), value.clone());
+                }
+                Ok(request.multipart(form))
+            }
+            Self::Encoded {
+                bytes,
+                content_type,
+                encoding,
+                ..
+            } => Ok(request
+                .header(CONTENT_TYPE, content_type)
+                .header(CONTENT_ENCODING, encoding.content_encoding())
+                .header(CONTENT_LENGTH, bytes.len())
+                .body(throttle.body(bytes.clone()))),
+            Self::Json(payload) if throttle.is_active() => {
+                let encoded = serde_json::to_vec(payload)
+                    .map_err(|e| format!("failed to serialize json body: {}", e))?;
+                Ok(request
+                    .header(CONTENT_TYPE, "application/json")
+                    .header(CONTENT_LENGTH, encoded.len())
+                    .body(throttle.body(Bytes::from(encoded))))
+            }
+            Self::Json(payload) => Ok(request.json(payload)),
+        }
+    }
+}
+
+/// 解析 Retry-After 响应头（秒数或 HTTP 日期），上限 5 分钟
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    let wait = if let Ok(secs) = value.parse::<u64>() {
+        Duration::from_secs(secs)
+    } else {
+        let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+        let delta = at.with_timezone(&chrono::Utc) - chrono::Utc::now();
+        delta.to_std().unwrap_or(Duration::ZERO)
+    };
+    Some(wait.min(Duration::from_secs(MAX_RETRY_AFTER_SECS)))
 }
 
 fn build_header_map(headers: &HashMap<String, String>) -> Result<HeaderMap, String> {
@@ -188,7 +873,138 @@ fn build_header_map(headers: &HashMap<String, String>) -> Result<HeaderMap, Stri
     Ok(map)
 }
 
-async fn finalize_response(response: Response) -> Result<UploadResponse, String> {
+/// 基于文件内容的模板占位符：`{md5}`、`{sha1}`、`{sha256}`、`{size}`、`{filename}`。
+/// 摘要只在模板实际引用时计算一次。
+struct FilePlaceholders<'a> {
+    bytes: &'a [u8],
+    file_name: &'a str,
+    md5: OnceCell<String>,
+    sha1: OnceCell<String>,
+    sha256: OnceCell<String>,
+}
+
+impl<'a> FilePlaceholders<'a> {
+    fn new(bytes: &'a [u8], file_name: &'a str) -> Self {
+        Self {
+            bytes,
+            file_name,
+            md5: OnceCell::new(),
+            sha1: OnceCell::new(),
+            sha256: OnceCell::new(),
+        }
+    }
+
+    fn render(&self, template: &str) -> String {
+        if !template.contains('{') {
+            return template.to_string();
+        }
+        let mut rendered = template.to_string();
+        if rendered.contains("{md5}") {
+            let digest = self
+                .md5
+                .get_or_init(|| format!("{:x}", md5::Md5::digest(self.bytes)));
+            rendered = rendered.replace("{md5}", digest);
+        }
+        if rendered.contains("{sha1}") {
+            let digest = self
+                .sha1
+                .get_or_init(|| format!("{:x}", sha1::Sha1::digest(self.bytes)));
+            rendered = rendered.replace("{sha1}", digest);
+        }
+        if rendered.contains("{sha256}") {
+            let digest = self
+                .sha256
+                .get_or_init(|| format!("{:x}", sha2::Sha256::digest(self.bytes)));
+            rendered = rendered.replace("{sha256}", digest);
+        }
+        rendered
+            .replace("{size}", &self.bytes.len().to_string())
+            .replace("{filename}", self.file_name)
+    }
+
+    fn render_map(&self, map: HashMap<String, String>) -> HashMap<String, String> {
+        map.into_iter()
+            .map(|(k, v)| {
+                let rendered = self.render(&v);
+                (k, rendered)
+            })
+            .collect()
+    }
+
+    /// 递归替换 JSON 中的字符串值；整个值恰为 `{size}` 时输出数字
+    fn render_json(&self, value: serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::String(text) if text == "{size}" => {
+                serde_json::Value::from(self.bytes.len())
+            }
+            serde_json::Value::String(text) => serde_json::Value::String(self.render(&text)),
+            serde_json::Value::Array(items) => {
+                items.into_iter().map(|v| self.render_json(v)).collect()
+            }
+            serde_json::Value::Object(map) => serde_json::Value::Object(
+                map.into_iter()
+                    .map(|(k, v)| (k, self.render_json(v)))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+}
+
+/// 声明式响应解析配置
+struct ResponseExtraction {
+    url_path: Option<String>,
+    delete_path: Option<String>,
+    error_path: Option<String>,
+}
+
+/// 按点号路径读取 JSON 值，支持可选的 `$.` 前缀与数组下标（如 `data.links[0].url`）
+fn extract_json_path<'a>(
+    value: &'a serde_json::Value,
+    path: &str,
+) -> Option<&'a serde_json::Value> {
+    let trimmed = path.trim();
+    let trimmed = trimmed
+        .strip_prefix("$.")
+        .or_else(|| trimmed.strip_prefix('$'))
+        .unwrap_or(trimmed);
+
+    let mut current = value;
+    for segment in trimmed.split('.').filter(|s| !s.is_empty()) {
+        let (key, indexes) = match segment.find('[') {
+            Some(pos) => (&segment[..pos], &segment[pos..]),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            current = current.get(key)?;
+        }
+        for index in indexes.split('[').filter(|s| !s.is_empty()) {
+            let index: usize = index.strip_suffix(']')?.trim().parse().ok()?;
+            current = current.get(index)?;
+        }
+    }
+    Some(current)
+}
+
+/// 将提取到的 JSON 值转为字符串；null 与空字符串视为不存在
+fn json_value_to_string(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(text) if text.is_empty() => None,
+        serde_json::Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn extract_string(body: &serde_json::Value, path: Option<&str>) -> Option<String> {
+    path.and_then(|path| extract_json_path(body, path))
+        .and_then(json_value_to_string)
+}
+
+async fn finalize_response(
+    response: Response,
+    extraction: &ResponseExtraction,
+) -> Result<UploadResponse, String> {
     let status = response.status();
     let headers = response.headers().clone();
     let raw_text = response
@@ -196,15 +1012,26 @@ async fn finalize_response(response: Response) -> Result<UploadResponse, String>
         .await
         .map_err(|e| format!("failed to read response body: {}", e))?;
 
+    let parsed_body = serde_json::from_str(&raw_text).unwrap_or(serde_json::Value::Null);
+    let error_message = extract_string(&parsed_body, extraction.error_path.as_deref());
+
     if !status.is_success() {
         return Err(format!(
             "upload failed with status {}: {}",
             status.as_u16(),
-            raw_text
+            error_message.unwrap_or(raw_text)
         ));
     }
 
-    let parsed_body = serde_json::from_str(&raw_text).unwrap_or(serde_json::Value::Null);
+    let url = extract_string(&parsed_body, extraction.url_path.as_deref());
+    if let (Some(path), None) = (&extraction.url_path, &url) {
+        return Err(match error_message {
+            Some(message) => format!("upload failed: {}", message),
+            None => format!("upload response has no value at `{}`: {}", path, raw_text),
+        });
+    }
+    let delete_id = extract_string(&parsed_body, extraction.delete_path.as_deref());
+
     let header_pairs = headers
         .iter()
         .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
@@ -215,5 +1042,63 @@ async fn finalize_response(response: Response) -> Result<UploadResponse, String>
         headers: header_pairs,
         body: parsed_body,
         raw_text,
+        url,
+        delete_id,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_json_path() {
+        let body = serde_json::json!({
+            "success": true,
+            "data": {
+                "url": "https://example.com/a.png",
+                "delete": 42,
+                "links": [{ "url": "https://cdn.example.com/a.png" }]
+            },
+            "message": ""
+        });
+
+        assert_eq!(
+            extract_string(&body, Some("data.url")).as_deref(),
+            Some("https://example.com/a.png")
+        );
+        assert_eq!(
+            extract_string(&body, Some("$.data.links[0].url")).as_deref(),
+            Some("https://cdn.example.com/a.png")
+        );
+        assert_eq!(
+            extract_string(&body, Some("data.delete")).as_deref(),
+            Some("42")
+        );
+        assert_eq!(extract_string(&body, Some("data.links[1].url")), None);
+        assert_eq!(extract_string(&body, Some("message")), None);
+        assert_eq!(extract_string(&body, None), None);
+    }
+
+    #[test]
+    fn test_file_placeholders() {
+        let placeholders = FilePlaceholders::new(b"abc", "a.png");
+        assert_eq!(
+            placeholders.render("{filename}:{size}:{md5}"),
+            "a.png:3:900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert_eq!(
+            placeholders.render("{sha1}"),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(
+            placeholders.render("sha256={sha256}"),
+            "sha256=ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            placeholders
+                .render_json(serde_json::json!({ "size": "{size}", "tags": ["{filename}"] })),
+            serde_json::json!({ "size": 3, "tags": ["a.png"] })
+        );
+    }
+}
@@ -0,0 +1,371 @@
+/*
+上传队列（托管状态）：
+1) `upload_image` / `s3_upload` / `s3_upload_dir`（逐文件）在真正发起请求前向队列申请执行许可；
+2) 同时运行的上传数不超过设置中的 `max_concurrent_uploads`，其余按提交顺序排队；
+3) 排队位置变化、开始执行、执行结束时通过事件通知前端；执行期间请求体每发送一段，
+   以 progress 阶段上报已发送字节数（由 `throttle::Throttle::body` 通过 `ProgressReporter` 上报）；
+4) 支持暂停 / 恢复：暂停后不再启动新的上传，已在进行的上传不受影响；
+5) 离线时（network_status）同样不启动新的上传，恢复联网后排队的上传自动继续；
+   目标为局域网 / 本机地址的任务不受离线挂起影响，可越过排在前面的挂起任务先执行。
+*/
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::{debug, info, warn};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+
+pub const QUEUE_EVENT: &str = "upload-queue://update";
+/// 两次进度事件的最小间隔，最后一块总会上报
+pub(crate) const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueuePhase {
+    Queued,
+    Started,
+    Progress,
+    Finished,
+}
+
+/// 单个任务的队列事件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueEvent {
+    pub upload_id: Option<String>,
+    pub phase: QueuePhase,
+    /// 排队位置（从 1 开始），仅 queued 阶段有值
+    pub position: Option<usize>,
+    /// 本次请求已发送 / 总字节数，仅 progress 阶段有值；重试时从 0 重新计数
+    pub bytes_sent: Option<u64>,
+    pub total_bytes: Option<u64>,
+    pub status: QueueStatus,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueStatus {
+    pub running: usize,
+    pub waiting: usize,
+    pub paused: bool,
+    /// 因离线或计费网络挂起
+    pub offline: bool,
+    pub max_concurrent: usize,
+}
+
+struct QueueInner {
+    max_concurrent: usize,
+    running: usize,
+    paused: bool,
+    offline: bool,
+    /// (票据, 离线时是否仍可执行)
+    waiting: VecDeque<(u64, bool)>,
+    next_ticket: u64,
+}
+
+impl QueueInner {
+    fn status(&self) -> QueueStatus {
+        QueueStatus {
+            running: self.running,
+            waiting: self.waiting.len(),
+            paused: self.paused,
+            offline: self.offline,
+            max_concurrent: self.max_concurrent,
+        }
+    }
+}
+
+pub struct UploadQueue {
+    inner: Mutex<QueueInner>,
+    notify: Notify,
+}
+
+/// 执行许可：释放时归还并发名额并唤醒排队任务
+pub struct QueuePermit {
+    app: AppHandle,
+    upload_id: Option<String>,
+}
+
+/// 排队期间被取消（future 被丢弃）时，将票据移出等待队列
+struct WaitingTicket<'a> {
+    queue: &'a UploadQueue,
+    ticket: u64,
+    acquired: bool,
+}
+
+impl Drop for WaitingTicket<'_> {
+    fn drop(&mut self) {
+        if self.acquired {
+            return;
+        }
+        if let Ok(mut inner) = self.queue.inner.lock() {
+            inner.waiting.retain(|(ticket, _)| *ticket != self.ticket);
+        }
+        self.queue.notify.notify_waiters();
+    }
+}
+
+impl UploadQueue {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            inner: Mutex::new(QueueInner {
+                max_concurrent: max_concurrent.max(1),
+                running: 0,
+                paused: false,
+                offline: false,
+                waiting: VecDeque::new(),
+                next_ticket: 0,
+            }),
+            notify: Notify::new(),
+        }
+    }
+
+    pub fn status(&self) -> QueueStatus {
+        match self.inner.lock() {
+            Ok(inner) => inner.status(),
+            Err(poisoned) => poisoned.into_inner().status(),
+        }
+    }
+
+    pub fn set_max_concurrent(&self, max_concurrent: usize) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.max_concurrent = max_concurrent.max(1);
+            info!("upload queue concurrency set to {}", inner.max_concurrent);
+        }
+        self.notify.notify_waiters();
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.paused = paused;
+        }
+        self.notify.notify_waiters();
+    }
+
+    pub fn set_offline(&self, offline: bool) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.offline = offline;
+        }
+        self.notify.notify_waiters();
+    }
+
+    /// 申请执行许可；在名额不足或队列暂停时按提交顺序等待。
+    /// `bypass_offline` 为 true 时离线挂起期间也可执行（目标在局域网 / 本机）
+    pub async fn acquire(
+        &self,
+        app: &AppHandle,
+        upload_id: Option<&str>,
+        bypass_offline: bool,
+    ) -> Result<QueuePermit, String> {
+        let ticket = {
+            let mut inner = self
+                .inner
+                .lock()
+                .map_err(|_| "upload queue poisoned".to_string())?;
+            let ticket = inner.next_ticket;
+            inner.next_ticket += 1;
+            inner.waiting.push_back((ticket, bypass_offline));
+            ticket
+        };
+        let mut waiting = WaitingTicket {
+            queue: self,
+            ticket,
+            acquired: false,
+        };
+
+        let mut last_position = None;
+        loop {
+            // 先注册通知再检查状态，避免检查与等待之间错过唤醒
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            let event = {
+                let mut inner = self
+                    .inner
+                    .lock()
+                    .map_err(|_| "upload queue poisoned".to_string())?;
+                let position = inner
+                    .waiting
+                    .iter()
+                    .position(|(t, _)| *t == ticket)
+                    .unwrap_or(0);
+                // 离线时只与同样可执行的任务比较先后，挂起的任务不阻塞它们
+                let ahead = if inner.offline {
+                    inner
+                        .waiting
+                        .iter()
+                        .take(position)
+                        .filter(|(_, bypass)| *bypass)
+                        .count()
+                } else {
+                    position
+                };
+                let can_start = !inner.paused
+                    && (!inner.offline || bypass_offline)
+                    && inner.running < inner.max_concurrent
+                    && ahead == 0;
+
+                if can_start {
+                    inner.waiting.remove(position);
+                    inner.running += 1;
+                    waiting.acquired = true;
+                    Some(QueueEvent {
+                        upload_id: upload_id.map(str::to_string),
+                        phase: QueuePhase::Started,
+                        position: None,
+                        bytes_sent: None,
+                        total_bytes: None,
+                        status: inner.status(),
+                    })
+                } else if last_position != Some(position) {
+                    last_position = Some(position);
+                    Some(QueueEvent {
+                        upload_id: upload_id.map(str::to_string),
+                        phase: QueuePhase::Queued,
+                        position: Some(position + 1),
+                        bytes_sent: None,
+                        total_bytes: None,
+                        status: inner.status(),
+                    })
+                } else {
+                    None
+                }
+            };
+
+            if let Some(event) = event {
+                let started = event.phase == QueuePhase::Started;
+                emit_queue_event(app, event);
+                if started {
+                    // 可能还有名额，唤醒下一个排队任务
+                    self.notify.notify_waiters();
+                    debug!("upload queue permit granted: upload_id={:?}", upload_id);
+                    return Ok(QueuePermit {
+                        app: app.clone(),
+                        upload_id: upload_id.map(str::to_string),
+                    });
+                }
+            }
+
+            notified.await;
+        }
+    }
+
+    fn release(&self) -> QueueStatus {
+        let status = match self.inner.lock() {
+            Ok(mut inner) => {
+                inner.running = inner.running.saturating_sub(1);
+                inner.status()
+            }
+            Err(poisoned) => poisoned.into_inner().status(),
+        };
+        self.notify.notify_waiters();
+        status
+    }
+}
+
+impl Drop for QueuePermit {
+    fn drop(&mut self) {
+        let queue = self.app.state::<UploadQueue>();
+        let status = queue.release();
+        emit_queue_event(
+            &self.app,
+            QueueEvent {
+                upload_id: self.upload_id.take(),
+                phase: QueuePhase::Finished,
+                position: None,
+                bytes_sent: None,
+                total_bytes: None,
+                status,
+            },
+        );
+    }
+}
+
+fn emit_queue_event(app: &AppHandle, event: QueueEvent) {
+    if let Err(err) = app.emit(QUEUE_EVENT, event) {
+        warn!("failed to emit upload queue event: {}", err);
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_ITEM: ProgressReporter;
+}
+
+/// 正在执行的队列任务的进度上报器
+#[derive(Clone)]
+pub(crate) struct ProgressReporter {
+    app: AppHandle,
+    upload_id: Option<String>,
+}
+
+impl ProgressReporter {
+    /// 在 `run_queued` 执行的任务内返回当前任务的上报器；请求体可能在其他任务中发送，
+    /// 因此需在构建请求体时获取
+    pub(crate) fn current() -> Option<Self> {
+        CURRENT_ITEM.try_with(Clone::clone).ok()
+    }
+
+    pub(crate) fn report(&self, bytes_sent: u64, total_bytes: u64) {
+        emit_queue_event(
+            &self.app,
+            QueueEvent {
+                upload_id: self.upload_id.clone(),
+                phase: QueuePhase::Progress,
+                position: None,
+                bytes_sent: Some(bytes_sent),
+                total_bytes: Some(total_bytes),
+                status: self.app.state::<UploadQueue>().status(),
+            },
+        );
+    }
+}
+
+/// 通过全局上传队列执行任务（先排队获取许可，再执行），执行期间可上报发送进度。
+/// `destination` 为上传目标（URL 或主机名），未知时为 None
+pub(crate) async fn run_queued<T, F>(
+    app: &AppHandle,
+    upload_id: Option<&str>,
+    destination: Option<&str>,
+    task: F,
+) -> Result<T, String>
+where
+    F: std::future::Future<Output = Result<T, String>>,
+{
+    let queue = app.state::<UploadQueue>();
+    let bypass_offline = destination.is_some_and(crate::network_status::is_private_destination);
+    let _permit = queue.acquire(app, upload_id, bypass_offline).await?;
+    let reporter = ProgressReporter {
+        app: app.clone(),
+        upload_id: upload_id.map(str::to_string),
+    };
+    CURRENT_ITEM.scope(reporter, task).await
+}
+
+#[tauri::command]
+pub fn upload_queue_status(app: AppHandle) -> QueueStatus {
+    app.state::<UploadQueue>().status()
+}
+
+#[tauri::command]
+pub fn upload_queue_pause(app: AppHandle) -> QueueStatus {
+    let queue = app.state::<UploadQueue>();
+    queue.set_paused(true);
+    info!("upload queue paused");
+    #[cfg(desktop)]
+    crate::tray::refresh(&app);
+    queue.status()
+}
+
+#[tauri::command]
+pub fn upload_queue_resume(app: AppHandle) -> QueueStatus {
+    let queue = app.state::<UploadQueue>();
+    queue.set_paused(false);
+    info!("upload queue resumed");
+    #[cfg(desktop)]
+    crate::tray::refresh(&app);
+    queue.status()
+}
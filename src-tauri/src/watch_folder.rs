@@ -0,0 +1,311 @@
+/*
+监听文件夹自动上传（截图文件夹 → 链接）：
+1) 设置 `watchFolders` 中每个启用的文件夹对应一个图床，新出现（新建或移入）的图片自动经过
+   重复检查、压缩（按设置）、上传并写入图库，启动监听前已存在的文件不会上传；
+2) 截图工具常分多次写入文件，文件大小在 `SETTLE` 内不再变化后才上传；隐藏文件与非图片文件忽略；
+3) 上传成功后按默认链接格式复制到剪贴板（`watchFolderCopyLink`），并发送 `watch-folder://uploaded`，
+   失败发送 `watch-folder://failed`，两者均按设置发送系统通知；
+4) 保存设置时配置有变化才重建监听器，旧监听线程随监听器释放而退出。
+*/
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Mutex, mpsc},
+    time::{Duration, Instant},
+};
+
+use log::{info, warn};
+use notify::{
+    Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+    event::{ModifyKind, RenameMode},
+};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::host_upload::{PipelineOptions, compress_and_upload};
+use crate::settings::{SettingsPayload, WatchFolder};
+
+const UPLOADED_EVENT: &str = "watch-folder://uploaded";
+const FAILED_EVENT: &str = "watch-folder://failed";
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// 文件大小保持不变的时长，超过后视为写入完成
+const SETTLE: Duration = Duration::from_secs(1);
+
+struct RunningWatcher {
+    folders: Vec<WatchFolder>,
+    _watcher: RecommendedWatcher,
+}
+
+static WATCHER: Mutex<Option<RunningWatcher>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchFolderEvent {
+    pub path: String,
+    pub host: String,
+    pub url: Option<String>,
+    pub delete_id: Option<String>,
+    pub error: Option<String>,
+    /// 内容已上传过，沿用图库中的链接
+    pub duplicate: bool,
+    /// 链接已复制到剪贴板
+    pub copied: bool,
+}
+
+/// 等待写入完成的文件
+struct PendingFile {
+    size: Option<u64>,
+    changed: Instant,
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+/// 事件是否表示文件新出现在监听目录中（新建或移入）
+fn is_arrival(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Create(_) => true,
+        EventKind::Modify(ModifyKind::Name(mode)) => *mode != RenameMode::From,
+        _ => false,
+    }
+}
+
+/// 文件所属的监听配置：取路径最长（最具体）的匹配，不递归的文件夹只匹配直接子文件
+fn folder_for<'a>(folders: &'a [WatchFolder], file: &Path) -> Option<&'a WatchFolder> {
+    folders
+        .iter()
+        .filter(|folder| {
+            let root = Path::new(&folder.path);
+            if folder.recursive {
+                file.starts_with(root)
+            } else {
+                file.parent() == Some(root)
+            }
+        })
+        .max_by_key(|folder| folder.path.len())
+}
+
+fn emit(app: &AppHandle, event: &str, payload: &WatchFolderEvent) {
+    if let Err(err) = app.emit(event, payload) {
+        warn!("emit {} failed: {}", event, err);
+    }
+}
+
+async fn upload(app: AppHandle, host: String, path: String) {
+    let options = PipelineOptions {
+        record_gallery: true,
+        retries: 1,
+        ..Default::default()
+    };
+    let Some(outcome) = compress_and_upload(&app, &host, vec![path.clone()], &options)
+        .await
+        .pop()
+    else {
+        return;
+    };
+    match outcome.result {
+        Ok(result) => {
+            info!("watch folder uploaded: file={}, url={}", path, result.url);
+            let copied = crate::settings::current_settings(&app).watch_folder_copy_link
+                && crate::link_format::copy_url_as_default(&app, &result.url)
+                    .inspect_err(|err| warn!("copy watched upload link failed: {}", err))
+                    .is_ok();
+            crate::notifier::watch_folder_uploaded(&app, &path, &result.url, copied);
+            emit(
+                &app,
+                UPLOADED_EVENT,
+                &WatchFolderEvent {
+                    path,
+                    host,
+                    url: Some(result.url),
+                    delete_id: Some(result.delete_id).filter(|id| !id.is_empty()),
+                    error: None,
+                    duplicate: outcome.duplicate,
+                    copied,
+                },
+            );
+        }
+        Err(error) => {
+            warn!("watch folder upload failed: file={}, error={}", path, error);
+            crate::notifier::watch_folder_failed(&app, &path, &host, &error);
+            emit(
+                &app,
+                FAILED_EVENT,
+                &WatchFolderEvent {
+                    path,
+                    host,
+                    url: None,
+                    delete_id: None,
+                    error: Some(error),
+                    duplicate: false,
+                    copied: false,
+                },
+            );
+        }
+    }
+}
+
+/// 处理文件事件，文件写入完成后交给上传流程；监听器释放后通道断开，线程退出
+fn run(app: AppHandle, folders: Vec<WatchFolder>, rx: mpsc::Receiver<notify::Result<Event>>) {
+    let mut pending: HashMap<PathBuf, PendingFile> = HashMap::new();
+    loop {
+        match rx.recv_timeout(POLL_INTERVAL) {
+            Ok(Ok(event)) => {
+                let arrival = is_arrival(&event.kind);
+                for path in event.paths {
+                    if let Some(file) = pending.get_mut(&path) {
+                        file.changed = Instant::now();
+                    } else if arrival
+                        && !is_hidden(&path)
+                        && crate::upload::is_image_file(&path)
+                        && folder_for(&folders, &path).is_some()
+                    {
+                        pending.insert(
+                            path,
+                            PendingFile {
+                                size: None,
+                                changed: Instant::now(),
+                            },
+                        );
+                    }
+                }
+            }
+            Ok(Err(err)) => warn!("watch folder error: {}", err),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+
+        let mut ready = Vec::new();
+        pending.retain(|path, file| {
+            // 文件在写入完成前被删除或移走
+            let Ok(size) = std::fs::metadata(path).map(|meta| meta.len()) else {
+                return false;
+            };
+            if file.size != Some(size) {
+                file.size = Some(size);
+                file.changed = Instant::now();
+                return true;
+            }
+            if size > 0 && file.changed.elapsed() >= SETTLE {
+                ready.push(path.clone());
+                return false;
+            }
+            true
+        });
+        for path in ready {
+            let Some(folder) = folder_for(&folders, &path) else {
+                continue;
+            };
+            tauri::async_runtime::spawn(upload(
+                app.clone(),
+                folder.host.clone(),
+                path.to_string_lossy().into_owned(),
+            ));
+        }
+    }
+}
+
+/// 按设置启动、重建或停止文件夹监听
+pub(crate) fn apply(app: &AppHandle, settings: &SettingsPayload) {
+    let folders: Vec<WatchFolder> = settings
+        .watch_folders
+        .iter()
+        .filter(|folder| folder.enabled && !folder.path.is_empty() && !folder.host.is_empty())
+        .cloned()
+        .collect();
+    let Ok(mut running) = WATCHER.lock() else {
+        return;
+    };
+    let current = running.as_ref().map(|running| running.folders.as_slice());
+    if current.unwrap_or_default() == folders.as_slice() {
+        return;
+    }
+    if running.take().is_some() {
+        info!("watch folders stopped");
+    }
+    if folders.is_empty() {
+        return;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            warn!("create folder watcher failed: {}", err);
+            return;
+        }
+    };
+    for folder in &folders {
+        let mode = if folder.recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        match watcher.watch(Path::new(&folder.path), mode) {
+            Ok(()) => info!("watching folder {} -> {}", folder.path, folder.host),
+            Err(err) => warn!("watch folder {} failed: {}", folder.path, err),
+        }
+    }
+
+    let (app, config) = (app.clone(), folders.clone());
+    std::thread::spawn(move || run(app, config, rx));
+    *running = Some(RunningWatcher {
+        folders,
+        _watcher: watcher,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::CreateKind;
+
+    fn folder(path: &str, host: &str, recursive: bool) -> WatchFolder {
+        WatchFolder {
+            path: path.to_string(),
+            host: host.to_string(),
+            recursive,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_folder_for() {
+        let folders = vec![
+            folder("/shots", "s3", true),
+            folder("/shots/work", "webdav", false),
+        ];
+        let host = |path: &str| folder_for(&folders, Path::new(path)).map(|f| f.host.as_str());
+        assert_eq!(host("/shots/a.png"), Some("s3"));
+        assert_eq!(host("/shots/2024/a.png"), Some("s3"));
+        assert_eq!(host("/shots/work/a.png"), Some("webdav"));
+        // 不递归的文件夹不匹配更深的文件，退回到上层的递归配置
+        assert_eq!(host("/shots/work/sub/a.png"), Some("s3"));
+        assert_eq!(host("/other/a.png"), None);
+        assert_eq!(host("/shotsx/a.png"), None);
+    }
+
+    #[test]
+    fn test_is_arrival() {
+        assert!(is_arrival(&EventKind::Create(CreateKind::File)));
+        assert!(is_arrival(&EventKind::Modify(ModifyKind::Name(
+            RenameMode::To
+        ))));
+        assert!(is_arrival(&EventKind::Modify(ModifyKind::Name(
+            RenameMode::Any
+        ))));
+        assert!(!is_arrival(&EventKind::Modify(ModifyKind::Name(
+            RenameMode::From
+        ))));
+        assert!(!is_arrival(&EventKind::Modify(ModifyKind::Data(
+            notify::event::DataChange::Any
+        ))));
+        assert!(!is_arrival(&EventKind::Remove(
+            notify::event::RemoveKind::File
+        )));
+    }
+}
@@ -0,0 +1,233 @@
+/*
+内置 WebDAV 图床（Nextcloud、Alist、坚果云等）：
+1) 上传：逐级 MKCOL 创建目录（已存在时返回 405，视为成功），再 PUT 文件；
+2) 删除：deleteId 中记录 WebDAV 根地址与相对路径，凭据从已保存的配置中读取；
+   记录的根地址与当前配置不一致时拒绝删除，避免把凭据发送到 deleteId 中的任意地址；
+3) 返回链接默认为文件的 WebDAV 地址，配置了 `publicUrl` 时按其拼接（支持 `{path}` `{filename}`）。
+*/
+
+use log::{error, info};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::internal_hosts::{
+    HostDeleteResult, HostHttpConfig, HostUploadResult, build_public_url, decode_delete_marker,
+    encode_delete_marker, join_remote_path, load_host_settings, read_upload_file,
+    render_path_template,
+};
+use crate::upload::run_cancellable;
+
+const PLUGIN_ID: &str = "webdav";
+const DEFAULT_TIMEOUT_MS: u64 = 60_000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebDavConfig {
+    /// WebDAV 根地址，例如 https://cloud.example.com/remote.php/dav/files/alice/
+    pub endpoint: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// 根地址下的上传目录
+    #[serde(default)]
+    pub remote_dir: Option<String>,
+    #[serde(default)]
+    pub path_template: Option<String>,
+    #[serde(default)]
+    pub public_url: Option<String>,
+    #[serde(flatten)]
+    pub http: HostHttpConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WebDavDeleteMarker {
+    endpoint: String,
+    path: String,
+}
+
+/// 比较两个根地址是否指向同一位置（忽略首尾空白与末尾斜杠）
+fn same_endpoint(a: &str, b: &str) -> bool {
+    let normalize = |value: &str| {
+        reqwest::Url::parse(value.trim())
+            .map(|url| url.as_str().trim_end_matches('/').to_string())
+            .unwrap_or_else(|_| value.trim().trim_end_matches('/').to_string())
+    };
+    normalize(a) == normalize(b)
+}
+
+/// 将相对路径逐段编码后拼接到根地址
+fn resource_url(endpoint: &str, path: &str) -> Result<reqwest::Url, String> {
+    let mut url = reqwest::Url::parse(endpoint.trim())
+        .map_err(|e| format!("invalid WebDAV endpoint {}: {e}", endpoint))?;
+    url.path_segments_mut()
+        .map_err(|_| format!("invalid WebDAV endpoint {}", endpoint))?
+        .pop_if_empty()
+        .extend(path.split('/').filter(|segment| !segment.is_empty()));
+    Ok(url)
+}
+
+impl WebDavConfig {
+    fn client(&self) -> Result<reqwest::Client, String> {
+        self.http.client(DEFAULT_TIMEOUT_MS)
+    }
+
+    fn request(
+        &self,
+        client: &reqwest::Client,
+        method: Method,
+        url: reqwest::Url,
+    ) -> reqwest::RequestBuilder {
+        let request = client.request(method, url);
+        match self.username.as_deref().filter(|u| !u.is_empty()) {
+            Some(username) => request.basic_auth(username, self.password.as_deref()),
+            None => request,
+        }
+    }
+
+    /// 逐级创建目录；405 表示目录已存在
+    async fn ensure_dirs(&self, client: &reqwest::Client, path: &str) -> Result<(), String> {
+        let mkcol = Method::from_bytes(b"MKCOL").map_err(|e| e.to_string())?;
+        let segments: Vec<&str> = path.split('/').collect();
+        for depth in 1..segments.len() {
+            let dir = segments[..depth].join("/");
+            let mut url = resource_url(&self.endpoint, &dir)?;
+            url.path_segments_mut()
+                .map_err(|_| "invalid WebDAV endpoint".to_string())?
+                .push("");
+            let response = self
+                .http
+                .send(&format!("WebDAV MKCOL {}", dir), || {
+                    Ok(self.request(client, mkcol.clone(), url.clone()))
+                })
+                .await?;
+            let status = response.status();
+            if !status.is_success() && status != StatusCode::METHOD_NOT_ALLOWED {
+                return Err(format!("创建目录 {} 失败: HTTP {}", dir, status));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub async fn webdav_upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: WebDavConfig,
+    upload_id: Option<String>,
+) -> Result<HostUploadResult, String> {
+    run_cancellable(upload_id, upload(file_path, original_file_name, config)).await
+}
+
+async fn upload(
+    file_path: String,
+    original_file_name: Option<String>,
+    config: WebDavConfig,
+) -> Result<HostUploadResult, String> {
+    let (file_name, bytes) = read_upload_file(&file_path, original_file_name).await?;
+    let path = join_remote_path(
+        config.remote_dir.as_deref(),
+        &render_path_template(config.path_template.as_deref(), &file_name),
+    );
+
+    let client = config.client()?;
+    config.ensure_dirs(&client, &path).await?;
+
+    let url = resource_url(&config.endpoint, &path)?;
+    let content_type = mime_guess::from_path(&file_name)
+        .first_or_octet_stream()
+        .to_string();
+    let size = bytes.len();
+    let throttle = config.http.throttle(url.as_str());
+    let response = config
+        .http
+        .send("WebDAV PUT", || {
+            Ok(config
+                .request(&client, Method::PUT, url.clone())
+                .header(reqwest::header::CONTENT_TYPE, &content_type)
+                .header(reqwest::header::CONTENT_LENGTH, size)
+                .body(throttle.body(bytes.clone())))
+        })
+        .await
+        .map_err(|e| format!("WebDAV 上传失败: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        error!("webdav_upload failed: path={}, status={}", path, status);
+        return Err(format!("WebDAV 上传失败: HTTP {} {}", status, text));
+    }
+
+    let public_url = match config
+        .public_url
+        .as_deref()
+        .filter(|u| !u.trim().is_empty())
+    {
+        Some(base) => build_public_url(base.trim(), &path),
+        None => url.to_string(),
+    };
+    let delete_id = encode_delete_marker(&WebDavDeleteMarker {
+        endpoint: config.endpoint.trim().to_string(),
+        path: path.clone(),
+    })?;
+
+    info!("webdav_upload success: path={}, size={}", path, size);
+    Ok(HostUploadResult {
+        url: public_url,
+        delete_id,
+        metadata: Some(serde_json::json!({ "path": path, "size": size })),
+    })
+}
+
+#[tauri::command]
+pub async fn webdav_delete(app: AppHandle, delete_id: String) -> Result<HostDeleteResult, String> {
+    let marker: WebDavDeleteMarker = decode_delete_marker(&delete_id)?;
+    let config: WebDavConfig = load_host_settings(&app, PLUGIN_ID)?;
+    // 凭据属于当前配置的服务，只发往同一根地址
+    if !same_endpoint(&marker.endpoint, &config.endpoint) {
+        return Err(format!(
+            "WebDAV 根地址已变更（上传时为 {}），无法用当前凭据删除",
+            marker.endpoint
+        ));
+    }
+
+    let client = config.client()?;
+    let url = resource_url(&config.endpoint, &marker.path)?;
+    let response = config
+        .http
+        .send("WebDAV DELETE", || {
+            Ok(config.request(&client, Method::DELETE, url.clone()))
+        })
+        .await
+        .map_err(|e| format!("WebDAV 删除失败: {}", e))?;
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND {
+        return Ok(HostDeleteResult::deleted("文件已不存在"));
+    }
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("WebDAV 删除失败: HTTP {} {}", status, text));
+    }
+
+    info!("webdav_delete success: path={}", marker.path);
+    Ok(HostDeleteResult::deleted("文件已从 WebDAV 删除"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_endpoint() {
+        assert!(same_endpoint(
+            "https://cloud.example.com/dav/",
+            " https://cloud.example.com/dav"
+        ));
+        assert!(!same_endpoint(
+            "https://evil.example.com/dav/",
+            "https://cloud.example.com/dav/"
+        ));
+    }
+}
@@ -0,0 +1,321 @@
+/*
+上传后的 Webhook 通知：
+1) 设置 `webhooks` 中每项包含地址、签名密钥与订阅的事件（上传成功 `success` / 上传失败 `failure`）；
+2) 后端上传流程（界面批量上传、命令行、本地服务、监听文件夹等）每个文件上传完成后，
+   向订阅了对应事件的地址 POST JSON：事件、时间、图床、文件名、链接、大小、SHA-256 与错误信息，
+   并附带 `text` / `content` 摘要，可直接发到 Slack / Discord 的 Incoming Webhook；
+3) 设置了密钥时附加 `X-Yana-Signature: sha256=<HMAC-SHA256(密钥, 请求体) 十六进制>`，
+   接收方可据此校验来源；
+4) 通知在后台发送，超时或 5xx 时重试一次，失败只记录日志，不影响上传结果；
+5) `test_webhook(webhook)` 发送一条测试事件并返回 HTTP 状态码，供设置页校验配置；
+6) 签名密钥保存在钥匙串中（见 settings_secrets），发送前按条目 id 读取；
+   Slack / Discord 的 Webhook 地址本身就是凭据，日志与错误信息中只出现地址的主机名。
+*/
+
+use std::time::Duration;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use tauri::AppHandle;
+
+use crate::settings_secrets::{resolve_settings_secret, webhook_key};
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+const RETRY_DELAY: Duration = Duration::from_secs(3);
+const SIGNATURE_HEADER: &str = "X-Yana-Signature";
+const EVENT_HEADER: &str = "X-Yana-Event";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookEvent {
+    Success,
+    Failure,
+}
+
+impl WebhookEvent {
+    fn name(self) -> &'static str {
+        match self {
+            WebhookEvent::Success => "upload.success",
+            WebhookEvent::Failure => "upload.failure",
+        }
+    }
+}
+
+fn default_events() -> Vec<WebhookEvent> {
+    vec![WebhookEvent::Success]
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    /// 钥匙串中签名密钥条目的标识，保存设置时分配
+    #[serde(default)]
+    pub id: String,
+    pub url: String,
+    /// 签名密钥，为空时不签名；保存后为钥匙串占位符
+    #[serde(default)]
+    pub secret: String,
+    /// 订阅的事件，默认只通知上传成功
+    #[serde(default = "default_events")]
+    pub events: Vec<WebhookEvent>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 单个文件的上传结果
+#[derive(Debug, Clone, Default)]
+pub(crate) struct UploadNotice {
+    pub host: String,
+    pub file_name: String,
+    pub url: Option<String>,
+    pub size: Option<u64>,
+    pub hash: Option<String>,
+    pub gallery_id: Option<i64>,
+    pub error: Option<String>,
+    /// 未提供 hash 时据此计算（上传的源文件）
+    pub source_path: Option<String>,
+}
+
+impl UploadNotice {
+    fn event(&self) -> WebhookEvent {
+        if self.error.is_none() && self.url.is_some() {
+            WebhookEvent::Success
+        } else {
+            WebhookEvent::Failure
+        }
+    }
+}
+
+fn payload(notice: &UploadNotice, timestamp: &str) -> Value {
+    let event = notice.event();
+    let summary = match event {
+        WebhookEvent::Success => format!(
+            "已上传 {} 到 {}：{}",
+            notice.file_name,
+            notice.host,
+            notice.url.as_deref().unwrap_or_default()
+        ),
+        WebhookEvent::Failure => format!(
+            "上传 {} 到 {} 失败：{}",
+            notice.file_name,
+            notice.host,
+            notice.error.as_deref().unwrap_or("未知错误")
+        ),
+    };
+    json!({
+        "event": event.name(),
+        "timestamp": timestamp,
+        "host": notice.host,
+        "fileName": notice.file_name,
+        "url": notice.url,
+        "size": notice.size,
+        "hash": notice.hash,
+        "galleryId": notice.gallery_id,
+        "error": notice.error,
+        "text": summary,
+        "content": summary,
+    })
+}
+
+/// 日志中代表 Webhook 的主机名，地址的路径与查询参数可能包含凭据
+fn log_target(url: &str) -> String {
+    url::Url::parse(url.trim())
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| "<invalid url>".to_string())
+}
+
+/// 从钥匙串读取签名密钥，返回可直接使用的配置
+fn with_secret(mut hook: Webhook) -> Result<Webhook, String> {
+    if !hook.secret.is_empty() {
+        hook.secret = resolve_settings_secret(&webhook_key(&hook.id), &hook.secret)?;
+    }
+    Ok(hook)
+}
+
+/// HMAC-SHA256(密钥, 请求体) 的十六进制
+fn signature(secret: &str, body: &[u8]) -> Result<String, String> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|e| format!("init hmac: {e}"))?;
+    mac.update(body);
+    let digest = mac.finalize().into_bytes();
+    Ok(digest.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+async fn post(
+    client: &reqwest::Client,
+    hook: &Webhook,
+    event: WebhookEvent,
+    body: &str,
+) -> Result<reqwest::StatusCode, String> {
+    let mut request = client
+        .post(hook.url.trim())
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, event.name())
+        .body(body.to_string());
+    if !hook.secret.is_empty() {
+        request = request.header(
+            SIGNATURE_HEADER,
+            format!("sha256={}", signature(&hook.secret, body.as_bytes())?),
+        );
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("发送 Webhook 失败: {}", e.without_url()))?;
+    Ok(response.status())
+}
+
+/// 发送一次，超时、连接失败或 5xx 时重试一次
+async fn deliver(hook: &Webhook, event: WebhookEvent, body: &str) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(SEND_TIMEOUT)
+        .build()
+        .map_err(|e| format!("failed to build http client: {e}"))?;
+    let mut last_error = String::new();
+    for attempt in 0..2 {
+        if attempt > 0 {
+            tokio::time::sleep(RETRY_DELAY).await;
+        }
+        match post(&client, hook, event, body).await {
+            Ok(status) if status.is_success() => return Ok(()),
+            Ok(status) if !status.is_server_error() => {
+                return Err(format!("HTTP {status}"));
+            }
+            Ok(status) => last_error = format!("HTTP {status}"),
+            Err(err) => last_error = err,
+        }
+    }
+    Err(last_error)
+}
+
+/// 按设置向订阅了该事件的 Webhook 发送通知（后台执行）
+pub(crate) fn notify(app: &AppHandle, notice: UploadNotice) {
+    let event = notice.event();
+    let hooks: Vec<Webhook> = crate::settings::current_settings(app)
+        .webhooks
+        .into_iter()
+        .filter(|hook| hook.enabled && !hook.url.is_empty() && hook.events.contains(&event))
+        .filter_map(|hook| {
+            let target = log_target(&hook.url);
+            with_secret(hook)
+                .inspect_err(|err| warn!("webhook {} skipped: {}", target, err))
+                .ok()
+        })
+        .collect();
+    if hooks.is_empty() {
+        return;
+    }
+    let timestamp = Utc::now().to_rfc3339();
+    tauri::async_runtime::spawn(async move {
+        let mut notice = notice;
+        if notice.hash.is_none()
+            && let Some(source) = notice.source_path.clone()
+        {
+            notice.hash = tokio::task::spawn_blocking(move || {
+                crate::dedup::hash_file(std::path::Path::new(&source)).ok()
+            })
+            .await
+            .ok()
+            .flatten();
+        }
+        let body = payload(&notice, &timestamp).to_string();
+        for hook in hooks {
+            if let Err(err) = deliver(&hook, event, &body).await {
+                warn!("webhook {} failed: {}", log_target(&hook.url), err);
+            }
+        }
+    });
+}
+
+/// 发送一条测试事件，返回 HTTP 状态码
+#[tauri::command]
+pub async fn test_webhook(webhook: Webhook) -> Result<u16, String> {
+    if webhook.url.trim().is_empty() {
+        return Err("未填写 Webhook 地址".to_string());
+    }
+    let notice = UploadNotice {
+        host: "test".to_string(),
+        file_name: "yana-test.png".to_string(),
+        url: Some("https://example.com/yana-test.png".to_string()),
+        size: Some(0),
+        ..Default::default()
+    };
+    let webhook = with_secret(webhook)?;
+    let body = payload(&notice, &Utc::now().to_rfc3339()).to_string();
+    let client = reqwest::Client::builder()
+        .timeout(SEND_TIMEOUT)
+        .build()
+        .map_err(|e| format!("failed to build http client: {e}"))?;
+    let status = post(&client, &webhook, notice.event(), &body).await?;
+    info!("test webhook {}: {}", log_target(&webhook.url), status);
+    Ok(status.as_u16())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature() {
+        // RFC 4231 测试用例 2
+        assert_eq!(
+            signature("Jefe", b"what do ya want for nothing?").unwrap(),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_payload() {
+        let notice = UploadNotice {
+            host: "s3".to_string(),
+            file_name: "a.png".to_string(),
+            url: Some("https://img.example.com/a.png".to_string()),
+            size: Some(42),
+            hash: Some("abc".to_string()),
+            gallery_id: Some(3),
+            error: None,
+            source_path: None,
+        };
+        let body = payload(&notice, "2026-05-01T08:00:00+00:00");
+        assert_eq!(body["event"], "upload.success");
+        assert_eq!(body["fileName"], "a.png");
+        assert_eq!(body["size"], 42);
+        assert_eq!(body["text"], body["content"]);
+
+        let failed = UploadNotice {
+            url: None,
+            error: Some("timeout".to_string()),
+            ..notice
+        };
+        let body = payload(&failed, "2026-05-01T08:00:00+00:00");
+        assert_eq!(body["event"], "upload.failure");
+        assert_eq!(body["error"], "timeout");
+        assert!(body["url"].is_null());
+    }
+
+    #[test]
+    fn test_webhook_defaults() {
+        let hook: Webhook = serde_json::from_str(r#"{"url":"https://hooks.example.com"}"#).unwrap();
+        assert_eq!(hook.events, vec![WebhookEvent::Success]);
+        assert!(hook.enabled);
+        assert!(hook.secret.is_empty());
+    }
+
+    #[test]
+    fn test_log_target() {
+        assert_eq!(
+            log_target("https://hooks.slack.com/services/T000/B000/XXXX"),
+            "hooks.slack.com"
+        );
+        assert_eq!(log_target("not a url"), "<invalid url>");
+    }
+}
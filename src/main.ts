@@ -3,6 +3,7 @@ import { createPinia } from 'pinia';
 import App from './App.vue';
 import { useDeviceStore } from './stores/device';
 import { attachConsole, info, error as logError } from '@tauri-apps/plugin-log';
+import { invoke } from '@tauri-apps/api/core';
 
 function describeError(err: unknown): string {
   if (err instanceof Error) {
@@ -25,7 +26,27 @@ function describeError(err: unknown): string {
   }
 })();
 
+// 未捕获的异常写入本地错误报告（是否发送由设置中的同意项决定）
+function reportError(err: unknown, context: string) {
+  const message = err instanceof Error ? `${err.name}: ${err.message}` : String(err);
+  const stack = err instanceof Error ? err.stack ?? null : null;
+  void logError(`[${context}] ${describeError(err)}`);
+  invoke('report_frontend_error', { message, context, stack }).catch(() => {
+    // 记录报告失败时忽略，避免循环上报
+  });
+}
+
+window.addEventListener('error', (event) => {
+  reportError(event.error ?? event.message, 'window.error');
+});
+window.addEventListener('unhandledrejection', (event) => {
+  reportError(event.reason, 'unhandledrejection');
+});
+
 const app = createApp(App);
+app.config.errorHandler = (err, _instance, info) => {
+  reportError(err, `vue: ${info}`);
+};
 const pinia = createPinia();
 app.use(pinia);
 
@@ -5,6 +5,13 @@
 export const name = 'freeimage.host';
 export const description = '使用 freeimage.host API 进行上传与删除操作。';
 
+/** 插件权限声明：仅访问 freeimage.host，只读取宿主交给插件的文件 */
+export const permissions = {
+  domains: ['freeimage.host'],
+  filesystem: 'none',
+  clipboard: false,
+};
+
 export const supportedFileTypes = [
   {
     mimeTypes: [
@@ -26,6 +33,7 @@ export const parameters = [
     label: 'API Key',
     type: 'password',
     required: true,
+    secret: true,
     description: '在 freeimage.host 获取的 API Key',
   },
 ];
@@ -0,0 +1,579 @@
+import { invoke } from '@tauri-apps/api/core';
+import type {
+  PluginAction,
+  PluginDeleteResult,
+  PluginParameterDescriptor,
+  PluginUploadResult,
+} from '../types/imageHostPlugin';
+import type { LoadedPlugin } from './registry';
+
+/**
+ * 由后端实现的内置图床（S3 之外），前端只负责声明参数并调用后端命令。
+ * 上传命令接收 `{ filePath, originalFileName, config }`，删除命令接收 `{ deleteId }`，
+ * 删除时由后端读取已保存的配置，无需前端传递凭据。
+ */
+interface InternalHostDefinition {
+  name: string;
+  description: string;
+  parameters: PluginParameterDescriptor[];
+  uploadCommand: string;
+  deleteCommand: string;
+  actions?: PluginAction[];
+}
+
+const imageFileTypes = [
+  {
+    description: '常见图片类型',
+    mimeTypes: ['image/png', 'image/jpeg', 'image/webp', 'image/gif'],
+  },
+];
+
+const pathTemplateParameter: PluginParameterDescriptor = {
+  key: 'pathTemplate',
+  label: '路径模板 (可选)',
+  type: 'text',
+  description:
+    '支持 {year} {month} {day} {uuid} {filename} {name} {ext}，默认 {year}/{month}/{day}/{uuid}-{filename}',
+};
+
+const definitions: Record<string, InternalHostDefinition> = {
+  webdav: {
+    name: 'WebDAV',
+    description: '上传到 WebDAV 服务（Nextcloud、Alist 等）',
+    uploadCommand: 'webdav_upload',
+    deleteCommand: 'webdav_delete',
+    parameters: [
+      {
+        key: 'endpoint',
+        label: 'WebDAV 地址',
+        type: 'text',
+        required: true,
+        description:
+          '例如 https://cloud.example.com/remote.php/dav/files/alice/',
+      },
+      { key: 'username', label: '用户名', type: 'text' },
+      { key: 'password', label: '密码', type: 'password', secret: true },
+      {
+        key: 'remoteDir',
+        label: '上传目录 (可选)',
+        type: 'text',
+        description: '相对于 WebDAV 地址的目录，例如 images/blog',
+      },
+      pathTemplateParameter,
+      {
+        key: 'publicUrl',
+        label: '对外访问根地址 (可选)',
+        type: 'text',
+        description:
+          '留空则返回 WebDAV 地址；可使用 {path} {filename} 占位符，例如 https://img.example.com/{path}',
+      },
+    ],
+  },
+  sftp: {
+    name: 'SFTP',
+    description: '通过 SFTP 上传到自己的服务器',
+    uploadCommand: 'sftp_upload',
+    deleteCommand: 'sftp_delete',
+    parameters: [
+      { key: 'host', label: '主机', type: 'text', required: true },
+      { key: 'port', label: '端口', type: 'number', defaultValue: 22 },
+      { key: 'username', label: '用户名', type: 'text', required: true },
+      {
+        key: 'password',
+        label: '密码',
+        type: 'password',
+        secret: true,
+        description: '使用私钥认证时可留空',
+      },
+      {
+        key: 'privateKeyPath',
+        label: '私钥文件路径 (可选)',
+        type: 'text',
+        description:
+          '私钥的绝对路径，例如 /home/alice/.ssh/id_ed25519，设置后优先使用私钥认证',
+      },
+      {
+        key: 'privateKeyPassphrase',
+        label: '私钥口令 (可选)',
+        type: 'password',
+        secret: true,
+      },
+      {
+        key: 'hostFingerprint',
+        label: '服务器公钥指纹 (可选)',
+        type: 'text',
+        description:
+          'SHA256 指纹，例如 SHA256:abc...，填写后连接时校验；留空则首次连接时记录服务器公钥，之后公钥变化会拒绝连接',
+      },
+      {
+        key: 'remoteDir',
+        label: '远程目录',
+        type: 'text',
+        required: true,
+        description: 'Web 服务器的站点目录，例如 /var/www/img',
+      },
+      pathTemplateParameter,
+      {
+        key: 'publicUrl',
+        label: '访问根地址',
+        type: 'text',
+        required: true,
+        description: '远程目录对应的访问地址，例如 https://img.example.com',
+      },
+    ],
+  },
+  github: {
+    name: 'GitHub 仓库',
+    description: '通过 Contents API 将图片提交到 GitHub 仓库',
+    uploadCommand: 'github_upload',
+    deleteCommand: 'github_delete',
+    parameters: [
+      {
+        key: 'token',
+        label: 'Personal Access Token',
+        type: 'password',
+        required: true,
+        secret: true,
+        description: '需要目标仓库的 contents 读写权限',
+      },
+      { key: 'owner', label: '仓库所有者', type: 'text', required: true },
+      { key: 'repo', label: '仓库名', type: 'text', required: true },
+      { key: 'branch', label: '分支', type: 'text', defaultValue: 'main' },
+      {
+        key: 'remoteDir',
+        label: '存放目录 (可选)',
+        type: 'text',
+        description: '仓库内的目录，例如 images',
+      },
+      pathTemplateParameter,
+      {
+        key: 'cdn',
+        label: '访问方式',
+        type: 'select',
+        defaultValue: 'raw',
+        options: [
+          { label: 'raw.githubusercontent.com', value: 'raw' },
+          { label: 'jsDelivr CDN', value: 'jsdelivr' },
+        ],
+      },
+      {
+        key: 'publicUrl',
+        label: '自定义访问根地址 (可选)',
+        type: 'text',
+        description: '例如 GitHub Pages 域名，设置后忽略访问方式',
+      },
+      {
+        key: 'commitMessage',
+        label: '提交信息 (可选)',
+        type: 'text',
+        description: '支持 {filename} 占位符，默认 Upload {filename}',
+      },
+    ],
+  },
+  imgur: {
+    name: 'Imgur',
+    description: '上传到 Imgur，支持匿名与账号两种模式',
+    uploadCommand: 'imgur_upload',
+    deleteCommand: 'imgur_delete',
+    parameters: [
+      {
+        key: 'clientId',
+        label: 'Client ID',
+        type: 'text',
+        required: true,
+        description: '在 https://api.imgur.com/oauth2/addclient 注册应用获得',
+      },
+      {
+        key: 'mode',
+        label: '上传模式',
+        type: 'select',
+        defaultValue: 'anonymous',
+        options: [
+          { label: '匿名上传', value: 'anonymous' },
+          { label: '上传到账号', value: 'account' },
+        ],
+      },
+      {
+        key: 'clientSecret',
+        label: 'Client Secret',
+        type: 'password',
+        secret: true,
+        description: '账号模式登录时需要',
+      },
+      {
+        key: 'redirectPort',
+        label: '回调端口',
+        type: 'number',
+        description:
+          '账号模式下与应用登记的回调地址 http://127.0.0.1:<端口>/callback 保持一致',
+      },
+      {
+        key: 'albumId',
+        label: '相册 ID (可选)',
+        type: 'text',
+        description: '账号模式下上传到指定相册',
+      },
+    ],
+    actions: [
+      {
+        label: '登录 Imgur 账号',
+        run: async (params) => {
+          const username = await invoke<string | null>('imgur_login', {
+            config: params,
+          });
+          return username ? `已登录：${username}` : '登录成功';
+        },
+      },
+      {
+        label: '退出登录',
+        run: async () => {
+          await invoke('imgur_logout');
+          return '已退出 Imgur 账号';
+        },
+      },
+    ],
+  },
+  chevereto: {
+    name: 'Chevereto',
+    description: '上传到基于 Chevereto 搭建的图床站点',
+    uploadCommand: 'chevereto_upload',
+    deleteCommand: 'chevereto_delete',
+    parameters: [
+      {
+        key: 'baseUrl',
+        label: '站点地址',
+        type: 'text',
+        required: true,
+        description: '例如 https://img.example.com',
+      },
+      {
+        key: 'apiKey',
+        label: 'API Key',
+        type: 'password',
+        required: true,
+        secret: true,
+        description: '在站点的 设置 → API 中获取',
+      },
+      { key: 'albumId', label: '相册 ID (可选)', type: 'text' },
+    ],
+  },
+  smms: {
+    name: 'SM.MS 图床',
+    description: '使用 SM.MS API v2 上传与删除，可查询剩余空间',
+    uploadCommand: 'smms_upload',
+    deleteCommand: 'smms_delete',
+    parameters: [
+      {
+        key: 'token',
+        label: 'API Token',
+        type: 'password',
+        required: true,
+        secret: true,
+        description: '请填写在 SM.MS 用户中心获取的 API Token',
+      },
+      {
+        key: 'apiBase',
+        label: 'API 地址 (可选)',
+        type: 'text',
+        description:
+          '默认 https://sm.ms/api/v2，无法访问时可改为 https://smms.app/api/v2',
+      },
+    ],
+    actions: [
+      {
+        label: '查询剩余空间',
+        run: async (params) => {
+          const profile = await invoke<{
+            username: string | null;
+            diskUsageBytes: number;
+            diskLimitBytes: number;
+          }>('smms_profile', { config: params });
+          const mb = (bytes: number) => `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+          const remaining = Math.max(
+            profile.diskLimitBytes - profile.diskUsageBytes,
+            0
+          );
+          return `${profile.username ?? 'SM.MS'}：已用 ${mb(
+            profile.diskUsageBytes
+          )} / ${mb(profile.diskLimitBytes)}，剩余 ${mb(remaining)}`;
+        },
+      },
+    ],
+  },
+  cloudinary: {
+    name: 'Cloudinary',
+    description: '使用签名上传到 Cloudinary',
+    uploadCommand: 'cloudinary_upload',
+    deleteCommand: 'cloudinary_delete',
+    parameters: [
+      { key: 'cloudName', label: 'Cloud Name', type: 'text', required: true },
+      { key: 'apiKey', label: 'API Key', type: 'text', required: true },
+      {
+        key: 'apiSecret',
+        label: 'API Secret',
+        type: 'password',
+        required: true,
+        secret: true,
+      },
+      { key: 'uploadPreset', label: '上传预设 (可选)', type: 'text' },
+      { key: 'folder', label: '目录 (可选)', type: 'text' },
+      {
+        key: 'transformation',
+        label: '变换参数 (可选)',
+        type: 'text',
+        description: '附加到返回链接中，例如 f_auto,q_auto',
+      },
+    ],
+  },
+  azure: {
+    name: 'Azure Blob Storage',
+    description: '以 BlockBlob 方式上传到 Azure 存储容器',
+    uploadCommand: 'azure_blob_upload',
+    deleteCommand: 'azure_blob_delete',
+    parameters: [
+      { key: 'account', label: '存储账号', type: 'text', required: true },
+      {
+        key: 'accountKey',
+        label: '访问密钥',
+        type: 'password',
+        secret: true,
+        description: '与 SAS 令牌二选一，填写后优先使用',
+      },
+      {
+        key: 'sasToken',
+        label: 'SAS 令牌',
+        type: 'password',
+        secret: true,
+        description: '需要写入与删除权限，例如 sv=...&sig=...',
+      },
+      { key: 'container', label: '容器', type: 'text', required: true },
+      { key: 'remoteDir', label: '存放目录 (可选)', type: 'text' },
+      pathTemplateParameter,
+      {
+        key: 'urlMode',
+        label: '链接类型',
+        type: 'select',
+        defaultValue: 'public',
+        options: [
+          { label: '公开链接（容器允许匿名读取）', value: 'public' },
+          { label: '带 SAS 的只读链接（需访问密钥）', value: 'sas' },
+        ],
+      },
+      {
+        key: 'sasExpiryDays',
+        label: 'SAS 链接有效天数',
+        type: 'number',
+        defaultValue: 365,
+        description: '仅在使用访问密钥生成 SAS 链接时生效',
+      },
+      {
+        key: 'publicUrl',
+        label: '自定义访问根地址 (可选)',
+        type: 'text',
+        description: 'CDN 或自定义域名，例如 https://cdn.example.com/images',
+      },
+      {
+        key: 'endpoint',
+        label: '服务地址 (可选)',
+        type: 'text',
+        description: '默认 https://<账号>.blob.core.windows.net，Azurite 等模拟器可在此填写',
+      },
+    ],
+  },
+  gcs: {
+    name: 'Google Cloud Storage',
+    description: '使用服务账号或 HMAC 互操作密钥上传到 GCS 存储桶',
+    uploadCommand: 'gcs_upload',
+    deleteCommand: 'gcs_delete',
+    parameters: [
+      {
+        key: 'auth',
+        label: '认证方式',
+        type: 'select',
+        defaultValue: 'service_account',
+        options: [
+          { label: '服务账号 JSON', value: 'service_account' },
+          { label: 'HMAC 互操作密钥', value: 'hmac' },
+        ],
+      },
+      {
+        key: 'serviceAccountJson',
+        label: '服务账号 JSON',
+        type: 'textarea',
+        secret: true,
+        description: '粘贴服务账号密钥文件的完整内容，需要 Storage Object Admin 权限',
+      },
+      { key: 'hmacAccessId', label: 'HMAC Access ID', type: 'text' },
+      {
+        key: 'hmacSecret',
+        label: 'HMAC Secret',
+        type: 'password',
+        secret: true,
+      },
+      { key: 'bucket', label: '存储桶', type: 'text', required: true },
+      { key: 'remoteDir', label: '存放目录 (可选)', type: 'text' },
+      pathTemplateParameter,
+      {
+        key: 'makePublic',
+        label: '设置公开读',
+        type: 'boolean',
+        defaultValue: false,
+        description: '上传时附加 publicRead ACL；启用统一存储桶级访问权限时请关闭',
+      },
+      {
+        key: 'publicUrl',
+        label: '自定义访问根地址 (可选)',
+        type: 'text',
+        description: '默认 https://storage.googleapis.com/<存储桶>，可填写 CDN 地址或含 {path} 的模板',
+      },
+    ],
+  },
+  local: {
+    name: '本地目录',
+    description: '将图片复制到本地目录（如静态站点或同步盘），并按访问根地址生成链接',
+    uploadCommand: 'local_folder_upload',
+    deleteCommand: 'local_folder_delete',
+    parameters: [
+      {
+        key: 'targetDir',
+        label: '目标目录',
+        type: 'text',
+        required: true,
+        description: '绝对路径，例如 /home/me/blog/static/images',
+      },
+      {
+        ...pathTemplateParameter,
+        defaultValue: '{year}/{month}/{filename}',
+        description:
+          '目标目录下的相对路径，支持 {year} {month} {day} {uuid} {filename} {name} {ext}，同名文件自动追加序号',
+      },
+      {
+        key: 'baseUrl',
+        label: '访问根地址 (可选)',
+        type: 'text',
+        description: '目标目录对应的地址，例如 /images 或 https://blog.example.com/images；留空返回 file:// 地址',
+      },
+    ],
+  },
+  custom: {
+    name: '自定义 HTTP 上传',
+    description: '按声明式配置上传到任意 HTTP 接口，可与 ShareX 的 .sxcu 互相导入导出',
+    uploadCommand: 'custom_http_upload',
+    deleteCommand: 'custom_http_delete',
+    parameters: [
+      { key: 'name', label: '名称 (可选)', type: 'text' },
+      { key: 'requestUrl', label: '请求地址', type: 'text', required: true },
+      { key: 'method', label: '请求方法', type: 'text', defaultValue: 'POST' },
+      {
+        key: 'body',
+        label: '请求体',
+        type: 'select',
+        defaultValue: 'multipart_form_data',
+        options: [
+          { label: 'multipart 表单', value: 'multipart_form_data' },
+          { label: '文件二进制', value: 'binary' },
+        ],
+      },
+      {
+        key: 'fileFormName',
+        label: '文件字段名',
+        type: 'text',
+        defaultValue: 'file',
+      },
+      {
+        key: 'headers',
+        label: '请求头 (JSON)',
+        type: 'textarea',
+        secret: true,
+        description: '例如 {"Authorization": "Bearer xxx"}',
+      },
+      {
+        key: 'parameters',
+        label: '查询参数 (JSON)',
+        type: 'textarea',
+        description: '支持 {filename} 占位符',
+      },
+      {
+        key: 'arguments',
+        label: '表单字段 (JSON)',
+        type: 'textarea',
+        description: '附加的表单字段，支持 {filename} 占位符',
+      },
+      {
+        key: 'urlTemplate',
+        label: '图片地址',
+        type: 'text',
+        defaultValue: '{response}',
+        description: '从响应中提取，例如 {json:data.url}；{response} 表示整个响应文本',
+      },
+      {
+        key: 'deletionUrlTemplate',
+        label: '删除地址 (可选)',
+        type: 'text',
+        description: '例如 {json:data.delete_url}',
+      },
+      {
+        key: 'deletionMethod',
+        label: '删除请求方法',
+        type: 'text',
+        defaultValue: 'GET',
+      },
+    ],
+  },
+};
+
+function toErrorMessage(error: unknown): string {
+  return error instanceof Error ? error.message : String(error ?? '未知错误');
+}
+
+function createInternalPlugin(
+  id: string,
+  definition: InternalHostDefinition
+): LoadedPlugin {
+  return {
+    id,
+    sourceUrl: '',
+    name: definition.name,
+    author: '官方内置',
+    version: '1.0.0',
+    description: definition.description,
+    supportedFileTypes: imageFileTypes,
+    parameters: definition.parameters,
+    actions: definition.actions,
+    upload: async (filePath, originalFileName, params, _context) => {
+      for (const param of definition.parameters) {
+        const value = params[param.key];
+        if (param.required && (value == null || String(value).trim() === '')) {
+          throw new Error(`${param.label} 不能为空`);
+        }
+      }
+      return invoke<PluginUploadResult>(definition.uploadCommand, {
+        filePath,
+        originalFileName,
+        config: params,
+      });
+    },
+    remove: async (deleteId, _context): Promise<PluginDeleteResult> => {
+      if (!deleteId) {
+        return {
+          success: false,
+          message: `缺少 deleteId，无法从 ${definition.name} 删除。`,
+        };
+      }
+      return invoke<PluginDeleteResult>(definition.deleteCommand, {
+        deleteId,
+      }).catch((error: unknown) => ({
+        success: false,
+        message: toErrorMessage(error),
+      }));
+    },
+  };
+}
+
+/**
+ * 返回后端实现的内置插件；不是内置插件时返回 null。
+ */
+export function loadInternalPlugin(id: string): LoadedPlugin | null {
+  const definition = definitions[id];
+  return definition ? createInternalPlugin(id, definition) : null;
+}
@@ -1,6 +1,12 @@
 import { invoke, convertFileSrc } from '@tauri-apps/api/core';
-import type { ImageHostPlugin } from '../types/imageHostPlugin';
+import type {
+  ImageHostPlugin,
+  PluginDeleteFunction,
+  PluginUploadFunction,
+} from '../types/imageHostPlugin';
+import { SECRET_PLACEHOLDER } from '../types/imageHostPlugin';
 import { error, debug } from '@tauri-apps/plugin-log';
+import { loadInternalPlugin } from './internal';
 
 export interface PluginEntry {
   /** 插件唯一标识，用于存储和导航 */
@@ -38,6 +44,7 @@ try {
 }
 
 const pluginCache = new Map<string, Promise<LoadedPlugin>>();
+const pluginRevisions = new Map<string, number>();
 let entriesPromise: Promise<PluginEntry[]> | null = null;
 // cache last loaded entries so callers can check readiness even if the event was
 // dispatched before they registered a listener.
@@ -105,6 +112,22 @@ export function getLoadedPluginEntries(): PluginEntry[] | null {
   return lastEntries;
 }
 
+/**
+ * 插件脚本变更后清除缓存，下次加载时附带修订号绕过模块缓存。
+ */
+export function invalidatePlugins(ids: string[]) {
+  for (const id of ids) {
+    pluginCache.delete(id);
+    pluginRevisions.set(id, Date.now());
+  }
+}
+
+function withRevision(url: string, id: string): string {
+  const revision = pluginRevisions.get(id);
+  if (revision === undefined) return url;
+  return `${url}${url.includes('?') ? '&' : '?'}v=${revision}`;
+}
+
 /**
  * 解析插件脚本加载地址。
  * DEV 下通过 HTTP URL；PROD 下通过 convertFileSrc 转换为 tauri.localhost URL。
@@ -169,6 +192,27 @@ function resolvePluginUrl(entry: PluginEntry): string {
   }
 }
 
+/**
+ * JS 插件的上传 / 删除交给后端运行时执行（plugin_runtime），webview 只读取插件的元数据与参数定义。
+ * 后端按插件声明的权限执行，并支持 `cancel_upload` 取消，Android 上同样适用。
+ */
+function backendExecution(id: string): {
+  upload: PluginUploadFunction;
+  remove: PluginDeleteFunction;
+} {
+  return {
+    upload: (filePath, originalFileName, params) =>
+      invoke('execute_plugin_upload', {
+        pluginId: id,
+        filePath,
+        originalFileName,
+        settings: params,
+      }),
+    remove: (deleteId) =>
+      invoke('execute_plugin_delete', { pluginId: id, deleteId }),
+  };
+}
+
 export async function loadPlugin(entry: PluginEntry): Promise<LoadedPlugin> {
   if (!pluginCache.has(entry.id)) {
     const promise = (async () => {
@@ -241,6 +285,7 @@ export async function loadPlugin(entry: PluginEntry): Promise<LoadedPlugin> {
               label: 'Secret Access Key',
               type: 'password',
               required: true,
+              secret: true,
             },
             {
               key: 'endpoint',
@@ -290,6 +335,47 @@ export async function loadPlugin(entry: PluginEntry): Promise<LoadedPlugin> {
               description:
                 '例如 https://cdn.example.com，返回链接会拼接该前缀，留空则使用 S3 默认域名',
             },
+            {
+              key: 'publicUrlTemplate',
+              label: '对外访问地址模板 (可选)',
+              type: 'text',
+              description:
+                '完整地址模板，支持 {bucket}、{region}、{key}，例如 https://cdn.example.com/{key}，优先于对外访问根地址',
+            },
+            {
+              key: 'cdnProvider',
+              label: 'CDN 缓存清除',
+              type: 'select',
+              defaultValue: 'none',
+              options: [
+                { label: '不使用', value: 'none' },
+                { label: 'Cloudflare', value: 'cloudflare' },
+                { label: 'BunnyCDN', value: 'bunny' },
+              ],
+              description: '对外访问地址经过 CDN 时，用于清除被覆盖文件的缓存',
+            },
+            {
+              key: 'cdnZoneId',
+              label: 'Cloudflare Zone ID',
+              type: 'text',
+              description: '仅 Cloudflare 需要，在域名概览页右侧可找到',
+            },
+            {
+              key: 'cdnApiToken',
+              label: 'CDN API Token',
+              type: 'password',
+              secret: true,
+              description:
+                'Cloudflare 填具有 Cache Purge 权限的 API Token，BunnyCDN 填账户 API Key',
+            },
+            {
+              key: 'cdnAutoPurge',
+              label: '自动清除缓存',
+              type: 'boolean',
+              defaultValue: false,
+              description:
+                '上传覆盖同名对象后、以及从 S3 迁出图片后自动清除对应链接的 CDN 缓存',
+            },
           ],
           upload: async (filePath, originalFileName, params, _context) => {
             // 调用后端命令执行 S3 上传
@@ -307,10 +393,15 @@ export async function loadPlugin(entry: PluginEntry): Promise<LoadedPlugin> {
               params.accessKeyId,
               'Access Key ID'
             );
+            // 密钥已存入钥匙串时只传凭据 id，由后端读取；没有钥匙串的平台上配置仍为明文
             const secretAccessKey = requireString(
               params.secretAccessKey,
               'Secret Access Key'
             );
+            const credentials =
+              secretAccessKey === SECRET_PLACEHOLDER
+                ? { credentialId: 'image-host:s3' }
+                : { secretAccessKey };
 
             const result = await invoke<S3UploadBackendResult>('s3_upload', {
               filePath,
@@ -318,12 +409,19 @@ export async function loadPlugin(entry: PluginEntry): Promise<LoadedPlugin> {
               bucket,
               region,
               accessKeyId,
-              secretAccessKey,
+              ...credentials,
               endpoint: asOptionalString(params.endpoint),
               forcePathStyle: asBoolean(params.forcePathStyle, false),
               objectPrefix: asOptionalString(params.objectPrefix),
               acl: asOptionalString(params.acl),
               publicBaseUrl: asOptionalString(params.publicBaseUrl),
+              publicUrlTemplate: asOptionalString(params.publicUrlTemplate),
+              cdn: {
+                cdnProvider: asOptionalString(params.cdnProvider) ?? 'none',
+                cdnZoneId: asOptionalString(params.cdnZoneId),
+                cdnApiToken: asOptionalString(params.cdnApiToken),
+                cdnAutoPurge: asBoolean(params.cdnAutoPurge, false),
+              },
             });
             return {
               url: result.url,
@@ -339,29 +437,11 @@ export async function loadPlugin(entry: PluginEntry): Promise<LoadedPlugin> {
               };
             }
 
-            const settings = await invoke<Record<string, unknown> | null>(
-              'load_image_host_settings',
-              { pluginId: 's3' }
-            ).catch(() => null);
-
-            const accessKeyId =
-              asOptionalString(settings?.['accessKeyId']) ?? '';
-            const secretAccessKey =
-              asOptionalString(settings?.['secretAccessKey']) ?? '';
-
-            if (!accessKeyId || !secretAccessKey) {
-              return {
-                success: false,
-                message:
-                  '请先在 S3 插件设置中填写 Access Key 和 Secret Access Key。',
-              };
-            }
-
-            const result = await invoke<S3DeleteBackendResult>('s3_delete', {
-              deleteId,
-              accessKeyId,
-              secretAccessKey,
-            }).catch((error: unknown) => ({
+            // 后端按已保存的配置删除，钥匙串字段由后端解析
+            const result = await invoke<S3DeleteBackendResult>(
+              'delete_image_from_host',
+              { pluginId: 's3', deleteId }
+            ).catch((error: unknown) => ({
               success: false,
               message:
                 error instanceof Error
@@ -375,6 +455,12 @@ export async function loadPlugin(entry: PluginEntry): Promise<LoadedPlugin> {
         return stub;
       }
 
+      // 其他后端实现的内置插件（WebDAV 等）
+      const internal = loadInternalPlugin(entry.id);
+      if (internal && entry.script.startsWith('__internal__/')) {
+        return internal;
+      }
+
       // 检查是否有静态导入的内置插件（用于 Android 等不支持动态加载的平台）
       const builtinModulePath = `./${entry.id}.js`;
       if (builtinPluginModules[builtinModulePath]) {
@@ -411,13 +497,12 @@ export async function loadPlugin(entry: PluginEntry): Promise<LoadedPlugin> {
           description: pluginModule.description,
           supportedFileTypes: pluginModule.supportedFileTypes,
           parameters: pluginModule.parameters ?? [],
-          upload: pluginModule.upload,
-          remove: pluginModule.remove,
+          ...backendExecution(entry.id),
         } satisfies LoadedPlugin;
       }
 
       // 回退到动态加载（桌面平台或用户自定义插件）
-      const url = resolvePluginUrl(entry);
+      const url = withRevision(resolvePluginUrl(entry), entry.id);
       let mod: any;
       try {
         mod = await import(/* @vite-ignore */ url);
@@ -470,8 +555,7 @@ export async function loadPlugin(entry: PluginEntry): Promise<LoadedPlugin> {
         description: pluginModule.description,
         supportedFileTypes: pluginModule.supportedFileTypes,
         parameters: pluginModule.parameters ?? [],
-        upload: pluginModule.upload,
-        remove: pluginModule.remove,
+        ...backendExecution(entry.id),
       } satisfies LoadedPlugin;
     })();
 
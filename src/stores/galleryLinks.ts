@@ -0,0 +1,25 @@
+import { ref, readonly } from 'vue';
+import { listen } from '@tauri-apps/api/event';
+
+export type GalleryLinkFilter = { host?: string | null; keyword?: string | null };
+
+// yana://gallery 深层链接携带的筛选条件，在图库页挂载后应用
+const pending = ref<GalleryLinkFilter | null>(null);
+let listening = false;
+
+export function useGalleryLinks() {
+  if (!listening) {
+    listening = true;
+    void listen<GalleryLinkFilter>('deep-link://gallery', (event) => {
+      pending.value = event.payload;
+    });
+  }
+
+  function take(): GalleryLinkFilter | null {
+    const filter = pending.value;
+    pending.value = null;
+    return filter;
+  }
+
+  return { pending: readonly(pending), take };
+}
@@ -1,5 +1,6 @@
 import { reactive, ref, readonly, watch } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import {
   info,
   warn as logWarn,
@@ -12,11 +13,26 @@ import {
 import {
   loadPlugin,
   getPluginEntries,
+  invalidatePlugins,
   type LoadedPlugin,
 } from '../plugins/registry';
 
 const STORAGE_PREFIX = 'image-host-settings:';
 
+interface PluginsChangedPayload {
+  added: string[];
+  modified: string[];
+  removed: string[];
+}
+
+type SettingsChangedPayload =
+  | { scope: 'app' }
+  | {
+      scope: 'imageHost';
+      pluginId: string;
+      values: Record<string, unknown> | null;
+    };
+
 interface PluginSettingsState {
   values: Record<string, unknown>;
   saving: boolean;
@@ -198,9 +214,14 @@ function createStore() {
     try {
       target.saving = true;
       const payload = cloneValues(target.values);
+      const plugin = plugins.value.find((item) => item.id === id);
+      const secretKeys = (plugin?.parameters ?? [])
+        .filter((param) => param.secret)
+        .map((param) => param.key);
       await invoke('save_image_host_settings', {
         pluginId: id,
         values: payload,
+        secretKeys,
       });
       target.lastSavedAt = Date.now();
       target.error = null;
@@ -318,6 +339,26 @@ function createStore() {
 
   void ensureLoaded();
 
+  // 插件脚本在磁盘上变更时热重载，无需重启应用
+  void listen<PluginsChangedPayload>('plugins://changed', async (event) => {
+    const { added, modified, removed } = event.payload;
+    invalidatePlugins([...added, ...modified, ...removed]);
+    await info(
+      `[imageHosts] 插件变更: +${added.length} ~${modified.length} -${removed.length}`
+    );
+    await loadAll(true);
+  });
+
+  // 其他窗口或后端修改了图床配置时重新读取；本窗口有未保存修改时以本地为准
+  void listen<SettingsChangedPayload>('settings://changed', async (event) => {
+    if (event.payload.scope !== 'imageHost') return;
+    const { pluginId } = event.payload;
+    const plugin = getPluginById(pluginId);
+    const state = settings[pluginId];
+    if (!plugin || !state || state.saving || timers.has(pluginId)) return;
+    await hydrateSettings(plugin);
+  });
+
   return {
     plugins: readonly(plugins),
     loading: readonly(loading),
@@ -1,18 +1,109 @@
 import { customRef, ref, readonly } from 'vue';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { info, debug, error as logError } from '@tauri-apps/plugin-log';
 
 type PngCompressionMode = 'lossy' | 'lossless';
-type PngOptimizationLevel = 'best' | 'default' | 'fast';
+export type PngOptimizationLevel = 'best' | 'default' | 'fast';
+// HEIC 图像序列（连拍、Live Photo）：取主图 / 转为动画 WebP / 转为 GIF
+export type HeicSequenceMode = 'still' | 'webp' | 'gif';
+// 图片含 GPS 或设备序列号时：阻止并提示 / 自动去除 / 不检查
+export type MetadataPrivacyPolicy = 'warn' | 'strip' | 'never';
+
+export type LinkTemplate = {
+  id: string;
+  name: string;
+  template: string;
+};
+
+export type WatchFolder = {
+  path: string;
+  // 上传使用的图床 id
+  host: string;
+  recursive: boolean;
+  enabled: boolean;
+};
+
+export type WebhookEvent = 'success' | 'failure';
+
+export type Webhook = {
+  // 钥匙串中签名密钥条目的标识，由后端在保存时分配
+  id: string;
+  url: string;
+  // 签名密钥，为空时不签名；已保存的密钥为钥匙串占位符
+  secret: string;
+  events: WebhookEvent[];
+  enabled: boolean;
+};
+
+export type TelegramPostTarget = 'chat' | 'telegraph';
+
+export type TelegramPost = {
+  // 钥匙串中 token 条目的标识，由后端在保存时分配
+  id: string;
+  // 触发发布的图床 id
+  host: string;
+  target: TelegramPostTarget;
+  // Bot token 或 Telegraph access token；已保存的 token 为钥匙串占位符
+  token: string;
+  chatId: string;
+  content: 'link' | 'photo';
+  // Telegraph 页面路径，为空时每次新建页面
+  pagePath: string;
+  enabled: boolean;
+};
 
 type PersistedSettings = {
   quality: number;
   convertToWebp: boolean;
   pngCompressionMode: PngCompressionMode;
   pngOptimization: PngOptimizationLevel;
+  autoQuality: boolean;
+  autoQualitySsim: number;
+  heicSequenceMode: HeicSequenceMode;
   enableUploadCompression: boolean;
   maxConcurrentUploads: number;
   enableThumbnailCache: boolean;
+  fileNameTemplate: string;
+  defaultSaveDir: string;
+  logLevel: string;
+  cleanTempOnExit: boolean;
+  tempMaxAgeHours: number;
+  // 从图库删除后可撤销的时长（秒）
+  deleteUndoSeconds: number;
+  // 加密上传的解密页面地址
+  encryptedViewerUrl: string;
+  clipboardWatch: boolean;
+  clipboardAutoUpload: boolean;
+  hotkeyUploadClipboard: string;
+  hotkeyUploadScreenshot: string;
+  hotkeyQuickDrop: string;
+  picgoServerEnabled: boolean;
+  picgoServerPort: number;
+  picgoServerToken: string;
+  picgoServerHost: string;
+  linkTemplates: LinkTemplate[];
+  defaultLinkFormat: string;
+  ocrAutoIndex: boolean;
+  ocrLanguage: string;
+  tesseractPath: string;
+  linkCheckEnabled: boolean;
+  linkCheckIntervalHours: number;
+  linkCheckBatchSize: number;
+  linkCheckRequireAc: boolean;
+  // 计费网络下暂停上传
+  pauseUploadsOnMetered: boolean;
+  // 断网时挂起上传
+  pauseUploadsWhenOffline: boolean;
+  skipDuplicateUploads: boolean;
+  crashReportingEnabled: boolean;
+  crashReportEndpoint: string;
+  watchFolders: WatchFolder[];
+  watchFolderCopyLink: boolean;
+  webhooks: Webhook[];
+  telegramPosts: TelegramPost[];
+  desktopNotifications: boolean;
+  metadataPrivacyPolicy: MetadataPrivacyPolicy;
 };
 
 const DEFAULTS: PersistedSettings = {
@@ -20,11 +111,54 @@ const DEFAULTS: PersistedSettings = {
   convertToWebp: false,
   pngCompressionMode: 'lossless',
   pngOptimization: 'default',
+  autoQuality: false,
+  autoQualitySsim: 0.98,
+  heicSequenceMode: 'still',
   enableUploadCompression: false,
   maxConcurrentUploads: 5,
   enableThumbnailCache: true,
+  fileNameTemplate: '',
+  defaultSaveDir: '',
+  logLevel: '',
+  cleanTempOnExit: true,
+  tempMaxAgeHours: 24,
+  deleteUndoSeconds: 15,
+  encryptedViewerUrl: '',
+  clipboardWatch: false,
+  clipboardAutoUpload: false,
+  hotkeyUploadClipboard: 'CmdOrCtrl+Shift+U',
+  hotkeyUploadScreenshot: 'CmdOrCtrl+Shift+S',
+  hotkeyQuickDrop: 'CmdOrCtrl+Shift+D',
+  picgoServerEnabled: false,
+  picgoServerPort: 36677,
+  picgoServerToken: '',
+  picgoServerHost: '',
+  linkTemplates: [],
+  defaultLinkFormat: 'link',
+  ocrAutoIndex: false,
+  ocrLanguage: 'eng',
+  tesseractPath: '',
+  linkCheckEnabled: false,
+  linkCheckIntervalHours: 24,
+  linkCheckBatchSize: 50,
+  linkCheckRequireAc: true,
+  pauseUploadsOnMetered: false,
+  pauseUploadsWhenOffline: true,
+  skipDuplicateUploads: true,
+  crashReportingEnabled: false,
+  crashReportEndpoint: '',
+  watchFolders: [],
+  watchFolderCopyLink: true,
+  webhooks: [],
+  telegramPosts: [],
+  desktopNotifications: true,
+  metadataPrivacyPolicy: 'warn',
 };
 
+type SettingsChangedPayload =
+  | { scope: 'app'; settings: Partial<PersistedSettings> }
+  | { scope: 'imageHost'; pluginId: string; values: unknown };
+
 let singleton: ReturnType<typeof createStore> | null = null;
 
 function safeJson(value: unknown): string {
@@ -52,6 +186,12 @@ function sanitizeQuality(input: unknown): number {
   return n;
 }
 
+function sanitizeAutoQualitySsim(input: unknown): number {
+  const n = Number(input);
+  if (!Number.isFinite(n)) return DEFAULTS.autoQualitySsim;
+  return Math.min(0.999, Math.max(0.9, n));
+}
+
 function sanitizePngMode(value: unknown): PngCompressionMode {
   return value === 'lossy' ? 'lossy' : 'lossless';
 }
@@ -63,6 +203,17 @@ function sanitizePngOptimization(value: unknown): PngOptimizationLevel {
   return 'default';
 }
 
+function sanitizeHeicSequenceMode(value: unknown): HeicSequenceMode {
+  return value === 'webp' || value === 'gif' ? value : 'still';
+}
+
+function sanitizePrivacyPolicy(value: unknown): MetadataPrivacyPolicy {
+  if (value === 'strip' || value === 'never') {
+    return value;
+  }
+  return 'warn';
+}
+
 function sanitizeConcurrency(input: unknown): number {
   let n = Number(input);
   if (!Number.isFinite(n)) n = DEFAULTS.maxConcurrentUploads;
@@ -72,6 +223,120 @@ function sanitizeConcurrency(input: unknown): number {
   return n;
 }
 
+function sanitizeTempMaxAge(input: unknown): number {
+  let n = Number(input);
+  if (!Number.isFinite(n)) n = DEFAULTS.tempMaxAgeHours;
+  n = Math.round(n);
+  if (n < 0) n = 0;
+  if (n > 24 * 365) n = 24 * 365;
+  return n;
+}
+
+function sanitizeDeleteUndo(input: unknown): number {
+  let n = Number(input);
+  if (!Number.isFinite(n)) n = DEFAULTS.deleteUndoSeconds;
+  n = Math.round(n);
+  if (n < 0) n = 0;
+  if (n > 300) n = 300;
+  return n;
+}
+
+function sanitizePort(input: unknown): number {
+  const n = Math.round(Number(input));
+  if (!Number.isFinite(n) || n < 1 || n > 65535) return DEFAULTS.picgoServerPort;
+  return n;
+}
+
+function sanitizeText(value: unknown): string {
+  return typeof value === 'string' ? value.trim() : DEFAULTS.fileNameTemplate;
+}
+
+function sanitizeLinkTemplates(input: unknown): LinkTemplate[] {
+  if (!Array.isArray(input)) return [];
+  return input
+    .filter((item) => item && typeof item === 'object')
+    .map((item: any) => ({
+      id: typeof item.id === 'string' ? item.id.trim() : '',
+      name: typeof item.name === 'string' ? item.name.trim() : '',
+      template: typeof item.template === 'string' ? item.template : '',
+    }))
+    .filter((item) => item.id && item.template);
+}
+
+function sanitizeWatchFolders(input: unknown): WatchFolder[] {
+  if (!Array.isArray(input)) return [];
+  return input
+    .filter((item) => item && typeof item === 'object')
+    .map((item: any) => ({
+      path: typeof item.path === 'string' ? item.path.trim() : '',
+      host: typeof item.host === 'string' ? item.host.trim() : '',
+      recursive: Boolean(item.recursive),
+      enabled: item.enabled !== false,
+    }))
+    .filter((item) => item.path);
+}
+
+function sanitizeWebhooks(input: unknown): Webhook[] {
+  if (!Array.isArray(input)) return [];
+  return input
+    .filter((item) => item && typeof item === 'object')
+    .map((item: any) => ({
+      id: typeof item.id === 'string' ? item.id.trim() : '',
+      url: typeof item.url === 'string' ? item.url.trim() : '',
+      secret: typeof item.secret === 'string' ? item.secret.trim() : '',
+      events: Array.isArray(item.events)
+        ? (['success', 'failure'] as WebhookEvent[]).filter((event) =>
+            item.events.includes(event)
+          )
+        : (['success'] as WebhookEvent[]),
+      enabled: item.enabled !== false,
+    }));
+}
+
+function sanitizeTelegramPosts(input: unknown): TelegramPost[] {
+  if (!Array.isArray(input)) return [];
+  return input
+    .filter((item) => item && typeof item === 'object')
+    .map((item: any) => ({
+      id: typeof item.id === 'string' ? item.id.trim() : '',
+      host: typeof item.host === 'string' ? item.host.trim() : '',
+      target: (item.target === 'telegraph'
+        ? 'telegraph'
+        : 'chat') as TelegramPostTarget,
+      token: typeof item.token === 'string' ? item.token.trim() : '',
+      chatId: typeof item.chatId === 'string' ? item.chatId.trim() : '',
+      content: (item.content === 'photo' ? 'photo' : 'link') as
+        | 'link'
+        | 'photo',
+      pagePath: typeof item.pagePath === 'string' ? item.pagePath.trim() : '',
+      enabled: item.enabled !== false,
+    }));
+}
+
+function sanitizeLinkFormat(value: unknown): string {
+  return sanitizeText(value) || DEFAULTS.defaultLinkFormat;
+}
+
+function sanitizeOcrLanguage(value: unknown): string {
+  return sanitizeText(value) || DEFAULTS.ocrLanguage;
+}
+
+function sanitizeLinkCheckInterval(input: unknown): number {
+  let n = Math.round(Number(input));
+  if (!Number.isFinite(n)) n = DEFAULTS.linkCheckIntervalHours;
+  if (n < 1) n = 1;
+  if (n > 24 * 30) n = 24 * 30;
+  return n;
+}
+
+function sanitizeLinkCheckBatch(input: unknown): number {
+  let n = Math.round(Number(input));
+  if (!Number.isFinite(n)) n = DEFAULTS.linkCheckBatchSize;
+  if (n < 1) n = 1;
+  if (n > 500) n = 500;
+  return n;
+}
+
 function normalizePayload(
   payload:
     | (Partial<PersistedSettings> & { maxUploadConcurrency?: number })
@@ -92,6 +357,13 @@ function normalizePayload(
     pngOptimization: sanitizePngOptimization(
       payload?.pngOptimization ?? DEFAULTS.pngOptimization
     ),
+    autoQuality: Boolean(payload?.autoQuality ?? DEFAULTS.autoQuality),
+    autoQualitySsim: sanitizeAutoQualitySsim(
+      payload?.autoQualitySsim ?? DEFAULTS.autoQualitySsim
+    ),
+    heicSequenceMode: sanitizeHeicSequenceMode(
+      payload?.heicSequenceMode ?? DEFAULTS.heicSequenceMode
+    ),
     enableUploadCompression: Boolean(
       payload?.enableUploadCompression ?? DEFAULTS.enableUploadCompression
     ),
@@ -101,6 +373,80 @@ function normalizePayload(
     enableThumbnailCache: Boolean(
       payload?.enableThumbnailCache ?? DEFAULTS.enableThumbnailCache
     ),
+    fileNameTemplate: sanitizeText(payload?.fileNameTemplate),
+    defaultSaveDir: sanitizeText(payload?.defaultSaveDir),
+    logLevel: sanitizeText(payload?.logLevel),
+    cleanTempOnExit: Boolean(
+      payload?.cleanTempOnExit ?? DEFAULTS.cleanTempOnExit
+    ),
+    tempMaxAgeHours: sanitizeTempMaxAge(
+      payload?.tempMaxAgeHours ?? DEFAULTS.tempMaxAgeHours
+    ),
+    deleteUndoSeconds: sanitizeDeleteUndo(
+      payload?.deleteUndoSeconds ?? DEFAULTS.deleteUndoSeconds
+    ),
+    encryptedViewerUrl: sanitizeText(payload?.encryptedViewerUrl),
+    clipboardWatch: Boolean(payload?.clipboardWatch ?? DEFAULTS.clipboardWatch),
+    clipboardAutoUpload: Boolean(
+      payload?.clipboardAutoUpload ?? DEFAULTS.clipboardAutoUpload
+    ),
+    hotkeyUploadClipboard: sanitizeText(
+      payload?.hotkeyUploadClipboard ?? DEFAULTS.hotkeyUploadClipboard
+    ),
+    hotkeyUploadScreenshot: sanitizeText(
+      payload?.hotkeyUploadScreenshot ?? DEFAULTS.hotkeyUploadScreenshot
+    ),
+    hotkeyQuickDrop: sanitizeText(
+      payload?.hotkeyQuickDrop ?? DEFAULTS.hotkeyQuickDrop
+    ),
+    picgoServerEnabled: Boolean(
+      payload?.picgoServerEnabled ?? DEFAULTS.picgoServerEnabled
+    ),
+    picgoServerPort: sanitizePort(
+      payload?.picgoServerPort ?? DEFAULTS.picgoServerPort
+    ),
+    picgoServerToken: sanitizeText(payload?.picgoServerToken),
+    picgoServerHost: sanitizeText(payload?.picgoServerHost),
+    linkTemplates: sanitizeLinkTemplates(payload?.linkTemplates),
+    defaultLinkFormat: sanitizeLinkFormat(payload?.defaultLinkFormat),
+    ocrAutoIndex: Boolean(payload?.ocrAutoIndex ?? DEFAULTS.ocrAutoIndex),
+    ocrLanguage: sanitizeOcrLanguage(payload?.ocrLanguage),
+    tesseractPath: sanitizeText(payload?.tesseractPath),
+    linkCheckEnabled: Boolean(
+      payload?.linkCheckEnabled ?? DEFAULTS.linkCheckEnabled
+    ),
+    linkCheckIntervalHours: sanitizeLinkCheckInterval(
+      payload?.linkCheckIntervalHours
+    ),
+    linkCheckBatchSize: sanitizeLinkCheckBatch(payload?.linkCheckBatchSize),
+    linkCheckRequireAc: Boolean(
+      payload?.linkCheckRequireAc ?? DEFAULTS.linkCheckRequireAc
+    ),
+    pauseUploadsOnMetered: Boolean(
+      payload?.pauseUploadsOnMetered ?? DEFAULTS.pauseUploadsOnMetered
+    ),
+    pauseUploadsWhenOffline: Boolean(
+      payload?.pauseUploadsWhenOffline ?? DEFAULTS.pauseUploadsWhenOffline
+    ),
+    skipDuplicateUploads: Boolean(
+      payload?.skipDuplicateUploads ?? DEFAULTS.skipDuplicateUploads
+    ),
+    crashReportingEnabled: Boolean(
+      payload?.crashReportingEnabled ?? DEFAULTS.crashReportingEnabled
+    ),
+    crashReportEndpoint: sanitizeText(payload?.crashReportEndpoint),
+    watchFolders: sanitizeWatchFolders(payload?.watchFolders),
+    webhooks: sanitizeWebhooks(payload?.webhooks),
+    telegramPosts: sanitizeTelegramPosts(payload?.telegramPosts),
+    desktopNotifications: Boolean(
+      payload?.desktopNotifications ?? DEFAULTS.desktopNotifications
+    ),
+    watchFolderCopyLink: Boolean(
+      payload?.watchFolderCopyLink ?? DEFAULTS.watchFolderCopyLink
+    ),
+    metadataPrivacyPolicy: sanitizePrivacyPolicy(
+      payload?.metadataPrivacyPolicy ?? DEFAULTS.metadataPrivacyPolicy
+    ),
   };
 }
 
@@ -110,6 +456,8 @@ function createStore() {
   const lastError = ref<string | null>(null);
 
   let hydrating = true;
+  // 外部（其他窗口 / 后端）修改设置时递增，使所有 customRef 重新读取
+  const revision = ref(0);
   let persistTimer: ReturnType<typeof setTimeout> | null = null;
 
   // 内部存储实际值
@@ -124,6 +472,7 @@ function createStore() {
       return {
         get() {
           track();
+          void revision.value;
           return internalState[key] as T;
         },
         set(newValue: T) {
@@ -180,11 +529,62 @@ function createStore() {
       convertToWebp: internalState.convertToWebp,
       pngCompressionMode: sanitizePngMode(internalState.pngCompressionMode),
       pngOptimization: sanitizePngOptimization(internalState.pngOptimization),
+      autoQuality: Boolean(internalState.autoQuality),
+      autoQualitySsim: sanitizeAutoQualitySsim(internalState.autoQualitySsim),
+      heicSequenceMode: sanitizeHeicSequenceMode(internalState.heicSequenceMode),
       enableUploadCompression: Boolean(internalState.enableUploadCompression),
       maxConcurrentUploads: sanitizeConcurrency(
         internalState.maxConcurrentUploads
       ),
       enableThumbnailCache: Boolean(internalState.enableThumbnailCache),
+      fileNameTemplate: sanitizeText(internalState.fileNameTemplate),
+      defaultSaveDir: sanitizeText(internalState.defaultSaveDir),
+      logLevel: sanitizeText(internalState.logLevel),
+      cleanTempOnExit: Boolean(internalState.cleanTempOnExit),
+      tempMaxAgeHours: sanitizeTempMaxAge(internalState.tempMaxAgeHours),
+      deleteUndoSeconds: sanitizeDeleteUndo(internalState.deleteUndoSeconds),
+      encryptedViewerUrl: sanitizeText(internalState.encryptedViewerUrl),
+      clipboardWatch: Boolean(internalState.clipboardWatch),
+      clipboardAutoUpload: Boolean(internalState.clipboardAutoUpload),
+      hotkeyUploadClipboard: sanitizeText(internalState.hotkeyUploadClipboard),
+      hotkeyUploadScreenshot: sanitizeText(
+        internalState.hotkeyUploadScreenshot
+      ),
+      hotkeyQuickDrop: sanitizeText(internalState.hotkeyQuickDrop),
+      picgoServerEnabled: Boolean(internalState.picgoServerEnabled),
+      picgoServerPort: sanitizePort(internalState.picgoServerPort),
+      picgoServerToken: sanitizeText(internalState.picgoServerToken),
+      picgoServerHost: sanitizeText(internalState.picgoServerHost),
+      linkTemplates: sanitizeLinkTemplates(internalState.linkTemplates),
+      defaultLinkFormat: sanitizeLinkFormat(internalState.defaultLinkFormat),
+      ocrAutoIndex: Boolean(internalState.ocrAutoIndex),
+      ocrLanguage: sanitizeOcrLanguage(internalState.ocrLanguage),
+      tesseractPath: sanitizeText(internalState.tesseractPath),
+      linkCheckEnabled: Boolean(internalState.linkCheckEnabled),
+      linkCheckIntervalHours: sanitizeLinkCheckInterval(
+        internalState.linkCheckIntervalHours
+      ),
+      linkCheckBatchSize: sanitizeLinkCheckBatch(
+        internalState.linkCheckBatchSize
+      ),
+      linkCheckRequireAc: Boolean(internalState.linkCheckRequireAc),
+      pauseUploadsOnMetered: Boolean(internalState.pauseUploadsOnMetered),
+      pauseUploadsWhenOffline: Boolean(internalState.pauseUploadsWhenOffline),
+      skipDuplicateUploads: Boolean(internalState.skipDuplicateUploads),
+      crashReportingEnabled: Boolean(internalState.crashReportingEnabled),
+      crashReportEndpoint: sanitizeText(internalState.crashReportEndpoint),
+      watchFolders: sanitizeWatchFolders(internalState.watchFolders),
+      watchFolderCopyLink: Boolean(internalState.watchFolderCopyLink),
+      webhooks: sanitizeWebhooks(internalState.webhooks).filter(
+        (hook) => hook.url
+      ),
+      telegramPosts: sanitizeTelegramPosts(internalState.telegramPosts).filter(
+        (post) => post.host && post.token
+      ),
+      desktopNotifications: Boolean(internalState.desktopNotifications),
+      metadataPrivacyPolicy: sanitizePrivacyPolicy(
+        internalState.metadataPrivacyPolicy
+      ),
     };
     try {
       await debug(`[settings] persist: saving ${safeJson(payload)}`);
@@ -215,9 +615,25 @@ function createStore() {
     }, 400);
   }
 
+  function applyRemoteChange(payload: Partial<PersistedSettings>) {
+    const normalized = normalizePayload(payload);
+    const changed = (Object.keys(normalized) as (keyof PersistedSettings)[])
+      .some((key) => safeJson(normalized[key]) !== safeJson(internalState[key]));
+    // 本窗口尚有未保存的修改时以本地为准，避免覆盖用户输入
+    if (!changed || persistTimer) return;
+    void debug(`[settings] applying remote change: ${safeJson(normalized)}`);
+    Object.assign(internalState, normalized);
+    revision.value += 1;
+  }
+
   // 启动加载
   void load();
 
+  void listen<SettingsChangedPayload>('settings://changed', (event) => {
+    if (event.payload.scope !== 'app' || loading.value) return;
+    applyRemoteChange(event.payload.settings);
+  });
+
   // 创建所有的 auto-save refs
   const quality = createAutoSaveRef<number>('quality', sanitizeQuality);
   const convertToWebp = createAutoSaveRef<boolean>('convertToWebp');
@@ -229,6 +645,15 @@ function createStore() {
     'pngOptimization',
     sanitizePngOptimization
   );
+  const autoQuality = createAutoSaveRef<boolean>('autoQuality');
+  const autoQualitySsim = createAutoSaveRef<number>(
+    'autoQualitySsim',
+    sanitizeAutoQualitySsim
+  );
+  const heicSequenceMode = createAutoSaveRef<HeicSequenceMode>(
+    'heicSequenceMode',
+    sanitizeHeicSequenceMode
+  );
   const enableUploadCompression = createAutoSaveRef<boolean>(
     'enableUploadCompression'
   );
@@ -239,15 +664,167 @@ function createStore() {
   const enableThumbnailCache = createAutoSaveRef<boolean>(
     'enableThumbnailCache'
   );
+  const fileNameTemplate = createAutoSaveRef<string>(
+    'fileNameTemplate',
+    sanitizeText
+  );
+  const defaultSaveDir = createAutoSaveRef<string>(
+    'defaultSaveDir',
+    sanitizeText
+  );
+  const logLevel = createAutoSaveRef<string>('logLevel', sanitizeText);
+  const cleanTempOnExit = createAutoSaveRef<boolean>('cleanTempOnExit');
+  const tempMaxAgeHours = createAutoSaveRef<number>(
+    'tempMaxAgeHours',
+    sanitizeTempMaxAge
+  );
+  const deleteUndoSeconds = createAutoSaveRef<number>(
+    'deleteUndoSeconds',
+    sanitizeDeleteUndo
+  );
+  const encryptedViewerUrl = createAutoSaveRef<string>(
+    'encryptedViewerUrl',
+    sanitizeText
+  );
+  const clipboardWatch = createAutoSaveRef<boolean>('clipboardWatch');
+  const clipboardAutoUpload = createAutoSaveRef<boolean>(
+    'clipboardAutoUpload'
+  );
+  const hotkeyUploadClipboard = createAutoSaveRef<string>(
+    'hotkeyUploadClipboard',
+    sanitizeText
+  );
+  const hotkeyUploadScreenshot = createAutoSaveRef<string>(
+    'hotkeyUploadScreenshot',
+    sanitizeText
+  );
+  const hotkeyQuickDrop = createAutoSaveRef<string>(
+    'hotkeyQuickDrop',
+    sanitizeText
+  );
+  const picgoServerEnabled = createAutoSaveRef<boolean>('picgoServerEnabled');
+  const picgoServerPort = createAutoSaveRef<number>(
+    'picgoServerPort',
+    sanitizePort
+  );
+  const picgoServerToken = createAutoSaveRef<string>(
+    'picgoServerToken',
+    sanitizeText
+  );
+  const picgoServerHost = createAutoSaveRef<string>(
+    'picgoServerHost',
+    sanitizeText
+  );
+  const linkTemplates = createAutoSaveRef<LinkTemplate[]>(
+    'linkTemplates',
+    sanitizeLinkTemplates
+  );
+  const defaultLinkFormat = createAutoSaveRef<string>(
+    'defaultLinkFormat',
+    sanitizeLinkFormat
+  );
+  const ocrAutoIndex = createAutoSaveRef<boolean>('ocrAutoIndex');
+  const ocrLanguage = createAutoSaveRef<string>(
+    'ocrLanguage',
+    sanitizeOcrLanguage
+  );
+  const tesseractPath = createAutoSaveRef<string>(
+    'tesseractPath',
+    sanitizeText
+  );
+  const linkCheckEnabled = createAutoSaveRef<boolean>('linkCheckEnabled');
+  const linkCheckIntervalHours = createAutoSaveRef<number>(
+    'linkCheckIntervalHours',
+    sanitizeLinkCheckInterval
+  );
+  const linkCheckBatchSize = createAutoSaveRef<number>(
+    'linkCheckBatchSize',
+    sanitizeLinkCheckBatch
+  );
+  const linkCheckRequireAc = createAutoSaveRef<boolean>('linkCheckRequireAc');
+  const pauseUploadsOnMetered = createAutoSaveRef<boolean>(
+    'pauseUploadsOnMetered'
+  );
+  const pauseUploadsWhenOffline = createAutoSaveRef<boolean>(
+    'pauseUploadsWhenOffline'
+  );
+  const skipDuplicateUploads = createAutoSaveRef<boolean>(
+    'skipDuplicateUploads'
+  );
+  const crashReportingEnabled = createAutoSaveRef<boolean>(
+    'crashReportingEnabled'
+  );
+  const crashReportEndpoint = createAutoSaveRef<string>(
+    'crashReportEndpoint',
+    sanitizeText
+  );
+  const watchFolders = createAutoSaveRef<WatchFolder[]>(
+    'watchFolders',
+    sanitizeWatchFolders
+  );
+  const webhooks = createAutoSaveRef<Webhook[]>('webhooks', sanitizeWebhooks);
+  const telegramPosts = createAutoSaveRef<TelegramPost[]>(
+    'telegramPosts',
+    sanitizeTelegramPosts
+  );
+  const desktopNotifications = createAutoSaveRef<boolean>(
+    'desktopNotifications'
+  );
+  const watchFolderCopyLink = createAutoSaveRef<boolean>(
+    'watchFolderCopyLink'
+  );
+  const metadataPrivacyPolicy = createAutoSaveRef<MetadataPrivacyPolicy>(
+    'metadataPrivacyPolicy',
+    sanitizePrivacyPolicy
+  );
 
   return {
     quality,
     convertToWebp,
     pngCompressionMode,
     pngOptimization,
+    autoQuality,
+    autoQualitySsim,
+    heicSequenceMode,
     enableUploadCompression,
     maxConcurrentUploads,
     enableThumbnailCache,
+    fileNameTemplate,
+    defaultSaveDir,
+    logLevel,
+    cleanTempOnExit,
+    tempMaxAgeHours,
+    deleteUndoSeconds,
+    encryptedViewerUrl,
+    clipboardWatch,
+    clipboardAutoUpload,
+    hotkeyUploadClipboard,
+    hotkeyUploadScreenshot,
+    hotkeyQuickDrop,
+    picgoServerEnabled,
+    picgoServerPort,
+    picgoServerToken,
+    picgoServerHost,
+    linkTemplates,
+    defaultLinkFormat,
+    ocrAutoIndex,
+    ocrLanguage,
+    tesseractPath,
+    linkCheckEnabled,
+    linkCheckIntervalHours,
+    linkCheckBatchSize,
+    linkCheckRequireAc,
+    pauseUploadsOnMetered,
+    pauseUploadsWhenOffline,
+    skipDuplicateUploads,
+    crashReportingEnabled,
+    crashReportEndpoint,
+    watchFolders,
+    watchFolderCopyLink,
+    webhooks,
+    telegramPosts,
+    desktopNotifications,
+    metadataPrivacyPolicy,
     ready: readonly(ready),
     loading: readonly(loading),
     error: readonly(lastError),
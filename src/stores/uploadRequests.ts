@@ -0,0 +1,40 @@
+import { ref, readonly } from 'vue';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+type UploadRequestPayload = { paths: string[]; source: string };
+
+// 后端入口（快捷键、托盘、Android 分享等）请求上传的文件，在上传页挂载后统一处理
+const pending = ref<string[]>([]);
+let listening = false;
+
+// Android 分享的图片由后端暂存，取走后加入待上传列表（冷启动时事件可能早于监听）
+async function takeSharedImages() {
+  try {
+    const paths = await invoke<string[]>('take_shared_images');
+    if (paths.length) pending.value = [...pending.value, ...paths];
+  } catch {
+    /* 非 Android 平台没有分享入口 */
+  }
+}
+
+export function useUploadRequests() {
+  if (!listening) {
+    listening = true;
+    void listen<UploadRequestPayload>('upload://request', (event) => {
+      pending.value = [...pending.value, ...event.payload.paths];
+    });
+    void listen<string[]>('share-target://received', () => {
+      void takeSharedImages();
+    });
+    void takeSharedImages();
+  }
+
+  function take(): string[] {
+    const paths = pending.value;
+    pending.value = [];
+    return paths;
+  }
+
+  return { pending: readonly(pending), take };
+}
@@ -0,0 +1,43 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface UploadEvent {
+  host: string;
+  success: boolean;
+  bytes?: number | null;
+  originalBytes?: number | null;
+  durationMs?: number;
+  error?: string | null;
+  timestamp?: string;
+}
+
+export interface DailyStats {
+  day: string;
+  uploads: number;
+  failures: number;
+  bytes: number;
+}
+
+export interface HostStats {
+  host: string;
+  uploads: number;
+  failures: number;
+  bytes: number;
+  avgDurationMs: number;
+}
+
+export interface AnalyticsSummary {
+  since: string;
+  uploads: number;
+  failures: number;
+  failureRate: number;
+  bytes: number;
+  avgCompressionRatio: number | null;
+  daily: DailyStats[];
+  hosts: HostStats[];
+}
+
+export const recordUploadEvents = (events: UploadEvent[]) =>
+  invoke<void>('record_upload_events', { events });
+
+export const getUploadAnalytics = (days?: number) =>
+  invoke<AnalyticsSummary>('upload_analytics', { days });
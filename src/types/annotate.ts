@@ -0,0 +1,65 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// 颜色为 #rrggbb 或 #rrggbbaa，坐标与尺寸为图片像素
+export type AnnotationOp =
+  | {
+      type: 'arrow';
+      x1: number;
+      y1: number;
+      x2: number;
+      y2: number;
+      color?: string;
+      stroke?: number;
+    }
+  | {
+      type: 'rect';
+      x: number;
+      y: number;
+      width: number;
+      height: number;
+      color?: string;
+      stroke?: number;
+    }
+  | {
+      type: 'highlight';
+      x: number;
+      y: number;
+      width: number;
+      height: number;
+      color?: string;
+      opacity?: number;
+    }
+  | {
+      // (x, y) 为左上角
+      type: 'text';
+      x: number;
+      y: number;
+      text: string;
+      color?: string;
+      size?: number;
+      background?: string | null;
+      font?: string | null;
+    }
+  | {
+      // (x, y) 为圆心
+      type: 'step';
+      x: number;
+      y: number;
+      number: number;
+      color?: string;
+      radius?: number;
+      font?: string | null;
+    };
+
+export interface AnnotateResult {
+  path: string;
+  width: number;
+  height: number;
+}
+
+// 在图片上绘制标注并写入新的 PNG，upload 为 true 时直接请求上传
+export const annotateImage = (
+  path: string,
+  ops: AnnotationOp[],
+  upload?: boolean
+) => invoke<AnnotateResult>('annotate_image', { path, ops, upload });
@@ -0,0 +1,27 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { BatchUploadOptions } from './batchUpload';
+import type { ArchiveFile } from './gallery';
+
+// 支持上传任意文件（压缩包）的图床
+export const ARCHIVE_HOSTS = ['s3', 'webdav', 'sftp', 'local'];
+
+export interface ArchiveOptions extends BatchUploadOptions {
+  // 压缩包文件名，默认按当前时间生成；仍会套用文件名模板
+  name?: string | null;
+}
+
+export interface ArchiveUploadResult {
+  fileName: string;
+  url: string;
+  deleteId: string | null;
+  galleryId: number | null;
+  size: number;
+  files: ArchiveFile[];
+}
+
+// 把多张图片打包为一个 ZIP 上传，作为一个图库条目记录
+export const uploadArchive = (
+  paths: string[],
+  host: string,
+  options?: ArchiveOptions
+) => invoke<ArchiveUploadResult>('upload_archive', { paths, host, options });
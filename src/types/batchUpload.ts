@@ -0,0 +1,97 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type BatchItemStatus =
+  | 'waiting'
+  | 'compressing'
+  | 'uploading'
+  | 'done'
+  | 'duplicate'
+  | 'failed'
+  // 含 GPS 或设备序列号，按隐私策略未上传
+  | 'blocked'
+  | 'cancelled';
+
+// 阻止上传时发现的元数据
+export interface PrivacyFindings {
+  gps: boolean;
+  serialNumber: boolean;
+}
+
+export interface BatchItem {
+  index: number;
+  path: string;
+  status: BatchItemStatus;
+  url: string | null;
+  deleteId: string | null;
+  galleryId: number | null;
+  error: string | null;
+  // 写入图库的标签（文件夹导入）
+  tags?: string[];
+  privacy?: PrivacyFindings;
+}
+
+export interface BatchJob {
+  id: string;
+  host: string;
+  createdAt: string;
+  total: number;
+  completed: number;
+  failed: number;
+  finished: boolean;
+  cancelled: boolean;
+  items: BatchItem[];
+}
+
+// batch-upload://progress 事件负载
+export interface BatchProgress {
+  jobId: string;
+  total: number;
+  completed: number;
+  failed: number;
+  item: BatchItem;
+}
+
+export interface BatchUploadOptions {
+  // 未指定时按设置「上传前压缩」
+  compress?: boolean;
+  webp?: boolean;
+  quality?: number;
+  recordGallery?: boolean;
+  // 忽略「跳过重复上传」
+  force?: boolean;
+  retries?: number;
+  // 覆盖设置中的元数据隐私策略
+  privacy?: 'warn' | 'strip' | 'never';
+}
+
+export interface FolderImportOptions extends BatchUploadOptions {
+  // 包含子文件夹，默认 true
+  recursive?: boolean;
+  // 以子文件夹名作为图库标签，默认 true
+  folderTags?: boolean;
+}
+
+export interface FolderImport {
+  jobId: string;
+  total: number;
+}
+
+export const startBatchUpload = (
+  paths: string[],
+  host: string,
+  options?: BatchUploadOptions
+) => invoke<string>('start_batch_upload', { paths, host, options });
+
+export const getBatchUploadStatus = (jobId: string) =>
+  invoke<BatchJob | null>('batch_upload_status', { jobId });
+
+export const listBatchUploads = () => invoke<BatchJob[]>('list_batch_uploads');
+
+export const cancelBatchUpload = (jobId: string) =>
+  invoke<boolean>('cancel_batch_upload', { jobId });
+
+export const importFolderToHost = (
+  dir: string,
+  host: string,
+  options?: FolderImportOptions
+) => invoke<FolderImport>('import_folder', { dir, host, options });
@@ -0,0 +1,37 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { PngOptimizationLevel } from '../stores/settings';
+
+export interface EncoderResult {
+  id: string;
+  label: string;
+  // 多轮中最短一次的耗时
+  millis: number;
+  millisPerMegapixel: number;
+  size: number;
+  // 输出大小 / 未压缩 RGB 大小
+  ratio: number;
+  // 仅有损编码且可解码输出时有值
+  ssim: number | null;
+  error: string | null;
+}
+
+export interface EncoderRecommendation {
+  pngOptimization: PngOptimizationLevel;
+  convertToWebp: boolean;
+  autoQuality: boolean;
+  reasons: string[];
+}
+
+export interface BenchmarkReport {
+  // 内置合成图时为 null
+  sample: string | null;
+  width: number;
+  height: number;
+  threads: number;
+  results: EncoderResult[];
+  recommendation: EncoderRecommendation;
+}
+
+// 在本机运行各编码器并给出推荐设置；不传样张时使用内置合成图
+export const benchmarkEncoders = (samplePath?: string) =>
+  invoke<BenchmarkReport>('benchmark_encoders', { samplePath: samplePath ?? null });
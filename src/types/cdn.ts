@@ -0,0 +1,20 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type CdnProvider = 'none' | 'cloudflare' | 'bunny';
+
+export interface CdnPurgeConfig {
+  cdnProvider: CdnProvider;
+  // Cloudflare 的 Zone ID，BunnyCDN 不需要
+  cdnZoneId?: string | null;
+  cdnApiToken?: string | null;
+  cdnAutoPurge?: boolean;
+}
+
+export interface PurgeResult {
+  purged: number;
+  errors: string[];
+}
+
+// 清除链接的 CDN 缓存；未传配置时使用已保存的 S3 设置
+export const purgeUrls = (urls: string[], config?: CdnPurgeConfig | null) =>
+  invoke<PurgeResult>('purge_urls', { urls, config });
@@ -0,0 +1,41 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { BatchUploadOptions } from './batchUpload';
+
+export interface DocumentImageOptions extends BatchUploadOptions {
+  // 相对路径的基准目录（通常为文稿所在目录）
+  baseDir?: string | null;
+  // 保留远程图片链接，只处理本地路径与 data URI
+  keepRemote?: boolean;
+}
+
+export interface ReplacedImage {
+  source: string;
+  url: string;
+  // 原文中出现的次数
+  count: number;
+  duplicate: boolean;
+}
+
+export interface FailedImage {
+  source: string;
+  error: string;
+}
+
+export interface DocumentImagesResult {
+  // 替换链接后的文稿
+  text: string;
+  replaced: ReplacedImage[];
+  failed: FailedImage[];
+}
+
+// 提取 Markdown / HTML 文稿中的图片上传到指定图床，返回替换链接后的文稿
+export const rewriteDocumentImages = (
+  text: string,
+  host: string,
+  options?: DocumentImageOptions
+) =>
+  invoke<DocumentImagesResult>('rewrite_document_images', {
+    text,
+    host,
+    options,
+  });
@@ -0,0 +1,26 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { BatchUploadOptions } from './batchUpload';
+
+export interface EncryptedUpload {
+  path: string;
+  fileName: string;
+  // 带密钥片段（#k=...）的密文链接，写入图库
+  url: string | null;
+  // 设置了解密页面时指向解密页面，否则与 url 相同
+  shareUrl: string | null;
+  deleteId: string | null;
+  galleryId: number | null;
+  error: string | null;
+}
+
+// 每个文件随机生成密钥，AES-256-GCM 加密后上传到支持任意文件的图床（同打包上传）
+export const uploadEncrypted = (
+  paths: string[],
+  host: string,
+  options?: BatchUploadOptions
+) =>
+  invoke<EncryptedUpload[]>('upload_encrypted', { paths, host, options });
+
+// 导出在浏览器中解密分享链接的页面模板
+export const saveDecryptPage = (path: string) =>
+  invoke<void>('save_decrypt_page', { path });
@@ -0,0 +1,16 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type FeedFormat = 'rss' | 'json';
+
+export interface FeedOptions {
+  format?: FeedFormat;
+  // 条目数，默认 50，最多 500
+  limit?: number | null;
+  host?: string | null;
+  tag?: string | null;
+  title?: string | null;
+}
+
+// 生成最近上传的 RSS / JSON Feed，指定 dest 时同时写入文件
+export const exportFeed = (options?: FeedOptions, dest?: string | null) =>
+  invoke<string>('export_feed', { options, dest });
@@ -10,6 +10,12 @@ export interface GalleryItem {
   filesize?: number | null;
 }
 
+// 压缩包条目中包含的文件（打包上传时记录）
+export interface ArchiveFile {
+  name: string;
+  size: number | null;
+}
+
 export interface NewGalleryItem {
   file_name: string;
   url: string;
@@ -17,6 +23,14 @@ export interface NewGalleryItem {
   delete_marker?: string | null;
   inserted_at?: string;
   filesize?: number | null;
+  // 上传前源文件内容的 SHA-256，用于跳过重复上传
+  content_hash?: string | null;
+}
+
+export interface DuplicateMatch {
+  path: string;
+  hash: string | null;
+  existing: GalleryItem | null;
 }
 
 export interface GalleryQuery {
@@ -26,6 +40,10 @@ export interface GalleryQuery {
   end_utc?: string;
   min_filesize?: number;
   max_filesize?: number;
+  // 图片中识别出的文字（OCR）
+  text?: string;
+  // 标签（文件夹导入时按子文件夹生成）
+  tag?: string;
 }
 
 export const insertGalleryItem = (item: NewGalleryItem) =>
@@ -38,3 +56,43 @@ export const queryGalleryItems = (query?: GalleryQuery) =>
   invoke<GalleryItem[]>('gallery_query_items', { query });
 
 export const listGalleryHosts = () => invoke<string[]>('gallery_list_hosts');
+
+export const listGalleryTags = () => invoke<string[]>('gallery_list_tags');
+
+export const getGalleryItemTags = (ids: number[]) =>
+  invoke<Record<number, string[]>>('gallery_item_tags', { ids });
+
+export const findDuplicateUploads = (
+  paths: string[],
+  host: string,
+  force = false
+) => invoke<DuplicateMatch[]>('find_duplicate_uploads', { paths, host, force });
+
+export const getGalleryOcrText = (id: number) =>
+  invoke<string | null>('gallery_ocr_text', { id });
+
+export const getGalleryArchiveFiles = (id: number) =>
+  invoke<ArchiveFile[]>('gallery_archive_files', { id });
+
+// 删除撤销窗口：条目先移入回收站，撤销时长结束后再彻底删除
+export interface StagedDelete {
+  ids: number[];
+  undoSeconds: number;
+}
+
+// 回收站条目彻底删除后发送的事件
+export interface TrashPurgedEvent {
+  id: number;
+  fileName: string;
+  host: string;
+  remote: boolean;
+  error: string | null;
+}
+
+export const TRASH_PURGED_EVENT = 'gallery-trash://purged';
+
+export const stageGalleryDelete = (ids: number[], remote = true) =>
+  invoke<StagedDelete>('stage_gallery_delete', { ids, remote });
+
+export const undoGalleryDelete = (id: number) =>
+  invoke<GalleryItem>('undo_delete', { id });
@@ -0,0 +1,29 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { GalleryQuery } from './gallery';
+
+export interface SiteOptions {
+  // 网站标题，默认「图库」
+  title?: string | null;
+  // 仅导出这些条目（选中项），优先于筛选条件
+  ids?: number[] | null;
+  // 把原图下载到导出目录，适合离线归档
+  copyOriginals?: boolean;
+}
+
+export interface SiteExportResult {
+  indexPath: string;
+  items: number;
+  thumbnails: number;
+  originals: number;
+  failed: string[];
+  // 跳过的加密上传条目数（不发布解密密钥）
+  skippedEncrypted: number;
+}
+
+// 把图库条目导出为静态网站（缩略图首页、大图页与标签筛选页）
+export const exportGallerySite = (
+  query: GalleryQuery | null,
+  destDir: string,
+  options?: SiteOptions
+) =>
+  invoke<SiteExportResult>('export_gallery_site', { query, destDir, options });
@@ -18,6 +18,9 @@ export interface PluginParameterOption<T = string> {
   value: T;
 }
 
+/** 敏感字段存入系统钥匙串后，配置中以该占位符表示 */
+export const SECRET_PLACEHOLDER = '__yana_secret__';
+
 export interface PluginParameterDescriptor<T = unknown> {
   /** 用作存储和提交的键名 */
   key: string;
@@ -33,6 +36,8 @@ export interface PluginParameterDescriptor<T = unknown> {
   options?: PluginParameterOption<T>[];
   /** 额外描述或提示 */
   description?: string;
+  /** 是否为敏感字段（API Token 等），保存时存入系统钥匙串 */
+  secret?: boolean;
 }
 
 /**
@@ -147,6 +152,14 @@ export interface ImageHostPlugin {
   remove: PluginDeleteFunction;
   /** 可选的描述信息 */
   description?: string;
+  /** 设置页中额外展示的操作按钮，例如账号登录 / 退出 */
+  actions?: PluginAction[];
+}
+
+export interface PluginAction {
+  label: string;
+  /** 执行操作，参数为当前配置；返回的文字会展示给用户 */
+  run: (params: Record<string, unknown>) => Promise<string | void>;
 }
 
 export const createPluginRuntimeContext = (): PluginRuntimeContext => ({
@@ -0,0 +1,49 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface CameraInfo {
+  make: string | null;
+  model: string | null;
+  lens: string | null;
+  serialNumber: string | null;
+  software: string | null;
+}
+
+export interface CaptureInfo {
+  takenAt: string | null;
+  exposureTime: string | null;
+  fNumber: string | null;
+  iso: number | null;
+  focalLength: string | null;
+  orientation: number | null;
+}
+
+export interface GpsInfo {
+  latitude: number;
+  longitude: number;
+  // 海拔（米），低于海平面为负
+  altitude: number | null;
+}
+
+export interface IccInfo {
+  size: number;
+  colorSpace: string | null;
+  description: string | null;
+}
+
+export interface ImageMetadata {
+  path: string;
+  format: string | null;
+  fileSize: number;
+  width: number | null;
+  height: number | null;
+  camera: CameraInfo;
+  capture: CaptureInfo;
+  gps: GpsInfo | null;
+  hasGps: boolean;
+  exif: Array<{ tag: string; value: string }>;
+  xmp: string | null;
+  icc: IccInfo | null;
+}
+
+export const readImageMetadata = (path: string) =>
+  invoke<ImageMetadata>('read_image_metadata', { path });
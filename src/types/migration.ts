@@ -0,0 +1,75 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { BatchUploadOptions } from './batchUpload';
+
+export type MigrationStatus =
+  | 'waiting'
+  | 'downloading'
+  | 'uploading'
+  | 'done'
+  // 已在目标图床上
+  | 'skipped'
+  | 'failed'
+  | 'cancelled';
+
+export interface MigrationItem {
+  index: number;
+  itemId: number;
+  fileName: string;
+  oldUrl: string;
+  newUrl: string | null;
+  status: MigrationStatus;
+  error: string | null;
+}
+
+export interface MigrationJob {
+  id: string;
+  targetHost: string;
+  createdAt: string;
+  total: number;
+  completed: number;
+  failed: number;
+  finished: boolean;
+  cancelled: boolean;
+  // 旧链接 → 新链接的 CSV 映射文件
+  mappingPath: string | null;
+  items: MigrationItem[];
+}
+
+// host-migration://progress 事件负载
+export interface MigrationProgress {
+  jobId: string;
+  total: number;
+  completed: number;
+  failed: number;
+  item: MigrationItem;
+}
+
+export interface MigrationOptions extends BatchUploadOptions {
+  itemIds?: number[];
+  // 迁移该图床上的全部条目，优先于 itemIds
+  sourceHost?: string;
+  targetHost: string;
+  // 默认 30
+  itemsPerMinute?: number;
+}
+
+export interface UrlHistoryEntry {
+  url: string;
+  host: string;
+  delete_marker: string | null;
+  replaced_at: string;
+}
+
+export const startHostMigration = (options: MigrationOptions) =>
+  invoke<MigrationJob>('start_host_migration', { options });
+
+export const getHostMigrationStatus = () =>
+  invoke<MigrationJob | null>('host_migration_status');
+
+export const cancelHostMigration = () =>
+  invoke<boolean>('cancel_host_migration');
+
+export const openMigrationDir = () => invoke<void>('open_migration_dir');
+
+export const getGalleryUrlHistory = (id: number) =>
+  invoke<UrlHistoryEntry[]>('gallery_url_history', { id });
@@ -0,0 +1,31 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// network://status 事件负载
+export interface NetworkStatus {
+  online: boolean;
+  // 是否为计费网络，无法判断时为 null
+  metered: boolean | null;
+  // 上传队列因离线或计费网络挂起，新的上传会排队等待
+  held: boolean;
+}
+
+// upload-queue://update 事件中的队列状态
+export interface UploadQueueStatus {
+  running: number;
+  waiting: number;
+  paused: boolean;
+  offline: boolean;
+  maxConcurrent: number;
+}
+
+// upload-queue://update 事件负载；progress 阶段携带本次请求的发送进度
+export interface UploadQueueEvent {
+  uploadId: string | null;
+  phase: 'queued' | 'started' | 'progress' | 'finished';
+  position: number | null;
+  bytesSent: number | null;
+  totalBytes: number | null;
+  status: UploadQueueStatus;
+}
+
+export const getNetworkStatus = () => invoke<NetworkStatus>('network_status');
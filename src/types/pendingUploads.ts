@@ -0,0 +1,46 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+
+export type PendingUploadStatus = 'pending' | 'failed' | 'completed';
+
+export interface PendingUpload {
+  id: number;
+  kind: 'upload_image' | 's3_upload' | 'host_upload';
+  filePath: string;
+  host: string | null;
+  configHash: string;
+  attempts: number;
+  status: PendingUploadStatus;
+  // host_upload 记录的阶段：compressed / uploading / gallery_pending
+  stage: string | null;
+  lastError: string | null;
+  result: unknown;
+  createdAt: string;
+  updatedAt: string;
+}
+
+export interface PendingUploadCompleted {
+  id: number;
+  success: boolean;
+  result: unknown;
+  error: string | null;
+}
+
+// 上次崩溃或退出时未完成、失败或已在后台补传完成的上传记录
+export const listPendingUploads = () =>
+  invoke<PendingUpload[]>('pending_uploads_list');
+
+// 重新执行一条记录，成功后记录被删除
+export const retryPendingUpload = (id: number) =>
+  invoke<unknown>('pending_uploads_retry', { id });
+
+export const discardPendingUpload = (id: number) =>
+  invoke<void>('pending_uploads_discard', { id });
+
+// 启动时后台恢复的上传结束（成功或失败）
+export const onPendingUploadCompleted = (
+  handler: (payload: PendingUploadCompleted) => void
+) =>
+  listen<PendingUploadCompleted>('pending-uploads://completed', (event) =>
+    handler(event.payload)
+  );
@@ -0,0 +1,20 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface QualityReport {
+  ssim: number;
+  // 两图完全相同时为 null（无穷大）
+  psnr: number | null;
+  originalSize: number;
+  compressedSize: number;
+  // 压缩后减少为负数
+  sizeDelta: number;
+  sizeRatio: number;
+  width: number;
+  height: number;
+  // 压缩图尺寸与原图不同，已缩放后比较
+  resized: boolean;
+}
+
+// 计算压缩前后的 SSIM、PSNR 与文件大小变化
+export const compareQuality = (originalPath: string, compressedPath: string) =>
+  invoke<QualityReport>('compare_quality', { originalPath, compressedPath });
@@ -0,0 +1,19 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { BatchUploadOptions } from './batchUpload';
+
+export interface RehostResult {
+  sourceUrl: string;
+  fileName: string;
+  url: string;
+  deleteId: string | null;
+  galleryId: number | null;
+  // 内容已上传过，沿用图库中的链接
+  duplicate: boolean;
+}
+
+// 下载远程图片后上传到指定图床并写入图库
+export const rehostUrl = (
+  url: string,
+  host: string,
+  options?: BatchUploadOptions
+) => invoke<RehostResult>('rehost_url', { url, host, options });
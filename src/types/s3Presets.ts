@@ -0,0 +1,52 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface S3ProviderPreset {
+  id: string;
+  name: string;
+  // null 表示使用 AWS 默认地址
+  endpointTemplate: string | null;
+  // 需要用户额外填写的占位符，不含 {bucket} / {region} / {key}
+  placeholders: string[];
+  forcePathStyle: boolean;
+  defaultRegion: string | null;
+  regionLocked: boolean;
+  supportsAcl: boolean;
+  publicUrlTemplate: string | null;
+  notes: string | null;
+}
+
+export const listS3Presets = () =>
+  invoke<S3ProviderPreset[]>('s3_list_presets');
+
+const fill = (
+  template: string,
+  values: Record<string, string>,
+  region: string
+) =>
+  template
+    .replace(/\{region\}/g, region)
+    .replace(/\{(\w+)\}/g, (match, name: string) =>
+      name === 'bucket' || name === 'key' ? match : values[name]?.trim() || match
+    );
+
+// 按预设填充 S3 插件参数；{bucket} 与 {key} 保留给后端在上传时替换
+export function applyS3Preset(
+  preset: S3ProviderPreset,
+  params: Record<string, unknown>,
+  placeholderValues: Record<string, string>
+) {
+  const current = typeof params.region === 'string' ? params.region.trim() : '';
+  const region =
+    preset.regionLocked || !current ? preset.defaultRegion ?? current : current;
+  params.region = region;
+  params.endpoint = preset.endpointTemplate
+    ? fill(preset.endpointTemplate, placeholderValues, region)
+    : '';
+  params.forcePathStyle = preset.forcePathStyle;
+  params.publicUrlTemplate = preset.publicUrlTemplate
+    ? fill(preset.publicUrlTemplate, placeholderValues, region)
+    : '';
+  if (!preset.supportsAcl) {
+    params.acl = '';
+  }
+}
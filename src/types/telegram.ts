@@ -0,0 +1,6 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { TelegramPost } from '../stores/settings';
+
+// 发送一条测试消息（Telegraph 为查询账号），返回结果说明
+export const testTelegramPost = (post: TelegramPost) =>
+  invoke<string>('test_telegram_post', { post });
@@ -0,0 +1,35 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { BatchUploadOptions } from './batchUpload';
+
+export interface TileOptions extends BatchUploadOptions {
+  // 单个切片的最大字节数，未指定时按图床的已知上限
+  maxBytes?: number | null;
+  // 单个切片的最大高度（像素）
+  maxHeight?: number | null;
+}
+
+export interface TileUpload {
+  index: number;
+  // 切片在原图中的纵向位置
+  y: number;
+  height: number;
+  size: number;
+  url: string | null;
+  error: string | null;
+}
+
+export interface TileUploadResult {
+  width: number;
+  height: number;
+  tiles: TileUpload[];
+  urls: string[];
+  // 全部切片上传成功时依次拼接的 Markdown
+  markdown: string | null;
+}
+
+// 把超限的长图切分为纵向切片后按顺序上传
+export const uploadImageTiles = (
+  path: string,
+  host: string,
+  options?: TileOptions
+) => invoke<TileUploadResult>('upload_image_tiles', { path, host, options });
@@ -0,0 +1,6 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { Webhook } from '../stores/settings';
+
+// 发送一条测试事件，返回 HTTP 状态码
+export const testWebhook = (webhook: Webhook) =>
+  invoke<number>('test_webhook', { webhook });
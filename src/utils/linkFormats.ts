@@ -0,0 +1,39 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { LinkTemplate } from '../stores/settings';
+
+// 与后端 link_format::LinkItem 对应；path 为本地文件，用于读取宽高
+export type LinkFormatItem = {
+  url: string;
+  fileName?: string | null;
+  host?: string | null;
+  filesize?: number | null;
+  width?: number | null;
+  height?: number | null;
+  path?: string | null;
+};
+
+// 后端不可用时仍提供纯链接格式
+const FALLBACK_FORMATS: LinkTemplate[] = [
+  { id: 'link', name: '纯链接', template: '{url}' },
+];
+
+export async function listLinkFormats(): Promise<LinkTemplate[]> {
+  try {
+    const formats = await invoke<LinkTemplate[]>('list_link_formats');
+    return formats.length ? formats : FALLBACK_FORMATS;
+  } catch (e) {
+    return FALLBACK_FORMATS;
+  }
+}
+
+// 按格式 id 渲染，每行一条；items 与 galleryIds 可同时传入
+export async function formatLinks(
+  format: string,
+  source: { items?: LinkFormatItem[]; galleryIds?: number[] }
+): Promise<string> {
+  return invoke<string>('format_links', {
+    format,
+    items: source.items ?? null,
+    galleryIds: source.galleryIds ?? null,
+  });
+}